@@ -42,6 +42,8 @@ interface RegistrationQrPayload {
   device_name: string;
   os: string;
   created_at: string;
+  organization_id?: string | null;
+  seat_label?: string | null;
 }
 
 function TerminalsPage() {
@@ -83,6 +85,8 @@ function TerminalsPage() {
           public_key: payload.public_key,
           device_name: payload.device_name,
           os: payload.os,
+          organization_id: payload.organization_id ?? undefined,
+          seat_label: payload.seat_label ?? undefined,
         },
       });
       if (error) {
@@ -166,6 +170,7 @@ function TerminalsPage() {
   const getOsIcon = (os: string) => {
     switch (os) {
       case "android":
+      case "ios":
         return <IconDeviceMobile size={18} />;
       default:
         return <IconDeviceDesktop size={18} />;
@@ -485,6 +490,16 @@ function TerminalsPage() {
                   </span>
                   <div>{scannedData.os}</div>
                 </div>
+                {scannedData.organization_id && scannedData.seat_label && (
+                  <div>
+                    <span className={css({ color: "#999", fontSize: "12px" })}>
+                      組織 / レジ
+                    </span>
+                    <div>
+                      {scannedData.organization_id} / {scannedData.seat_label}
+                    </div>
+                  </div>
+                )}
                 <div>
                   <span className={css({ color: "#999", fontSize: "12px" })}>
                     端末ID
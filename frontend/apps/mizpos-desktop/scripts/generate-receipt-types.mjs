@@ -0,0 +1,28 @@
+#!/usr/bin/env node
+// Generate TypeScript types for the receipt models from the JSON Schema
+// committed by the mizpos-core crate (src-tauri/crates/mizpos-core).
+// Usage: pnpm generate-receipt-types
+
+import { readFileSync, writeFileSync } from "node:fs";
+import { dirname, join } from "node:path";
+import { fileURLToPath } from "node:url";
+import { declarations } from "./schema-to-ts.mjs";
+
+const root = join(dirname(fileURLToPath(import.meta.url)), "..");
+const schemaPath = join(
+  root,
+  "src-tauri/crates/mizpos-core/schema/receipt.schema.json",
+);
+const outputPath = join(root, "src/types/receipt.gen.ts");
+
+const schema = JSON.parse(readFileSync(schemaPath, "utf8"));
+
+const lines = [
+  "// This file is generated by scripts/generate-receipt-types.mjs",
+  "// from src-tauri/crates/mizpos-core/schema/receipt.schema.json.",
+  "// Do not edit by hand.",
+  ...declarations(schema.$defs),
+];
+
+writeFileSync(outputPath, `${lines.join("\n")}\n`);
+console.log(`Generated ${outputPath}`);
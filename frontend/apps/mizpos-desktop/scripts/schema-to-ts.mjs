@@ -0,0 +1,118 @@
+// Convert the JSON Schemas generated by the Rust side (schemars, draft 2020-12)
+// into TypeScript declarations. Only the subset of JSON Schema that schemars
+// emits for the app's types is supported; anything else throws so that new
+// shapes are noticed when the types are regenerated.
+
+export function refName(ref) {
+  return ref.replace("#/$defs/", "");
+}
+
+/** JSDoc comment lines for a description */
+export function comment(description, indent) {
+  if (!description) return [];
+  const text = description.split("\n");
+  if (text.length === 1) return [`${indent}/** ${description} */`];
+  return [
+    `${indent}/**`,
+    ...text.map((line) => `${indent} * ${line}`.trimEnd()),
+    `${indent} */`,
+  ];
+}
+
+/** Fields of an object schema, one per line */
+function fieldLines(node, indent) {
+  const required = new Set(node.required ?? []);
+  const lines = [];
+  for (const [field, property] of Object.entries(node.properties ?? {})) {
+    const optional = required.has(field) ? "" : "?";
+    lines.push(...comment(property.description, indent));
+    lines.push(`${indent}${field}${optional}: ${typeOf(property, indent)};`);
+  }
+  return lines;
+}
+
+function objectLiteral(node, indent) {
+  const fields = fieldLines(node, `${indent}  `);
+  if (fields.length === 0) return "Record<string, never>";
+  return `{\n${fields.join("\n")}\n${indent}}`;
+}
+
+function union(variants, indent) {
+  return variants.map((variant) => typeOf(variant, indent)).join(" | ");
+}
+
+/** TypeScript type expression for a schema */
+export function typeOf(node, indent = "") {
+  if (node === true || Object.keys(node).length === 0) return "unknown";
+  if (node.$ref) return refName(node.$ref);
+  if (node.const !== undefined) return JSON.stringify(node.const);
+  if (node.enum) return node.enum.map((v) => JSON.stringify(v)).join(" | ");
+  if (node.anyOf) return union(node.anyOf, indent);
+  if (node.oneOf && !node.properties) return union(node.oneOf, indent);
+  if (node.oneOf) {
+    return `${objectLiteral(node, indent)} & (${union(node.oneOf, indent)})`;
+  }
+  if (node.properties) return objectLiteral(node, indent);
+
+  const types = Array.isArray(node.type) ? node.type : [node.type];
+  return types
+    .map((type) => {
+      switch (type) {
+        case "string":
+          return "string";
+        case "integer":
+        case "number":
+          return "number";
+        case "boolean":
+          return "boolean";
+        case "null":
+          return "null";
+        case "array": {
+          if (node.prefixItems) {
+            return `[${node.prefixItems.map((item) => typeOf(item, indent)).join(", ")}]`;
+          }
+          const item = typeOf(node.items, indent);
+          return /[|&]/.test(item) ? `(${item})[]` : `${item}[]`;
+        }
+        case "object":
+          return node.additionalProperties
+            ? `Record<string, ${typeOf(node.additionalProperties, indent)}>`
+            : "Record<string, unknown>";
+        default:
+          throw new Error(`Unsupported schema type: ${type}`);
+      }
+    })
+    .join(" | ");
+}
+
+/** Declarations for the `$defs` of a schema (skipping names in `exclude`) */
+export function declarations(defs, exclude = new Set()) {
+  const lines = [];
+  for (const [name, def] of Object.entries(defs)) {
+    if (exclude.has(name)) continue;
+    lines.push("");
+    lines.push(...comment(def.description, ""));
+    if (def.properties && !def.oneOf) {
+      lines.push(`export interface ${name} {`);
+      lines.push(...fieldLines(def, "  "));
+      lines.push("}");
+    } else if (def.oneOf) {
+      const variants = def.oneOf.flatMap((variant) => [
+        ...comment(variant.description, "  "),
+        `  | ${typeOf(variant, "  ")}`,
+      ]);
+      if (def.properties) {
+        lines.push(`export type ${name} = ${objectLiteral(def, "")} & (`);
+        lines.push(...variants);
+        lines.push(");");
+      } else {
+        lines.push(`export type ${name} =`);
+        lines.push(...variants);
+        lines[lines.length - 1] += ";";
+      }
+    } else {
+      lines.push(`export type ${name} = ${typeOf(def)};`);
+    }
+  }
+  return lines;
+}
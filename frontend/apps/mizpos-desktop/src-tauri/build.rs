@@ -1,3 +1,229 @@
+use std::path::Path;
+
+/// ラスター描画用の同梱フォント（src/raster.rs で埋め込む）
+const EMBEDDED_FONT: &str = "fonts/BIZUDGothic-Regular.ttf";
+
+/// アプリのコマンド（`allow-xxx` の権限が生成され、capabilities/ で許可したものだけ呼べる）
+///
+/// コマンドを追加した場合はここと permissions/*.toml の権限セットにも追加する
+const APP_COMMANDS: &[&str] = &[
+    "get_platform",
+    // 端末認証コマンド
+    "get_terminal_status",
+    "initialize_terminal",
+    "generate_registration_qr",
+    "create_auth_signature",
+    "get_seat_binding",
+    "verify_terminal",
+    "check_terminal_verification",
+    "clear_terminal_keychain",
+    // アップロードキューコマンド
+    "get_pending_uploads",
+    "complete_upload",
+    "fail_upload",
+    "email_receipt",
+    // リモートコマンド・サーバー鍵コマンド
+    "execute_remote_command",
+    "get_remote_command_history",
+    "get_terminal_lock",
+    "get_server_key",
+    "pin_server_key",
+    // ハートビートコマンド
+    "get_heartbeat_status",
+    "create_heartbeat",
+    "ack_heartbeat",
+    "fail_heartbeat",
+    "report_battery",
+    // イベントコンテキストコマンド
+    "get_active_event",
+    "select_event",
+    "list_local_events",
+    "issue_receipt_number",
+    "archive_event",
+    // 端末設定コマンド
+    "get_terminal_config",
+    "update_terminal_config",
+    "apply_signed_config",
+    "apply_price_update",
+    "get_price_book",
+    "get_print_strings",
+    "update_print_strings",
+    // レシートQRコマンド
+    "decode_receipt_qr",
+    "decode_closing_report_qr",
+    // プリンタープロファイルコマンド
+    "get_printer_profiles",
+    "save_printer_profile",
+    "delete_printer_profile",
+    "get_printer_features",
+    // 用紙使用量コマンド
+    "get_paper_usage",
+    // スタッフ認証コマンド
+    "register_supervisor",
+    "verify_supervisor",
+    "set_duress_pin",
+    "begin_supervisor_session",
+    "end_supervisor_session",
+    "get_supervisor_session",
+    // ジャーナルコマンド
+    "get_journal",
+    "get_reprint_count",
+    "refund_sale",
+    "void_sale",
+    "get_journal_totals",
+    // セルフレジ（キオスク）コマンド
+    "get_kiosk_state",
+    "start_kiosk",
+    "stop_kiosk",
+    "kiosk_scan",
+    "kiosk_remove_item",
+    "kiosk_clear_basket",
+    "kiosk_checkout",
+    "kiosk_cancel_payment",
+    "kiosk_complete_payment",
+    // 印刷スプーラーコマンド
+    "spool_receipt",
+    "spool_parked_slip",
+    "spool_closing_report",
+    "get_print_queue",
+    "cancel_print_job",
+    // 保留取引コマンド
+    "park_sale",
+    "list_parked_sales",
+    "resume_parked_sale",
+    // 呼び出し表示コマンド
+    "get_now_serving",
+    "call_now_serving",
+    "clear_now_serving",
+    // 見積コマンド
+    "get_quote",
+    "convert_quote",
+    // 予約コマンド
+    "register_reservation",
+    "find_reservation",
+    "list_reservations",
+    "redeem_reservation",
+    "cancel_reservation",
+    "export_reservations",
+    "merge_reservations",
+    // 内金コマンド
+    "list_open_deposits",
+    "prepare_deposit_balance",
+    "get_deposit_balance_sale",
+    // 商品券コマンド
+    "get_voucher_catalog",
+    "validate_voucher_tender",
+    // 支払コマンド
+    "validate_payments",
+    // ギフト券コマンド
+    "validate_gift_voucher",
+    "redeem_gift_voucher",
+    "list_gift_vouchers",
+    "export_gift_vouchers",
+    "merge_gift_vouchers",
+    // 会員コマンド
+    "find_member",
+    "list_members",
+    "import_members",
+    "get_member_purchases",
+    "get_points_balance",
+    // 価格変更コマンド
+    "get_price_override_reasons",
+    "approve_price_override",
+    // 年齢確認コマンド
+    "confirm_age_verification",
+    // キャンペーンコマンド
+    "get_promotions",
+    "quote_promotions",
+    // 端数調整コマンド
+    "quote_rounding",
+    // 売上確定コマンド
+    "prepare_sale",
+    // 営業セッションコマンド
+    "get_session",
+    "open_session",
+    "check_venue_network",
+    "report_network_ssid",
+    "switch_staff",
+    "close_session",
+    "set_closing_reminder",
+    "get_closing_reminder",
+    "cancel_closing_reminder",
+    // ドロワーコマンド
+    "get_drawer_contents",
+    "set_drawer_contents",
+    "suggest_change",
+    // 計測コマンド
+    "get_metrics",
+    // 接続先環境コマンド
+    "get_environment",
+    "switch_environment",
+    // 商品データコマンド（取り込み・仮登録・ISDN照会）
+    "import_catalog",
+    "create_product_from_scan",
+    "lookup_isdn",
+    "list_provisional_products",
+    "review_provisional_product",
+    // 売上目標コマンド
+    "get_sales_target",
+    "set_sales_target",
+    "get_sales_progress",
+    // プリンターコマンド（デスクトップ）
+    "get_usb_devices",
+    "text_print",
+    "text_print_to",
+    "print_barcode",
+    "print_test_page",
+    "print_test_page_to",
+    "discover_network_printers",
+    "network_print_test_page",
+    "network_print_text",
+    "list_serial_ports",
+    "list_bluetooth_printers",
+    "bluetooth_printer_status",
+    "get_paper_status",
+    "get_printer_status",
+    "print_product_label",
+    "welcome_print",
+    "print_receipt",
+    "print_receipt_to",
+    "bluetooth_print_receipt",
+    "estimate_receipt_length",
+    "reprint_receipt",
+    "reprint_receipt_to",
+    "print_refund_receipt",
+    "print_receipt_from_stub",
+    "print_gift_receipt",
+    "print_closing_report",
+    "issue_gift_voucher",
+    "print_gift_voucher",
+    "print_parked_slip",
+    "print_recall_slip",
+    "print_terminal_verification",
+    "print_quote",
+    "print_reservation_slip",
+    "print_pickup_confirmation",
+    "print_sales_progress",
+    // プリンターコマンド（Android）
+    "get_bluetooth_devices",
+    "connect_bluetooth_printer",
+    "bluetooth_print",
+    "bluetooth_welcome_print",
+];
+
 fn main() {
-    tauri_build::build()
+    // 同梱フォントなしではレシートの字形・字幅が端末ごとに変わるため、ビルドを止める
+    println!("cargo:rerun-if-changed={}", EMBEDDED_FONT);
+    if !Path::new(EMBEDDED_FONT).exists() {
+        panic!(
+            "{} not found: the raster receipt font (BIZ UDGothic, SIL OFL 1.1) must be committed under fonts/",
+            EMBEDDED_FONT
+        );
+    }
+
+    tauri_build::try_build(
+        tauri_build::Attributes::new()
+            .app_manifest(tauri_build::AppManifest::new().commands(APP_COMMANDS)),
+    )
+    .expect("failed to run tauri-build")
 }
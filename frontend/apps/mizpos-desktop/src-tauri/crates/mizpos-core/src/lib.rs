@@ -0,0 +1,7 @@
+//! mizpos 共通データモデル
+//!
+//! レシート・閉局レポートのデータモデルを定義し、JSON Schema を生成する
+//! フロントエンドの TypeScript の型は、コミットした JSON Schema から生成する（`pnpm generate-receipt-types`）
+
+pub mod receipt;
+pub mod schema;
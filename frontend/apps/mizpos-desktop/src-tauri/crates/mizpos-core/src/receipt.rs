@@ -0,0 +1,566 @@
+//! レシートデータモジュール
+//!
+//! 印刷・ジャーナルで共通に使うレシート／閉局レポートのデータモデル
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 商品明細
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Type)]
+pub struct ReceiptItem {
+    /// 商品ID（端末の価格表の価格と照合する）
+    #[specta(optional)]
+    pub product_id: Option<String>,
+    /// 出版サークル名
+    pub circle_name: String,
+    /// サークル名の読み（受け渡し時の呼び出し用）
+    #[serde(default)]
+    pub circle_name_ruby: Option<String>,
+    /// 商品名
+    pub name: String,
+    /// JAN
+    pub jan: String,
+    /// ISBN
+    pub isbn: String,
+    /// ISDN（書籍の場合）
+    #[specta(optional)]
+    pub isdn: Option<String>,
+    /// 2段目バーコード（Cコード＋値段、書籍の場合）
+    #[specta(optional)]
+    pub jan2: Option<String>,
+    /// 書籍フラグ
+    pub is_book: bool,
+    /// 商品数
+    pub quantity: u32,
+    /// 値段（単価 x 数量）
+    pub price: u32,
+    /// 明細のメモ（特典付き、サイン本など）
+    #[serde(default)]
+    pub note: Option<String>,
+    /// 商品カテゴリー（未指定時は書籍フラグから判定）
+    #[serde(default)]
+    pub category: Option<String>,
+    /// 責任者の承認による価格変更
+    #[serde(default)]
+    pub price_override: Option<PriceOverride>,
+    /// 成人向け（R18）の商品
+    #[serde(default)]
+    pub age_restricted: bool,
+    /// 明細の下に JAN のバーコードを印刷する（受け渡し・返品時の読み取り用）
+    #[serde(default)]
+    pub print_barcode: bool,
+}
+
+/// 明細の価格変更の印（承認時のジャーナルの記録と対応する）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Type)]
+pub struct PriceOverride {
+    /// 承認を記録したジャーナルのID
+    pub override_id: String,
+    /// 変更前の値段（単価 x 数量）
+    pub original_price: u32,
+    pub reason_code: String,
+    /// 理由の表示名
+    pub reason: String,
+    /// 承認した責任者
+    pub supervisor_id: String,
+}
+
+impl ReceiptItem {
+    /// 並べ替え・グループ化に使うカテゴリー名
+    pub fn category_label(&self) -> String {
+        match self.category.as_deref() {
+            Some(c) if !c.is_empty() => c.to_string(),
+            _ if self.is_book => "書籍".to_string(),
+            _ => "グッズ".to_string(),
+        }
+    }
+}
+
+/// 現金払いの支払手段名
+pub const PAYMENT_METHOD_CASH: &str = "現金";
+
+/// 支払情報
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Type)]
+pub struct PaymentInfo {
+    /// 支払手段名（現金、クレジットカードなど）
+    pub method: String,
+    /// 支払金額
+    pub amount: u32,
+}
+
+impl PaymentInfo {
+    /// 現金払いか（釣り銭は現金から出す）
+    pub fn is_cash(&self) -> bool {
+        self.method == PAYMENT_METHOD_CASH
+    }
+}
+
+/// カード詳細情報（クレジット売上票用）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Type)]
+pub struct CardDetails {
+    /// カードブランド（visa, mastercard等）
+    #[specta(optional)]
+    pub brand: Option<String>,
+    /// カード番号下4桁
+    #[specta(optional)]
+    pub last4: Option<String>,
+    /// 有効期限（月）
+    #[specta(optional)]
+    pub exp_month: Option<u32>,
+    /// 有効期限（年）
+    #[specta(optional)]
+    pub exp_year: Option<u32>,
+    /// カード名義人
+    #[specta(optional)]
+    pub cardholder_name: Option<String>,
+    /// カード種別（credit, debit等）
+    #[specta(optional)]
+    pub funding: Option<String>,
+    /// 端末シリアル番号
+    #[specta(optional)]
+    pub terminal_serial_number: Option<String>,
+    /// 加盟店名（Stripeアカウント名）
+    #[specta(optional)]
+    pub merchant_name: Option<String>,
+    /// 取引種別（sale/refund）
+    #[specta(optional)]
+    pub transaction_type: Option<String>,
+    /// 支払区分
+    #[specta(optional)]
+    pub payment_type: Option<String>,
+    /// 取引日時（ISO8601形式）
+    #[specta(optional)]
+    pub transaction_at: Option<String>,
+}
+
+impl CardDetails {
+    /// 端末に残してよい項目だけにした写し
+    ///
+    /// カード情報はブランド・下4桁までに限定し、有効期限・名義人は保持しない
+    /// 下4桁は数字以外を除いた末尾4桁だけを残す（全桁が渡されても保存しない）
+    /// トラックデータなど定義外の項目は読み込み時に捨てる
+    pub fn sanitized(&self) -> Self {
+        let last4 = self.last4.as_deref().and_then(|s| {
+            let digits: Vec<char> = s.chars().filter(|c| c.is_ascii_digit()).collect();
+            (digits.len() >= 4).then(|| digits[digits.len() - 4..].iter().collect())
+        });
+
+        Self {
+            last4,
+            exp_month: None,
+            exp_year: None,
+            cardholder_name: None,
+            ..self.clone()
+        }
+    }
+
+    /// 保存期間を過ぎたときの写し（下4桁・有効期限も消す）
+    pub fn purged(&self) -> Self {
+        Self {
+            last4: None,
+            exp_month: None,
+            exp_year: None,
+            cardholder_name: None,
+            ..self.clone()
+        }
+    }
+
+    /// 保存期間経過後の消去が済んでいるか
+    pub fn is_purged(&self) -> bool {
+        self.last4.is_none()
+            && self.exp_month.is_none()
+            && self.exp_year.is_none()
+            && self.cardholder_name.is_none()
+    }
+}
+
+/// レシートデータ
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Type)]
+pub struct ReceiptData {
+    /// イベント名称
+    pub event_name: String,
+    /// サークル名（トップに大きく表示）
+    #[specta(optional)]
+    pub circle_name: Option<String>,
+    /// サークル名を縦書きで表示する（領収書風の装飾）
+    #[serde(default)]
+    pub circle_name_vertical: bool,
+    /// ロゴ画像（レシートの先頭に印刷）
+    #[serde(default)]
+    pub logo: Option<ReceiptLogo>,
+    /// 会場住所
+    #[specta(optional)]
+    pub venue_address: Option<String>,
+    /// 発売日時
+    #[specta(optional)]
+    pub sale_start_date_time: Option<String>,
+    /// スタッフ番号
+    pub staff_id: String,
+    /// 宛名（様の前に表示。会員の売上では会員名）
+    #[specta(optional)]
+    pub customer_name: Option<String>,
+    /// 会員番号
+    #[serde(default)]
+    pub member_id: Option<String>,
+    /// 会員の獲得ポイントと残高（印刷時に付与して入れる）
+    #[serde(default)]
+    pub points: Option<ReceiptPoints>,
+    /// 商品明細リスト
+    pub items: Vec<ReceiptItem>,
+    /// 適用したキャンペーン（合計は割引後の金額）
+    #[serde(default)]
+    pub promotions: Vec<AppliedPromotion>,
+    /// 合計金額
+    pub total: u32,
+    /// 支払情報リスト
+    pub payments: Vec<PaymentInfo>,
+    /// 消費税率（%）
+    pub tax_rate: u32,
+    /// 消費税金額
+    pub tax_amount: u32,
+    /// レシート番号
+    pub receipt_number: String,
+    /// カード詳細情報（クレジット決済時、読み込み時に保存してよい範囲に絞る）
+    #[serde(default, deserialize_with = "deserialize_sanitized_card")]
+    #[schemars(with = "Option<CardDetails>")]
+    pub card_details: Option<CardDetails>,
+    /// Stripe PaymentIntent ID（クレジット決済時）
+    #[specta(optional)]
+    pub payment_intent_id: Option<String>,
+    /// 釣り銭の金種内訳（高額の釣り銭時に印字）
+    #[specta(optional)]
+    pub change_breakdown: Option<Vec<DenominationCount>>,
+    /// 年齢確認の記録（成人向けの商品を含む場合に必須）
+    #[serde(default)]
+    pub age_verification: Option<AgeVerification>,
+    /// 内金・残金（予約販売の売上のみ。total はこの取引で受け取る内金または残金）
+    #[serde(default)]
+    pub deposit: Option<DepositTerms>,
+    /// 端数調整額（キャンペーンの割引後の金額を丸めた差額、値引はマイナス。total は調整後の金額）
+    #[serde(default)]
+    pub rounding_adjustment: i32,
+    /// 会計時の価格表の版（価格改定の発行日時。未指定の場合は売上の確定時の版を入れる）
+    #[serde(default)]
+    pub price_version: Option<u64>,
+}
+
+/// レシートに印刷するロゴ画像（PNG）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Type)]
+pub struct ReceiptLogo {
+    pub source: ImageSource,
+    /// 印刷する幅の上限（ドット、未指定の場合は用紙幅。これより大きい画像は縮小する）
+    #[serde(default)]
+    pub max_width: Option<u16>,
+    /// 白黒への変換方法
+    #[serde(default)]
+    pub mode: ImageMode,
+}
+
+/// 画像の読み込み元
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ImageSource {
+    /// Base64 でエンコードした PNG（`data:image/png;base64,` から始まるものも可）
+    Base64 { data: String },
+    /// 端末の PNG ファイル
+    File { path: String },
+}
+
+/// 画像を白黒に変換する方法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageMode {
+    /// 誤差拡散（写真・グラデーション向け）
+    #[default]
+    Dither,
+    /// しきい値で2値化（線画・文字のロゴ向け）
+    Threshold,
+}
+
+/// 内金・残金の条件
+///
+/// 内金を受け取る売上と残金を受け取る売上の2件に分けて記録し、残金の売上は内金の売上のレシート番号を持つ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+pub struct DepositTerms {
+    /// ご注文の合計金額（内金と残金の合計）
+    pub order_total: u32,
+    /// 内金
+    pub deposit: u32,
+    /// 残金のお支払期日（YYYY-MM-DD）
+    pub due_date: String,
+    /// 内金を受け取った売上のレシート番号（残金の売上のみ）
+    #[serde(default)]
+    pub deposit_receipt_number: Option<String>,
+}
+
+impl DepositTerms {
+    /// 残金
+    pub fn balance(&self) -> u32 {
+        self.order_total.saturating_sub(self.deposit)
+    }
+
+    /// 残金を受け取る売上か
+    pub fn is_balance_payment(&self) -> bool {
+        self.deposit_receipt_number.is_some()
+    }
+}
+
+/// 適用したキャンペーン
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+pub struct AppliedPromotion {
+    pub promotion_id: String,
+    pub name: String,
+    /// 割引額
+    pub discount: u32,
+    /// 明細ごとの割引額（合計は discount と一致する）
+    #[serde(default)]
+    pub lines: Vec<PromotionLineDiscount>,
+}
+
+/// キャンペーンの割引額のうち、1つの明細に割り当てた分
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+pub struct PromotionLineDiscount {
+    /// 明細の番号（items の並び順、0始まり）
+    pub item_index: usize,
+    pub discount: u32,
+}
+
+/// 金種カウント
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+pub struct DenominationCount {
+    pub denomination: u32,
+    pub count: u32,
+}
+
+/// 年齢確認の記録
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Type)]
+pub struct AgeVerification {
+    /// 端末が発行した確認の番号（1件の売上にだけ使える）
+    #[serde(default)]
+    pub token: String,
+    /// 確認したスタッフ番号
+    pub staff_id: String,
+    /// 確認日時（UNIX秒）
+    pub verified_at: u64,
+}
+
+/// レシートに印字するポイント
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+pub struct ReceiptPoints {
+    /// 今回の獲得ポイント
+    pub earned: u64,
+    /// 今回の分を含む残高
+    pub balance: u64,
+}
+
+/// 商品券カウント
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Type)]
+pub struct VoucherCount {
+    /// 表示名（種別コードがある場合はカタログの名前で置き換える）
+    #[serde(rename = "type")]
+    pub voucher_type: String,
+    /// 商品券カタログの種別コード
+    #[serde(default)]
+    pub code: Option<String>,
+    pub amount: u32,
+    #[specta(optional)]
+    pub memo: Option<String>,
+}
+
+/// カード情報を読み込むときに保存してよい範囲に絞る（フロントエンド・保存ファイルのどちらから来ても同じ）
+fn deserialize_sanitized_card<'de, D>(deserializer: D) -> Result<Option<CardDetails>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<CardDetails>::deserialize(deserializer)?.map(|card| card.sanitized()))
+}
+
+impl ReceiptData {
+    /// カード情報を保存・印刷してよい範囲に絞る
+    pub fn sanitize_card_details(&mut self) {
+        if let Some(card) = self.card_details.as_mut() {
+            *card = card.sanitized();
+        }
+    }
+}
+
+/// 閉局レポートデータ
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Type)]
+pub struct ClosingReportData {
+    pub id: String,
+    pub terminal_id: String,
+    pub staff_id: String,
+    pub staff_name: String,
+    #[specta(optional)]
+    pub event_name: Option<String>,
+    pub denominations: Vec<DenominationCount>,
+    pub cash_total: u32,
+    pub vouchers: Vec<VoucherCount>,
+    pub voucher_total: u32,
+    pub grand_total: u32,
+    pub expected_total: u32,
+    pub difference: i32,
+    pub transaction_count: u32,
+    pub closed_at: String,
+    /// 支払方法別の集計（空の場合は印刷時にジャーナルから集計する）
+    #[serde(default)]
+    pub payment_methods: Vec<PaymentMethodTotal>,
+    /// 時間帯別の売上（空の場合は印刷時にジャーナルから集計する）
+    #[serde(default)]
+    pub hourly_sales: Vec<HourlySales>,
+    /// 担当者別の集計（空の場合は印刷時にジャーナルから集計する）
+    #[serde(default)]
+    pub staff_totals: Vec<StaffTotal>,
+    /// 売上に使われた商品券の種別ごとの集計（空の場合は印刷時にジャーナルから集計する）
+    #[serde(default)]
+    pub voucher_sales: Vec<VoucherSales>,
+}
+
+/// 売上に使われた商品券の集計
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+pub struct VoucherSales {
+    pub code: String,
+    pub name: String,
+    /// 使われた売上の件数
+    pub count: u32,
+    /// 売上に充てた額
+    pub amount: u64,
+}
+
+/// 担当者別の集計
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+pub struct StaffTotal {
+    pub staff_id: String,
+    #[specta(optional)]
+    pub staff_name: Option<String>,
+    pub sale_count: u32,
+    pub sale_amount: u64,
+    /// 返品の件数と額
+    pub refund_count: u32,
+    pub refund_amount: u64,
+    /// 取消の件数と額
+    #[serde(default)]
+    pub void_count: u32,
+    #[serde(default)]
+    pub void_amount: u64,
+    /// ドロワー在高の設定（釣銭の補充・回収）の回数
+    pub drawer_count: u32,
+}
+
+/// 時間帯別の売上
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+pub struct HourlySales {
+    /// 日付（YYYY-MM-DD、端末のタイムゾーン）
+    pub date: String,
+    /// 時（0〜23）
+    pub hour: u32,
+    pub count: u32,
+    pub amount: u64,
+}
+
+/// 支払方法別の集計
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+pub struct PaymentMethodTotal {
+    /// 支払手段名
+    pub method: String,
+    /// 売上件数（併用された売上はそれぞれの支払方法で数える）
+    pub count: u32,
+    /// 受取額（現金は釣り銭を除く）
+    pub amount: u64,
+    /// 返品・取消の件数と額（元の売上で最も多く支払われた方法に計上する）
+    pub refund_count: u32,
+    pub refund_amount: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn card(last4: &str) -> CardDetails {
+        serde_json::from_value(json!({
+            "brand": "visa",
+            "last4": last4,
+            "exp_month": 12,
+            "exp_year": 2028,
+            "cardholder_name": "TARO MIZ",
+            "funding": "credit",
+            "terminal_serial_number": null,
+            "merchant_name": null,
+            "transaction_type": "sale",
+            "payment_type": null,
+            "transaction_at": null,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sanitized_masks_full_pan_to_last4() {
+        assert_eq!(card("4242 4242 4242 4242").sanitized().last4.as_deref(), Some("4242"));
+        assert_eq!(card("4111-1111-1111-1234").sanitized().last4.as_deref(), Some("1234"));
+        assert_eq!(card("1234").sanitized().last4.as_deref(), Some("1234"));
+        // 4桁に満たない値は残さない
+        assert_eq!(card("12").sanitized().last4, None);
+    }
+
+    #[test]
+    fn test_sanitized_drops_expiry_and_cardholder() {
+        let sanitized = card("4242").sanitized();
+        assert_eq!(sanitized.exp_month, None);
+        assert_eq!(sanitized.exp_year, None);
+        assert_eq!(sanitized.cardholder_name, None);
+        assert_eq!(sanitized.brand.as_deref(), Some("visa"));
+        assert_eq!(sanitized.transaction_type.as_deref(), Some("sale"));
+    }
+
+    #[test]
+    fn test_purged_drops_last4() {
+        let purged = card("4242").sanitized().purged();
+        assert!(purged.is_purged());
+        assert_eq!(purged.brand.as_deref(), Some("visa"));
+        assert!(!card("4242").sanitized().is_purged());
+    }
+
+    #[test]
+    fn test_receipt_card_details_are_sanitized_on_load() {
+        let receipt: ReceiptData = serde_json::from_value(json!({
+            "event_name": "テスト即売会",
+            "circle_name": null,
+            "venue_address": null,
+            "sale_start_date_time": null,
+            "staff_id": "S001",
+            "customer_name": null,
+            "items": [],
+            "total": 3000,
+            "payments": [{ "method": "クレジットカード", "amount": 3000 }],
+            "tax_rate": 10,
+            "tax_amount": 272,
+            "receipt_number": "C107-00042",
+            "card_details": {
+                "brand": "visa",
+                "last4": "4242424242424242",
+                "exp_month": 12,
+                "exp_year": 2028,
+                "cardholder_name": "TARO MIZ",
+                "track2": ";4242424242424242=28121010000000000000?",
+                "funding": "credit",
+                "terminal_serial_number": null,
+                "merchant_name": null,
+                "transaction_type": "sale",
+                "payment_type": null,
+                "transaction_at": null
+            },
+            "payment_intent_id": null,
+            "change_breakdown": null,
+        }))
+        .unwrap();
+
+        let saved = serde_json::to_string(&receipt).unwrap();
+        assert!(!saved.contains("4242424242424242"));
+        assert!(!saved.contains("track2"));
+        assert!(!saved.contains("TARO MIZ"));
+        assert!(!saved.contains("2028"));
+        assert_eq!(receipt.card_details.unwrap().last4.as_deref(), Some("4242"));
+    }
+}
@@ -0,0 +1,46 @@
+//! JSON Schema の生成
+//!
+//! レシートと閉局レポートの型を1つのスキーマの `$defs` にまとめる
+//! スキーマは `schema/receipt.schema.json` にコミットしてあり、型を変更した場合は
+//! `UPDATE_SCHEMA=1 cargo test` でファイルを更新し、フロントエンドの型も生成し直す
+
+use crate::receipt::{ClosingReportData, ReceiptData};
+use schemars::generate::SchemaSettings;
+use serde_json::{json, Value};
+
+/// レシート・閉局レポートの JSON Schema
+pub fn receipt_schema() -> Value {
+    let mut generator = SchemaSettings::draft2020_12().into_generator();
+    generator.subschema_for::<ReceiptData>();
+    generator.subschema_for::<ClosingReportData>();
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "mizpos receipt models",
+        "$defs": generator.take_definitions(true),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// コミットしたスキーマが型と一致するか（UPDATE_SCHEMA=1 のときは書き換える）
+    #[test]
+    fn test_committed_schema_is_up_to_date() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("schema/receipt.schema.json");
+        let actual = serde_json::to_string_pretty(&receipt_schema()).unwrap() + "\n";
+
+        if std::env::var("UPDATE_SCHEMA").is_ok() {
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("Schema not found: {} (run with UPDATE_SCHEMA=1)", path.display()));
+        assert_eq!(
+            expected, actual,
+            "Receipt models changed (run with UPDATE_SCHEMA=1 and regenerate the frontend types)"
+        );
+    }
+}
@@ -5,9 +5,13 @@ import android.bluetooth.BluetoothAdapter
 import android.bluetooth.BluetoothDevice
 import android.bluetooth.BluetoothManager
 import android.bluetooth.BluetoothSocket
+import android.content.BroadcastReceiver
 import android.content.Context
+import android.content.Intent
+import android.content.IntentFilter
 import android.util.Log
 import java.io.IOException
+import java.io.InputStream
 import java.io.OutputStream
 import java.util.UUID
 
@@ -56,6 +60,10 @@ class CitizenPrinter(private val context: Context) {
         private val ESC_REVERSE_ON = byteArrayOf(0x1D, 0x42, 0x01) // GS B n
         private val ESC_REVERSE_OFF = byteArrayOf(0x1D, 0x42, 0x00)
 
+        // DLE EOT 4 - Real-time paper sensor status
+        private val DLE_EOT_PAPER = byteArrayOf(0x10, 0x04, 0x04)
+        private const val STATUS_TIMEOUT_MS = 500L
+
         // Japanese character set (Shift-JIS for CMP-30II)
         private val ESC_KANJI_MODE = byteArrayOf(0x1C, 0x26) // Enable Kanji mode
         private val ESC_KANJI_OFF = byteArrayOf(0x1C, 0x2E) // Disable Kanji mode
@@ -68,8 +76,16 @@ class CitizenPrinter(private val context: Context) {
     private var bluetoothAdapter: BluetoothAdapter? = null
     private var socket: BluetoothSocket? = null
     private var outputStream: OutputStream? = null
+    private var inputStream: InputStream? = null
     private var connectedDevice: BluetoothDevice? = null
 
+    // Last signal strength seen during discovery (classic Bluetooth cannot read RSSI while connected)
+    @Volatile private var lastRssi: Short? = null
+    private var rssiAddress: String? = null
+    // Printer to measure while disconnected (kept after disconnect)
+    private var lastAddress: String? = null
+    private var rssiReceiver: BroadcastReceiver? = null
+
     init {
         val bluetoothManager = context.getSystemService(Context.BLUETOOTH_SERVICE) as BluetoothManager
         bluetoothAdapter = bluetoothManager.adapter
@@ -106,7 +122,13 @@ class CitizenPrinter(private val context: Context) {
             socket = device.createRfcommSocketToServiceRecord(SPP_UUID)
             socket?.connect()
             outputStream = socket?.outputStream
+            inputStream = socket?.inputStream
             connectedDevice = device
+            lastAddress = address
+            // Keep the signal measured for this printer before connecting
+            if (rssiAddress != address) {
+                lastRssi = null
+            }
 
             Log.i(TAG, "Connected to printer: ${device.name}")
             return true
@@ -123,11 +145,13 @@ class CitizenPrinter(private val context: Context) {
     fun disconnect() {
         try {
             outputStream?.close()
+            inputStream?.close()
             socket?.close()
         } catch (e: IOException) {
             Log.e(TAG, "Disconnect error: ${e.message}")
         } finally {
             outputStream = null
+            inputStream = null
             socket = null
             connectedDevice = null
         }
@@ -451,6 +475,88 @@ class CitizenPrinter(private val context: Context) {
         return true
     }
 
+    /**
+     * Read paper sensor status (DLE EOT 4)
+     * Returns null if the printer does not answer within the timeout
+     */
+    fun readPaperStatus(): Int? {
+        val input = inputStream ?: return null
+        return try {
+            while (input.available() > 0) input.read()
+            if (!write(DLE_EOT_PAPER)) return null
+            val deadline = System.currentTimeMillis() + STATUS_TIMEOUT_MS
+            while (input.available() == 0) {
+                if (System.currentTimeMillis() > deadline) return null
+                Thread.sleep(20)
+            }
+            input.read()
+        } catch (e: IOException) {
+            Log.e(TAG, "Status read error: ${e.message}")
+            null
+        }
+    }
+
+    /**
+     * Battery level of the connected printer (0-100)
+     * Uses BluetoothDevice.getBatteryLevel, which is only reported by printers
+     * supporting the Bluetooth battery indicator; returns null otherwise
+     */
+    fun getBatteryLevel(): Int? {
+        val device = connectedDevice ?: return null
+        return try {
+            val level = device.javaClass.getMethod("getBatteryLevel").invoke(device) as Int
+            if (level in 0..100) level else null
+        } catch (e: Exception) {
+            null
+        }
+    }
+
+    /**
+     * Last observed signal strength in dBm (null until refreshSignal finds the printer)
+     */
+    fun getRssi(): Int? = lastRssi?.takeIf { rssiAddress == lastAddress }?.toInt()
+
+    /**
+     * Run a short discovery to measure the last printer's signal strength
+     * Discovery disrupts a live connection, so this is skipped while connected
+     * (the value measured before connecting is kept)
+     */
+    @SuppressLint("MissingPermission")
+    fun refreshSignal(): Boolean {
+        if (isConnected()) return false
+        val adapter = bluetoothAdapter ?: return false
+        val address = lastAddress ?: return false
+        if (rssiReceiver == null) {
+            val receiver = object : BroadcastReceiver() {
+                override fun onReceive(ctx: Context, intent: Intent) {
+                    when (intent.action) {
+                        BluetoothDevice.ACTION_FOUND -> {
+                            @Suppress("DEPRECATION")
+                            val found: BluetoothDevice? = intent.getParcelableExtra(BluetoothDevice.EXTRA_DEVICE)
+                            if (found?.address == address) {
+                                lastRssi = intent.getShortExtra(BluetoothDevice.EXTRA_RSSI, Short.MIN_VALUE)
+                                    .takeIf { it != Short.MIN_VALUE }
+                                rssiAddress = address
+                                adapter.cancelDiscovery()
+                            }
+                        }
+                        BluetoothAdapter.ACTION_DISCOVERY_FINISHED -> {
+                            context.unregisterReceiver(this)
+                            rssiReceiver = null
+                        }
+                    }
+                }
+            }
+            val filter = IntentFilter().apply {
+                addAction(BluetoothDevice.ACTION_FOUND)
+                addAction(BluetoothAdapter.ACTION_DISCOVERY_FINISHED)
+            }
+            context.registerReceiver(receiver, filter)
+            rssiReceiver = receiver
+        }
+        return adapter.startDiscovery()
+    }
+
     /**
      * Write raw bytes to printer
      */
@@ -63,14 +63,20 @@ class MainActivity : TauriActivity() {
         webView.addJavascriptInterface(PrinterBridge(), "MizPosPrinter")
         // Add JavaScript interface for terminal authentication
         webView.addJavascriptInterface(TerminalAuthBridge(), "MizPosTerminalAuth")
+        // Add JavaScript interface for background work (foreground service)
+        webView.addJavascriptInterface(BackgroundWorkBridge(), "MizPosBackground")
     }
 
     private fun requestBluetoothPermissions() {
         if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.S) {
-            val permissions = arrayOf(
+            val permissions = mutableListOf(
                 Manifest.permission.BLUETOOTH_CONNECT,
                 Manifest.permission.BLUETOOTH_SCAN
             )
+            // フォアグラウンドサービスの通知を表示するため
+            if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.TIRAMISU) {
+                permissions.add(Manifest.permission.POST_NOTIFICATIONS)
+            }
             val permissionsToRequest = permissions.filter {
                 ContextCompat.checkSelfPermission(this, it) != PackageManager.PERMISSION_GRANTED
             }
@@ -86,9 +92,41 @@ class MainActivity : TauriActivity() {
 
     override fun onDestroy() {
         printer.disconnect()
+        PosForegroundService.releaseAll(this)
         super.onDestroy()
     }
 
+    /**
+     * JavaScript Interface for background work
+     * 同期など、アプリが背面に回っても続けたい処理の前後で呼ぶ:
+     * - window.MizPosBackground.begin("sync")
+     * - window.MizPosBackground.end("sync")
+     */
+    inner class BackgroundWorkBridge {
+        @JavascriptInterface
+        fun begin(work: String): String {
+            return try {
+                PosForegroundService.acquire(this@MainActivity, work)
+                JSONObject().apply {
+                    put("success", true)
+                }.toString()
+            } catch (e: Exception) {
+                JSONObject().apply {
+                    put("success", false)
+                    put("error", e.message ?: "Unknown error")
+                }.toString()
+            }
+        }
+
+        @JavascriptInterface
+        fun end(work: String): String {
+            PosForegroundService.release(this@MainActivity, work)
+            return JSONObject().apply {
+                put("success", true)
+            }.toString()
+        }
+    }
+
     /**
      * JavaScript Interface for Bluetooth printer control
      * These methods can be called from JavaScript as:
@@ -165,6 +203,44 @@ class MainActivity : TauriActivity() {
             }.toString()
         }
 
+        /**
+         * 用紙・電池残量・電波強度（取得できない項目はnull）
+         */
+        @JavascriptInterface
+        fun getPrinterStatus(): String {
+            return try {
+                val connected = printer.isConnected()
+                val paper = if (connected) printer.readPaperStatus() else null
+                JSONObject().apply {
+                    put("success", true)
+                    put("connected", connected)
+                    put("paper", paper?.let {
+                        JSONObject().apply {
+                            put("near_end", it and 0x0c != 0)
+                            put("out", it and 0x60 != 0)
+                        }
+                    } ?: JSONObject.NULL)
+                    put("battery_level", printer.getBatteryLevel() ?: JSONObject.NULL)
+                    put("rssi", printer.getRssi() ?: JSONObject.NULL)
+                }.toString()
+            } catch (e: Exception) {
+                JSONObject().apply {
+                    put("success", false)
+                    put("error", e.message ?: "Unknown error")
+                }.toString()
+            }
+        }
+
+        /**
+         * 電波強度を測り直す（結果は次の getPrinterStatus に反映）
+         */
+        @JavascriptInterface
+        fun refreshSignal(): String {
+            return JSONObject().apply {
+                put("success", printer.refreshSignal())
+            }.toString()
+        }
+
         @JavascriptInterface
         fun printText(text: String): String {
             return try {
@@ -218,6 +294,8 @@ class MainActivity : TauriActivity() {
 
         @JavascriptInterface
         fun printReceipt(jsonData: String): String {
+            // 長いレシートの印刷中に決済アプリへ切り替わっても止まらないよう、フォアグラウンドサービスで保持する
+            PosForegroundService.acquire(this@MainActivity, PosForegroundService.WORK_PRINT)
             return try {
                 if (!printer.isConnected()) {
                     return JSONObject().apply {
@@ -344,11 +422,14 @@ class MainActivity : TauriActivity() {
                     put("success", false)
                     put("error", e.message ?: "Unknown error")
                 }.toString()
+            } finally {
+                PosForegroundService.release(this@MainActivity, PosForegroundService.WORK_PRINT)
             }
         }
 
         @JavascriptInterface
         fun printClosingReport(jsonData: String): String {
+            PosForegroundService.acquire(this@MainActivity, PosForegroundService.WORK_PRINT)
             return try {
                 if (!printer.isConnected()) {
                     return JSONObject().apply {
@@ -457,6 +538,8 @@ class MainActivity : TauriActivity() {
                     put("success", false)
                     put("error", e.message ?: "Unknown error")
                 }.toString()
+            } finally {
+                PosForegroundService.release(this@MainActivity, PosForegroundService.WORK_PRINT)
             }
         }
     }
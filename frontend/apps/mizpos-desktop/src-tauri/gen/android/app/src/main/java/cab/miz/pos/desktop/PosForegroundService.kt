@@ -0,0 +1,130 @@
+package cab.miz.pos.desktop
+
+import android.app.Notification
+import android.app.NotificationChannel
+import android.app.NotificationManager
+import android.app.Service
+import android.content.Context
+import android.content.Intent
+import android.content.pm.ServiceInfo
+import android.os.Build
+import android.os.IBinder
+import android.util.Log
+import androidx.core.app.NotificationCompat
+import androidx.core.app.ServiceCompat
+import androidx.core.content.ContextCompat
+
+/**
+ * Foreground service that keeps printing and sync alive while the activity is backgrounded
+ *
+ * Switching to a payment app during checkout moves the POS to the background, and Android
+ * may then throttle or kill the process in the middle of a long receipt or a pending upload.
+ * Callers hold the service with acquire()/release() around such work. Both are delivered to
+ * the service as start commands and counted there, so every start reaches startForeground()
+ * before the service can stop itself, even when the work finishes right away.
+ */
+class PosForegroundService : Service() {
+
+    companion object {
+        private const val TAG = "PosForegroundService"
+        private const val CHANNEL_ID = "pos_background_work"
+        private const val NOTIFICATION_ID = 1001
+
+        private const val ACTION_ACQUIRE = "cab.miz.pos.desktop.action.ACQUIRE_WORK"
+        private const val ACTION_RELEASE = "cab.miz.pos.desktop.action.RELEASE_WORK"
+        private const val ACTION_RELEASE_ALL = "cab.miz.pos.desktop.action.RELEASE_ALL_WORK"
+        private const val EXTRA_WORK = "work"
+
+        /** Work kind: printing over Bluetooth */
+        const val WORK_PRINT = "print"
+        /** Work kind: uploading pending data */
+        const val WORK_SYNC = "sync"
+
+        /**
+         * Start (or keep) the service for a piece of work
+         */
+        fun acquire(context: Context, work: String) {
+            send(context, ACTION_ACQUIRE, work)
+        }
+
+        /**
+         * Release a piece of work (the service stops when nothing is left)
+         */
+        fun release(context: Context, work: String) {
+            send(context, ACTION_RELEASE, work)
+        }
+
+        /**
+         * Release everything (when the activity is destroyed)
+         */
+        fun releaseAll(context: Context) {
+            send(context, ACTION_RELEASE_ALL, null)
+        }
+
+        private fun send(context: Context, action: String, work: String?) {
+            val intent = Intent(context, PosForegroundService::class.java).setAction(action)
+            if (work != null) intent.putExtra(EXTRA_WORK, work)
+            try {
+                ContextCompat.startForegroundService(context, intent)
+            } catch (e: Exception) {
+                // Starting from the background is restricted on Android 12+; the work still runs
+                Log.w(TAG, "Failed to send $action to foreground service: ${e.message}")
+            }
+        }
+    }
+
+    // Start commands arrive on the main thread one at a time, so no locking is needed
+    private val holders = WorkHolders()
+
+    override fun onBind(intent: Intent?): IBinder? = null
+
+    override fun onCreate() {
+        super.onCreate()
+        startInForeground()
+    }
+
+    override fun onStartCommand(intent: Intent?, flags: Int, startId: Int): Int {
+        // Every startForegroundService() call must be answered with startForeground(),
+        // including the ones that only release work
+        startInForeground()
+        val work = intent?.getStringExtra(EXTRA_WORK)
+        when (intent?.action) {
+            ACTION_ACQUIRE -> if (work != null) holders.acquire(work)
+            ACTION_RELEASE -> if (work != null) holders.release(work)
+            ACTION_RELEASE_ALL -> holders.clear()
+        }
+        if (holders.isIdle) {
+            // Only stops if no newer start command is still pending
+            stopSelf(startId)
+        }
+        // Do not restart after the process is killed; the work itself is retried by the app
+        return START_NOT_STICKY
+    }
+
+    private fun startInForeground() {
+        val type = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.Q) {
+            ServiceInfo.FOREGROUND_SERVICE_TYPE_CONNECTED_DEVICE or ServiceInfo.FOREGROUND_SERVICE_TYPE_DATA_SYNC
+        } else {
+            0
+        }
+        ServiceCompat.startForeground(this, NOTIFICATION_ID, buildNotification(), type)
+    }
+
+    private fun buildNotification(): Notification {
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+            val channel = NotificationChannel(
+                CHANNEL_ID,
+                "印刷・同期",
+                NotificationManager.IMPORTANCE_LOW
+            )
+            getSystemService(NotificationManager::class.java).createNotificationChannel(channel)
+        }
+        return NotificationCompat.Builder(this, CHANNEL_ID)
+            .setSmallIcon(R.mipmap.ic_launcher)
+            .setContentTitle(getString(R.string.app_name))
+            .setContentText("印刷・同期を続けています")
+            .setOngoing(true)
+            .setPriority(NotificationCompat.PRIORITY_LOW)
+            .build()
+    }
+}
@@ -0,0 +1,43 @@
+package cab.miz.pos.desktop
+
+/**
+ * Reference count of in-progress background work, per work kind
+ *
+ * Kept free of Android types so the counting can be unit tested on the JVM.
+ */
+class WorkHolders {
+    private val counts = mutableMapOf<String, Int>()
+
+    /** True while no work is held */
+    val isIdle: Boolean
+        get() = counts.isEmpty()
+
+    /**
+     * Hold one piece of work
+     *
+     * @return true if nothing was held before
+     */
+    fun acquire(work: String): Boolean {
+        val wasIdle = isIdle
+        counts[work] = (counts[work] ?: 0) + 1
+        return wasIdle
+    }
+
+    /**
+     * Release one piece of work (releasing work that is not held is ignored)
+     *
+     * @return true if nothing is held afterwards
+     */
+    fun release(work: String): Boolean {
+        val count = counts[work]
+        if (count != null) {
+            if (count <= 1) counts.remove(work) else counts[work] = count - 1
+        }
+        return isIdle
+    }
+
+    /** Release everything */
+    fun clear() {
+        counts.clear()
+    }
+}
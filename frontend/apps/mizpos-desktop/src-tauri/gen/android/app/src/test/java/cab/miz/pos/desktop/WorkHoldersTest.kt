@@ -0,0 +1,40 @@
+package cab.miz.pos.desktop
+
+import org.junit.Assert.assertFalse
+import org.junit.Assert.assertTrue
+import org.junit.Test
+
+class WorkHoldersTest {
+    @Test
+    fun staysHeldUntilEveryAcquireIsReleased() {
+        val holders = WorkHolders()
+        assertTrue(holders.acquire(PosForegroundService.WORK_PRINT))
+        assertFalse(holders.acquire(PosForegroundService.WORK_PRINT))
+        assertFalse(holders.acquire(PosForegroundService.WORK_SYNC))
+
+        assertFalse(holders.release(PosForegroundService.WORK_PRINT))
+        assertFalse(holders.release(PosForegroundService.WORK_SYNC))
+        assertTrue(holders.release(PosForegroundService.WORK_PRINT))
+        assertTrue(holders.isIdle)
+    }
+
+    @Test
+    fun ignoresReleaseOfWorkThatIsNotHeld() {
+        val holders = WorkHolders()
+        assertTrue(holders.release(PosForegroundService.WORK_SYNC))
+
+        holders.acquire(PosForegroundService.WORK_PRINT)
+        assertFalse(holders.release(PosForegroundService.WORK_SYNC))
+        assertFalse(holders.isIdle)
+    }
+
+    @Test
+    fun clearReleasesEverything() {
+        val holders = WorkHolders()
+        holders.acquire(PosForegroundService.WORK_PRINT)
+        holders.acquire(PosForegroundService.WORK_SYNC)
+        holders.clear()
+        assertTrue(holders.isIdle)
+        assertTrue(holders.acquire(PosForegroundService.WORK_SYNC))
+    }
+}
@@ -0,0 +1,200 @@
+//! 年齢確認モジュール
+//!
+//! 成人向け（R18）の商品を含む売上は、スタッフが年齢を確認した記録（スタッフ番号・確認日時）がないと印刷しない
+//! 確認の記録は端末が発行して保存し、レシートに入れた記録は保存済みの記録と照合する（フロントエンドが作った記録は通らない）
+//! 1件の確認は1件の売上にだけ使え、確認の記録はレシートに入れたままジャーナルの売上に残る
+//! 成人向けかどうかは商品マスタの指定（明細のフラグ）と、端末設定のJAN一覧のどちらかで判定する
+
+use crate::config;
+use crate::event_context;
+use crate::receipt::{AgeVerification, ReceiptData, ReceiptItem};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 発行した確認の保存ファイル名
+const VERIFICATIONS_FILE: &str = "age_verifications.json";
+/// 確認の記録を有効とする時間（秒）
+const VERIFICATION_TTL_SECS: u64 = 30 * 60;
+
+/// 発行した確認
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IssuedVerification {
+    verification: AgeVerification,
+    /// 確認を使った売上のレシート番号（同じ売上の再送だけ通す）
+    receipt_number: Option<String>,
+}
+
+/// 成人向けの商品か
+pub fn is_restricted(item: &ReceiptItem, restricted_jans: &[String]) -> bool {
+    item.age_restricted || restricted_jans.contains(&item.jan)
+}
+
+fn load_issued() -> Result<Vec<IssuedVerification>, String> {
+    Ok(storage::load_json(&event_context::scoped(VERIFICATIONS_FILE)?)?.unwrap_or_default())
+}
+
+/// 期限切れの確認を除いて保存
+fn save_issued(issued: &mut Vec<IssuedVerification>, now: u64) -> Result<(), String> {
+    issued.retain(|i| now.saturating_sub(i.verification.verified_at) <= VERIFICATION_TTL_SECS);
+    storage::save_json(&event_context::scoped(VERIFICATIONS_FILE)?, issued)
+}
+
+/// スタッフの年齢確認を記録（確認日時は端末の時計で付ける）
+pub fn confirm(staff_id: &str) -> Result<AgeVerification, String> {
+    if staff_id.trim().is_empty() {
+        return Err("Staff ID is required for age verification".to_string());
+    }
+    let now = storage::now_unix();
+    let verification = AgeVerification {
+        token: Uuid::new_v4().to_string(),
+        staff_id: staff_id.to_string(),
+        verified_at: now,
+    };
+    let mut issued = load_issued()?;
+    issued.push(IssuedVerification {
+        verification: verification.clone(),
+        receipt_number: None,
+    });
+    save_issued(&mut issued, now)?;
+    Ok(verification)
+}
+
+/// 発行済みの確認と照合し、確認をこの売上に使ったことを記録する
+///
+/// 成人向けの商品を含まない売上は確認しない（false を返す）
+fn check_at(
+    receipt: &ReceiptData,
+    restricted_jans: &[String],
+    issued: &mut [IssuedVerification],
+    now: u64,
+) -> Result<bool, String> {
+    if !receipt.items.iter().any(|i| is_restricted(i, restricted_jans)) {
+        return Ok(false);
+    }
+    let verification = receipt
+        .age_verification
+        .as_ref()
+        .ok_or_else(|| "Age verification is required".to_string())?;
+    let found = issued
+        .iter_mut()
+        .find(|i| !verification.token.is_empty() && i.verification.token == verification.token)
+        .filter(|i| {
+            i.verification.staff_id == verification.staff_id && i.verification.verified_at == verification.verified_at
+        })
+        .ok_or_else(|| "Age verification was not issued by this terminal".to_string())?;
+    if found.verification.verified_at > now || now - found.verification.verified_at > VERIFICATION_TTL_SECS {
+        return Err("Age verification has expired".to_string());
+    }
+    match &found.receipt_number {
+        Some(used) if *used != receipt.receipt_number => {
+            Err("Age verification has already been used for another sale".to_string())
+        }
+        _ => {
+            found.receipt_number = Some(receipt.receipt_number.clone());
+            Ok(true)
+        }
+    }
+}
+
+/// 成人向けの商品を含む売上に、端末が発行した有効な年齢確認の記録があるか確認
+pub fn check(receipt: &ReceiptData) -> Result<(), String> {
+    let restricted_jans = config::load()?.age_restricted_jans;
+    if !receipt.items.iter().any(|i| is_restricted(i, &restricted_jans)) {
+        return Ok(());
+    }
+    let now = storage::now_unix();
+    let mut issued = load_issued()?;
+    if check_at(receipt, &restricted_jans, &mut issued, now)? {
+        save_issued(&mut issued, now)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipt::test_support;
+    use crate::storage::test_support::TempDataDir;
+    use serde_json::json;
+
+    fn receipt(age_restricted: bool, verification: serde_json::Value) -> ReceiptData {
+        test_support::receipt("R-1")
+            .item(json!({ "age_restricted": age_restricted }))
+            .with(json!({ "age_verification": verification }))
+            .build()
+    }
+
+    fn issued(token: &str, verified_at: u64) -> IssuedVerification {
+        IssuedVerification {
+            verification: AgeVerification {
+                token: token.to_string(),
+                staff_id: "S001".to_string(),
+                verified_at,
+            },
+            receipt_number: None,
+        }
+    }
+
+    #[test]
+    fn test_restricted_sale_requires_issued_verification() {
+        let now = 10_000;
+        let mut store = vec![issued("t1", now - 60)];
+        let verified = json!({ "token": "t1", "staff_id": "S001", "verified_at": now - 60 });
+        assert!(!check_at(&receipt(false, json!(null)), &[], &mut store, now).unwrap());
+        assert!(check_at(&receipt(true, json!(null)), &[], &mut store, now).is_err());
+        assert!(check_at(&receipt(true, verified), &[], &mut store, now).unwrap());
+
+        // 端末設定のJAN一覧でも判定する
+        let jans = vec!["4900000000011".to_string()];
+        assert!(check_at(&receipt(false, json!(null)), &jans, &mut store, now).is_err());
+    }
+
+    #[test]
+    fn test_forged_or_missing_verification_is_rejected() {
+        let now = 10_000;
+        let mut store = vec![issued("t1", now - 60)];
+        // 端末が発行していない記録、発行した記録の書き換え
+        let forged = json!({ "token": "t9", "staff_id": "S001", "verified_at": now });
+        assert!(check_at(&receipt(true, forged), &[], &mut store, now).is_err());
+        let no_token = json!({ "staff_id": "S001", "verified_at": now - 60 });
+        assert!(check_at(&receipt(true, no_token), &[], &mut store, now).is_err());
+        let other_staff = json!({ "token": "t1", "staff_id": "S002", "verified_at": now - 60 });
+        assert!(check_at(&receipt(true, other_staff), &[], &mut store, now).is_err());
+        assert!(store[0].receipt_number.is_none());
+    }
+
+    #[test]
+    fn test_expired_verification_is_rejected() {
+        let now = 10_000;
+        let verified_at = now - VERIFICATION_TTL_SECS - 1;
+        let mut store = vec![issued("t1", verified_at)];
+        let stale = json!({ "token": "t1", "staff_id": "S001", "verified_at": verified_at });
+        assert!(check_at(&receipt(true, stale), &[], &mut store, now).is_err());
+    }
+
+    #[test]
+    fn test_verification_is_used_by_one_sale() {
+        let now = 10_000;
+        let mut store = vec![issued("t1", now - 60)];
+        let verified = json!({ "token": "t1", "staff_id": "S001", "verified_at": now - 60 });
+        let sale = receipt(true, verified.clone());
+        assert!(check_at(&sale, &[], &mut store, now).is_ok());
+        // 同じ売上の再送は通し、別の売上には使えない
+        assert!(check_at(&sale, &[], &mut store, now).is_ok());
+        let mut other = receipt(true, verified);
+        other.receipt_number = "R-2".to_string();
+        assert!(check_at(&other, &[], &mut store, now).is_err());
+    }
+
+    #[test]
+    fn test_confirm_issues_a_verification_for_check() {
+        let _dir = TempDataDir::new();
+        let verification = confirm("S001").unwrap();
+        let sale = receipt(true, serde_json::to_value(&verification).unwrap());
+        assert!(check(&sale).is_ok());
+        let mut other = sale.clone();
+        other.receipt_number = "R-2".to_string();
+        assert!(check(&other).is_err());
+    }
+}
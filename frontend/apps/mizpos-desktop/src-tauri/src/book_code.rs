@@ -0,0 +1,158 @@
+//! 書籍JANコード（2段目）モジュール
+//!
+//! 書籍の2段目バーコード（192 + Cコード4桁 + 本体価格5桁 + チェックデジット）を検証して読み取る
+//! 商品データの取り込みで使う JAN（1段目）・ISBN の検証もここで行う
+
+/// 2段目バーコードの先頭（書籍JANコード2段目のフラグ）
+const JAN2_PREFIX: &str = "192";
+
+/// 2段目バーコードの内容
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Jan2 {
+    /// 分類コード（Cコード、4桁）
+    pub c_code: String,
+    /// 本体価格
+    pub price: u32,
+}
+
+/// 2段目バーコードを読み取る
+///
+/// チェックデジットを含む13桁、または含まない12桁の数字を受け付ける
+pub fn parse_jan2(jan2: &str) -> Result<Jan2, String> {
+    if !jan2.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("JAN2 must be digits only: {}", jan2));
+    }
+    if jan2.len() != 12 && jan2.len() != 13 {
+        return Err(format!("JAN2 must be 12 or 13 digits: {}", jan2));
+    }
+    if !jan2.starts_with(JAN2_PREFIX) {
+        return Err(format!("JAN2 must start with {}: {}", JAN2_PREFIX, jan2));
+    }
+    if jan2.len() == 13 && !check_digit_matches(jan2) {
+        return Err(format!("JAN2 check digit mismatch: {}", jan2));
+    }
+
+    // 数字のみであることを確認済みのため位置で切り出せる
+    let price = jan2[7..12]
+        .parse()
+        .map_err(|e| format!("Invalid JAN2 price: {}", e))?;
+    Ok(Jan2 {
+        c_code: jan2[3..7].to_string(),
+        price,
+    })
+}
+
+/// JAN（EAN-13・短縮のEAN-8）として正しいか（チェックデジットまで検証）
+pub fn is_valid_jan(jan: &str) -> bool {
+    (jan.len() == 13 || jan.len() == 8) && jan.bytes().all(|b| b.is_ascii_digit()) && check_digit_matches(jan)
+}
+
+/// ISBNを検証して13桁に揃える
+///
+/// ハイフン・空白は除く。10桁のISBNは末尾の X を含めて検証し、978 から始まる13桁に変換する
+pub fn normalize_isbn(isbn: &str) -> Result<String, String> {
+    let code: String = isbn
+        .chars()
+        .filter(|c| *c != '-' && !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if !code.is_ascii() {
+        return Err(format!("Invalid ISBN: {}", isbn));
+    }
+    match code.len() {
+        13 => {
+            if !(code.starts_with("978") || code.starts_with("979")) || !is_valid_jan(&code) {
+                return Err(format!("Invalid ISBN: {}", isbn));
+            }
+            Ok(code)
+        }
+        10 => {
+            let (body, check) = code.split_at(9);
+            if !body.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(format!("Invalid ISBN: {}", isbn));
+            }
+            let sum: u32 = digits_of(body)
+                .iter()
+                .enumerate()
+                .map(|(i, d)| (10 - i as u32) * d)
+                .sum();
+            let expected = match (11 - sum % 11) % 11 {
+                10 => "X".to_string(),
+                d => d.to_string(),
+            };
+            if check != expected {
+                return Err(format!("ISBN check digit mismatch: {}", isbn));
+            }
+            let body = format!("978{}", body);
+            let check = ean_check_digit(&digits_of(&body));
+            Ok(format!("{}{}", body, check))
+        }
+        _ => Err(format!("ISBN must be 10 or 13 digits: {}", isbn)),
+    }
+}
+
+/// JAN（EAN-13・EAN-8）のチェックデジットを検証
+///
+/// チェックデジットの隣から左へ 3, 1, 3, ... の重みで数える（桁数によらず同じ計算になる）
+fn check_digit_matches(code: &str) -> bool {
+    let digits = digits_of(code);
+    let Some((&check, body)) = digits.split_last() else {
+        return false;
+    };
+    ean_check_digit(body) == check
+}
+
+/// チェックデジットを除いた数字からチェックデジットを求める
+fn ean_check_digit(body: &[u32]) -> u32 {
+    let sum: u32 = body
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { d * 3 } else { *d })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+fn digits_of(code: &str) -> Vec<u32> {
+    code.bytes().map(|b| u32::from(b - b'0')).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_jan2_with_check_digit() {
+        let jan2 = parse_jan2("1920094016000").unwrap();
+        assert_eq!(jan2.c_code, "0094");
+        assert_eq!(jan2.price, 1600);
+    }
+
+    #[test]
+    fn test_parse_jan2_without_check_digit() {
+        let jan2 = parse_jan2("192009412345").unwrap();
+        assert_eq!(jan2.price, 12345);
+    }
+
+    #[test]
+    fn test_parse_jan2_rejects_invalid() {
+        assert!(parse_jan2("1920094016005").is_err()); // チェックデジット不一致
+        assert!(parse_jan2("9784000000000").is_err()); // 1段目
+        assert!(parse_jan2("19200940160").is_err());
+        assert!(parse_jan2("１９２００９４０１６０００").is_err());
+    }
+
+    #[test]
+    fn test_jan_and_isbn_check_digits() {
+        assert!(is_valid_jan("4901234567894"));
+        assert!(is_valid_jan("49123456"));
+        assert!(!is_valid_jan("4901234567890"));
+        assert!(!is_valid_jan("490123456789"));
+
+        assert_eq!(normalize_isbn("978-4-10-109205-8"), Ok("9784101092058".to_string()));
+        assert_eq!(normalize_isbn("4-10-109205-2"), Ok("9784101092058".to_string()));
+        assert_eq!(normalize_isbn("080442957x"), Ok("9780804429573".to_string()));
+        assert!(normalize_isbn("4-10-109205-3").is_err());
+        assert!(normalize_isbn("4901234567894").is_err()); // ISBNではないJAN
+    }
+}
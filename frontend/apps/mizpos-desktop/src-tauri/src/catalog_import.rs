@@ -0,0 +1,342 @@
+//! 商品データの取り込みモジュール
+//!
+//! サークルから表計算ソフトのファイル（CSV・Excel）で受け取った商品一覧を読み込み、
+//! 列の割り当てに従って商品データに変換する（JAN・ISBNはチェックデジットまで検証する）
+//! 取り込んだ商品はフロントエンドのローカルの商品キャッシュに追加する
+//!
+//! 1行目は見出しの行とし、列は見出しの名前で割り当てる
+//! CSVは UTF-8（BOM付きも可）と Shift_JIS のどちらでも読める
+
+use crate::book_code;
+use calamine::Reader;
+use encoding_rs::SHIFT_JIS;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// 取り込める最大の行数（見出しを除く）
+const MAX_ROWS: usize = 20_000;
+
+/// 列の割り当て（見出しの名前）
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CatalogMapping {
+    /// 商品名の列
+    pub name: String,
+    /// 値段（税込）の列
+    pub price: String,
+    /// JAN（1段目）の列
+    pub jan: Option<String>,
+    /// 書籍JANコード2段目の列
+    pub jan2: Option<String>,
+    /// ISBNの列
+    pub isbn: Option<String>,
+    /// サークル名（版元）の列
+    pub circle_name: Option<String>,
+    /// 成人向けの列（「1」「true」「○」「R18」などを成人向けとみなす）
+    pub age_restricted: Option<String>,
+}
+
+/// 取り込んだ商品
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ImportedProduct {
+    /// 商品ID（取り込んだ商品はコードから作る）
+    pub id: String,
+    /// JAN（書籍でJANの列がない場合はISBNの13桁）
+    pub jan: String,
+    pub jan2: Option<String>,
+    /// ISBN（13桁）
+    pub isbn: Option<String>,
+    pub is_book: bool,
+    pub name: String,
+    pub circle_name: Option<String>,
+    pub price: u32,
+    pub age_restricted: bool,
+}
+
+/// 取り込めなかった行
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct CatalogImportError {
+    /// ファイル上の行番号（見出しが1行目）
+    pub row: u32,
+    pub message: String,
+}
+
+/// 取り込みの結果
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CatalogImport {
+    pub products: Vec<ImportedProduct>,
+    pub errors: Vec<CatalogImportError>,
+}
+
+/// ファイルを読み込んで商品データに変換する
+///
+/// 拡張子で形式を判断する（csv・txt は CSV、xlsx・xlsm・xls・ods は最初のシート）
+/// 不正な行は飛ばして errors に入れる（見出しに割り当てた列がない場合はファイル全体をエラーにする）
+pub fn import(path: &Path, mapping: &CatalogMapping) -> Result<CatalogImport, String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    let rows = match extension.as_str() {
+        "csv" | "txt" => {
+            let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            read_csv(&bytes)?
+        }
+        "xlsx" | "xlsm" | "xls" | "ods" => read_sheet(path)?,
+        _ => return Err(format!("Unsupported file type: {}", path.display())),
+    };
+    convert(&rows, mapping)
+}
+
+/// 文字コードを判定してCSVを読み込む
+fn read_csv(bytes: &[u8]) -> Result<Vec<Vec<String>>, String> {
+    let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => SHIFT_JIS.decode(bytes).0.into_owned(),
+    };
+    let mut rows = Vec::new();
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    // 空行は読み飛ばされるため、エラーの行番号がファイルと合うように空の行で埋める
+    // （読み飛ばした空行はレコードの開始位置に含まれるため、空行の後ろから行番号を数える）
+    let (mut counted, mut line) = (0, 1);
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("Invalid CSV: {}", e))?;
+        let start = record.position().map_or(counted, |p| p.byte() as usize);
+        let start = start + text[start..].bytes().take_while(|b| *b == b'\n' || *b == b'\r').count();
+        line += text[counted..start].matches('\n').count();
+        counted = start;
+        while rows.len() + 1 < line {
+            rows.push(Vec::new());
+        }
+        rows.push(record.iter().map(|field| field.trim().to_string()).collect());
+    }
+    Ok(rows)
+}
+
+/// 表計算ソフトのファイルの最初のシートを読み込む
+fn read_sheet(path: &Path) -> Result<Vec<Vec<String>>, String> {
+    let mut workbook = calamine::open_workbook_auto(path).map_err(|e| e.to_string())?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| "The workbook has no sheets".to_string())?
+        .map_err(|e| e.to_string())?;
+    // 範囲は値のある最初のセルから始まるため、行番号がシートと合うように空の行で埋める
+    let first_row = range.start().map_or(0, |(row, _)| row as usize);
+    // 数値のセルは整数なら小数点なしで文字列になる（JANが数値で入っている場合もそのまま使える）
+    Ok(std::iter::repeat_n(Vec::new(), first_row)
+        .chain(
+            range
+                .rows()
+                .map(|row| row.iter().map(|cell| cell.to_string().trim().to_string()).collect()),
+        )
+        .collect())
+}
+
+/// 見出しの名前から列番号を探す
+fn column(header: &[String], name: &str) -> Result<usize, String> {
+    header
+        .iter()
+        .position(|h| h == name.trim())
+        .ok_or_else(|| format!("Column not found: {}", name))
+}
+
+fn optional_column(header: &[String], name: &Option<String>) -> Result<Option<usize>, String> {
+    name.as_deref()
+        .filter(|n| !n.trim().is_empty())
+        .map(|n| column(header, n))
+        .transpose()
+}
+
+/// 値段を読み取る（「1,000」「¥1000」「1000円」も受け付ける）
+fn parse_price(value: &str) -> Result<u32, String> {
+    let digits: String = value
+        .trim()
+        .trim_start_matches(['¥', '￥'])
+        .trim_end_matches('円')
+        .chars()
+        .filter(|c| *c != ',')
+        .collect();
+    digits.parse().map_err(|_| format!("Invalid price: {}", value))
+}
+
+fn parse_flag(value: &str) -> bool {
+    matches!(
+        value.trim().to_lowercase().as_str(),
+        "1" | "true" | "yes" | "y" | "○" | "◯" | "r18" | "r-18" | "成人向け"
+    )
+}
+
+/// 1行を商品に変換
+fn convert_row(cell: impl Fn(Option<usize>) -> String, columns: &Columns) -> Result<ImportedProduct, String> {
+    let name = cell(Some(columns.name));
+    if name.is_empty() {
+        return Err("Name is empty".to_string());
+    }
+    let price = parse_price(&cell(Some(columns.price)))?;
+
+    let isbn = match cell(columns.isbn) {
+        isbn if isbn.is_empty() => None,
+        isbn => Some(book_code::normalize_isbn(&isbn)?),
+    };
+    let jan = match cell(columns.jan) {
+        jan if jan.is_empty() => isbn.clone().ok_or_else(|| "JAN or ISBN is required".to_string())?,
+        jan if book_code::is_valid_jan(&jan) => jan,
+        jan => return Err(format!("Invalid JAN: {}", jan)),
+    };
+    let jan2 = match cell(columns.jan2) {
+        jan2 if jan2.is_empty() => None,
+        jan2 => {
+            book_code::parse_jan2(&jan2)?;
+            Some(jan2)
+        }
+    };
+    let circle_name = Some(cell(columns.circle_name)).filter(|c| !c.is_empty());
+
+    Ok(ImportedProduct {
+        id: format!("import-{}", jan),
+        is_book: isbn.is_some() || jan2.is_some() || jan.starts_with("978") || jan.starts_with("979"),
+        jan,
+        jan2,
+        isbn,
+        name,
+        circle_name,
+        price,
+        age_restricted: parse_flag(&cell(columns.age_restricted)),
+    })
+}
+
+/// 見出しから求めた列番号
+struct Columns {
+    name: usize,
+    price: usize,
+    jan: Option<usize>,
+    jan2: Option<usize>,
+    isbn: Option<usize>,
+    circle_name: Option<usize>,
+    age_restricted: Option<usize>,
+}
+
+fn is_blank(row: &[String]) -> bool {
+    row.iter().all(|c| c.is_empty())
+}
+
+/// 読み込んだ行を商品データに変換する
+fn convert(rows: &[Vec<String>], mapping: &CatalogMapping) -> Result<CatalogImport, String> {
+    // 見出しより上の空行は飛ばす
+    let skipped = rows.iter().take_while(|row| is_blank(row)).count();
+    let Some((header, rows)) = rows[skipped..].split_first() else {
+        return Err("The file is empty".to_string());
+    };
+    if rows.len() > MAX_ROWS {
+        return Err(format!("Too many rows: {} (max {})", rows.len(), MAX_ROWS));
+    }
+    let columns = Columns {
+        name: column(header, &mapping.name)?,
+        price: column(header, &mapping.price)?,
+        jan: optional_column(header, &mapping.jan)?,
+        jan2: optional_column(header, &mapping.jan2)?,
+        isbn: optional_column(header, &mapping.isbn)?,
+        circle_name: optional_column(header, &mapping.circle_name)?,
+        age_restricted: optional_column(header, &mapping.age_restricted)?,
+    };
+    if columns.jan.is_none() && columns.isbn.is_none() {
+        return Err("Map a JAN or ISBN column".to_string());
+    }
+
+    let mut result = CatalogImport {
+        products: Vec::new(),
+        errors: Vec::new(),
+    };
+    let mut seen = HashSet::new();
+    for (index, row) in rows.iter().enumerate() {
+        if is_blank(row) {
+            continue;
+        }
+        let cell = |column: Option<usize>| {
+            column
+                .and_then(|c| row.get(c))
+                .cloned()
+                .unwrap_or_default()
+        };
+        let product = convert_row(cell, &columns).and_then(|product| {
+            if seen.insert(product.jan.clone()) {
+                Ok(product)
+            } else {
+                Err(format!("Duplicate code: {}", product.jan))
+            }
+        });
+        match product {
+            Ok(product) => result.products.push(product),
+            Err(message) => result.errors.push(CatalogImportError {
+                row: (skipped + index + 2) as u32,
+                message,
+            }),
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping() -> CatalogMapping {
+        CatalogMapping {
+            name: "商品名".to_string(),
+            price: "頒布価格".to_string(),
+            jan: Some("JAN".to_string()),
+            jan2: None,
+            isbn: Some("ISBN".to_string()),
+            circle_name: Some("サークル".to_string()),
+            age_restricted: Some("R18".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_import_csv_validates_codes() {
+        let csv = "\u{feff}商品名,JAN,ISBN,頒布価格,サークル,R18\n\
+                   新刊セット,4901234567894,,\"1,000\",サークルみず,\n\
+                   既刊,,4-10-109205-2,¥500,,○\n\
+                   \n\
+                   誤ったJAN,4901234567890,,800,,\n\
+                   重複,4901234567894,,300,,\n\
+                   値段なし,49123456,,,,\n";
+        let result = convert(&read_csv(csv.as_bytes()).unwrap(), &mapping()).unwrap();
+
+        assert_eq!(result.products.len(), 2);
+        assert_eq!(result.products[0].price, 1000);
+        assert_eq!(result.products[0].circle_name.as_deref(), Some("サークルみず"));
+        assert!(!result.products[0].is_book);
+        assert_eq!(result.products[1].jan, "9784101092058");
+        assert!(result.products[1].is_book && result.products[1].age_restricted);
+        assert_eq!(
+            result.errors.iter().map(|e| e.row).collect::<Vec<_>>(),
+            vec![5, 6, 7]
+        );
+    }
+
+    #[test]
+    fn test_import_shift_jis_csv_and_missing_columns() {
+        let (bytes, _, _) = SHIFT_JIS.encode("商品名,JAN,頒布価格\n缶バッジ,49123456,300\n");
+        let mapping = CatalogMapping {
+            isbn: None,
+            circle_name: None,
+            age_restricted: None,
+            ..mapping()
+        };
+        let result = convert(&read_csv(&bytes).unwrap(), &mapping).unwrap();
+        assert_eq!(result.products[0].name, "缶バッジ");
+
+        let no_price = CatalogMapping {
+            price: "価格".to_string(),
+            ..mapping
+        };
+        assert!(convert(&read_csv(&bytes).unwrap(), &no_price).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+//! 閉局リマインダーモジュール
+//!
+//! 設定したイベント終了時刻を過ぎても開局したままの場合に通知する
+//! 必要に応じて「閉局してください」の短い伝票も印刷する
+
+use crate::event_context;
+use crate::session;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::thread;
+use std::time::Duration;
+use tauri::Emitter;
+
+/// リマインダー設定の保存ファイル名
+const REMINDER_FILE: &str = "closing_reminder.json";
+/// 確認間隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 閉局リマインダーイベント名
+pub const EVENT_CLOSING_REMINDER: &str = "closing-reminder";
+
+/// リマインダー伝票の印刷先
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ReminderPrinter {
+    pub vendor_id: u16,
+    pub device_id: u16,
+    pub paper_width: Option<u8>,
+}
+
+/// リマインダー設定
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ReminderConfig {
+    /// イベント終了時刻（UNIX秒）
+    pub event_end_at: u64,
+    /// 伝票を印刷する場合の印刷先
+    pub printer: Option<ReminderPrinter>,
+    /// 通知済みか（1回のみ通知する）
+    #[serde(default)]
+    pub fired: bool,
+}
+
+/// フロントエンドへ通知する内容
+#[derive(Debug, Clone, Serialize)]
+pub struct ClosingReminderEvent {
+    pub event_end_at: u64,
+    pub staff_id: Option<String>,
+    pub opened_at: Option<u64>,
+    /// 伝票印刷のエラー（印刷しない・成功した場合はNone）
+    pub print_error: Option<String>,
+}
+
+/// リマインダーを設定
+pub fn schedule(event_end_at: u64, printer: Option<ReminderPrinter>) -> Result<ReminderConfig, String> {
+    let config = ReminderConfig {
+        event_end_at,
+        printer,
+        fired: false,
+    };
+    storage::save_json(&event_context::scoped(REMINDER_FILE)?, &config)?;
+    Ok(config)
+}
+
+/// 現在のリマインダー設定を取得
+pub fn current() -> Result<Option<ReminderConfig>, String> {
+    storage::load_json(&event_context::scoped(REMINDER_FILE)?)
+}
+
+/// リマインダーを解除
+pub fn cancel() -> Result<(), String> {
+    let file = event_context::scoped(REMINDER_FILE)?;
+    if let Some(path) = storage::data_dir().map(|p| p.join(file)) {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(())
+}
+
+/// バックグラウンドで終了時刻を監視する
+pub fn start(app: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(CHECK_INTERVAL);
+        let _ = check(&app);
+    });
+}
+
+fn check(app: &tauri::AppHandle) -> Result<(), String> {
+    let Some(mut config) = current()? else {
+        return Ok(());
+    };
+    if config.fired || storage::now_unix() < config.event_end_at {
+        return Ok(());
+    }
+
+    let state = session::current()?;
+    if !state.open {
+        return Ok(());
+    }
+
+    let print_error = config.printer.as_ref().and_then(|p| print_slip(p).err());
+
+    let _ = app.emit(
+        EVENT_CLOSING_REMINDER,
+        ClosingReminderEvent {
+            event_end_at: config.event_end_at,
+            staff_id: state.staff_id,
+            opened_at: state.opened_at,
+            print_error,
+        },
+    );
+
+    config.fired = true;
+    storage::save_json(&event_context::scoped(REMINDER_FILE)?, &config)
+}
+
+#[cfg(not(target_os = "android"))]
+fn print_slip(printer: &ReminderPrinter) -> Result<(), String> {
+    crate::desktop_printer::print_closing_reminder_slip(
+        printer.vendor_id,
+        printer.device_id,
+        printer.paper_width,
+    )
+}
+
+#[cfg(target_os = "android")]
+fn print_slip(_printer: &ReminderPrinter) -> Result<(), String> {
+    Err("Bluetooth printing not yet implemented".to_string())
+}
@@ -0,0 +1,226 @@
+//! 端末設定モジュール
+//!
+//! 端末ごとの動作設定を保持する
+
+use crate::datetime;
+use crate::heartbeat::HeartbeatConfig;
+use crate::isdn_registry::IsdnRegistryConfig;
+use crate::metrics::MetricsConfig;
+use crate::network_printer::NetworkPrinter;
+use crate::number_format::NumberFormat;
+use crate::paper_monitor::PaperMonitorConfig;
+use crate::points::PointsConfig;
+use crate::price_overrides::{self, PriceOverrideReason};
+use crate::printer_keepalive::PrinterKeepaliveConfig;
+use crate::promotions::Promotion;
+use crate::receipt::ItemOrder;
+use crate::receipt_sections::ReceiptSection;
+use crate::rounding::RoundingConfig;
+use crate::sale_limits::SaleLimitsConfig;
+use crate::serial_printer::SerialPrinter;
+use crate::spooler::UsbPrinter;
+use crate::storage;
+use crate::text_normalize::TextNormalization;
+use crate::venue_guard::VenueGuardConfig;
+use crate::vouchers::{self, VoucherKind};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 設定の保存ファイル名
+const CONFIG_FILE: &str = "config.json";
+
+/// レシート上部に印刷する店舗情報
+///
+/// 未設定の項目はレシートデータの値（サークル名・会場住所）を使う
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct ReceiptHeader {
+    /// 店舗名（サークル名）
+    pub shop_name: Option<String>,
+    /// 住所
+    pub address: Option<String>,
+    /// 電話番号
+    pub phone: Option<String>,
+    /// 適格請求書発行事業者の登録番号
+    pub registration_number: Option<String>,
+    /// スペース番号（ブース番号）
+    pub booth_number: Option<String>,
+}
+
+/// 接続テスト（ウェルカム）ページの内容
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct WelcomePage {
+    /// ロゴ（白黒反転・2倍角で印刷する文字列）
+    pub logo_text: Option<String>,
+    /// タイトル
+    pub title: String,
+    /// 本文の各行
+    pub lines: Vec<String>,
+    /// ターミナルIDのQRコードを印刷する
+    pub terminal_id_qr: bool,
+}
+
+impl Default for WelcomePage {
+    fn default() -> Self {
+        Self {
+            logo_text: None,
+            title: "WELCOME TO mizPOS".to_string(),
+            lines: vec![
+                "mizPOS デスクトップターミナル".to_string(),
+                "接続テスト完了".to_string(),
+            ],
+            terminal_id_qr: false,
+        }
+    }
+}
+
+/// セルフレジ（キオスク）の設定
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct KioskConfig {
+    /// 自動印刷するプリンターのUSBベンダーID
+    pub printer_vendor_id: Option<u16>,
+    /// 自動印刷するプリンターのUSBデバイスID
+    pub printer_device_id: Option<u16>,
+    /// 用紙幅（mm）
+    pub paper_width: Option<u8>,
+    /// 消費税率（%、内税）
+    pub tax_rate: u32,
+    /// レシートに印字する支払手段名
+    pub payment_method_label: String,
+    /// 1会計の上限金額（無人のため）
+    pub max_total: u32,
+    /// 1会計の上限明細数
+    pub max_lines: usize,
+}
+
+impl Default for KioskConfig {
+    fn default() -> Self {
+        Self {
+            printer_vendor_id: None,
+            printer_device_id: None,
+            paper_width: None,
+            tax_rate: 10,
+            payment_method_label: "QR決済".to_string(),
+            max_total: 30000,
+            max_lines: 20,
+        }
+    }
+}
+
+/// 端末設定
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct TerminalConfig {
+    /// 責任者承認なしで再発行できる回数
+    pub reprint_limit: u32,
+    /// レシート明細の並び順
+    pub item_order: ItemOrder,
+    /// フッターのQRコードにレシート内容（署名付き・圧縮）を埋め込む
+    pub receipt_qr_payload: bool,
+    /// クレジット売上票をお客様控・加盟店控の2枚に分けて印刷する
+    pub credit_slip_copies: bool,
+    /// カード情報（下4桁・有効期限）をジャーナルに残す日数
+    pub card_retention_days: u32,
+    /// レシート上部の店舗情報
+    pub receipt_header: ReceiptHeader,
+    /// レシートに差し込む追加セクション（アンケートQR・次回イベントの告知・SNSなど）
+    pub receipt_sections: Vec<ReceiptSection>,
+    /// 接続テストページ
+    pub welcome_page: WelcomePage,
+    /// 印字する日時のタイムゾーン（IANA名）
+    pub timezone: String,
+    /// 金額・数値の書式
+    pub number_format: NumberFormat,
+    /// 印刷する商品名・サークル名の全角・半角の正規化
+    pub text_normalization: TextNormalization,
+    /// セルフレジ（キオスク）
+    pub kiosk: KioskConfig,
+    /// バックオフィスのジャーナル用プリンター（設定時はスプーラーのレシートをミラー印刷する）
+    pub journal_printer: Option<UsbPrinter>,
+    /// 用紙ニアエンド監視
+    pub paper_monitor: PaperMonitorConfig,
+    /// プリンターのキープアライブ
+    pub printer_keepalive: PrinterKeepaliveConfig,
+    /// レシート・オン・デマンド（QRだけの控えを印刷し、必要な方にだけ本レシートを発行する）
+    pub receipt_on_demand: bool,
+    /// メールレシートの送信先リレー（未設定の場合はメール送信できない）
+    pub email_relay_url: Option<String>,
+    /// シミュレーションモード（開発用。USBプリンターの代わりに印刷プレビューをイベントで通知する）
+    pub simulation_mode: bool,
+    /// ハートビート（端末状態の定期送信）
+    pub heartbeat: HeartbeatConfig,
+    /// 開局時の会場ネットワーク確認
+    pub venue_guard: VenueGuardConfig,
+    /// 受け付ける商品券の種別
+    pub voucher_catalog: Vec<VoucherKind>,
+    /// 会員のポイント
+    pub points: PointsConfig,
+    /// キャンペーン
+    pub promotions: Vec<Promotion>,
+    /// 端数調整（合計の丸め）
+    pub rounding: RoundingConfig,
+    /// 価格変更の理由コード
+    pub price_override_reasons: Vec<PriceOverrideReason>,
+    /// 成人向け（R18）として扱う商品のJAN（商品マスタの指定に加えて判定する）
+    pub age_restricted_jans: Vec<String>,
+    /// 売上の上限（超える場合は責任者の承認が必要）
+    pub sale_limits: SaleLimitsConfig,
+    /// LAN接続のレシートプリンター
+    pub network_printers: Vec<NetworkPrinter>,
+    /// シリアル接続のレシートプリンター
+    pub serial_printers: Vec<SerialPrinter>,
+    /// 稼働状況の計測（オプトイン）
+    pub metrics: MetricsConfig,
+    /// ISDNの登録情報の照会（オプトイン）
+    pub isdn_registry: IsdnRegistryConfig,
+}
+
+impl Default for TerminalConfig {
+    fn default() -> Self {
+        Self {
+            reprint_limit: 1,
+            item_order: ItemOrder::default(),
+            receipt_qr_payload: false,
+            credit_slip_copies: false,
+            card_retention_days: 30,
+            receipt_header: ReceiptHeader::default(),
+            receipt_sections: vec![],
+            welcome_page: WelcomePage::default(),
+            timezone: datetime::DEFAULT_TIMEZONE.to_string(),
+            number_format: NumberFormat::default(),
+            text_normalization: TextNormalization::default(),
+            kiosk: KioskConfig::default(),
+            journal_printer: None,
+            paper_monitor: PaperMonitorConfig::default(),
+            printer_keepalive: PrinterKeepaliveConfig::default(),
+            receipt_on_demand: false,
+            email_relay_url: None,
+            simulation_mode: false,
+            heartbeat: HeartbeatConfig::default(),
+            venue_guard: VenueGuardConfig::default(),
+            voucher_catalog: vouchers::default_catalog(),
+            points: PointsConfig::default(),
+            promotions: vec![],
+            rounding: RoundingConfig::default(),
+            price_override_reasons: price_overrides::default_reasons(),
+            age_restricted_jans: vec![],
+            sale_limits: SaleLimitsConfig::default(),
+            network_printers: vec![],
+            serial_printers: vec![],
+            metrics: MetricsConfig::default(),
+            isdn_registry: IsdnRegistryConfig::default(),
+        }
+    }
+}
+
+/// 現在の設定を取得
+pub fn load() -> Result<TerminalConfig, String> {
+    Ok(storage::load_json(CONFIG_FILE)?.unwrap_or_default())
+}
+
+/// 設定を保存
+pub fn save(config: &TerminalConfig) -> Result<(), String> {
+    storage::save_json(CONFIG_FILE, config)
+}
@@ -0,0 +1,98 @@
+//! 日時モジュール
+//!
+//! フロントエンドや決済端末から渡される ISO8601 形式の日時を検証し、
+//! 端末のタイムゾーンに変換して印字用に整形する
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+
+/// 端末のタイムゾーンの既定値
+pub const DEFAULT_TIMEZONE: &str = "Asia/Tokyo";
+/// 印字用の日時形式
+const PRINT_FORMAT: &str = "%Y/%m/%d %H:%M";
+
+/// IANAタイムゾーン名を読み取る
+///
+/// 不正な名前の場合は既定値（Asia/Tokyo）を使う
+pub fn timezone(name: &str) -> Tz {
+    name.parse().unwrap_or(chrono_tz::Asia::Tokyo)
+}
+
+/// ISO8601（RFC3339）形式の日時を読み取る
+///
+/// タイムゾーンの指定がない場合は `tz` の現地時刻として扱う
+pub fn parse_iso8601(value: &str, tz: Tz) -> Result<DateTime<FixedOffset>, String> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt);
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+        .map_err(|e| format!("Invalid ISO8601 datetime: {} ({})", value, e))?;
+    // 夏時間の切り替わりで曖昧な時刻は早い方を採用する
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.fixed_offset())
+        .ok_or_else(|| format!("Invalid ISO8601 datetime: {}", value))
+}
+
+/// ISO8601形式の日時を端末のタイムゾーンの印字形式（例: 2025/12/16 19:30）に変換
+pub fn format_local(value: &str, tz: Tz) -> Result<String, String> {
+    let dt = parse_iso8601(value, tz)?;
+    Ok(dt.with_timezone(&tz).format(PRINT_FORMAT).to_string())
+}
+
+/// 印字用に日時を整形する
+///
+/// 読み取れない場合（フロントエンドで整形済みの文字列など）はそのまま表示する
+pub fn display(value: &str, timezone_name: &str) -> String {
+    format_local(value, timezone(timezone_name)).unwrap_or_else(|_| value.to_string())
+}
+
+/// UNIX秒を端末のタイムゾーンの印字形式に変換
+pub fn display_unix(timestamp: u64, timezone_name: &str) -> String {
+    DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|dt| dt.with_timezone(&timezone(timezone_name)).format(PRINT_FORMAT).to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_converts_utc_to_jst() {
+        assert_eq!(display("2025-12-30T15:30:00.000Z", "Asia/Tokyo"), "2025/12/31 00:30");
+    }
+
+    #[test]
+    fn test_display_keeps_offset_time() {
+        assert_eq!(display("2025-12-16T10:30:00+09:00", "Asia/Tokyo"), "2025/12/16 10:30");
+    }
+
+    #[test]
+    fn test_display_uses_configured_timezone() {
+        assert_eq!(display("2025-07-01T12:00:00Z", "America/New_York"), "2025/07/01 08:00");
+        assert_eq!(display("2025-12-01T12:00:00Z", "America/New_York"), "2025/12/01 07:00");
+    }
+
+    #[test]
+    fn test_display_without_offset_is_local_time() {
+        assert_eq!(display("2025-12-16T10:30:00", "Asia/Tokyo"), "2025/12/16 10:30");
+    }
+
+    #[test]
+    fn test_display_falls_back_to_raw_value() {
+        assert_eq!(display("2025/12/16 10:30", "Asia/Tokyo"), "2025/12/16 10:30");
+        assert_eq!(display("2025-13-01T00:00:00Z", "Asia/Tokyo"), "2025-13-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_display_unix() {
+        assert_eq!(display_unix(1767106800, "Asia/Tokyo"), "2025/12/31 00:00");
+    }
+
+    #[test]
+    fn test_invalid_timezone_falls_back_to_tokyo() {
+        assert_eq!(timezone("Mars/Olympus"), chrono_tz::Asia::Tokyo);
+        assert_eq!(display("2025-12-16T01:30:00Z", "Mars/Olympus"), "2025/12/16 10:30");
+    }
+}
@@ -0,0 +1,241 @@
+//! 内金（予約販売の前受け）モジュール
+//!
+//! 予約販売で内金だけを受け取る売上と、後日残金を受け取る売上を、それぞれ1件の売上としてジャーナルに記録する
+//! 残金の売上は内金の売上のレシート番号を持ち、明細とご注文合計・内金・残金を載せた完了レシートとして印刷する
+//! 売上の集計はその取引で受け取った金額（内金・残金）で行うため、2件の合計がご注文合計になる
+
+use crate::event_context;
+use crate::journal::{self, JournalEntry, JournalRecord};
+use crate::receipt::{DepositTerms, ReceiptData};
+
+/// 内金・残金の条件がレシートの内容と合っているか確認（売上の記録前に呼ぶ）
+///
+/// 残金の売上は、内金の売上が記録済みで、取消されておらず、残金をまだ受け取っていないことも確認する
+pub fn check(receipt: &ReceiptData) -> Result<(), String> {
+    let Some(ref terms) = receipt.deposit else {
+        return Ok(());
+    };
+    match terms.deposit_receipt_number {
+        None => check_deposit(receipt, terms),
+        Some(ref deposit_receipt_number) => {
+            check_balance_against(&journal::entries()?, receipt, terms, deposit_receipt_number)
+        }
+    }
+}
+
+fn check_deposit(receipt: &ReceiptData, terms: &DepositTerms) -> Result<(), String> {
+    let subtotal: u32 = receipt.items.iter().map(|i| i.price).sum();
+    let discount: u32 = receipt.promotions.iter().map(|p| p.discount).sum();
+    if terms.order_total != subtotal.saturating_sub(discount) {
+        return Err("Deposit order total does not match the items".to_string());
+    }
+    if terms.deposit == 0 || terms.deposit >= terms.order_total {
+        return Err("Deposit must be less than the order total".to_string());
+    }
+    if receipt.total != terms.deposit {
+        return Err("Deposit sale total must be the deposit".to_string());
+    }
+    if terms.due_date.is_empty() {
+        return Err("Balance due date is required".to_string());
+    }
+    Ok(())
+}
+
+fn check_balance_against(
+    entries: &[JournalEntry],
+    receipt: &ReceiptData,
+    terms: &DepositTerms,
+    deposit_receipt_number: &str,
+) -> Result<(), String> {
+    let deposit_sale = sales(entries)
+        .find(|s| s.receipt_number == deposit_receipt_number)
+        .ok_or_else(|| format!("Deposit sale not found: {}", deposit_receipt_number))?;
+    let Some(ref original) = deposit_sale.deposit else {
+        return Err(format!("Not a deposit sale: {}", deposit_receipt_number));
+    };
+    if original.is_balance_payment() {
+        return Err(format!("Not a deposit sale: {}", deposit_receipt_number));
+    }
+    if original.order_total != terms.order_total || original.deposit != terms.deposit {
+        return Err("Balance terms do not match the deposit sale".to_string());
+    }
+    if receipt.total != terms.balance() {
+        return Err("Balance sale total must be the balance".to_string());
+    }
+    if is_voided(entries, deposit_receipt_number) {
+        return Err("Deposit sale has been voided".to_string());
+    }
+    // 同じレシート番号の再送は記録済みとして通す
+    if balance_sale_of(entries, deposit_receipt_number)
+        .is_some_and(|s| s.receipt_number != receipt.receipt_number)
+    {
+        return Err("Balance has already been paid".to_string());
+    }
+    Ok(())
+}
+
+fn sales(entries: &[JournalEntry]) -> impl Iterator<Item = &ReceiptData> {
+    entries.iter().filter_map(|e| match &e.record {
+        JournalRecord::Sale { receipt } => Some(receipt.as_ref()),
+        _ => None,
+    })
+}
+
+fn is_voided(entries: &[JournalEntry], receipt_number: &str) -> bool {
+    entries.iter().any(|e| {
+        matches!(&e.record, JournalRecord::Void { receipt_number: n, .. } if n == receipt_number)
+    })
+}
+
+/// 内金の売上に対する、取消されていない残金の売上
+fn balance_sale_of<'a>(entries: &'a [JournalEntry], deposit_receipt_number: &str) -> Option<&'a ReceiptData> {
+    sales(entries).find(|s| {
+        s.deposit
+            .as_ref()
+            .and_then(|d| d.deposit_receipt_number.as_deref())
+            == Some(deposit_receipt_number)
+            && !is_voided(entries, &s.receipt_number)
+    })
+}
+
+/// 残金を受け取った売上（内金の売上のレシート番号から）
+pub fn balance_sale(deposit_receipt_number: &str) -> Result<Option<ReceiptData>, String> {
+    Ok(balance_sale_of(&journal::entries()?, deposit_receipt_number).cloned())
+}
+
+/// 残金を受け取っていない内金の売上（取消済みを除く、記録順）
+pub fn open_deposits() -> Result<Vec<ReceiptData>, String> {
+    let entries = journal::entries()?;
+    Ok(sales(&entries)
+        .filter(|s| s.deposit.as_ref().is_some_and(|d| !d.is_balance_payment()))
+        .filter(|s| !is_voided(&entries, &s.receipt_number))
+        .filter(|s| balance_sale_of(&entries, &s.receipt_number).is_none())
+        .cloned()
+        .collect())
+}
+
+/// 内金の売上から残金の売上を作る（支払情報はフロントエンドで入れて印刷する）
+///
+/// 明細・宛名・会員は内金の売上を引き継ぎ、レシート番号は新しく採番する
+/// 年齢確認は受け渡し時にやり直すため引き継がない
+pub fn prepare_balance(deposit_receipt_number: &str, staff_id: &str) -> Result<ReceiptData, String> {
+    let entries = journal::entries()?;
+    let deposit_sale = sales(&entries)
+        .find(|s| s.receipt_number == deposit_receipt_number)
+        .ok_or_else(|| format!("Deposit sale not found: {}", deposit_receipt_number))?;
+    let terms = deposit_sale
+        .deposit
+        .clone()
+        .filter(|d| !d.is_balance_payment())
+        .ok_or_else(|| format!("Not a deposit sale: {}", deposit_receipt_number))?;
+    let mut receipt = balance_receipt(deposit_sale.clone(), terms, deposit_receipt_number, staff_id);
+    receipt.receipt_number = event_context::next_receipt_number()?;
+    if let Some(ref terms) = receipt.deposit {
+        check_balance_against(&entries, &receipt, terms, deposit_receipt_number)?;
+    }
+    Ok(receipt)
+}
+
+/// 内金の売上の写しを残金の売上に書き換える
+fn balance_receipt(
+    mut receipt: ReceiptData,
+    mut terms: DepositTerms,
+    deposit_receipt_number: &str,
+    staff_id: &str,
+) -> ReceiptData {
+    terms.deposit_receipt_number = Some(deposit_receipt_number.to_string());
+    receipt.staff_id = staff_id.to_string();
+    receipt.total = terms.balance();
+    receipt.tax_amount = receipt.total * receipt.tax_rate / (100 + receipt.tax_rate);
+    receipt.payments = vec![];
+    receipt.sale_start_date_time = Some(chrono::Utc::now().to_rfc3339());
+    receipt.card_details = None;
+    receipt.payment_intent_id = None;
+    receipt.change_breakdown = None;
+    receipt.points = None;
+    receipt.age_verification = None;
+    receipt.deposit = Some(terms);
+    receipt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::ApiEnvironment;
+    use crate::journal::SaleChannel;
+    use crate::receipt::test_support;
+    use serde_json::json;
+
+    fn entry(record: JournalRecord) -> JournalEntry {
+        JournalEntry {
+            id: "E".to_string(),
+            recorded_at: 0,
+            staff_id: Some("S001".to_string()),
+            channel: SaleChannel::Counter,
+            duress: false,
+            environment: ApiEnvironment::Production,
+            record,
+        }
+    }
+
+    fn deposit_sale() -> ReceiptData {
+        test_support::receipt("R-1")
+            .item(json!({ "name": "受注生産 画集", "price": 10000 }))
+            .with(json!({
+                "total": 3000,
+                "deposit": { "order_total": 10000, "deposit": 3000, "due_date": "2026-12-30" },
+            }))
+            .build()
+    }
+
+    #[test]
+    fn test_check_deposit_requires_partial_payment_of_items() {
+        let sale = deposit_sale();
+        assert!(check(&sale).is_ok());
+
+        let mut full = sale.clone();
+        let terms = full.deposit.as_mut().unwrap();
+        terms.deposit = 10000;
+        full.total = 10000;
+        assert!(check(&full).is_err());
+
+        let mut mismatched = sale;
+        mismatched.deposit.as_mut().unwrap().order_total = 9000;
+        assert!(check(&mismatched).is_err());
+    }
+
+    #[test]
+    fn test_balance_links_to_deposit_sale_once() {
+        let deposit = deposit_sale();
+        let mut balance = balance_receipt(deposit.clone(), deposit.deposit.clone().unwrap(), "R-1", "S002");
+        balance.receipt_number = "R-2".to_string();
+        let terms = balance.deposit.clone().unwrap();
+        assert_eq!(balance.total, 7000);
+        assert_eq!(balance.tax_amount, 636);
+        assert!(balance.payments.is_empty());
+
+        let mut entries = vec![entry(JournalRecord::Sale {
+            receipt: Box::new(deposit.clone()),
+        })];
+        assert!(check_balance_against(&entries, &balance, &terms, "R-1").is_ok());
+        assert!(check_balance_against(&entries, &balance, &terms, "R-9").is_err());
+
+        entries.push(entry(JournalRecord::Sale {
+            receipt: Box::new(balance.clone()),
+        }));
+        assert_eq!(balance_sale_of(&entries, "R-1").map(|s| s.receipt_number.as_str()), Some("R-2"));
+        // 同じレシート番号の再送は通し、別の売上での二重の受け取りは拒否する
+        assert!(check_balance_against(&entries, &balance, &terms, "R-1").is_ok());
+        let mut again = balance.clone();
+        again.receipt_number = "R-3".to_string();
+        assert!(check_balance_against(&entries, &again, &terms, "R-1").is_err());
+
+        // 残金の売上を取り消せば受け取り直せる
+        entries.push(entry(JournalRecord::Void {
+            receipt_number: "R-2".to_string(),
+            amount: 7000,
+            reason: "金額誤り".to_string(),
+        }));
+        assert!(check_balance_against(&entries, &again, &terms, "R-1").is_ok());
+    }
+}
@@ -0,0 +1,543 @@
+//! レシートレイアウトのテスト
+//!
+//! 印刷コマンドを文字列に変換するテスト用ドライバーで描画結果を取り出し、
+//! `tests/fixtures/layout/` のゴールデンファイルと比較する
+//! レイアウトを変更した場合は `UPDATE_LAYOUT_FIXTURES=1 cargo test` でファイルを更新し、差分を確認してコミットする
+
+use super::preview::render_text;
+use super::{
+    estimate_length, render_closing_report, render_gift_receipt, render_recall_slip, render_receipt,
+    render_receipt_stub, render_refund_receipt, Reprint,
+};
+use crate::config::TerminalConfig;
+use crate::jp_escpos::{JpPrinter, PaperWidth};
+use crate::journal::{JournalTotals, RefundItem, RefundReceipt, SaleStatus};
+use crate::now_serving::NowServing;
+use crate::receipt::{test_support, ClosingReportData, ReceiptData};
+use escpos::driver::Driver;
+use escpos::errors::Result as DriverResult;
+use serde_json::json;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// 送信されたバイト列を記録するテスト用ドライバー
+#[derive(Clone, Default)]
+pub(super) struct TextSink {
+    bytes: Rc<RefCell<Vec<u8>>>,
+}
+
+impl Driver for TextSink {
+    fn name(&self) -> String {
+        "text-sink".to_string()
+    }
+
+    fn write(&self, data: &[u8]) -> DriverResult<()> {
+        self.bytes.borrow_mut().extend_from_slice(data);
+        Ok(())
+    }
+
+    fn read(&self, _buf: &mut [u8]) -> DriverResult<usize> {
+        Ok(0)
+    }
+
+    fn flush(&self) -> DriverResult<()> {
+        Ok(())
+    }
+}
+
+/// テスト用プリンターで描画し、テキストに変換
+pub(super) fn render_with<F>(paper_width: PaperWidth, draw: F) -> String
+where
+    F: FnOnce(&mut JpPrinter<TextSink>) -> Result<(), String>,
+{
+    let sink = TextSink::default();
+    let mut printer = JpPrinter::with_paper_width(sink.clone(), paper_width);
+    printer.init().unwrap();
+    draw(&mut printer).unwrap();
+    let bytes = sink.bytes.borrow();
+    render_text(&bytes, paper_width.chars())
+}
+
+/// ゴールデンファイルと比較（UPDATE_LAYOUT_FIXTURES=1 のときは書き換える）
+fn assert_fixture(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/layout")
+        .join(format!("{}.txt", name));
+
+    if std::env::var("UPDATE_LAYOUT_FIXTURES").is_ok() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("Fixture not found: {} (run with UPDATE_LAYOUT_FIXTURES=1)", path.display()));
+    assert_eq!(
+        expected, actual,
+        "Layout changed: {} (review and run with UPDATE_LAYOUT_FIXTURES=1 to update)",
+        name
+    );
+}
+
+fn item(circle: &str, name: &str, jan: &str, quantity: u32, price: u32) -> serde_json::Value {
+    json!({
+        "circle_name": circle,
+        "name": name,
+        "jan": jan,
+        "isbn": "",
+        "isdn": null,
+        "jan2": null,
+        "is_book": false,
+        "quantity": quantity,
+        "price": price,
+    })
+}
+
+fn book(circle: &str, name: &str, isdn: &str, jan2: &str, price: u32) -> serde_json::Value {
+    json!({
+        "circle_name": circle,
+        "name": name,
+        "jan": "9784000000000",
+        "isbn": "",
+        "isdn": isdn,
+        "jan2": jan2,
+        "is_book": true,
+        "quantity": 1,
+        "price": price,
+    })
+}
+
+fn receipt(items: Vec<serde_json::Value>, payments: serde_json::Value, extra: serde_json::Value) -> ReceiptData {
+    items
+        .into_iter()
+        .fold(test_support::receipt("C107-00042").no_items(), |receipt, item| receipt.item(item))
+        .with(json!({
+            "circle_name": "サークルみず",
+            "venue_address": "東京都江東区有明3-11-1",
+            "sale_start_date_time": "2025-12-30T01:30:00.000Z",
+            "payments": payments,
+        }))
+        .with(extra)
+        .build()
+}
+
+#[test]
+fn receipt_books_with_isdn_58mm() {
+    let receipt = receipt(
+        vec![
+            book("サークルみず", "技術書 第1巻", "ISDN278-4-0000-0000-0", "1920094016000", 1600),
+            book("サークルみず", "技術書 第2巻", "ISDN278-4-0000-0001-0", "1920094020007", 2000),
+        ],
+        json!([{ "method": "現金", "amount": 5000 }]),
+        json!({
+            "change_breakdown": [
+                { "denomination": 1000, "count": 1 },
+                { "denomination": 100, "count": 4 }
+            ]
+        }),
+    );
+    let text = render_with(PaperWidth::Mm58, |p| {
+        render_receipt(p, &receipt, None, &TerminalConfig::default())
+    });
+    assert_fixture("receipt_books_58", &text);
+}
+
+#[test]
+fn receipt_card_payment_58mm() {
+    let receipt = receipt(
+        vec![item("サークルみず", "アクリルスタンド", "4900000000011", 2, 3000)],
+        json!([{ "method": "クレジットカード", "amount": 3000 }]),
+        json!({
+            "payment_intent_id": "pi_3QabcdefghijklmnOPQRST",
+            "card_details": {
+                "brand": "visa",
+                "last4": "4242",
+                "exp_month": 12,
+                "exp_year": 2028,
+                "cardholder_name": null,
+                "funding": "credit",
+                "terminal_serial_number": "WSC513000000",
+                "merchant_name": "mizPOS",
+                "transaction_type": "sale",
+                "payment_type": "一括",
+                "transaction_at": "2025-12-30T01:30:00Z"
+            }
+        }),
+    );
+    let text = render_with(PaperWidth::Mm58, |p| {
+        render_receipt(p, &receipt, None, &TerminalConfig::default())
+    });
+    assert_fixture("receipt_card_58", &text);
+}
+
+#[test]
+fn receipt_with_sections_58mm() {
+    let receipt = receipt(
+        vec![item("サークルみず", "アクリルスタンド", "4900000000011", 1, 1500)],
+        json!([{ "method": "現金", "amount": 1500 }]),
+        json!({}),
+    );
+    let config = TerminalConfig {
+        receipt_sections: serde_json::from_value(json!([
+            { "slot": "after_header", "lines": ["本日限定 新刊セット"] },
+            { "slot": "after_items", "lines": ["おまけ: ポストカード"] },
+            { "slot": "after_totals", "title": "ご来場ありがとうございます" },
+            { "slot": "footer", "title": "アンケート", "qr": "https://example.com/q?r={receipt_number}" },
+            { "slot": "footer", "lines": ["次回 {event_name} でお会いしましょう", "X: @mizpos"] },
+        ]))
+        .unwrap(),
+        ..TerminalConfig::default()
+    };
+    let text = render_with(PaperWidth::Mm58, |p| render_receipt(p, &receipt, None, &config));
+    assert_fixture("receipt_sections_58", &text);
+}
+
+#[test]
+fn receipt_normalized_names_58mm() {
+    // 全角英数・半角カナの混じった名前は NFKC でそろえてから印刷する
+    let receipt = receipt(
+        vec![
+            item("ｻｰｸﾙＭＩＺ", "ｱｸﾘﾙｽﾀﾝﾄﾞ ＶＯＬ.２", "4900000000011", 1, 1500),
+            item("サークルMIZ", "アクリルスタンド VOL.2", "4900000000011", 1, 1500),
+        ],
+        json!([{ "method": "現金", "amount": 3000 }]),
+        json!({}),
+    );
+    let text = render_with(PaperWidth::Mm58, |p| {
+        render_receipt(p, &receipt, None, &TerminalConfig::default())
+    });
+    assert_fixture("receipt_normalized_names_58", &text);
+}
+
+#[test]
+fn receipt_with_logo_58mm() {
+    // 16x8 の市松模様の PNG
+    let receipt = receipt(
+        vec![item("サークルみず", "アクリルスタンド", "4900000000011", 1, 1500)],
+        json!([{ "method": "現金", "amount": 1500 }]),
+        json!({
+            "logo": {
+                "source": {
+                    "type": "base64",
+                    "data": "iVBORw0KGgoAAAANSUhEUgAAABAAAAAICAAAAADVHSBLAAAAFklEQVR4nGNgAIL/QIBMkypAqX4gAADweD/Bd3h9nQAAAABJRU5ErkJggg=="
+                },
+                "mode": "threshold"
+            }
+        }),
+    );
+    let text = render_with(PaperWidth::Mm58, |p| {
+        render_receipt(p, &receipt, None, &TerminalConfig::default())
+    });
+    assert_fixture("receipt_logo_58", &text);
+}
+
+#[test]
+fn receipt_deposit_58mm() {
+    let receipt = receipt(
+        vec![item("サークルみず", "受注生産 画集", "4900000000011", 1, 10000)],
+        json!([{ "method": "現金", "amount": 5000 }]),
+        json!({
+            "total": 3000,
+            "tax_amount": 272,
+            "deposit": { "order_total": 10000, "deposit": 3000, "due_date": "2026-12-30" },
+        }),
+    );
+    let text = render_with(PaperWidth::Mm58, |p| render_receipt(p, &receipt, None, &TerminalConfig::default()));
+    assert_fixture("receipt_deposit_58", &text);
+}
+
+#[test]
+fn receipt_deposit_balance_58mm() {
+    let receipt = receipt(
+        vec![item("サークルみず", "受注生産 画集", "4900000000011", 1, 10000)],
+        json!([{ "method": "クレジットカード", "amount": 7000 }]),
+        json!({
+            "total": 7000,
+            "tax_amount": 636,
+            "deposit": {
+                "order_total": 10000, "deposit": 3000, "due_date": "2026-12-30",
+                "deposit_receipt_number": "C107-00041",
+            },
+        }),
+    );
+    let text = render_with(PaperWidth::Mm58, |p| render_receipt(p, &receipt, None, &TerminalConfig::default()));
+    assert_fixture("receipt_deposit_balance_58", &text);
+}
+
+#[test]
+fn receipt_item_barcodes_58mm() {
+    let mut sticker = item("サークルみず", "ステッカー", "4901234567894", 1, 300);
+    sticker["print_barcode"] = json!(true);
+    let mut badge = item("サークルみず", "缶バッジ", "49123456", 1, 500);
+    badge["print_barcode"] = json!(true);
+    // チェックデジットが合わない JAN はバーコードを印刷しない
+    let mut invalid = item("サークルみず", "ポストカード", "4900000000010", 1, 200);
+    invalid["print_barcode"] = json!(true);
+    let receipt = receipt(
+        vec![sticker, badge, invalid],
+        json!([{ "method": "現金", "amount": 1000 }]),
+        json!({}),
+    );
+    let text = render_with(PaperWidth::Mm58, |p| {
+        render_receipt(p, &receipt, None, &TerminalConfig::default())
+    });
+    assert_fixture("receipt_item_barcodes_58", &text);
+}
+
+#[test]
+fn receipt_stub_58mm() {
+    let receipt = receipt(
+        vec![item("サークルみず", "アクリルスタンド", "4900000000011", 2, 3000)],
+        json!([{ "method": "現金", "amount": 3000 }]),
+        json!({}),
+    );
+    let text = render_with(PaperWidth::Mm58, |p| {
+        render_receipt_stub(p, &receipt, &TerminalConfig::default())
+    });
+    assert_fixture("receipt_stub_58", &text);
+}
+
+#[test]
+fn receipt_card_payment_80mm() {
+    let receipt = receipt(
+        vec![item("サークルみず", "アクリルスタンド", "4900000000011", 2, 3000)],
+        json!([{ "method": "クレジットカード", "amount": 3000 }]),
+        json!({
+            "payment_intent_id": "pi_3QabcdefghijklmnOPQRST",
+            "card_details": {
+                "brand": "visa",
+                "last4": "4242",
+                "exp_month": 12,
+                "exp_year": 2028,
+                "cardholder_name": null,
+                "funding": "credit",
+                "terminal_serial_number": "WSC513000000",
+                "merchant_name": "mizPOS",
+                "transaction_type": "sale",
+                "payment_type": "一括",
+                "transaction_at": "2025-12-30T01:30:00Z"
+            }
+        }),
+    );
+    let text = render_with(PaperWidth::Mm80, |p| {
+        render_receipt(p, &receipt, None, &TerminalConfig::default())
+    });
+    assert_fixture("receipt_card_80", &text);
+}
+
+#[test]
+fn receipt_goods_reprint_80mm() {
+    let receipt = receipt(
+        vec![
+            item("サークルみず", "ステッカー", "4900000000028", 12, 3600),
+            {
+                let mut badge = item("となりのサークル", "缶バッジ", "4900000000035", 1, 500);
+                badge["price_override"] = json!({
+                    "override_id": "OV-1",
+                    "original_price": 800,
+                    "reason_code": "damaged",
+                    "reason": "汚損・破損",
+                    "supervisor_id": "SV01"
+                });
+                badge
+            },
+        ],
+        json!([{ "method": "現金", "amount": 4100 }]),
+        json!({
+            "customer_name": "みず",
+            "member_id": "M0001",
+            "points": { "earned": 41, "balance": 1250 }
+        }),
+    );
+    let text = render_with(PaperWidth::Mm80, |p| {
+        render_receipt(
+            p,
+            &receipt,
+            Some(Reprint {
+                copy_number: 2,
+                status: SaleStatus::PartiallyRefunded,
+            }),
+            &TerminalConfig::default(),
+        )
+    });
+    assert_fixture("receipt_goods_reprint_80", &text);
+}
+
+#[test]
+fn receipt_with_promotions_58mm() {
+    let mut receipt = receipt(
+        vec![
+            item("サークルみず", "ステッカー", "4900000000028", 3, 900),
+            item("サークルみず", "アクリルスタンド", "4900000000011", 1, 1500),
+        ],
+        json!([{ "method": "現金", "amount": 3000 }]),
+        json!({
+            "promotions": [
+                { "promotion_id": "sticker-3for2", "name": "ステッカー3枚で1枚無料", "discount": 300 }
+            ]
+        }),
+    );
+    receipt.total = 2100;
+    let text = render_with(PaperWidth::Mm58, |p| {
+        render_receipt(p, &receipt, None, &TerminalConfig::default())
+    });
+    assert_fixture("receipt_promotions_58", &text);
+}
+
+#[test]
+fn receipt_rounding_58mm() {
+    let mut receipt = receipt(
+        vec![item("サークルみず", "ポストカード", "4900000000042", 3, 495)],
+        json!([{ "method": "現金", "amount": 1000 }]),
+        json!({ "rounding_adjustment": -5 }),
+    );
+    receipt.total = 490;
+    let text = render_with(PaperWidth::Mm58, |p| {
+        render_receipt(p, &receipt, None, &TerminalConfig::default())
+    });
+    assert_fixture("receipt_rounding_58", &text);
+}
+
+#[test]
+fn gift_receipt_58mm() {
+    let mut receipt = receipt(
+        vec![
+            item("サークルみず", "ステッカー", "4900000000028", 3, 900),
+            item("サークルみず", "アクリルスタンド", "4900000000011", 1, 1500),
+        ],
+        json!([{ "method": "クレジットカード", "amount": 2400 }]),
+        json!({ "customer_name": "みず" }),
+    );
+    receipt.items[1].note = Some("ラッピング済み".to_string());
+    let text = render_with(PaperWidth::Mm58, |p| {
+        render_gift_receipt(p, &receipt, &TerminalConfig::default())
+    });
+    assert!(!text.contains('￥'));
+    assert_fixture("gift_receipt_58", &text);
+}
+
+#[test]
+fn refund_receipt_58mm() {
+    let receipt = receipt(
+        vec![
+            item("サークルみず", "ステッカー", "4900000000028", 3, 900),
+            item("サークルみず", "アクリルスタンド", "4900000000011", 1, 1500),
+        ],
+        json!([{ "method": "現金", "amount": 3000 }]),
+        json!({}),
+    );
+    let refund = RefundReceipt {
+        receipt,
+        refund_id: "0b7c9a52-3f1e-4d8a-9c2b-6e5f4a3d2c1b".to_string(),
+        refunded_at: 1767063600,
+        staff_id: Some("S002".to_string()),
+        items: vec![RefundItem {
+            line_index: 0,
+            jan: "4900000000028".to_string(),
+            name: "ステッカー".to_string(),
+            quantity: 2,
+            amount: 600,
+        }],
+        amount: 600,
+        reason: "印刷不良".to_string(),
+    };
+    let text = render_with(PaperWidth::Mm58, |p| {
+        render_refund_receipt(p, &refund, &TerminalConfig::default())
+    });
+    assert_fixture("refund_receipt_58", &text);
+}
+
+#[test]
+fn recall_slip_58mm() {
+    let state = NowServing {
+        current: Some("P0012".to_string()),
+        call_count: 2,
+        called_at: Some(1767063600),
+        staff_id: Some("S001".to_string()),
+        recent: vec!["P0011".to_string()],
+    };
+    let text = render_with(PaperWidth::Mm58, |p| {
+        render_recall_slip(p, &state, &TerminalConfig::default())
+    });
+    assert_fixture("recall_slip_58", &text);
+}
+
+#[test]
+fn closing_report_with_refunds_58mm() {
+    let report: ClosingReportData = serde_json::from_value(json!({
+        "id": "CR-20251230-01",
+        "terminal_id": "T-0001",
+        "staff_id": "S001",
+        "staff_name": "みず",
+        "event_name": "テスト即売会",
+        "denominations": [
+            { "denomination": 10000, "count": 1 },
+            { "denomination": 1000, "count": 5 },
+            { "denomination": 100, "count": 3 }
+        ],
+        "cash_total": 15300,
+        "vouchers": [{ "type": "商品券", "amount": 1000, "memo": null }],
+        "voucher_total": 1000,
+        "grand_total": 16300,
+        "expected_total": 16500,
+        "difference": -200,
+        "transaction_count": 8,
+        "closed_at": "2025-12-30T07:00:00.000Z",
+        "payment_methods": [
+            { "method": "現金", "count": 6, "amount": 12000, "refund_count": 1, "refund_amount": 1000 },
+            { "method": "クレジットカード", "count": 2, "amount": 5000, "refund_count": 0, "refund_amount": 0 },
+            { "method": "商品券", "count": 1, "amount": 1000, "refund_count": 1, "refund_amount": 500 }
+        ],
+        "hourly_sales": [
+            { "date": "2025-12-30", "hour": 10, "count": 4, "amount": 9000 },
+            { "date": "2025-12-30", "hour": 11, "count": 0, "amount": 0 },
+            { "date": "2025-12-30", "hour": 12, "count": 3, "amount": 6000 },
+            { "date": "2025-12-30", "hour": 13, "count": 2, "amount": 3000 }
+        ],
+        "staff_totals": [
+            {
+                "staff_id": "S001", "staff_name": "みず", "sale_count": 5, "sale_amount": 11000,
+                "refund_count": 2, "refund_amount": 1500, "drawer_count": 1
+            },
+            {
+                "staff_id": "S002", "staff_name": null, "sale_count": 4, "sale_amount": 7000,
+                "refund_count": 0, "refund_amount": 0, "drawer_count": 0
+            }
+        ],
+        "voucher_sales": [
+            { "code": "voucher_department", "name": "百貨店商品券", "count": 1, "amount": 1000 }
+        ]
+    }))
+    .unwrap();
+    let totals = JournalTotals {
+        sale_count: 9,
+        sale_amount: 18000,
+        refund_count: 1,
+        refund_amount: 1000,
+        void_count: 1,
+        void_amount: 500,
+        rounding_adjustment: -15,
+        net_amount: 16500,
+    };
+    let text = render_with(PaperWidth::Mm58, |p| render_closing_report(p, &report, &totals, None, &TerminalConfig::default()));
+    assert_fixture("closing_report_refunds_58", &text);
+}
+
+#[test]
+fn estimate_length_grows_with_items() {
+    let config = TerminalConfig::default();
+    let payments = json!([{ "method": "現金", "amount": 100000 }]);
+    let short = receipt(vec![item("サークルみず", "缶バッジ", "4901234567894", 1, 300)], payments.clone(), json!({}));
+    let long = receipt(
+        (0..40).map(|_| item("サークルみず", "缶バッジ", "4901234567894", 1, 300)).collect(),
+        payments,
+        json!({}),
+    );
+
+    let short = estimate_length(&short, PaperWidth::Mm58, &config).unwrap();
+    let long = estimate_length(&long, PaperWidth::Mm58, &config).unwrap();
+    assert!(short.length_mm > 0 && short.lines > 0);
+    // 明細1行ごとに少なくとも1行分（約4mm）長くなる
+    assert!(long.lines >= short.lines + 39);
+    assert!(long.length_mm >= short.length_mm + 39 * 4);
+}
@@ -0,0 +1,396 @@
+//! 印刷プレビュー（シミュレーションモード）
+//!
+//! プリンターに送るはずのバイト列を記録し、人が読めるテキストに変換する
+//! 端末設定でシミュレーションモードを有効にすると、すべての印刷コマンドがUSBプリンターの代わりに
+//! このドライバーへ出力し、印刷が終わるたびに描画結果を print-preview イベントで通知する
+//! プリンターのない開発機でもフロントエンドの画面遷移を作れるようにするためのもの
+
+#[cfg(not(target_os = "ios"))]
+use super::serial::SerialDriver;
+use crate::jp_escpos::str_width;
+use crate::paper_usage::{PaperLength, PaperMeter};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use encoding_rs::SHIFT_JIS;
+#[cfg(not(target_os = "ios"))]
+use escpos::driver::NativeUsbDriver;
+use escpos::driver::{Driver, FileDriver, NetworkDriver};
+use escpos::errors::Result as DriverResult;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::OnceLock;
+use tauri::Emitter;
+
+/// 印刷プレビューのイベント名
+pub const EVENT_PRINT_PREVIEW: &str = "print-preview";
+
+/// 用紙状態の問い合わせに返す応答（用紙あり）
+const SIMULATED_PAPER_STATUS: u8 = 0x12;
+
+/// 印刷プレビューの通知内容
+#[derive(Debug, Clone, Serialize)]
+pub struct PrintPreviewEvent {
+    pub vendor_id: u16,
+    pub device_id: u16,
+    /// 描画結果（QR・バーコード・画像・カットは `[...]` で表す）
+    pub text: String,
+    /// 送信されたバイト列（Base64）
+    pub data: String,
+}
+
+/// 通知先のアプリ
+fn app_handle() -> &'static OnceLock<tauri::AppHandle> {
+    static APP: OnceLock<tauri::AppHandle> = OnceLock::new();
+    &APP
+}
+
+/// プレビューの通知先を登録（起動時に1回呼ぶ）
+pub fn install(app: tauri::AppHandle) {
+    let _ = app_handle().set(app);
+}
+
+/// 記録中の印刷内容（最後の参照が破棄されたときに通知する）
+struct Capture {
+    vendor_id: u16,
+    device_id: u16,
+    chars: usize,
+    bytes: RefCell<Vec<u8>>,
+}
+
+impl Drop for Capture {
+    fn drop(&mut self) {
+        let bytes = self.bytes.borrow();
+        if bytes.is_empty() {
+            return;
+        }
+        if let Some(app) = app_handle().get() {
+            let _ = app.emit(
+                EVENT_PRINT_PREVIEW,
+                PrintPreviewEvent {
+                    vendor_id: self.vendor_id,
+                    device_id: self.device_id,
+                    text: render_text(&bytes, self.chars),
+                    data: BASE64.encode(&*bytes),
+                },
+            );
+        }
+    }
+}
+
+/// 送信されたバイト列を記録するドライバー
+#[derive(Clone)]
+pub struct CaptureDriver {
+    capture: Rc<Capture>,
+}
+
+impl CaptureDriver {
+    pub fn new(vendor_id: u16, device_id: u16, chars: usize) -> Self {
+        Self {
+            capture: Rc::new(Capture {
+                vendor_id,
+                device_id,
+                chars,
+                bytes: RefCell::new(Vec::new()),
+            }),
+        }
+    }
+}
+
+impl Driver for CaptureDriver {
+    fn name(&self) -> String {
+        "simulation".to_string()
+    }
+
+    fn write(&self, data: &[u8]) -> DriverResult<()> {
+        self.capture.bytes.borrow_mut().extend_from_slice(data);
+        Ok(())
+    }
+
+    fn read(&self, buf: &mut [u8]) -> DriverResult<usize> {
+        // 状態の問い合わせには常に「用紙あり」と答える
+        match buf.first_mut() {
+            Some(b) => {
+                *b = SIMULATED_PAPER_STATUS;
+                Ok(1)
+            }
+            None => Ok(0),
+        }
+    }
+
+    fn flush(&self) -> DriverResult<()> {
+        Ok(())
+    }
+}
+
+/// 送るはずのバイト列から用紙の長さだけを数えるドライバー（印刷前の見積もり用）
+#[derive(Clone, Default)]
+pub struct MeterDriver {
+    meter: Rc<RefCell<PaperMeter>>,
+}
+
+impl MeterDriver {
+    pub fn length(&self) -> PaperLength {
+        self.meter.borrow().length()
+    }
+}
+
+impl Driver for MeterDriver {
+    fn name(&self) -> String {
+        "meter".to_string()
+    }
+
+    fn write(&self, data: &[u8]) -> DriverResult<()> {
+        self.meter.borrow_mut().feed(data);
+        Ok(())
+    }
+
+    fn read(&self, _buf: &mut [u8]) -> DriverResult<usize> {
+        Ok(0)
+    }
+
+    fn flush(&self) -> DriverResult<()> {
+        Ok(())
+    }
+}
+
+/// 印刷先のドライバー（USBプリンター・シリアルプリンター・ネットワークプリンター・ファイルまたはシミュレーション）
+#[derive(Clone)]
+pub enum PrinterDriver {
+    #[cfg(not(target_os = "ios"))]
+    Usb(NativeUsbDriver),
+    #[cfg(not(target_os = "ios"))]
+    Serial(SerialDriver),
+    Network(NetworkDriver),
+    File(FileDriver),
+    Simulated(CaptureDriver),
+}
+
+impl Driver for PrinterDriver {
+    fn name(&self) -> String {
+        match self {
+            #[cfg(not(target_os = "ios"))]
+            PrinterDriver::Usb(d) => d.name(),
+            #[cfg(not(target_os = "ios"))]
+            PrinterDriver::Serial(d) => d.name(),
+            PrinterDriver::Network(d) => d.name(),
+            PrinterDriver::File(d) => d.name(),
+            PrinterDriver::Simulated(d) => d.name(),
+        }
+    }
+
+    fn write(&self, data: &[u8]) -> DriverResult<()> {
+        match self {
+            #[cfg(not(target_os = "ios"))]
+            PrinterDriver::Usb(d) => d.write(data),
+            #[cfg(not(target_os = "ios"))]
+            PrinterDriver::Serial(d) => d.write(data),
+            PrinterDriver::Network(d) => d.write(data),
+            PrinterDriver::File(d) => d.write(data),
+            PrinterDriver::Simulated(d) => d.write(data),
+        }
+    }
+
+    fn read(&self, buf: &mut [u8]) -> DriverResult<usize> {
+        match self {
+            #[cfg(not(target_os = "ios"))]
+            PrinterDriver::Usb(d) => d.read(buf),
+            #[cfg(not(target_os = "ios"))]
+            PrinterDriver::Serial(d) => d.read(buf),
+            PrinterDriver::Network(d) => d.read(buf),
+            PrinterDriver::File(d) => d.read(buf),
+            PrinterDriver::Simulated(d) => d.read(buf),
+        }
+    }
+
+    fn flush(&self) -> DriverResult<()> {
+        match self {
+            #[cfg(not(target_os = "ios"))]
+            PrinterDriver::Usb(d) => d.flush(),
+            #[cfg(not(target_os = "ios"))]
+            PrinterDriver::Serial(d) => d.flush(),
+            PrinterDriver::Network(d) => d.flush(),
+            PrinterDriver::File(d) => d.flush(),
+            PrinterDriver::Simulated(d) => d.flush(),
+        }
+    }
+}
+
+/// 印刷中の行の状態
+#[derive(Default)]
+struct LineState {
+    text: Vec<u8>,
+    align: u8,
+    double_width: bool,
+}
+
+/// ESC/POS のバイト列を人が読めるテキストに変換
+///
+/// 文字は揃え位置を反映して1行の文字数に合わせて配置し、QR・バーコード・画像・カットは `[...]` で表す
+/// 途中で切れたコマンドはそこで変換を打ち切る
+pub fn render_text(bytes: &[u8], chars: usize) -> String {
+    let mut out = String::new();
+    let mut line = LineState::default();
+    let mut qr_data = String::new();
+    let mut i = 0;
+    let at = |n: usize| bytes.get(n).copied().unwrap_or(0);
+
+    let flush_line = |line: &mut LineState, out: &mut String| {
+        let (text, _, _) = SHIFT_JIS.decode(&line.text);
+        let scale = if line.double_width { 2 } else { 1 };
+
+        // 1行の文字数を超える分はプリンターと同じく折り返す
+        let mut rows = vec![String::new()];
+        let mut width = 0;
+        for c in text.chars() {
+            let w = str_width(c.encode_utf8(&mut [0; 4])) * scale;
+            if width + w > chars {
+                rows.push(String::new());
+                width = 0;
+            }
+            width += w;
+            if let Some(row) = rows.last_mut() {
+                row.push(c);
+            }
+        }
+
+        for row in rows {
+            let pad = chars.saturating_sub(str_width(&row) * scale);
+            let left = match line.align {
+                1 => pad / 2,
+                2 => pad,
+                _ => 0,
+            };
+            let marker = if line.double_width { "(2x) " } else { "" };
+            out.push_str(format!("{}{}{}", " ".repeat(left), marker, row).trim_end_matches(' '));
+            out.push('\n');
+        }
+        line.text.clear();
+        line.double_width = false;
+    };
+
+    while i < bytes.len() {
+        match bytes[i] {
+            0x0a => {
+                flush_line(&mut line, &mut out);
+                i += 1;
+            }
+            0x1b => {
+                match at(i + 1) {
+                    b'@' => i += 2,
+                    b'a' => {
+                        // 行の途中で揃えが戻されても、その行は最初の揃えで印刷される
+                        if line.text.is_empty() {
+                            line.align = at(i + 2);
+                        }
+                        i += 3;
+                    }
+                    b'!' => {
+                        if at(i + 2) & 0x20 != 0 {
+                            line.double_width = true;
+                        }
+                        i += 3;
+                    }
+                    _ => i += 3,
+                }
+            }
+            0x1c => {
+                match at(i + 1) {
+                    b'&' | b'.' => i += 2,
+                    b'!' => {
+                        if at(i + 2) & 0x04 != 0 {
+                            line.double_width = true;
+                        }
+                        i += 3;
+                    }
+                    b'(' => {
+                        // FS ( fn pL pH ...（ラベル紙の位置合わせなど）
+                        let len = at(i + 3) as usize | (at(i + 4) as usize) << 8;
+                        i += 5 + len;
+                    }
+                    _ => i += 3,
+                }
+            }
+            0x1d => {
+                match at(i + 1) {
+                    b'V' => {
+                        let label = if at(i + 2) == 0x00 { "[CUT]" } else { "[PARTIAL CUT]" };
+                        out.push_str(label);
+                        out.push('\n');
+                        i += 3;
+                    }
+                    b'L' | b'W' => i += 4,
+                    b'(' => {
+                        // GS ( k pL pH cn fn ...
+                        let len = at(i + 3) as usize | (at(i + 4) as usize) << 8;
+                        let Some(params) = bytes.get(i + 7..i + 5 + len) else {
+                            break;
+                        };
+                        match at(i + 6) {
+                            0x50 => {
+                                qr_data = String::from_utf8_lossy(params.get(1..).unwrap_or_default()).to_string()
+                            }
+                            0x51 => {
+                                out.push_str(&format!("[QR] {}\n", qr_data));
+                            }
+                            _ => {}
+                        }
+                        i += 5 + len;
+                    }
+                    b'k' => {
+                        // GS k m n d1...dn（CODE128 は {B を除いて出力）
+                        let n = at(i + 3) as usize;
+                        let Some(data) = bytes.get(i + 4..i + 4 + n) else {
+                            break;
+                        };
+                        let symbology = match at(i + 2) {
+                            67 => "EAN13",
+                            68 => "EAN8",
+                            _ => "CODE128",
+                        };
+                        let data = data.strip_prefix(b"{B").unwrap_or(data);
+                        out.push_str(&format!("[{}] {}\n", symbology, String::from_utf8_lossy(data)));
+                        i += 4 + n;
+                    }
+                    b'v' => {
+                        // GS v 0 m xL xH yL yH d1...dk
+                        let x = at(i + 4) as usize | (at(i + 5) as usize) << 8;
+                        let y = at(i + 6) as usize | (at(i + 7) as usize) << 8;
+                        out.push_str(&format!("[IMAGE {}x{}]\n", x * 8, y));
+                        i += 8 + x * y;
+                    }
+                    _ => i += 3,
+                }
+            }
+            b => {
+                line.text.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    if !line.text.is_empty() {
+        flush_line(&mut line, &mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_text_stops_at_truncated_command() {
+        // GS ( k のパラメータ長がデータより長い
+        let bytes = [b'A', b'B', 0x0a, 0x1d, b'(', b'k', 0xff, 0x00, 0x31, 0x50];
+        assert_eq!(render_text(&bytes, 32), "AB\n");
+    }
+
+    #[test]
+    fn test_simulated_paper_status_has_paper() {
+        let driver = CaptureDriver::new(0, 0, 32);
+        let mut buf = [0u8; 1];
+        assert_eq!(driver.read(&mut buf).unwrap(), 1);
+        let status = crate::printer_status::PaperStatus::from_status_byte(buf[0]);
+        assert!(!status.near_end && !status.out);
+    }
+}
@@ -0,0 +1,107 @@
+//! 文字幅・Shift_JIS変換・折り返しのプロパティテスト
+//!
+//! 任意のUnicode文字列（サークル名・商品名など）を流しても
+//! パニックや範囲外の切り出しが起きず、表示幅の不変条件が保たれることを確認する
+
+use super::layout_tests::render_with;
+use super::format_book_number;
+use crate::datetime;
+use crate::number_format::NumberFormat;
+use crate::jp_escpos::{char_width, str_width, PaperWidth, TextStyle};
+use proptest::prelude::*;
+
+/// Shift_JISで表現できる文字だけの文字列（印刷結果の幅を比較するため）
+fn sjis_text() -> impl Strategy<Value = String> {
+    "[ぁ-んァ-ヶa-zA-Z0-9 ・ー（）東京都渋谷区同人誌漫画技術書缶限定新刊既刊]{0,40}"
+}
+
+fn any_paper_width() -> impl Strategy<Value = PaperWidth> {
+    prop_oneof![Just(PaperWidth::Mm58), Just(PaperWidth::Mm80)]
+}
+
+proptest! {
+    #[test]
+    fn str_width_is_between_one_and_two_per_char(s in any::<String>()) {
+        let count = s.chars().count();
+        let width = str_width(&s);
+        prop_assert!(width >= count);
+        prop_assert!(width <= count * 2);
+    }
+
+    #[test]
+    fn str_width_is_additive(a in any::<String>(), b in any::<String>()) {
+        prop_assert_eq!(str_width(&format!("{}{}", a, b)), str_width(&a) + str_width(&b));
+        prop_assert_eq!(str_width(&a), a.chars().map(char_width).sum::<usize>());
+    }
+
+    #[test]
+    fn jp_text_never_fails_on_arbitrary_text(s in any::<String>(), width in any_paper_width()) {
+        render_with(width, |p| {
+            p.jp_textln(&s, TextStyle::default())?;
+            p.jp_textln(&s, TextStyle::default().double().reverse().center())?;
+            p.jp_textln_padded(&s, TextStyle::default().double().center())?;
+            p.row_auto(&s, &s)?;
+            p.row_auto_bold(&s, &s)?;
+            p.jp_textln_ruby(&s, &s, TextStyle::default().right())
+        });
+    }
+
+    #[test]
+    fn fixed_columns_end_at_right_edge(
+        left in sjis_text(),
+        quantity in 0u32..10_000,
+        price in any::<u32>(),
+        width in any_paper_width(),
+    ) {
+        let qty = format!("{} 点", quantity);
+        let subtotal = format!("￥{}", price);
+        let text = render_with(width, |p| {
+            p.fixed_columns(&left, &[(&qty, 7), (&subtotal, 11)], TextStyle::default())
+        });
+
+        let last = text.lines().last().unwrap_or_default();
+        prop_assert!(last.ends_with(&subtotal));
+        prop_assert_eq!(str_width(last), width.chars());
+    }
+
+    #[test]
+    fn separators_fill_exactly_one_line(c in any::<char>(), width in any_paper_width()) {
+        prop_assume!(c != '\n' && !c.is_control());
+        let count = width.chars() / char_width(c);
+        prop_assert!(count * char_width(c) <= width.chars());
+        render_with(width, |p| p.separator_with(c, TextStyle::default()));
+    }
+
+    #[test]
+    fn format_book_number_never_panics(isdn in any::<Option<String>>(), jan2 in any::<Option<String>>()) {
+        if let Some((label, _)) = format_book_number(&isdn, &jan2, &NumberFormat::default()) {
+            prop_assert!(label.starts_with(isdn.as_deref().unwrap_or_default()));
+        }
+    }
+
+    #[test]
+    fn format_book_number_reads_c_code_and_price(c_code in "[0-9]{4}", price in 0u32..100_000) {
+        let jan2 = format!("192{}{:05}", c_code, price);
+        let formatted = format_book_number(&Some("ISDN278-4".to_string()), &Some(jan2), &NumberFormat::default());
+        let expected_c_code = format!("C{}", c_code);
+        let expected_price = NumberFormat::default().price(price);
+        prop_assert!(formatted.is_some_and(|(label, price)| label.ends_with(&expected_c_code) && price == expected_price));
+    }
+
+    #[test]
+    fn book_number_price_is_never_split(isdn in "ISDN[0-9-]{0,30}", price in 0u32..100_000, width in any_paper_width()) {
+        let jan2 = format!("1920094{:05}", price);
+        let (label, price) = format_book_number(&Some(isdn), &Some(jan2), &NumberFormat::default()).unwrap();
+        let text = render_with(width, |p| {
+            p.fixed_columns(&label, &[(&price, str_width(&price) + 1)], TextStyle::default().bold())
+        });
+        let last = text.lines().last().unwrap_or_default();
+        prop_assert!(last.ends_with(&price));
+        prop_assert!(text.lines().all(|line| str_width(line) <= width.chars()));
+    }
+
+    #[test]
+    fn datetime_display_never_panics(s in any::<String>(), tz in "[A-Za-z_/]{0,24}") {
+        datetime::display(&s, &tz);
+    }
+}
@@ -0,0 +1,98 @@
+//! シリアルポートのドライバー
+//!
+//! RS-232・USB-シリアル変換で繋いだレシートプリンターへ ESC/POS を送る
+//! 通信速度とフロー制御はプリンター本体の設定に合わせて端末設定で指定する
+
+use crate::serial_printer::{self, BluetoothSerialPort, SerialFlowControl, SerialPortInfo, SerialPrinter};
+use escpos::driver::Driver;
+use escpos::errors::{PrinterError, Result as DriverResult};
+use serialport::{FlowControl, SerialPort, SerialPortType};
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 読み書きのタイムアウト（フロー制御で待たされる間も含む）
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// シリアルポートのドライバー
+#[derive(Clone)]
+pub struct SerialDriver {
+    path: String,
+    port: Arc<Mutex<Box<dyn SerialPort>>>,
+}
+
+impl SerialDriver {
+    pub fn open(printer: &SerialPrinter) -> DriverResult<Self> {
+        let flow_control = match printer.flow_control {
+            SerialFlowControl::None => FlowControl::None,
+            SerialFlowControl::Hardware => FlowControl::Hardware,
+            SerialFlowControl::Software => FlowControl::Software,
+        };
+        let port = serialport::new(&printer.port, printer.baud_rate)
+            .flow_control(flow_control)
+            .timeout(IO_TIMEOUT)
+            .open()
+            .map_err(|e| PrinterError::Io(format!("{}: {}", printer.port, e)))?;
+        Ok(Self {
+            path: printer.port.clone(),
+            port: Arc::new(Mutex::new(port)),
+        })
+    }
+
+    fn lock(&self) -> DriverResult<std::sync::MutexGuard<'_, Box<dyn SerialPort>>> {
+        self.port
+            .lock()
+            .map_err(|_| PrinterError::Io("Serial port lock poisoned".to_string()))
+    }
+}
+
+impl Driver for SerialDriver {
+    fn name(&self) -> String {
+        format!("Serial ({})", self.path)
+    }
+
+    fn write(&self, data: &[u8]) -> DriverResult<()> {
+        self.lock()?
+            .write_all(data)
+            .map_err(|e| PrinterError::Io(e.to_string()))
+    }
+
+    fn read(&self, buf: &mut [u8]) -> DriverResult<usize> {
+        self.lock()?
+            .read(buf)
+            .map_err(|e| PrinterError::Io(e.to_string()))
+    }
+
+    fn flush(&self) -> DriverResult<()> {
+        self.lock()?
+            .flush()
+            .map_err(|e| PrinterError::Io(e.to_string()))
+    }
+}
+
+/// 端末のシリアルポートの一覧
+pub fn available_ports() -> Result<Vec<SerialPortInfo>, String> {
+    let ports = serialport::available_ports().map_err(|e| e.to_string())?;
+    Ok(ports
+        .into_iter()
+        .map(|p| SerialPortInfo {
+            description: match p.port_type {
+                SerialPortType::UsbPort(usb) => usb.product.or(usb.manufacturer),
+                _ => None,
+            },
+            port: p.port_name,
+        })
+        .collect())
+}
+
+/// ペアリング済みの Bluetooth（SPP）プリンターのポートの一覧
+pub fn bluetooth_ports() -> Result<Vec<BluetoothSerialPort>, String> {
+    let ports = serialport::available_ports().map_err(|e| e.to_string())?;
+    Ok(ports
+        .into_iter()
+        .filter_map(|p| {
+            let reported = matches!(p.port_type, SerialPortType::BluetoothPort);
+            serial_printer::bluetooth_port(&p.port_name, reported)
+        })
+        .collect())
+}
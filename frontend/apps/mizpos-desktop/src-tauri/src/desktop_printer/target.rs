@@ -0,0 +1,206 @@
+//! 印刷先の指定
+//!
+//! 印刷コマンドはベンダーID・デバイスIDで指定するプリンター（USBプリンター、設定済みのネットワーク・シリアルプリンター）のほか、
+//! 設定していないネットワークプリンター・シリアルプリンター、Bluetooth（SPP）プリンター、ファイル（プリンターのデバイスファイル・スプールディレクトリ）にも同じレイアウトで印刷できる
+//! 接続方法（トランスポート）ごとの違いはこのモジュールで PrinterDriver に吸収し、レイアウトのコードは印刷先を意識しない
+
+#[cfg(not(target_os = "ios"))]
+use super::serial::{self, SerialDriver};
+use super::preview::{CaptureDriver, PrinterDriver};
+use crate::config;
+use crate::jp_escpos::PaperWidth;
+use crate::network_printer;
+use crate::serial_printer::{self, BluetoothSerialPort, SerialFlowControl, SerialPrinter};
+#[cfg(not(target_os = "ios"))]
+use escpos::driver::NativeUsbDriver;
+use escpos::driver::{FileDriver, NetworkDriver};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+
+/// 印刷先に指定できるデバイスファイル（後ろに番号が付くもの）
+const DEVICE_FILE_PREFIXES: &[&str] = &["/dev/usb/lp", "/dev/lp"];
+/// ファイル名だけを指定した印刷データの保存先（データディレクトリ配下）
+const SPOOL_DIR: &str = "print_spool";
+
+/// 印刷先
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "transport", rename_all = "snake_case")]
+pub enum PrinterTarget {
+    /// ベンダーID・デバイスIDで指定するプリンター（USB、設定済みのネットワーク・シリアルプリンターは仮想のベンダーID）
+    Device { vendor_id: u16, device_id: u16 },
+    /// 設定していないネットワークプリンター（RAW）
+    Network { host: String, port: u16 },
+    /// 設定していないシリアルプリンター
+    Serial {
+        port: String,
+        baud_rate: u32,
+        flow_control: SerialFlowControl,
+    },
+    /// ペアリング済みの Bluetooth（SPP）プリンター（list_bluetooth_printers のポート）
+    Bluetooth { port: String },
+    /// ファイル（Linux のプリンターのデバイスファイル、またはファイル名だけを指定して印刷データをスプールディレクトリに保存）
+    ///
+    /// 画面から任意のファイルに書き込めないよう、これ以外のパスは受け付けない
+    File { path: String },
+}
+
+impl PrinterTarget {
+    pub fn device(vendor_id: u16, device_id: u16) -> Self {
+        PrinterTarget::Device { vendor_id, device_id }
+    }
+
+    /// プロファイル・QRコマンドの判定に使うベンダーID・デバイスID（ID で指定した印刷先のみ）
+    pub fn ids(&self) -> Option<(u16, u16)> {
+        match *self {
+            PrinterTarget::Device { vendor_id, device_id } => Some((vendor_id, device_id)),
+            _ => None,
+        }
+    }
+
+    /// 用紙を使う印刷先か（ファイルへの書き出しは用紙使用量に数えない）
+    pub fn uses_paper(&self) -> bool {
+        !matches!(self, PrinterTarget::File { .. })
+    }
+}
+
+/// 印刷先のドライバーを開く（シミュレーションモードでは実際の印刷先を使わない）
+pub fn open_driver(target: &PrinterTarget, width: PaperWidth) -> Result<PrinterDriver, String> {
+    let config = config::load()?;
+    if config.simulation_mode {
+        let (vendor_id, device_id) = target.ids().unwrap_or_default();
+        return Ok(PrinterDriver::Simulated(CaptureDriver::new(vendor_id, device_id, width.chars())));
+    }
+    match target {
+        PrinterTarget::Device { vendor_id, device_id } => {
+            let (vendor_id, device_id) = (*vendor_id, *device_id);
+            if vendor_id == network_printer::NETWORK_VENDOR_ID {
+                let printer = network_printer::find(&config.network_printers, vendor_id, device_id)
+                    .ok_or_else(|| format!("Network printer not configured: {}", device_id))?;
+                return open_network_driver(&printer.host, printer.port);
+            }
+            if vendor_id == serial_printer::SERIAL_VENDOR_ID {
+                let printer = serial_printer::find(&config.serial_printers, vendor_id, device_id)
+                    .ok_or_else(|| format!("Serial printer not configured: {}", device_id))?;
+                return open_serial_driver(printer);
+            }
+            open_usb_driver(vendor_id, device_id)
+        }
+        PrinterTarget::Network { host, port } => open_network_driver(host, *port),
+        PrinterTarget::Serial {
+            port,
+            baud_rate,
+            flow_control,
+        } => open_serial_driver(&SerialPrinter {
+            name: port.clone(),
+            port: port.clone(),
+            baud_rate: *baud_rate,
+            flow_control: *flow_control,
+        }),
+        PrinterTarget::Bluetooth { port } => {
+            let port = bluetooth_ports()?
+                .into_iter()
+                .find(|p| &p.port == port)
+                .ok_or_else(|| format!("Bluetooth printer not paired: {}", port))?;
+            open_serial_driver(&serial_printer::bluetooth_printer(&port))
+        }
+        PrinterTarget::File { path } => {
+            let path = file_target_path(path)?;
+            FileDriver::open(&path).map(PrinterDriver::File).map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// ファイルの印刷先を書き込み先のパスにする（デバイスファイルかスプールディレクトリのファイルのみ）
+fn file_target_path(path: &str) -> Result<PathBuf, String> {
+    let is_device_file = DEVICE_FILE_PREFIXES.iter().any(|prefix| {
+        path.strip_prefix(prefix)
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+    });
+    if is_device_file {
+        return Ok(PathBuf::from(path));
+    }
+
+    let is_file_name = !matches!(path, "" | "." | "..") && !path.contains(['/', '\\']);
+    if !is_file_name {
+        return Err(format!("File printer target not allowed: {}", path));
+    }
+    let dir = crate::storage::data_dir()
+        .ok_or_else(|| "Cannot determine data directory".to_string())?
+        .join(SPOOL_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+    Ok(dir.join(path))
+}
+
+fn open_network_driver(host: &str, port: u16) -> Result<PrinterDriver, String> {
+    NetworkDriver::open(host, port, Some(network_printer::CONNECT_TIMEOUT))
+        .map(PrinterDriver::Network)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_os = "ios"))]
+fn open_usb_driver(vendor_id: u16, device_id: u16) -> Result<PrinterDriver, String> {
+    NativeUsbDriver::open(vendor_id, device_id)
+        .map(PrinterDriver::Usb)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "ios")]
+fn open_usb_driver(_vendor_id: u16, _device_id: u16) -> Result<PrinterDriver, String> {
+    Err("USB printers are not supported on iOS".to_string())
+}
+
+#[cfg(not(target_os = "ios"))]
+fn open_serial_driver(printer: &SerialPrinter) -> Result<PrinterDriver, String> {
+    SerialDriver::open(printer)
+        .map(PrinterDriver::Serial)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "ios")]
+fn open_serial_driver(_printer: &SerialPrinter) -> Result<PrinterDriver, String> {
+    Err("Serial printers are not supported on iOS".to_string())
+}
+
+/// ペアリング済みの Bluetooth（SPP）プリンターのポート
+#[cfg(not(target_os = "ios"))]
+pub fn bluetooth_ports() -> Result<Vec<BluetoothSerialPort>, String> {
+    serial::bluetooth_ports()
+}
+
+#[cfg(target_os = "ios")]
+pub fn bluetooth_ports() -> Result<Vec<BluetoothSerialPort>, String> {
+    Ok(vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_target_is_tagged_by_transport() {
+        let target: PrinterTarget =
+            serde_json::from_value(json!({ "transport": "network", "host": "192.168.0.50", "port": 9100 }))
+                .unwrap();
+        assert_eq!(target.ids(), None);
+        assert_eq!(
+            serde_json::to_value(PrinterTarget::device(0x04b8, 0x0202)).unwrap(),
+            json!({ "transport": "device", "vendor_id": 0x04b8, "device_id": 0x0202 })
+        );
+        assert!(!PrinterTarget::File { path: "/dev/usb/lp0".to_string() }.uses_paper());
+    }
+
+    #[test]
+    fn test_file_target_is_limited_to_device_files_and_spool_dir() {
+        let _dir = crate::storage::test_support::TempDataDir::new();
+        assert_eq!(file_target_path("/dev/usb/lp0").unwrap(), PathBuf::from("/dev/usb/lp0"));
+        assert_eq!(
+            file_target_path("receipt.bin").unwrap(),
+            crate::storage::data_dir().unwrap().join(SPOOL_DIR).join("receipt.bin")
+        );
+        for path in ["/etc/passwd", "/dev/usb/lp0/../../etc/passwd", "/dev/sda", "../receipt.bin", "a/b.bin", "a\\b.bin", "..", ""] {
+            assert!(file_target_path(path).is_err(), "{}", path);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! 印刷コマンドのテスト
+//!
+//! シミュレーションモードの印刷先（CaptureDriver）で実行し、ジャーナルへの記録を確認する
+
+use super::target::PrinterTarget;
+use super::{print_receipt_to, ReprintRequest};
+use crate::config::{self, TerminalConfig};
+use crate::journal::{self, JournalRecord, SaleChannel};
+use crate::receipt::{test_support, ReceiptData};
+use crate::storage::test_support::TempDataDir;
+use serde_json::json;
+
+fn receipt(receipt_number: &str, paid: u32) -> ReceiptData {
+    test_support::receipt(receipt_number)
+        .with(json!({ "payments": [{ "method": "現金", "amount": paid }] }))
+        .build()
+}
+
+#[test]
+fn test_reprint_prints_from_journal_without_recording_the_sale_again() {
+    let _dir = TempDataDir::new();
+    config::save(&TerminalConfig {
+        simulation_mode: true,
+        ..TerminalConfig::default()
+    })
+    .unwrap();
+    journal::record_sale_via(&receipt("R001", 1000), SaleChannel::Counter).unwrap();
+    let target = PrinterTarget::device(0x04b8, 0x0202);
+
+    // 支払額が足りない内容は売上の確認（prepare_sale）で受け付けない
+    assert!(print_receipt_to(target.clone(), receipt("R001", 500), None, None, None, None).is_err());
+
+    // 再発行は渡された内容を確認・記録せず、操作したスタッフで再発行を記録する
+    let request = ReprintRequest {
+        staff_id: "S002".to_string(),
+        approval: None,
+    };
+    print_receipt_to(target, receipt("R001", 500), None, None, None, Some(request)).unwrap();
+
+    let entries = journal::entries().unwrap();
+    let sales: Vec<_> = entries
+        .iter()
+        .filter(|e| matches!(e.record, JournalRecord::Sale { .. }))
+        .collect();
+    assert_eq!(sales.len(), 1);
+    let reprint = entries.last().unwrap();
+    assert_eq!(reprint.staff_id.as_deref(), Some("S002"));
+    assert!(matches!(
+        &reprint.record,
+        JournalRecord::Reprint { receipt_number, copy_number: 1, supervisor_id: None } if receipt_number == "R001"
+    ));
+    assert_eq!(journal::find_sale("R001").unwrap().unwrap().payments[0].amount, 1000);
+}
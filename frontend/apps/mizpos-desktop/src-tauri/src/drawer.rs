@@ -0,0 +1,129 @@
+//! ドロワー（釣銭）モジュール
+//!
+//! 釣銭準備金の金種別在高を保持し、釣り銭の金種内訳を提案する
+
+use crate::event_context;
+use crate::storage;
+pub use mizpos_core::receipt::DenominationCount;
+
+/// ドロワー在高の保存ファイル名
+const DRAWER_FILE: &str = "drawer.json";
+/// 計算を打ち切る釣り銭額の上限
+const MAX_CHANGE_AMOUNT: u32 = 100_000;
+
+/// 現在のドロワー在高を取得
+pub fn contents() -> Result<Vec<DenominationCount>, String> {
+    Ok(storage::load_json(&event_context::scoped(DRAWER_FILE)?)?.unwrap_or_default())
+}
+
+/// ドロワー在高（釣銭準備金）を設定
+pub fn set_contents(denominations: Vec<DenominationCount>) -> Result<(), String> {
+    storage::save_json(&event_context::scoped(DRAWER_FILE)?, &denominations)
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// 在高の範囲で枚数が最小となる釣り銭の内訳を計算
+///
+/// 在高に制限があると大きい金種から貪欲に選ぶ方法では解けない場合があるため、
+/// 個数制限付きナップサックとして解く
+pub fn suggest_change(
+    amount: u32,
+    available: &[DenominationCount],
+) -> Result<Vec<DenominationCount>, String> {
+    if amount == 0 {
+        return Ok(vec![]);
+    }
+    if amount > MAX_CHANGE_AMOUNT {
+        return Err(format!("Change amount too large: {}", amount));
+    }
+
+    let mut denoms: Vec<&DenominationCount> = available
+        .iter()
+        .filter(|d| d.denomination > 0 && d.count > 0)
+        .collect();
+    denoms.sort_by_key(|d| std::cmp::Reverse(d.denomination));
+
+    // 最大公約数で割って計算量を減らす
+    let unit = denoms.iter().fold(0, |g, d| gcd(g, d.denomination));
+    if unit == 0 || !amount.is_multiple_of(unit) {
+        return Err("Cannot make change with available denominations".to_string());
+    }
+    let target = (amount / unit) as usize;
+
+    // best[v] = 金額vを作る最小枚数、used[v] = その時の各金種の使用枚数
+    let mut best: Vec<Option<u32>> = vec![None; target + 1];
+    let mut used: Vec<Vec<u32>> = vec![vec![0; denoms.len()]; target + 1];
+    best[0] = Some(0);
+
+    for (i, d) in denoms.iter().enumerate() {
+        let value = (d.denomination / unit) as usize;
+        // 2冪分割して0-1ナップサックに帰着
+        let mut remaining = d.count;
+        let mut chunk = 1;
+        while remaining > 0 {
+            let take = chunk.min(remaining);
+            remaining -= take;
+            chunk *= 2;
+
+            let step = value * take as usize;
+            if step > target {
+                continue;
+            }
+            for v in (step..=target).rev() {
+                if let Some(prev) = best[v - step] {
+                    let candidate = prev + take;
+                    if best[v].is_none_or(|cur| candidate < cur) {
+                        best[v] = Some(candidate);
+                        let mut counts = used[v - step].clone();
+                        counts[i] += take;
+                        used[v] = counts;
+                    }
+                }
+            }
+        }
+    }
+
+    if best[target].is_none() {
+        return Err("Cannot make change with available denominations".to_string());
+    }
+
+    Ok(denoms
+        .iter()
+        .zip(&used[target])
+        .filter(|(_, &count)| count > 0)
+        .map(|(d, &count)| DenominationCount {
+            denomination: d.denomination,
+            count,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dc(denomination: u32, count: u32) -> DenominationCount {
+        DenominationCount { denomination, count }
+    }
+
+    #[test]
+    fn test_suggest_change_greedy_fails() {
+        // 5000円から選ぶと1000円が足りないケース
+        let available = vec![dc(5000, 1), dc(2000, 3), dc(1000, 0)];
+        let change = suggest_change(6000, &available).unwrap();
+        assert_eq!(change, vec![dc(2000, 3)]);
+    }
+
+    #[test]
+    fn test_suggest_change_insufficient() {
+        let available = vec![dc(1000, 1), dc(100, 2)];
+        assert!(suggest_change(1500, &available).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+//! 脅迫PINモジュール
+//!
+//! 責任者が脅迫PINで承認した場合、画面上は通常どおり承認しつつ、署名した通報をアップロードキューに積む
+//! 以降の取引はジャーナルで脅迫下の取引として記録し、管理サーバーからの解除コマンドで通常に戻す
+//! 画面やイベントには一切通知しない
+
+use crate::event_context;
+use crate::session;
+use crate::storage;
+use crate::upload_queue;
+use serde::{Deserialize, Serialize};
+
+/// 状態の保存ファイル名
+const DURESS_FILE: &str = "duress.json";
+
+/// 脅迫PINの状態
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DuressState {
+    pub active: bool,
+    /// 脅迫PINを入力した責任者
+    pub supervisor_id: Option<String>,
+    /// 最初に入力した日時（UNIX秒）
+    pub triggered_at: Option<u64>,
+}
+
+/// サーバーへの通報（署名対象）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuressAlert {
+    pub supervisor_id: String,
+    pub triggered_at: u64,
+    pub event_id: String,
+    /// 開局中のスタッフ番号
+    pub session_staff_id: Option<String>,
+}
+
+/// 現在の状態を取得
+pub fn state() -> Result<DuressState, String> {
+    Ok(storage::load_json(DURESS_FILE)?.unwrap_or_default())
+}
+
+/// 脅迫下か（ジャーナルの記録時に確認する）
+pub fn is_active() -> Result<bool, String> {
+    Ok(state()?.active)
+}
+
+/// 脅迫PINの入力を記録し、通報をアップロードキューに積む
+///
+/// 入力のたびに通報するが、最初に入力した日時は保持する
+pub fn trigger(supervisor_id: &str) -> Result<(), String> {
+    let now = storage::now_unix();
+    let mut state = state()?;
+    if !state.active {
+        state = DuressState {
+            active: true,
+            supervisor_id: Some(supervisor_id.to_string()),
+            triggered_at: Some(now),
+        };
+        storage::save_json(DURESS_FILE, &state)?;
+    }
+
+    let alert = DuressAlert {
+        supervisor_id: supervisor_id.to_string(),
+        triggered_at: now,
+        event_id: event_context::active_event_id()?,
+        session_staff_id: session::current()?.staff_id,
+    };
+    upload_queue::enqueue(
+        upload_queue::KIND_DURESS_ALERT,
+        &format!("{}:{}", supervisor_id, now),
+        &alert,
+    )?;
+    Ok(())
+}
+
+/// 脅迫下の記録を解除（管理サーバーのコマンドで呼ぶ）
+pub fn clear() -> Result<(), String> {
+    storage::save_json(DURESS_FILE, &DuressState::default())
+}
@@ -0,0 +1,228 @@
+//! メールレシートモジュール
+//!
+//! ジャーナルに記録済みのレシートをプレーンテキストに整形し、端末鍵で署名した送信依頼を
+//! アップロードキューに積む（送信はフロントエンドが設定のメールリレーへ行う）
+//! 送信依頼はジャーナルに記録するが、宛先はマスクして残す
+
+use crate::config;
+use crate::datetime;
+use crate::journal::{self, JournalRecord};
+use crate::print_strings;
+use crate::receipt::{self, ReceiptData};
+use crate::tenders;
+use crate::upload_queue::{self, PendingUpload};
+use serde::{Deserialize, Serialize};
+
+/// メールの送信依頼（署名対象）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailReceiptRequest {
+    /// 送信先のメールリレー
+    pub relay_url: String,
+    pub receipt_number: String,
+    /// 宛先メールアドレス
+    pub to: String,
+    pub subject: String,
+    /// 本文（プレーンテキスト）
+    pub body: String,
+}
+
+/// メールアドレスを簡易的に検証して正規化（前後の空白を除く）
+pub fn validate_address(address: &str) -> Result<String, String> {
+    let address = address.trim();
+    let valid = match address.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !address.chars().any(|c| c.is_whitespace() || c.is_control())
+        }
+        None => false,
+    };
+    if !valid {
+        return Err(format!("Invalid email address: {}", address));
+    }
+    Ok(address.to_string())
+}
+
+/// ジャーナル用に宛先をマスク（例: taro@example.com → ta***@example.com）
+pub fn mask_address(address: &str) -> String {
+    match address.split_once('@') {
+        Some((local, domain)) => {
+            let visible: String = local.chars().take(2).collect();
+            format!("{}***@{}", visible, domain)
+        }
+        None => "***".to_string(),
+    }
+}
+
+/// 件名
+fn subject(receipt: &ReceiptData) -> String {
+    let title = &print_strings::current().receipt.title;
+    format!("{} {} #{}", title, receipt.event_name, receipt.receipt_number)
+        .trim()
+        .to_string()
+}
+
+/// レシートをプレーンテキストに整形（印刷レイアウトと同じ項目を並べる）
+pub fn render_text(receipt: &ReceiptData, config: &config::TerminalConfig) -> String {
+    let fmt = &config.number_format;
+    let strings = print_strings::current();
+    let text = &strings.receipt;
+    let header = &config.receipt_header;
+    let non_empty = |v: &Option<String>| v.clone().filter(|s| !s.is_empty());
+    let mut lines: Vec<String> = Vec::new();
+
+    if let Some(shop_name) = non_empty(&header.shop_name).or(non_empty(&receipt.circle_name)) {
+        lines.push(shop_name);
+    }
+    if !receipt.event_name.is_empty() {
+        lines.push(receipt.event_name.clone());
+    }
+    if let Some(address) = non_empty(&header.address).or(non_empty(&receipt.venue_address)) {
+        lines.push(address);
+    }
+    if let Some(booth) = non_empty(&header.booth_number) {
+        lines.push(format!("スペース: {}", booth));
+    }
+    if let Some(registration) = non_empty(&header.registration_number) {
+        lines.push(format!("登録番号: {}", registration));
+    }
+
+    lines.push(String::new());
+    lines.push(text.title.clone());
+    lines.push(format!("# {}", receipt.receipt_number));
+    let staff = print_strings::fill(&text.staff, &[("staff", &receipt.staff_id)]);
+    match receipt.sale_start_date_time {
+        Some(ref sale_date_time) => lines.push(format!(
+            "{} {}",
+            datetime::display(sale_date_time, &config.timezone),
+            staff
+        )),
+        None => lines.push(staff),
+    }
+    lines.push("-".repeat(32));
+
+    for group in receipt::arrange_items(&receipt.items, config.item_order) {
+        if let Some(label) = group.label {
+            lines.push(format!("■ {}", label));
+        }
+        for item in group.items {
+            lines.push(format!("{} / {}", item.circle_name, item.name));
+            lines.push(format!("  {}点  {}", item.quantity, fmt.price(item.price)));
+            if let Some(ref note) = item.note {
+                lines.push(format!("  ※{}", note));
+            }
+        }
+    }
+
+    lines.push("=".repeat(32));
+    lines.push(format!("{}  {}", text.total, fmt.price(receipt.total)));
+    if receipt.tax_rate > 0 && receipt.tax_amount > 0 {
+        let tax = print_strings::fill(&text.tax, &[("rate", &receipt.tax_rate)]);
+        lines.push(format!("{}  {}", tax, fmt.price(receipt.tax_amount)));
+    }
+    for payment in &receipt.payments {
+        lines.push(format!("  {}  {}", payment.method, fmt.price(payment.amount)));
+    }
+    let change = tenders::validate_receipt(&config.voucher_catalog, receipt)
+        .map(|b| b.change)
+        .unwrap_or_default();
+    if change > 0 {
+        lines.push(format!("  {}  {}", text.change, fmt.price(change)));
+    }
+    if let Some(ref card) = receipt.card_details {
+        if let Some(ref last4) = card.last4 {
+            lines.push(format!("  カード番号  ****{}", last4));
+        }
+    }
+    lines.push("-".repeat(32));
+    lines.push("当店は免税事業者であり、適格請求書を発行することはできません。返品・返金は落丁・乱丁の場合のみ受け付けます。返品・返金の場合は本メールを添えてサポートセンター support-pos@miz.cabにご連絡ください。".to_string());
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+/// レシートのメール送信を依頼する
+///
+/// 署名した送信依頼をアップロードキューに積み、ジャーナルに記録する
+pub fn send(receipt_number: &str, address: &str, staff_id: &str) -> Result<PendingUpload, String> {
+    let address = validate_address(address)?;
+    let config = config::load()?;
+    let relay_url = config
+        .email_relay_url
+        .clone()
+        .filter(|u| !u.is_empty())
+        .ok_or_else(|| "Email relay is not configured".to_string())?;
+    let receipt = journal::find_sale(receipt_number)?
+        .ok_or_else(|| format!("Receipt not found: {}", receipt_number))?;
+
+    let request = EmailReceiptRequest {
+        relay_url,
+        receipt_number: receipt.receipt_number.clone(),
+        to: address.clone(),
+        subject: subject(&receipt),
+        body: render_text(&receipt, &config),
+    };
+    // 同じレシートを別の宛先に送り直せるよう、宛先ごとに別のアップロードにする
+    let reference_id = format!("{}:{}", receipt.receipt_number, address);
+    let upload = upload_queue::enqueue(upload_queue::KIND_EMAIL_RECEIPT, &reference_id, &request)?;
+
+    journal::append(
+        Some(staff_id),
+        JournalRecord::ReceiptEmailed {
+            receipt_number: receipt.receipt_number,
+            address: mask_address(&address),
+            upload_id: upload.id.clone(),
+        },
+    )?;
+
+    Ok(upload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipt::test_support;
+    use serde_json::json;
+
+    fn sample_receipt() -> ReceiptData {
+        test_support::receipt("R-0001")
+            .item(json!({ "circle_name": "みず技研", "name": "Rust入門", "jan": "9784000000000", "quantity": 2, "price": 2000 }))
+            .with(json!({
+                "event_name": "技術書典",
+                "circle_name": "みず技研",
+                "sale_start_date_time": "2025-12-16T01:30:00Z",
+                "payments": [{ "method": "現金", "amount": 5000 }],
+            }))
+            .build()
+    }
+
+    #[test]
+    fn test_validate_address() {
+        assert_eq!(validate_address(" taro@example.com ").unwrap(), "taro@example.com");
+        assert!(validate_address("taro").is_err());
+        assert!(validate_address("@example.com").is_err());
+        assert!(validate_address("taro@example").is_err());
+        assert!(validate_address("taro@@example.com").is_err());
+        assert!(validate_address("ta ro@example.com").is_err());
+    }
+
+    #[test]
+    fn test_mask_address() {
+        assert_eq!(mask_address("taro@example.com"), "ta***@example.com");
+        assert_eq!(mask_address("t@example.com"), "t***@example.com");
+    }
+
+    #[test]
+    fn test_render_text() {
+        let text = render_text(&sample_receipt(), &config::TerminalConfig::default());
+        assert!(text.contains("# R-0001"));
+        assert!(text.contains("2025/12/16 10:30 責: S001"));
+        assert!(text.contains("みず技研 / Rust入門"));
+        assert!(text.contains("合計(税込)  ￥2,000"));
+        assert!(text.contains("釣り銭  ￥3,000"));
+    }
+}
@@ -0,0 +1,115 @@
+//! 接続先環境モジュール
+//!
+//! バックエンドの接続先（本番・ステージング・ローカル）を端末ごとに切り替える
+//! 接続先のURLはフロントエンドのビルド設定（`VITE_*API_BASE_URL`）で持ち、ここでは選択中の環境だけを保存する
+//! 本番以外ではイベント単位のデータを環境別のディレクトリに分け、ジャーナルと印刷物にも環境を明記して、
+//! テストの売上が本番のレポートに混ざらないようにする
+
+use crate::session;
+use crate::storage;
+use crate::upload_queue;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 選択中の環境の保存ファイル名
+const ENVIRONMENT_FILE: &str = "environment.json";
+
+/// 接続先の環境
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiEnvironment {
+    #[default]
+    Production,
+    Staging,
+    Local,
+}
+
+impl ApiEnvironment {
+    /// すべての環境
+    pub const ALL: [ApiEnvironment; 3] = [Self::Production, Self::Staging, Self::Local];
+
+    pub fn is_production(&self) -> bool {
+        *self == Self::Production
+    }
+
+    /// 環境名（ディレクトリ名・印字に使う）
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Production => "production",
+            Self::Staging => "staging",
+            Self::Local => "local",
+        }
+    }
+}
+
+/// 選択中の環境と切り替えた記録
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct EnvironmentState {
+    pub environment: ApiEnvironment,
+    /// 切り替えた日時（UNIX秒）
+    pub switched_at: u64,
+    /// 切り替えた責任者のスタッフ番号
+    pub switched_by: Option<String>,
+}
+
+/// 選択中の環境（未設定の場合は本番）
+///
+/// 保存ファイルが壊れている場合はエラーにする（本番のデータにテストの売上を書き込まないため）
+pub fn current() -> Result<ApiEnvironment, String> {
+    Ok(state()?.map(|s| s.environment).unwrap_or_default())
+}
+
+/// 切り替えの記録を取得
+pub fn state() -> Result<Option<EnvironmentState>, String> {
+    storage::load_json(ENVIRONMENT_FILE)
+}
+
+/// 切り替えてよいか確認
+///
+/// 送信待ちのデータは元の環境のサーバーへ送るものなので、すべて送信してから切り替える
+fn check_switch(session_open: bool, pending_uploads: usize) -> Result<(), String> {
+    if session_open {
+        return Err("Close the current session before switching environments".to_string());
+    }
+    if pending_uploads > 0 {
+        return Err(format!(
+            "Send the {} pending uploads before switching environments",
+            pending_uploads
+        ));
+    }
+    Ok(())
+}
+
+/// 環境を切り替える（閉局中で、送信待ちのデータがない場合のみ）
+pub fn switch(environment: ApiEnvironment, staff_id: Option<&str>) -> Result<EnvironmentState, String> {
+    check_switch(session::current()?.open, upload_queue::list_pending()?.len())?;
+    let state = EnvironmentState {
+        environment,
+        switched_at: storage::now_unix(),
+        switched_by: staff_id.map(|s| s.to_string()),
+    };
+    storage::save_json(ENVIRONMENT_FILE, &state)?;
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_support::TempDataDir;
+
+    #[test]
+    fn test_switch_requires_closed_session_and_empty_queue() {
+        assert!(check_switch(false, 0).is_ok());
+        assert!(check_switch(true, 0).is_err());
+        assert!(check_switch(false, 2).is_err());
+    }
+
+    #[test]
+    fn test_broken_environment_file_is_not_production() {
+        let dir = TempDataDir::new();
+        assert_eq!(current(), Ok(ApiEnvironment::Production));
+
+        dir.write(ENVIRONMENT_FILE, "{");
+        assert!(current().is_err());
+    }
+}
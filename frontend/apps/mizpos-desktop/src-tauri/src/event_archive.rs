@@ -0,0 +1,303 @@
+//! イベント終了時のアーカイブモジュール
+//!
+//! 選択中イベントのデータ（ジャーナル・レポート・ログなど）を目録付きの tar.gz に書き出す
+//! 書き出したアーカイブは読み直して目録のハッシュと照合し、照合できた場合だけイベントのデータを端末から消去する
+//! 貸出端末を空の状態で返却し、手元には検証済みのアーカイブを残せるようにする
+
+use crate::environment;
+use crate::event_context;
+use crate::session;
+use crate::storage;
+use crate::upload_queue;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// アーカイブ内の目録のファイル名
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// アーカイブに入れたファイル
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ArchivedFile {
+    /// イベントのデータディレクトリからの相対パス
+    pub path: String,
+    pub size: u64,
+    /// SHA-256（16進）
+    pub sha256: String,
+}
+
+/// アーカイブの目録（アーカイブの先頭に manifest.json として入れる）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ArchiveManifest {
+    pub event_id: String,
+    pub event_name: Option<String>,
+    /// 接続先環境名
+    pub environment: String,
+    /// 作成日時（UNIX秒）
+    pub created_at: u64,
+    pub files: Vec<ArchivedFile>,
+}
+
+/// 作成したアーカイブ
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct EventArchive {
+    /// 書き出したファイルのパス
+    pub path: String,
+    pub manifest: ArchiveManifest,
+    /// アーカイブファイル全体の SHA-256（16進、受け渡し後の照合用）
+    pub archive_sha256: String,
+    pub archive_size: u64,
+    /// 目録をアップロードキューに積んだか
+    pub uploaded: bool,
+    /// イベントのデータを端末から消去したか
+    pub reset: bool,
+}
+
+/// 本部へ送る目録（アーカイブ本体は送らず、後で受け取ったアーカイブの照合に使う）
+#[derive(Serialize)]
+struct ArchiveRecord<'a> {
+    archive_sha256: &'a str,
+    archive_size: u64,
+    manifest: &'a ArchiveManifest,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// ディレクトリ配下のファイルを相対パス順に集める（書き込み途中の一時ファイルは除く）
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<(String, PathBuf)>) -> Result<(), String> {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+        .filter_map(|e| e.ok())
+        .collect();
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, &format!("{}{}/", prefix, name), files)?;
+        } else if !name.ends_with(".tmp") {
+            files.push((format!("{}{}", prefix, name), path));
+        }
+    }
+    Ok(())
+}
+
+fn append_entry<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    bytes: &[u8],
+    mtime: u64,
+) -> Result<(), String> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(mtime);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, path, bytes)
+        .map_err(|e| format!("Failed to write archive: {}", e))
+}
+
+/// 目録と読み込んだファイルを tar.gz に書き出す
+fn write_archive(
+    destination: &Path,
+    manifest: &ArchiveManifest,
+    contents: &[Vec<u8>],
+) -> Result<(), String> {
+    let file = File::create(destination)
+        .map_err(|e| format!("Failed to create {}: {}", destination.display(), e))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    let manifest_json =
+        serde_json::to_vec_pretty(manifest).map_err(|e| format!("Failed to serialize: {}", e))?;
+    append_entry(&mut builder, MANIFEST_FILE, &manifest_json, manifest.created_at)?;
+    for (file, bytes) in manifest.files.iter().zip(contents) {
+        append_entry(&mut builder, &file.path, bytes, manifest.created_at)?;
+    }
+    let file = builder
+        .into_inner()
+        .and_then(|gz| gz.finish())
+        .map_err(|e| format!("Failed to write archive: {}", e))?;
+    file.sync_all()
+        .map_err(|e| format!("Failed to write archive: {}", e))
+}
+
+/// アーカイブを読み直し、目録と中身が一致することを確かめる
+///
+/// 一致した場合はアーカイブファイル全体の SHA-256 を返す
+fn verify_archive(path: &Path, manifest: &ArchiveManifest) -> Result<String, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read archive: {}", e))?;
+    let mut expected: HashMap<&str, &ArchivedFile> =
+        manifest.files.iter().map(|f| (f.path.as_str(), f)).collect();
+    let mut manifest_found = false;
+
+    let mut archive = tar::Archive::new(GzDecoder::new(bytes.as_slice()));
+    let entries = archive
+        .entries()
+        .map_err(|e| format!("Broken archive: {}", e))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Broken archive: {}", e))?;
+        let name = entry
+            .path()
+            .map_err(|e| format!("Broken archive: {}", e))?
+            .to_string_lossy()
+            .to_string();
+        let mut content = Vec::new();
+        entry
+            .read_to_end(&mut content)
+            .map_err(|e| format!("Broken archive: {}", e))?;
+
+        if name == MANIFEST_FILE {
+            let archived: ArchiveManifest = serde_json::from_slice(&content)
+                .map_err(|e| format!("Broken archive manifest: {}", e))?;
+            if &archived != manifest {
+                return Err("Archive manifest does not match".to_string());
+            }
+            manifest_found = true;
+            continue;
+        }
+        let file = expected
+            .remove(name.as_str())
+            .ok_or_else(|| format!("Unexpected file in archive: {}", name))?;
+        if content.len() as u64 != file.size || sha256_hex(&content) != file.sha256 {
+            return Err(format!("Archived file does not match: {}", name));
+        }
+    }
+
+    if !manifest_found {
+        return Err("Archive manifest is missing".to_string());
+    }
+    if let Some(path) = expected.keys().next() {
+        return Err(format!("File missing from archive: {}", path));
+    }
+    Ok(sha256_hex(&bytes))
+}
+
+/// 選択中イベントのデータをアーカイブに書き出す
+///
+/// - `upload`: 目録とアーカイブのハッシュを本部へ送る（アップロードキューに積む）
+/// - `reset`: 検証できた場合にイベントのデータを端末から消去し、イベント未選択に戻す
+///
+/// 消去する場合は、閉局済みで送信待ちのアップロードがないことを先に確かめる
+pub fn archive(destination: &Path, upload: bool, reset: bool) -> Result<EventArchive, String> {
+    if reset {
+        if session::current()?.open {
+            return Err("Close the current session before resetting the event".to_string());
+        }
+        let pending = upload_queue::list_pending()?.len();
+        if pending > 0 {
+            return Err(format!("{} uploads are still pending", pending));
+        }
+    }
+
+    let data_dir = storage::data_dir().ok_or_else(|| "Cannot determine data directory".to_string())?;
+    let event_dir = data_dir.join(event_context::scoped_dir()?);
+    if !event_dir.is_dir() {
+        return Err("No data for the current event".to_string());
+    }
+    // 消去するディレクトリの中に書き出すとアーカイブごと消える
+    if destination.starts_with(&event_dir) {
+        return Err("Cannot write the archive into the event data directory".to_string());
+    }
+
+    let mut files = Vec::new();
+    collect_files(&event_dir, "", &mut files)?;
+    let mut contents = Vec::with_capacity(files.len());
+    let mut archived = Vec::with_capacity(files.len());
+    for (path, full_path) in files {
+        // ハッシュと書き出す中身が食い違わないよう、一度読み込んだものを使う
+        let bytes = fs::read(&full_path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        archived.push(ArchivedFile {
+            path,
+            size: bytes.len() as u64,
+            sha256: sha256_hex(&bytes),
+        });
+        contents.push(bytes);
+    }
+
+    let event_id = event_context::active_event_id()?;
+    let manifest = ArchiveManifest {
+        event_name: event_context::active()?.map(|e| e.event_name),
+        event_id,
+        environment: environment::current()?.name().to_string(),
+        created_at: storage::now_unix(),
+        files: archived,
+    };
+
+    write_archive(destination, &manifest, &contents)?;
+    let archive_sha256 = match verify_archive(destination, &manifest) {
+        Ok(hash) => hash,
+        Err(e) => {
+            // 検証できないアーカイブを正しいものと取り違えないよう消しておく
+            let _ = fs::remove_file(destination);
+            return Err(e);
+        }
+    };
+    let archive_size = fs::metadata(destination)
+        .map(|m| m.len())
+        .map_err(|e| format!("Failed to read archive: {}", e))?;
+
+    if upload {
+        let record = ArchiveRecord {
+            archive_sha256: &archive_sha256,
+            archive_size,
+            manifest: &manifest,
+        };
+        upload_queue::enqueue(upload_queue::KIND_EVENT_ARCHIVE, &manifest.event_id, &record)?;
+    }
+    if reset {
+        event_context::clear_active()?;
+    }
+
+    Ok(EventArchive {
+        path: destination.display().to_string(),
+        manifest,
+        archive_sha256,
+        archive_size,
+        uploaded: upload,
+        reset,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_support::TempDataDir;
+
+    #[test]
+    fn test_archive_verifies_and_resets_event() {
+        let dir = TempDataDir::new();
+        event_context::select("e1", "Event 1").unwrap();
+        dir.write("events/e1/journal.jsonl", "{\"seq\":1}\n");
+        dir.write("events/e1/reports/closing.json", "{}");
+        dir.write("events/e1/quotes.tmp", "");
+        let destination = std::env::temp_dir().join(format!("{}.tar.gz", uuid::Uuid::new_v4()));
+
+        let result = archive(&destination, false, true).unwrap();
+        let paths: Vec<&str> = result.manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["journal.jsonl", "reports/closing.json"]);
+        assert_eq!(result.manifest.event_name.as_deref(), Some("Event 1"));
+        assert_eq!(verify_archive(&destination, &result.manifest), Ok(result.archive_sha256.clone()));
+
+        // 端末からはイベントのデータが消え、イベント未選択に戻る
+        assert!(event_context::active().unwrap().is_none());
+        assert!(event_context::list_local_events(environment::ApiEnvironment::Production).is_empty());
+
+        // 目録と中身が食い違うアーカイブは検証に通らない
+        let mut tampered = result.manifest.clone();
+        tampered.files[0].sha256 = sha256_hex(b"other");
+        assert!(verify_archive(&destination, &tampered).is_err());
+        let _ = fs::remove_file(&destination);
+    }
+}
@@ -0,0 +1,277 @@
+//! イベントコンテキストモジュール
+//!
+//! 選択中のイベントを保持し、イベント単位のデータを別ディレクトリに分離する
+//! 1台の端末を複数のイベントで使い回してもジャーナル・採番・レポートが混ざらないようにする
+//! 本番以外の接続先環境では、環境名のディレクトリの下に分ける（テストの売上を本番のデータに混ぜない）
+
+use crate::environment::{self, ApiEnvironment};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+
+/// 選択中イベントの保存ファイル名
+const ACTIVE_EVENT_FILE: &str = "active_event.json";
+/// イベント単位データのディレクトリ名
+const EVENTS_DIR: &str = "events";
+/// イベント未選択時の名前空間
+const DEFAULT_EVENT_ID: &str = "default";
+/// 採番カウンターの保存ファイル名
+const COUNTERS_FILE: &str = "counters.json";
+
+/// 選択中のイベント
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ActiveEvent {
+    pub event_id: String,
+    pub event_name: String,
+    /// 選択日時（UNIX秒）
+    pub selected_at: u64,
+}
+
+/// イベント単位の採番カウンター
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct EventCounters {
+    receipt_seq: u32,
+    #[serde(default)]
+    parked_seq: u32,
+    #[serde(default)]
+    quote_seq: u32,
+}
+
+/// 選択中のイベントを取得
+pub fn active() -> Result<Option<ActiveEvent>, String> {
+    storage::load_json(ACTIVE_EVENT_FILE)
+}
+
+/// 選択中のイベントID（未選択時は default）
+pub fn active_event_id() -> Result<String, String> {
+    Ok(active()?
+        .map(|e| e.event_id)
+        .unwrap_or_else(|| DEFAULT_EVENT_ID.to_string()))
+}
+
+/// イベントIDをディレクトリ名として安全な文字列に変換
+///
+/// 英数字・`-`・`_` 以外は UTF-8 のバイトごとに `%XX` へ変換する（`%` 自身も変換する）
+/// 異なるイベントIDが同じディレクトリにならず、ディレクトリ名から元のIDに戻せる
+fn encode_event_id(event_id: &str) -> String {
+    let mut encoded = String::with_capacity(event_id.len());
+    for byte in event_id.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// ディレクトリ名からイベントIDに戻す（encode_event_id で作られていない名前は None）
+fn decode_event_id(dir_name: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(dir_name.len());
+    let mut rest = dir_name.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+            bytes.push(byte);
+            rest = tail;
+        } else {
+            return None;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// 選択中の環境・イベントのデータファイルの相対パスを取得
+pub fn scoped(file_name: &str) -> Result<String, String> {
+    Ok(scoped_for(environment::current()?, &active_event_id()?, file_name))
+}
+
+/// 選択中の環境・イベントのデータディレクトリの相対パスを取得
+pub fn scoped_dir() -> Result<String, String> {
+    Ok(format!(
+        "{}/{}",
+        events_dir(environment::current()?),
+        encode_event_id(&active_event_id()?)
+    ))
+}
+
+/// 環境ごとのイベント単位データのディレクトリ（本番は従来どおり events/）
+fn events_dir(environment: ApiEnvironment) -> String {
+    if environment.is_production() {
+        EVENTS_DIR.to_string()
+    } else {
+        format!("{}/{}", environment.name(), EVENTS_DIR)
+    }
+}
+
+/// 指定した環境・イベントのデータファイルの相対パスを取得
+pub fn scoped_for(environment: ApiEnvironment, event_id: &str, file_name: &str) -> String {
+    format!(
+        "{}/{}/{}",
+        events_dir(environment),
+        encode_event_id(event_id),
+        file_name
+    )
+}
+
+/// イベントを選択（切り替え）
+pub fn select(event_id: &str, event_name: &str) -> Result<ActiveEvent, String> {
+    if event_id.trim().is_empty() {
+        return Err("Event ID is empty".to_string());
+    }
+
+    let event = ActiveEvent {
+        event_id: event_id.to_string(),
+        event_name: event_name.to_string(),
+        selected_at: storage::now_unix(),
+    };
+    storage::save_json(ACTIVE_EVENT_FILE, &event)?;
+    Ok(event)
+}
+
+/// 選択中イベントのデータを消去し、イベント未選択の状態に戻す
+pub fn clear_active() -> Result<(), String> {
+    storage::remove_dir_all(&scoped_dir()?)?;
+    storage::remove_file(ACTIVE_EVENT_FILE)
+}
+
+/// 指定した環境で、端末内にデータが残っているイベントID一覧
+pub fn list_local_events(environment: ApiEnvironment) -> Vec<String> {
+    let Some(dir) = storage::data_dir().map(|p| p.join(events_dir(environment))) else {
+        return vec![];
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut events: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter_map(|name| decode_event_id(&name))
+        .collect();
+    events.sort();
+    events
+}
+
+/// 選択中イベントのレシート番号を採番
+///
+/// 形式: `{イベントID}-{連番5桁}`
+pub fn next_receipt_number() -> Result<String, String> {
+    let seq = next_seq(|c| &mut c.receipt_seq)?;
+    Ok(format!("{}-{:05}", active_event_id()?, seq))
+}
+
+/// 選択中イベントの保留取引番号を採番
+///
+/// 形式: `P{連番4桁}`
+pub fn next_parked_code() -> Result<String, String> {
+    let seq = next_seq(|c| &mut c.parked_seq)?;
+    Ok(format!("P{:04}", seq))
+}
+
+/// 選択中イベントの見積番号を採番
+///
+/// 形式: `Q{連番4桁}`
+pub fn next_quote_code() -> Result<String, String> {
+    let seq = next_seq(|c| &mut c.quote_seq)?;
+    Ok(format!("Q{:04}", seq))
+}
+
+/// カウンターを1つ進めて保存
+fn next_seq(counter: impl FnOnce(&mut EventCounters) -> &mut u32) -> Result<u32, String> {
+    let path = scoped(COUNTERS_FILE)?;
+    let mut counters: EventCounters = storage::load_json(&path)?.unwrap_or_default();
+    let seq = counter(&mut counters);
+    *seq += 1;
+    let seq = *seq;
+    storage::save_json(&path, &counters)?;
+    Ok(seq)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_support::TempDataDir;
+
+    #[test]
+    fn test_scoped_for_does_not_collide() {
+        let ids = ["a/b", "a_b", "a%2Fb", "a.b", "..", "", "イベント"];
+        let paths: Vec<String> = ids
+            .iter()
+            .map(|id| scoped_for(ApiEnvironment::Production, id, "journal.jsonl"))
+            .collect();
+        for (i, a) in paths.iter().enumerate() {
+            for b in &paths[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+        assert_eq!(
+            scoped_for(ApiEnvironment::Production, "comiket-105", "x.json"),
+            "events/comiket-105/x.json"
+        );
+        assert_eq!(scoped_for(ApiEnvironment::Production, "..", "x.json"), "events/%2E%2E/x.json");
+        assert_eq!(
+            scoped_for(ApiEnvironment::Staging, "comiket-105", "x.json"),
+            "staging/events/comiket-105/x.json"
+        );
+    }
+
+    #[test]
+    fn test_event_id_roundtrip() {
+        for id in ["comiket-105", "a/b", "a%2Fb", "イベント 1"] {
+            assert_eq!(decode_event_id(&encode_event_id(id)).as_deref(), Some(id));
+        }
+        assert_eq!(decode_event_id("a%2"), None);
+        assert_eq!(decode_event_id("a.b"), None);
+    }
+
+    #[test]
+    fn test_scoped_follows_active_event() {
+        let _dir = TempDataDir::new();
+        assert_eq!(scoped("x.json").unwrap(), "events/default/x.json");
+
+        select("a/b", "Event").unwrap();
+        assert_eq!(scoped("x.json").unwrap(), "events/a%2Fb/x.json");
+        storage::save_json(&scoped("x.json").unwrap(), &1u32).unwrap();
+        assert_eq!(list_local_events(ApiEnvironment::Production), vec!["a/b".to_string()]);
+        assert!(list_local_events(ApiEnvironment::Staging).is_empty());
+    }
+
+    #[test]
+    fn test_scoped_fails_on_broken_active_event() {
+        let dir = TempDataDir::new();
+        dir.write(ACTIVE_EVENT_FILE, "{");
+        // 既定の名前空間に書き込まないようエラーにする
+        assert!(scoped("x.json").is_err());
+    }
+
+    #[test]
+    fn test_next_seq_is_per_event() {
+        let _dir = TempDataDir::new();
+        select("e1", "Event 1").unwrap();
+        assert_eq!(next_receipt_number().unwrap(), "e1-00001");
+        assert_eq!(next_receipt_number().unwrap(), "e1-00002");
+        assert_eq!(next_parked_code().unwrap(), "P0001");
+
+        select("e2", "Event 2").unwrap();
+        assert_eq!(next_receipt_number().unwrap(), "e2-00001");
+        assert_eq!(next_quote_code().unwrap(), "Q0001");
+    }
+
+    #[test]
+    fn test_next_seq_keeps_corrupt_counters() {
+        let dir = TempDataDir::new();
+        select("e1", "Event 1").unwrap();
+        let path = scoped(COUNTERS_FILE).unwrap();
+        dir.write(&path, "{\"receipt_seq\": 4");
+
+        // 壊れたカウンターを 0 から数え直すと番号が重複するため採番しない
+        assert!(next_receipt_number().is_err());
+        assert_eq!(dir.read(&path), "{\"receipt_seq\": 4");
+    }
+}
@@ -0,0 +1,130 @@
+//! 代替の控えモジュール
+//!
+//! 決済後のレシート印刷が（再試行しても）失敗した場合、レシート番号と金額だけの控えを別のプリンターで印刷する
+//! 使えるプリンターが他にない場合は、失敗したプリンターへの印刷を待ち行列に積み直す
+//! お客様が何も持たずに帰ることがないようにし、失敗したことはジャーナルに記録する
+//! 控えの印刷に失敗しても、さらに控えは作らない
+
+use crate::config::{self, TerminalConfig};
+use crate::journal::{self, JournalRecord};
+use crate::network_printer;
+use crate::receipt::ReceiptData;
+use crate::spooler::{self, SpoolDocument, SpoolJob, UsbPrinter};
+
+/// 控えを印刷できるプリンター（端末設定にあるプリンターのうち、失敗したもの以外）
+///
+/// バックオフィスのジャーナル用プリンター、セルフレジのプリンター、LAN接続のプリンターの順
+fn candidates(config: &TerminalConfig, failed: &[UsbPrinter]) -> Vec<UsbPrinter> {
+    let kiosk = match (config.kiosk.printer_vendor_id, config.kiosk.printer_device_id) {
+        (Some(vendor_id), Some(device_id)) => Some(UsbPrinter {
+            vendor_id,
+            device_id,
+            paper_width: config.kiosk.paper_width,
+        }),
+        _ => None,
+    };
+    let network = network_printer::devices(&config.network_printers).map(|(device_id, _)| UsbPrinter {
+        vendor_id: network_printer::NETWORK_VENDOR_ID,
+        device_id,
+        paper_width: None,
+    });
+
+    let mut printers: Vec<UsbPrinter> = Vec::new();
+    for printer in config.journal_printer.clone().into_iter().chain(kiosk).chain(network) {
+        let failed = failed
+            .iter()
+            .any(|f| f.vendor_id == printer.vendor_id && f.device_id == printer.device_id);
+        if !failed && !printers.contains(&printer) {
+            printers.push(printer);
+        }
+    }
+    printers
+}
+
+/// レシートの印刷に失敗した売上の控えを印刷し、ジャーナルに記録する
+///
+/// 他に使えるプリンターがある場合は最初の1台に、ない場合は失敗したプリンターに控えの印刷を積む
+pub fn handle(receipt: &ReceiptData, failed: &[UsbPrinter], error: &str) -> Result<Option<SpoolJob>, String> {
+    let targets = candidates(&config::load()?, failed)
+        .into_iter()
+        .take(1)
+        .collect::<Vec<_>>();
+    let targets = if targets.is_empty() { failed.to_vec() } else { targets };
+    let job = spooler::submit(
+        SpoolDocument::FallbackSlip {
+            receipt_number: receipt.receipt_number.clone(),
+            event_name: receipt.event_name.clone(),
+            total: receipt.total,
+        },
+        targets,
+    );
+
+    journal::append(
+        Some(&receipt.staff_id),
+        JournalRecord::ReceiptPrintFailed {
+            receipt_number: receipt.receipt_number.clone(),
+            total: receipt.total,
+            error: error.to_string(),
+            fallback_job_id: job.as_ref().ok().map(|j| j.id.clone()),
+        },
+    )?;
+    Ok(job.ok())
+}
+
+/// スプーラーのジョブが終わったときに、お客様のレシートが印刷できなかったか確認する
+///
+/// 先頭（お客様用）のプリンターで失敗したレシートだけが対象（ミラー先の失敗は控えを作らない）
+pub fn on_job_finished(job: &SpoolJob) {
+    let SpoolDocument::Receipt { receipt } = &job.document else {
+        return;
+    };
+    let Some(main) = job.results.first().filter(|r| !r.success) else {
+        return;
+    };
+    let error = main.error.clone().unwrap_or_default();
+    let _ = handle(receipt, std::slice::from_ref(&main.printer), &error);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network_printer::NetworkPrinter;
+
+    fn printer(vendor_id: u16, device_id: u16) -> UsbPrinter {
+        UsbPrinter {
+            vendor_id,
+            device_id,
+            paper_width: Some(58),
+        }
+    }
+
+    #[test]
+    fn test_candidates_skip_failed_printers() {
+        let mut config = TerminalConfig {
+            journal_printer: Some(printer(0x04b8, 0x0202)),
+            ..TerminalConfig::default()
+        };
+        config.kiosk.printer_vendor_id = Some(0x0416);
+        config.kiosk.printer_device_id = Some(0x5011);
+        config.network_printers.push(NetworkPrinter {
+            name: "レジ1".to_string(),
+            host: "192.168.0.10".to_string(),
+            port: 9100,
+        });
+
+        let ids = |printers: Vec<UsbPrinter>| -> Vec<(u16, u16)> {
+            printers.iter().map(|p| (p.vendor_id, p.device_id)).collect()
+        };
+        assert_eq!(
+            ids(candidates(&config, &[printer(0x0416, 0x5011)])),
+            vec![(0x04b8, 0x0202), (network_printer::NETWORK_VENDOR_ID, 0)]
+        );
+        // 用紙幅が違っても同じプリンターなら除く
+        let failed = UsbPrinter {
+            paper_width: Some(80),
+            ..printer(0x04b8, 0x0202)
+        };
+        assert_eq!(ids(candidates(&config, &[failed]))[0], (0x0416, 0x5011));
+        assert!(candidates(&TerminalConfig::default(), &[]).is_empty());
+    }
+}
@@ -0,0 +1,288 @@
+//! ギフト券モジュール
+//!
+//! 券番号付きのギフト券を発行し、端末鍵で署名した券面QRを印刷する
+//! 支払時に券番号・QRで照合して使用済みにし、発行・使用はジャーナルに記録する
+//! 同じブースの複数端末で使用状況を共有できるよう、予約と同じく差分の取り出しとマージで同期する
+//! イベントをまたいで使えるよう、イベント単位ではなく端末全体で保存する
+
+use crate::journal::{self, JournalRecord};
+use crate::storage;
+use crate::terminal_auth::{self, SignatureData};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+/// ギフト券の保存ファイル名
+const GIFT_VOUCHERS_FILE: &str = "gift_vouchers.json";
+/// QRコードの接頭辞（形式のバージョンを含む）
+const GIFT_VOUCHER_QR_PREFIX: &str = "MZG1:";
+/// 1枚の額面の上限
+const MAX_AMOUNT: u32 = 100_000;
+
+/// ギフト券の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum GiftVoucherStatus {
+    /// 未使用
+    Issued,
+    /// 使用済み
+    Redeemed,
+}
+
+/// ギフト券
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct GiftVoucher {
+    /// 券番号（端末間で重複しないようUUIDから生成）
+    pub serial: String,
+    pub amount: u32,
+    pub status: GiftVoucherStatus,
+    /// 発行したスタッフ番号
+    pub issued_by: String,
+    /// 発行日時（UNIX秒）
+    pub issued_at: u64,
+    /// 券面の署名（署名対象は `signed_message` の文字列）
+    pub signature: SignatureData,
+    /// 使用したスタッフ番号
+    pub redeemed_by: Option<String>,
+    /// 使用日時（UNIX秒）
+    pub redeemed_at: Option<u64>,
+    /// 使用した売上のレシート番号
+    pub redeemed_receipt: Option<String>,
+    /// 最終更新日時（UNIX秒、同期時のマージに使用）
+    pub updated_at: u64,
+}
+
+/// 署名対象の文字列
+fn signed_message(serial: &str, amount: u32, issued_at: u64) -> String {
+    format!("gift:{}:{}:{}", serial, amount, issued_at)
+}
+
+impl GiftVoucher {
+    /// 券面のQRコードに埋め込む文字列（券番号・額面・発行端末・署名）
+    ///
+    /// 署名の検証はサーバー側で発行端末の公開鍵により行う
+    pub fn qr_data(&self) -> String {
+        format!(
+            "{}{}.{}.{}.{}.{}.{}",
+            GIFT_VOUCHER_QR_PREFIX,
+            self.serial,
+            self.amount,
+            self.issued_at,
+            self.signature.terminal_id,
+            self.signature.timestamp,
+            self.signature.signature
+        )
+    }
+}
+
+fn load_vouchers() -> Result<Vec<GiftVoucher>, String> {
+    Ok(storage::load_json(GIFT_VOUCHERS_FILE)?.unwrap_or_default())
+}
+
+fn save_vouchers(vouchers: &[GiftVoucher]) -> Result<(), String> {
+    storage::save_json(GIFT_VOUCHERS_FILE, &vouchers)
+}
+
+/// 券番号またはQRコードの文字列から券番号を取り出す
+pub fn serial_from_scan(data: &str) -> Result<String, String> {
+    let data = data.trim();
+    let serial = match data.strip_prefix(GIFT_VOUCHER_QR_PREFIX) {
+        Some(payload) => payload.split('.').next().unwrap_or_default(),
+        None => data,
+    };
+    if serial.is_empty() {
+        return Err("Scanned code is empty".to_string());
+    }
+    Ok(serial.to_uppercase())
+}
+
+/// ギフト券を発行し、ジャーナルに記録する
+pub fn issue(amount: u32, staff_id: &str) -> Result<GiftVoucher, String> {
+    if amount == 0 || amount > MAX_AMOUNT {
+        return Err(format!("Invalid gift voucher amount: {}", amount));
+    }
+
+    let now = storage::now_unix();
+    let serial = format!("G{}", &Uuid::new_v4().simple().to_string()[..10].to_uppercase());
+    let signature = terminal_auth::sign_message(&signed_message(&serial, amount, now))
+        .map_err(|e| e.to_string())?;
+    let voucher = GiftVoucher {
+        serial,
+        amount,
+        status: GiftVoucherStatus::Issued,
+        issued_by: staff_id.to_string(),
+        issued_at: now,
+        signature,
+        redeemed_by: None,
+        redeemed_at: None,
+        redeemed_receipt: None,
+        updated_at: now,
+    };
+
+    let mut vouchers = load_vouchers()?;
+    vouchers.push(voucher.clone());
+    save_vouchers(&vouchers)?;
+    journal::append(
+        Some(staff_id),
+        JournalRecord::GiftVoucherIssued {
+            serial: voucher.serial.clone(),
+            amount,
+        },
+    )?;
+    Ok(voucher)
+}
+
+/// 支払に使えるか確認（未使用のギフト券を返す）
+///
+/// 他の端末で発行され、まだ同期していない券は確認できない
+pub fn validate(code_or_qr: &str) -> Result<GiftVoucher, String> {
+    let serial = serial_from_scan(code_or_qr)?;
+    let voucher = load_vouchers()?
+        .into_iter()
+        .find(|v| v.serial == serial)
+        .ok_or_else(|| format!("Gift voucher not found: {}", serial))?;
+    if voucher.status != GiftVoucherStatus::Issued {
+        return Err(format!("Gift voucher has already been redeemed: {}", serial));
+    }
+    Ok(voucher)
+}
+
+/// 使用済みにし、ジャーナルに記録する
+pub fn redeem(
+    code_or_qr: &str,
+    staff_id: &str,
+    receipt_number: Option<String>,
+) -> Result<GiftVoucher, String> {
+    let serial = serial_from_scan(code_or_qr)?;
+    let mut vouchers = load_vouchers()?;
+    let voucher = vouchers
+        .iter_mut()
+        .find(|v| v.serial == serial)
+        .ok_or_else(|| format!("Gift voucher not found: {}", serial))?;
+    if voucher.status != GiftVoucherStatus::Issued {
+        return Err(format!("Gift voucher has already been redeemed: {}", serial));
+    }
+
+    let now = storage::now_unix();
+    voucher.status = GiftVoucherStatus::Redeemed;
+    voucher.redeemed_by = Some(staff_id.to_string());
+    voucher.redeemed_at = Some(now);
+    voucher.redeemed_receipt = receipt_number.clone();
+    voucher.updated_at = now;
+
+    let redeemed = voucher.clone();
+    save_vouchers(&vouchers)?;
+    journal::append(
+        Some(staff_id),
+        JournalRecord::GiftVoucherRedeemed {
+            serial: redeemed.serial.clone(),
+            amount: redeemed.amount,
+            receipt_number,
+        },
+    )?;
+    Ok(redeemed)
+}
+
+/// ギフト券の一覧
+pub fn list() -> Result<Vec<GiftVoucher>, String> {
+    load_vouchers()
+}
+
+/// 指定日時以降に更新されたギフト券を取得（同期の送信用）
+pub fn changed_since(since: u64) -> Result<Vec<GiftVoucher>, String> {
+    Ok(load_vouchers()?
+        .into_iter()
+        .filter(|v| v.updated_at >= since)
+        .collect())
+}
+
+/// 他端末のギフト券をマージし、変更件数を返す
+///
+/// 使用済みは取り消せないため優先し、それ以外は更新日時が新しい方を採用する
+fn merge_into(vouchers: &mut Vec<GiftVoucher>, remote: Vec<GiftVoucher>) -> usize {
+    let mut changed = 0;
+    for incoming in remote {
+        match vouchers.iter_mut().find(|v| v.serial == incoming.serial) {
+            Some(local) => {
+                let local_redeemed = local.status == GiftVoucherStatus::Redeemed;
+                let incoming_redeemed = incoming.status == GiftVoucherStatus::Redeemed;
+                let take = match (local_redeemed, incoming_redeemed) {
+                    (false, true) => true,
+                    (true, false) => false,
+                    _ => incoming.updated_at > local.updated_at,
+                };
+                if take {
+                    *local = incoming;
+                    changed += 1;
+                }
+            }
+            None => {
+                vouchers.push(incoming);
+                changed += 1;
+            }
+        }
+    }
+    changed
+}
+
+/// 他端末のギフト券をマージ（同期の受信用）
+pub fn merge(remote: Vec<GiftVoucher>) -> Result<usize, String> {
+    let mut vouchers = load_vouchers()?;
+    let changed = merge_into(&mut vouchers, remote);
+    if changed > 0 {
+        save_vouchers(&vouchers)?;
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn voucher(serial: &str, status: GiftVoucherStatus, updated_at: u64) -> GiftVoucher {
+        GiftVoucher {
+            serial: serial.to_string(),
+            amount: 1000,
+            status,
+            issued_by: "S001".to_string(),
+            issued_at: 1767000000,
+            signature: SignatureData {
+                terminal_id: "T-0001".to_string(),
+                timestamp: 1767000000,
+                signature: "c2lnbmF0dXJl".to_string(),
+                organization_id: None,
+                seat_label: None,
+            },
+            redeemed_by: None,
+            redeemed_at: None,
+            redeemed_receipt: None,
+            updated_at,
+        }
+    }
+
+    #[test]
+    fn test_serial_from_scan_accepts_qr_and_plain_serial() {
+        let v = voucher("G0123456789", GiftVoucherStatus::Issued, 0);
+        assert_eq!(serial_from_scan(&v.qr_data()).unwrap(), "G0123456789");
+        assert_eq!(serial_from_scan(" g0123456789 ").unwrap(), "G0123456789");
+        assert!(serial_from_scan("MZG1:").is_err());
+        assert!(serial_from_scan("").is_err());
+    }
+
+    #[test]
+    fn test_merge_prefers_redeemed_vouchers() {
+        let mut local = vec![
+            voucher("G1", GiftVoucherStatus::Issued, 200),
+            voucher("G2", GiftVoucherStatus::Redeemed, 100),
+        ];
+        let remote = vec![
+            voucher("G1", GiftVoucherStatus::Redeemed, 150),
+            voucher("G2", GiftVoucherStatus::Issued, 300),
+            voucher("G3", GiftVoucherStatus::Issued, 300),
+        ];
+        assert_eq!(merge_into(&mut local, remote), 2);
+        assert_eq!(local[0].status, GiftVoucherStatus::Redeemed);
+        assert_eq!(local[1].status, GiftVoucherStatus::Redeemed);
+        assert_eq!(local[2].serial, "G3");
+    }
+}
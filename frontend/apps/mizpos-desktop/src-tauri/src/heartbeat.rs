@@ -0,0 +1,242 @@
+//! ハートビートモジュール
+//!
+//! 定期的に端末の状態（アプリのバージョン、営業セッション、プリンター、未送信件数、バッテリー）を
+//! 端末鍵で署名してフロントエンドへ通知し、フロントエンドがサーバーへ送信する
+//! サーバーの受信確認（ACK）の日時を保存し、運用側・端末側の双方で稼働状況を確認できるようにする
+
+use crate::config;
+use crate::event_context;
+use crate::paper_monitor;
+use crate::printer_status::PaperStatus;
+use crate::session;
+use crate::spooler;
+use crate::storage;
+use crate::terminal_auth;
+use crate::upload_queue;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::Emitter;
+use uuid::Uuid;
+
+/// 送信状況の保存ファイル名
+const HEARTBEAT_FILE: &str = "heartbeat.json";
+/// 送信するハートビートを通知するイベント名
+pub const EVENT_HEARTBEAT: &str = "terminal-heartbeat";
+
+/// ハートビート設定
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct HeartbeatConfig {
+    pub enabled: bool,
+    /// 送信間隔（秒）
+    pub interval_secs: u64,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 300,
+        }
+    }
+}
+
+/// バッテリーの状態（フロントエンドから報告される）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BatteryStatus {
+    /// 残量（0.0〜1.0）
+    pub level: f32,
+    pub charging: bool,
+}
+
+/// 端末の状態（署名対象）
+///
+/// フロントエンドを経由して送信するため、脅迫PINの状態は含めない（通報は署名したアップロードのみで送る）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminalStatusReport {
+    pub app_version: String,
+    pub event_id: String,
+    pub session_open: bool,
+    pub session_staff_id: Option<String>,
+    /// 用紙ニアエンド監視で最後に確認した用紙の状態（未確認の場合はNone）
+    pub printer: Option<PaperStatus>,
+    /// 未送信のアップロード件数
+    pub unsynced_uploads: usize,
+    /// 印刷待ちのジョブ件数
+    pub pending_print_jobs: usize,
+    pub battery: Option<BatteryStatus>,
+    /// 作成日時（UNIX秒）
+    pub reported_at: u64,
+}
+
+/// 署名済みのハートビート
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SignedHeartbeat {
+    pub id: String,
+    /// 送信するJSON文字列（署名対象）
+    pub payload: String,
+    pub signature: terminal_auth::SignatureData,
+}
+
+/// 送信状況
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct HeartbeatStatus {
+    /// 最後に作成したハートビートのID
+    pub last_heartbeat_id: Option<String>,
+    /// 最後に作成した日時（UNIX秒）
+    pub last_sent_at: Option<u64>,
+    /// サーバーが最後に受信を確認した日時（UNIX秒）
+    pub last_acknowledged_at: Option<u64>,
+    /// 最後の送信エラー
+    pub last_error: Option<String>,
+}
+
+/// 最後に報告されたバッテリーの状態
+static BATTERY: Mutex<Option<BatteryStatus>> = Mutex::new(None);
+
+/// 送信状況を取得
+pub fn status() -> Result<HeartbeatStatus, String> {
+    Ok(storage::load_json(HEARTBEAT_FILE)?.unwrap_or_default())
+}
+
+fn save_status(status: &HeartbeatStatus) -> Result<(), String> {
+    storage::save_json(HEARTBEAT_FILE, status)
+}
+
+/// バッテリーの状態を記録（次のハートビートに含める）
+pub fn report_battery(battery: BatteryStatus) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&battery.level) {
+        return Err(format!("Invalid battery level: {}", battery.level));
+    }
+    if let Ok(mut current) = BATTERY.lock() {
+        *current = Some(battery);
+    }
+    Ok(())
+}
+
+/// 現在の端末の状態を集める
+pub fn collect() -> Result<TerminalStatusReport, String> {
+    let session = session::current()?;
+    Ok(TerminalStatusReport {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        event_id: event_context::active_event_id()?,
+        session_open: session.open,
+        session_staff_id: session.staff_id,
+        printer: paper_monitor::last_status(),
+        unsynced_uploads: upload_queue::list_pending()?.len(),
+        pending_print_jobs: spooler::pending().len(),
+        battery: BATTERY.lock().ok().and_then(|b| *b),
+        reported_at: storage::now_unix(),
+    })
+}
+
+/// ハートビートを作成して署名する
+pub fn create() -> Result<SignedHeartbeat, String> {
+    let payload = serde_json::to_string(&collect()?).map_err(|e| e.to_string())?;
+    let signature = terminal_auth::sign_message(&payload).map_err(|e| e.to_string())?;
+    let heartbeat = SignedHeartbeat {
+        id: Uuid::new_v4().to_string(),
+        payload,
+        signature,
+    };
+
+    let mut status = status()?;
+    status.last_heartbeat_id = Some(heartbeat.id.clone());
+    status.last_sent_at = Some(storage::now_unix());
+    save_status(&status)?;
+    Ok(heartbeat)
+}
+
+/// サーバーの受信確認を記録
+///
+/// 古いハートビートへの確認でも、受信できていることに変わりはないため記録する
+pub fn acknowledge(acknowledged_at: Option<u64>) -> Result<HeartbeatStatus, String> {
+    let mut status = status()?;
+    status.last_acknowledged_at = Some(acknowledged_at.unwrap_or_else(storage::now_unix));
+    status.last_error = None;
+    save_status(&status)?;
+    Ok(status)
+}
+
+/// 送信失敗を記録
+pub fn mark_failed(error: &str) -> Result<HeartbeatStatus, String> {
+    let mut status = status()?;
+    status.last_error = Some(error.to_string());
+    save_status(&status)?;
+    Ok(status)
+}
+
+/// バックグラウンドで定期的にハートビートを通知する
+pub fn start(app: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        let config = config::load().unwrap_or_default().heartbeat;
+        thread::sleep(Duration::from_secs(config.interval_secs.max(30)));
+        if !config.enabled {
+            continue;
+        }
+        match create() {
+            Ok(heartbeat) => {
+                let _ = app.emit(EVENT_HEARTBEAT, &heartbeat);
+            }
+            Err(e) => {
+                let _ = mark_failed(&e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_support::TempDataDir;
+
+    #[test]
+    fn test_collect_reports_session_and_battery() {
+        let _dir = TempDataDir::new();
+        let report = collect().unwrap();
+        assert!(!report.session_open);
+        assert_eq!(report.unsynced_uploads, 0);
+
+        assert!(report_battery(BatteryStatus { level: 1.5, charging: false }).is_err());
+        let battery = BatteryStatus { level: 0.4, charging: true };
+        report_battery(battery).unwrap();
+        session::open("S001").unwrap();
+
+        let report = collect().unwrap();
+        assert!(report.session_open);
+        assert_eq!(report.session_staff_id.as_deref(), Some("S001"));
+        assert_eq!(report.battery, Some(battery));
+    }
+
+    #[test]
+    fn test_heartbeat_payload_omits_duress() {
+        let _dir = TempDataDir::new();
+        let state = crate::duress::DuressState {
+            active: true,
+            supervisor_id: Some("SUP1".to_string()),
+            triggered_at: Some(1),
+        };
+        storage::save_json("duress.json", &state).unwrap();
+        assert!(crate::duress::is_active().unwrap());
+        let payload = serde_json::to_string(&collect().unwrap()).unwrap();
+        assert!(!payload.contains("duress"));
+    }
+
+    #[test]
+    fn test_acknowledge_clears_last_error() {
+        let _dir = TempDataDir::new();
+        assert!(status().unwrap().last_acknowledged_at.is_none());
+
+        let failed = mark_failed("timeout").unwrap();
+        assert_eq!(failed.last_error.as_deref(), Some("timeout"));
+
+        let acknowledged = acknowledge(Some(1_700_000_000)).unwrap();
+        assert_eq!(acknowledged.last_acknowledged_at, Some(1_700_000_000));
+        assert!(acknowledged.last_error.is_none());
+        // 保存した状況を読み直せる
+        assert_eq!(status().unwrap().last_acknowledged_at, Some(1_700_000_000));
+    }
+}
@@ -0,0 +1,190 @@
+//! 冪等キーモジュール
+//!
+//! フロントエンドがタイムアウト後に同じ印刷コマンドを再送しても、二重印刷・二重記録しないようにする
+//! 成功したコマンドの結果を冪等キーごとに保存し、同じキーの再送には保存した結果をそのまま返す
+//! 失敗したコマンドは保存しないため、同じキーで再試行すると改めて実行する
+
+use crate::event_context;
+use crate::storage;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
+/// 完了済みキーの保存ファイル名
+const IDEMPOTENCY_FILE: &str = "idempotency.json";
+/// 完了済みキーの保持期間（秒）
+const RETENTION_SECS: u64 = 24 * 60 * 60;
+/// 保持する完了済みキーの上限
+const MAX_RECORDS: usize = 1000;
+
+/// 完了済みのコマンド
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletedCommand {
+    pub key: String,
+    /// コマンド名（別のコマンドでのキーの使い回しを検出する）
+    pub command: String,
+    /// コマンドの戻り値
+    pub result: serde_json::Value,
+    /// 完了日時（UNIX秒）
+    pub completed_at: u64,
+}
+
+/// 完了済みキーの一覧
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CompletedCommands(Vec<CompletedCommand>);
+
+impl CompletedCommands {
+    fn find(&self, key: &str) -> Option<&CompletedCommand> {
+        self.0.iter().find(|c| c.key == key)
+    }
+
+    /// 追加し、保持期間を過ぎたもの・上限を超えた古いものを削除する
+    fn insert(&mut self, record: CompletedCommand, now: u64) {
+        self.0.retain(|c| c.key != record.key && c.completed_at + RETENTION_SECS > now);
+        self.0.push(record);
+        if self.0.len() > MAX_RECORDS {
+            let excess = self.0.len() - MAX_RECORDS;
+            self.0.drain(..excess);
+        }
+    }
+}
+
+fn load() -> Result<CompletedCommands, String> {
+    Ok(storage::load_json(&event_context::scoped(IDEMPOTENCY_FILE)?)?.unwrap_or_default())
+}
+
+fn save(records: &CompletedCommands) -> Result<(), String> {
+    storage::save_json(&event_context::scoped(IDEMPOTENCY_FILE)?, records)
+}
+
+/// 実行中のキー
+fn in_flight() -> &'static Mutex<HashSet<String>> {
+    static IN_FLIGHT: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    IN_FLIGHT.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// 実行中の印を外す（失敗時も含めて必ず外す）
+struct InFlightGuard(String);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if let Ok(mut keys) = in_flight().lock() {
+            keys.remove(&self.0);
+        }
+    }
+}
+
+/// 保存済みの結果を確認する
+fn completed_result<T: DeserializeOwned>(key: &str, command: &str) -> Result<Option<T>, String> {
+    let records = load()?;
+    let Some(record) = records.find(key) else {
+        return Ok(None);
+    };
+    if record.command != command {
+        return Err(format!(
+            "Idempotency key {} was already used for {}",
+            key, record.command
+        ));
+    }
+    serde_json::from_value(record.result.clone())
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+/// 冪等キー付きでコマンドを実行する
+///
+/// キーがない場合はそのまま実行する
+/// 同じキーが実行中の場合はエラー、完了済みの場合は保存した結果を返す
+pub fn run<T, F>(key: Option<&str>, command: &str, f: F) -> Result<T, String>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Result<T, String>,
+{
+    let Some(key) = key.filter(|k| !k.is_empty()) else {
+        return f();
+    };
+
+    let _guard = {
+        let mut keys = in_flight()
+            .lock()
+            .map_err(|_| "Idempotency guard is unavailable".to_string())?;
+        if !keys.insert(key.to_string()) {
+            return Err(format!("Command with idempotency key {} is already running", key));
+        }
+        InFlightGuard(key.to_string())
+    };
+
+    if let Some(result) = completed_result(key, command)? {
+        return Ok(result);
+    }
+
+    let result = f()?;
+
+    let value = serde_json::to_value(&result).map_err(|e| e.to_string())?;
+    let mut records = load()?;
+    records.insert(
+        CompletedCommand {
+            key: key.to_string(),
+            command: command.to_string(),
+            result: value,
+            completed_at: storage::now_unix(),
+        },
+        storage::now_unix(),
+    );
+    // 印刷は終わっているため、保存の失敗でコマンドを失敗扱いにしない
+    let _ = save(&records);
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(key: &str, completed_at: u64) -> CompletedCommand {
+        CompletedCommand {
+            key: key.to_string(),
+            command: "print_receipt".to_string(),
+            result: serde_json::Value::Null,
+            completed_at,
+        }
+    }
+
+    #[test]
+    fn test_insert_replaces_same_key_and_prunes_expired() {
+        let now = 100_000;
+        let mut records = CompletedCommands::default();
+        records.insert(record("old", now - RETENTION_SECS), now);
+        records.insert(record("a", now - 10), now);
+        records.insert(record("a", now), now);
+
+        let keys: Vec<&str> = records.0.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(keys, vec!["a"]);
+        assert_eq!(records.find("a").unwrap().completed_at, now);
+    }
+
+    #[test]
+    fn test_insert_keeps_newest_records() {
+        let mut records = CompletedCommands::default();
+        for i in 0..MAX_RECORDS + 5 {
+            records.insert(record(&i.to_string(), 1), 1);
+        }
+        assert_eq!(records.0.len(), MAX_RECORDS);
+        assert!(records.find("0").is_none());
+        assert!(records.find(&(MAX_RECORDS + 4).to_string()).is_some());
+    }
+
+    #[test]
+    fn test_run_without_key_always_executes() {
+        let mut calls = 0;
+        for _ in 0..2 {
+            run(None, "print_receipt", || {
+                calls += 1;
+                Ok(())
+            })
+            .unwrap();
+        }
+        assert_eq!(calls, 2);
+    }
+}
@@ -0,0 +1,46 @@
+//! コマンドの型定義の生成
+//!
+//! `specta_builder()` に登録したコマンドの引数・戻り値・エラーの型から、フロントエンドの型付きの呼び出し関数を生成する
+//! 結果は `src/lib/commands.gen.ts` にコミットしてある
+//! コマンドの引数・戻り値を変更した場合は `UPDATE_BINDINGS=1 cargo test` でファイルを更新する
+//! Android のプリンターコマンドは JavaScript Interface 経由で呼ぶため対象外
+
+use specta_typescript::{BigIntExportBehavior, Typescript};
+use std::path::PathBuf;
+
+/// コマンドの型定義（TypeScript）
+fn command_bindings() -> String {
+    let language = Typescript::default()
+        // 使わない import が残るため型検査の対象外にする
+        .header("// @ts-nocheck")
+        // 金額・時刻は Number.MAX_SAFE_INTEGER に収まる
+        .bigint(BigIntExportBehavior::Number);
+    crate::specta_builder().export_str(language).unwrap()
+}
+
+/// 型付きのエラーも型定義に含める
+#[test]
+fn test_bindings_export_error_types() {
+    let bindings = command_bindings();
+    assert!(bindings.contains("export type TerminalAuthError"));
+    assert!(bindings.contains("async getTerminalStatus()"));
+}
+
+/// コミットした型定義がコマンドと一致するか（UPDATE_BINDINGS=1 のときは書き換える）
+#[test]
+fn test_committed_bindings_are_up_to_date() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../src/lib/commands.gen.ts");
+    let actual = command_bindings();
+
+    if std::env::var("UPDATE_BINDINGS").is_ok() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("Bindings not found: {} (run with UPDATE_BINDINGS=1)", path.display()));
+    assert_eq!(
+        expected, actual,
+        "Command signatures changed (run with UPDATE_BINDINGS=1)"
+    );
+}
@@ -0,0 +1,260 @@
+//! ISDN登録情報の照会モジュール
+//!
+//! 同人誌のISDN（278・279から始まる13桁）をISDNの登録機関に照会し、商品の追加時に書名・サークル名・値段を補う
+//! 照会は端末設定で有効にした場合だけ行い、結果は端末に保存して同じISDNを何度も問い合わせない
+//! オフラインや照会に失敗した場合は保存済みの結果（古くても）を返し、なければ結果なしとして入力を続けられるようにする
+
+use crate::config;
+use crate::storage;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// 照会結果の保存ファイル名（イベントによらず端末で共通）
+const ISDN_CACHE_FILE: &str = "isdn_cache.json";
+/// 保存した結果を使う期間（これより古い場合は照会し直す）
+const CACHE_TTL_SECS: u64 = 30 * 24 * 60 * 60;
+/// 照会のタイムアウト（会場の回線が遅くても入力を待たせない）
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 照会の設定（既定は無効）
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct IsdnRegistryConfig {
+    /// 登録機関に照会する
+    pub enabled: bool,
+    /// 登録情報（XML）のURL（{isdn}: ハイフンなしのISDN）
+    pub url: String,
+}
+
+impl Default for IsdnRegistryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: "https://isdn.jp/xml/{isdn}".to_string(),
+        }
+    }
+}
+
+/// ISDNの登録情報（商品の追加に使う項目のみ）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct IsdnRecord {
+    /// ハイフンなしのISDN
+    pub isdn: String,
+    /// 書名
+    pub title: String,
+    /// 発行者（サークル）名
+    pub circle_name: Option<String>,
+    /// 値段
+    pub price: Option<u32>,
+    /// 2段目バーコード
+    pub jan2: Option<String>,
+    /// 成人向け（登録の対象年齢が18歳以上）
+    pub age_restricted: bool,
+}
+
+/// 照会結果の出どころ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum IsdnLookupSource {
+    /// 登録機関に照会した
+    Registry,
+    /// 保存済みの結果
+    Cache,
+    /// 照会できず、保存済みの結果もない（オフラインなど）
+    Unavailable,
+    /// 照会が無効
+    Disabled,
+}
+
+/// 照会結果
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct IsdnLookup {
+    pub record: Option<IsdnRecord>,
+    pub source: IsdnLookupSource,
+    /// 照会に失敗した理由（保存済みの結果を返した場合も入る）
+    pub error: Option<String>,
+}
+
+/// 保存した照会結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedRecord {
+    record: IsdnRecord,
+    fetched_at: u64,
+}
+
+fn load_cache() -> Result<HashMap<String, CachedRecord>, String> {
+    Ok(storage::load_json(ISDN_CACHE_FILE)?.unwrap_or_default())
+}
+
+/// ISDNを検証してハイフンなしの13桁にする
+pub fn normalize(isdn: &str) -> Result<String, String> {
+    let code: String = isdn.chars().filter(|c| *c != '-' && !c.is_whitespace()).collect();
+    let valid = code.len() == 13
+        && (code.starts_with("278") || code.starts_with("279"))
+        && crate::book_code::is_valid_jan(&code);
+    if !valid {
+        return Err(format!("Invalid ISDN: {}", isdn));
+    }
+    Ok(code)
+}
+
+/// 登録機関のXMLから商品の追加に使う項目を取り出す
+///
+/// 書名がない場合（未登録のISDNなど）は None
+fn parse_record(isdn: &str, xml: &str) -> Result<Option<IsdnRecord>, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut fields: HashMap<String, String> = HashMap::new();
+    let mut current: Option<String> = None;
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("Invalid ISDN registry response: {}", e))?;
+        // 文字参照・実体参照（&amp; など）は本文とは別のイベントで届く
+        let text = match event {
+            Event::Start(e) => {
+                current = Some(String::from_utf8_lossy(e.local_name().as_ref()).to_string());
+                continue;
+            }
+            Event::End(_) => {
+                current = None;
+                continue;
+            }
+            Event::Eof => break,
+            Event::Text(e) => e.decode().map_err(|e| e.to_string())?.into_owned(),
+            Event::CData(e) => e.decode().map_err(|e| e.to_string())?.into_owned(),
+            Event::GeneralRef(e) => match e.resolve_char_ref().map_err(|e| e.to_string())? {
+                Some(c) => c.to_string(),
+                None => {
+                    let name = e.decode().map_err(|e| e.to_string())?;
+                    quick_xml::escape::resolve_xml_entity(&name).unwrap_or_default().to_string()
+                }
+            },
+            _ => continue,
+        };
+        if let Some(name) = current.as_ref() {
+            fields.entry(name.clone()).or_default().push_str(&text);
+        }
+    }
+
+    let field = |name: &str| {
+        fields
+            .get(name)
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let Some(title) = field("product-name") else {
+        return Ok(None);
+    };
+    Ok(Some(IsdnRecord {
+        isdn: isdn.to_string(),
+        title,
+        circle_name: field("publisher-name"),
+        price: field("price").and_then(|p| p.replace(',', "").parse().ok()),
+        jan2: field("barcode2").map(|b| b.replace('-', "")),
+        age_restricted: field("rating_age").is_some_and(|r| r.contains("18")),
+    }))
+}
+
+/// 登録機関に照会する（見つからない場合は None）
+fn fetch(url_template: &str, isdn: &str) -> Result<Option<IsdnRecord>, String> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(LOOKUP_TIMEOUT))
+        .build()
+        .into();
+    let url = url_template.replace("{isdn}", isdn);
+    let xml = match agent.get(&url).call() {
+        Ok(mut response) => response.body_mut().read_to_string().map_err(|e| e.to_string())?,
+        Err(ureq::Error::StatusCode(404)) => return Ok(None),
+        Err(e) => return Err(format!("ISDN registry lookup failed: {}", e)),
+    };
+    parse_record(isdn, &xml)
+}
+
+/// ISDNの登録情報を取得する
+///
+/// 保存済みの結果が新しければ照会しない。照会に失敗した場合は古い結果でも返す
+pub fn lookup(isdn: &str) -> Result<IsdnLookup, String> {
+    let isdn = normalize(isdn)?;
+    let config = config::load()?.isdn_registry;
+    if !config.enabled {
+        return Ok(IsdnLookup {
+            record: None,
+            source: IsdnLookupSource::Disabled,
+            error: None,
+        });
+    }
+
+    let mut cache = load_cache()?;
+    let cached = cache.get(&isdn).cloned();
+    if let Some(cached) = cached.as_ref() {
+        if storage::now_unix().saturating_sub(cached.fetched_at) < CACHE_TTL_SECS {
+            return Ok(IsdnLookup {
+                record: Some(cached.record.clone()),
+                source: IsdnLookupSource::Cache,
+                error: None,
+            });
+        }
+    }
+
+    match fetch(&config.url, &isdn) {
+        Ok(record) => {
+            if let Some(record) = record.as_ref() {
+                cache.insert(
+                    isdn,
+                    CachedRecord {
+                        record: record.clone(),
+                        fetched_at: storage::now_unix(),
+                    },
+                );
+                // 保存できなくても照会結果は使える
+                let _ = storage::save_json(ISDN_CACHE_FILE, &cache);
+            }
+            Ok(IsdnLookup {
+                record,
+                source: IsdnLookupSource::Registry,
+                error: None,
+            })
+        }
+        Err(error) => Ok(IsdnLookup {
+            source: if cached.is_some() {
+                IsdnLookupSource::Cache
+            } else {
+                IsdnLookupSource::Unavailable
+            },
+            record: cached.map(|c| c.record),
+            error: Some(error),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_registry_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<isdn xmlns="http://isdn.jp/">
+  <isdn>2784702901978</isdn>
+  <product-name>みずの本 &amp; 設定資料集</product-name>
+  <publisher-name>サークルみず</publisher-name>
+  <price>1,000</price>
+  <barcode2>1920094010008</barcode2>
+  <rating_age>一般</rating_age>
+</isdn>"#;
+        let record = parse_record("2784702901978", xml).unwrap().unwrap();
+        assert_eq!(record.title, "みずの本 & 設定資料集");
+        assert_eq!(record.circle_name.as_deref(), Some("サークルみず"));
+        assert_eq!(record.price, Some(1000));
+        assert_eq!(record.jan2.as_deref(), Some("1920094010008"));
+        assert!(!record.age_restricted);
+
+        assert_eq!(parse_record("2784702901978", "<isdn></isdn>").unwrap(), None);
+        assert!(normalize("278-4-702901-97-8").is_ok());
+        assert!(normalize("9784101092058").is_err());
+    }
+}
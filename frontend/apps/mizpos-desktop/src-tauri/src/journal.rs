@@ -0,0 +1,1123 @@
+//! ジャーナルモジュール
+//!
+//! 売上・返品・取消・再発行などの取引記録をイベント単位の追記専用ファイルに保存する
+
+use crate::config;
+use crate::datetime;
+use crate::deposits;
+use crate::drawer::DenominationCount;
+use crate::duress;
+use crate::environment::{self, ApiEnvironment};
+use crate::event_context;
+use crate::members;
+use crate::price_book::PriceChange;
+use crate::receipt::{
+    ClosingReportData, HourlySales, PaymentMethodTotal, ReceiptData, StaffTotal, VoucherSales,
+};
+use crate::sale_limits::LimitViolation;
+use crate::session::{self, StaffShift};
+use crate::storage;
+use crate::vouchers::{self, VoucherKind};
+use chrono::{DateTime, Timelike};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+/// ジャーナルの保存ファイル名
+const JOURNAL_FILE: &str = "journal.jsonl";
+
+/// ジャーナルの記録内容
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JournalRecord {
+    /// 売上
+    Sale { receipt: Box<ReceiptData> },
+    /// レシート再発行
+    Reprint {
+        receipt_number: String,
+        /// 何回目の再発行か
+        copy_number: u32,
+        /// 承認した責任者（上限超過時のみ）
+        supervisor_id: Option<String>,
+    },
+    /// レシート控えのQRから本レシートを発行（レシート・オン・デマンド）
+    ReceiptIssued { receipt_number: String },
+    /// 決済後のレシート印刷の失敗（代替の控えを印刷する）
+    ReceiptPrintFailed {
+        receipt_number: String,
+        total: u32,
+        /// 最後のエラー
+        error: String,
+        /// 代替の控えの印刷ジョブID
+        fallback_job_id: Option<String>,
+    },
+    /// レシートをメールで送信（宛先はマスクして記録）
+    ReceiptEmailed {
+        receipt_number: String,
+        address: String,
+        /// 送信依頼のアップロードID
+        upload_id: String,
+    },
+    /// 返品（全部・一部）
+    Refund {
+        /// 元のレシート番号
+        original_receipt_number: String,
+        /// 返品明細
+        items: Vec<RefundItem>,
+        /// 返金額
+        amount: u32,
+        /// 返品理由
+        reason: String,
+    },
+    /// 取引取消（売上全体の取り消し）
+    Void {
+        receipt_number: String,
+        /// 取消額
+        amount: u32,
+        /// 取消理由
+        reason: String,
+    },
+    /// ドロワー在高の設定（釣銭の補充・回収）
+    DrawerUpdated {
+        denominations: Vec<DenominationCount>,
+    },
+    /// 明細の価格変更（責任者の承認済み。エントリーのIDを明細の印に使う）
+    PriceOverride {
+        jan: String,
+        quantity: u32,
+        original_price: u32,
+        price: u32,
+        reason_code: String,
+        supervisor_id: String,
+    },
+    /// 上限を超える売上の責任者承認
+    LimitOverride {
+        receipt_number: String,
+        violations: Vec<LimitViolation>,
+        supervisor_id: String,
+    },
+    /// ギフト券の発行
+    GiftVoucherIssued { serial: String, amount: u32 },
+    /// ギフト券の使用
+    GiftVoucherRedeemed {
+        serial: String,
+        amount: u32,
+        /// 使用した売上のレシート番号
+        receipt_number: Option<String>,
+    },
+    /// 価格改定の反映（変わった商品のみ）
+    PriceUpdated {
+        /// 価格改定の発行日時（価格表の版）
+        issued_at: u64,
+        /// 反映日時（UNIX秒）
+        effective_at: u64,
+        changes: Vec<PriceChange>,
+    },
+}
+
+/// 返品明細
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RefundItem {
+    /// 元のレシート内の明細位置
+    pub line_index: usize,
+    pub jan: String,
+    pub name: String,
+    pub quantity: u32,
+    pub amount: u32,
+}
+
+/// 返品対象の指定（フロントエンドから渡される）
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct RefundLine {
+    pub line_index: usize,
+    pub quantity: u32,
+}
+
+/// 売上の返品・取消の状況（再発行のレシートに明記する）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaleStatus {
+    /// 返品・取消なし
+    Completed,
+    /// 一部の明細を返品済み
+    PartiallyRefunded,
+    /// 全明細を返品済み
+    Refunded,
+    /// 取消済み
+    Voided,
+}
+
+/// 閉局レポート用の集計
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct JournalTotals {
+    pub sale_count: u32,
+    pub sale_amount: u64,
+    pub refund_count: u32,
+    pub refund_amount: u64,
+    pub void_count: u32,
+    pub void_amount: u64,
+    /// 売上の端数調整額の合計（値引はマイナス、売上の金額には調整後の金額で含まれる）
+    #[serde(default)]
+    pub rounding_adjustment: i64,
+    /// 売上 - 返品 - 取消
+    pub net_amount: i64,
+}
+
+/// 売上の経路
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SaleChannel {
+    /// 有人レジ
+    #[default]
+    Counter,
+    /// セルフレジ（キオスク）
+    Kiosk,
+}
+
+/// ジャーナルの1件
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct JournalEntry {
+    pub id: String,
+    /// 記録日時（UNIX秒）
+    pub recorded_at: u64,
+    /// 操作したスタッフ番号
+    pub staff_id: Option<String>,
+    /// 記録した経路
+    #[serde(default)]
+    pub channel: SaleChannel,
+    /// 脅迫PINの入力後、解除されるまでに記録したか
+    #[serde(default)]
+    pub duress: bool,
+    /// 記録したときの接続先環境
+    #[serde(default)]
+    pub environment: ApiEnvironment,
+    #[serde(flatten)]
+    pub record: JournalRecord,
+}
+
+/// 選択中イベントのジャーナルを全件取得
+pub fn entries() -> Result<Vec<JournalEntry>, String> {
+    Ok(storage::load_jsonl(&event_context::scoped(JOURNAL_FILE)?))
+}
+
+/// ジャーナルに追記
+pub fn append(staff_id: Option<&str>, record: JournalRecord) -> Result<JournalEntry, String> {
+    append_via(staff_id, SaleChannel::Counter, record)
+}
+
+/// 経路を指定してジャーナルに追記
+pub fn append_via(
+    staff_id: Option<&str>,
+    channel: SaleChannel,
+    record: JournalRecord,
+) -> Result<JournalEntry, String> {
+    let entry = JournalEntry {
+        id: Uuid::new_v4().to_string(),
+        recorded_at: storage::now_unix(),
+        staff_id: staff_id.map(|s| s.to_string()),
+        channel,
+        duress: duress::is_active()?,
+        environment: environment::current()?,
+        record,
+    };
+    storage::append_jsonl(&event_context::scoped(JOURNAL_FILE)?, &entry)?;
+    Ok(entry)
+}
+
+/// レシート番号から売上を検索
+pub fn find_sale(receipt_number: &str) -> Result<Option<ReceiptData>, String> {
+    Ok(entries()?.into_iter().find_map(|e| match e.record {
+        JournalRecord::Sale { receipt } if receipt.receipt_number == receipt_number => {
+            Some(*receipt)
+        }
+        _ => None,
+    }))
+}
+
+/// 経路を指定して売上を記録（同じレシート番号が記録済みの場合は何もしない）
+///
+/// カード情報は保存してよい範囲に絞ってから記録する
+pub fn record_sale_via(receipt: &ReceiptData, channel: SaleChannel) -> Result<(), String> {
+    if find_sale(&receipt.receipt_number)?.is_some() {
+        return Ok(());
+    }
+    let mut receipt = receipt.clone();
+    receipt.sanitize_card_details();
+    let staff_id = receipt.staff_id.clone();
+    members::record_purchase(&receipt)?;
+    append_via(
+        Some(&staff_id),
+        channel,
+        JournalRecord::Sale {
+            receipt: Box::new(receipt),
+        },
+    )?;
+    Ok(())
+}
+
+/// 保存期間を過ぎた売上のカード情報（下4桁・有効期限）を全環境・全イベントのジャーナルから消去
+///
+/// 消去した件数を返す
+pub fn purge_card_details(retention_days: u32) -> Result<usize, String> {
+    let cutoff = storage::now_unix().saturating_sub(u64::from(retention_days) * 24 * 60 * 60);
+    let mut purged = 0;
+
+    let events = ApiEnvironment::ALL
+        .into_iter()
+        .flat_map(|env| event_context::list_local_events(env).into_iter().map(move |id| (env, id)));
+    for (environment, event_id) in events {
+        let path = event_context::scoped_for(environment, &event_id, JOURNAL_FILE);
+        // 壊れた行があるジャーナルは書き換えない
+        let Ok(mut entries) = storage::load_jsonl_strict::<JournalEntry>(&path) else {
+            continue;
+        };
+        let mut changed = 0;
+
+        for entry in entries.iter_mut().filter(|e| e.recorded_at < cutoff) {
+            if let JournalRecord::Sale { receipt } = &mut entry.record {
+                if let Some(card) = receipt.card_details.as_mut().filter(|c| !c.is_purged()) {
+                    *card = card.purged();
+                    changed += 1;
+                }
+            }
+        }
+
+        if changed > 0 {
+            storage::save_jsonl(&path, &entries)?;
+            purged += changed;
+        }
+    }
+
+    Ok(purged)
+}
+
+/// レシートの再発行回数を取得
+pub fn reprint_count(receipt_number: &str) -> Result<u32, String> {
+    Ok(entries()?
+        .iter()
+        .filter(|e| {
+            matches!(&e.record, JournalRecord::Reprint { receipt_number: n, .. } if n == receipt_number)
+        })
+        .count() as u32)
+}
+
+/// レシート控えから本レシートを発行済みか
+pub fn receipt_issued(receipt_number: &str) -> Result<bool, String> {
+    Ok(entries()?.iter().any(|e| {
+        matches!(&e.record, JournalRecord::ReceiptIssued { receipt_number: n } if n == receipt_number)
+    }))
+}
+
+/// 元レシートに対する返品済み数量（明細位置ごと）と取消済みかを取得
+fn refund_state(
+    receipt_number: &str,
+    line_count: usize,
+) -> Result<(Vec<u32>, Vec<u32>, bool), String> {
+    let mut refunded_qty = vec![0; line_count];
+    let mut refunded_amount = vec![0; line_count];
+    let mut voided = false;
+
+    for entry in entries()? {
+        match entry.record {
+            JournalRecord::Refund {
+                original_receipt_number,
+                items,
+                ..
+            } if original_receipt_number == receipt_number => {
+                for item in items {
+                    if item.line_index < line_count {
+                        refunded_qty[item.line_index] += item.quantity;
+                        refunded_amount[item.line_index] += item.amount;
+                    }
+                }
+            }
+            JournalRecord::Void {
+                receipt_number: n, ..
+            } if n == receipt_number => {
+                voided = true;
+            }
+            _ => {}
+        }
+    }
+
+    Ok((refunded_qty, refunded_amount, voided))
+}
+
+/// 売上の返品・取消の状況を取得
+pub fn sale_status(sale: &ReceiptData) -> Result<SaleStatus, String> {
+    let (refunded_qty, _, voided) = refund_state(&sale.receipt_number, sale.items.len())?;
+    if voided {
+        return Ok(SaleStatus::Voided);
+    }
+    if refunded_qty.iter().all(|&q| q == 0) {
+        return Ok(SaleStatus::Completed);
+    }
+    let fully_refunded = sale
+        .items
+        .iter()
+        .zip(&refunded_qty)
+        .all(|(item, &refunded)| refunded >= item.quantity);
+    Ok(if fully_refunded {
+        SaleStatus::Refunded
+    } else {
+        SaleStatus::PartiallyRefunded
+    })
+}
+
+/// 返品を記録
+///
+/// lines を省略した場合は未返品の全明細を返品する
+pub fn record_refund(
+    original_receipt_number: &str,
+    lines: Option<Vec<RefundLine>>,
+    reason: &str,
+    staff_id: &str,
+) -> Result<JournalEntry, String> {
+    let sale = find_sale(original_receipt_number)?
+        .ok_or_else(|| format!("Receipt not found: {}", original_receipt_number))?;
+    let (refunded_qty, refunded_amount, voided) =
+        refund_state(original_receipt_number, sale.items.len())?;
+    if voided {
+        return Err("Receipt has been voided".to_string());
+    }
+    // 内金・残金の売上は明細の金額を受け取っていないため、明細単位では返品しない
+    if sale.deposit.is_some() {
+        return Err("Deposit sales cannot be refunded by line; void the sale instead".to_string());
+    }
+
+    let lines = lines.unwrap_or_else(|| {
+        (0..sale.items.len())
+            .map(|i| RefundLine {
+                line_index: i,
+                quantity: sale.items[i].quantity - refunded_qty[i],
+            })
+            .filter(|l| l.quantity > 0)
+            .collect()
+    });
+    if lines.is_empty() {
+        return Err("Nothing to refund".to_string());
+    }
+
+    let mut items = Vec::with_capacity(lines.len());
+    for line in &lines {
+        let original = sale
+            .items
+            .get(line.line_index)
+            .ok_or_else(|| format!("Invalid line index: {}", line.line_index))?;
+        let remaining = original.quantity - refunded_qty[line.line_index];
+        if line.quantity == 0 || line.quantity > remaining {
+            return Err(format!(
+                "Invalid refund quantity for {}: {} (remaining {})",
+                original.name, line.quantity, remaining
+            ));
+        }
+
+        // 残数量をすべて返品する場合は端数も含めて残額を返金する
+        let amount = if line.quantity == remaining {
+            original.price - refunded_amount[line.line_index]
+        } else {
+            original.price / original.quantity.max(1) * line.quantity
+        };
+
+        items.push(RefundItem {
+            line_index: line.line_index,
+            jan: original.jan.clone(),
+            name: original.name.clone(),
+            quantity: line.quantity,
+            amount,
+        });
+    }
+
+    let amount = items.iter().map(|i| i.amount).sum();
+    append(
+        Some(staff_id),
+        JournalRecord::Refund {
+            original_receipt_number: original_receipt_number.to_string(),
+            items,
+            amount,
+            reason: reason.to_string(),
+        },
+    )
+}
+
+/// 返品伝票の印字内容
+///
+/// 店舗情報・明細のサークル名は元の売上のレシートを使い、返品の記録を重ねる
+#[derive(Debug, Clone)]
+pub struct RefundReceipt {
+    /// 元の売上のレシート
+    pub receipt: ReceiptData,
+    /// 返品記録のID
+    pub refund_id: String,
+    /// 返品を記録した日時（UNIX秒）
+    pub refunded_at: u64,
+    /// 返品を受け付けたスタッフ番号
+    pub staff_id: Option<String>,
+    /// 返品明細
+    pub items: Vec<RefundItem>,
+    /// 返金額
+    pub amount: u32,
+    /// 返品理由
+    pub reason: String,
+}
+
+/// 返品記録のIDから返品伝票の印字内容を作る
+pub fn refund_receipt(refund_id: &str) -> Result<RefundReceipt, String> {
+    let entry = entries()?
+        .into_iter()
+        .find(|e| e.id == refund_id)
+        .ok_or_else(|| format!("Refund not found: {}", refund_id))?;
+    let JournalRecord::Refund {
+        original_receipt_number,
+        items,
+        amount,
+        reason,
+    } = entry.record
+    else {
+        return Err(format!("Not a refund: {}", refund_id));
+    };
+    let receipt = find_sale(&original_receipt_number)?
+        .ok_or_else(|| format!("Receipt not found: {}", original_receipt_number))?;
+    Ok(RefundReceipt {
+        receipt,
+        refund_id: entry.id,
+        refunded_at: entry.recorded_at,
+        staff_id: entry.staff_id,
+        items,
+        amount,
+        reason,
+    })
+}
+
+/// 取引取消を記録（返品済みの取引は取消できない）
+pub fn record_void(receipt_number: &str, reason: &str, staff_id: &str) -> Result<JournalEntry, String> {
+    let sale = find_sale(receipt_number)?
+        .ok_or_else(|| format!("Receipt not found: {}", receipt_number))?;
+    let (refunded_qty, _, voided) = refund_state(receipt_number, sale.items.len())?;
+    if voided {
+        return Err("Receipt has already been voided".to_string());
+    }
+    if refunded_qty.iter().any(|&q| q > 0) {
+        return Err("Receipt has refunds and cannot be voided".to_string());
+    }
+    let balance_paid = sale.deposit.as_ref().is_some_and(|d| !d.is_balance_payment())
+        && deposits::balance_sale(receipt_number)?.is_some();
+    if balance_paid {
+        return Err("Balance has been paid; void the balance sale first".to_string());
+    }
+
+    append(
+        Some(staff_id),
+        JournalRecord::Void {
+            receipt_number: receipt_number.to_string(),
+            amount: sale.total,
+            reason: reason.to_string(),
+        },
+    )
+}
+
+/// 選択中イベントの売上・返品・取消を集計
+pub fn totals() -> Result<JournalTotals, String> {
+    let mut totals = JournalTotals::default();
+    for entry in entries()? {
+        match entry.record {
+            JournalRecord::Sale { receipt } => {
+                totals.sale_count += 1;
+                totals.sale_amount += receipt.total as u64;
+                totals.rounding_adjustment += i64::from(receipt.rounding_adjustment);
+            }
+            JournalRecord::Refund { amount, .. } => {
+                totals.refund_count += 1;
+                totals.refund_amount += amount as u64;
+            }
+            JournalRecord::Void { amount, .. } => {
+                totals.void_count += 1;
+                totals.void_amount += amount as u64;
+            }
+            JournalRecord::Reprint { .. }
+            | JournalRecord::ReceiptIssued { .. }
+            | JournalRecord::ReceiptPrintFailed { .. }
+            | JournalRecord::ReceiptEmailed { .. }
+            | JournalRecord::DrawerUpdated { .. }
+            | JournalRecord::PriceOverride { .. }
+            | JournalRecord::LimitOverride { .. }
+            | JournalRecord::GiftVoucherIssued { .. }
+            | JournalRecord::GiftVoucherRedeemed { .. }
+            | JournalRecord::PriceUpdated { .. } => {}
+        }
+    }
+    totals.net_amount =
+        totals.sale_amount as i64 - totals.refund_amount as i64 - totals.void_amount as i64;
+    Ok(totals)
+}
+
+/// 売上の支払方法ごとの受取額
+///
+/// 支払額の合計が売上を超える分は釣り銭として現金から差し引く
+fn received_amounts(receipt: &ReceiptData) -> Vec<(&str, u64)> {
+    let paid: u64 = receipt.payments.iter().map(|p| p.amount as u64).sum();
+    let mut change = paid.saturating_sub(receipt.total as u64);
+    receipt
+        .payments
+        .iter()
+        .map(|p| {
+            let mut amount = p.amount as u64;
+            if p.is_cash() {
+                let deducted = change.min(amount);
+                amount -= deducted;
+                change -= deducted;
+            }
+            (p.method.as_str(), amount)
+        })
+        .collect()
+}
+
+/// ジャーナルを支払方法別に集計（最初に現れた順）
+fn payment_breakdown_of(entries: &[JournalEntry]) -> Vec<PaymentMethodTotal> {
+    let mut methods: Vec<PaymentMethodTotal> = Vec::new();
+    // レシート番号ごとの、最も多く支払われた方法
+    let mut primary: HashMap<&str, &str> = HashMap::new();
+
+    fn slot<'a>(methods: &'a mut Vec<PaymentMethodTotal>, method: &str) -> &'a mut PaymentMethodTotal {
+        match methods.iter().position(|m| m.method == method) {
+            Some(i) => &mut methods[i],
+            None => {
+                methods.push(PaymentMethodTotal {
+                    method: method.to_string(),
+                    ..Default::default()
+                });
+                methods.last_mut().unwrap()
+            }
+        }
+    }
+
+    for entry in entries {
+        let (receipt_number, amount) = match &entry.record {
+            JournalRecord::Sale { receipt } => {
+                let received = received_amounts(receipt);
+                for (method, amount) in &received {
+                    let total = slot(&mut methods, method);
+                    total.count += 1;
+                    total.amount += amount;
+                }
+                if let Some((method, _)) = received.iter().rev().max_by_key(|(_, a)| *a) {
+                    primary.insert(receipt.receipt_number.as_str(), method);
+                }
+                continue;
+            }
+            JournalRecord::Refund {
+                original_receipt_number,
+                amount,
+                ..
+            } => (original_receipt_number, amount),
+            JournalRecord::Void {
+                receipt_number,
+                amount,
+                ..
+            } => (receipt_number, amount),
+            JournalRecord::Reprint { .. }
+            | JournalRecord::ReceiptIssued { .. }
+            | JournalRecord::ReceiptPrintFailed { .. }
+            | JournalRecord::ReceiptEmailed { .. }
+            | JournalRecord::DrawerUpdated { .. }
+            | JournalRecord::PriceOverride { .. }
+            | JournalRecord::LimitOverride { .. }
+            | JournalRecord::GiftVoucherIssued { .. }
+            | JournalRecord::GiftVoucherRedeemed { .. }
+            | JournalRecord::PriceUpdated { .. } => continue,
+        };
+        if let Some(method) = primary.get(receipt_number.as_str()) {
+            let total = slot(&mut methods, method);
+            total.refund_count += 1;
+            total.refund_amount += *amount as u64;
+        }
+    }
+    methods
+}
+
+/// 選択中イベントの売上を支払方法別に集計
+pub fn payment_breakdown() -> Result<Vec<PaymentMethodTotal>, String> {
+    Ok(payment_breakdown_of(&entries()?))
+}
+
+/// ジャーナルの売上を日付・時間帯別に集計
+///
+/// 日ごとに最初と最後の売上の間の時間帯は、売上がなくても0件として含める
+fn hourly_sales_of(entries: &[JournalEntry], tz: Tz) -> Vec<HourlySales> {
+    let mut buckets: BTreeMap<(String, u32), (u32, u64)> = BTreeMap::new();
+    for entry in entries {
+        let JournalRecord::Sale { receipt } = &entry.record else {
+            continue;
+        };
+        let Some(at) = DateTime::from_timestamp(entry.recorded_at as i64, 0) else {
+            continue;
+        };
+        let at = at.with_timezone(&tz);
+        let bucket = buckets
+            .entry((at.format("%Y-%m-%d").to_string(), at.hour()))
+            .or_default();
+        bucket.0 += 1;
+        bucket.1 += receipt.total as u64;
+    }
+
+    let mut hourly: Vec<HourlySales> = Vec::new();
+    for ((date, hour), (count, amount)) in buckets {
+        // 同じ日の直前の時間帯との間を埋める
+        if let Some(last) = hourly.last().filter(|h| h.date == date) {
+            for gap in last.hour + 1..hour {
+                hourly.push(HourlySales {
+                    date: date.clone(),
+                    hour: gap,
+                    count: 0,
+                    amount: 0,
+                });
+            }
+        }
+        hourly.push(HourlySales {
+            date,
+            hour,
+            count,
+            amount,
+        });
+    }
+    hourly
+}
+
+/// 選択中イベントの売上を時間帯別に集計（設定のタイムゾーン）
+pub fn hourly_sales() -> Result<Vec<HourlySales>, String> {
+    let tz = datetime::timezone(&config::load()?.timezone);
+    Ok(hourly_sales_of(&entries()?, tz))
+}
+
+/// ジャーナルを担当者別に集計
+///
+/// 交代履歴にある担当者は操作がなくても含め、表示名を補う
+fn staff_totals_of(entries: &[JournalEntry], shifts: &[StaffShift]) -> Vec<StaffTotal> {
+    let mut staff: Vec<StaffTotal> = Vec::new();
+    fn slot<'a>(staff: &'a mut Vec<StaffTotal>, staff_id: &str) -> &'a mut StaffTotal {
+        match staff.iter().position(|s| s.staff_id == staff_id) {
+            Some(i) => &mut staff[i],
+            None => {
+                staff.push(StaffTotal {
+                    staff_id: staff_id.to_string(),
+                    ..Default::default()
+                });
+                staff.last_mut().unwrap()
+            }
+        }
+    }
+
+    for shift in shifts {
+        let total = slot(&mut staff, &shift.staff_id);
+        if shift.staff_name.is_some() {
+            total.staff_name = shift.staff_name.clone();
+        }
+    }
+    for entry in entries {
+        let Some(staff_id) = entry.staff_id.as_deref() else {
+            continue;
+        };
+        match &entry.record {
+            JournalRecord::Sale { receipt } => {
+                let total = slot(&mut staff, staff_id);
+                total.sale_count += 1;
+                total.sale_amount += receipt.total as u64;
+            }
+            JournalRecord::Refund { amount, .. } => {
+                let total = slot(&mut staff, staff_id);
+                total.refund_count += 1;
+                total.refund_amount += *amount as u64;
+            }
+            JournalRecord::Void { amount, .. } => {
+                let total = slot(&mut staff, staff_id);
+                total.void_count += 1;
+                total.void_amount += *amount as u64;
+            }
+            JournalRecord::DrawerUpdated { .. } => {
+                slot(&mut staff, staff_id).drawer_count += 1;
+            }
+            JournalRecord::Reprint { .. }
+            | JournalRecord::ReceiptIssued { .. }
+            | JournalRecord::ReceiptPrintFailed { .. }
+            | JournalRecord::ReceiptEmailed { .. }
+            | JournalRecord::PriceOverride { .. }
+            | JournalRecord::LimitOverride { .. }
+            | JournalRecord::GiftVoucherIssued { .. }
+            | JournalRecord::GiftVoucherRedeemed { .. }
+            | JournalRecord::PriceUpdated { .. } => {}
+        }
+    }
+    staff
+}
+
+/// 選択中イベントの取引を担当者別に集計
+pub fn staff_totals() -> Result<Vec<StaffTotal>, String> {
+    Ok(staff_totals_of(&entries()?, &session::current()?.shifts))
+}
+
+/// 売上に使われた商品券を種別ごとに集計（カタログの順）
+fn voucher_sales_of(entries: &[JournalEntry], catalog: &[VoucherKind]) -> Vec<VoucherSales> {
+    let mut sales: Vec<VoucherSales> = catalog
+        .iter()
+        .map(|k| VoucherSales {
+            code: k.code.clone(),
+            name: k.name.clone(),
+            count: 0,
+            amount: 0,
+        })
+        .collect();
+    for entry in entries {
+        let JournalRecord::Sale { receipt } = &entry.record else {
+            continue;
+        };
+        for (method, amount) in received_amounts(receipt) {
+            if let Some(kind) = vouchers::find(catalog, method) {
+                if let Some(total) = sales.iter_mut().find(|s| s.code == kind.code) {
+                    total.count += 1;
+                    total.amount += amount;
+                }
+            }
+        }
+    }
+    sales.retain(|s| s.count > 0);
+    sales
+}
+
+/// 選択中イベントの売上に使われた商品券を集計
+pub fn voucher_sales() -> Result<Vec<VoucherSales>, String> {
+    Ok(voucher_sales_of(&entries()?, &config::load()?.voucher_catalog))
+}
+
+/// 閉局レポートにジャーナルからの集計を補い、商品券の実査をカタログで検証する（印刷・アップロードの前に呼ぶ）
+pub fn complete_closing_report(report: &mut ClosingReportData) -> Result<(), String> {
+    vouchers::resolve_counts(&config::load()?.voucher_catalog, &mut report.vouchers)?;
+    if report.voucher_sales.is_empty() {
+        report.voucher_sales = voucher_sales()?;
+    }
+    if report.payment_methods.is_empty() {
+        report.payment_methods = payment_breakdown()?;
+    }
+    if report.hourly_sales.is_empty() {
+        report.hourly_sales = hourly_sales()?;
+    }
+    if report.staff_totals.is_empty() {
+        report.staff_totals = staff_totals()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipt::test_support;
+    use serde_json::json;
+
+    fn entry(record: JournalRecord) -> JournalEntry {
+        JournalEntry {
+            id: Uuid::new_v4().to_string(),
+            recorded_at: 0,
+            staff_id: None,
+            channel: SaleChannel::Counter,
+            duress: false,
+            environment: ApiEnvironment::Production,
+            record,
+        }
+    }
+
+    fn sale(receipt_number: &str, total: u32, payments: serde_json::Value) -> JournalEntry {
+        let receipt = test_support::receipt(receipt_number)
+            .no_items()
+            .with(json!({ "total": total, "payments": payments, "tax_amount": 0 }))
+            .build();
+        entry(JournalRecord::Sale { receipt: Box::new(receipt) })
+    }
+
+    #[test]
+    fn test_payment_breakdown_deducts_change_from_cash() {
+        let entries = vec![
+            sale("R-1", 1500, json!([{ "method": "現金", "amount": 2000 }])),
+            sale("R-2", 3000, json!([{ "method": "クレジットカード", "amount": 3000 }])),
+            sale(
+                "R-3",
+                2500,
+                json!([
+                    { "method": "商品券", "amount": 1000 },
+                    { "method": "現金", "amount": 2000 }
+                ]),
+            ),
+        ];
+        let breakdown = payment_breakdown_of(&entries);
+        let summary: Vec<_> = breakdown.iter().map(|m| (m.method.as_str(), m.count, m.amount)).collect();
+        assert_eq!(
+            summary,
+            vec![("現金", 2, 3000), ("クレジットカード", 1, 3000), ("商品券", 1, 1000)]
+        );
+    }
+
+    #[test]
+    fn test_payment_breakdown_attributes_refunds_to_primary_method() {
+        let entries = vec![
+            sale(
+                "R-1",
+                3000,
+                json!([
+                    { "method": "現金", "amount": 1000 },
+                    { "method": "QR決済", "amount": 2000 }
+                ]),
+            ),
+            entry(JournalRecord::Refund {
+                original_receipt_number: "R-1".to_string(),
+                items: vec![],
+                amount: 1000,
+                reason: "破損".to_string(),
+            }),
+            entry(JournalRecord::Void {
+                receipt_number: "R-unknown".to_string(),
+                amount: 500,
+                reason: "誤打".to_string(),
+            }),
+        ];
+        let breakdown = payment_breakdown_of(&entries);
+        assert_eq!(breakdown[0].refund_count, 0);
+        assert_eq!(breakdown[1].method, "QR決済");
+        assert_eq!(breakdown[1].refund_count, 1);
+        assert_eq!(breakdown[1].refund_amount, 1000);
+    }
+
+    #[test]
+    fn test_voucher_sales_match_code_or_name() {
+        let entries = vec![
+            sale(
+                "R-1",
+                1500,
+                json!([
+                    { "method": "百貨店商品券", "amount": 1000 },
+                    { "method": "現金", "amount": 1000 }
+                ]),
+            ),
+            sale("R-2", 800, json!([{ "method": "voucher_department", "amount": 800 }])),
+            sale("R-3", 500, json!([{ "method": "現金", "amount": 500 }])),
+        ];
+        let sales = voucher_sales_of(&entries, &vouchers::default_catalog());
+        assert_eq!(
+            sales,
+            vec![VoucherSales {
+                code: "voucher_department".to_string(),
+                name: "百貨店商品券".to_string(),
+                count: 2,
+                amount: 1800,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_staff_totals_include_shifts_and_drawer_updates() {
+        let by = |staff_id: &str, entry: JournalEntry| JournalEntry {
+            staff_id: Some(staff_id.to_string()),
+            ..entry
+        };
+        let entries = vec![
+            by("S001", entry(JournalRecord::DrawerUpdated { denominations: vec![] })),
+            by("S001", sale("R-1", 1000, json!([{ "method": "現金", "amount": 1000 }]))),
+            by("S002", sale("R-2", 2000, json!([{ "method": "現金", "amount": 2000 }]))),
+            by(
+                "S002",
+                entry(JournalRecord::Void {
+                    receipt_number: "R-2".to_string(),
+                    amount: 2000,
+                    reason: "誤打".to_string(),
+                }),
+            ),
+        ];
+        let shifts = vec![
+            StaffShift {
+                staff_id: "S002".to_string(),
+                staff_name: Some("みず".to_string()),
+                started_at: 0,
+            },
+            StaffShift {
+                staff_id: "S003".to_string(),
+                staff_name: None,
+                started_at: 0,
+            },
+        ];
+        let staff = staff_totals_of(&entries, &shifts);
+        let summary: Vec<_> = staff
+            .iter()
+            .map(|s| {
+                (
+                    s.staff_id.as_str(),
+                    s.sale_count,
+                    s.refund_count,
+                    s.void_count,
+                    s.void_amount,
+                    s.drawer_count,
+                )
+            })
+            .collect();
+        // 取消は返品と分けて数える
+        assert_eq!(
+            summary,
+            vec![("S002", 1, 0, 1, 2000, 0), ("S003", 0, 0, 0, 0, 0), ("S001", 1, 0, 0, 0, 1)]
+        );
+        assert_eq!(staff[0].staff_name.as_deref(), Some("みず"));
+    }
+
+    #[test]
+    fn test_hourly_sales_fills_gaps_within_each_day() {
+        let at = |recorded_at, total| JournalEntry {
+            recorded_at,
+            ..sale("R", total, json!([{ "method": "現金", "amount": total }]))
+        };
+        // 2025-12-30 10:15, 10:45, 12:05 JST と 2025-12-31 09:30 JST
+        let entries = vec![
+            at(1767057300, 1000),
+            at(1767059100, 500),
+            at(1767063900, 2000),
+            at(1767141000, 800),
+        ];
+        let hourly: Vec<_> = hourly_sales_of(&entries, chrono_tz::Asia::Tokyo)
+            .into_iter()
+            .map(|h| (h.date, h.hour, h.count, h.amount))
+            .collect();
+        assert_eq!(
+            hourly,
+            vec![
+                ("2025-12-30".to_string(), 10, 2, 1500),
+                ("2025-12-30".to_string(), 11, 0, 0),
+                ("2025-12-30".to_string(), 12, 1, 2000),
+                ("2025-12-31".to_string(), 9, 1, 800),
+            ]
+        );
+    }
+
+    /// 明細（数量・値段）を指定した売上をジャーナルに記録
+    fn record_goods_sale(receipt_number: &str, lines: &[(u32, u32)]) {
+        let receipt = lines
+            .iter()
+            .enumerate()
+            .fold(test_support::receipt(receipt_number).no_items(), |receipt, (i, &(quantity, price))| {
+                receipt.item(json!({
+                    "name": format!("商品{}", i + 1),
+                    "jan": format!("490000000000{}", i),
+                    "is_book": false,
+                    "quantity": quantity,
+                    "price": price,
+                }))
+            })
+            .with(json!({ "tax_amount": 0 }))
+            .build();
+        append(Some("S001"), JournalRecord::Sale { receipt: Box::new(receipt) }).unwrap();
+    }
+
+    #[test]
+    fn test_sale_status_follows_refunds_and_voids() {
+        let _dir = crate::storage::test_support::TempDataDir::new();
+        record_goods_sale("R-1", &[(2, 1000), (1, 500)]);
+        record_goods_sale("R-2", &[(1, 700)]);
+        let status = |n: &str| sale_status(&find_sale(n).unwrap().unwrap()).unwrap();
+
+        assert_eq!(status("R-1"), SaleStatus::Completed);
+        record_refund(
+            "R-1",
+            Some(vec![RefundLine { line_index: 0, quantity: 1 }]),
+            "落丁",
+            "S001",
+        )
+        .unwrap();
+        assert_eq!(status("R-1"), SaleStatus::PartiallyRefunded);
+        record_refund("R-1", None, "落丁", "S001").unwrap();
+        assert_eq!(status("R-1"), SaleStatus::Refunded);
+
+        record_void("R-2", "誤操作", "S001").unwrap();
+        assert_eq!(status("R-2"), SaleStatus::Voided);
+    }
+
+    #[test]
+    fn test_partial_refund_splits_remaining_amount() {
+        let _dir = crate::storage::test_support::TempDataDir::new();
+        // 3個で1000円（単価は割り切れない）
+        record_goods_sale("R-1", &[(3, 1000), (1, 500)]);
+
+        let line = |quantity| Some(vec![RefundLine { line_index: 0, quantity }]);
+        let first = record_refund("R-1", line(1), "落丁", "S001").unwrap();
+        let JournalRecord::Refund { amount, .. } = first.record else {
+            panic!("not a refund");
+        };
+        assert_eq!(amount, 333);
+
+        // 残りをすべて返品すると端数も含めて返金する
+        let second = record_refund("R-1", line(2), "落丁", "S001").unwrap();
+        let JournalRecord::Refund { amount, .. } = second.record else {
+            panic!("not a refund");
+        };
+        assert_eq!(amount, 667);
+
+        let totals = totals().unwrap();
+        assert_eq!((totals.refund_count, totals.refund_amount), (2, 1000));
+
+        // 返品伝票は元の売上と返品ごとの明細・返金額を持つ
+        let refund = refund_receipt(&second.id).unwrap();
+        assert_eq!(refund.receipt.receipt_number, "R-1");
+        assert_eq!((refund.items[0].quantity, refund.amount), (2, 667));
+        assert!(refund_receipt(&"x".repeat(36)).is_err());
+    }
+
+    #[test]
+    fn test_refund_cannot_exceed_sold_quantity() {
+        let _dir = crate::storage::test_support::TempDataDir::new();
+        record_goods_sale("R-1", &[(2, 1000)]);
+
+        let line = |quantity| Some(vec![RefundLine { line_index: 0, quantity }]);
+        assert!(record_refund("R-1", line(3), "落丁", "S001").is_err());
+        record_refund("R-1", None, "落丁", "S001").unwrap();
+
+        // 二重の返品は受け付けない
+        assert!(record_refund("R-1", line(1), "落丁", "S001").is_err());
+        assert_eq!(record_refund("R-1", None, "落丁", "S001").unwrap_err(), "Nothing to refund");
+        assert!(record_refund(
+            "R-1",
+            Some(vec![RefundLine { line_index: 5, quantity: 1 }]),
+            "落丁",
+            "S001"
+        )
+        .is_err());
+        assert_eq!(totals().unwrap().refund_amount, 1000);
+    }
+
+    #[test]
+    fn test_void_and_refund_exclude_each_other() {
+        let _dir = crate::storage::test_support::TempDataDir::new();
+        record_goods_sale("R-1", &[(2, 1000)]);
+        record_goods_sale("R-2", &[(1, 500)]);
+
+        // 返品済みの取引は取り消せない
+        record_refund("R-1", Some(vec![RefundLine { line_index: 0, quantity: 1 }]), "落丁", "S001")
+            .unwrap();
+        assert!(record_void("R-1", "誤操作", "S001").is_err());
+
+        // 取消済みの取引は返品・再取消できない
+        record_void("R-2", "誤操作", "S001").unwrap();
+        assert!(record_refund("R-2", None, "落丁", "S001").is_err());
+        assert!(record_void("R-2", "誤操作", "S001").is_err());
+
+        let totals = totals().unwrap();
+        assert_eq!((totals.refund_count, totals.void_count, totals.void_amount), (1, 1, 500));
+    }
+
+    #[test]
+    fn test_receipt_issued_from_stub_once() {
+        let _dir = crate::storage::test_support::TempDataDir::new();
+        record_goods_sale("R-1", &[(1, 1000)]);
+        assert!(!receipt_issued("R-1").unwrap());
+
+        append(Some("S001"), JournalRecord::ReceiptIssued { receipt_number: "R-1".to_string() })
+            .unwrap();
+        assert!(receipt_issued("R-1").unwrap());
+        assert!(!receipt_issued("R-2").unwrap());
+        // 本レシートの発行は売上・再発行回数に数えない
+        assert_eq!(totals().unwrap().sale_count, 1);
+        assert_eq!(reprint_count("R-1").unwrap(), 0);
+    }
+}
@@ -1,7 +1,14 @@
 #![allow(dead_code)]
 
+use crate::book_code;
+use crate::paper_usage::{self, PaperLength, PaperMeter};
+use crate::printer_profile::{MediaMode, QrCommandSet};
+use crate::printer_status::{ErrorStatus, OfflineStatus, PaperStatus, PrinterStatus};
+use crate::raster::{Bitmap, RasterCanvas};
 use encoding_rs::SHIFT_JIS;
 use escpos::driver::Driver;
+use serde::{Deserialize, Serialize};
+use specta::Type;
 
 pub const HW_INIT: &[u8] = b"\x1b\x40";
 pub const CTL_LF: &[u8] = b"\x0a";
@@ -30,6 +37,33 @@ pub const QR_SIZE_PREFIX: &[u8] = b"\x1d\x28\x6b\x03\x00\x31\x43"; // size comma
 pub const QR_ERROR_L: &[u8] = b"\x1d\x28\x6b\x03\x00\x31\x45\x30"; // Error correction L
 pub const QR_ERROR_M: &[u8] = b"\x1d\x28\x6b\x03\x00\x31\x45\x31"; // Error correction M
 pub const QR_PRINT: &[u8] = b"\x1d\x28\x6b\x03\x00\x31\x51\x30"; // Print QR code
+// QR Code function codes (EPSON standard, see QrCommandSet for other vendors)
+const QR_FN_SIZE: u8 = 0x43;
+const QR_FN_ERROR: u8 = 0x45;
+const QR_FN_STORE: u8 = 0x50;
+const QR_FN_PRINT: u8 = 0x51;
+
+// Barcode commands
+pub const BARCODE_HRI_BELOW: &[u8] = b"\x1d\x48\x02"; // HRI characters below barcode
+pub const BARCODE_HRI_PREFIX: &[u8] = b"\x1d\x48"; // GS H n
+pub const BARCODE_HEIGHT_PREFIX: &[u8] = b"\x1d\x68"; // GS h n
+pub const BARCODE_WIDTH_PREFIX: &[u8] = b"\x1d\x77"; // GS w n
+pub const BARCODE_CODE128: u8 = 73; // GS k m=73
+pub const BARCODE_EAN13: u8 = 67; // GS k m=67 (JAN-13)
+pub const BARCODE_EAN8: u8 = 68; // GS k m=68 (JAN-8)
+
+// Label / black mark positioning (FS ( L)
+const LABEL_FN_CUT_POSITION: u8 = 0x42; // Feed paper to the cutting position
+const LABEL_FN_PRINT_START: u8 = 0x43; // Feed paper to the print starting position
+const LABEL_CURRENT: u8 = 0x30; // m=48: position of the current label
+const LABEL_NEXT: u8 = 0x31; // m=49: position of the next label
+
+// Real-time status
+pub const DLE_EOT_PRINTER: &[u8] = b"\x10\x04\x01"; // DLE EOT 1: printer status (online/offline)
+pub const DLE_EOT_OFFLINE: &[u8] = b"\x10\x04\x02"; // DLE EOT 2: offline cause (cover open, paper end)
+pub const DLE_EOT_ERROR: &[u8] = b"\x10\x04\x03"; // DLE EOT 3: error cause (autocutter, unrecoverable)
+pub const DLE_EOT_PAPER: &[u8] = b"\x10\x04\x04"; // DLE EOT 4: paper roll sensor status
+pub const GS_R_PAPER: &[u8] = b"\x1d\x72\x01"; // GS r 1: transmit paper sensor status
 
 pub const JP_CHARCODE_JIS: &[u8] = b"\x1b\x74\x02";
 pub const JP_KANJI_SELECT: &[u8] = b"\x1c\x43\x01";
@@ -44,6 +78,13 @@ pub enum PaperWidth {
 }
 
 impl PaperWidth {
+    pub fn mm(&self) -> u8 {
+        match self {
+            PaperWidth::Mm58 => 58,
+            PaperWidth::Mm80 => 80,
+        }
+    }
+
     pub fn dots(&self) -> u16 {
         match self {
             PaperWidth::Mm58 => 384,
@@ -73,7 +114,7 @@ impl Default for PaperWidth {
 }
 
 /// 文字の表示幅を計算（全角=2, 半角=1）
-fn char_width(c: char) -> usize {
+pub(crate) fn char_width(c: char) -> usize {
     let code = c as u32;
     // ASCII printable characters (0x20-0x7E)
     if (0x0020..=0x007E).contains(&code) {
@@ -88,10 +129,58 @@ fn char_width(c: char) -> usize {
 }
 
 /// 文字列の表示幅を計算
-fn str_width(s: &str) -> usize {
+pub(crate) fn str_width(s: &str) -> usize {
     s.chars().map(char_width).sum()
 }
 
+/// バーコードの下・上に印刷する読み取り用の数字（HRI）の位置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum HriPosition {
+    None,
+    Above,
+    #[default]
+    Below,
+    Both,
+}
+
+impl HriPosition {
+    /// GS H n の n
+    fn command_value(self) -> u8 {
+        match self {
+            HriPosition::None => 0,
+            HriPosition::Above => 1,
+            HriPosition::Below => 2,
+            HriPosition::Both => 3,
+        }
+    }
+}
+
+/// 印刷するバーコードの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum BarcodeSymbology {
+    /// EAN-13（JAN-13）
+    Ean13,
+    /// EAN-8（JAN-8）
+    Ean8,
+    /// CODE128（コードセットB）
+    Code128,
+}
+
+/// バーコードの高さ・幅・HRI の位置（省略した項目は既定値）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct BarcodeOptions {
+    /// 高さ（ドット、既定: 80）
+    #[serde(default)]
+    pub height: Option<u8>,
+    /// モジュール幅 2〜6（既定: 2）
+    #[serde(default)]
+    pub width: Option<u8>,
+    #[serde(default)]
+    pub hri: HriPosition,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Align {
     Left,
@@ -166,9 +255,22 @@ impl TextStyle {
     }
 }
 
+/// ラベルの位置合わせ用の FS ( L コマンド
+fn label_command(function: u8, position: u8) -> [u8; 7] {
+    [0x1c, 0x28, 0x4c, 0x02, 0x00, function, position]
+}
+
 pub struct JpPrinter<D: Driver> {
     driver: D,
     paper_width: PaperWidth,
+    /// ラスターモード時の描画先（Noneならテキストモード）
+    raster: Option<RasterCanvas>,
+    /// QRコードのコマンド体系
+    qr_command_set: QrCommandSet,
+    /// 用紙の種類
+    media_mode: MediaMode,
+    /// 用紙使用量の計測（有効な場合は破棄するときに使用量を記録する）
+    paper_meter: Option<PaperMeter>,
 }
 
 impl<D: Driver> JpPrinter<D> {
@@ -176,6 +278,10 @@ impl<D: Driver> JpPrinter<D> {
         Self {
             driver,
             paper_width: PaperWidth::default(),
+            raster: None,
+            qr_command_set: QrCommandSet::default(),
+            media_mode: MediaMode::default(),
+            paper_meter: None,
         }
     }
 
@@ -183,6 +289,10 @@ impl<D: Driver> JpPrinter<D> {
         Self {
             driver,
             paper_width,
+            raster: None,
+            qr_command_set: QrCommandSet::default(),
+            media_mode: MediaMode::default(),
+            paper_meter: None,
         }
     }
 
@@ -194,12 +304,132 @@ impl<D: Driver> JpPrinter<D> {
         self.paper_width.chars()
     }
 
-    fn raw(&mut self, data: &[u8]) -> Result<(), String> {
+    /// ラスターモードに切り替え
+    /// 以降のテキストはフォントで描画し、QR・カットなどのコマンド送信前にまとめて画像で印刷する
+    pub fn enable_raster(&mut self, font: fontdue::Font) {
+        self.raster = Some(RasterCanvas::new(font, self.paper_width.dots() as usize));
+    }
+
+    /// QRコードのコマンド体系を設定
+    pub fn set_qr_command_set(&mut self, command_set: QrCommandSet) {
+        self.qr_command_set = command_set;
+    }
+
+    /// 用紙の種類を設定
+    pub fn set_media_mode(&mut self, media_mode: MediaMode) {
+        self.media_mode = media_mode;
+    }
+
+    pub fn media_mode(&self) -> MediaMode {
+        self.media_mode
+    }
+
+    /// 用紙使用量の計測を始める（印刷が終わってプリンターを破棄するときに日別の使用量へ記録する）
+    pub fn meter_paper_usage(&mut self) {
+        self.paper_meter = Some(PaperMeter::new());
+    }
+
+    /// 計測中の用紙使用量
+    pub fn paper_used(&self) -> Option<PaperLength> {
+        self.paper_meter.as_ref().map(|m| m.length())
+    }
+
+    /// ラベル・ブラックマーク紙を印字開始位置に合わせる（連続紙では何もしない）
+    pub fn align_to_print_start(&mut self) -> Result<(), String> {
+        if self.media_mode == MediaMode::Continuous {
+            return Ok(());
+        }
+        self.raw(&label_command(LABEL_FN_PRINT_START, LABEL_CURRENT))
+    }
+
+    /// ラベル・ブラックマーク紙を次のラベルのカット位置まで送る（連続紙では何もしない）
+    fn feed_to_cut_position(&mut self) -> Result<(), String> {
+        if self.media_mode == MediaMode::Continuous {
+            return Ok(());
+        }
+        self.raw(&label_command(LABEL_FN_CUT_POSITION, LABEL_NEXT))
+    }
+
+    /// QRコード用の GS ( k コマンドを組み立てる（機能コードはコマンド体系に合わせて変換）
+    fn qr_command(&self, epson_fn: u8, params: &[u8]) -> Vec<u8> {
+        let len = params.len() + 2; // +2 for cn, fn
+        let mut cmd = vec![
+            0x1d,
+            0x28,
+            0x6b,
+            (len & 0xFF) as u8,
+            ((len >> 8) & 0xFF) as u8,
+            0x31,
+            self.qr_command_set.function_code(epson_fn),
+        ];
+        cmd.extend_from_slice(params);
+        cmd
+    }
+
+    pub fn is_raster(&self) -> bool {
+        self.raster.is_some()
+    }
+
+    fn write_driver(&mut self, data: &[u8]) -> Result<(), String> {
+        if let Some(meter) = self.paper_meter.as_mut() {
+            meter.feed(data);
+        }
         self.driver.write(data).map_err(|e| e.to_string())?;
         self.driver.flush().map_err(|e| e.to_string())?;
         Ok(())
     }
 
+    /// ラスターモードで描画済みの内容を GS v 0 で送信
+    fn flush_raster(&mut self) -> Result<(), String> {
+        let data = match self.raster.as_mut() {
+            Some(canvas) if !canvas.is_empty() => canvas.take_gs_v0(),
+            _ => return Ok(()),
+        };
+        self.write_driver(&data)
+    }
+
+    fn raw(&mut self, data: &[u8]) -> Result<(), String> {
+        self.flush_raster()?;
+        self.write_driver(data)
+    }
+
+    /// 状態の問い合わせを送り、1バイトの応答を読む
+    fn status_byte(&mut self, command: &[u8]) -> Result<u8, String> {
+        self.raw(command)?;
+        let mut buf = [0u8; 1];
+        let read = self.driver.read(&mut buf).map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Err("No status response from printer".to_string());
+        }
+        Ok(buf[0])
+    }
+
+    /// 用紙センサーの状態を問い合わせる
+    pub fn paper_status(&mut self) -> Result<PaperStatus, String> {
+        self.status_byte(DLE_EOT_PAPER).map(PaperStatus::from_status_byte)
+    }
+
+    /// プリンターの状態をまとめて問い合わせる（DLE EOT 1〜4）
+    ///
+    /// DLE EOT 4 に応答しない機種は、用紙センサーの状態を GS r 1 で問い合わせる
+    /// （GS r は受信バッファの順に処理されるため、オフライン中は応答しない）
+    pub fn status(&mut self) -> Result<PrinterStatus, String> {
+        let printer = self.status_byte(DLE_EOT_PRINTER)?;
+        let offline = self.status_byte(DLE_EOT_OFFLINE)?;
+        let error = self.status_byte(DLE_EOT_ERROR)?;
+        let paper = self
+            .paper_status()
+            .or_else(|_| self.status_byte(GS_R_PAPER).map(PaperStatus::from_transmitted_byte))
+            .ok();
+        Ok(PrinterStatus {
+            connected: true,
+            paper,
+            offline: Some(OfflineStatus::from_status_bytes(printer, offline)),
+            error: Some(ErrorStatus::from_status_byte(error)),
+            ..Default::default()
+        })
+    }
+
     pub fn init(&mut self) -> Result<(), String> {
         self.raw(HW_INIT)?;
         self.raw(JP_CHARCODE_JIS)?;
@@ -221,17 +451,26 @@ impl<D: Driver> JpPrinter<D> {
     }
 
     pub fn feed(&mut self, lines: u8) -> Result<(), String> {
+        if let Some(canvas) = self.raster.as_mut() {
+            for _ in 0..lines {
+                canvas.newline();
+            }
+            return Ok(());
+        }
         for _ in 0..lines {
             self.raw(CTL_LF)?;
         }
         Ok(())
     }
 
+    /// カット（ラベル・ブラックマーク紙はラベルの境目まで送ってからカット）
     pub fn cut(&mut self) -> Result<(), String> {
+        self.feed_to_cut_position()?;
         self.raw(PAPER_FULL_CUT)
     }
 
     pub fn partial_cut(&mut self) -> Result<(), String> {
+        self.feed_to_cut_position()?;
         self.raw(PAPER_PART_CUT)
     }
 
@@ -289,6 +528,11 @@ impl<D: Driver> JpPrinter<D> {
     }
 
     pub fn jp_text(&mut self, txt: &str, style: TextStyle) -> Result<(), String> {
+        if let Some(canvas) = self.raster.as_mut() {
+            canvas.push_text(txt, style);
+            return Ok(());
+        }
+
         // ESC ! でサイズを設定（ANK文字・全体設定用）
         // 一部のプリンターはFS!だけでは倍角が効かないため、ESC!も送る
         let is_double = style.double_width || style.double_height;
@@ -358,7 +602,25 @@ impl<D: Driver> JpPrinter<D> {
     }
 
     pub fn separator(&mut self) -> Result<(), String> {
-        self.line(self.paper_width.chars())
+        self.separator_with('-', TextStyle::default())
+    }
+
+    /// 指定した文字で1行分の区切り線を印刷（全角文字・倍角は幅を考慮）
+    pub fn separator_with(&mut self, c: char, style: TextStyle) -> Result<(), String> {
+        let scale = if style.double_width { 2 } else { 1 };
+        let count = self.paper_width.chars() / (char_width(c) * scale);
+        self.jp_textln(&c.to_string().repeat(count), style)
+    }
+
+    /// 点線の区切り線（クレジット売上票などの切り取り・補足部分用）
+    pub fn dashed_separator(&mut self) -> Result<(), String> {
+        let line: String = "- ".repeat(self.paper_width.chars() / 2);
+        self.jp_textln(line.trim_end(), TextStyle::default())
+    }
+
+    /// 星の区切り線（お知らせ・特記事項用）
+    pub fn star_separator(&mut self) -> Result<(), String> {
+        self.separator_with('*', TextStyle::default())
     }
 
     pub fn double_line(&mut self, width: usize) -> Result<(), String> {
@@ -367,7 +629,7 @@ impl<D: Driver> JpPrinter<D> {
     }
 
     pub fn double_separator(&mut self) -> Result<(), String> {
-        self.double_line(self.paper_width.chars())
+        self.separator_with('=', TextStyle::default())
     }
 
     pub fn row(&mut self, left: &str, right: &str, width: usize) -> Result<(), String> {
@@ -380,6 +642,28 @@ impl<D: Driver> JpPrinter<D> {
         self.textln(&row)
     }
 
+    /// 左寄せのテキストと、右端に揃えた固定幅の列を1行に印刷
+    ///
+    /// 各列は指定幅の中で右寄せし、最後の列が必ず右端で終わる
+    /// 左のテキストが入りきらない場合は左のテキストだけを先に1行で印刷し、列は次の行に印刷する
+    pub fn fixed_columns(&mut self, left: &str, columns: &[(&str, usize)], style: TextStyle) -> Result<(), String> {
+        let total = self.paper_width.chars();
+        let right: String = columns
+            .iter()
+            .map(|(text, width)| format!("{}{}", " ".repeat(width.saturating_sub(str_width(text))), text))
+            .collect();
+        let right_width = str_width(&right);
+        let left_width = str_width(left);
+
+        let line = if left_width + right_width <= total {
+            format!("{}{}{}", left, " ".repeat(total - left_width - right_width), right)
+        } else {
+            self.jp_textln(left, style)?;
+            format!("{}{}", " ".repeat(total.saturating_sub(right_width)), right)
+        };
+        self.jp_textln(&line, style)
+    }
+
     pub fn row_auto(&mut self, left: &str, right: &str) -> Result<(), String> {
         self.row(left, right, self.paper_width.chars())
     }
@@ -409,31 +693,28 @@ impl<D: Driver> JpPrinter<D> {
         // CellWidthCommand: Set module size
         // GS ( k pL pH cn fn n
         // pL pH = 3, cn = 49 (0x31), fn = 67 (0x43), n = size (1-16)
-        let mut size_cmd = QR_SIZE_PREFIX.to_vec();
-        size_cmd.push(size);
+        let size_cmd = self.qr_command(QR_FN_SIZE, &[size]);
         self.raw(&size_cmd)?;
 
         // ECCCommand: Set error correction level L
         // GS ( k pL pH cn fn n
         // pL pH = 3, cn = 49 (0x31), fn = 69 (0x45), n = 48 (L)
-        self.raw(QR_ERROR_L)?;
+        let ecc_cmd = self.qr_command(QR_FN_ERROR, &[0x30]);
+        self.raw(&ecc_cmd)?;
 
         // DataHeadCommand: Store QR Code data
         // GS ( k pL pH cn fn m d1...dk
         // (pL + pH*256) = dataLen + 3, cn = 49 (0x31), fn = 80 (0x50), m = 48 (0x30)
-        let data_bytes = data.as_bytes();
-        let len = data_bytes.len() + 3; // +3 for cn, fn, m
-        let pl = (len & 0xFF) as u8;
-        let ph = ((len >> 8) & 0xFF) as u8;
-
-        let mut store_cmd = vec![0x1d, 0x28, 0x6b, pl, ph, 0x31, 0x50, 0x30];
-        store_cmd.extend_from_slice(data_bytes);
+        let mut params = vec![0x30];
+        params.extend_from_slice(data.as_bytes());
+        let store_cmd = self.qr_command(QR_FN_STORE, &params);
         self.raw(&store_cmd)?;
 
         // PrintCommand: Print QR Code
         // GS ( k pL pH cn fn m
         // pL pH = 3, cn = 49 (0x31), fn = 81 (0x51), m = 48 (0x30)
-        self.raw(QR_PRINT)?;
+        let print_cmd = self.qr_command(QR_FN_PRINT, &[0x30]);
+        self.raw(&print_cmd)?;
 
         // Line feed after QR code
         self.feed(1)?;
@@ -441,6 +722,81 @@ impl<D: Driver> JpPrinter<D> {
         Ok(())
     }
 
+    /// Print CODE128 barcode (code set B, HRI below)
+    /// height: dots (default: 80), width: module width 2-6 (default: 2)
+    pub fn code128(&mut self, data: &str, height: Option<u8>, width: Option<u8>) -> Result<(), String> {
+        let cmd = code128_command(data)?;
+        self.barcode(&cmd, BarcodeOptions { height, width, hri: HriPosition::Below })
+    }
+
+    /// Print CODE128 barcode centered
+    pub fn code128_center(&mut self, data: &str, height: Option<u8>) -> Result<(), String> {
+        self.set_align(Align::Center)?;
+        let result = self.code128(data, height, None);
+        self.set_align(Align::Left)?;
+        result
+    }
+
+    /// Print EAN-13 (JAN-13) barcode
+    /// data: 13 digits including the check digit
+    pub fn barcode_ean13(&mut self, data: &str, options: BarcodeOptions) -> Result<(), String> {
+        let cmd = ean_command(BARCODE_EAN13, data, 13)?;
+        self.barcode(&cmd, options)
+    }
+
+    /// Print EAN-8 (JAN-8) barcode
+    /// data: 8 digits including the check digit
+    pub fn barcode_ean8(&mut self, data: &str, options: BarcodeOptions) -> Result<(), String> {
+        let cmd = ean_command(BARCODE_EAN8, data, 8)?;
+        self.barcode(&cmd, options)
+    }
+
+    /// Print JAN barcode centered (EAN-13 or EAN-8 by length)
+    pub fn jan_center(&mut self, data: &str, options: BarcodeOptions) -> Result<(), String> {
+        let symbology = if data.len() == 8 {
+            BarcodeSymbology::Ean8
+        } else {
+            BarcodeSymbology::Ean13
+        };
+        self.barcode_center(symbology, data, options)
+    }
+
+    /// Print barcode of the given symbology centered
+    pub fn barcode_center(
+        &mut self,
+        symbology: BarcodeSymbology,
+        data: &str,
+        options: BarcodeOptions,
+    ) -> Result<(), String> {
+        let cmd = match symbology {
+            BarcodeSymbology::Ean13 => ean_command(BARCODE_EAN13, data, 13)?,
+            BarcodeSymbology::Ean8 => ean_command(BARCODE_EAN8, data, 8)?,
+            BarcodeSymbology::Code128 => code128_command(data)?,
+        };
+        self.set_align(Align::Center)?;
+        let result = self.barcode(&cmd, options);
+        self.set_align(Align::Left)?;
+        result
+    }
+
+    /// 高さ・幅・HRI の位置を設定してバーコードを印刷
+    fn barcode(&mut self, cmd: &[u8], options: BarcodeOptions) -> Result<(), String> {
+        let mut height_cmd = BARCODE_HEIGHT_PREFIX.to_vec();
+        height_cmd.push(options.height.unwrap_or(80).max(1));
+        self.raw(&height_cmd)?;
+
+        let mut width_cmd = BARCODE_WIDTH_PREFIX.to_vec();
+        width_cmd.push(options.width.unwrap_or(2).clamp(2, 6));
+        self.raw(&width_cmd)?;
+
+        let mut hri_cmd = BARCODE_HRI_PREFIX.to_vec();
+        hri_cmd.push(options.hri.command_value());
+        self.raw(&hri_cmd)?;
+        self.raw(cmd)?;
+
+        self.feed(1)
+    }
+
     /// Print QR code centered
     pub fn qr_code_center(&mut self, data: &str, size: Option<u8>) -> Result<(), String> {
         self.set_align(Align::Center)?;
@@ -449,6 +805,81 @@ impl<D: Driver> JpPrinter<D> {
         Ok(())
     }
 
+    /// ルビ（ふりがな）付きで1行印刷
+    ///
+    /// ラスターモードでは小さい文字で本文の上に描画し、
+    /// テキストモードでは本文の中央に揃えたルビ行を本文の前に印刷する
+    pub fn jp_textln_ruby(&mut self, base: &str, ruby: &str, style: TextStyle) -> Result<(), String> {
+        if ruby.is_empty() {
+            return self.jp_textln(base, style);
+        }
+
+        if let Some(canvas) = self.raster.as_mut() {
+            canvas.push_ruby(base, ruby, style);
+            return self.jp_textln(base, style);
+        }
+
+        let scale = if style.double_width { 2 } else { 1 };
+        let base_width = (str_width(base) * scale).min(self.paper_width.chars());
+        let ruby_width = str_width(ruby);
+        let ruby_line = match style.align {
+            Align::Left => {
+                let indent = base_width.saturating_sub(ruby_width) / 2;
+                format!("{}{}", " ".repeat(indent), ruby)
+            }
+            Align::Center => ruby.to_string(),
+            Align::Right => {
+                let trail = base_width.saturating_sub(ruby_width) / 2;
+                format!("{}{}", ruby, " ".repeat(trail))
+            }
+        };
+
+        self.jp_textln(&ruby_line, TextStyle::default().align(style.align))?;
+        self.jp_textln(base, style)
+    }
+
+    /// 補足用の小さい行を字下げして印刷
+    ///
+    /// ラスターモードでは小さい文字で描画し、テキストモードでは通常の大きさで印刷する
+    pub fn jp_textln_small(&mut self, txt: &str, indent: usize) -> Result<(), String> {
+        if let Some(canvas) = self.raster.as_mut() {
+            canvas.push_small_line(txt, indent);
+            return Ok(());
+        }
+        self.jp_textln(&format!("{}{}", " ".repeat(indent), txt), TextStyle::default())
+    }
+
+    /// 縦書きのテキストブロックを画像で印刷（改行で列を分ける）
+    ///
+    /// テキストモードでもこのブロックだけは同梱フォントで描画して送信する
+    pub fn jp_vertical_block(&mut self, text: &str, align: Align) -> Result<(), String> {
+        if let Some(canvas) = self.raster.as_mut() {
+            canvas.push_vertical(text, align);
+            return Ok(());
+        }
+
+        let font = crate::raster::load_font(None)?;
+        let mut canvas = RasterCanvas::new(font, self.paper_width.dots() as usize);
+        canvas.push_vertical(text, align);
+        if canvas.is_empty() {
+            return Ok(());
+        }
+        let data = canvas.take_gs_v0();
+        self.raw(&data)
+    }
+
+    /// 白黒画像を印刷（GS v 0）
+    pub fn bitmap(&mut self, bitmap: &Bitmap, align: Align) -> Result<(), String> {
+        if let Some(canvas) = self.raster.as_mut() {
+            canvas.push_bitmap(bitmap, align);
+            return Ok(());
+        }
+        self.set_align(align)?;
+        let result = self.raw(&bitmap.to_gs_v0());
+        self.set_align(Align::Left)?;
+        result
+    }
+
     /// Print text with padding to fill line (for reverse style)
     /// Uses full-width spaces for proper alignment with double-size text
     pub fn jp_textln_padded(&mut self, txt: &str, style: TextStyle) -> Result<(), String> {
@@ -488,3 +919,163 @@ impl<D: Driver> JpPrinter<D> {
         self.jp_textln(&padded, left_style)
     }
 }
+
+impl<D: Driver> Drop for JpPrinter<D> {
+    fn drop(&mut self) {
+        if let Some(meter) = self.paper_meter.as_ref() {
+            // 記録できなくても印刷は済んでいる
+            let _ = paper_usage::record(self.paper_width.mm(), meter.length());
+        }
+    }
+}
+
+/// CODE128（コードセットB）で印刷できる文字列か（空でなく、ASCIIの印字可能文字のみ）
+pub fn is_code128_data(data: &str) -> bool {
+    !data.is_empty() && data.bytes().all(|b| (0x20..=0x7e).contains(&b))
+}
+
+/// CODE128（コードセットB）の印字コマンド GS k m n d1...dn を組み立てる
+///
+/// コードセットBで表せるのは 0x20〜0x7E のみ
+/// `{` はコードセット指定の開始文字なので、データ中の `{` は `{{` に置き換える
+fn code128_command(data: &str) -> Result<Vec<u8>, String> {
+    if !is_code128_data(data) {
+        return Err(format!("Invalid CODE128 data: {}", data));
+    }
+
+    let mut body = vec![b'{', b'B'];
+    for byte in data.bytes() {
+        if byte == b'{' {
+            body.push(b'{');
+        }
+        body.push(byte);
+    }
+    // コードセット指定を含めて255バイトまで
+    let len = u8::try_from(body.len()).map_err(|_| "CODE128 data too long".to_string())?;
+
+    let mut cmd = vec![0x1d, 0x6b, BARCODE_CODE128, len];
+    cmd.extend_from_slice(&body);
+    Ok(cmd)
+}
+
+/// EAN-13・EAN-8 の印字コマンド GS k m n d1...dn を組み立てる
+///
+/// チェックデジットを含めた桁数の数字のみ受け付け、チェックデジットが合わないものはエラーにする
+fn ean_command(m: u8, data: &str, digits: usize) -> Result<Vec<u8>, String> {
+    if data.len() != digits || !book_code::is_valid_jan(data) {
+        return Err(format!("Invalid JAN code: {}", data));
+    }
+    let mut cmd = vec![0x1d, 0x6b, m, digits as u8];
+    cmd.extend_from_slice(data.as_bytes());
+    Ok(cmd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use escpos::errors::Result as DriverResult;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// 送信されたバイト列を記録するテスト用ドライバー
+    #[derive(Clone, Default)]
+    struct ByteSink {
+        bytes: Rc<RefCell<Vec<u8>>>,
+    }
+
+    impl Driver for ByteSink {
+        fn name(&self) -> String {
+            "byte-sink".to_string()
+        }
+
+        fn write(&self, data: &[u8]) -> DriverResult<()> {
+            self.bytes.borrow_mut().extend_from_slice(data);
+            Ok(())
+        }
+
+        fn read(&self, _buf: &mut [u8]) -> DriverResult<usize> {
+            Ok(0)
+        }
+
+        fn flush(&self) -> DriverResult<()> {
+            Ok(())
+        }
+    }
+
+    /// 用紙の種類を設定したプリンターで位置合わせとカットを行い、送信されたバイト列を返す
+    fn align_and_cut(media_mode: MediaMode) -> Vec<u8> {
+        let sink = ByteSink::default();
+        let mut printer = JpPrinter::new(sink.clone());
+        printer.set_media_mode(media_mode);
+        printer.align_to_print_start().unwrap();
+        printer.cut().unwrap();
+        let bytes = sink.bytes.borrow().clone();
+        bytes
+    }
+
+    #[test]
+    fn test_continuous_media_cuts_without_label_commands() {
+        assert_eq!(align_and_cut(MediaMode::Continuous), PAPER_FULL_CUT);
+    }
+
+    #[test]
+    fn test_label_media_aligns_and_feeds_to_cut_position() {
+        let mut expected = label_command(LABEL_FN_PRINT_START, LABEL_CURRENT).to_vec();
+        expected.extend_from_slice(&label_command(LABEL_FN_CUT_POSITION, LABEL_NEXT));
+        expected.extend_from_slice(PAPER_FULL_CUT);
+        assert_eq!(align_and_cut(MediaMode::Gap), expected);
+        assert_eq!(align_and_cut(MediaMode::BlackMark), expected);
+    }
+
+    #[test]
+    fn test_code128_command_selects_code_set_b() {
+        assert_eq!(
+            code128_command("R-01").unwrap(),
+            vec![0x1d, 0x6b, BARCODE_CODE128, 6, b'{', b'B', b'R', b'-', b'0', b'1']
+        );
+    }
+
+    #[test]
+    fn test_code128_command_escapes_brace() {
+        let cmd = code128_command("a{b").unwrap();
+        assert_eq!(&cmd[3..], &[6, b'{', b'B', b'a', b'{', b'{', b'b'][..]);
+    }
+
+    #[test]
+    fn test_code128_command_rejects_invalid_data() {
+        assert!(code128_command("").is_err());
+        assert!(code128_command("レシート").is_err());
+        assert!(code128_command("a\tb").is_err());
+        assert!(code128_command(&"1".repeat(253)).is_ok());
+        assert!(code128_command(&"1".repeat(254)).is_err());
+        // エスケープで長さが上限を超える場合
+        assert!(code128_command(&"{".repeat(127)).is_err());
+    }
+
+    #[test]
+    fn test_ean_command_validates_check_digit() {
+        assert_eq!(
+            ean_command(BARCODE_EAN8, "49123456", 8).unwrap(),
+            vec![0x1d, 0x6b, BARCODE_EAN8, 8, b'4', b'9', b'1', b'2', b'3', b'4', b'5', b'6']
+        );
+        assert!(ean_command(BARCODE_EAN13, "4901234567894", 13).is_ok());
+        assert!(ean_command(BARCODE_EAN13, "4901234567890", 13).is_err());
+        // 桁数が合わないもの・8桁を EAN-13 として印刷するもの
+        assert!(ean_command(BARCODE_EAN13, "490123456789", 13).is_err());
+        assert!(ean_command(BARCODE_EAN13, "49123456", 13).is_err());
+    }
+
+    #[test]
+    fn test_barcode_options_set_height_width_and_hri() {
+        let sink = ByteSink::default();
+        let mut printer = JpPrinter::new(sink.clone());
+        let options = BarcodeOptions {
+            height: Some(50),
+            width: Some(9),
+            hri: HriPosition::Both,
+        };
+        printer.barcode_ean8("49123456", options).unwrap();
+        let bytes = sink.bytes.borrow().clone();
+        assert_eq!(&bytes[..9], b"\x1d\x68\x32\x1d\x77\x06\x1d\x48\x03");
+    }
+}
@@ -0,0 +1,199 @@
+//! 鍵の使用状況モジュール
+//!
+//! 端末鍵で署名した回数と日時を日ごと（設定のタイムゾーン）に記録し、端末の状態と一緒に返す
+//! 深夜の大量署名など、認証情報の不正利用が疑われる使い方を異常として示す
+
+use crate::config;
+use crate::datetime;
+use crate::storage;
+use chrono::{DateTime, Timelike};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+
+/// 使用状況の保存ファイル名
+const KEY_USAGE_FILE: &str = "key_usage.json";
+/// 日ごとの記録を残す日数
+const RETENTION_DAYS: usize = 30;
+/// 1日の署名回数がこれを超えたら異常とする
+const DAILY_LIMIT: u64 = 5000;
+/// 深夜（0時〜6時）の署名回数がこれを超えたら異常とする
+const NIGHT_LIMIT: u64 = 500;
+/// 深夜とみなす時間帯の終わり（時）
+const NIGHT_END_HOUR: u32 = 6;
+
+/// 1日の使用状況
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DailyUsage {
+    /// 日付（YYYY-MM-DD）
+    pub date: String,
+    pub count: u64,
+    /// 深夜の署名回数
+    pub night_count: u64,
+    /// 最初・最後に署名した日時（UNIX秒）
+    pub first_at: u64,
+    pub last_at: u64,
+}
+
+/// 使用状況の異常
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UsageAnomaly {
+    /// 1日の署名回数が多すぎる
+    HighDailyVolume { date: String, count: u64 },
+    /// 深夜の署名回数が多すぎる
+    NightActivity { date: String, count: u64 },
+}
+
+/// 保存する使用状況
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct KeyUsage {
+    total: u64,
+    days: Vec<DailyUsage>,
+}
+
+/// 端末の状態と一緒に返す使用状況
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct KeyUsageSummary {
+    /// 記録を始めてからの署名回数
+    pub total: u64,
+    pub last_used_at: Option<u64>,
+    /// 日ごとの使用状況（古い順）
+    pub days: Vec<DailyUsage>,
+    pub anomalies: Vec<UsageAnomaly>,
+}
+
+/// 読み込みから保存までを直列にする（署名は複数のスレッドから行われる）
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn load() -> Result<KeyUsage, String> {
+    Ok(storage::load_json(KEY_USAGE_FILE)?.unwrap_or_default())
+}
+
+/// 署名1回分を記録
+fn record_at(usage: &mut KeyUsage, now: DateTime<Tz>) {
+    let date = now.format("%Y-%m-%d").to_string();
+    let timestamp = now.timestamp().max(0) as u64;
+    let night = now.hour() < NIGHT_END_HOUR;
+
+    usage.total += 1;
+    match usage.days.last_mut() {
+        Some(day) if day.date == date => {
+            day.count += 1;
+            day.last_at = timestamp;
+            if night {
+                day.night_count += 1;
+            }
+        }
+        _ => usage.days.push(DailyUsage {
+            date,
+            count: 1,
+            night_count: night as u64,
+            first_at: timestamp,
+            last_at: timestamp,
+        }),
+    }
+    if usage.days.len() > RETENTION_DAYS {
+        let excess = usage.days.len() - RETENTION_DAYS;
+        usage.days.drain(..excess);
+    }
+}
+
+/// 日ごとの使用状況から異常を探す
+fn anomalies(days: &[DailyUsage]) -> Vec<UsageAnomaly> {
+    let mut anomalies = Vec::new();
+    for day in days {
+        if day.count > DAILY_LIMIT {
+            anomalies.push(UsageAnomaly::HighDailyVolume {
+                date: day.date.clone(),
+                count: day.count,
+            });
+        }
+        if day.night_count > NIGHT_LIMIT {
+            anomalies.push(UsageAnomaly::NightActivity {
+                date: day.date.clone(),
+                count: day.night_count,
+            });
+        }
+    }
+    anomalies
+}
+
+/// 署名したことを記録
+pub fn record() -> Result<(), String> {
+    let _guard = LOCK.lock().map_err(|e| e.to_string())?;
+    let tz = datetime::timezone(&config::load()?.timezone);
+    let mut usage = load()?;
+    record_at(&mut usage, chrono::Utc::now().with_timezone(&tz));
+    storage::save_json(KEY_USAGE_FILE, &usage)
+}
+
+/// 使用状況と異常を取得
+pub fn summary() -> Result<KeyUsageSummary, String> {
+    let usage = load()?;
+    Ok(KeyUsageSummary {
+        total: usage.total,
+        last_used_at: usage.days.last().map(|d| d.last_at),
+        anomalies: anomalies(&usage.days),
+        days: usage.days,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono_tz::Asia::Tokyo;
+
+    #[test]
+    fn test_record_counts_per_local_day() {
+        let mut usage = KeyUsage::default();
+        // 2025-12-15 23:30 JST と 2025-12-16 01:00 JST
+        record_at(&mut usage, Tokyo.with_ymd_and_hms(2025, 12, 15, 23, 30, 0).unwrap());
+        record_at(&mut usage, Tokyo.with_ymd_and_hms(2025, 12, 16, 1, 0, 0).unwrap());
+        record_at(&mut usage, Tokyo.with_ymd_and_hms(2025, 12, 16, 10, 0, 0).unwrap());
+
+        assert_eq!(usage.total, 3);
+        assert_eq!(usage.days.len(), 2);
+        assert_eq!(usage.days[0].date, "2025-12-15");
+        assert_eq!(usage.days[0].night_count, 0);
+        assert_eq!(usage.days[1].date, "2025-12-16");
+        assert_eq!(usage.days[1].count, 2);
+        assert_eq!(usage.days[1].night_count, 1);
+    }
+
+    #[test]
+    fn test_record_keeps_retention_days() {
+        let mut usage = KeyUsage::default();
+        let start = Tokyo.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        for day in 0..40 {
+            record_at(&mut usage, start + chrono::Duration::days(day));
+        }
+        assert_eq!(usage.days.len(), RETENTION_DAYS);
+        assert_eq!(usage.total, 40);
+    }
+
+    #[test]
+    fn test_anomalies() {
+        let day = |date: &str, count, night_count| DailyUsage {
+            date: date.to_string(),
+            count,
+            night_count,
+            first_at: 0,
+            last_at: 0,
+        };
+        let days = vec![
+            day("2025-12-14", 300, 10),
+            day("2025-12-15", 6000, 20),
+            day("2025-12-16", 900, 800),
+        ];
+        assert_eq!(
+            anomalies(&days),
+            vec![
+                UsageAnomaly::HighDailyVolume { date: "2025-12-15".to_string(), count: 6000 },
+                UsageAnomaly::NightActivity { date: "2025-12-16".to_string(), count: 800 },
+            ]
+        );
+    }
+}
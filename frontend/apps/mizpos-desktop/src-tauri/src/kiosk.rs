@@ -0,0 +1,427 @@
+//! セルフレジ（キオスク）モジュール
+//!
+//! 無人のサテライトブース向けに、スキャン → カゴ → QR決済 → 自動印刷までをバックエンド側で進める
+//! 現金を扱わないため、キオスクモード中はドロワー操作・返品・取消を受け付けない
+
+use crate::age_check;
+use crate::config;
+use crate::event_context;
+use crate::journal::SaleChannel;
+use crate::receipt::{PaymentInfo, ReceiptData, ReceiptItem};
+use crate::sales;
+use crate::session;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+/// キオスク状態の保存ファイル名
+const KIOSK_FILE: &str = "kiosk.json";
+/// キオスクの売上を記録するときのスタッフ番号
+pub const KIOSK_STAFF_ID: &str = "KIOSK";
+/// 状態が変わったときに通知するイベント名
+pub const EVENT_KIOSK_STATE: &str = "kiosk-state";
+/// 自動印刷に失敗したときに通知するイベント名
+pub const EVENT_KIOSK_PRINT_FAILED: &str = "kiosk-print-failed";
+
+/// 会計の段階
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum KioskStep {
+    /// 商品をスキャン中
+    #[default]
+    Scanning,
+    /// QR決済の完了待ち（カゴは変更できない）
+    AwaitingPayment {
+        /// 決済の照合用ID
+        payment_ref: String,
+        /// 請求額
+        amount: u32,
+        /// 請求日時（UNIX秒）
+        requested_at: u64,
+    },
+}
+
+/// キオスクの状態
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct KioskState {
+    /// キオスクモード中か
+    pub active: bool,
+    /// カゴの明細
+    pub basket: Vec<ReceiptItem>,
+    /// カゴの合計
+    pub total: u32,
+    #[serde(flatten)]
+    pub step: KioskStep,
+    /// キオスクモードの開始日時（UNIX秒）
+    pub started_at: Option<u64>,
+}
+
+/// 自動印刷の失敗通知
+#[derive(Debug, Clone, Serialize)]
+pub struct KioskPrintFailedEvent {
+    pub receipt_number: String,
+    pub error: String,
+}
+
+/// 現在の状態を取得
+pub fn current() -> Result<KioskState, String> {
+    Ok(storage::load_json(&event_context::scoped(KIOSK_FILE)?)?.unwrap_or_default())
+}
+
+fn save(state: &KioskState) -> Result<(), String> {
+    storage::save_json(&event_context::scoped(KIOSK_FILE)?, state)
+}
+
+/// キオスクモード中か
+pub fn is_active() -> Result<bool, String> {
+    Ok(current()?.active)
+}
+
+/// 有人レジでのみ許可する操作の前に呼ぶ（キオスクモード中はエラー）
+pub fn ensure_attended(operation: &str) -> Result<(), String> {
+    if is_active()? {
+        return Err(format!("{} is not available in kiosk mode", operation));
+    }
+    Ok(())
+}
+
+/// 操作中のキオスク状態を取得（キオスクモードでなければエラー）
+fn active_state() -> Result<KioskState, String> {
+    let state = current()?;
+    if !state.active {
+        return Err("Kiosk mode is not active".to_string());
+    }
+    Ok(state)
+}
+
+/// スキャン中の状態を取得（決済待ちならエラー）
+fn scanning_state() -> Result<KioskState, String> {
+    let state = active_state()?;
+    if state.step != KioskStep::Scanning {
+        return Err("Payment is in progress".to_string());
+    }
+    Ok(state)
+}
+
+/// キオスクモードを開始（開局中のみ）
+pub fn start() -> Result<KioskState, String> {
+    let state = current()?;
+    if state.active {
+        return Ok(state);
+    }
+    if !session::current()?.open {
+        return Err("Open a session before starting kiosk mode".to_string());
+    }
+
+    let state = KioskState {
+        active: true,
+        started_at: Some(storage::now_unix()),
+        ..KioskState::default()
+    };
+    save(&state)?;
+    Ok(state)
+}
+
+/// キオスクモードを終了（会計途中のカゴがある場合は終了できない）
+pub fn stop() -> Result<KioskState, String> {
+    let state = active_state()?;
+    if !state.basket.is_empty() || state.step != KioskStep::Scanning {
+        return Err("Kiosk has a sale in progress".to_string());
+    }
+
+    let state = KioskState::default();
+    save(&state)?;
+    Ok(state)
+}
+
+/// スキャンした商品をカゴに追加（同じJANは数量をまとめる）
+pub fn scan(item: ReceiptItem) -> Result<KioskState, String> {
+    if item.quantity == 0 {
+        return Err("Quantity must be at least 1".to_string());
+    }
+    let mut state = scanning_state()?;
+    let config = config::load()?;
+    // セルフレジでは年齢確認ができない
+    if age_check::is_restricted(&item, &config.age_restricted_jans) {
+        return Err("Age-restricted items cannot be sold at the kiosk".to_string());
+    }
+    let limits = config.kiosk;
+
+    let total = state.total.saturating_add(item.price);
+    if total > limits.max_total {
+        return Err(format!("Kiosk sale limit exceeded: {} > {}", total, limits.max_total));
+    }
+
+    match state.basket.iter_mut().find(|i| i.jan == item.jan) {
+        Some(existing) => {
+            existing.quantity += item.quantity;
+            existing.price += item.price;
+        }
+        None => {
+            if state.basket.len() >= limits.max_lines {
+                return Err(format!("Kiosk basket is full ({} lines)", limits.max_lines));
+            }
+            state.basket.push(item);
+        }
+    }
+    state.total = total;
+    save(&state)?;
+    Ok(state)
+}
+
+/// カゴから明細を削除
+pub fn remove_item(line_index: usize) -> Result<KioskState, String> {
+    let mut state = scanning_state()?;
+    if line_index >= state.basket.len() {
+        return Err(format!("Invalid line index: {}", line_index));
+    }
+    let removed = state.basket.remove(line_index);
+    state.total -= removed.price;
+    save(&state)?;
+    Ok(state)
+}
+
+/// カゴを空にする
+pub fn clear_basket() -> Result<KioskState, String> {
+    let mut state = scanning_state()?;
+    state.basket.clear();
+    state.total = 0;
+    save(&state)?;
+    Ok(state)
+}
+
+/// 会計に進む（QR決済の請求を発行）
+pub fn checkout() -> Result<KioskState, String> {
+    let mut state = scanning_state()?;
+    if state.basket.is_empty() || state.total == 0 {
+        return Err("Basket is empty".to_string());
+    }
+
+    state.step = KioskStep::AwaitingPayment {
+        payment_ref: Uuid::new_v4().to_string(),
+        amount: state.total,
+        requested_at: storage::now_unix(),
+    };
+    save(&state)?;
+    Ok(state)
+}
+
+/// 決済をやめてスキャンに戻る
+pub fn cancel_payment() -> Result<KioskState, String> {
+    let mut state = active_state()?;
+    state.step = KioskStep::Scanning;
+    save(&state)?;
+    Ok(state)
+}
+
+/// QR決済の完了を受けて売上を確定する
+///
+/// 対面の会計と同じ確認を通してジャーナルにはキオスクの売上として記録し、カゴを空にしてスキャンに戻る
+/// 確認に失敗した場合は売上を記録せず、決済待ちのまま残す（スタッフが対応する）
+pub fn complete_payment(
+    payment_ref: &str,
+    payment_id: Option<String>,
+) -> Result<(ReceiptData, KioskState), String> {
+    let mut state = active_state()?;
+    let amount = match &state.step {
+        KioskStep::AwaitingPayment {
+            payment_ref: expected,
+            amount,
+            ..
+        } if expected == payment_ref => *amount,
+        KioskStep::AwaitingPayment { .. } => {
+            return Err(format!("Payment reference mismatch: {}", payment_ref))
+        }
+        KioskStep::Scanning => return Err("No payment is in progress".to_string()),
+    };
+
+    let config = config::load()?;
+    let mut receipt = build_receipt(&state.basket, amount, payment_id, &config)?;
+    sales::prepare_sale(&mut receipt, None, SaleChannel::Kiosk)?;
+
+    state.basket.clear();
+    state.total = 0;
+    state.step = KioskStep::Scanning;
+    save(&state)?;
+    Ok((receipt, state))
+}
+
+/// カゴからレシートデータを作成
+fn build_receipt(
+    basket: &[ReceiptItem],
+    total: u32,
+    payment_id: Option<String>,
+    config: &config::TerminalConfig,
+) -> Result<ReceiptData, String> {
+    let kiosk = &config.kiosk;
+    let tax_amount = total * kiosk.tax_rate / (100 + kiosk.tax_rate);
+
+    Ok(ReceiptData {
+        event_name: event_context::active()?
+            .map(|e| e.event_name)
+            .unwrap_or_default(),
+        circle_name: config.receipt_header.shop_name.clone(),
+        circle_name_vertical: false,
+        logo: None,
+        venue_address: None,
+        sale_start_date_time: Some(chrono::Utc::now().to_rfc3339()),
+        staff_id: KIOSK_STAFF_ID.to_string(),
+        customer_name: None,
+        member_id: None,
+        points: None,
+        promotions: vec![],
+        items: basket.to_vec(),
+        total,
+        payments: vec![PaymentInfo {
+            method: kiosk.payment_method_label.clone(),
+            amount: total,
+        }],
+        tax_rate: kiosk.tax_rate,
+        tax_amount,
+        receipt_number: event_context::next_receipt_number()?,
+        card_details: None,
+        payment_intent_id: payment_id,
+        change_breakdown: None,
+        age_verification: None,
+        deposit: None,
+        rounding_adjustment: 0,
+        price_version: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::journal;
+    use crate::receipt::test_support;
+    use crate::storage::test_support::TempDataDir;
+    use serde_json::json;
+
+    fn item(jan: &str, quantity: u32, price: u32) -> ReceiptItem {
+        test_support::item(json!({ "circle_name": "テストサークル", "jan": jan, "is_book": false, "quantity": quantity, "price": price }))
+    }
+
+    fn start_kiosk() {
+        session::open("S001").unwrap();
+        start().unwrap();
+    }
+
+    fn payment_ref(state: &KioskState) -> String {
+        match &state.step {
+            KioskStep::AwaitingPayment { payment_ref, .. } => payment_ref.clone(),
+            KioskStep::Scanning => panic!("not awaiting payment"),
+        }
+    }
+
+    #[test]
+    fn test_start_requires_open_session() {
+        let _dir = TempDataDir::new();
+        assert!(start().is_err());
+        assert!(!is_active().unwrap());
+
+        session::open("S001").unwrap();
+        assert!(start().unwrap().active);
+        // キオスクモード中は有人の操作を受け付けない
+        assert!(ensure_attended("Refund").is_err());
+    }
+
+    #[test]
+    fn test_scan_merges_same_jan_and_enforces_limits() {
+        let _dir = TempDataDir::new();
+        start_kiosk();
+
+        scan(item("4900000000001", 1, 500)).unwrap();
+        let state = scan(item("4900000000001", 2, 1000)).unwrap();
+        assert_eq!(state.basket.len(), 1);
+        assert_eq!((state.basket[0].quantity, state.basket[0].price), (3, 1500));
+        assert_eq!(state.total, 1500);
+
+        assert!(scan(item("4900000000002", 0, 0)).is_err());
+        let err = scan(item("4900000000002", 1, 29000)).unwrap_err();
+        assert!(err.starts_with("Kiosk sale limit exceeded"));
+        // 上限を超えた商品はカゴに入らない
+        assert_eq!(current().unwrap().total, 1500);
+
+        let mut config = config::load().unwrap();
+        config.kiosk.max_lines = 1;
+        config::save(&config).unwrap();
+        assert!(scan(item("4900000000002", 1, 100)).is_err());
+    }
+
+    #[test]
+    fn test_scan_rejects_age_restricted_items() {
+        let _dir = TempDataDir::new();
+        start_kiosk();
+        let mut config = config::load().unwrap();
+        config.age_restricted_jans = vec!["4900000000018".to_string()];
+        config::save(&config).unwrap();
+
+        assert!(scan(item("4900000000018", 1, 1000)).is_err());
+        assert!(current().unwrap().basket.is_empty());
+    }
+
+    #[test]
+    fn test_checkout_locks_basket_until_payment_resolves() {
+        let _dir = TempDataDir::new();
+        start_kiosk();
+        assert_eq!(checkout().unwrap_err(), "Basket is empty");
+
+        scan(item("4900000000001", 1, 1100)).unwrap();
+        let state = checkout().unwrap();
+        assert!(scan(item("4900000000002", 1, 100)).is_err());
+        assert!(clear_basket().is_err());
+
+        // 決済をやめるとスキャンに戻り、カゴは残る
+        let state = {
+            let cancelled = cancel_payment().unwrap();
+            assert_eq!(cancelled.step, KioskStep::Scanning);
+            assert_eq!(cancelled.total, state.total);
+            checkout().unwrap()
+        };
+        assert!(complete_payment("other-ref", None).is_err());
+        assert!(journal::entries().unwrap().is_empty());
+
+        let paid_ref = payment_ref(&state);
+        let (receipt, state) = complete_payment(&paid_ref, Some("pi_1".to_string())).unwrap();
+        assert_eq!(receipt.total, 1100);
+        assert_eq!(receipt.tax_amount, 100);
+        assert_eq!(receipt.staff_id, KIOSK_STAFF_ID);
+        assert!(state.basket.is_empty());
+        assert_eq!(state.step, KioskStep::Scanning);
+
+        let entries = journal::entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].channel, SaleChannel::Kiosk);
+        // 確定済みの決済は二重に記録しない
+        assert!(complete_payment(&paid_ref, None).is_err());
+    }
+
+    #[test]
+    fn test_complete_payment_runs_sale_checks() {
+        let _dir = TempDataDir::new();
+        start_kiosk();
+        scan(item("4900000000018", 1, 1000)).unwrap();
+        let state = checkout().unwrap();
+
+        // 決済待ちの間に成人向けに指定された商品は、年齢確認がないため確定しない
+        let mut config = config::load().unwrap();
+        config.age_restricted_jans = vec!["4900000000018".to_string()];
+        config::save(&config).unwrap();
+        assert!(complete_payment(&payment_ref(&state), None).is_err());
+        assert!(journal::entries().unwrap().is_empty());
+        assert!(matches!(current().unwrap().step, KioskStep::AwaitingPayment { .. }));
+    }
+
+    #[test]
+    fn test_stop_requires_empty_basket() {
+        let _dir = TempDataDir::new();
+        start_kiosk();
+        scan(item("4900000000001", 1, 500)).unwrap();
+        assert_eq!(stop().unwrap_err(), "Kiosk has a sale in progress");
+
+        clear_basket().unwrap();
+        assert!(!stop().unwrap().active);
+        assert!(ensure_attended("Refund").is_ok());
+    }
+}
@@ -1,118 +1,290 @@
 // Desktop-only modules
 #[cfg(not(target_os = "android"))]
 mod jp_escpos;
+#[cfg(not(target_os = "android"))]
+mod raster;
+// レシートのロゴ画像（PNG の読み込みと白黒変換）
+#[cfg(not(target_os = "android"))]
+mod logo;
 
 // 端末認証モジュール
 mod terminal_auth;
 
+// ローカル保存
+mod storage;
+
+// イベントコンテキスト（イベント単位のデータ分離）
+mod event_archive;
+mod event_context;
+
+// 署名付きアップロードキュー
+mod upload_queue;
+
+// 営業セッション
+mod session;
+
+// 閉局リマインダー
+mod closing_reminder;
+
+// ドロワー（釣銭）
+mod drawer;
+
+// レシートデータモデル
+mod receipt;
+
+// 端末設定
+mod config;
+
+// スタッフ認証（責任者承認）
+mod staff_auth;
+
+// ジャーナル（取引記録）
+mod journal;
+
+// 保留取引
+mod parked_sales;
+
+// 価格表（サーバー配信の価格改定の反映）
+mod price_book;
+
+// 呼び出し表示（客面表示の呼び出し番号）
+mod now_serving;
+
+// 見積・予約票
+mod quotes;
+
+// 予約（取り置き）
+mod reservations;
+
+// レシートQR（署名付き圧縮ペイロード）
+mod receipt_qr;
+
+// レシートの追加セクション（アンケートQR・告知など）
+mod receipt_sections;
+
+// 書籍JANコード（2段目）
+mod book_code;
+
+// 日時の検証・整形
+mod datetime;
+
+// 金額・数値の書式
+mod number_format;
+
+// 商品名・サークル名の全角・半角の正規化
+mod text_normalize;
+
+// プリンタープロファイル
+mod printer_profile;
+
+// セルフレジ（キオスク）
+mod kiosk;
+
+// 印刷スプーラー（ミラー印刷・再試行）
+mod spooler;
+
+// プリンター状態（リアルタイムステータス）
+mod printer_status;
+
+// ネットワークプリンター（LAN接続・iPad からの印刷）
+mod network_printer;
+
+// シリアルプリンター（RS-232・USB-シリアル変換）
+mod serial_printer;
+
+// 用紙ニアエンド監視
+mod paper_monitor;
+
+// 用紙使用量の見積もりと日別の記録
+mod paper_usage;
+
+// プリンターのキープアライブ（省電力による切断の防止）
+mod printer_keepalive;
+
+// メールレシート
+mod email_receipt;
+
+// 印刷コマンドの冪等キー（再送による二重印刷の防止）
+mod idempotency;
+
+// 印字文言（レシート・売上票・閉局レポートの文言リソース）
+mod print_strings;
+
+// ハートビート（端末状態の定期送信）
+mod heartbeat;
+
+// 管理サーバーの公開鍵（ピン留め・署名検証）
+mod server_key;
+
+// リモートコマンド（管理サーバーからの署名付きコマンド）
+mod remote_commands;
+
+// サーバー配信データ（端末設定・価格改定の署名検証）
+mod server_updates;
+
+// 会場ネットワーク確認（開局時のSSID・IPアドレス範囲の確認）
+mod venue_guard;
+
+// 脅迫PIN（サイレント通報・取引の記録）
+mod duress;
+
+// レート制限（署名・PIN照合・Keychain操作）
+mod rate_limit;
+
+// 鍵の使用状況（署名回数の記録・異常の検知）
+mod key_usage;
+
+// 商品券（カタログ・支払時の検証）
+mod vouchers;
+
+// ギフト券（発行・使用の記録と端末間の同期）
+mod gift_vouchers;
+
+// 会員（会員証の読み取り・宛名・購入履歴）
+mod members;
+
+// ポイント（会員の売上への付与・残高）
+mod points;
+
+// キャンペーン（まとめ買い・セット割引・期間限定の特価）
+mod promotions;
+
+// 価格変更（責任者承認・理由コード）
+mod price_overrides;
+// 端数調整（合計の丸め）
+mod rounding;
+
+// 年齢確認（成人向けの商品の販売）
+mod age_check;
+
+// 売上の上限チェック（数量・合計・釣り銭）
+mod sale_limits;
+
+// 内金・残金（予約販売の前受け）
+mod deposits;
+
+// 支払の検証（分割払い・おつりの規則）
+mod tenders;
+
+// 売上の確定（各経路で共通の確認・付与・記録）
+mod sales;
+
+// コマンドの権限（ウィンドウ・責任者セッション）
+mod permissions;
+
+// 稼働状況の計測（オプトインの匿名カウンター）
+mod metrics;
+
+// 接続先環境（本番・ステージング・ローカルの切り替え）
+mod environment;
+
+// ログのマスキング（診断用のコマンドの記録・診断情報）
+mod redaction;
+
+// レシートの印刷に失敗した売上の控え
+mod fallback_slip;
+
+// 商品データの取り込み（CSV・Excel）
+mod catalog_import;
+
+// 未登録のコードから仮登録した商品
+mod provisional_products;
+
+// ISDNの登録情報の照会
+mod isdn_registry;
+
+// イベントごとの売上目標と進捗
+mod sales_targets;
+
+// コマンドの型定義の生成（フロントエンドの呼び出し関数）
+#[cfg(all(test, not(target_os = "android")))]
+mod ipc_bindings;
+
 // Desktop USB printer implementation
 #[cfg(not(target_os = "android"))]
 mod desktop_printer {
-    use escpos::driver::NativeUsbDriver;
-    use crate::jp_escpos::{JpPrinter, PaperWidth, TextStyle};
-    use serde::Deserialize;
+    use escpos::driver::Driver;
+    use preview::{MeterDriver, PrinterDriver};
+    use target::PrinterTarget;
+    use crate::book_code;
+    use crate::config;
+    use crate::datetime;
+    use crate::environment;
+    use crate::fallback_slip;
+    use crate::gift_vouchers;
+    use crate::idempotency;
+    use crate::jp_escpos::{self, Align, BarcodeOptions, BarcodeSymbology, JpPrinter, PaperWidth, TextStyle};
+    use crate::journal::{self, SaleChannel};
+    use crate::logo;
+    use crate::metrics;
+    use crate::network_printer;
+    use crate::now_serving;
+    use crate::number_format::NumberFormat;
+    use crate::paper_usage::PaperLength;
+    use crate::parked_sales;
+    use crate::print_strings;
+    use crate::printer_profile::{self, MediaMode, QrCommandSet, RenderMode};
+    use crate::printer_status::{PaperStatus, PrinterStatus};
+    use crate::quotes;
+    use crate::raster;
+    use crate::receipt::{self, CardDetails, ClosingReportData, ReceiptData, ReceiptItem};
+    use crate::receipt_qr;
+    use crate::receipt_sections::{self, ReceiptSlot};
+    use crate::remote_commands;
+    use crate::reservations;
+    use crate::sales;
+    use crate::sales_targets::{self, SalesProgress};
+    use crate::serial_printer::{self, BluetoothLinkStatus, BluetoothSerialPort, SerialPortInfo};
+    use crate::spooler::{SpoolDocument, UsbPrinter};
+    use crate::staff_auth::{self, SupervisorApproval};
+    use crate::tenders;
+    use crate::terminal_auth::{self, TerminalVerification};
+    use crate::upload_queue;
+    use tauri::Emitter;
 
-    #[derive(Debug, Clone, serde::Serialize)]
+    #[derive(Debug, Clone, serde::Serialize, specta::Type)]
     pub struct DeviceInfo {
         pub vendor_id: u16,
         pub device_id: u16,
         pub name: String,
     }
 
-    /// 商品明細
-    #[derive(Debug, Clone, Deserialize)]
-    pub struct ReceiptItem {
-        /// 出版サークル名
-        pub circle_name: String,
-        /// 商品名
-        pub name: String,
-        /// JAN
-        pub jan: String,
-        /// ISBN
-        pub isbn: String,
-        /// ISDN（書籍の場合）
-        pub isdn: Option<String>,
-        /// 2段目バーコード（Cコード＋値段、書籍の場合）
-        pub jan2: Option<String>,
-        /// 書籍フラグ
-        pub is_book: bool,
-        /// 商品数
-        pub quantity: u32,
-        /// 値段（単価 x 数量）
-        pub price: u32,
-    }
-
-    /// 支払情報
-    #[derive(Debug, Clone, Deserialize)]
-    pub struct PaymentInfo {
-        /// 支払手段名（現金、クレジットカードなど）
-        pub method: String,
-        /// 支払金額
-        pub amount: u32,
-    }
-
-    /// カード詳細情報（クレジット売上票用）
-    #[derive(Debug, Clone, Deserialize)]
-    pub struct CardDetails {
-        /// カードブランド（visa, mastercard等）
-        pub brand: Option<String>,
-        /// カード番号下4桁
-        pub last4: Option<String>,
-        /// 有効期限（月）
-        pub exp_month: Option<u32>,
-        /// 有効期限（年）
-        pub exp_year: Option<u32>,
-        /// カード名義人
-        pub cardholder_name: Option<String>,
-        /// カード種別（credit, debit等）
-        pub funding: Option<String>,
-        /// 端末シリアル番号
-        pub terminal_serial_number: Option<String>,
-        /// 加盟店名（Stripeアカウント名）
-        pub merchant_name: Option<String>,
-        /// 取引種別（sale/refund）
-        pub transaction_type: Option<String>,
-        /// 支払区分
-        pub payment_type: Option<String>,
-        /// 取引日時（ISO8601形式）
-        pub transaction_at: Option<String>,
-    }
-
-    /// レシートデータ
-    #[derive(Debug, Clone, Deserialize)]
-    pub struct ReceiptData {
-        /// イベント名称
-        pub event_name: String,
-        /// サークル名（トップに大きく表示）
-        pub circle_name: Option<String>,
-        /// 会場住所
-        pub venue_address: Option<String>,
-        /// 発売日時
-        pub sale_start_date_time: Option<String>,
-        /// スタッフ番号
-        pub staff_id: String,
-        /// 宛名（様の前に表示、未使用）
-        pub customer_name: Option<String>,
-        /// 商品明細リスト
-        pub items: Vec<ReceiptItem>,
-        /// 合計金額
-        pub total: u32,
-        /// 支払情報リスト
-        pub payments: Vec<PaymentInfo>,
-        /// 消費税率（%）
-        pub tax_rate: u32,
-        /// 消費税金額
-        pub tax_amount: u32,
-        /// レシート番号
-        pub receipt_number: String,
-        /// カード詳細情報（クレジット決済時）
-        pub card_details: Option<CardDetails>,
-        /// Stripe PaymentIntent ID（クレジット決済時）
-        pub payment_intent_id: Option<String>,
-    }
+    /// シミュレーションモードで一覧に出すプリンター
+    const SIMULATED_DEVICE_NAME: &str = "Simulated printer";
 
+    /// 接続されているプリンターの一覧（設定したシリアルプリンター・ネットワークプリンターを含む）
     #[tauri::command]
+    #[specta::specta]
     pub fn get_usb_devices() -> Result<Vec<DeviceInfo>, String> {
+        let config = config::load()?;
+        if config.simulation_mode {
+            return Ok(vec![DeviceInfo {
+                vendor_id: 0,
+                device_id: 0,
+                name: SIMULATED_DEVICE_NAME.to_string(),
+            }]);
+        }
+
+        let mut devices = list_usb_devices()?;
+        devices.extend(
+            serial_printer::devices(&config.serial_printers).map(|(device_id, printer)| DeviceInfo {
+                vendor_id: serial_printer::SERIAL_VENDOR_ID,
+                device_id,
+                name: printer.name.clone(),
+            }),
+        );
+        devices.extend(
+            network_printer::devices(&config.network_printers).map(|(device_id, printer)| DeviceInfo {
+                vendor_id: network_printer::NETWORK_VENDOR_ID,
+                device_id,
+                name: printer.name.clone(),
+            }),
+        );
+        Ok(devices)
+    }
+
+    #[cfg(not(target_os = "ios"))]
+    fn list_usb_devices() -> Result<Vec<DeviceInfo>, String> {
         use nusb::MaybeFuture;
 
         let device_list = nusb::list_devices()
@@ -138,6 +310,29 @@ mod desktop_printer {
         Ok(devices)
     }
 
+    /// iOSではUSBプリンターを使えない
+    #[cfg(target_os = "ios")]
+    fn list_usb_devices() -> Result<Vec<DeviceInfo>, String> {
+        Ok(vec![])
+    }
+
+    /// USBデバイスの製品名を取得
+    #[cfg(not(target_os = "ios"))]
+    fn usb_product_name(vendor_id: u16, device_id: u16) -> Option<String> {
+        use nusb::MaybeFuture;
+
+        nusb::list_devices()
+            .wait()
+            .ok()?
+            .find(|d| d.vendor_id() == vendor_id && d.product_id() == device_id)
+            .and_then(|d| d.product_string().map(|s| s.to_string()))
+    }
+
+    #[cfg(target_os = "ios")]
+    fn usb_product_name(_vendor_id: u16, _device_id: u16) -> Option<String> {
+        None
+    }
+
     fn parse_paper_width(paper_width: Option<u8>) -> PaperWidth {
         match paper_width {
             Some(80) => PaperWidth::Mm80,
@@ -145,27 +340,171 @@ mod desktop_printer {
         }
     }
 
-    #[tauri::command]
-    pub fn welcome_print(
+    /// ベンダーID・デバイスIDで指定したプリンターを開いて初期化
+    fn open_usb_printer(
         vendor_id: u16,
         device_id: u16,
-        id: String,
         paper_width: Option<u8>,
-    ) -> Result<(), String> {
-        let driver = NativeUsbDriver::open(vendor_id, device_id)
-            .map_err(|e| e.to_string())?;
+    ) -> Result<JpPrinter<PrinterDriver>, String> {
+        open_printer(&PrinterTarget::device(vendor_id, device_id), paper_width)
+    }
 
+    /// 印刷先を開いて初期化（プロファイルでラスターモードが指定されていれば切り替える）
+    ///
+    /// プロファイルはベンダーID・デバイスIDで指定した印刷先のみ適用する
+    fn open_printer(target: &PrinterTarget, paper_width: Option<u8>) -> Result<JpPrinter<PrinterDriver>, String> {
         let width = parse_paper_width(paper_width);
+        let driver = target::open_driver(target, width)?;
         let mut printer = JpPrinter::with_paper_width(driver, width);
+        // シミュレーションでは用紙を使わない
+        if target.uses_paper() && !config::load()?.simulation_mode {
+            printer.meter_paper_usage();
+        }
+
+        let profile = match target.ids() {
+            Some((vendor_id, device_id)) => {
+                printer_profile::find(&printer_profile::usb_profile_id(vendor_id, device_id))?
+            }
+            None => None,
+        };
+        let qr_command_set = profile
+            .as_ref()
+            .and_then(|p| p.qr_command_set)
+            .unwrap_or_else(|| QrCommandSet::detect(target.ids().map(|(vendor_id, _)| vendor_id).unwrap_or_default()));
+        printer.set_qr_command_set(qr_command_set);
+        printer.set_media_mode(profile.as_ref().map(|p| p.media_mode).unwrap_or_default());
+
+        if let Some(profile) = profile.filter(|p| p.render_mode == RenderMode::Raster) {
+            let font = raster::load_font(profile.raster_font_path.as_deref())?;
+            printer.enable_raster(font);
+        }
+
         printer.init()?;
+        // ラベル紙は印字開始位置に合わせてから印刷する
+        printer.align_to_print_start()?;
+        // 本番以外の環境で印刷したものは売上と区別できるよう先頭に明記する（ラベル紙は大きさが決まっているため除く）
+        let environment = environment::current()?;
+        if !environment.is_production() && printer.media_mode() == MediaMode::Continuous {
+            printer.jp_textln_padded(
+                &print_strings::fill(
+                    &print_strings::current().receipt.test_environment,
+                    &[("environment", &environment.name().to_uppercase())],
+                ),
+                TextStyle::default().bold().reverse().center(),
+            )?;
+        }
+        Ok(printer)
+    }
+
+    /// 端末のシリアルポートの一覧（シリアルプリンターの設定用）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn list_serial_ports() -> Result<Vec<SerialPortInfo>, String> {
+        list_serial_ports_impl()
+    }
+
+    #[cfg(not(target_os = "ios"))]
+    fn list_serial_ports_impl() -> Result<Vec<SerialPortInfo>, String> {
+        serial::available_ports()
+    }
+
+    #[cfg(target_os = "ios")]
+    fn list_serial_ports_impl() -> Result<Vec<SerialPortInfo>, String> {
+        Ok(vec![])
+    }
+
+    /// ペアリング済みの Bluetooth（SPP）プリンターの一覧
+    ///
+    /// ペアリングは OS の Bluetooth 設定で行う（ペアリングするとシリアルポートが作られる）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn list_bluetooth_printers() -> Result<Vec<BluetoothSerialPort>, String> {
+        target::bluetooth_ports()
+    }
+
+    /// Bluetooth（SPP）プリンターのペアリング・接続状態（接続できるかは実際に開いて確かめる）
+    #[tauri::command(async)]
+    #[specta::specta]
+    pub fn bluetooth_printer_status(port: String) -> Result<BluetoothLinkStatus, String> {
+        if !target::bluetooth_ports()?.iter().any(|p| p.port == port) {
+            return Ok(BluetoothLinkStatus::NotPaired);
+        }
+        Ok(match target::open_driver(&PrinterTarget::Bluetooth { port }, PaperWidth::default()) {
+            Ok(_) => BluetoothLinkStatus::Connected,
+            Err(_) => BluetoothLinkStatus::Paired,
+        })
+    }
+
+    #[tauri::command]
+    #[specta::specta]
+    pub fn welcome_print(
+        target: PrinterTarget,
+        id: String,
+        paper_width: Option<u8>,
+    ) -> Result<(), String> {
+        let page = config::load()?.welcome_page;
+        let mut printer = open_printer(&target, paper_width)?;
 
-        printer.jp_textln("WELCOME TO mizPOS", TextStyle::default().bold().underline().center())?;
+        if let Some(ref logo) = page.logo_text {
+            printer.jp_textln_padded(logo, TextStyle::default().double().reverse().center())?;
+            printer.textln("")?;
+        }
+        printer.jp_textln(&page.title, TextStyle::default().bold().underline().center())?;
         printer.textln("")?;
-        printer.jp_textln("mizPOS デスクトップターミナル", TextStyle::default().center())?;
-        printer.jp_textln("接続テスト完了", TextStyle::default().center())?;
+        for line in &page.lines {
+            printer.jp_textln(line, TextStyle::default().center())?;
+        }
         printer.textln("")?;
         printer.separator()?;
         printer.row_auto("ターミナルID:", &id)?;
+        if page.terminal_id_qr {
+            printer.qr_code_center(&id, Some(4))?;
+        }
+        printer.separator()?;
+
+        // 設定確認用のプリンター情報
+        let config = config::load()?;
+        let (profile, model) = match target {
+            PrinterTarget::Device { vendor_id, device_id } => (
+                printer_profile::find(&printer_profile::usb_profile_id(vendor_id, device_id))?,
+                network_printer::find(&config.network_printers, vendor_id, device_id)
+                    .map(|p| format!("{}:{}", p.host, p.port))
+                    .or_else(|| {
+                        serial_printer::find(&config.serial_printers, vendor_id, device_id)
+                            .map(|p| format!("{} ({}bps)", p.port, p.baud_rate))
+                    })
+                    .or_else(|| usb_product_name(vendor_id, device_id))
+                    .unwrap_or_else(|| format!("{:04x}:{:04x}", vendor_id, device_id)),
+            ),
+            PrinterTarget::Network { host, port } => (None, format!("{}:{}", host, port)),
+            PrinterTarget::Serial { port, baud_rate, .. } => (None, format!("{} ({}bps)", port, baud_rate)),
+            PrinterTarget::Bluetooth { port } => (None, format!("Bluetooth {}", port)),
+            PrinterTarget::File { path } => (None, path),
+        };
+        printer.row_auto("機種:", &model)?;
+        printer.row_auto(
+            "プロファイル:",
+            profile.as_ref().map(|p| p.name.as_str()).unwrap_or("(未設定)"),
+        )?;
+        printer.row_auto(
+            "印刷方式:",
+            if printer.is_raster() { "ラスター" } else { "テキスト" },
+        )?;
+        printer.row_auto(
+            "用紙幅:",
+            match printer.paper_width() {
+                PaperWidth::Mm58 => "58mm",
+                PaperWidth::Mm80 => "80mm",
+            },
+        )?;
+        printer.row_auto(
+            "用紙:",
+            match printer.media_mode() {
+                MediaMode::Continuous => "連続紙",
+                MediaMode::BlackMark => "ブラックマーク",
+                MediaMode::Gap => "ラベル",
+            },
+        )?;
         printer.separator()?;
         printer.textln("")?;
         printer.jp_textln("日本語印刷テスト", TextStyle::default().bold())?;
@@ -179,618 +518,3926 @@ mod desktop_printer {
         Ok(())
     }
 
+    /// プリンターの動作確認ページ印刷
+    ///
+    /// レシートで使う機能（文字装飾・漢字・QR・バーコード・画像・カット）を見出し付きで一通り印刷する
     #[tauri::command]
-    pub fn text_print(
+    #[specta::specta]
+    pub fn print_test_page(
         vendor_id: u16,
         device_id: u16,
-        text: String,
         paper_width: Option<u8>,
     ) -> Result<(), String> {
-        let driver = NativeUsbDriver::open(vendor_id, device_id)
-            .map_err(|e| e.to_string())?;
+        print_test_page_to(PrinterTarget::device(vendor_id, device_id), paper_width)
+    }
 
-        let width = parse_paper_width(paper_width);
-        let mut printer = JpPrinter::with_paper_width(driver, width);
-        printer.init()?;
-        printer.textln(&text)?;
+    /// 印刷先を指定して動作確認ページ印刷
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_test_page_to(target: PrinterTarget, paper_width: Option<u8>) -> Result<(), String> {
+        let mut printer = open_printer(&target, paper_width)?;
+        let fmt = config::load()?.number_format;
+        let chars = printer.chars_per_line();
+
+        printer.jp_textln_padded("印刷テスト", TextStyle::default().double().reverse().center())?;
+        printer.textln("")?;
+
+        // 桁位置の確認（1行の文字数ちょうどの目盛り）
+        printer.jp_textln("[桁位置]", TextStyle::default().bold())?;
+        let ruler: String = (1..=chars).map(|i| char::from(b'0' + (i % 10) as u8)).collect();
+        printer.textln(&ruler)?;
+        printer.separator()?;
+
+        printer.jp_textln("[文字装飾]", TextStyle::default().bold())?;
+        printer.jp_textln("標準 Normal", TextStyle::default())?;
+        printer.jp_textln("太字 Bold", TextStyle::default().bold())?;
+        printer.jp_textln("下線 Underline", TextStyle::default().underline())?;
+        printer.jp_textln("反転 Reverse", TextStyle::default().reverse())?;
+        printer.jp_textln("倍幅", TextStyle::default().double_width())?;
+        printer.jp_textln("倍高", TextStyle::default().double_height())?;
+        printer.jp_textln("2倍角", TextStyle::default().double())?;
+        printer.jp_textln("中央揃え", TextStyle::default().center())?;
+        printer.jp_textln("右揃え", TextStyle::default().right())?;
+        printer.separator()?;
+
+        printer.jp_textln("[漢字・記号]", TextStyle::default().bold())?;
+        printer.textln("ひらがな: あいうえお")?;
+        printer.textln("カタカナ: アイウエオ ｱｲｳｴｵ")?;
+        printer.textln("漢字: 東京都渋谷区 髙﨑")?;
+        printer.textln("記号: ※〒①")?;
+        printer.row_auto("左右揃え:", &fmt.price(12345u32))?;
+        printer.separator()?;
+
+        printer.jp_textln("[QRコード]", TextStyle::default().bold())?;
+        printer.qr_code_center("MIZPOS-TEST", Some(6))?;
+        printer.separator()?;
+
+        printer.jp_textln("[バーコード CODE128]", TextStyle::default().bold())?;
+        printer.code128_center("MIZPOS-TEST", None)?;
+        printer.separator()?;
+
+        // 画像（GS v 0）は同梱フォントで描画した縦書きブロックで確認する
+        printer.jp_textln("[画像]", TextStyle::default().bold())?;
+        if let Err(e) = printer.jp_vertical_block("縦書\n画像", Align::Center) {
+            printer.jp_textln(&format!("画像印刷不可: {}", e), TextStyle::default())?;
+        }
+        printer.separator()?;
+
+        printer.jp_textln("[パーシャルカット]", TextStyle::default().bold())?;
+        printer.feed(3)?;
+        printer.partial_cut()?;
+        printer.jp_textln("[フルカット]", TextStyle::default().bold())?;
         printer.feed(3)?;
         printer.cut()?;
 
         Ok(())
     }
 
-    /// 数値を全角数字に変換
-    fn to_fullwidth_number(num: u32) -> String {
-        num.to_string()
-            .chars()
-            .map(|c| match c {
-                '0' => '０',
-                '1' => '１',
-                '2' => '２',
-                '3' => '３',
-                '4' => '４',
-                '5' => '５',
-                '6' => '６',
-                '7' => '７',
-                '8' => '８',
-                '9' => '９',
-                _ => c,
-            })
-            .collect()
+    #[tauri::command]
+    #[specta::specta]
+    pub fn text_print(
+        vendor_id: u16,
+        device_id: u16,
+        text: String,
+        paper_width: Option<u8>,
+    ) -> Result<(), String> {
+        text_print_to(PrinterTarget::device(vendor_id, device_id), text, paper_width)
     }
 
-    /// 金額をフォーマット（カンマ区切り + 円）
-    /// 全角￥（U+FFE5）を使用（Shift-JISで半角¥と\は同じコードのため）
-    fn format_price(price: u32) -> String {
-        let s = price.to_string();
-        let mut result = String::new();
-        for (i, c) in s.chars().rev().enumerate() {
-            if i > 0 && i % 3 == 0 {
-                result.insert(0, ',');
-            }
-            result.insert(0, c);
-        }
-        format!("￥{}", result)
-    }
+    /// 印刷先を指定してテキストを印刷
+    #[tauri::command]
+    #[specta::specta]
+    pub fn text_print_to(target: PrinterTarget, text: String, paper_width: Option<u8>) -> Result<(), String> {
+        let mut printer = open_printer(&target, paper_width)?;
+        printer.textln(&text)?;
+        printer.feed(3)?;
+        printer.cut()?;
 
-    /// ISDN + jan2からCコード＋値段の表示文字列を生成
-    fn format_book_number(isdn: &Option<String>, jan2: &Option<String>) -> Option<String> {
-        let isdn_str = isdn.as_ref()?;
-        let jan2_str = jan2.as_ref()?;
+        Ok(())
+    }
 
-        if isdn_str.is_empty() || jan2_str.len() < 12 {
-            return None;
-        }
+    /// バーコードを1枚印刷（棚札・取り置き札の貼り替え用）
+    ///
+    /// EAN-13・EAN-8 はチェックデジットを含めて指定し、合わない場合は印刷しない
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_barcode(
+        target: PrinterTarget,
+        data: String,
+        symbology: BarcodeSymbology,
+        options: Option<BarcodeOptions>,
+        paper_width: Option<u8>,
+    ) -> Result<(), String> {
+        let mut printer = open_printer(&target, paper_width)?;
+        printer.barcode_center(symbology, &data, options.unwrap_or_default())?;
+        printer.feed(3)?;
+        printer.cut()?;
 
-        // jan2からCコードを抽出（例: 1920094001600 → C0094）
-        let c_code = format!("C{}", &jan2_str[3..7]);
+        Ok(())
+    }
 
-        // jan2から値段を抽出
-        let price_str = &jan2_str[8..12];
-        let price_value: u32 = price_str.trim_start_matches('0').parse().unwrap_or(0);
+    /// 同じサブネットでRAW印刷のポート（既定は9100番）に接続できる機器を探す
+    #[tauri::command(async)]
+    #[specta::specta]
+    pub fn discover_network_printers(
+        port: Option<u16>,
+    ) -> Result<Vec<network_printer::NetworkPrinter>, String> {
+        network_printer::discover(port.unwrap_or(9100))
+    }
 
-        Some(format!("{} {} {}", isdn_str, c_code, format_price(price_value)))
+    /// アドレスを指定してネットワークプリンターに接続確認を印刷（設定に追加する前の確認用）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn network_print_test_page(
+        host: String,
+        port: u16,
+        paper_width: Option<u8>,
+    ) -> Result<(), String> {
+        let target = PrinterTarget::Network { host: host.clone(), port };
+        let mut printer = open_printer(&target, paper_width)?;
+        printer.jp_textln_padded("接続確認", TextStyle::default().double().reverse().center())?;
+        printer.textln("")?;
+        printer.row_auto("アドレス:", &format!("{}:{}", host, port))?;
+        printer.row_auto("1行の文字数:", &printer.chars_per_line().to_string())?;
+        printer.jp_textln("この用紙が出ていれば接続できています", TextStyle::default().center())?;
+        printer.feed(3)?;
+        printer.cut()?;
+        Ok(())
     }
 
-    /// レシート印刷
+    /// アドレスを指定してネットワークプリンターにテキストを印刷
     #[tauri::command]
-    pub fn print_receipt(
+    #[specta::specta]
+    pub fn network_print_text(
+        host: String,
+        port: u16,
+        text: String,
+        paper_width: Option<u8>,
+    ) -> Result<(), String> {
+        text_print_to(PrinterTarget::Network { host, port }, text, paper_width)
+    }
+
+    /// 閉局リマインダー伝票印刷
+    pub fn print_closing_reminder_slip(
         vendor_id: u16,
         device_id: u16,
-        receipt: ReceiptData,
         paper_width: Option<u8>,
     ) -> Result<(), String> {
-        let driver = NativeUsbDriver::open(vendor_id, device_id)
-            .map_err(|e| e.to_string())?;
+        let mut printer = open_usb_printer(vendor_id, device_id, paper_width)?;
 
-        let width = parse_paper_width(paper_width);
-        let mut printer = JpPrinter::with_paper_width(driver, width);
-        printer.init()?;
+        printer.jp_textln_padded("閉局してください", TextStyle::default().double().reverse().center())?;
+        printer.textln("")?;
+        printer.jp_textln("イベント終了時刻を過ぎています。", TextStyle::default())?;
+        printer.jp_textln("売上を確定し閉局処理を行ってください。", TextStyle::default())?;
+        printer.feed(3)?;
+        printer.cut()?;
 
-        // サークル名（トップに大きく表示）
-        if let Some(ref circle_name) = receipt.circle_name {
-            if !circle_name.is_empty() {
-                printer.jp_textln_padded(circle_name, TextStyle::default().double().center())?;
-            }
-        }
+        Ok(())
+    }
 
-        // イベント名・会場住所（サークル名の下に表示）
-        if let Some(ref venue_address) = receipt.venue_address {
-            if !venue_address.is_empty() && !receipt.event_name.is_empty() {
-                printer.jp_textln(&receipt.event_name, TextStyle::default().bold())?;
-                printer.jp_textln(venue_address, TextStyle::default())?;
-            }
-        }
+    /// サポート向けの端末確認票を印刷
+    ///
+    /// 確認コードを大きく印字し、サポート側で計算したコードと読み合わせる
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_terminal_verification(
+        target: PrinterTarget,
+        public_key: String,
+        challenge: String,
+        paper_width: Option<u8>,
+    ) -> Result<TerminalVerification, String> {
+        let verification =
+            terminal_auth::verify_terminal(&public_key, &challenge).map_err(|e| e.to_string())?;
 
-        // ご明細書（黒背景中央揃え文字２倍サイズ）
-        printer.jp_textln_padded("ご明細書", TextStyle::default().double().reverse().center())?;
+        let mut printer = open_printer(&target, paper_width)?;
+        printer.jp_textln("端末確認", TextStyle::default().bold().center())?;
+        printer.separator()?;
+        printer.jp_textln(&format!("端末ID: {}", verification.terminal_id), TextStyle::default())?;
+        printer.jp_textln(&format!("チャレンジ: {}", verification.challenge), TextStyle::default())?;
+        printer.jp_textln(
+            if verification.key_matches { "公開鍵: 一致" } else { "公開鍵: 不一致" },
+            TextStyle::default(),
+        )?;
+        printer.textln("")?;
+        printer.jp_textln("確認コード", TextStyle::default().center())?;
+        printer.jp_textln(&verification.code, TextStyle::default().double().center())?;
+        printer.feed(3)?;
+        printer.cut()?;
 
-        // レシート番号
-        printer.jp_textln(&format!("# {}", receipt.receipt_number), TextStyle::default())?;
+        Ok(verification)
+    }
 
-        // 発売日時 責: {スタッフ番号}
-        if let Some(ref sale_date_time) = receipt.sale_start_date_time {
-            printer.jp_textln(&format!("{} 責: {}", sale_date_time, receipt.staff_id), TextStyle::default())?;
-        } else {
-            printer.jp_textln(&format!("責: {}", receipt.staff_id), TextStyle::default())?;
-        }
+    /// ギフト券を発行して印刷
+    ///
+    /// 発行した時点でジャーナルに記録するため、印刷に失敗した場合は券番号を指定して再印刷する
+    #[tauri::command]
+    #[specta::specta]
+    pub fn issue_gift_voucher(
+        target: PrinterTarget,
+        amount: u32,
+        staff_id: String,
+        paper_width: Option<u8>,
+    ) -> Result<gift_vouchers::GiftVoucher, String> {
+        let voucher = gift_vouchers::issue(amount, &staff_id)?;
+        print_gift_voucher(target, voucher.serial.clone(), paper_width)?;
+        Ok(voucher)
+    }
 
-        printer.separator()?;
+    /// 発行済みのギフト券を印刷（未使用のもののみ）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_gift_voucher(
+        target: PrinterTarget,
+        serial: String,
+        paper_width: Option<u8>,
+    ) -> Result<(), String> {
+        let voucher = gift_vouchers::validate(&serial)?;
+        let config = config::load()?;
 
-        // 商品明細
-        for item in &receipt.items {
-            // 商品番号: 書籍の場合は「ISDN Cコード 値段」、それ以外はJAN
-            let display_number = if item.is_book {
-                format_book_number(&item.isdn, &item.jan2).unwrap_or_else(|| item.jan.clone())
-            } else {
-                item.jan.clone()
-            };
+        let mut printer = open_printer(&target, paper_width)?;
+        render_gift_voucher(&mut printer, &voucher, &config)?;
+        printer.feed(3)?;
+        printer.cut()?;
 
-            printer.jp_textln(&display_number, TextStyle::default().bold())?;
-            printer.jp_textln(&format!("{} / {}", item.circle_name, item.name), TextStyle::default())?;
+        Ok(())
+    }
 
-            // 単価を計算
-            let unit_price = if item.quantity > 0 { item.price / item.quantity } else { item.price };
-            // @ {単価} {点数}点 {小計} （右寄せ・太字）
-            printer.jp_textln(
-                &format!("@ {}　 {} 点　{}", format_price(unit_price), item.quantity, format_price(item.price)),
-                TextStyle::default().right().bold()
-            )?;
+    fn render_gift_voucher<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        voucher: &gift_vouchers::GiftVoucher,
+        config: &config::TerminalConfig,
+    ) -> Result<(), String> {
+        printer.jp_textln_padded("ギフト券", TextStyle::default().double().reverse().center())?;
+        printer.textln("")?;
+        if let Some(ref shop_name) = config.receipt_header.shop_name {
+            printer.jp_textln(shop_name, TextStyle::default().bold().center())?;
         }
-
+        printer.jp_textln_padded(
+            &config.number_format.price(voucher.amount),
+            TextStyle::default().double().center(),
+        )?;
+        printer.textln("")?;
+        printer.row_auto("券番号:", &voucher.serial)?;
+        printer.row_auto(
+            "発行日時:",
+            &datetime::display_unix(voucher.issued_at, &config.timezone),
+        )?;
         printer.separator()?;
+        printer.code128_center(&voucher.serial, None)?;
+        printer.qr_code_center(&voucher.qr_data(), Some(4))?;
+        printer.jp_textln("1回限り有効です。おつりは出ません", TextStyle::default().center())?;
 
-        // 合計（税込）（太字・右寄せ）
-        printer.row_auto_bold("合計(税込)", &format_price(receipt.total))?;
+        Ok(())
+    }
 
-        // 内税表示（税率と税額）
-        if receipt.tax_rate > 0 && receipt.tax_amount > 0 {
-            printer.row_auto(
-                &format!("(内 {}%税)", receipt.tax_rate),
-                &format_price(receipt.tax_amount)
-            )?;
-        }
+    /// 保留取引の呼び出し伝票印刷
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_parked_slip(
+        target: PrinterTarget,
+        code: String,
+        paper_width: Option<u8>,
+    ) -> Result<(), String> {
+        let sale = parked_sales::find(&code)?
+            .ok_or_else(|| format!("Parked sale not found: {}", code))?;
 
-        // 支払情報
-        for payment in &receipt.payments {
-            printer.row_auto(&format!("　 {}", payment.method), &format_price(payment.amount))?;
+        let mut printer = open_printer(&target, paper_width)?;
+        render_parked_slip(&mut printer, &sale, &config::load()?.number_format)?;
+        printer.feed(3)?;
+        printer.cut()?;
+
+        Ok(())
+    }
+
+    /// 呼び出し中の番号の呼び出し票を印刷（呼び直しの場合は回数を添える）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_recall_slip(target: PrinterTarget, paper_width: Option<u8>) -> Result<(), String> {
+        let state = now_serving::current()?;
+        if state.current.is_none() {
+            return Err("No number is being called".to_string());
         }
 
-        // 釣り銭計算（現金支払いの場合）
-        let cash_payment = receipt.payments.iter().find(|p| p.method == "現金");
-        if let Some(cash) = cash_payment {
-            let change = cash.amount.saturating_sub(receipt.total);
-            if change > 0 {
-                printer.row_auto("　 釣り銭", &format_price(change))?;
-            }
+        let mut printer = open_printer(&target, paper_width)?;
+        render_recall_slip(&mut printer, &state, &config::load()?)?;
+        printer.feed(3)?;
+        printer.cut()?;
+
+        Ok(())
+    }
+
+    /// 呼び出し票を描画
+    fn render_recall_slip<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        state: &now_serving::NowServing,
+        config: &config::TerminalConfig,
+    ) -> Result<(), String> {
+        let Some(ref code) = state.current else {
+            return Ok(());
+        };
+        printer.jp_textln_padded("お呼び出し", TextStyle::default().double().reverse().center())?;
+        printer.textln("")?;
+        printer.jp_textln_padded(code, TextStyle::default().double().center())?;
+        printer.textln("")?;
+        if state.call_count > 1 {
+            printer.jp_textln(&format!("{}回目のお呼び出し", state.call_count), TextStyle::default().bold().center())?;
         }
+        if let Some(called_at) = state.called_at {
+            printer.row_auto("呼出日時:", &datetime::display_unix(called_at, &config.timezone))?;
+        }
+        printer.separator()?;
+        printer.jp_textln("番号をお呼びしました", TextStyle::default().center())?;
+        printer.jp_textln("レジまでお越しください", TextStyle::default().center())?;
 
+        Ok(())
+    }
+
+    /// お預かり票を描画
+    fn render_parked_slip<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        sale: &parked_sales::ParkedSale,
+        fmt: &NumberFormat,
+    ) -> Result<(), String> {
+        printer.jp_textln_padded("お預かり票", TextStyle::default().double().reverse().center())?;
+        printer.textln("")?;
+        printer.jp_textln_padded(&sale.code, TextStyle::default().double().center())?;
+        printer.textln("")?;
+        printer.row_auto("点数:", &format!("{}点", sale.items.iter().map(|i| i.quantity).sum::<u32>()))?;
+        printer.row_auto_bold("合計(税込):", &fmt.price(sale.total))?;
+        if let Some(ref memo) = sale.memo {
+            printer.jp_textln(memo, TextStyle::default())?;
+        }
         printer.separator()?;
+        printer.code128_center(&sale.code, None)?;
+        printer.jp_textln("お会計の際にこの票をお渡しください", TextStyle::default().center())?;
 
-        // 免税事業者の説明文
-        printer.jp_textln("当店は免税事業者であり、適格請求書を発行することはできません。返品・返金は落丁・乱丁の場合のみ受け付けます。返品・返金の場合は本明細書を添付しサポートセンター support-pos@miz.cabにご連絡ください。", TextStyle::default())?;
+        Ok(())
+    }
+
+    /// 売上目標の進捗を印刷（目標と実績を並べた短い帳票）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_sales_progress(
+        target: PrinterTarget,
+        paper_width: Option<u8>,
+    ) -> Result<SalesProgress, String> {
+        let progress = sales_targets::progress()?;
+        let mut printer = open_printer(&target, paper_width)?;
+        render_sales_progress(&mut printer, &progress, &config::load()?)?;
+        printer.feed(3)?;
+        printer.cut()?;
+
+        Ok(progress)
+    }
+
+    /// 売上目標の進捗を描画
+    fn render_sales_progress<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        progress: &SalesProgress,
+        config: &config::TerminalConfig,
+    ) -> Result<(), String> {
+        let fmt = &config.number_format;
+        printer.jp_textln_padded("売上目標の進捗", TextStyle::default().reverse().center())?;
+        printer.row_auto(
+            "集計日時:",
+            &datetime::display_unix(crate::storage::now_unix(), &config.timezone),
+        )?;
+        printer.row_auto("取引件数:", &format!("{}件", progress.sale_count))?;
+        printer.row_auto_bold("純売上:", &fmt.signed_price(progress.net_amount))?;
+
+        let Some(ref target) = progress.target else {
+            printer.jp_textln("売上目標は設定されていません", TextStyle::default())?;
+            return Ok(());
+        };
+        printer.row_auto("目標:", &fmt.price(target.target_amount))?;
+        if let Some(percent) = progress.achieved_percent {
+            printer.row_auto(&sales_targets::progress_bar(percent), &format!("{}%", percent))?;
+        }
+        if let Some(remaining) = progress.remaining_amount {
+            let label = if remaining == 0 { "目標達成" } else { "目標まであと:" };
+            printer.row_auto_bold(label, &fmt.price(remaining))?;
+        }
+        if let (Some(break_even), Some(reached)) =
+            (target.break_even_amount, progress.break_even_reached)
+        {
+            printer.row_auto("損益分岐点:", &fmt.price(break_even))?;
+            let status = if reached {
+                "損益分岐点を超えました"
+            } else {
+                "損益分岐点まで届いていません"
+            };
+            printer.jp_textln(status, TextStyle::default().bold())?;
+        }
+
+        Ok(())
+    }
+
+    /// 見積・予約票印刷
+    ///
+    /// 見積を保存して印刷する（売上にはならない）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_quote(
+        target: PrinterTarget,
+        items: Vec<ReceiptItem>,
+        valid_until: String,
+        staff_id: String,
+        customer_name: Option<String>,
+        paper_width: Option<u8>,
+    ) -> Result<quotes::Quote, String> {
+        let quote = quotes::create(items, &valid_until, &staff_id, customer_name)?;
+
+        let mut printer = open_printer(&target, paper_width)?;
+        let config = config::load()?;
+        let fmt = &config.number_format;
+
+        printer.jp_textln_padded("御見積書", TextStyle::default().double().reverse().center())?;
+        if let Some(ref name) = quote.customer_name {
+            if !name.is_empty() {
+                printer.jp_textln(&format!("{} 様", name), TextStyle::default().bold())?;
+            }
+        }
+        printer.jp_textln(&format!("# {}", quote.code), TextStyle::default())?;
+        printer.jp_textln(&format!("責: {}", quote.staff_id), TextStyle::default())?;
+        printer.separator()?;
+
+        for item in &quote.items {
+            let item = &config.text_normalization.item(item);
+            printer.jp_textln(&format!("{} / {}", item.circle_name, item.name), TextStyle::default())?;
+            printer.row_auto(&format!("　 {} 点", item.quantity), &fmt.price(item.price))?;
+            print_item_note(&mut printer, item)?;
+        }
 
+        printer.separator()?;
+        printer.row_auto_bold("合計(税込)", &fmt.price(quote.total))?;
+        printer.row_auto("有効期限:", &quote.valid_until)?;
+        printer.separator()?;
+
+        printer.jp_textln("※本票は領収書ではありません。お受け取りの際にご提示ください。", TextStyle::default())?;
         printer.textln("")?;
+        printer.qr_code_center(&quote.qr_data(), Some(6))?;
 
-        // QRコード（レシート番号）
-        printer.qr_code_center(&receipt.receipt_number, Some(6))?;
+        printer.feed(3)?;
+        printer.cut()?;
 
-        // クレジット売上票（カード詳細がある場合のみ）
-        if let Some(ref card) = receipt.card_details {
-            printer.textln("")?;
-            printer.jp_textln_padded("クレジット売上票", TextStyle::default().reverse().center())?;
-            printer.textln("")?;
+        Ok(quote)
+    }
+
+    /// 予約票印刷（受け取り時に提示するQRコード付き）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_reservation_slip(
+        target: PrinterTarget,
+        code: String,
+        paper_width: Option<u8>,
+    ) -> Result<(), String> {
+        let reservation = reservations::find(&code)?
+            .ok_or_else(|| format!("Reservation not found: {}", code))?;
+
+        let mut printer = open_printer(&target, paper_width)?;
+        let config = config::load()?;
+        let fmt = &config.number_format;
 
-            // 加盟店名（Stripeアカウント名を優先、なければサークル名を使用）
-            let merchant_name = card.merchant_name.as_ref()
-                .filter(|s| !s.is_empty())
-                .or(receipt.circle_name.as_ref().filter(|s| !s.is_empty()));
-            if let Some(name) = merchant_name {
-                printer.row_auto("加盟店名:", name)?;
+        printer.jp_textln_padded("予約票", TextStyle::default().double().reverse().center())?;
+        if let Some(ref name) = reservation.customer_name {
+            if !name.is_empty() {
+                printer.jp_textln(&format!("{} 様", name), TextStyle::default().bold())?;
             }
+        }
+        printer.jp_textln(&format!("# {}", reservation.code), TextStyle::default())?;
+        printer.separator()?;
+
+        for item in &reservation.items {
+            let item = &config.text_normalization.item(item);
+            printer.jp_textln_ruby(
+                &item.circle_name,
+                item.circle_name_ruby.as_deref().unwrap_or_default(),
+                TextStyle::default(),
+            )?;
+            printer.jp_textln(&format!("　{}", item.name), TextStyle::default())?;
+            printer.row_auto(&format!("　 {} 点", item.quantity), &fmt.price(item.price))?;
+            print_item_note(&mut printer, item)?;
+        }
+
+        printer.separator()?;
+        printer.row_auto_bold("合計(税込)", &fmt.price(reservation.total))?;
+        if let Some(ref note) = reservation.pickup_note {
+            printer.jp_textln(note, TextStyle::default())?;
+        }
+        printer.separator()?;
+        printer.jp_textln("お受け取りの際にこの票をご提示ください", TextStyle::default().center())?;
+        printer.textln("")?;
+        printer.qr_code_center(&reservation.qr_data(), Some(6))?;
+
+        printer.feed(3)?;
+        printer.cut()?;
+
+        Ok(())
+    }
+
+    /// 予約の受け渡し確認票印刷
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_pickup_confirmation(
+        target: PrinterTarget,
+        code: String,
+        paper_width: Option<u8>,
+    ) -> Result<(), String> {
+        let reservation = reservations::find(&code)?
+            .ok_or_else(|| format!("Reservation not found: {}", code))?;
+        if reservation.status != reservations::ReservationStatus::Redeemed {
+            return Err(format!("Reservation is not redeemed: {}", code));
+        }
+
+        let mut printer = open_printer(&target, paper_width)?;
+        let config = config::load()?;
+        let fmt = &config.number_format;
 
-            // 端末番号
-            if let Some(ref terminal_sn) = card.terminal_serial_number {
-                printer.row_auto("端末番号:", terminal_sn)?;
+        printer.jp_textln_padded("お受取確認", TextStyle::default().double().reverse().center())?;
+        if let Some(ref name) = reservation.customer_name {
+            if !name.is_empty() {
+                printer.jp_textln(&format!("{} 様", name), TextStyle::default().bold())?;
             }
+        }
+        printer.jp_textln(&format!("# {}", reservation.code), TextStyle::default())?;
+        if let Some(ref staff) = reservation.redeemed_by {
+            printer.jp_textln(&format!("責: {}", staff), TextStyle::default())?;
+        }
+        printer.separator()?;
+
+        for item in &reservation.items {
+            let item = &config.text_normalization.item(item);
+            printer.jp_textln_ruby(
+                &item.circle_name,
+                item.circle_name_ruby.as_deref().unwrap_or_default(),
+                TextStyle::default(),
+            )?;
+            printer.jp_textln(&format!("　{}", item.name), TextStyle::default())?;
+            printer.row_auto(&format!("　 {} 点", item.quantity), &fmt.price(item.price))?;
+            print_item_note(&mut printer, item)?;
+        }
+
+        printer.separator()?;
+        printer.row_auto_bold("合計(税込)", &fmt.price(reservation.total))?;
+        printer.separator()?;
+        printer.jp_textln("上記の商品をお渡ししました", TextStyle::default().center())?;
+
+        printer.feed(3)?;
+        printer.cut()?;
+
+        Ok(())
+    }
 
-            // ご利用日時
-            if let Some(ref tx_at) = card.transaction_at {
-                // ISO8601をフォーマット（例: 2025-12-16T10:30:00Z → 2025/12/16 10:30）
-                let formatted = format_transaction_datetime(tx_at);
-                printer.row_auto("ご利用日時:", &formatted)?;
+    /// 明細1行分を印刷
+    fn print_receipt_item<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        item: &ReceiptItem,
+        fmt: &NumberFormat,
+    ) -> Result<(), String> {
+        // 商品番号: 書籍の場合は「ISDN Cコード 値段」、それ以外はJAN
+        // 入りきらない場合は値段を分割せず、値段だけを次の行の右端に印刷する
+        match format_book_number(&item.isdn, &item.jan2, fmt).filter(|_| item.is_book) {
+            Some((label, price)) => {
+                printer.fixed_columns(
+                    &label,
+                    &[(&price, jp_escpos::str_width(&price) + 1)],
+                    TextStyle::default().bold(),
+                )?
             }
+            None => printer.jp_textln(&item.jan, TextStyle::default().bold())?,
+        }
+        printer.jp_textln(&format!("{} / {}", item.circle_name, item.name), TextStyle::default())?;
+
+        // 単価を計算
+        let unit_price = if item.quantity > 0 { item.price / item.quantity } else { item.price };
+        // @ {単価}  {点数}点  {小計} （小計は右端揃え・太字）
+        let (qty_width, subtotal_width) = match printer.paper_width() {
+            PaperWidth::Mm58 => (7, 11),
+            PaperWidth::Mm80 => (9, 14),
+        };
+        printer.fixed_columns(
+            &format!("@ {}", fmt.price(unit_price)),
+            &[
+                (&format!("{} 点", item.quantity), qty_width),
+                (&fmt.price(item.price), subtotal_width),
+            ],
+            TextStyle::default().bold(),
+        )?;
+        // 価格変更した明細は理由と変更前の値段を添える
+        if let Some(ref mark) = item.price_override {
+            let text = print_strings::fill(
+                &print_strings::current().receipt.price_override,
+                &[("reason", &mark.reason), ("price", &fmt.price(mark.original_price))],
+            );
+            printer.jp_textln_small(&text, 2)?;
+        }
+        print_item_note(printer, item)?;
+        // 指定された明細は JAN のバーコードを添える（チェックデジットが合わない JAN は印刷しない）
+        if item.print_barcode && book_code::is_valid_jan(&item.jan) {
+            let options = BarcodeOptions {
+                height: Some(40),
+                ..Default::default()
+            };
+            printer.jan_center(&item.jan, options)?;
+        }
+        Ok(())
+    }
+
+    /// 明細のメモを字下げして印刷（メモがなければ何もしない）
+    fn print_item_note<D: Driver>(printer: &mut JpPrinter<D>, item: &ReceiptItem) -> Result<(), String> {
+        match item.note.as_deref() {
+            Some(note) if !note.is_empty() => printer.jp_textln_small(&format!("※{}", note), 2),
+            _ => Ok(()),
+        }
+    }
+
+    /// ISDN + jan2から「ISDN Cコード」と値段の表示文字列を生成
+    ///
+    /// jan2が不正な場合はNone（呼び出し側でJANを表示する）
+    fn format_book_number(
+        isdn: &Option<String>,
+        jan2: &Option<String>,
+        fmt: &NumberFormat,
+    ) -> Option<(String, String)> {
+        let isdn_str = isdn.as_ref().filter(|s| !s.is_empty())?;
+
+        // jan2からCコードと値段を抽出（例: 1920094016000 → C0094 ￥1,600）
+        let jan2 = book_code::parse_jan2(jan2.as_ref()?).ok()?;
+
+        Some((format!("{} C{}", isdn_str, jan2.c_code), fmt.price(jan2.price)))
+    }
+
+    /// レシートを再発行として印刷する指定
+    #[derive(Debug, Clone, serde::Deserialize, specta::Type)]
+    pub struct ReprintRequest {
+        /// 再発行を操作するスタッフ番号（ジャーナルの再発行記録に残す）
+        pub staff_id: String,
+        /// 再発行の上限を超えるときの責任者承認
+        pub approval: Option<SupervisorApproval>,
+    }
+
+    /// レシート印刷
+    ///
+    /// reprint: 記録済みの売上の写しとして印刷する（再発行(コピー)と明記し、回数をジャーナルに残す）
+    /// 再発行では receipt のレシート番号だけを使い、売上の記録・確認（prepare_sale）と limit_approval は使わない
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_receipt(
+        vendor_id: u16,
+        device_id: u16,
+        receipt: ReceiptData,
+        paper_width: Option<u8>,
+        idempotency_key: Option<String>,
+        limit_approval: Option<SupervisorApproval>,
+        reprint: Option<ReprintRequest>,
+    ) -> Result<(), String> {
+        print_receipt_to(
+            PrinterTarget::device(vendor_id, device_id),
+            receipt,
+            paper_width,
+            idempotency_key,
+            limit_approval,
+            reprint,
+        )
+    }
+
+    /// Bluetooth（SPP）プリンターでレシート印刷（持ち歩くモバイルプリンター用）
+    ///
+    /// 売上の記録とレシートの内容は print_receipt と同じ
+    #[tauri::command(async)]
+    #[specta::specta]
+    pub fn bluetooth_print_receipt(
+        port: String,
+        receipt: ReceiptData,
+        paper_width: Option<u8>,
+        idempotency_key: Option<String>,
+        limit_approval: Option<SupervisorApproval>,
+    ) -> Result<(), String> {
+        print_receipt_to(
+            PrinterTarget::Bluetooth { port },
+            receipt,
+            paper_width,
+            idempotency_key,
+            limit_approval,
+            None,
+        )
+    }
+
+    /// 印刷先を指定してレシート印刷
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_receipt_to(
+        target: PrinterTarget,
+        receipt: ReceiptData,
+        paper_width: Option<u8>,
+        idempotency_key: Option<String>,
+        limit_approval: Option<SupervisorApproval>,
+        reprint: Option<ReprintRequest>,
+    ) -> Result<(), String> {
+        // 再発行は渡された内容ではなくジャーナルの売上記録から印刷し、売上として記録し直さない
+        if let Some(request) = reprint {
+            return reprint_receipt_to(
+                target,
+                receipt.receipt_number,
+                request.staff_id,
+                paper_width,
+                request.approval,
+                idempotency_key,
+            )
+            .map(|_| ());
+        }
+        remote_commands::ensure_unlocked("Sale")?;
+        idempotency::run(idempotency_key.as_deref(), "print_receipt", || {
+            // 印刷前に確認してジャーナルへ記録
+            let mut receipt = receipt;
+            sales::prepare_sale(&mut receipt, limit_approval.as_ref(), SaleChannel::Counter)?;
 
-            // 伝票番号（PaymentIntent ID）
-            if let Some(ref pi_id) = receipt.payment_intent_id {
-                // IDが長い場合は末尾のみ表示
-                let display_id = if pi_id.len() > 16 {
-                    format!("...{}", &pi_id[pi_id.len()-12..])
+            let config = config::load()?;
+            let result = (|| {
+                let mut printer = open_printer(&target, paper_width)?;
+                // クレジット売上票はお客様控として必ず渡すため、カード決済は常に本レシートを印刷する
+                if config.receipt_on_demand && receipt.card_details.is_none() {
+                    render_receipt_stub(&mut printer, &receipt, &config)?;
                 } else {
-                    pi_id.clone()
-                };
-                printer.row_auto("伝票番号:", &display_id)?;
+                    render_receipt(&mut printer, &receipt, None, &config)?;
+                }
+                printer.feed(3)?;
+                printer.cut()
+            })();
+            metrics::record_print(&result);
+            if let Err(ref e) = result {
+                // 決済は済んでいるため、別のプリンターで控えを渡す（ID で指定した印刷先以外は設定済みのプリンターから選ぶ）
+                let failed: Vec<UsbPrinter> = target
+                    .ids()
+                    .map(|(vendor_id, device_id)| UsbPrinter {
+                        vendor_id,
+                        device_id,
+                        paper_width,
+                    })
+                    .into_iter()
+                    .collect();
+                if let Err(fallback_error) = fallback_slip::handle(&receipt, &failed, e) {
+                    return Err(format!("{} (fallback slip: {})", e, fallback_error));
+                }
             }
+            result
+        })
+    }
 
-            printer.separator()?;
+    /// レシート・オン・デマンドの控えを描画（合計とレシート番号のQRのみ）
+    fn render_receipt_stub<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        receipt: &ReceiptData,
+        config: &config::TerminalConfig,
+    ) -> Result<(), String> {
+        let strings = print_strings::current();
+        printer.jp_textln(&receipt.event_name, TextStyle::default().center())?;
+        printer.jp_textln(&format!("# {}", receipt.receipt_number), TextStyle::default().center())?;
+        printer.row_auto_bold(&strings.receipt.total, &config.number_format.price(receipt.total))?;
+        printer.qr_code_center(&receipt.receipt_number, Some(6))?;
+        for line in &strings.receipt.stub_notice {
+            printer.jp_textln(line, TextStyle::default().center())?;
+        }
+        Ok(())
+    }
+
+    /// レシートを印刷した場合の用紙の長さを見積もる（print_receipt と同じ内容を描画して数える）
+    fn estimate_length(
+        receipt: &ReceiptData,
+        paper_width: PaperWidth,
+        config: &config::TerminalConfig,
+    ) -> Result<PaperLength, String> {
+        let driver = MeterDriver::default();
+        let mut printer = JpPrinter::with_paper_width(driver.clone(), paper_width);
+        printer.init()?;
+        if config.receipt_on_demand && receipt.card_details.is_none() {
+            render_receipt_stub(&mut printer, receipt, config)?;
+        } else {
+            render_receipt(&mut printer, receipt, None, config)?;
+        }
+        printer.feed(3)?;
+        printer.cut()?;
+        Ok(driver.length())
+    }
+
+    /// レシートの長さ（mm）と行数を見積もる（印刷はしない）
+    ///
+    /// 大量の明細で長くなるレシートを、印刷前に分割するか確認するために使う
+    #[tauri::command]
+    #[specta::specta]
+    pub fn estimate_receipt_length(
+        receipt: ReceiptData,
+        paper_width: Option<u8>,
+    ) -> Result<PaperLength, String> {
+        estimate_length(&receipt, parse_paper_width(paper_width), &config::load()?)
+    }
+
+    /// レシートの印刷に失敗した売上の控えを描画（レシート番号と合計のみ）
+    fn render_fallback_slip<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        receipt_number: &str,
+        event_name: &str,
+        total: u32,
+        config: &config::TerminalConfig,
+    ) -> Result<(), String> {
+        let strings = print_strings::current();
+        printer.jp_textln_padded(
+            &strings.receipt.fallback_title,
+            TextStyle::default().double().reverse().center(),
+        )?;
+        printer.jp_textln(event_name, TextStyle::default().center())?;
+        printer.jp_textln(&format!("# {}", receipt_number), TextStyle::default().center())?;
+        printer.row_auto_bold(&strings.receipt.total, &config.number_format.price(total))?;
+        printer.qr_code_center(receipt_number, Some(6))?;
+        for line in &strings.receipt.fallback_notice {
+            printer.jp_textln(line, TextStyle::default().center())?;
+        }
+        Ok(())
+    }
+
+    /// レシート・オン・デマンドの控えのQRから本レシートを印刷
+    ///
+    /// 1回目は再発行扱いにしない（2回目以降は reprint_receipt を使う）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_receipt_from_stub(
+        target: PrinterTarget,
+        scanned: String,
+        staff_id: String,
+        paper_width: Option<u8>,
+        idempotency_key: Option<String>,
+    ) -> Result<String, String> {
+        idempotency::run(idempotency_key.as_deref(), "print_receipt_from_stub", || {
+            let receipt_number = receipt_qr::receipt_number_from_scan(&scanned)?;
+            let receipt = journal::find_sale(&receipt_number)?
+                .ok_or_else(|| format!("Receipt not found: {}", receipt_number))?;
+            if journal::receipt_issued(&receipt_number)? {
+                return Err(format!("Receipt has already been issued: {}", receipt_number));
+            }
+
+            let mut printer = open_printer(&target, paper_width)?;
+            render_receipt(&mut printer, &receipt, None, &config::load()?)?;
+            printer.feed(3)?;
+            printer.cut()?;
+
+            journal::append(
+                Some(&staff_id),
+                journal::JournalRecord::ReceiptIssued {
+                    receipt_number: receipt_number.clone(),
+                },
+            )?;
+
+            Ok(receipt_number)
+        })
+    }
+
+    /// ギフトレシート（金額非表示）を印刷
+    ///
+    /// ジャーナルの売上記録から、商品名と点数だけを載せたレシートを描画する（取消済みの売上は印刷しない）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_gift_receipt(
+        target: PrinterTarget,
+        receipt_number: String,
+        paper_width: Option<u8>,
+    ) -> Result<(), String> {
+        let receipt = journal::find_sale(&receipt_number)?
+            .ok_or_else(|| format!("Receipt not found: {}", receipt_number))?;
+        if journal::sale_status(&receipt)? == journal::SaleStatus::Voided {
+            return Err(format!("Receipt has been voided: {}", receipt_number));
+        }
+
+        let mut printer = open_printer(&target, paper_width)?;
+        render_gift_receipt(&mut printer, &receipt, &config::load()?)?;
+        printer.feed(3)?;
+        printer.cut()?;
+
+        Ok(())
+    }
+
+    /// ギフトレシートを描画
+    ///
+    /// 単価・合計・支払情報は印刷せず、明細の商品名と点数、レシート番号のQRコードを載せる
+    fn render_gift_receipt<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        receipt: &ReceiptData,
+        config: &config::TerminalConfig,
+    ) -> Result<(), String> {
+        let strings = print_strings::current();
+        let text = &strings.receipt;
+        let normalized = config.text_normalization.receipt(receipt);
+        let receipt = &normalized;
+        let mut header = config.receipt_header.clone();
+        header.shop_name = header.shop_name.map(|n| config.text_normalization.apply(&n));
+        render_header_block(printer, &header, receipt)?;
+
+        printer.jp_textln_padded(&text.gift_title, TextStyle::default().double().reverse().center())?;
+        printer.jp_textln(&format!("# {}", receipt.receipt_number), TextStyle::default())?;
+        if let Some(ref sale_date_time) = receipt.sale_start_date_time {
+            printer.jp_textln(&datetime::display(sale_date_time, &config.timezone), TextStyle::default())?;
+        }
+
+        printer.separator()?;
+
+        // 商品明細（端末設定の並び順でグループ化、金額は印刷しない）
+        let groups = receipt::arrange_items(&receipt.items, config.item_order);
+        for group in &groups {
+            if let Some(ref label) = group.label {
+                printer.jp_textln(&format!("■ {}", label), TextStyle::default().bold())?;
+            }
+            for &item in &group.items {
+                printer.jp_textln(&format!("{} / {}", item.circle_name, item.name), TextStyle::default())?;
+                printer.jp_textln(
+                    &print_strings::fill(&text.gift_quantity, &[("count", &item.quantity)]),
+                    TextStyle::default().bold().right(),
+                )?;
+                print_item_note(printer, item)?;
+            }
+        }
+
+        printer.double_separator()?;
+
+        let quantity: u32 = receipt.items.iter().map(|i| i.quantity).sum();
+        printer.row_auto_bold(&text.gift_total_quantity, &print_strings::fill(&text.gift_quantity, &[("count", &quantity)]))?;
+        printer.jp_textln(&text.gift_notice, TextStyle::default())?;
+
+        printer.textln("")?;
+
+        // レシート番号のQRコード（返品・交換の窓口で元の売上を呼び出す）
+        printer.qr_code_center(&receipt.receipt_number, Some(6))?;
+
+        Ok(())
+    }
+
+    /// スプーラーのジョブを1台のプリンターに印刷
+    pub(crate) fn print_spool_document(target: &UsbPrinter, document: &SpoolDocument) -> Result<(), String> {
+        let mut printer = open_usb_printer(target.vendor_id, target.device_id, target.paper_width)?;
+        let config = config::load()?;
+        match document {
+            SpoolDocument::Receipt { receipt } => {
+                render_receipt(&mut printer, receipt, None, &config)?;
+            }
+            SpoolDocument::ParkedSlip { sale } => {
+                render_parked_slip(&mut printer, sale, &config.number_format)?;
+            }
+            SpoolDocument::ClosingReport { report, totals } => {
+                let qr = closing_report_qr(report, totals);
+                render_closing_report(&mut printer, report, totals, qr.as_deref(), &config)?;
+            }
+            SpoolDocument::FallbackSlip {
+                receipt_number,
+                event_name,
+                total,
+            } => {
+                render_fallback_slip(&mut printer, receipt_number, event_name, *total, &config)?;
+            }
+        }
+        printer.feed(3)?;
+        printer.cut()?;
+
+        Ok(())
+    }
+
+    /// 商品ラベル印刷（ラベル紙1枚に1商品）
+    ///
+    /// copies 枚を連続して印刷する（ラベル紙以外でも1枚ずつカットして印刷する）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_product_label(
+        target: PrinterTarget,
+        item: ReceiptItem,
+        copies: Option<u32>,
+        paper_width: Option<u8>,
+    ) -> Result<(), String> {
+        let mut printer = open_printer(&target, paper_width)?;
+        let config = config::load()?;
+        let fmt = &config.number_format;
+        let item = config.text_normalization.item(&item);
+        let unit_price = item.price / item.quantity.max(1);
 
-            // 会員番号（マスク済みカード番号）
-            if let Some(ref last4) = card.last4 {
-                printer.row_auto("会員番号:", &format!("**** **** **** {}", last4))?;
+        for _ in 0..copies.unwrap_or(1).max(1) {
+            if !item.circle_name.is_empty() {
+                printer.jp_textln(&item.circle_name, TextStyle::default().center())?;
             }
+            printer.jp_textln(&item.name, TextStyle::default().bold().center())?;
+            printer.jp_textln(&fmt.price(unit_price), TextStyle::default().double().center())?;
+            printer.code128_center(&item.jan, Some(40))?;
+            if printer.media_mode() == MediaMode::Continuous {
+                printer.feed(2)?;
+            }
+            printer.cut()?;
+        }
+
+        Ok(())
+    }
+
+    /// 用紙センサーの状態を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_paper_status(vendor_id: u16, device_id: u16) -> Result<PaperStatus, String> {
+        // 問い合わせのみのため初期化（ESC @）やプロファイルの適用はしない
+        let driver = target::open_driver(&PrinterTarget::device(vendor_id, device_id), PaperWidth::default())?;
+        JpPrinter::new(driver).paper_status()
+    }
+
+    /// プリンターの状態を取得（用紙ニアエンド・用紙切れ・カバー開・エラー。USB接続のため電池残量・電波強度はない）
+    ///
+    /// 開けない・状態の問い合わせに応答しない場合は未接続として返す
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_printer_status(vendor_id: u16, device_id: u16) -> PrinterStatus {
+        // 問い合わせのみのため初期化（ESC @）やプロファイルの適用はしない
+        target::open_driver(&PrinterTarget::device(vendor_id, device_id), PaperWidth::default())
+            .and_then(|driver| JpPrinter::new(driver).status())
+            .unwrap_or_default()
+    }
+
+    /// 用紙ニアエンド監視用に用紙センサーの状態を取得
+    pub(crate) fn read_paper_status(target: &UsbPrinter) -> Result<PaperStatus, String> {
+        get_paper_status(target.vendor_id, target.device_id)
+    }
+
+    /// 用紙残りわずかの警告伝票を印刷（ロール交換を促す短い伝票）
+    pub(crate) fn print_paper_warning_strip(target: &UsbPrinter) -> Result<(), String> {
+        let mut printer = open_usb_printer(target.vendor_id, target.device_id, target.paper_width)?;
+        printer.jp_textln_padded("用紙残りわずか", TextStyle::default().double().reverse().center())?;
+        printer.jp_textln("お客様の合間にロール紙を交換してください", TextStyle::default().center())?;
+        printer.feed(2)?;
+        printer.cut()?;
+
+        Ok(())
+    }
+
+    /// キオスクの売上レシートを設定済みのプリンターに自動印刷
+    pub(crate) fn print_kiosk_receipt(receipt: &ReceiptData) -> Result<(), String> {
+        let config = config::load()?;
+        let (Some(vendor_id), Some(device_id)) =
+            (config.kiosk.printer_vendor_id, config.kiosk.printer_device_id)
+        else {
+            return Err("Kiosk printer is not configured".to_string());
+        };
+
+        let mut printer = open_usb_printer(vendor_id, device_id, config.kiosk.paper_width)?;
+        render_receipt(&mut printer, receipt, None, &config)?;
+        printer.feed(3)?;
+        printer.cut()?;
+
+        Ok(())
+    }
+
+    /// レシート再発行
+    ///
+    /// ジャーナルの売上記録から再描画する
+    /// 再発行回数が設定の上限を超える場合は責任者承認が必要
+    #[tauri::command]
+    #[specta::specta]
+    pub fn reprint_receipt(
+        vendor_id: u16,
+        device_id: u16,
+        receipt_number: String,
+        staff_id: String,
+        paper_width: Option<u8>,
+        approval: Option<SupervisorApproval>,
+        idempotency_key: Option<String>,
+    ) -> Result<u32, String> {
+        reprint_receipt_to(
+            PrinterTarget::device(vendor_id, device_id),
+            receipt_number,
+            staff_id,
+            paper_width,
+            approval,
+            idempotency_key,
+        )
+    }
+
+    /// 印刷先を指定してレシート再発行
+    ///
+    /// 元の売上と異なるプリンター・用紙幅でも、印刷先の用紙幅でレイアウトし直して印刷する
+    /// （例: 58mm のモバイルプリンターで渡したレシートを、事務所の 80mm のプリンターで再発行する）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn reprint_receipt_to(
+        target: PrinterTarget,
+        receipt_number: String,
+        staff_id: String,
+        paper_width: Option<u8>,
+        approval: Option<SupervisorApproval>,
+        idempotency_key: Option<String>,
+    ) -> Result<u32, String> {
+        idempotency::run(idempotency_key.as_deref(), "reprint_receipt", || {
+            let receipt = journal::find_sale(&receipt_number)?
+                .ok_or_else(|| format!("Receipt not found: {}", receipt_number))?;
+
+            let config = config::load()?;
+            let copy_number = journal::reprint_count(&receipt_number)? + 1;
+            let supervisor_id = if copy_number > config.reprint_limit {
+                let approval = approval
+                    .ok_or_else(|| "Supervisor approval required for reprint".to_string())?;
+                staff_auth::verify_supervisor(&approval)?;
+                Some(approval.supervisor_id)
+            } else {
+                None
+            };
+
+            let reprint = Reprint {
+                copy_number,
+                status: journal::sale_status(&receipt)?,
+            };
+
+            // 記録できない再発行は印刷しない（印刷に失敗した場合も回数に含める）
+            journal::append(
+                Some(&staff_id),
+                journal::JournalRecord::Reprint {
+                    receipt_number,
+                    copy_number,
+                    supervisor_id,
+                },
+            )?;
+
+            let mut printer = open_printer(&target, paper_width)?;
+            render_receipt(&mut printer, &receipt, Some(reprint), &config)?;
+            printer.feed(3)?;
+            printer.cut()?;
+
+            Ok(copy_number)
+        })
+    }
+
+    /// 返品伝票印刷
+    ///
+    /// ジャーナルの返品記録（refund_sale の戻り値のID）と元の売上から描画する
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_refund_receipt(
+        target: PrinterTarget,
+        refund_id: String,
+        paper_width: Option<u8>,
+    ) -> Result<(), String> {
+        let refund = journal::refund_receipt(&refund_id)?;
+        let config = config::load()?;
+
+        let mut printer = open_printer(&target, paper_width)?;
+        render_refund_receipt(&mut printer, &refund, &config)?;
+        printer.feed(3)?;
+        printer.cut()?;
+
+        Ok(())
+    }
+
+    /// 返品伝票を描画
+    ///
+    /// 店舗情報と元のレシート番号の下に返品した明細と返金額をマイナスで並べ、返品理由と署名欄を添える
+    fn render_refund_receipt<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        refund: &journal::RefundReceipt,
+        config: &config::TerminalConfig,
+    ) -> Result<(), String> {
+        let fmt = &config.number_format;
+        let strings = print_strings::current();
+        let text = &strings.refund_slip;
+        let normalized = config.text_normalization.receipt(&refund.receipt);
+        let receipt = &normalized;
+        let mut header = config.receipt_header.clone();
+        header.shop_name = header.shop_name.map(|n| config.text_normalization.apply(&n));
+        render_header_block(printer, &header, receipt)?;
+
+        // 返品伝票（黒背景中央揃え文字２倍サイズ）
+        printer.jp_textln_padded(&text.title, TextStyle::default().double().reverse().center())?;
+
+        // 返品番号（返品記録のIDの先頭）と元のレシート番号
+        let refund_number: String = refund.refund_id.chars().take(8).collect();
+        printer.jp_textln(
+            &print_strings::fill(&text.refund_number, &[("id", &refund_number)]),
+            TextStyle::default(),
+        )?;
+        printer.jp_textln(
+            &print_strings::fill(&text.original_receipt, &[("receipt_number", &receipt.receipt_number)]),
+            TextStyle::default().bold(),
+        )?;
+
+        // 返品日時 責: {スタッフ番号}
+        let staff_id = refund.staff_id.as_deref().unwrap_or(&receipt.staff_id);
+        printer.jp_textln(
+            &format!(
+                "{} {}",
+                datetime::display_unix(refund.refunded_at, &config.timezone),
+                print_strings::fill(&strings.receipt.staff, &[("staff", &staff_id)]),
+            ),
+            TextStyle::default(),
+        )?;
+
+        printer.separator()?;
+
+        // 返品明細（サークル名は元の明細から、金額はマイナス）
+        let (qty_width, amount_width) = match printer.paper_width() {
+            PaperWidth::Mm58 => (7, 11),
+            PaperWidth::Mm80 => (9, 14),
+        };
+        for item in &refund.items {
+            let original = receipt.items.get(item.line_index);
+            let name = match original {
+                Some(original) => format!("{} / {}", original.circle_name, original.name),
+                None => config.text_normalization.apply(&item.name),
+            };
+            let unit_price = original.map_or(item.amount / item.quantity.max(1), |o| o.price / o.quantity.max(1));
+            printer.jp_textln(&item.jan, TextStyle::default().bold())?;
+            printer.jp_textln(&name, TextStyle::default())?;
+            printer.fixed_columns(
+                &format!("@ {}", fmt.price(unit_price)),
+                &[
+                    (&format!("{} 点", item.quantity), qty_width),
+                    (&fmt.negative_price(item.amount), amount_width),
+                ],
+                TextStyle::default().bold(),
+            )?;
+        }
+
+        // 明細と合計の区切りは二重線
+        printer.double_separator()?;
+
+        // 返金合計（太字・右寄せ）と内税
+        printer.row_auto_bold(&text.total, &fmt.negative_price(refund.amount))?;
+        let tax_amount = refund.amount * receipt.tax_rate / (100 + receipt.tax_rate);
+        if receipt.tax_rate > 0 && tax_amount > 0 {
+            printer.row_auto(
+                &print_strings::fill(&text.tax, &[("rate", &receipt.tax_rate)]),
+                &fmt.negative_price(tax_amount),
+            )?;
+        }
+
+        // 返品理由
+        if !refund.reason.is_empty() {
+            printer.jp_textln(
+                &print_strings::fill(&text.reason, &[("reason", &refund.reason)]),
+                TextStyle::default(),
+            )?;
+        }
+
+        printer.separator()?;
+
+        // 署名欄
+        printer.jp_textln(&text.signature_heading, TextStyle::default().bold())?;
+        printer.textln("")?;
+        printer.textln("")?;
+        printer.dashed_separator()?;
+
+        printer.textln("")?;
+
+        // 元のレシート番号のQRコード（返品窓口で元の売上を呼び出す）
+        printer.qr_code_center(&receipt.receipt_number, Some(6))?;
+
+        Ok(())
+    }
+
+    /// 再発行の印字内容
+    #[derive(Debug, Clone, Copy)]
+    struct Reprint {
+        /// 何回目の再発行か
+        copy_number: u32,
+        /// 元の取引の返品・取消の状況
+        status: journal::SaleStatus,
+    }
+
+    /// レシート本文を描画
+    ///
+    /// reprint: 再発行の場合の回数と元の取引の状況
+    /// config: 端末設定（店舗情報・明細の並び順など）
+    fn render_receipt<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        receipt: &ReceiptData,
+        reprint: Option<Reprint>,
+        config: &config::TerminalConfig,
+    ) -> Result<(), String> {
+        let fmt = &config.number_format;
+        let strings = print_strings::current();
+        let text = &strings.receipt;
+        // 商品名・サークル名の全角・半角をそろえる
+        let normalized = config.text_normalization.receipt(receipt);
+        let receipt = &normalized;
+        let mut header = config.receipt_header.clone();
+        header.shop_name = header.shop_name.map(|n| config.text_normalization.apply(&n));
+        // ロゴ（読み込めない画像は印刷せず、レシートは印刷する）
+        if let Some(ref logo) = receipt.logo {
+            if let Ok(bitmap) = logo::load(logo, printer.paper_width().dots() as usize) {
+                printer.bitmap(&bitmap, Align::Center)?;
+            }
+        }
+        // 店舗情報（端末設定を優先し、未設定の項目はレシートデータを使う）
+        render_header_block(printer, &header, receipt)?;
+        render_sections(printer, config, ReceiptSlot::AfterHeader, receipt)?;
+
+        // ご明細書（黒背景中央揃え文字２倍サイズ）
+        printer.jp_textln_padded(&text.title, TextStyle::default().double().reverse().center())?;
+
+        // 再発行の場合は回数と、返品・取消済みであればその旨を明記
+        if let Some(reprint) = reprint {
+            printer.jp_textln_padded(
+                &print_strings::fill(&text.reprint, &[("copy", &reprint.copy_number)]),
+                TextStyle::default().bold().reverse().center(),
+            )?;
+            let status = match reprint.status {
+                journal::SaleStatus::Completed => None,
+                journal::SaleStatus::PartiallyRefunded => Some(&text.reprint_partially_refunded),
+                journal::SaleStatus::Refunded => Some(&text.reprint_refunded),
+                journal::SaleStatus::Voided => Some(&text.reprint_voided),
+            };
+            if let Some(status) = status {
+                printer.jp_textln_padded(status, TextStyle::default().bold().center())?;
+            }
+        }
+
+        // 内金・残金の売上はその旨を明記
+        if let Some(ref deposit) = receipt.deposit {
+            let banner = if deposit.is_balance_payment() {
+                &text.balance_completed
+            } else {
+                &text.deposit_banner
+            };
+            printer.jp_textln_padded(banner, TextStyle::default().bold().reverse().center())?;
+        }
+
+        // 宛名
+        if let Some(ref name) = receipt.customer_name {
+            if !name.is_empty() {
+                printer.jp_textln(
+                    &print_strings::fill(&text.customer, &[("name", name)]),
+                    TextStyle::default().bold(),
+                )?;
+            }
+        }
+
+        // レシート番号
+        printer.jp_textln(&format!("# {}", receipt.receipt_number), TextStyle::default())?;
+
+        // 発売日時 責: {スタッフ番号}
+        let staff = print_strings::fill(&text.staff, &[("staff", &receipt.staff_id)]);
+        if let Some(ref sale_date_time) = receipt.sale_start_date_time {
+            let sale_date_time = datetime::display(sale_date_time, &config.timezone);
+            printer.jp_textln(&format!("{} {}", sale_date_time, staff), TextStyle::default())?;
+        } else {
+            printer.jp_textln(&staff, TextStyle::default())?;
+        }
+
+        printer.separator()?;
+
+        // 商品明細（端末設定の並び順でグループ化）
+        let groups = receipt::arrange_items(&receipt.items, config.item_order);
+        for group in &groups {
+            if let Some(ref label) = group.label {
+                printer.jp_textln(&format!("■ {}", label), TextStyle::default().bold())?;
+            }
+            for &item in &group.items {
+                print_receipt_item(printer, item, fmt)?;
+            }
+        }
+        render_sections(printer, config, ReceiptSlot::AfterItems, receipt)?;
+
+        // 適用したキャンペーン・端数調整（小計と割引額）
+        if !receipt.promotions.is_empty() || receipt.rounding_adjustment != 0 {
+            let subtotal: u32 = receipt.items.iter().map(|i| i.price).sum();
+            printer.separator()?;
+            printer.row_auto(&text.subtotal, &fmt.price(subtotal))?;
+            for promotion in &receipt.promotions {
+                printer.row_auto(&format!("　 {}", promotion.name), &fmt.negative_price(promotion.discount))?;
+            }
+            let adjustment = receipt.rounding_adjustment;
+            if adjustment < 0 {
+                printer.row_auto(&format!("　 {}", text.rounding_discount), &fmt.negative_price(adjustment.unsigned_abs()))?;
+            } else if adjustment > 0 {
+                printer.row_auto(&format!("　 {}", text.rounding_surcharge), &fmt.price(adjustment as u32))?;
+            }
+        }
+
+        // 明細と合計の区切りは二重線
+        printer.double_separator()?;
+
+        // 合計（税込）（太字・右寄せ）
+        // 内金・残金の売上はご注文合計と、この取引で受け取る内金・残金
+        match receipt.deposit {
+            None => printer.row_auto_bold(&text.total, &fmt.price(receipt.total))?,
+            Some(ref deposit) => {
+                printer.row_auto(&text.order_total, &fmt.price(deposit.order_total))?;
+                match deposit.deposit_receipt_number {
+                    None => printer.row_auto_bold(&text.deposit, &fmt.price(receipt.total))?,
+                    Some(ref deposit_receipt_number) => {
+                        printer.row_auto(
+                            &print_strings::fill(&text.deposit_paid, &[("receipt_number", deposit_receipt_number)]),
+                            &fmt.negative_price(deposit.deposit),
+                        )?;
+                        printer.row_auto_bold(&text.balance_paid, &fmt.price(receipt.total))?;
+                    }
+                }
+            }
+        }
+
+        // 内税表示（税率と税額）
+        if receipt.tax_rate > 0 && receipt.tax_amount > 0 {
+            printer.row_auto(
+                &print_strings::fill(&text.tax, &[("rate", &receipt.tax_rate)]),
+                &fmt.price(receipt.tax_amount)
+            )?;
+        }
+
+        // 支払情報
+        for payment in &receipt.payments {
+            printer.row_auto(&format!("　 {}", payment.method), &fmt.price(payment.amount))?;
+        }
+
+        // 釣り銭（現金・おつりを出せる商品券の超過分）
+        let change = tenders::validate_receipt(&config.voucher_catalog, receipt)
+            .map(|b| b.change)
+            .unwrap_or_default();
+        if change > 0 {
+            printer.row_auto(&format!("　 {}", text.change), &fmt.price(change))?;
+
+            // 釣り銭の金種内訳
+            if let Some(ref breakdown) = receipt.change_breakdown {
+                for d in breakdown {
+                    printer.row_auto(
+                        &format!("　　 {}円 x {}", d.denomination, d.count),
+                        &fmt.price(d.denomination * d.count),
+                    )?;
+                }
+            }
+        }
+
+        // 内金の売上は残金とお支払期日
+        if let Some(deposit) = receipt.deposit.as_ref().filter(|d| !d.is_balance_payment()) {
+            printer.row_auto_bold(&text.balance, &fmt.price(deposit.balance()))?;
+            printer.jp_textln(
+                &print_strings::fill(&text.balance_due, &[("date", &deposit.due_date)]),
+                TextStyle::default().bold(),
+            )?;
+        }
+
+        // 会員のポイント
+        if let Some(ref points) = receipt.points {
+            printer.row_auto(
+                &text.points_earned,
+                &print_strings::fill(&text.points, &[("points", &points.earned)]),
+            )?;
+            printer.row_auto(
+                &text.points_balance,
+                &print_strings::fill(&text.points, &[("points", &points.balance)]),
+            )?;
+        }
+
+        render_sections(printer, config, ReceiptSlot::AfterTotals, receipt)?;
+
+        printer.separator()?;
+
+        // 再発行は末尾にも写しであることを明記（経費精算で原本と取り違えないように）
+        if reprint.is_some() {
+            printer.jp_textln_padded(&text.reprint_notice, TextStyle::default().bold().reverse().center())?;
+        }
+
+        // 免税事業者の説明文
+        printer.jp_textln(&text.disclaimer, TextStyle::default())?;
+
+        printer.textln("")?;
+
+        // QRコード（設定によりレシート内容を埋め込む。埋め込めない場合はレシート番号）
+        let payload = config
+            .receipt_qr_payload
+            .then(|| receipt_qr::encode(receipt).ok())
+            .flatten();
+        match payload {
+            Some(data) => printer.qr_code_center(&data, Some(3))?,
+            None => printer.qr_code_center(&receipt.receipt_number, Some(6))?,
+        }
+
+        // 1次元スキャナーしかない返品窓口でも読めるよう、レシート番号をバーコードでも印刷
+        if jp_escpos::is_code128_data(&receipt.receipt_number) {
+            printer.code128_center(&receipt.receipt_number, Some(60))?;
+        }
+
+        render_sections(printer, config, ReceiptSlot::Footer, receipt)?;
+
+        // クレジット売上票（カード詳細がある場合のみ）
+        if let Some(ref card) = receipt.card_details {
+            if config.credit_slip_copies {
+                // お客様控と加盟店控を部分カットで区切って2枚印刷
+                let copies = &strings.credit_slip;
+                render_credit_slip(printer, receipt, card, Some(&copies.customer_copy), config)?;
+                printer.feed(3)?;
+                printer.partial_cut()?;
+                render_credit_slip(printer, receipt, card, Some(&copies.merchant_copy), config)?;
+            } else {
+                render_credit_slip(printer, receipt, card, None, config)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 端末設定の追加セクションのうち、スロットに登録されたものを描画
+    fn render_sections<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        config: &config::TerminalConfig,
+        slot: ReceiptSlot,
+        receipt: &ReceiptData,
+    ) -> Result<(), String> {
+        for section in receipt_sections::for_slot(&config.receipt_sections, slot) {
+            printer.textln("")?;
+            if let Some(ref title) = section.title {
+                printer.jp_textln(title, TextStyle::default().bold().center())?;
+            }
+            for line in section.lines(&receipt.receipt_number, &receipt.event_name) {
+                printer.jp_textln(&line, TextStyle::default().center())?;
+            }
+            if let Some(qr) = section.qr(&receipt.receipt_number, &receipt.event_name) {
+                printer.qr_code_center(&qr, Some(4))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// レシート上部の店舗情報を描画
+    fn render_header_block<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        header: &config::ReceiptHeader,
+        receipt: &ReceiptData,
+    ) -> Result<(), String> {
+        let non_empty = |v: &Option<String>| v.clone().filter(|s| !s.is_empty());
+
+        // サークル名（トップに大きく表示、指定があれば縦書き）
+        if let Some(shop_name) = non_empty(&header.shop_name).or(non_empty(&receipt.circle_name)) {
+            let vertical = receipt.circle_name_vertical
+                && printer.jp_vertical_block(&shop_name, Align::Center).is_ok();
+            if !vertical {
+                printer.jp_textln_padded(&shop_name, TextStyle::default().double().center())?;
+            }
+        }
+
+        // イベント名・会場住所（サークル名の下に表示）
+        if let Some(address) = non_empty(&header.address).or(non_empty(&receipt.venue_address)) {
+            if !receipt.event_name.is_empty() {
+                printer.jp_textln(&receipt.event_name, TextStyle::default().bold())?;
+            }
+            printer.jp_textln(&address, TextStyle::default())?;
+        }
+
+        if let Some(booth) = non_empty(&header.booth_number) {
+            printer.jp_textln(&format!("スペース: {}", booth), TextStyle::default())?;
+        }
+        if let Some(phone) = non_empty(&header.phone) {
+            printer.jp_textln(&format!("TEL: {}", phone), TextStyle::default())?;
+        }
+        if let Some(registration) = non_empty(&header.registration_number) {
+            printer.jp_textln(&format!("登録番号: {}", registration), TextStyle::default())?;
+        }
+
+        Ok(())
+    }
+
+    /// クレジット売上票を描画
+    ///
+    /// copy_label: 控えを分けて印刷する場合の見出し（お客様控・加盟店控）
+    /// クレジット売上票の項目を印刷
+    ///
+    /// 80mmでは続く2項目が半分の幅に収まる場合は左右に並べ、収まらない項目は1行で印刷する
+    fn render_slip_details<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        rows: &[(&str, String)],
+    ) -> Result<(), String> {
+        if printer.paper_width() != PaperWidth::Mm80 {
+            for (label, value) in rows {
+                printer.row_auto(label, value)?;
+            }
+            return Ok(());
+        }
+
+        let gap = 2;
+        let half = (printer.chars_per_line() - gap) / 2;
+        let fits = |(label, value): &(&str, String)| {
+            jp_escpos::str_width(label) + 1 + jp_escpos::str_width(value) <= half
+        };
+        let cell = |(label, value): &(&str, String)| {
+            let space = half - jp_escpos::str_width(label) - jp_escpos::str_width(value);
+            format!("{}{}{}", label, " ".repeat(space), value)
+        };
+        let mut i = 0;
+        while i < rows.len() {
+            match rows.get(i + 1) {
+                Some(right) if fits(&rows[i]) && fits(right) => {
+                    printer.jp_textln(
+                        &format!("{}{}{}", cell(&rows[i]), " ".repeat(gap), cell(right)),
+                        TextStyle::default(),
+                    )?;
+                    i += 2;
+                }
+                _ => {
+                    printer.row_auto(rows[i].0, &rows[i].1)?;
+                    i += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn render_credit_slip<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        receipt: &ReceiptData,
+        card: &CardDetails,
+        copy_label: Option<&str>,
+        config: &config::TerminalConfig,
+    ) -> Result<(), String> {
+        let fmt = &config.number_format;
+        let strings = print_strings::current();
+        let text = &strings.credit_slip;
+        printer.textln("")?;
+        printer.jp_textln_padded(&text.title, TextStyle::default().reverse().center())?;
+        if let Some(label) = copy_label {
+            printer.jp_textln(label, TextStyle::default().bold().right())?;
+        }
+        printer.textln("")?;
+
+        // 加盟店・取引の情報
+        let mut details: Vec<(&str, String)> = Vec::new();
+
+        // 加盟店名（Stripeアカウント名を優先、なければサークル名を使用）
+        let merchant_name = card.merchant_name.as_ref()
+            .filter(|s| !s.is_empty())
+            .or(receipt.circle_name.as_ref().filter(|s| !s.is_empty()));
+        if let Some(name) = merchant_name {
+            details.push((&text.merchant_name, name.clone()));
+        }
+
+        // 端末番号
+        if let Some(ref terminal_sn) = card.terminal_serial_number {
+            details.push((&text.terminal_number, terminal_sn.clone()));
+        }
+
+        // ご利用日時
+        if let Some(ref tx_at) = card.transaction_at {
+            // ISO8601を端末のタイムゾーンでフォーマット（例: 2025-12-16T10:30:00Z → 2025/12/16 19:30）
+            let formatted = datetime::display(tx_at, &config.timezone);
+            details.push((&text.transaction_at, formatted));
+        }
+
+        // 伝票番号（PaymentIntent ID）
+        if let Some(ref pi_id) = receipt.payment_intent_id {
+            // IDが長い場合は末尾のみ表示
+            let display_id = if pi_id.len() > 16 && pi_id.is_ascii() {
+                format!("...{}", &pi_id[pi_id.len()-12..])
+            } else {
+                pi_id.clone()
+            };
+            details.push((&text.slip_number, display_id));
+        }
+
+        render_slip_details(printer, &details)?;
+        printer.dashed_separator()?;
+
+        // カードの情報
+        let mut card_rows: Vec<(&str, String)> = Vec::new();
+
+        // 会員番号（マスク済みカード番号）
+        if let Some(ref last4) = card.last4 {
+            card_rows.push((&text.member_number, format!("**** **** **** {}", last4)));
+        }
+
+        // 取引内容
+        let tx_type = card.transaction_type.as_deref().unwrap_or("sale");
+        let tx_type_display = match tx_type {
+            "sale" => &text.sale,
+            "refund" => &text.refund,
+            _ => tx_type,
+        };
+        card_rows.push((&text.transaction_type, tx_type_display.to_string()));
+
+        // 支払い区分
+        let payment_type = card.payment_type.as_deref().unwrap_or(&text.lump_sum);
+        card_rows.push((&text.payment_type, payment_type.to_string()));
+
+        // カード会社
+        if let Some(ref brand) = card.brand {
+            let brand_display = match brand.to_lowercase().as_str() {
+                "visa" => "VISA",
+                "mastercard" | "mc" => "MasterCard",
+                "amex" | "american_express" => "AMEX",
+                "jcb" => "JCB",
+                "diners" | "diners_club" => "Diners Club",
+                "discover" => "Discover",
+                "unionpay" => "UnionPay",
+                _ => brand,
+            };
+            card_rows.push((&text.card_brand, brand_display.to_string()));
+        }
+
+        // 有効期限は端末に保持しないため印字しない
+
+        render_slip_details(printer, &card_rows)?;
+        printer.dashed_separator()?;
+
+        // 利用金額
+        printer.row_auto_bold(&text.amount, &fmt.price(receipt.total))?;
+
+        printer.textln("")?;
+
+        // 署名欄
+        printer.jp_textln(&text.signature_heading, TextStyle::default().bold())?;
+        if printer.paper_width() == PaperWidth::Mm80 {
+            // 80mmは枠付きの広い署名欄
+            let inner = printer.chars_per_line().saturating_sub(2);
+            let note = &text.signature_not_required;
+            let pad = inner.saturating_sub(jp_escpos::str_width(note));
+            printer.textln(&format!("+{}+", "-".repeat(inner)))?;
+            printer.textln(&format!("|{}|", " ".repeat(inner)))?;
+            printer.jp_textln(
+                &format!("|{}{}{}|", " ".repeat(pad / 2), note, " ".repeat(pad - pad / 2)),
+                TextStyle::default(),
+            )?;
+            printer.textln(&format!("|{}|", " ".repeat(inner)))?;
+            printer.textln(&format!("+{}+", "-".repeat(inner)))?;
+        } else {
+            printer.textln("")?;
+            printer.jp_textln_padded(&text.signature_not_required, TextStyle::default().center())?;
+            printer.textln("")?;
+            printer.dashed_separator()?;
+        }
+
+        printer.jp_textln(&text.acknowledgement, TextStyle::default().center())?;
+
+        // 決済番号QRコード（PaymentIntent ID）
+        if let Some(ref pi_id) = receipt.payment_intent_id {
+            printer.textln("")?;
+            printer.qr_code_center(pi_id, Some(4))?;
+        }
+
+        Ok(())
+    }
+
+    /// 閉局レポート印刷
+    ///
+    /// 印刷に成功したらレポートを署名付きでアップロードキューに追加する
+    #[tauri::command]
+    #[specta::specta]
+    pub fn print_closing_report(
+        app: tauri::AppHandle,
+        target: PrinterTarget,
+        mut report: ClosingReportData,
+        paper_width: Option<u8>,
+        idempotency_key: Option<String>,
+    ) -> Result<(), String> {
+        journal::complete_closing_report(&mut report)?;
+        idempotency::run(idempotency_key.as_deref(), "print_closing_report", || {
+            let mut printer = open_printer(&target, paper_width)?;
+            let totals = journal::totals()?;
+            let qr = closing_report_qr(&report, &totals);
+            render_closing_report(&mut printer, &report, &totals, qr.as_deref(), &config::load()?)?;
+
+            printer.feed(3)?;
+            printer.cut()?;
+
+            // 本部への自動アップロードをキューに追加（印刷は成功しているので失敗は通知のみ）
+            match upload_queue::enqueue(upload_queue::KIND_CLOSING_REPORT, &report.id, &report) {
+                Ok(upload) => {
+                    let _ = app.emit(upload_queue::EVENT_UPLOAD_QUEUED, &upload);
+                }
+                Err(e) => {
+                    let _ = app.emit(
+                        upload_queue::EVENT_UPLOAD_FAILED,
+                        upload_queue::UploadFailedEvent {
+                            upload_id: None,
+                            kind: upload_queue::KIND_CLOSING_REPORT.to_string(),
+                            reference_id: report.id.clone(),
+                            error: e,
+                            attempts: 0,
+                        },
+                    );
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    /// 閉局レポート本文を描画
+    ///
+    /// totals: ジャーナルの返品・取消の集計
+    /// qr: 末尾のQRコードに埋め込む署名付きの集計（closing_report_qr で作る）
+    fn render_closing_report<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        report: &ClosingReportData,
+        totals: &journal::JournalTotals,
+        qr: Option<&str>,
+        config: &config::TerminalConfig,
+    ) -> Result<(), String> {
+        let fmt = &config.number_format;
+        let strings = print_strings::current();
+        let text = &strings.closing_report;
+        // ヘッダー
+        printer.jp_textln_padded(&text.title, TextStyle::default().double().reverse().center())?;
+        printer.textln("")?;
+
+        // イベント名
+        if let Some(ref event_name) = report.event_name {
+            if !event_name.is_empty() {
+                printer.jp_textln(event_name, TextStyle::default().bold().center())?;
+            }
+        }
+
+        // 基本情報
+        printer.separator()?;
+        printer.row_auto(&text.report_id, &report.id)?;
+        printer.row_auto(&text.terminal_id, &report.terminal_id)?;
+        printer.row_auto(&text.staff, &format!("{} ({})", report.staff_name, report.staff_id))?;
+        printer.row_auto(&text.closed_at, &datetime::display(&report.closed_at, &config.timezone))?;
+        printer.separator()?;
+
+        // 売上サマリー
+        printer.jp_textln(&text.sales_summary, TextStyle::default().bold())?;
+        printer.row_auto(
+            &text.transaction_count,
+            &print_strings::fill(&text.count, &[("count", &report.transaction_count)]),
+        )?;
+        printer.row_auto(&text.sales_total_with_tax, &fmt.price(report.expected_total))?;
+        // 売上合計に含まれる端数調整（ジャーナルから集計）
+        if totals.rounding_adjustment != 0 {
+            printer.row_auto(&text.rounding, &fmt.signed_price(totals.rounding_adjustment))?;
+        }
+
+        // 返品・取消（ジャーナルから集計）
+        if totals.refund_count > 0 {
+            printer.row_auto(
+                &print_strings::fill(&text.refunds, &[("count", &totals.refund_count)]),
+                &fmt.negative_price(totals.refund_amount),
+            )?;
+        }
+        if totals.void_count > 0 {
+            printer.row_auto(
+                &print_strings::fill(&text.voids, &[("count", &totals.void_count)]),
+                &fmt.negative_price(totals.void_amount),
+            )?;
+        }
+        printer.separator()?;
+
+        // 支払方法別
+        if !report.payment_methods.is_empty() {
+            printer.jp_textln(&text.payment_methods, TextStyle::default().bold())?;
+            for m in &report.payment_methods {
+                printer.row_auto(
+                    &print_strings::fill(
+                        &text.payment_method,
+                        &[("method", &m.method), ("count", &m.count)],
+                    ),
+                    &fmt.price(m.amount),
+                )?;
+                if m.refund_count > 0 {
+                    printer.row_auto(
+                        &print_strings::fill(
+                            &text.payment_method_refunds,
+                            &[("count", &m.refund_count)],
+                        ),
+                        &fmt.negative_price(m.refund_amount),
+                    )?;
+                }
+            }
+            printer.separator()?;
+        }
+
+        // 時間帯別
+        if !report.hourly_sales.is_empty() {
+            printer.jp_textln(&text.hourly_sales, TextStyle::default().bold())?;
+            render_hourly_sales(printer, &report.hourly_sales, config)?;
+            printer.separator()?;
+        }
+
+        // 担当者別（複数の担当者が操作した場合のみ）
+        if report.staff_totals.len() > 1 {
+            printer.jp_textln(&text.staff_totals, TextStyle::default().bold())?;
+            for s in &report.staff_totals {
+                let name = match s.staff_name {
+                    Some(ref name) => format!("{} ({})", name, s.staff_id),
+                    None => s.staff_id.clone(),
+                };
+                printer.jp_textln(&name, TextStyle::default())?;
+                printer.row_auto(
+                    &print_strings::fill(&text.staff_sales, &[("count", &s.sale_count)]),
+                    &fmt.price(s.sale_amount),
+                )?;
+                if s.refund_count > 0 {
+                    printer.row_auto(
+                        &print_strings::fill(&text.staff_refunds, &[("count", &s.refund_count)]),
+                        &fmt.negative_price(s.refund_amount),
+                    )?;
+                }
+                if s.void_count > 0 {
+                    printer.row_auto(
+                        &print_strings::fill(&text.staff_voids, &[("count", &s.void_count)]),
+                        &fmt.negative_price(s.void_amount),
+                    )?;
+                }
+                if s.drawer_count > 0 {
+                    printer.row_auto(
+                        &text.staff_drawer,
+                        &print_strings::fill(&text.times, &[("count", &s.drawer_count)]),
+                    )?;
+                }
+            }
+            printer.separator()?;
+        }
+
+        // 金種別カウント
+        printer.jp_textln(&text.cash_breakdown, TextStyle::default().bold())?;
+        for d in &report.denominations {
+            if d.count > 0 {
+                let subtotal = d.denomination * d.count;
+                printer.row_auto(
+                    &format!("{}円 x {}", d.denomination, d.count),
+                    &fmt.price(subtotal),
+                )?;
+            }
+        }
+        printer.row_auto_bold(&text.cash_total, &fmt.price(report.cash_total))?;
+        printer.separator()?;
+
+        // 商品券等
+        if !report.vouchers.is_empty() || !report.voucher_sales.is_empty() {
+            printer.jp_textln(&text.vouchers, TextStyle::default().bold())?;
+            for v in &report.vouchers {
+                let label = if let Some(ref memo) = v.memo {
+                    format!("{} ({})", v.voucher_type, memo)
+                } else {
+                    v.voucher_type.clone()
+                };
+                printer.row_auto(&label, &fmt.price(v.amount))?;
+            }
+            printer.row_auto_bold(&text.voucher_total, &fmt.price(report.voucher_total))?;
+            // 売上に使われた分（ジャーナルから集計）
+            for s in &report.voucher_sales {
+                printer.row_auto(
+                    &print_strings::fill(
+                        &text.voucher_sales,
+                        &[("name", &s.name), ("count", &s.count)],
+                    ),
+                    &fmt.price(s.amount),
+                )?;
+            }
+            printer.separator()?;
+        }
+
+        // 合計と差異
+        printer.jp_textln(&text.settlement, TextStyle::default().bold())?;
+        printer.row_auto_bold(&text.counted_total, &fmt.price(report.grand_total))?;
+        printer.row_auto(&text.sales_total, &fmt.price(report.expected_total))?;
+
+        printer.row_auto_bold(&text.difference, &fmt.signed_price(i64::from(report.difference)))?;
+
+        printer.textln("")?;
+        printer.separator()?;
+
+        // フッター
+        printer.jp_textln(&text.footer, TextStyle::default().center())?;
+
+        // 本部が紙から集計を取り込めるよう、署名付きの集計をQRコードで印刷
+        if let Some(qr) = qr {
+            printer.textln("")?;
+            printer.qr_code_center(qr, Some(3))?;
+        }
+
+        Ok(())
+    }
+
+    /// 閉局レポートのQRコードの内容（署名できない場合はQRコードを印刷しない）
+    fn closing_report_qr(report: &ClosingReportData, totals: &journal::JournalTotals) -> Option<String> {
+        receipt_qr::encode_closing_report(report, totals).ok()
+    }
+
+    /// 時間帯別の売上を横棒グラフで描画
+    ///
+    /// 棒の長さは金額に比例させ、複数日にわたる場合は日付ごとに見出しを付ける
+    fn render_hourly_sales<D: Driver>(
+        printer: &mut JpPrinter<D>,
+        hourly: &[receipt::HourlySales],
+        config: &config::TerminalConfig,
+    ) -> Result<(), String> {
+        let fmt = &config.number_format;
+        let text = &print_strings::current().closing_report;
+        let rows: Vec<(String, String)> = hourly
+            .iter()
+            .map(|h| {
+                let hour = print_strings::fill(&text.hour, &[("hour", &format!("{:>2}", h.hour))]);
+                let value = print_strings::fill(
+                    &text.hourly_value,
+                    &[("count", &h.count), ("amount", &fmt.price(h.amount))],
+                );
+                (hour, value)
+            })
+            .collect();
+        let label_width = rows.iter().map(|(l, _)| jp_escpos::str_width(l)).max().unwrap_or(0);
+        let value_width = rows.iter().map(|(_, v)| jp_escpos::str_width(v)).max().unwrap_or(0);
+        let bar_width = printer
+            .chars_per_line()
+            .saturating_sub(label_width + value_width + 2);
+        let max_amount = hourly.iter().map(|h| h.amount).max().unwrap_or(0).max(1);
+        let multiple_days = hourly.iter().any(|h| h.date != hourly[0].date);
+
+        let mut date = None;
+        for (h, (hour, value)) in hourly.iter().zip(&rows) {
+            if multiple_days && date != Some(&h.date) {
+                printer.jp_textln(&h.date.replace('-', "/"), TextStyle::default())?;
+                date = Some(&h.date);
+            }
+            let bar = (h.amount * bar_width as u64).div_ceil(max_amount) as usize;
+            printer.row_auto(&format!("{} {}", hour, "#".repeat(bar)), value)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) mod preview;
+
+    #[cfg(not(target_os = "ios"))]
+    mod serial;
+
+    mod target;
+
+    #[cfg(test)]
+    mod layout_tests;
+
+    #[cfg(test)]
+    mod tests;
+
+    #[cfg(test)]
+    mod property_tests;
+}
+
+// Android Bluetooth printer implementation
+#[cfg(target_os = "android")]
+mod android_printer {
+    #[derive(Debug, Clone, serde::Serialize)]
+    pub struct BluetoothDevice {
+        pub address: String,
+        pub name: String,
+    }
+
+    #[tauri::command]
+    #[specta::specta]
+    pub async fn get_bluetooth_devices() -> Result<Vec<BluetoothDevice>, String> {
+        // TODO: Implement Bluetooth device discovery via JNI
+        // For now, return empty list
+        Ok(vec![])
+    }
+
+    #[tauri::command]
+    #[specta::specta]
+    pub fn connect_bluetooth_printer(address: String) -> Result<(), String> {
+        // TODO: Implement Bluetooth connection
+        Err("Bluetooth printer not yet implemented".to_string())
+    }
+
+    #[tauri::command]
+    #[specta::specta]
+    pub fn bluetooth_print(
+        address: String,
+        text: String,
+        paper_width: Option<u8>,
+    ) -> Result<(), String> {
+        // TODO: Implement Bluetooth printing
+        Err("Bluetooth printing not yet implemented".to_string())
+    }
+
+    #[tauri::command]
+    #[specta::specta]
+    pub fn bluetooth_welcome_print(
+        address: String,
+        id: String,
+        paper_width: Option<u8>,
+    ) -> Result<(), String> {
+        // TODO: Implement Bluetooth welcome print
+        Err("Bluetooth printing not yet implemented".to_string())
+    }
+}
+
+// Common commands available on all platforms
+mod common {
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_platform() -> String {
+        #[cfg(target_os = "android")]
+        return "android".to_string();
+
+        #[cfg(target_os = "ios")]
+        return "ios".to_string();
+
+        #[cfg(target_os = "macos")]
+        return "macos".to_string();
+
+        #[cfg(target_os = "windows")]
+        return "windows".to_string();
+
+        #[cfg(target_os = "linux")]
+        return "linux".to_string();
+
+        #[cfg(not(any(
+            target_os = "android",
+            target_os = "ios",
+            target_os = "macos",
+            target_os = "windows",
+            target_os = "linux"
+        )))]
+        return "unknown".to_string();
+    }
+}
+
+// 端末認証コマンド
+mod terminal_commands {
+    use crate::permissions;
+    use crate::terminal_auth::{self, TerminalAuthError};
+
+    /// 端末の状態を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_terminal_status() -> Result<terminal_auth::TerminalAuthResult, TerminalAuthError> {
+        terminal_auth::get_terminal_status()
+    }
+
+    /// 端末を初期化（キーペア生成）
+    ///
+    /// 組織とレジを指定した場合は割り当てて登録ペイロードに含める
+    #[tauri::command]
+    #[specta::specta]
+    pub fn initialize_terminal(
+        device_name: String,
+        seat: Option<terminal_auth::SeatRequest>,
+    ) -> Result<terminal_auth::RegistrationQrPayload, TerminalAuthError> {
+        terminal_auth::initialize_terminal(&device_name, seat.as_ref())
+    }
+
+    /// 割り当てた組織とレジを取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_seat_binding() -> Result<Option<terminal_auth::SeatBinding>, TerminalAuthError> {
+        terminal_auth::seat_binding().map_err(TerminalAuthError::KeychainError)
+    }
+
+    /// QRコード用のJSONデータを生成
+    ///
+    /// 初期化済みの端末でも組織とレジを指定すると割り当て直す（サーバーへの再登録が必要）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn generate_registration_qr(
+        device_name: String,
+        seat: Option<terminal_auth::SeatRequest>,
+    ) -> Result<String, TerminalAuthError> {
+        // 既に初期化されている場合は現在の状態を返す
+        let status = terminal_auth::get_terminal_status()?;
+
+        let payload = if status.status == "initialized" {
+            if let Some(ref seat) = seat {
+                terminal_auth::bind_seat(seat)?;
+            }
+            // 既存のデータからペイロードを構築
+            let mut payload = terminal_auth::RegistrationQrPayload {
+                v: 1,
+                terminal_id: status.terminal_id.unwrap_or_default(),
+                public_key: status.public_key.unwrap_or_default(),
+                device_name,
+                os: crate::common::get_platform(),
+                created_at: "".to_string(), // 既存のため空
+                organization_id: None,
+                seat_label: None,
+            };
+            terminal_auth::apply_seat_binding(&mut payload)?;
+            payload
+        } else {
+            // 新規初期化
+            terminal_auth::initialize_terminal(&device_name, seat.as_ref())?
+        };
+
+        serde_json::to_string(&payload)
+            .map_err(|e| TerminalAuthError::CryptoError(format!("Failed to encode payload: {}", e)))
+    }
+
+    /// 認証用の署名データを生成
+    #[tauri::command]
+    #[specta::specta]
+    pub fn create_auth_signature() -> Result<terminal_auth::SignatureData, TerminalAuthError> {
+        terminal_auth::create_auth_signature()
+    }
+
+    /// サポートから伝えられた公開鍵とチャレンジで端末を確認
+    #[tauri::command]
+    #[specta::specta]
+    pub fn verify_terminal(
+        public_key: String,
+        challenge: String,
+    ) -> Result<terminal_auth::TerminalVerification, TerminalAuthError> {
+        terminal_auth::verify_terminal(&public_key, &challenge)
+    }
+
+    /// 端末の応答を検証して確認コードを返す（サポート側で使う）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn check_terminal_verification(
+        public_key: String,
+        challenge: String,
+        signature: terminal_auth::SignatureData,
+    ) -> Result<String, TerminalAuthError> {
+        terminal_auth::check_verification(&public_key, &challenge, &signature)
+    }
+
+    /// Keychainをクリア（デバッグ用、メインウィンドウから責任者セッション中にだけ呼べる）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn clear_terminal_keychain(window: tauri::Window) -> Result<(), TerminalAuthError> {
+        permissions::ensure_privileged(window.label(), "Keychain")
+            .map_err(TerminalAuthError::KeychainError)?;
+        terminal_auth::clear_keychain()
+    }
+}
+
+// アップロードキューコマンド
+mod upload_commands {
+    use crate::email_receipt;
+    use crate::upload_queue;
+    use tauri::Emitter;
+
+    /// 送信待ちのアップロード一覧を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_pending_uploads() -> Result<Vec<upload_queue::PendingUpload>, String> {
+        upload_queue::list_pending()
+    }
+
+    /// 送信完了を通知
+    #[tauri::command]
+    #[specta::specta]
+    pub fn complete_upload(id: String) -> Result<(), String> {
+        upload_queue::mark_uploaded(&id)
+    }
+
+    /// 送信失敗を通知（再送を促すイベントを発行）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn fail_upload(app: tauri::AppHandle, id: String, error: String) -> Result<(), String> {
+        let upload = upload_queue::mark_failed(&id, &error)?;
+        let _ = app.emit(
+            upload_queue::EVENT_UPLOAD_FAILED,
+            upload_queue::UploadFailedEvent {
+                upload_id: Some(upload.id),
+                kind: upload.kind,
+                reference_id: upload.reference_id,
+                error,
+                attempts: upload.attempts,
+            },
+        );
+        Ok(())
+    }
+
+    /// レシートをメールで送信（署名した送信依頼をキューに積む）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn email_receipt(
+        app: tauri::AppHandle,
+        receipt_number: String,
+        address: String,
+        staff_id: String,
+    ) -> Result<upload_queue::PendingUpload, String> {
+        let upload = email_receipt::send(&receipt_number, &address, &staff_id)?;
+        let _ = app.emit(upload_queue::EVENT_UPLOAD_QUEUED, &upload);
+        Ok(upload)
+    }
+}
+
+// ハートビートコマンド
+mod heartbeat_commands {
+    use crate::heartbeat::{self, BatteryStatus, HeartbeatStatus, SignedHeartbeat};
+
+    /// 送信状況（最後にサーバーが受信を確認した日時など）を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_heartbeat_status() -> Result<HeartbeatStatus, String> {
+        heartbeat::status()
+    }
+
+    /// すぐにハートビートを作成（定期送信を待たずに送る場合）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn create_heartbeat() -> Result<SignedHeartbeat, String> {
+        heartbeat::create()
+    }
+
+    /// サーバーの受信確認を通知
+    #[tauri::command]
+    #[specta::specta]
+    pub fn ack_heartbeat(acknowledged_at: Option<u64>) -> Result<HeartbeatStatus, String> {
+        heartbeat::acknowledge(acknowledged_at)
+    }
+
+    /// 送信失敗を通知
+    #[tauri::command]
+    #[specta::specta]
+    pub fn fail_heartbeat(error: String) -> Result<HeartbeatStatus, String> {
+        heartbeat::mark_failed(&error)
+    }
+
+    /// バッテリーの状態を報告
+    #[tauri::command]
+    #[specta::specta]
+    pub fn report_battery(level: f32, charging: bool) -> Result<(), String> {
+        heartbeat::report_battery(BatteryStatus { level, charging })
+    }
+}
+
+// リモートコマンド・サーバー鍵コマンド
+mod remote_command_commands {
+    use crate::remote_commands::{
+        self, RemoteCommandResult, SignedRemoteCommand, TerminalLock,
+    };
+    use crate::server_key::{self, PinnedServerKey};
+    use crate::staff_auth::{self, SupervisorApproval};
+    use tauri::Emitter;
+
+    /// サーバーから受け取った署名付きコマンドを検証して実行
+    #[tauri::command]
+    #[specta::specta]
+    pub fn execute_remote_command(
+        app: tauri::AppHandle,
+        command: SignedRemoteCommand,
+    ) -> Result<RemoteCommandResult, String> {
+        let result = remote_commands::execute(&command)?;
+        let _ = app.emit(remote_commands::EVENT_REMOTE_COMMAND_EXECUTED, &result);
+        if result.command == "lock_terminal" || result.command == "unlock_terminal" {
+            if let Ok(lock) = remote_commands::lock_state() {
+                let _ = app.emit(remote_commands::EVENT_TERMINAL_LOCK, lock);
+            }
+        }
+        Ok(result)
+    }
+
+    /// リモートコマンドの実行履歴を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_remote_command_history() -> Result<Vec<RemoteCommandResult>, String> {
+        remote_commands::history()
+    }
+
+    /// 端末のロック状態を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_terminal_lock() -> Result<TerminalLock, String> {
+        remote_commands::lock_state()
+    }
+
+    /// 固定したサーバー公開鍵を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_server_key() -> Result<Option<PinnedServerKey>, String> {
+        server_key::pinned()
+    }
+
+    /// サーバー公開鍵を固定（別の鍵への置き換えは責任者承認が必要）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn pin_server_key(
+        public_key: String,
+        approval: Option<SupervisorApproval>,
+    ) -> Result<PinnedServerKey, String> {
+        let replace = match approval {
+            Some(approval) => {
+                staff_auth::verify_supervisor(&approval)?;
+                true
+            }
+            None => false,
+        };
+        server_key::pin(&public_key, replace)
+    }
+}
+
+// 営業セッションコマンド
+mod session_commands {
+    use crate::closing_reminder;
+    use crate::config;
+    use crate::metrics;
+    use crate::remote_commands;
+    use crate::session;
+    use crate::staff_auth::{self, SupervisorApproval};
+    use crate::upload_queue;
+    use crate::venue_guard::{self, VenueCheck, VenueGuardMode};
+    use tauri::Emitter;
+
+    /// 現在のセッション状態を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_session() -> Result<session::SessionState, String> {
+        session::current()
+    }
+
+    /// 開局
+    ///
+    /// 会場ネットワーク確認に一致しない場合、警告モードでは警告を通知して開局し、
+    /// ブロックモードでは責任者の承認がない限り開局しない
+    #[tauri::command]
+    #[specta::specta]
+    pub fn open_session(
+        app: tauri::AppHandle,
+        staff_id: String,
+        approval: Option<SupervisorApproval>,
+    ) -> Result<session::SessionState, String> {
+        remote_commands::ensure_unlocked("Opening a session")?;
+        let venue = check_venue_network()?;
+        if !venue.matched {
+            match (venue.mode, approval) {
+                (VenueGuardMode::Block, None) => {
+                    return Err(venue.reason.unwrap_or_default());
+                }
+                (VenueGuardMode::Block, Some(approval)) => {
+                    staff_auth::verify_supervisor(&approval)?;
+                }
+                _ => {
+                    let _ = app.emit(venue_guard::EVENT_VENUE_GUARD_WARNING, &venue);
+                }
+            }
+        }
+        session::open(&staff_id)
+    }
+
+    /// 会場ネットワークに接続しているか確認
+    #[tauri::command]
+    #[specta::specta]
+    pub fn check_venue_network() -> Result<VenueCheck, String> {
+        venue_guard::check(&config::load()?.venue_guard, venue_guard::current_network())
+    }
+
+    /// 接続中のSSIDを報告（取得できない場合はnull）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn report_network_ssid(ssid: Option<String>) {
+        venue_guard::report_ssid(ssid)
+    }
+
+    /// 担当者の交代を記録（スタッフのログイン時に呼ぶ）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn switch_staff(
+        staff_id: String,
+        staff_name: Option<String>,
+    ) -> Result<session::SessionState, String> {
+        session::switch_staff(&staff_id, staff_name.as_deref())
+    }
+
+    /// 閉局
+    ///
+    /// 計測値の送信が有効な場合は、あわせてアップロードキューに追加する（失敗しても閉局は続ける）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn close_session(app: tauri::AppHandle) -> Result<session::SessionState, String> {
+        let state = session::close()?;
+        if let Ok(Some(upload)) = metrics::upload() {
+            let _ = app.emit(upload_queue::EVENT_UPLOAD_QUEUED, &upload);
+        }
+        Ok(state)
+    }
+
+    /// 閉局リマインダーを設定
+    #[tauri::command]
+    #[specta::specta]
+    pub fn set_closing_reminder(
+        event_end_at: u64,
+        printer: Option<closing_reminder::ReminderPrinter>,
+    ) -> Result<closing_reminder::ReminderConfig, String> {
+        closing_reminder::schedule(event_end_at, printer)
+    }
+
+    /// 閉局リマインダー設定を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_closing_reminder() -> Result<Option<closing_reminder::ReminderConfig>, String> {
+        closing_reminder::current()
+    }
+
+    /// 閉局リマインダーを解除
+    #[tauri::command]
+    #[specta::specta]
+    pub fn cancel_closing_reminder() -> Result<(), String> {
+        closing_reminder::cancel()
+    }
+}
+
+// ドロワーコマンド
+mod drawer_commands {
+    use crate::drawer;
+    use crate::journal;
+    use crate::kiosk;
+    use crate::permissions;
+
+    /// ドロワー在高を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_drawer_contents() -> Result<Vec<drawer::DenominationCount>, String> {
+        drawer::contents()
+    }
+
+    /// ドロワー在高（釣銭準備金）を設定
+    ///
+    /// 担当者別の集計のため、設定したことをジャーナルに記録する
+    /// メインウィンドウから責任者セッション中にだけ呼べる
+    #[tauri::command]
+    #[specta::specta]
+    pub fn set_drawer_contents(
+        window: tauri::Window,
+        denominations: Vec<drawer::DenominationCount>,
+        staff_id: Option<String>,
+    ) -> Result<(), String> {
+        permissions::ensure_privileged(window.label(), "Drawer")?;
+        kiosk::ensure_attended("Drawer")?;
+        drawer::set_contents(denominations.clone())?;
+        journal::append(
+            staff_id.as_deref(),
+            journal::JournalRecord::DrawerUpdated { denominations },
+        )?;
+        Ok(())
+    }
+
+    /// 釣り銭の金種内訳を提案（availableを省略した場合はドロワー在高を使用）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn suggest_change(
+        amount: u32,
+        available: Option<Vec<drawer::DenominationCount>>,
+    ) -> Result<Vec<drawer::DenominationCount>, String> {
+        kiosk::ensure_attended("Drawer")?;
+        let available = match available {
+            Some(available) => available,
+            None => drawer::contents()?,
+        };
+        drawer::suggest_change(amount, &available)
+    }
+}
+
+// イベントコンテキストコマンド
+mod event_commands {
+    use crate::environment;
+    use crate::event_archive;
+    use crate::event_context;
+    use crate::permissions;
+    use crate::print_strings;
+    use crate::session;
+    use tauri::Emitter;
+
+    /// 選択中のイベントを取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_active_event() -> Result<Option<event_context::ActiveEvent>, String> {
+        event_context::active()
+    }
+
+    /// イベントを選択（開局中は切り替え不可）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn select_event(
+        app: tauri::AppHandle,
+        event_id: String,
+        event_name: String,
+    ) -> Result<event_context::ActiveEvent, String> {
+        let current = event_context::active_event_id()?;
+        if current != event_id && session::current()?.open {
+            return Err("Close the current session before switching events".to_string());
+        }
+
+        let event = event_context::select(&event_id, &event_name)?;
+        // イベント別の印字文言に切り替える（壊れている場合は既定の文言で続ける）
+        let _ = print_strings::reload();
+        let _ = app.emit("event-switched", &event);
+        Ok(event)
+    }
+
+    /// 端末内にデータが残っているイベント一覧
+    #[tauri::command]
+    #[specta::specta]
+    pub fn list_local_events() -> Result<Vec<String>, String> {
+        Ok(event_context::list_local_events(environment::current()?))
+    }
+
+    /// 選択中イベントのレシート番号を採番
+    #[tauri::command]
+    #[specta::specta]
+    pub fn issue_receipt_number() -> Result<String, String> {
+        event_context::next_receipt_number()
+    }
+
+    /// 選択中イベントのデータをアーカイブに書き出し、必要なら端末から消去する（責任者のみ）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn archive_event(
+        window: tauri::Window,
+        destination: String,
+        upload: bool,
+        reset: bool,
+    ) -> Result<event_archive::EventArchive, String> {
+        permissions::ensure_privileged(window.label(), "Event archive")?;
+        let archive = event_archive::archive(std::path::Path::new(&destination), upload, reset)?;
+        if archive.reset {
+            // 消去したイベントの印字文言を使い続けない
+            let _ = print_strings::reload();
+        }
+        Ok(archive)
+    }
+}
+
+// 端末設定コマンド
+mod config_commands {
+    use crate::config;
+    use crate::permissions;
+    use crate::print_strings;
+    use crate::server_key::SignedPayload;
+    use crate::price_book::{self, PriceBook, PriceBookUpdate};
+    use crate::server_updates;
+    use tauri::Emitter;
+
+    /// 端末設定を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_terminal_config() -> Result<config::TerminalConfig, String> {
+        config::load()
+    }
+
+    /// 端末設定を保存（メインウィンドウから責任者セッション中にだけ呼べる）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn update_terminal_config(
+        window: tauri::Window,
+        config: config::TerminalConfig,
+    ) -> Result<(), String> {
+        permissions::ensure_privileged(window.label(), "Terminal config")?;
+        config::save(&config)
+    }
+
+    /// サーバーから配信された署名付きの端末設定を検証して保存
+    #[tauri::command]
+    #[specta::specta]
+    pub fn apply_signed_config(update: SignedPayload) -> Result<config::TerminalConfig, String> {
+        server_updates::apply_config(&update)
+    }
+
+    /// サーバーから配信された署名付きの価格改定を検証して価格表に反映（変わった商品はジャーナルに記録）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn apply_price_update(
+        app: tauri::AppHandle,
+        update: SignedPayload,
+        staff_id: Option<String>,
+    ) -> Result<PriceBookUpdate, String> {
+        let applied = server_updates::apply_prices(&update, staff_id.as_deref())?;
+        let _ = app.emit(price_book::EVENT_PRICE_BOOK_UPDATED, &applied.book);
+        Ok(applied)
+    }
+
+    /// 価格表を取得（会計中のカゴはこの版の価格で計算し、レシートに版を入れる）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_price_book() -> Result<PriceBook, String> {
+        price_book::snapshot()
+    }
+
+    /// 印字文言を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_print_strings() -> print_strings::PrintStrings {
+        (*print_strings::current()).clone()
+    }
+
+    /// 選択中のイベントの印字文言を保存
+    #[tauri::command]
+    #[specta::specta]
+    pub fn update_print_strings(strings: print_strings::PrintStrings) -> Result<(), String> {
+        print_strings::save_for_event(&strings).map(|_| ())
+    }
+}
+
+// レシートQRコマンド
+mod receipt_qr_commands {
+    use crate::receipt_qr::{self, CompactClosingReport, CompactReceipt, SignedClosingReport, SignedReceipt};
+    use crate::terminal_auth;
+    use serde::Serialize;
+
+    /// 読み取ったレシートQRの内容
+    #[derive(Serialize, specta::Type)]
+    pub struct DecodedReceiptQr {
+        pub receipt: CompactReceipt,
+        /// サーバーでの検証用
+        pub signed: SignedReceipt,
+    }
+
+    /// レシートQRを復元し、発行端末の公開鍵で署名を検証する（返品処理用）
+    ///
+    /// 別の端末が発行したレシートは、その端末の公開鍵（サーバーの登録情報）を指定する
+    /// 省略した場合はこの端末が発行したレシートとして検証する
+    #[tauri::command]
+    #[specta::specta]
+    pub fn decode_receipt_qr(data: String, public_key: Option<String>) -> Result<DecodedReceiptQr, String> {
+        let signed = receipt_qr::decode(&data)?;
+        let public_key = match public_key {
+            Some(key) => key,
+            None => {
+                let status = terminal_auth::get_terminal_status().map_err(|e| e.to_string())?;
+                if status.terminal_id.as_deref() != Some(signed.signature.terminal_id.as_str()) {
+                    return Err(format!(
+                        "Receipt was issued by another terminal: {}",
+                        signed.signature.terminal_id
+                    ));
+                }
+                status.public_key.ok_or_else(|| "Terminal is not initialized".to_string())?
+            }
+        };
+        Ok(DecodedReceiptQr {
+            receipt: signed.verify(&public_key)?,
+            signed,
+        })
+    }
+
+    /// 読み取った閉局レポートQRの内容
+    #[derive(Serialize, specta::Type)]
+    pub struct DecodedClosingReportQr {
+        pub report: CompactClosingReport,
+        /// サーバーでの検証用
+        pub signed: SignedClosingReport,
+    }
+
+    /// 閉局レポートのQRを復元し、発行端末の公開鍵で署名を検証する（本部での取り込み用）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn decode_closing_report_qr(data: String, public_key: String) -> Result<DecodedClosingReportQr, String> {
+        let signed = receipt_qr::decode_closing_report(&data)?;
+        Ok(DecodedClosingReportQr {
+            report: signed.verify(&public_key)?,
+            signed,
+        })
+    }
+}
+
+// プリンタープロファイルコマンド
+mod printer_profile_commands {
+    use crate::printer_profile::{self, PrinterFeatures, PrinterProfile};
+
+    /// プリンタープロファイル一覧を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_printer_profiles() -> Result<Vec<PrinterProfile>, String> {
+        printer_profile::list()
+    }
+
+    /// プリンタープロファイルを保存
+    #[tauri::command]
+    #[specta::specta]
+    pub fn save_printer_profile(profile: PrinterProfile) -> Result<(), String> {
+        printer_profile::save(profile)
+    }
+
+    /// プリンタープロファイルを削除
+    #[tauri::command]
+    #[specta::specta]
+    pub fn delete_printer_profile(id: String) -> Result<(), String> {
+        printer_profile::delete(&id)
+    }
+
+    /// プリンターの機能を取得（QR・バーコード・カッター・ドロワー・用紙幅・画像印刷）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_printer_features(printer_id: String) -> Result<PrinterFeatures, String> {
+        printer_profile::features(&printer_id)
+    }
+}
+
+// 用紙使用量コマンド
+mod paper_usage_commands {
+    use crate::paper_usage::{self, DailyPaperUsage};
+
+    /// 選択中イベントの日別の用紙使用量
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_paper_usage() -> Result<Vec<DailyPaperUsage>, String> {
+        paper_usage::daily()
+    }
+}
+
+// スタッフ認証コマンド
+mod staff_commands {
+    use crate::staff_auth;
+
+    /// 責任者を登録（2人目以降は既存責任者の承認が必要）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn register_supervisor(
+        supervisor_id: String,
+        pin: String,
+        approval: Option<staff_auth::SupervisorApproval>,
+    ) -> Result<(), String> {
+        staff_auth::register_supervisor(&supervisor_id, &pin, approval.as_ref())
+    }
+
+    /// 責任者承認を検証
+    #[tauri::command]
+    #[specta::specta]
+    pub fn verify_supervisor(approval: staff_auth::SupervisorApproval) -> Result<(), String> {
+        staff_auth::verify_supervisor(&approval)
+    }
+
+    /// 脅迫PINを設定（本人の通常のPINでの承認が必要）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn set_duress_pin(
+        approval: staff_auth::SupervisorApproval,
+        duress_pin: String,
+    ) -> Result<(), String> {
+        staff_auth::set_duress_pin(&approval, &duress_pin)
+    }
+
+    /// 責任者セッションを開始（責任者向けのコマンドを一定時間受け付ける）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn begin_supervisor_session(
+        approval: staff_auth::SupervisorApproval,
+    ) -> Result<staff_auth::SupervisorSession, String> {
+        staff_auth::begin_session(&approval)
+    }
+
+    /// 責任者セッションを終了
+    #[tauri::command]
+    #[specta::specta]
+    pub fn end_supervisor_session() -> Result<(), String> {
+        staff_auth::end_session()
+    }
+
+    /// 開始中の責任者セッションを取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_supervisor_session() -> Result<Option<staff_auth::SupervisorSession>, String> {
+        staff_auth::active_session()
+    }
+}
+
+// ジャーナルコマンド
+mod journal_commands {
+    use crate::journal;
+    use crate::kiosk;
+
+    /// 選択中イベントのジャーナルを取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_journal() -> Result<Vec<journal::JournalEntry>, String> {
+        journal::entries()
+    }
+
+    /// レシートの再発行回数を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_reprint_count(receipt_number: String) -> Result<u32, String> {
+        journal::reprint_count(&receipt_number)
+    }
+
+    /// 返品を記録（linesを省略した場合は全返品）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn refund_sale(
+        original_receipt_number: String,
+        lines: Option<Vec<journal::RefundLine>>,
+        reason: String,
+        staff_id: String,
+    ) -> Result<journal::JournalEntry, String> {
+        kiosk::ensure_attended("Refund")?;
+        journal::record_refund(&original_receipt_number, lines, &reason, &staff_id)
+    }
+
+    /// 取引取消を記録
+    #[tauri::command]
+    #[specta::specta]
+    pub fn void_sale(
+        receipt_number: String,
+        reason: String,
+        staff_id: String,
+    ) -> Result<journal::JournalEntry, String> {
+        kiosk::ensure_attended("Void")?;
+        journal::record_void(&receipt_number, &reason, &staff_id)
+    }
+
+    /// 売上・返品・取消の集計を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_journal_totals() -> Result<journal::JournalTotals, String> {
+        journal::totals()
+    }
+}
+
+// セルフレジ（キオスク）コマンド
+mod kiosk_commands {
+    use crate::kiosk::{self, KioskState};
+    use crate::receipt::{ReceiptData, ReceiptItem};
+    use crate::remote_commands;
+    use crate::staff_auth::{self, SupervisorApproval};
+    use tauri::Emitter;
+
+    /// 状態を通知して返す
+    fn notify(app: &tauri::AppHandle, state: KioskState) -> KioskState {
+        let _ = app.emit(kiosk::EVENT_KIOSK_STATE, &state);
+        state
+    }
+
+    /// キオスクの状態を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_kiosk_state() -> Result<KioskState, String> {
+        kiosk::current()
+    }
+
+    /// キオスクモードを開始
+    #[tauri::command]
+    #[specta::specta]
+    pub fn start_kiosk(app: tauri::AppHandle) -> Result<KioskState, String> {
+        remote_commands::ensure_unlocked("Kiosk mode")?;
+        Ok(notify(&app, kiosk::start()?))
+    }
+
+    /// キオスクモードを終了（無人運用のため責任者承認が必要）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn stop_kiosk(app: tauri::AppHandle, approval: SupervisorApproval) -> Result<KioskState, String> {
+        staff_auth::verify_supervisor(&approval)?;
+        Ok(notify(&app, kiosk::stop()?))
+    }
+
+    /// スキャンした商品をカゴに追加
+    #[tauri::command]
+    #[specta::specta]
+    pub fn kiosk_scan(app: tauri::AppHandle, item: ReceiptItem) -> Result<KioskState, String> {
+        Ok(notify(&app, kiosk::scan(item)?))
+    }
+
+    /// カゴから明細を削除
+    #[tauri::command]
+    #[specta::specta]
+    pub fn kiosk_remove_item(app: tauri::AppHandle, line_index: usize) -> Result<KioskState, String> {
+        Ok(notify(&app, kiosk::remove_item(line_index)?))
+    }
+
+    /// カゴを空にする
+    #[tauri::command]
+    #[specta::specta]
+    pub fn kiosk_clear_basket(app: tauri::AppHandle) -> Result<KioskState, String> {
+        Ok(notify(&app, kiosk::clear_basket()?))
+    }
+
+    /// 会計に進む（QR決済の照合用IDを発行）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn kiosk_checkout(app: tauri::AppHandle) -> Result<KioskState, String> {
+        Ok(notify(&app, kiosk::checkout()?))
+    }
+
+    /// 決済をやめてスキャンに戻る
+    #[tauri::command]
+    #[specta::specta]
+    pub fn kiosk_cancel_payment(app: tauri::AppHandle) -> Result<KioskState, String> {
+        Ok(notify(&app, kiosk::cancel_payment()?))
+    }
+
+    /// QR決済の完了を受けて売上を確定し、レシートを自動印刷する
+    ///
+    /// 売上は確定済みのため、印刷の失敗は通知のみ（スタッフが再発行で対応する）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn kiosk_complete_payment(
+        app: tauri::AppHandle,
+        payment_ref: String,
+        payment_id: Option<String>,
+    ) -> Result<ReceiptData, String> {
+        let (receipt, state) = kiosk::complete_payment(&payment_ref, payment_id)?;
+        notify(&app, state);
+
+        #[cfg(not(target_os = "android"))]
+        if let Err(error) = crate::desktop_printer::print_kiosk_receipt(&receipt) {
+            let _ = app.emit(
+                kiosk::EVENT_KIOSK_PRINT_FAILED,
+                kiosk::KioskPrintFailedEvent {
+                    receipt_number: receipt.receipt_number.clone(),
+                    error,
+                },
+            );
+        }
+
+        Ok(receipt)
+    }
+}
+
+// 印刷スプーラーコマンド
+mod spooler_commands {
+    use crate::config;
+    use crate::idempotency;
+    use crate::journal::{self, SaleChannel};
+    use crate::parked_sales;
+    use crate::receipt::{ClosingReportData, ReceiptData};
+    use crate::remote_commands;
+    use crate::sales;
+    use crate::spooler::{self, PrintQueue, SpoolDocument, SpoolJob, UsbPrinter};
+    use crate::staff_auth::SupervisorApproval;
+    use crate::upload_queue;
+    use tauri::Emitter;
+
+    /// レシートをスプーラー経由で印刷（結果は print-job-finished イベントで通知）
+    ///
+    /// mirror を省略した場合は端末設定のジャーナル用プリンターにもミラー印刷する
+    #[tauri::command]
+    #[specta::specta]
+    pub fn spool_receipt(
+        receipt: ReceiptData,
+        printer: UsbPrinter,
+        mirror: Option<UsbPrinter>,
+        idempotency_key: Option<String>,
+        limit_approval: Option<SupervisorApproval>,
+    ) -> Result<SpoolJob, String> {
+        remote_commands::ensure_unlocked("Sale")?;
+        idempotency::run(idempotency_key.as_deref(), "spool_receipt", || {
+            // ミラー先の数によらずジャーナルへの記録は1回だけ
+            let mut receipt = receipt;
+            sales::prepare_sale(&mut receipt, limit_approval.as_ref(), SaleChannel::Counter)?;
+
+            let mirror = mirror.or(config::load()?.journal_printer);
+            let targets = std::iter::once(printer).chain(mirror).collect();
+            spooler::submit(
+                SpoolDocument::Receipt {
+                    receipt: Box::new(receipt),
+                },
+                targets,
+            )
+        })
+    }
+
+    /// 保留取引のお預かり票をスプーラー経由で印刷（レシートより後、レポートより先に印刷）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn spool_parked_slip(code: String, printer: UsbPrinter,
+        idempotency_key: Option<String>,
+    ) -> Result<SpoolJob, String> {
+        idempotency::run(idempotency_key.as_deref(), "spool_parked_slip", || {
+            let sale = parked_sales::find(&code)?
+                .ok_or_else(|| format!("Parked sale not found: {}", code))?;
+            spooler::submit(
+                SpoolDocument::ParkedSlip {
+                    sale: Box::new(sale),
+                },
+                vec![printer],
+            )
+        })
+    }
+
+    /// 閉局レポートをスプーラー経由で印刷（最も低い優先度で印刷）
+    ///
+    /// 集計は受付時点のジャーナルで固定し、本部へのアップロードも受付時にキューに追加する
+    #[tauri::command]
+    #[specta::specta]
+    pub fn spool_closing_report(
+        app: tauri::AppHandle,
+        mut report: ClosingReportData,
+        printer: UsbPrinter,
+        idempotency_key: Option<String>,
+    ) -> Result<SpoolJob, String> {
+        journal::complete_closing_report(&mut report)?;
+        idempotency::run(idempotency_key.as_deref(), "spool_closing_report", || {
+            let job = spooler::submit(
+                SpoolDocument::ClosingReport {
+                    report: Box::new(report.clone()),
+                    totals: journal::totals()?,
+                },
+                vec![printer],
+            )?;
+
+            match upload_queue::enqueue(upload_queue::KIND_CLOSING_REPORT, &report.id, &report) {
+                Ok(upload) => {
+                    let _ = app.emit(upload_queue::EVENT_UPLOAD_QUEUED, &upload);
+                }
+                Err(e) => {
+                    let _ = app.emit(
+                        upload_queue::EVENT_UPLOAD_FAILED,
+                        upload_queue::UploadFailedEvent {
+                            upload_id: None,
+                            kind: upload_queue::KIND_CLOSING_REPORT.to_string(),
+                            reference_id: report.id.clone(),
+                            error: e,
+                            attempts: 0,
+                        },
+                    );
+                }
+            }
+
+            Ok(job)
+        })
+    }
+
+    /// 印刷中のジョブと印刷待ちのジョブ一覧（優先度順、進み具合は print-job-progress イベントで通知）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_print_queue() -> PrintQueue {
+        spooler::queue()
+    }
+
+    /// 印刷待ちのジョブを取り消す（印刷中のジョブは取り消せない）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn cancel_print_job(job_id: String) -> Result<SpoolJob, String> {
+        spooler::cancel(&job_id)
+    }
+}
+
+// 保留取引コマンド
+mod parked_sale_commands {
+    use crate::parked_sales;
+    use crate::receipt::ReceiptItem;
+
+    /// カゴを保留
+    #[tauri::command]
+    #[specta::specta]
+    pub fn park_sale(
+        items: Vec<ReceiptItem>,
+        total: u32,
+        staff_id: String,
+        memo: Option<String>,
+    ) -> Result<parked_sales::ParkedSale, String> {
+        parked_sales::park(items, total, &staff_id, memo)
+    }
+
+    /// 保留中の取引一覧
+    #[tauri::command]
+    #[specta::specta]
+    pub fn list_parked_sales() -> Result<Vec<parked_sales::ParkedSale>, String> {
+        parked_sales::list()
+    }
+
+    /// 保留を解除してカゴを取り出す
+    #[tauri::command]
+    #[specta::specta]
+    pub fn resume_parked_sale(code: String) -> Result<parked_sales::ParkedSale, String> {
+        parked_sales::resume(&code)
+    }
+}
+
+// 呼び出し表示コマンド
+mod now_serving_commands {
+    use crate::now_serving::{self, NowServing};
+    use tauri::Emitter;
+
+    /// 状態を客面表示に通知して返す
+    fn notify(app: &tauri::AppHandle, state: NowServing) -> NowServing {
+        let _ = app.emit(now_serving::EVENT_NOW_SERVING, &state);
+        state
+    }
+
+    /// 呼び出し表示の状態を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_now_serving() -> Result<NowServing, String> {
+        now_serving::current()
+    }
+
+    /// 番号を呼び出して客面表示に出す（呼び出し票は print_recall_slip で印刷）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn call_now_serving(app: tauri::AppHandle, code: String, staff_id: String) -> Result<NowServing, String> {
+        Ok(notify(&app, now_serving::call(&code, &staff_id)?))
+    }
+
+    /// 呼び出し表示を消す
+    #[tauri::command]
+    #[specta::specta]
+    pub fn clear_now_serving(app: tauri::AppHandle) -> Result<NowServing, String> {
+        Ok(notify(&app, now_serving::clear()?))
+    }
+}
 
-            // 取引内容
-            let tx_type = card.transaction_type.as_deref().unwrap_or("sale");
-            let tx_type_display = match tx_type {
-                "sale" => "売上",
-                "refund" => "返品",
-                _ => tx_type,
-            };
-            printer.row_auto("取引内容:", tx_type_display)?;
-
-            // 支払い区分
-            let payment_type = card.payment_type.as_deref().unwrap_or("一括");
-            printer.row_auto("支払区分:", payment_type)?;
-
-            // カード会社
-            if let Some(ref brand) = card.brand {
-                let brand_display = match brand.to_lowercase().as_str() {
-                    "visa" => "VISA",
-                    "mastercard" | "mc" => "MasterCard",
-                    "amex" | "american_express" => "AMEX",
-                    "jcb" => "JCB",
-                    "diners" | "diners_club" => "Diners Club",
-                    "discover" => "Discover",
-                    "unionpay" => "UnionPay",
-                    _ => brand,
-                };
-                printer.row_auto("カード会社:", brand_display)?;
-            }
+// 見積コマンド
+mod quote_commands {
+    use crate::quotes;
 
-            // 有効期限
-            if let (Some(month), Some(year)) = (card.exp_month, card.exp_year) {
-                let year_short = year % 100;
-                printer.row_auto("有効期限:", &format!("{:02}/{:02}", month, year_short))?;
-            }
+    /// 見積番号またはQRコードの文字列から見積を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_quote(code: String) -> Result<quotes::Quote, String> {
+        quotes::find(&code)?.ok_or_else(|| format!("Quote not found: {}", code))
+    }
 
-            printer.separator()?;
+    /// 見積を売上に変換済みとして記録
+    #[tauri::command]
+    #[specta::specta]
+    pub fn convert_quote(code: String, receipt_number: String) -> Result<quotes::Quote, String> {
+        quotes::mark_converted(&code, &receipt_number)
+    }
+}
 
-            // 利用金額
-            printer.row_auto_bold("ご利用金額:", &format_price(receipt.total))?;
+// 予約コマンド
+mod reservation_commands {
+    use crate::receipt::ReceiptItem;
+    use crate::reservations;
 
-            printer.textln("")?;
+    /// 予約を登録
+    #[tauri::command]
+    #[specta::specta]
+    pub fn register_reservation(
+        items: Vec<ReceiptItem>,
+        customer_name: Option<String>,
+        pickup_note: Option<String>,
+        quote_code: Option<String>,
+        staff_id: String,
+    ) -> Result<reservations::Reservation, String> {
+        reservations::register(items, customer_name, pickup_note, quote_code, &staff_id)
+    }
 
-            // 署名欄
-            printer.jp_textln("【お客様サイン】", TextStyle::default().bold())?;
-            printer.textln("")?;
-            printer.jp_textln_padded("＜IC取引につき不要＞", TextStyle::default().center())?;
-            printer.textln("")?;
-            printer.separator()?;
+    /// 予約番号またはQRコードの文字列から予約を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn find_reservation(code: String) -> Result<reservations::Reservation, String> {
+        reservations::find(&code)?.ok_or_else(|| format!("Reservation not found: {}", code))
+    }
 
-            printer.jp_textln("上記正に受領いたしました", TextStyle::default().center())?;
+    /// 予約一覧
+    #[tauri::command]
+    #[specta::specta]
+    pub fn list_reservations() -> Result<Vec<reservations::Reservation>, String> {
+        reservations::list()
+    }
 
-            // 決済番号QRコード（PaymentIntent ID）
-            if let Some(ref pi_id) = receipt.payment_intent_id {
-                printer.textln("")?;
-                printer.qr_code_center(pi_id, Some(4))?;
-            }
-        }
+    /// 受け渡し済みにする
+    #[tauri::command]
+    #[specta::specta]
+    pub fn redeem_reservation(
+        code: String,
+        staff_id: String,
+    ) -> Result<reservations::Reservation, String> {
+        reservations::redeem(&code, &staff_id)
+    }
 
-        printer.feed(3)?;
-        printer.cut()?;
+    /// 予約をキャンセル
+    #[tauri::command]
+    #[specta::specta]
+    pub fn cancel_reservation(
+        code: String,
+        staff_id: String,
+    ) -> Result<reservations::Reservation, String> {
+        reservations::cancel(&code, &staff_id)
+    }
 
-        Ok(())
+    /// 同期用: 指定日時以降に更新された予約を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn export_reservations(since: u64) -> Result<Vec<reservations::Reservation>, String> {
+        reservations::changed_since(since)
     }
 
-    /// ISO8601形式の日時を読みやすい形式に変換
-    fn format_transaction_datetime(iso_datetime: &str) -> String {
-        // 簡易パース: 2025-12-16T10:30:00.000Z のような形式を想定
-        if iso_datetime.len() >= 16 {
-            let date_part = &iso_datetime[0..10];
-            let time_part = &iso_datetime[11..16];
-            let date_formatted = date_part.replace('-', "/");
-            format!("{} {}", date_formatted, time_part)
-        } else {
-            iso_datetime.to_string()
-        }
+    /// 同期用: 他端末の予約をマージ
+    #[tauri::command]
+    #[specta::specta]
+    pub fn merge_reservations(
+        reservations: Vec<reservations::Reservation>,
+    ) -> Result<usize, String> {
+        reservations::merge(reservations)
     }
+}
+
+// 内金コマンド
+mod deposit_commands {
+    use crate::deposits;
+    use crate::kiosk;
+    use crate::receipt::ReceiptData;
 
-    /// 金種カウント
-    #[derive(Debug, Clone, Deserialize)]
-    pub struct DenominationCount {
-        pub denomination: u32,
-        pub count: u32,
+    /// 残金を受け取っていない内金の売上一覧
+    #[tauri::command]
+    #[specta::specta]
+    pub fn list_open_deposits() -> Result<Vec<ReceiptData>, String> {
+        deposits::open_deposits()
     }
 
-    /// 商品券カウント
-    #[derive(Debug, Clone, Deserialize)]
-    pub struct VoucherCount {
-        #[serde(rename = "type")]
-        pub voucher_type: String,
-        pub amount: u32,
-        pub memo: Option<String>,
+    /// 内金の売上から残金の売上を作る（支払情報を入れて print_receipt で印刷すると記録される）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn prepare_deposit_balance(
+        deposit_receipt_number: String,
+        staff_id: String,
+    ) -> Result<ReceiptData, String> {
+        kiosk::ensure_attended("Deposit balance")?;
+        deposits::prepare_balance(&deposit_receipt_number, &staff_id)
     }
 
-    /// 閉局レポートデータ
-    #[derive(Debug, Clone, Deserialize)]
-    pub struct ClosingReportData {
-        pub id: String,
-        pub terminal_id: String,
-        pub staff_id: String,
-        pub staff_name: String,
-        pub event_name: Option<String>,
-        pub denominations: Vec<DenominationCount>,
-        pub cash_total: u32,
-        pub vouchers: Vec<VoucherCount>,
-        pub voucher_total: u32,
-        pub grand_total: u32,
-        pub expected_total: u32,
-        pub difference: i32,
-        pub transaction_count: u32,
-        pub closed_at: String,
+    /// 内金の売上に対する残金の売上（残金を受け取っていない場合は null）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_deposit_balance_sale(deposit_receipt_number: String) -> Result<Option<ReceiptData>, String> {
+        deposits::balance_sale(&deposit_receipt_number)
     }
+}
 
-    /// 閉局レポート印刷
+// 商品券コマンド
+mod voucher_commands {
+    use crate::config;
+    use crate::vouchers::{self, VoucherKind, VoucherTender, VoucherTenderResult};
+
+    /// 商品券カタログを取得
     #[tauri::command]
-    pub fn print_closing_report(
-        vendor_id: u16,
-        device_id: u16,
-        report: ClosingReportData,
-        paper_width: Option<u8>,
-    ) -> Result<(), String> {
-        let driver = NativeUsbDriver::open(vendor_id, device_id)
-            .map_err(|e| e.to_string())?;
+    #[specta::specta]
+    pub fn get_voucher_catalog() -> Result<Vec<VoucherKind>, String> {
+        Ok(config::load()?.voucher_catalog)
+    }
 
-        let width = parse_paper_width(paper_width);
-        let mut printer = JpPrinter::with_paper_width(driver, width);
-        printer.init()?;
+    /// 支払に使う商品券を検証し、充当額・おつりを計算
+    #[tauri::command]
+    #[specta::specta]
+    pub fn validate_voucher_tender(
+        tenders: Vec<VoucherTender>,
+        amount_due: u32,
+    ) -> Result<VoucherTenderResult, String> {
+        vouchers::validate_tender(&config::load()?.voucher_catalog, &tenders, amount_due)
+    }
+}
 
-        // ヘッダー
-        printer.jp_textln_padded("閉局レポート", TextStyle::default().double().reverse().center())?;
-        printer.textln("")?;
+// 支払コマンド
+mod tender_commands {
+    use crate::config;
+    use crate::receipt::PaymentInfo;
+    use crate::tenders::{self, TenderBreakdown};
 
-        // イベント名
-        if let Some(ref event_name) = report.event_name {
-            if !event_name.is_empty() {
-                printer.jp_textln(event_name, TextStyle::default().bold().center())?;
-            }
-        }
+    /// 分割払いの支払情報を検証し、支払ごとの充当額・おつりを計算（印刷時にも同じ検証を行う）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn validate_payments(total: u32, payments: Vec<PaymentInfo>) -> Result<TenderBreakdown, String> {
+        tenders::validate(&config::load()?.voucher_catalog, total, &payments)
+    }
+}
 
-        // 基本情報
-        printer.separator()?;
-        printer.row_auto("レポートID:", &report.id)?;
-        printer.row_auto("端末ID:", &report.terminal_id)?;
-        printer.row_auto("担当者:", &format!("{} ({})", report.staff_name, report.staff_id))?;
-        printer.row_auto("閉局日時:", &report.closed_at)?;
-        printer.separator()?;
+// ギフト券コマンド
+mod gift_voucher_commands {
+    use crate::gift_vouchers::{self, GiftVoucher};
 
-        // 売上サマリー
-        printer.jp_textln("【売上サマリー】", TextStyle::default().bold())?;
-        printer.row_auto("取引件数:", &format!("{}件", report.transaction_count))?;
-        printer.row_auto("売上合計(税込):", &format_price(report.expected_total))?;
-        printer.separator()?;
+    /// 券番号またはQRコードの文字列から、支払に使えるか確認
+    #[tauri::command]
+    #[specta::specta]
+    pub fn validate_gift_voucher(code: String) -> Result<GiftVoucher, String> {
+        gift_vouchers::validate(&code)
+    }
 
-        // 金種別カウント
-        printer.jp_textln("【現金内訳】", TextStyle::default().bold())?;
-        for d in &report.denominations {
-            if d.count > 0 {
-                let subtotal = d.denomination * d.count;
-                printer.row_auto(
-                    &format!("{}円 x {}", d.denomination, d.count),
-                    &format_price(subtotal),
-                )?;
-            }
-        }
-        printer.row_auto_bold("現金合計:", &format_price(report.cash_total))?;
-        printer.separator()?;
+    /// 使用済みにする
+    #[tauri::command]
+    #[specta::specta]
+    pub fn redeem_gift_voucher(
+        code: String,
+        staff_id: String,
+        receipt_number: Option<String>,
+    ) -> Result<GiftVoucher, String> {
+        gift_vouchers::redeem(&code, &staff_id, receipt_number)
+    }
 
-        // 商品券等
-        if !report.vouchers.is_empty() {
-            printer.jp_textln("【商品券等】", TextStyle::default().bold())?;
-            for v in &report.vouchers {
-                let label = if let Some(ref memo) = v.memo {
-                    format!("{} ({})", v.voucher_type, memo)
-                } else {
-                    v.voucher_type.clone()
-                };
-                printer.row_auto(&label, &format_price(v.amount))?;
-            }
-            printer.row_auto_bold("商品券等合計:", &format_price(report.voucher_total))?;
-            printer.separator()?;
-        }
+    /// ギフト券一覧
+    #[tauri::command]
+    #[specta::specta]
+    pub fn list_gift_vouchers() -> Result<Vec<GiftVoucher>, String> {
+        gift_vouchers::list()
+    }
 
-        // 合計と差異
-        printer.jp_textln("【精算】", TextStyle::default().bold())?;
-        printer.row_auto_bold("実査合計:", &format_price(report.grand_total))?;
-        printer.row_auto("売上合計:", &format_price(report.expected_total))?;
+    /// 同期用: 指定日時以降に更新されたギフト券を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn export_gift_vouchers(since: u64) -> Result<Vec<GiftVoucher>, String> {
+        gift_vouchers::changed_since(since)
+    }
 
-        let diff_str = if report.difference >= 0 {
-            format!("+{}", format_price(report.difference as u32))
-        } else {
-            format!("-{}", format_price((-report.difference) as u32))
-        };
-        printer.row_auto_bold("差異:", &diff_str)?;
+    /// 同期用: 他端末のギフト券をマージ
+    #[tauri::command]
+    #[specta::specta]
+    pub fn merge_gift_vouchers(vouchers: Vec<GiftVoucher>) -> Result<usize, String> {
+        gift_vouchers::merge(vouchers)
+    }
+}
 
-        printer.textln("")?;
-        printer.separator()?;
+// 会員コマンド
+mod member_commands {
+    use crate::members::{self, Member, MemberPurchase};
+    use crate::points;
 
-        // フッター
-        printer.jp_textln("このレポートは閉局処理の記録です", TextStyle::default().center())?;
+    /// 会員証のQRコード・NFCのUID・会員番号から会員を検索
+    #[tauri::command]
+    #[specta::specta]
+    pub fn find_member(scanned: String) -> Result<Member, String> {
+        members::find(&scanned)?.ok_or_else(|| "Member not found".to_string())
+    }
 
-        printer.feed(3)?;
-        printer.cut()?;
+    /// 会員一覧
+    #[tauri::command]
+    #[specta::specta]
+    pub fn list_members() -> Result<Vec<Member>, String> {
+        members::list()
+    }
 
-        Ok(())
+    /// サーバーから受け取った会員を取り込む
+    #[tauri::command]
+    #[specta::specta]
+    pub fn import_members(members: Vec<Member>) -> Result<usize, String> {
+        members::import(members)
     }
-}
 
-// Android Bluetooth printer implementation
-#[cfg(target_os = "android")]
-mod android_printer {
-    #[derive(Debug, Clone, serde::Serialize)]
-    pub struct BluetoothDevice {
-        pub address: String,
-        pub name: String,
+    /// 購入履歴を取得（書き出し用。member_idを省略した場合は全会員）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_member_purchases(member_id: Option<String>, since: u64) -> Vec<MemberPurchase> {
+        members::purchases(member_id.as_deref(), since)
     }
 
+    /// 会員のポイント残高
     #[tauri::command]
-    pub async fn get_bluetooth_devices() -> Result<Vec<BluetoothDevice>, String> {
-        // TODO: Implement Bluetooth device discovery via JNI
-        // For now, return empty list
-        Ok(vec![])
+    #[specta::specta]
+    pub fn get_points_balance(member_id: String) -> Result<u64, String> {
+        points::balance(&member_id)?.ok_or_else(|| "Member not found".to_string())
     }
+}
+
+// 価格変更コマンド
+mod price_override_commands {
+    use crate::config;
+    use crate::price_overrides::{self, PriceOverrideReason, PriceOverrideRequest};
+    use crate::receipt::PriceOverride;
+    use crate::staff_auth::SupervisorApproval;
 
+    /// 価格変更の理由一覧
     #[tauri::command]
-    pub fn connect_bluetooth_printer(address: String) -> Result<(), String> {
-        // TODO: Implement Bluetooth connection
-        Err("Bluetooth printer not yet implemented".to_string())
+    #[specta::specta]
+    pub fn get_price_override_reasons() -> Result<Vec<PriceOverrideReason>, String> {
+        Ok(config::load()?.price_override_reasons)
     }
 
+    /// 責任者の承認で明細の価格を変更（返した印を明細の price_override に入れて印刷する）
     #[tauri::command]
-    pub fn bluetooth_print(
-        address: String,
-        text: String,
-        paper_width: Option<u8>,
-    ) -> Result<(), String> {
-        // TODO: Implement Bluetooth printing
-        Err("Bluetooth printing not yet implemented".to_string())
+    #[specta::specta]
+    pub fn approve_price_override(
+        request: PriceOverrideRequest,
+        staff_id: String,
+        approval: SupervisorApproval,
+    ) -> Result<PriceOverride, String> {
+        price_overrides::approve(&request, &staff_id, &approval)
     }
+}
+
+// 年齢確認コマンド
+mod age_check_commands {
+    use crate::age_check;
+    use crate::receipt::AgeVerification;
 
+    /// スタッフが年齢を確認したことを記録（返した記録をレシートの age_verification に入れて印刷する。1件の売上にだけ使える）
     #[tauri::command]
-    pub fn bluetooth_welcome_print(
-        address: String,
-        id: String,
-        paper_width: Option<u8>,
-    ) -> Result<(), String> {
-        // TODO: Implement Bluetooth welcome print
-        Err("Bluetooth printing not yet implemented".to_string())
+    #[specta::specta]
+    pub fn confirm_age_verification(staff_id: String) -> Result<AgeVerification, String> {
+        age_check::confirm(&staff_id)
     }
 }
 
-// Common commands available on all platforms
-mod common {
+// キャンペーンコマンド
+mod promotion_commands {
+    use crate::config;
+    use crate::promotions::{self, Promotion, PromotionQuote};
+    use crate::receipt::ReceiptItem;
+
+    /// 設定されたキャンペーン一覧
     #[tauri::command]
-    pub fn get_platform() -> String {
-        #[cfg(target_os = "android")]
-        return "android".to_string();
+    #[specta::specta]
+    pub fn get_promotions() -> Result<Vec<Promotion>, String> {
+        Ok(config::load()?.promotions)
+    }
 
-        #[cfg(target_os = "ios")]
-        return "ios".to_string();
+    /// 会計の明細にキャンペーンを当てはめ、割引後の合計を計算
+    #[tauri::command]
+    #[specta::specta]
+    pub fn quote_promotions(items: Vec<ReceiptItem>) -> Result<PromotionQuote, String> {
+        promotions::quote(&items)
+    }
+}
 
-        #[cfg(target_os = "macos")]
-        return "macos".to_string();
+// 端数調整コマンド
+mod rounding_commands {
+    use crate::rounding::{self, RoundingQuote};
 
-        #[cfg(target_os = "windows")]
-        return "windows".to_string();
+    /// 会計の合計（キャンペーンの割引後）を端末設定で丸める
+    #[tauri::command]
+    #[specta::specta]
+    pub fn quote_rounding(amount: u32, cash_only_payment: bool) -> Result<RoundingQuote, String> {
+        rounding::quote(amount, cash_only_payment)
+    }
+}
 
-        #[cfg(target_os = "linux")]
-        return "linux".to_string();
+// 売上確定コマンド
+mod sale_commands {
+    use crate::idempotency;
+    use crate::journal::SaleChannel;
+    use crate::receipt::ReceiptData;
+    use crate::remote_commands;
+    use crate::sales;
+    use crate::staff_auth::SupervisorApproval;
 
-        #[cfg(not(any(
-            target_os = "android",
-            target_os = "ios",
-            target_os = "macos",
-            target_os = "windows",
-            target_os = "linux"
-        )))]
-        return "unknown".to_string();
+    /// 売上を確認してジャーナルに記録し、印刷するレシートを返す
+    ///
+    /// Rustで印刷しない経路（AndroidのBluetoothプリンター）は、印刷の前にこのコマンドを通す
+    #[tauri::command]
+    #[specta::specta]
+    pub fn prepare_sale(
+        receipt: ReceiptData,
+        idempotency_key: Option<String>,
+        limit_approval: Option<SupervisorApproval>,
+    ) -> Result<ReceiptData, String> {
+        remote_commands::ensure_unlocked("Sale")?;
+        idempotency::run(idempotency_key.as_deref(), "prepare_sale", || {
+            let mut receipt = receipt;
+            sales::prepare_sale(&mut receipt, limit_approval.as_ref(), SaleChannel::Counter)?;
+            Ok(receipt)
+        })
     }
 }
 
-// 端末認証コマンド
-mod terminal_commands {
-    use crate::terminal_auth;
+// 計測コマンド
+mod metrics_commands {
+    use crate::metrics;
 
-    /// 端末の状態を取得
+    /// 記録した稼働状況の計測値を取得（計測が無効な場合は空の値）
     #[tauri::command]
-    pub fn get_terminal_status() -> Result<terminal_auth::TerminalAuthResult, String> {
-        terminal_auth::get_terminal_status().map_err(|e| e.to_string())
+    #[specta::specta]
+    pub fn get_metrics() -> Result<metrics::Metrics, String> {
+        metrics::current()
     }
+}
 
-    /// 端末を初期化（キーペア生成）
+// 接続先環境コマンド
+mod environment_commands {
+    use crate::environment::{self, ApiEnvironment, EnvironmentState};
+    use crate::permissions;
+    use crate::print_strings;
+    use crate::staff_auth;
+    use tauri::Emitter;
+
+    /// 選択中の接続先環境を取得
     #[tauri::command]
-    pub fn initialize_terminal(
-        device_name: String,
-    ) -> Result<terminal_auth::RegistrationQrPayload, String> {
-        terminal_auth::initialize_terminal(&device_name).map_err(|e| e.to_string())
+    #[specta::specta]
+    pub fn get_environment() -> Result<ApiEnvironment, String> {
+        environment::current()
     }
 
-    /// QRコード用のJSONデータを生成
+    /// 接続先環境を切り替え
+    ///
+    /// メインウィンドウから責任者セッション中に、閉局して送信待ちのデータがない状態でだけ切り替えられる
     #[tauri::command]
-    pub fn generate_registration_qr(
-        device_name: String,
-    ) -> Result<String, String> {
-        // 既に初期化されている場合は現在の状態を返す
-        let status = terminal_auth::get_terminal_status().map_err(|e| e.to_string())?;
+    #[specta::specta]
+    pub fn switch_environment(
+        app: tauri::AppHandle,
+        window: tauri::Window,
+        environment: ApiEnvironment,
+    ) -> Result<EnvironmentState, String> {
+        permissions::ensure_privileged(window.label(), "Environment")?;
+        let supervisor = staff_auth::active_session()?.map(|s| s.supervisor_id);
+        let state = environment::switch(environment, supervisor.as_deref())?;
+        // 環境ごとのイベント別の印字文言に切り替える（壊れている場合は既定の文言で続ける）
+        let _ = print_strings::reload();
+        let _ = app.emit("environment-switched", &state);
+        Ok(state)
+    }
+}
 
-        let payload = if status.status == "initialized" {
-            // 既存のデータからペイロードを構築
-            terminal_auth::RegistrationQrPayload {
-                v: 1,
-                terminal_id: status.terminal_id.unwrap_or_default(),
-                public_key: status.public_key.unwrap_or_default(),
-                device_name,
-                os: get_os_type(),
-                created_at: "".to_string(), // 既存のため空
-            }
-        } else {
-            // 新規初期化
-            terminal_auth::initialize_terminal(&device_name).map_err(|e| e.to_string())?
-        };
+// 商品データコマンド（取り込み・仮登録・ISDN照会）
+mod catalog_commands {
+    use crate::catalog_import::{self, CatalogImport, CatalogMapping};
+    use crate::isdn_registry::{self, IsdnLookup};
+    use crate::permissions;
+    use crate::provisional_products::{self, ProvisionalProduct};
+    use std::path::Path;
 
-        serde_json::to_string(&payload).map_err(|e| e.to_string())
+    /// CSV・Excelの商品一覧を読み込んで検証する（ローカルの商品キャッシュへの追加はフロントエンドで行う）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn import_catalog(path: String, mapping: CatalogMapping) -> Result<CatalogImport, String> {
+        catalog_import::import(Path::new(&path), &mapping)
     }
 
-    /// 認証用の署名データを生成
+    /// 商品データにないコードをスキャンした場合に、入力した値段で商品を仮登録する
+    ///
+    /// 要確認の印を付けて保存し、本部への送信をアップロードキューに積む
     #[tauri::command]
-    pub fn create_auth_signature() -> Result<terminal_auth::SignatureData, String> {
-        terminal_auth::create_auth_signature().map_err(|e| e.to_string())
+    #[specta::specta]
+    pub fn create_product_from_scan(
+        code: String,
+        name: String,
+        price: u32,
+        circle_name: Option<String>,
+        staff_id: String,
+    ) -> Result<ProvisionalProduct, String> {
+        provisional_products::create(&code, &name, price, circle_name, &staff_id)
+    }
+
+    /// ISDNの登録情報を照会する（商品の追加時の入力補助）
+    ///
+    /// 照会に時間がかかってもメインスレッドを止めないよう、非同期のコマンドとして実行する
+    #[tauri::command(async)]
+    #[specta::specta]
+    pub fn lookup_isdn(isdn: String) -> Result<IsdnLookup, String> {
+        isdn_registry::lookup(&isdn)
     }
 
-    /// Keychainをクリア（デバッグ用）
+    /// 仮登録した商品の一覧
     #[tauri::command]
-    pub fn clear_terminal_keychain() -> Result<(), String> {
-        terminal_auth::clear_keychain().map_err(|e| e.to_string())
+    #[specta::specta]
+    pub fn list_provisional_products() -> Result<Vec<ProvisionalProduct>, String> {
+        provisional_products::list()
     }
 
-    fn get_os_type() -> String {
-        #[cfg(target_os = "macos")]
-        return "macos".to_string();
+    /// 仮登録した商品を確認済みにする（メインウィンドウから責任者セッション中にだけ呼べる）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn review_provisional_product(
+        window: tauri::Window,
+        jan: String,
+    ) -> Result<ProvisionalProduct, String> {
+        permissions::ensure_privileged(window.label(), "Catalog")?;
+        provisional_products::mark_reviewed(&jan)
+    }
+}
 
-        #[cfg(target_os = "windows")]
-        return "windows".to_string();
+// 売上目標コマンド
+mod sales_target_commands {
+    use crate::sales_targets::{self, SalesProgress, SalesTarget};
 
-        #[cfg(target_os = "linux")]
-        return "linux".to_string();
+    /// 設定中の売上目標を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_sales_target() -> Result<Option<SalesTarget>, String> {
+        sales_targets::current()
+    }
 
-        #[cfg(target_os = "android")]
-        return "android".to_string();
+    /// 売上目標を設定（目標を 0 にすると設定を消す）
+    #[tauri::command]
+    #[specta::specta]
+    pub fn set_sales_target(
+        target_amount: u64,
+        break_even_amount: Option<u64>,
+        staff_id: Option<String>,
+    ) -> Result<Option<SalesTarget>, String> {
+        sales_targets::set(target_amount, break_even_amount, staff_id.as_deref())
+    }
 
-        #[cfg(not(any(
-            target_os = "macos",
-            target_os = "windows",
-            target_os = "linux",
-            target_os = "android"
-        )))]
-        return "unknown".to_string();
+    /// 売上目標に対する進捗を取得
+    #[tauri::command]
+    #[specta::specta]
+    pub fn get_sales_progress() -> Result<SalesProgress, String> {
+        sales_targets::progress()
     }
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_store::Builder::new().build())
-        .plugin(tauri_plugin_dialog::init())
-        .invoke_handler(tauri::generate_handler![
+/// 登録するコマンドの一覧（プラットフォームごとのプリンターコマンドを末尾に加える）
+macro_rules! pos_commands {
+    ($($printer:tt)*) => {
+        tauri_specta::collect_commands![
             common::get_platform,
             // 端末認証コマンド
             terminal_commands::get_terminal_status,
             terminal_commands::initialize_terminal,
             terminal_commands::generate_registration_qr,
             terminal_commands::create_auth_signature,
+            terminal_commands::get_seat_binding,
+            terminal_commands::verify_terminal,
+            terminal_commands::check_terminal_verification,
             terminal_commands::clear_terminal_keychain,
-            // プリンターコマンド（デスクトップ）
-            #[cfg(not(target_os = "android"))]
-            desktop_printer::get_usb_devices,
-            #[cfg(not(target_os = "android"))]
-            desktop_printer::text_print,
-            #[cfg(not(target_os = "android"))]
-            desktop_printer::welcome_print,
-            #[cfg(not(target_os = "android"))]
-            desktop_printer::print_receipt,
+            // アップロードキューコマンド
+            upload_commands::get_pending_uploads,
+            upload_commands::complete_upload,
+            upload_commands::fail_upload,
+            upload_commands::email_receipt,
+            // リモートコマンド・サーバー鍵コマンド
+            remote_command_commands::execute_remote_command,
+            remote_command_commands::get_remote_command_history,
+            remote_command_commands::get_terminal_lock,
+            remote_command_commands::get_server_key,
+            remote_command_commands::pin_server_key,
+            // ハートビートコマンド
+            heartbeat_commands::get_heartbeat_status,
+            heartbeat_commands::create_heartbeat,
+            heartbeat_commands::ack_heartbeat,
+            heartbeat_commands::fail_heartbeat,
+            heartbeat_commands::report_battery,
+            // イベントコンテキストコマンド
+            event_commands::get_active_event,
+            event_commands::select_event,
+            event_commands::list_local_events,
+            event_commands::issue_receipt_number,
+            event_commands::archive_event,
+            // 端末設定コマンド
+            config_commands::get_terminal_config,
+            config_commands::update_terminal_config,
+            config_commands::apply_signed_config,
+            config_commands::apply_price_update,
+            config_commands::get_price_book,
+            config_commands::get_print_strings,
+            config_commands::update_print_strings,
+            // レシートQRコマンド
+            receipt_qr_commands::decode_receipt_qr,
+            receipt_qr_commands::decode_closing_report_qr,
+        receipt_qr_commands::decode_closing_report_qr,
+            // プリンタープロファイルコマンド
+            printer_profile_commands::get_printer_profiles,
+            printer_profile_commands::save_printer_profile,
+            printer_profile_commands::delete_printer_profile,
+            printer_profile_commands::get_printer_features,
+            paper_usage_commands::get_paper_usage,
+            // スタッフ認証コマンド
+            staff_commands::register_supervisor,
+            staff_commands::verify_supervisor,
+            staff_commands::set_duress_pin,
+            staff_commands::begin_supervisor_session,
+            staff_commands::end_supervisor_session,
+            staff_commands::get_supervisor_session,
+            // ジャーナルコマンド
+            journal_commands::get_journal,
+            journal_commands::get_reprint_count,
+            journal_commands::refund_sale,
+            journal_commands::void_sale,
+            journal_commands::get_journal_totals,
+            // セルフレジ（キオスク）コマンド
+            kiosk_commands::get_kiosk_state,
+            kiosk_commands::start_kiosk,
+            kiosk_commands::stop_kiosk,
+            kiosk_commands::kiosk_scan,
+            kiosk_commands::kiosk_remove_item,
+            kiosk_commands::kiosk_clear_basket,
+            kiosk_commands::kiosk_checkout,
+            kiosk_commands::kiosk_cancel_payment,
+            kiosk_commands::kiosk_complete_payment,
+            // 印刷スプーラーコマンド
+            spooler_commands::spool_receipt,
+            spooler_commands::spool_parked_slip,
+            spooler_commands::spool_closing_report,
+            spooler_commands::get_print_queue,
+            spooler_commands::cancel_print_job,
+            // 保留取引コマンド
+            parked_sale_commands::park_sale,
+            parked_sale_commands::list_parked_sales,
+            parked_sale_commands::resume_parked_sale,
+            // 呼び出し表示コマンド
+            now_serving_commands::get_now_serving,
+            now_serving_commands::call_now_serving,
+            now_serving_commands::clear_now_serving,
+            // 見積コマンド
+            quote_commands::get_quote,
+            quote_commands::convert_quote,
+            // 予約コマンド
+            reservation_commands::register_reservation,
+            reservation_commands::find_reservation,
+            reservation_commands::list_reservations,
+            reservation_commands::redeem_reservation,
+            reservation_commands::cancel_reservation,
+            reservation_commands::export_reservations,
+            reservation_commands::merge_reservations,
+            deposit_commands::list_open_deposits,
+            deposit_commands::prepare_deposit_balance,
+            deposit_commands::get_deposit_balance_sale,
+            // 商品券コマンド
+            voucher_commands::get_voucher_catalog,
+            voucher_commands::validate_voucher_tender,
+            tender_commands::validate_payments,
+            // ギフト券コマンド
+            gift_voucher_commands::validate_gift_voucher,
+            gift_voucher_commands::redeem_gift_voucher,
+            gift_voucher_commands::list_gift_vouchers,
+            gift_voucher_commands::export_gift_vouchers,
+            gift_voucher_commands::merge_gift_vouchers,
+            // 会員コマンド
+            member_commands::find_member,
+            member_commands::list_members,
+            member_commands::import_members,
+            member_commands::get_member_purchases,
+            member_commands::get_points_balance,
+            // 価格変更コマンド
+            price_override_commands::get_price_override_reasons,
+            price_override_commands::approve_price_override,
+            // 年齢確認コマンド
+            age_check_commands::confirm_age_verification,
+            // キャンペーンコマンド
+            promotion_commands::get_promotions,
+            promotion_commands::quote_promotions,
+            // 端数調整コマンド
+            rounding_commands::quote_rounding,
+            // 売上確定コマンド
+            sale_commands::prepare_sale,
+            // 営業セッションコマンド
+            session_commands::get_session,
+            session_commands::open_session,
+            session_commands::check_venue_network,
+            session_commands::report_network_ssid,
+            session_commands::switch_staff,
+            session_commands::close_session,
+            session_commands::set_closing_reminder,
+            session_commands::get_closing_reminder,
+            session_commands::cancel_closing_reminder,
+            // ドロワーコマンド
+            drawer_commands::get_drawer_contents,
+            drawer_commands::set_drawer_contents,
+            drawer_commands::suggest_change,
+            // 計測コマンド
+            metrics_commands::get_metrics,
+            // 接続先環境コマンド
+            environment_commands::get_environment,
+            environment_commands::switch_environment,
+            // 商品データコマンド（取り込み・仮登録・ISDN照会）
+            catalog_commands::import_catalog,
+            catalog_commands::create_product_from_scan,
+            catalog_commands::lookup_isdn,
+            catalog_commands::list_provisional_products,
+            catalog_commands::review_provisional_product,
+            // 売上目標コマンド
+            sales_target_commands::get_sales_target,
+            sales_target_commands::set_sales_target,
+            sales_target_commands::get_sales_progress,
+            $($printer)*
+        ]
+    };
+}
+
+/// コマンドとその型定義をまとめたビルダー
+///
+/// フロントエンドの型付きの呼び出し関数（`src/lib/commands.gen.ts`）もここから生成する（ipc_bindings.rs）
+/// エラーは reject された Promise で返す（`TerminalAuthError` などの型付きのエラーも型定義に含める）
+fn specta_builder() -> tauri_specta::Builder<tauri::Wry> {
+    #[cfg(not(target_os = "android"))]
+    let commands = pos_commands![
+        // プリンターコマンド（デスクトップ）
+        desktop_printer::get_usb_devices,
+        desktop_printer::text_print,
+        desktop_printer::text_print_to,
+        desktop_printer::print_barcode,
+        desktop_printer::print_test_page,
+        desktop_printer::print_test_page_to,
+        desktop_printer::discover_network_printers,
+        desktop_printer::network_print_test_page,
+        desktop_printer::network_print_text,
+        desktop_printer::list_serial_ports,
+        desktop_printer::list_bluetooth_printers,
+        desktop_printer::bluetooth_printer_status,
+        desktop_printer::get_paper_status,
+        desktop_printer::get_printer_status,
+        desktop_printer::print_product_label,
+        desktop_printer::welcome_print,
+        desktop_printer::print_receipt,
+        desktop_printer::bluetooth_print_receipt,
+        desktop_printer::print_receipt_to,
+        desktop_printer::estimate_receipt_length,
+        desktop_printer::reprint_receipt,
+        desktop_printer::reprint_receipt_to,
+        desktop_printer::print_refund_receipt,
+        desktop_printer::print_receipt_from_stub,
+        desktop_printer::print_gift_receipt,
+        desktop_printer::print_closing_report,
+        desktop_printer::issue_gift_voucher,
+        desktop_printer::print_gift_voucher,
+        desktop_printer::print_parked_slip,
+        desktop_printer::print_recall_slip,
+        desktop_printer::print_terminal_verification,
+        desktop_printer::print_quote,
+        desktop_printer::print_reservation_slip,
+        desktop_printer::print_pickup_confirmation,
+        desktop_printer::print_sales_progress,
+    ];
+    #[cfg(target_os = "android")]
+    let commands = pos_commands![
+        // プリンターコマンド（Android）
+        android_printer::get_bluetooth_devices,
+        android_printer::connect_bluetooth_printer,
+        android_printer::bluetooth_print,
+        android_printer::bluetooth_welcome_print,
+    ];
+    tauri_specta::Builder::<tauri::Wry>::new()
+        .commands(commands)
+        .error_handling(tauri_specta::ErrorHandlingMode::Throw)
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    tauri::Builder::default()
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(tauri_plugin_dialog::init())
+        .setup(|app| {
             #[cfg(not(target_os = "android"))]
-            desktop_printer::print_closing_report,
-            // プリンターコマンド（Android）
-            #[cfg(target_os = "android")]
-            android_printer::get_bluetooth_devices,
-            #[cfg(target_os = "android")]
-            android_printer::connect_bluetooth_printer,
-            #[cfg(target_os = "android")]
-            android_printer::bluetooth_print,
-            #[cfg(target_os = "android")]
-            android_printer::bluetooth_welcome_print,
-        ])
+            desktop_printer::preview::install(app.handle().clone());
+            rate_limit::install(app.handle().clone());
+            // 印字文言を読み込む（壊れている場合は既定の文言で起動する）
+            let _ = print_strings::reload();
+            closing_reminder::start(app.handle().clone());
+            spooler::start(app.handle().clone());
+            paper_monitor::start(app.handle().clone());
+            printer_keepalive::start(app.handle().clone());
+            heartbeat::start(app.handle().clone());
+            // 保存期間を過ぎたカード情報を消去
+            if let Ok(config) = config::load() {
+                let _ = journal::purge_card_details(config.card_retention_days);
+            }
+            Ok(())
+        })
+        .invoke_handler({
+            let handler = specta_builder().invoke_handler();
+            // 診断用に、引数を伏せてコマンドを記録してから処理する
+            move |invoke: tauri::ipc::Invoke<tauri::Wry>| {
+                redaction::log_invoke(&invoke.message);
+                handler(invoke)
+            }
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,194 @@
+//! ロゴ画像モジュール
+//!
+//! レシートの先頭に印刷するロゴ（PNG）を読み込み、用紙幅に収まるよう縮小して白黒のビットマップに変換する
+//! 変換したビットマップは GS v 0 で印刷する（ラスターモードではレシートのビットマップに描き込む）
+
+use crate::raster::Bitmap;
+use crate::receipt::{ImageMode, ImageSource, ReceiptLogo};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+
+/// しきい値で2値化する場合に黒とみなす明るさ（0〜255、未満が黒）
+const THRESHOLD: u8 = 128;
+
+/// 明るさの画像（1ドット1バイト、0が黒・255が白）
+struct Grayscale {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+/// ロゴを読み込んで白黒のビットマップにする
+///
+/// 幅は max_width と用紙幅（paper_dots）の小さい方に収める（小さい画像は拡大しない）
+pub fn load(logo: &ReceiptLogo, paper_dots: usize) -> Result<Bitmap, String> {
+    let bytes = match logo.source {
+        ImageSource::Base64 { ref data } => {
+            let data = data.split_once("base64,").map_or(data.as_str(), |(_, d)| d);
+            BASE64
+                .decode(data.trim())
+                .map_err(|e| format!("Invalid logo data: {}", e))?
+        }
+        ImageSource::File { ref path } => {
+            std::fs::read(path).map_err(|e| format!("Failed to read logo {}: {}", path, e))?
+        }
+    };
+    let max_width = logo.max_width.map_or(paper_dots, |w| (w as usize).min(paper_dots));
+    let image = fit_width(decode_png(&bytes)?, max_width);
+    Ok(match logo.mode {
+        ImageMode::Dither => dither(&image),
+        ImageMode::Threshold => threshold(&image),
+    })
+}
+
+/// PNG を明るさの画像にする（透明な部分は白）
+fn decode_png(bytes: &[u8]) -> Result<Grayscale, String> {
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|e| format!("Invalid PNG: {}", e))?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buf).map_err(|e| format!("Invalid PNG: {}", e))?;
+    let (width, height) = (frame.width as usize, frame.height as usize);
+    if width == 0 || height == 0 {
+        return Err("Empty PNG".to_string());
+    }
+
+    let channels = frame.color_type.samples();
+    let pixels = buf[..frame.buffer_size()]
+        .chunks(frame.line_size)
+        .flat_map(|line| line.chunks(channels).take(width))
+        .map(|p| {
+            let (luma, alpha) = match *p {
+                [l] => (l as u32, 255),
+                [l, a] => (l as u32, a as u32),
+                [r, g, b] => ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000, 255),
+                [r, g, b, a] => ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000, a as u32),
+                _ => (255, 255),
+            };
+            // 白地に重ねる
+            ((luma * alpha + 255 * (255 - alpha)) / 255) as u8
+        })
+        .collect();
+    Ok(Grayscale { width, height, pixels })
+}
+
+/// 幅が max_width を超える場合は縦横比を保って縮小（面積平均）
+fn fit_width(image: Grayscale, max_width: usize) -> Grayscale {
+    if image.width <= max_width || max_width == 0 {
+        return image;
+    }
+    let width = max_width;
+    let height = (image.height * width / image.width).max(1);
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let (y0, y1) = span(y, height, image.height);
+        for x in 0..width {
+            let (x0, x1) = span(x, width, image.width);
+            let mut sum = 0u32;
+            for sy in y0..y1 {
+                for sx in x0..x1 {
+                    sum += image.pixels[sy * image.width + sx] as u32;
+                }
+            }
+            pixels.push((sum / ((y1 - y0) * (x1 - x0)) as u32) as u8);
+        }
+    }
+    Grayscale { width, height, pixels }
+}
+
+/// 縮小後の1ドットに対応する元画像の範囲
+fn span(i: usize, scaled: usize, original: usize) -> (usize, usize) {
+    let start = i * original / scaled;
+    let end = ((i + 1) * original / scaled).max(start + 1);
+    (start, end)
+}
+
+/// しきい値で2値化
+fn threshold(image: &Grayscale) -> Bitmap {
+    Bitmap {
+        width: image.width,
+        height: image.height,
+        pixels: image.pixels.iter().map(|&p| (p < THRESHOLD) as u8).collect(),
+    }
+}
+
+/// 誤差拡散（Floyd-Steinberg）で2値化
+fn dither(image: &Grayscale) -> Bitmap {
+    let (width, height) = (image.width, image.height);
+    let mut levels: Vec<i32> = image.pixels.iter().map(|&p| p as i32).collect();
+    let mut pixels = vec![0u8; width * height];
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * width + x;
+            let black = levels[i] < THRESHOLD as i32;
+            pixels[i] = black as u8;
+            let error = levels[i] - if black { 0 } else { 255 };
+            let mut spread = |dx: isize, dy: usize, weight: i32| {
+                let nx = x as isize + dx;
+                if nx >= 0 && (nx as usize) < width && y + dy < height {
+                    levels[(y + dy) * width + nx as usize] += error * weight / 16;
+                }
+            };
+            spread(1, 0, 7);
+            spread(-1, 1, 3);
+            spread(0, 1, 5);
+            spread(1, 1, 1);
+        }
+    }
+    Bitmap { width, height, pixels }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// テスト用の PNG（グレースケール＋透明度）
+    fn png(width: u32, height: u32, pixels: &[[u8; 2]]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, width, height);
+        encoder.set_color(png::ColorType::GrayscaleAlpha);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(pixels.concat().as_slice()).unwrap();
+        writer.finish().unwrap();
+        bytes
+    }
+
+    fn logo(bytes: &[u8], mode: ImageMode) -> ReceiptLogo {
+        ReceiptLogo {
+            source: ImageSource::Base64 {
+                data: format!("data:image/png;base64,{}", BASE64.encode(bytes)),
+            },
+            max_width: None,
+            mode,
+        }
+    }
+
+    #[test]
+    fn test_load_scales_to_paper_width_and_treats_transparency_as_white() {
+        // 左半分が黒、右半分が透明な 8x2 の画像
+        let pixels: Vec<[u8; 2]> = (0..16).map(|i| if i % 8 < 4 { [0, 255] } else { [0, 0] }).collect();
+        let bytes = png(8, 2, &pixels);
+
+        let bitmap = load(&logo(&bytes, ImageMode::Threshold), 384).unwrap();
+        assert_eq!((bitmap.width, bitmap.height), (8, 2));
+        assert_eq!(&bitmap.pixels[..8], &[1, 1, 1, 1, 0, 0, 0, 0]);
+
+        let bitmap = load(&logo(&bytes, ImageMode::Threshold), 4).unwrap();
+        assert_eq!((bitmap.width, bitmap.height), (4, 1));
+        assert_eq!(bitmap.pixels, vec![1, 1, 0, 0]);
+        assert!(load(&logo(b"not a png", ImageMode::Dither), 384).is_err());
+    }
+
+    #[test]
+    fn test_dither_keeps_average_darkness() {
+        // 中間の灰色はおよそ半分のドットが黒になる
+        let image = Grayscale {
+            width: 16,
+            height: 16,
+            pixels: vec![128; 256],
+        };
+        let black = dither(&image).pixels.iter().filter(|&&p| p != 0).count();
+        assert!((112..=144).contains(&black), "{}", black);
+        assert_eq!(threshold(&image).pixels.iter().filter(|&&p| p != 0).count(), 0);
+    }
+}
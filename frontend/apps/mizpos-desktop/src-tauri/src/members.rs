@@ -0,0 +1,174 @@
+//! 会員モジュール
+//!
+//! サーバーから取り込んだ会員を会員証のQR・NFCで検索し、売上に会員番号を付ける
+//! 会員の売上ではレシートの宛名に会員名を印字し、購入履歴を端末内に貯めて後でまとめて送る
+//! 会員と購入履歴はイベントをまたいで使うため、端末全体で保存する
+
+use crate::event_context;
+use crate::receipt::ReceiptData;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 会員の保存ファイル名
+const MEMBERS_FILE: &str = "members.json";
+/// 購入履歴の保存ファイル名
+const PURCHASES_FILE: &str = "member_purchases.jsonl";
+/// 会員証QRコードの接頭辞
+const MEMBER_QR_PREFIX: &str = "MZM:";
+
+/// 会員
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Member {
+    pub member_id: String,
+    /// 宛名に印字する名前
+    pub name: String,
+    /// 会員証のNFCタグのUID（16進数）
+    #[serde(default)]
+    pub card_uid: Option<String>,
+    /// サーバーでのポイント残高（更新日時の時点）
+    #[serde(default)]
+    pub points_balance: u64,
+    /// 最終更新日時（UNIX秒、取り込み時のマージに使用）
+    pub updated_at: u64,
+}
+
+/// 会員の購入履歴
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct MemberPurchase {
+    pub member_id: String,
+    pub receipt_number: String,
+    pub event_id: String,
+    pub total: u32,
+    /// 点数
+    pub quantity: u32,
+    /// 記録日時（UNIX秒）
+    pub purchased_at: u64,
+}
+
+fn load_members() -> Result<Vec<Member>, String> {
+    Ok(storage::load_json(MEMBERS_FILE)?.unwrap_or_default())
+}
+
+/// NFCのUIDを比較用にそろえる（区切り文字を除いて大文字にする）
+fn normalize_uid(uid: &str) -> String {
+    uid.chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// 会員証のQRコード・NFCのUID・会員番号から会員を検索
+pub fn find(scanned: &str) -> Result<Option<Member>, String> {
+    Ok(find_in(&load_members()?, scanned))
+}
+
+fn find_in(members: &[Member], scanned: &str) -> Option<Member> {
+    let scanned = scanned.trim();
+    if let Some(member_id) = scanned.strip_prefix(MEMBER_QR_PREFIX) {
+        return members.iter().find(|m| m.member_id == member_id).cloned();
+    }
+    if let Some(member) = members.iter().find(|m| m.member_id == scanned) {
+        return Some(member.clone());
+    }
+    let uid = normalize_uid(scanned);
+    if uid.is_empty() {
+        return None;
+    }
+    members
+        .iter()
+        .find(|m| m.card_uid.as_deref().is_some_and(|c| normalize_uid(c) == uid))
+        .cloned()
+}
+
+/// 会員一覧
+pub fn list() -> Result<Vec<Member>, String> {
+    load_members()
+}
+
+/// サーバーから受け取った会員を取り込む（更新日時が新しい方を採用し、変更件数を返す）
+pub fn import(remote: Vec<Member>) -> Result<usize, String> {
+    let mut members = load_members()?;
+    let mut changed = 0;
+    for incoming in remote {
+        match members.iter_mut().find(|m| m.member_id == incoming.member_id) {
+            Some(local) if incoming.updated_at > local.updated_at => {
+                *local = incoming;
+                changed += 1;
+            }
+            Some(_) => {}
+            None => {
+                members.push(incoming);
+                changed += 1;
+            }
+        }
+    }
+    if changed > 0 {
+        storage::save_json(MEMBERS_FILE, &members)?;
+    }
+    Ok(changed)
+}
+
+/// 会員の売上なら宛名を会員名にする（宛名が指定済みの場合はそのまま）
+pub fn personalize(receipt: &mut ReceiptData) -> Result<(), String> {
+    if receipt.customer_name.is_some() {
+        return Ok(());
+    }
+    let Some(member_id) = receipt.member_id.as_deref() else {
+        return Ok(());
+    };
+    if let Some(member) = find(member_id)? {
+        receipt.customer_name = Some(member.name);
+    }
+    Ok(())
+}
+
+/// 会員の売上を購入履歴に追記
+pub fn record_purchase(receipt: &ReceiptData) -> Result<(), String> {
+    let Some(ref member_id) = receipt.member_id else {
+        return Ok(());
+    };
+    let purchase = MemberPurchase {
+        member_id: member_id.clone(),
+        receipt_number: receipt.receipt_number.clone(),
+        event_id: event_context::active_event_id()?,
+        total: receipt.total,
+        quantity: receipt.items.iter().map(|i| i.quantity).sum(),
+        purchased_at: storage::now_unix(),
+    };
+    storage::append_jsonl(PURCHASES_FILE, &purchase)
+}
+
+/// 指定日時以降の購入履歴を取得（会員を指定した場合はその会員のみ）
+pub fn purchases(member_id: Option<&str>, since: u64) -> Vec<MemberPurchase> {
+    storage::load_jsonl::<MemberPurchase>(PURCHASES_FILE)
+        .into_iter()
+        .filter(|p| p.purchased_at >= since)
+        .filter(|p| member_id.is_none_or(|id| p.member_id == id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(member_id: &str, card_uid: Option<&str>) -> Member {
+        Member {
+            member_id: member_id.to_string(),
+            name: "みず".to_string(),
+            card_uid: card_uid.map(|u| u.to_string()),
+            points_balance: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_find_by_qr_member_id_or_nfc_uid() {
+        let members = vec![member("M0001", Some("04:A2:2B:1C")), member("M0002", None)];
+        assert_eq!(find_in(&members, "MZM:M0002").unwrap().member_id, "M0002");
+        assert_eq!(find_in(&members, "M0001").unwrap().member_id, "M0001");
+        assert_eq!(find_in(&members, "04a22b1c").unwrap().member_id, "M0001");
+        assert!(find_in(&members, "MZM:M9999").is_none());
+        assert!(find_in(&members, "").is_none());
+    }
+}
@@ -0,0 +1,194 @@
+//! 稼働状況の計測モジュール
+//!
+//! 信頼性の改善に使うため、印刷の成否（エラーの種類別）・同期の遅延・送信待ちの件数を匿名のカウンターとして記録する
+//! 売上・商品・スタッフ・お客様の情報やエラーの本文は残さない
+//! 記録は端末設定で有効にした場合だけ行い（オプトイン）、送信も有効にした場合は閉局時にアップロードキューで送る
+
+use crate::config;
+use crate::storage;
+use crate::upload_queue::{self, PendingUpload};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// 計測値の保存ファイル名
+const METRICS_FILE: &str = "metrics.json";
+
+/// 計測の設定（既定はすべて無効）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// 端末に記録する
+    pub enabled: bool,
+    /// 閉局時に本部へ送信する
+    pub upload: bool,
+}
+
+/// 記録した計測値
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct Metrics {
+    /// 集計を始めた日時（UNIX秒、送信するたびに区切る）
+    pub since: u64,
+    /// 印刷に成功した回数
+    pub prints: u64,
+    /// 印刷に失敗した回数（エラーの種類別）
+    pub print_failures: BTreeMap<String, u64>,
+    /// 送信に成功した件数
+    pub syncs: u64,
+    /// 送信に失敗した回数
+    pub sync_failures: u64,
+    /// キュー投入から送信完了までの秒数の合計・最大
+    pub sync_latency_total_secs: u64,
+    pub sync_latency_max_secs: u64,
+    /// 最後に確認した送信待ちの件数・その最大
+    pub queue_depth: usize,
+    pub queue_depth_max: usize,
+}
+
+/// 読み込みから保存までを直列にする（印刷はスプーラーのスレッドからも記録される）
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn load() -> Result<Metrics, String> {
+    Ok(storage::load_json(METRICS_FILE)?.unwrap_or_default())
+}
+
+/// 印刷エラーの種類（エラーの本文には機器名などが含まれるため送らない）
+fn print_error_code(error: &str) -> &'static str {
+    let error = error.to_lowercase();
+    if error.contains("not found") || error.contains("no device") {
+        "printer_not_found"
+    } else if error.contains("timed out") || error.contains("timeout") {
+        "timeout"
+    } else if error.contains("paper") {
+        "paper"
+    } else if error.contains("network") || error.contains("connection") {
+        "network"
+    } else if error.contains("font") || error.contains("raster") {
+        "render"
+    } else {
+        "other"
+    }
+}
+
+fn record_print_to(metrics: &mut Metrics, result: &Result<(), String>) {
+    match result {
+        Ok(()) => metrics.prints += 1,
+        Err(e) => *metrics.print_failures.entry(print_error_code(e).to_string()).or_default() += 1,
+    }
+}
+
+fn record_sync_to(metrics: &mut Metrics, latency_secs: Option<u64>) {
+    match latency_secs {
+        Some(latency) => {
+            metrics.syncs += 1;
+            metrics.sync_latency_total_secs += latency;
+            metrics.sync_latency_max_secs = metrics.sync_latency_max_secs.max(latency);
+        }
+        None => metrics.sync_failures += 1,
+    }
+}
+
+fn record_queue_depth_to(metrics: &mut Metrics, depth: usize) {
+    metrics.queue_depth = depth;
+    metrics.queue_depth_max = metrics.queue_depth_max.max(depth);
+}
+
+/// 有効な場合だけ計測値を更新する
+///
+/// 計測の失敗で売上・印刷を止めないよう、エラーは無視する
+fn record(update: impl FnOnce(&mut Metrics)) {
+    let Ok(_guard) = LOCK.lock() else {
+        return;
+    };
+    if !config::load().is_ok_and(|c| c.metrics.enabled) {
+        return;
+    }
+    let Ok(mut metrics) = load() else {
+        return;
+    };
+    if metrics.since == 0 {
+        metrics.since = storage::now_unix();
+    }
+    update(&mut metrics);
+    let _ = storage::save_json(METRICS_FILE, &metrics);
+}
+
+/// 印刷の結果を記録
+pub fn record_print(result: &Result<(), String>) {
+    record(|m| record_print_to(m, result));
+}
+
+/// 送信の結果を記録（成功した場合はキュー投入からの秒数）
+pub fn record_sync(latency_secs: Option<u64>) {
+    record(|m| record_sync_to(m, latency_secs));
+}
+
+/// 送信待ちの件数を記録
+pub fn record_queue_depth(depth: usize) {
+    record(|m| record_queue_depth_to(m, depth));
+}
+
+/// 記録した計測値を取得
+pub fn current() -> Result<Metrics, String> {
+    load()
+}
+
+/// 送信が有効な場合、計測値をアップロードキューに追加して集計を区切る
+///
+/// 記録がない場合・送信が無効な場合は None
+pub fn upload() -> Result<Option<PendingUpload>, String> {
+    let config = config::load()?.metrics;
+    let metrics = load()?;
+    if !(config.enabled && config.upload) || metrics.since == 0 {
+        return Ok(None);
+    }
+    // キューへの投入でも送信待ちの件数を記録するため、ロックは区切るときだけ取る
+    let upload = upload_queue::enqueue(
+        upload_queue::KIND_METRICS,
+        &format!("{}-{}", metrics.since, storage::now_unix()),
+        &metrics,
+    )?;
+    let _guard = LOCK.lock().map_err(|e| e.to_string())?;
+    let queue_depth = load()?.queue_depth;
+    storage::save_json(
+        METRICS_FILE,
+        &Metrics {
+            since: storage::now_unix(),
+            queue_depth,
+            queue_depth_max: queue_depth,
+            ..Metrics::default()
+        },
+    )?;
+    Ok(Some(upload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_keep_only_error_codes_and_latency() {
+        let mut metrics = Metrics::default();
+        record_print_to(&mut metrics, &Ok(()));
+        record_print_to(&mut metrics, &Ok(()));
+        record_print_to(&mut metrics, &Err("Printer not found: TM-T20 (0x04b8)".to_string()));
+        record_print_to(&mut metrics, &Err("Operation timed out".to_string()));
+        record_print_to(&mut metrics, &Err("Printer not found".to_string()));
+        record_sync_to(&mut metrics, Some(3));
+        record_sync_to(&mut metrics, Some(40));
+        record_sync_to(&mut metrics, None);
+        record_queue_depth_to(&mut metrics, 5);
+        record_queue_depth_to(&mut metrics, 1);
+
+        assert_eq!(metrics.prints, 2);
+        assert_eq!(
+            metrics.print_failures,
+            BTreeMap::from([("printer_not_found".to_string(), 2), ("timeout".to_string(), 1)])
+        );
+        assert_eq!((metrics.syncs, metrics.sync_failures), (2, 1));
+        assert_eq!((metrics.sync_latency_total_secs, metrics.sync_latency_max_secs), (43, 40));
+        assert_eq!((metrics.queue_depth, metrics.queue_depth_max), (1, 5));
+    }
+}
@@ -0,0 +1,150 @@
+//! ネットワークプリンターモジュール
+//!
+//! LAN接続のレシートプリンター（Epson の ePOS 対応機・Star の LAN モデルなど）へ、ESC/POS をTCP（RAW 9100番）で送る
+//! Star のプリンターは本体を ESC/POS エミュレーションに設定して使う
+//! USBプリンターを繋げない iPad でも印刷できるよう、設定したプリンターを仮想のベンダーIDのデバイスとして一覧に出し、
+//! 既存の印刷コマンド（ベンダーID・デバイスIDの指定）からそのまま使えるようにする
+//! 設定する前のプリンターを探せるよう、端末と同じサブネット（/24）で 9100番に接続できる機器を探索する
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::net::{Ipv4Addr, SocketAddr, TcpStream, UdpSocket};
+use std::time::Duration;
+
+/// ネットワークプリンターに割り当てる仮想のベンダーID（USBでは予約済みの値）
+pub const NETWORK_VENDOR_ID: u16 = 0xffff;
+
+/// 接続のタイムアウト
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 探索で1台ごとに接続を待つ時間（会場のLANで1サブネットを数秒で回れるよう短くする）
+const PROBE_TIMEOUT: Duration = Duration::from_millis(300);
+/// 探索で同時に接続を試す数
+const PROBE_WORKERS: usize = 32;
+
+/// ネットワークプリンターの設定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct NetworkPrinter {
+    /// 一覧に出す名前
+    pub name: String,
+    /// IPアドレスまたはホスト名
+    pub host: String,
+    /// ポート番号
+    #[serde(default = "default_port")]
+    pub port: u16,
+}
+
+fn default_port() -> u16 {
+    9100
+}
+
+/// 一覧に出すデバイスIDとプリンターの組（デバイスIDは設定の並び順）
+pub fn devices(printers: &[NetworkPrinter]) -> impl Iterator<Item = (u16, &NetworkPrinter)> {
+    printers
+        .iter()
+        .take(usize::from(u16::MAX))
+        .enumerate()
+        .map(|(i, p)| (i as u16, p))
+}
+
+/// ベンダーID・デバイスIDに対応するネットワークプリンターを探す
+pub fn find(printers: &[NetworkPrinter], vendor_id: u16, device_id: u16) -> Option<&NetworkPrinter> {
+    if vendor_id != NETWORK_VENDOR_ID {
+        return None;
+    }
+    printers.get(usize::from(device_id))
+}
+
+/// 端末のLAN側のIPv4アドレス
+///
+/// UDPソケットの接続先を決めて送信元のアドレスを得る（実際には何も送らない）
+fn local_ipv4() -> Option<Ipv4Addr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(192, 0, 2, 1), 9)).ok()?;
+    match socket.local_addr().ok()?.ip() {
+        std::net::IpAddr::V4(ip) if !ip.is_loopback() && !ip.is_unspecified() => Some(ip),
+        _ => None,
+    }
+}
+
+/// 探索する同じサブネット（/24）のアドレス（端末自身を除く）
+fn subnet_hosts(local: Ipv4Addr) -> Vec<Ipv4Addr> {
+    let [a, b, c, _] = local.octets();
+    (1..=254)
+        .map(|d| Ipv4Addr::new(a, b, c, d))
+        .filter(|ip| *ip != local)
+        .collect()
+}
+
+/// 同じサブネットで指定したポートに接続できる機器を探す（アドレス順）
+///
+/// RAW印刷のポートを開けている機器をプリンターの候補として返す（名前はアドレス）
+pub fn discover(port: u16) -> Result<Vec<NetworkPrinter>, String> {
+    let local = local_ipv4().ok_or_else(|| "Not connected to a local network".to_string())?;
+    let hosts = subnet_hosts(local);
+    let chunk_size = hosts.len().div_ceil(PROBE_WORKERS);
+
+    let mut found: Vec<Ipv4Addr> = std::thread::scope(|scope| {
+        let workers: Vec<_> = hosts
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .copied()
+                        .filter(|ip| {
+                            TcpStream::connect_timeout(&SocketAddr::from((*ip, port)), PROBE_TIMEOUT)
+                                .is_ok()
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().unwrap_or_default())
+            .collect()
+    });
+    found.sort();
+
+    Ok(found
+        .into_iter()
+        .map(|ip| NetworkPrinter {
+            name: ip.to_string(),
+            host: ip.to_string(),
+            port,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_find_maps_virtual_ids_to_configured_printers() {
+        let printers: Vec<NetworkPrinter> = serde_json::from_value(json!([
+            { "name": "レジ1", "host": "192.168.0.10" },
+            { "name": "レジ2", "host": "192.168.0.11", "port": 9101 },
+        ]))
+        .unwrap();
+        assert_eq!(printers[0].port, 9100);
+
+        let ids: Vec<u16> = devices(&printers).map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![0, 1]);
+        assert_eq!(find(&printers, NETWORK_VENDOR_ID, 1).map(|p| p.port), Some(9101));
+        assert!(find(&printers, NETWORK_VENDOR_ID, 2).is_none());
+        // USBプリンターのIDには対応しない
+        assert!(find(&printers, 0x04b8, 0).is_none());
+    }
+
+    #[test]
+    fn test_subnet_hosts_skip_self_and_broadcast() {
+        let hosts = subnet_hosts(Ipv4Addr::new(192, 168, 0, 20));
+        assert_eq!(hosts.len(), 253);
+        assert_eq!(hosts.first(), Some(&Ipv4Addr::new(192, 168, 0, 1)));
+        assert_eq!(hosts.last(), Some(&Ipv4Addr::new(192, 168, 0, 254)));
+        assert!(!hosts.contains(&Ipv4Addr::new(192, 168, 0, 20)));
+    }
+}
@@ -0,0 +1,117 @@
+//! 呼び出し表示モジュール
+//!
+//! お預かり票などの呼び出し番号を客面表示（customer-display ウィンドウ）に出す
+//! 呼び出し中の番号と直近の履歴をイベント単位で保存し、客面表示を開き直しても同じ表示に戻す
+//! 呼び出し中の番号をもう一度呼ぶと呼び直しとして回数を数え、呼び出し票（再呼び出し票）に印字する
+
+use crate::event_context;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 呼び出し表示の保存ファイル名
+const NOW_SERVING_FILE: &str = "now_serving.json";
+/// 呼び出し表示が変わったときに通知するイベント名
+pub const EVENT_NOW_SERVING: &str = "now-serving";
+/// 客面表示に出す直近の呼び出し番号の件数
+const RECENT_LIMIT: usize = 5;
+
+/// 呼び出し表示の状態
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct NowServing {
+    /// 呼び出し中の番号
+    pub current: Option<String>,
+    /// 呼び出し中の番号を呼んだ回数（呼び直しで増える）
+    pub call_count: u32,
+    /// 呼び出した日時（UNIX秒）
+    pub called_at: Option<u64>,
+    /// 呼び出した番号を操作したスタッフ番号
+    pub staff_id: Option<String>,
+    /// 以前に呼び出した番号（新しい順）
+    pub recent: Vec<String>,
+}
+
+/// 現在の状態を取得
+pub fn current() -> Result<NowServing, String> {
+    Ok(storage::load_json(&event_context::scoped(NOW_SERVING_FILE)?)?.unwrap_or_default())
+}
+
+fn save(state: &NowServing) -> Result<(), String> {
+    storage::save_json(&event_context::scoped(NOW_SERVING_FILE)?, state)
+}
+
+/// 番号を呼び出す
+pub fn call(code: &str, staff_id: &str) -> Result<NowServing, String> {
+    let code = code.trim();
+    if code.is_empty() {
+        return Err("Call number is empty".to_string());
+    }
+    let state = advance(current()?, code, staff_id, storage::now_unix());
+    save(&state)?;
+    Ok(state)
+}
+
+/// 呼び出し表示を消す（履歴は残す）
+pub fn clear() -> Result<NowServing, String> {
+    let mut state = current()?;
+    if let Some(code) = state.current.take() {
+        push_recent(&mut state.recent, code);
+    }
+    state.call_count = 0;
+    state.called_at = None;
+    state.staff_id = None;
+    save(&state)?;
+    Ok(state)
+}
+
+/// 呼び出し中の番号を切り替える（同じ番号なら呼び直し）
+fn advance(mut state: NowServing, code: &str, staff_id: &str, now: u64) -> NowServing {
+    if state.current.as_deref() == Some(code) {
+        state.call_count += 1;
+    } else {
+        if let Some(previous) = state.current.take() {
+            push_recent(&mut state.recent, previous);
+        }
+        state.recent.retain(|c| c != code);
+        state.current = Some(code.to_string());
+        state.call_count = 1;
+    }
+    state.called_at = Some(now);
+    state.staff_id = Some(staff_id.to_string());
+    state
+}
+
+fn push_recent(recent: &mut Vec<String>, code: String) {
+    recent.retain(|c| *c != code);
+    recent.insert(0, code);
+    recent.truncate(RECENT_LIMIT);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advance_counts_recalls_and_keeps_recent_numbers() {
+        let state = advance(NowServing::default(), "P0001", "S001", 100);
+        assert_eq!((state.current.as_deref(), state.call_count), (Some("P0001"), 1));
+
+        // 同じ番号は呼び直し
+        let state = advance(state, "P0001", "S002", 160);
+        assert_eq!((state.call_count, state.called_at), (2, Some(160)));
+        assert_eq!(state.staff_id.as_deref(), Some("S002"));
+
+        let mut state = advance(state, "P0002", "S001", 200);
+        assert_eq!((state.current.as_deref(), state.call_count), (Some("P0002"), 1));
+        assert_eq!(state.recent, vec!["P0001"]);
+
+        // 以前の番号を呼び直すと履歴から外し、履歴は新しい順に上限まで
+        state = advance(state, "P0001", "S001", 300);
+        assert_eq!(state.recent, vec!["P0002"]);
+        for i in 3..10 {
+            state = advance(state, &format!("P{:04}", i), "S001", 400);
+        }
+        assert_eq!(state.recent.len(), RECENT_LIMIT);
+        assert_eq!(state.recent[0], "P0008");
+    }
+}
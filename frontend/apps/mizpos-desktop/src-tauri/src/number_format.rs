@@ -0,0 +1,138 @@
+//! 数値書式モジュール
+//!
+//! 印刷レイアウトで使う金額・数値の表記（通貨記号、桁区切り、全角数字）を端末設定に従って整形する
+
+use crate::text_normalize::to_fullwidth_digits;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 通貨記号の位置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolPosition {
+    /// 金額の前（例: ￥1,000）
+    #[default]
+    Prefix,
+    /// 金額の後（例: 1,000円）
+    Suffix,
+}
+
+/// 数値・金額の書式
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct NumberFormat {
+    /// 通貨記号
+    ///
+    /// 既定は全角￥（U+FFE5）。Shift_JISでは半角¥と\が同じコードになるため
+    pub currency_symbol: String,
+    /// 通貨記号の位置
+    pub symbol_position: SymbolPosition,
+    /// 桁区切り文字（空文字の場合は区切らない）
+    pub grouping_separator: String,
+    /// 桁区切りの間隔（0の場合は区切らない）
+    pub grouping_size: usize,
+    /// 数字を全角で印字する
+    pub fullwidth_digits: bool,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            currency_symbol: "￥".to_string(),
+            symbol_position: SymbolPosition::Prefix,
+            grouping_separator: ",".to_string(),
+            grouping_size: 3,
+            fullwidth_digits: false,
+        }
+    }
+}
+
+impl NumberFormat {
+    /// 数値を桁区切りして整形（例: 12345 → 12,345）
+    pub fn number(&self, value: u64) -> String {
+        let digits = value.to_string();
+        let mut result = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            let remaining = digits.len() - i;
+            if i > 0 && self.grouping_size > 0 && remaining.is_multiple_of(self.grouping_size) {
+                result.push_str(&self.grouping_separator);
+            }
+            result.push(c);
+        }
+
+        if self.fullwidth_digits {
+            to_fullwidth_digits(&result)
+        } else {
+            result
+        }
+    }
+
+    /// 金額を整形（例: 1600 → ￥1,600）
+    pub fn price(&self, amount: impl Into<u64>) -> String {
+        let number = self.number(amount.into());
+        match self.symbol_position {
+            SymbolPosition::Prefix => format!("{}{}", self.currency_symbol, number),
+            SymbolPosition::Suffix => format!("{}{}", number, self.currency_symbol),
+        }
+    }
+
+    /// 符号付きの金額を整形（例: -500 → -￥500、0以上は+を付ける）
+    pub fn signed_price(&self, amount: i64) -> String {
+        let sign = if amount < 0 { '-' } else { '+' };
+        format!("{}{}", sign, self.price(amount.unsigned_abs()))
+    }
+
+    /// 減額を整形（例: 500 → -￥500）
+    pub fn negative_price(&self, amount: impl Into<u64>) -> String {
+        format!("-{}", self.price(amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_price() {
+        let fmt = NumberFormat::default();
+        assert_eq!(fmt.price(0u32), "￥0");
+        assert_eq!(fmt.price(999u32), "￥999");
+        assert_eq!(fmt.price(1600u32), "￥1,600");
+        assert_eq!(fmt.price(1234567u32), "￥1,234,567");
+    }
+
+    #[test]
+    fn test_suffix_and_fullwidth() {
+        let fmt = NumberFormat {
+            currency_symbol: "円".to_string(),
+            symbol_position: SymbolPosition::Suffix,
+            fullwidth_digits: true,
+            ..NumberFormat::default()
+        };
+        assert_eq!(fmt.price(12345u32), "１２,３４５円");
+    }
+
+    #[test]
+    fn test_without_grouping() {
+        let fmt = NumberFormat {
+            grouping_separator: String::new(),
+            ..NumberFormat::default()
+        };
+        assert_eq!(fmt.price(1234567u32), "￥1234567");
+
+        let fmt = NumberFormat {
+            grouping_size: 0,
+            ..NumberFormat::default()
+        };
+        assert_eq!(fmt.number(1234567), "1234567");
+    }
+
+    #[test]
+    fn test_signed_price() {
+        let fmt = NumberFormat::default();
+        assert_eq!(fmt.signed_price(0), "+￥0");
+        assert_eq!(fmt.signed_price(1200), "+￥1,200");
+        assert_eq!(fmt.signed_price(-1200), "-￥1,200");
+        assert_eq!(fmt.negative_price(300u32), "-￥300");
+    }
+}
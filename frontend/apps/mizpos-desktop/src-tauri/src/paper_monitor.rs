@@ -0,0 +1,127 @@
+//! 用紙ニアエンド監視モジュール
+//!
+//! 開局中は定期的にプリンターの用紙センサーを確認し、残りわずかになったら通知する
+//! 必要に応じて「用紙残りわずか」の短い伝票も印刷し、お客様の合間にロールを交換できるようにする
+
+use crate::config;
+use crate::printer_status::PaperStatus;
+use crate::session;
+use crate::spooler::{self, UsbPrinter};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::Emitter;
+
+/// 用紙が残りわずかになったときに通知するイベント名
+pub const EVENT_PAPER_NEAR_END: &str = "paper-near-end";
+
+/// 最後に確認した用紙の状態
+static LAST_STATUS: Mutex<Option<PaperStatus>> = Mutex::new(None);
+
+/// 監視設定
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct PaperMonitorConfig {
+    /// 監視するプリンター（未設定なら監視しない）
+    pub printer: Option<UsbPrinter>,
+    /// 確認間隔（秒）
+    pub interval_secs: u64,
+    /// ニアエンドを検知したときに警告伝票を印刷する
+    pub print_warning_strip: bool,
+}
+
+impl Default for PaperMonitorConfig {
+    fn default() -> Self {
+        Self {
+            printer: None,
+            interval_secs: 60,
+            print_warning_strip: false,
+        }
+    }
+}
+
+/// フロントエンドへ通知する内容
+#[derive(Debug, Clone, Serialize)]
+pub struct PaperNearEndEvent {
+    pub printer: UsbPrinter,
+    /// 用紙切れ（ニアエンドを通り越している）
+    pub out: bool,
+    /// 警告伝票の印刷エラー（印刷しない・成功した場合はNone）
+    pub print_error: Option<String>,
+}
+
+/// 最後に確認した用紙の状態（未確認の場合はNone）
+pub fn last_status() -> Option<PaperStatus> {
+    LAST_STATUS.lock().ok().and_then(|s| *s)
+}
+
+/// バックグラウンドで用紙センサーを監視する
+pub fn start(app: tauri::AppHandle) {
+    thread::spawn(move || {
+        // 通知済みか（ロールを交換して解消するまで再通知しない）
+        let mut notified = false;
+        loop {
+            let config = config::load().unwrap_or_default().paper_monitor;
+            thread::sleep(Duration::from_secs(config.interval_secs.max(10)));
+            if let Some(near_end) = check(&app, &config, notified) {
+                notified = near_end;
+            }
+        }
+    });
+}
+
+/// 用紙センサーを確認し、ニアエンドかどうかを返す（確認しなかった場合はNone）
+fn check(app: &tauri::AppHandle, config: &PaperMonitorConfig, notified: bool) -> Option<bool> {
+    let printer = config.printer.as_ref()?;
+    if !session::current().ok()?.open {
+        return None;
+    }
+    // 印刷中のレシートを邪魔しないよう、スプーラーが空のときだけ問い合わせる
+    if !spooler::pending().is_empty() {
+        return None;
+    }
+
+    let status = read_status(printer).ok()?;
+    if let Ok(mut last) = LAST_STATUS.lock() {
+        *last = Some(status);
+    }
+    let near_end = status.near_end || status.out;
+    if near_end && !notified {
+        let print_error = if config.print_warning_strip && !status.out {
+            print_strip(printer).err()
+        } else {
+            None
+        };
+        let _ = app.emit(
+            EVENT_PAPER_NEAR_END,
+            PaperNearEndEvent {
+                printer: printer.clone(),
+                out: status.out,
+                print_error,
+            },
+        );
+    }
+    Some(near_end)
+}
+
+#[cfg(not(target_os = "android"))]
+fn read_status(printer: &UsbPrinter) -> Result<PaperStatus, String> {
+    crate::desktop_printer::read_paper_status(printer)
+}
+
+#[cfg(not(target_os = "android"))]
+fn print_strip(printer: &UsbPrinter) -> Result<(), String> {
+    crate::desktop_printer::print_paper_warning_strip(printer)
+}
+
+#[cfg(target_os = "android")]
+fn read_status(_printer: &UsbPrinter) -> Result<PaperStatus, String> {
+    Err("Bluetooth printing not yet implemented".to_string())
+}
+
+#[cfg(target_os = "android")]
+fn print_strip(_printer: &UsbPrinter) -> Result<(), String> {
+    Err("Bluetooth printing not yet implemented".to_string())
+}
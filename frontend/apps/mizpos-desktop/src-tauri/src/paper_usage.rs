@@ -0,0 +1,356 @@
+//! 用紙使用量モジュール
+//!
+//! プリンターに送る ESC/POS のバイト列から、印刷で送った用紙の長さを見積もる
+//! （改行の行送り・倍高の行・ラスター画像の高さ・QRコード・バーコードから計算する）
+//! 印刷1回ごとの長さを日別に記録し、次のイベントで用意するロール紙の数を見積もれるようにする
+
+use crate::config;
+use crate::datetime;
+use crate::event_context;
+use crate::storage;
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 使用量の保存ファイル名
+const PAPER_USAGE_FILE: &str = "paper_usage.json";
+/// 1mmあたりのドット数（203dpi）
+const DOTS_PER_MM: u32 = 8;
+/// 既定の行送り（1/6インチ）
+const DEFAULT_LINE_SPACING: u32 = 34;
+/// 倍高の文字で増える高さ（24ドットの文字を2倍にした分）
+const DOUBLE_HEIGHT_EXTRA: u32 = 24;
+/// バーコードの既定の高さ（GS h の初期値）
+const DEFAULT_BARCODE_HEIGHT: u32 = 162;
+/// QRコードの既定のモジュールサイズ（GS ( k の初期値）
+const DEFAULT_QR_MODULE: u32 = 3;
+/// QRコードの型番ごとの格納できるバイト数（8ビットバイトモード・誤り訂正M）
+const QR_BYTE_CAPACITY_M: [usize; 20] = [
+    14, 26, 42, 62, 84, 106, 122, 152, 180, 213, 251, 287, 331, 362, 412, 450, 504, 560, 624, 666,
+];
+
+/// 用紙の長さ
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct PaperLength {
+    /// 用紙を送った長さ（mm）
+    pub length_mm: u32,
+    /// 改行の数
+    pub lines: u32,
+}
+
+/// 送ったバイト列から用紙の長さを数える
+///
+/// コマンドの途中で区切って渡しても、続きのバイト列で数え続けられる
+#[derive(Debug, Clone)]
+pub struct PaperMeter {
+    dots: u32,
+    lines: u32,
+    line_spacing: u32,
+    /// 現在の行に倍高の文字がある
+    tall_line: bool,
+    /// 改行待ちの文字がある
+    text_pending: bool,
+    qr_module: u32,
+    qr_data_len: usize,
+    barcode_height: u32,
+    /// バーコードの上下に印刷する HRI の行数
+    hri_lines: u32,
+    /// 読み飛ばすデータの残り（画像・QRコードのデータなど）
+    skip: usize,
+    /// 続きのバイト列を待っているコマンドの先頭
+    partial: Vec<u8>,
+}
+
+impl Default for PaperMeter {
+    fn default() -> Self {
+        Self {
+            dots: 0,
+            lines: 0,
+            line_spacing: DEFAULT_LINE_SPACING,
+            tall_line: false,
+            text_pending: false,
+            qr_module: DEFAULT_QR_MODULE,
+            qr_data_len: 0,
+            barcode_height: DEFAULT_BARCODE_HEIGHT,
+            hri_lines: 0,
+            skip: 0,
+            partial: Vec::new(),
+        }
+    }
+}
+
+/// データ長からQRコードの1辺のモジュール数を見積もる（型番20を超える場合は20とする）
+fn qr_modules(data_len: usize) -> u32 {
+    let version = QR_BYTE_CAPACITY_M
+        .iter()
+        .position(|capacity| data_len <= *capacity)
+        .unwrap_or(QR_BYTE_CAPACITY_M.len() - 1) as u32
+        + 1;
+    17 + 4 * version
+}
+
+impl PaperMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// これまでに数えた長さ
+    pub fn length(&self) -> PaperLength {
+        PaperLength {
+            length_mm: self.dots.div_ceil(DOTS_PER_MM),
+            lines: self.lines,
+        }
+    }
+
+    fn newline(&mut self) {
+        self.dots += self.line_spacing + if self.tall_line { DOUBLE_HEIGHT_EXTRA } else { 0 };
+        self.lines += 1;
+        self.tall_line = false;
+        self.text_pending = false;
+    }
+
+    /// 画像・QRコードなど、行とは別に高さを持つものを印刷した
+    fn block(&mut self, dots: u32) {
+        // 改行待ちの文字は先に印刷される
+        if self.text_pending {
+            self.newline();
+        }
+        self.dots += dots;
+    }
+
+    /// バイト列を読み進める
+    pub fn feed(&mut self, data: &[u8]) {
+        let skipped = self.skip.min(data.len());
+        self.skip -= skipped;
+        let data = &data[skipped..];
+        if data.is_empty() {
+            return;
+        }
+        let mut bytes = std::mem::take(&mut self.partial);
+        bytes.extend_from_slice(data);
+
+        let mut i = 0;
+        while i < bytes.len() {
+            match self.command(&bytes[i..]) {
+                Some(len) if i + len <= bytes.len() => i += len,
+                Some(len) => {
+                    // データ部分がまだ届いていない（コマンドの処理は済んでいる）
+                    self.skip = i + len - bytes.len();
+                    return;
+                }
+                None => {
+                    self.partial = bytes[i..].to_vec();
+                    return;
+                }
+            }
+        }
+    }
+
+    /// 先頭のコマンドを処理して長さを返す（コマンドの先頭部分が途中で切れている場合は None）
+    fn command(&mut self, bytes: &[u8]) -> Option<usize> {
+        let at = |n: usize| bytes.get(n).copied();
+        let len16 = |n: usize| Some(at(n)? as usize | (at(n + 1)? as usize) << 8);
+        match bytes[0] {
+            0x0a => {
+                self.newline();
+                Some(1)
+            }
+            0x10 => Some(3),
+            0x1b => match at(1)? {
+                b'@' => {
+                    self.line_spacing = DEFAULT_LINE_SPACING;
+                    Some(2)
+                }
+                b'2' => {
+                    self.line_spacing = DEFAULT_LINE_SPACING;
+                    Some(2)
+                }
+                b'3' => {
+                    self.line_spacing = u32::from(at(2)?);
+                    Some(3)
+                }
+                b'!' => {
+                    self.tall_line |= at(2)? & 0x10 != 0;
+                    Some(3)
+                }
+                b'J' => {
+                    self.block(u32::from(at(2)?));
+                    Some(3)
+                }
+                b'd' => {
+                    for _ in 0..at(2)? {
+                        self.newline();
+                    }
+                    Some(3)
+                }
+                _ => Some(3),
+            },
+            0x1c => match at(1)? {
+                b'&' | b'.' => Some(2),
+                b'!' => {
+                    self.tall_line |= at(2)? & 0x08 != 0;
+                    Some(3)
+                }
+                b'(' => Some(5 + len16(3)?),
+                _ => Some(3),
+            },
+            0x1d => match at(1)? {
+                b'V' => {
+                    // 改行せずにカットした文字も印刷される
+                    if self.text_pending {
+                        self.newline();
+                    }
+                    Some(3)
+                }
+                b'h' => {
+                    self.barcode_height = u32::from(at(2)?);
+                    Some(3)
+                }
+                b'H' => {
+                    // n: 0 なし・1 上・2 下・3 上下
+                    let n = at(2)?;
+                    self.hri_lines = u32::from(n & 0x01) + u32::from((n >> 1) & 0x01);
+                    Some(3)
+                }
+                b'L' | b'W' => Some(4),
+                b'(' => {
+                    // GS ( k pL pH cn fn ...
+                    let len = len16(3)?;
+                    let function = at(6)?;
+                    match function {
+                        0x43 => self.qr_module = u32::from(at(7)?),
+                        // 格納するデータ（pL pH には cn fn m の3バイトを含む）
+                        0x50 => self.qr_data_len = len.saturating_sub(3),
+                        0x51 => self.block(qr_modules(self.qr_data_len) * self.qr_module),
+                        _ => {}
+                    }
+                    Some(5 + len)
+                }
+                b'k' => {
+                    // GS k m n d1...dn
+                    let n = at(3)? as usize;
+                    let hri = self.hri_lines * DEFAULT_LINE_SPACING;
+                    self.block(self.barcode_height + hri);
+                    Some(4 + n)
+                }
+                b'v' => {
+                    // GS v 0 m xL xH yL yH d1...dk
+                    let x = len16(4)?;
+                    let y = len16(6)?;
+                    self.block(y as u32);
+                    Some(8 + x * y)
+                }
+                _ => Some(3),
+            },
+            _ => {
+                self.text_pending = true;
+                Some(1)
+            }
+        }
+    }
+}
+
+/// 日別・用紙幅別の使用量
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct DailyPaperUsage {
+    /// 日付（設定のタイムゾーン、YYYY-MM-DD）
+    pub date: String,
+    /// 用紙幅（mm）
+    pub paper_width: u8,
+    /// 印刷回数
+    pub jobs: u32,
+    /// 用紙を送った長さ（mm）
+    pub length_mm: u64,
+}
+
+/// 選択中イベントの日別の使用量（日付・用紙幅の順）
+pub fn daily() -> Result<Vec<DailyPaperUsage>, String> {
+    Ok(storage::load_json(&event_context::scoped(PAPER_USAGE_FILE)?)?.unwrap_or_default())
+}
+
+/// 日別の使用量に印刷1回分を加える
+fn add(usage: &mut Vec<DailyPaperUsage>, date: String, paper_width: u8, length: PaperLength) {
+    match usage
+        .iter_mut()
+        .find(|u| u.date == date && u.paper_width == paper_width)
+    {
+        Some(day) => {
+            day.jobs += 1;
+            day.length_mm += u64::from(length.length_mm);
+        }
+        None => {
+            usage.push(DailyPaperUsage {
+                date,
+                paper_width,
+                jobs: 1,
+                length_mm: u64::from(length.length_mm),
+            });
+            usage.sort_by(|a, b| (&a.date, a.paper_width).cmp(&(&b.date, b.paper_width)));
+        }
+    }
+}
+
+/// 印刷1回分の使用量を記録する（用紙幅はmm）
+pub fn record(paper_width: u8, length: PaperLength) -> Result<(), String> {
+    if length.length_mm == 0 {
+        return Ok(());
+    }
+    let tz = datetime::timezone(&config::load()?.timezone);
+    let date = DateTime::from_timestamp(storage::now_unix() as i64, 0)
+        .map(|dt| dt.with_timezone(&tz).format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+
+    let file = event_context::scoped(PAPER_USAGE_FILE)?;
+    let mut usage = daily()?;
+    add(&mut usage, date, paper_width, length);
+    storage::save_json(&file, &usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meter_counts_lines_images_and_codes() {
+        let mut meter = PaperMeter::new();
+        // 通常の行・倍高の行・3行送り
+        meter.feed(b"\x1b@ABC\n");
+        meter.feed(b"\x1b!\x30\x1c!\x0cBIG\x1b!\x00\x1c!\x00\n");
+        meter.feed(b"\n\n\n");
+        assert_eq!(meter.length().lines, 5);
+        assert_eq!(meter.dots, 34 * 5 + 24);
+
+        // ラスター画像（データを分けて送っても高さは1回だけ数える）
+        let mut image = vec![0x1d, b'v', b'0', 0, 2, 0, 100, 0];
+        image.extend(vec![0u8; 100]);
+        meter.feed(&image);
+        meter.feed(&[0u8; 100]);
+        assert_eq!(meter.dots, 34 * 5 + 24 + 100);
+
+        // 5バイトのQRコード（型番1: 21モジュール × 6ドット）と HRI 付きのバーコード
+        meter.feed(b"\x1d(k\x03\x001C\x06");
+        meter.feed(b"\x1d(k\x08\x001P0HELLO\x1d(k\x03\x001Q0");
+        meter.feed(b"\x1dH\x02\x1dh\x3c\x1dkI\x03{BA");
+        assert_eq!(meter.dots, 34 * 5 + 24 + 100 + 21 * 6 + 60 + 34);
+        assert_eq!(meter.length().length_mm, (34 * 5 + 24 + 100 + 21 * 6 + 60 + 34_u32).div_ceil(8));
+    }
+
+    #[test]
+    fn test_add_sums_jobs_per_day_and_width() {
+        let mut usage = Vec::new();
+        let length = |mm| PaperLength { length_mm: mm, lines: 0 };
+        add(&mut usage, "2026-08-16".to_string(), 58, length(120));
+        add(&mut usage, "2026-08-15".to_string(), 58, length(80));
+        add(&mut usage, "2026-08-16".to_string(), 58, length(100));
+        add(&mut usage, "2026-08-16".to_string(), 80, length(50));
+
+        let days: Vec<(&str, u8, u32, u64)> = usage
+            .iter()
+            .map(|u| (u.date.as_str(), u.paper_width, u.jobs, u.length_mm))
+            .collect();
+        assert_eq!(
+            days,
+            vec![("2026-08-15", 58, 1, 80), ("2026-08-16", 58, 2, 220), ("2026-08-16", 80, 1, 50)]
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! 保留取引モジュール
+//!
+//! 会計途中のカゴを一時保存し、後で呼び出せるようにする
+//! （現金を下ろしに行った客の間に次の客を会計するため）
+//!
+//! 保留取引はイベント単位の SQLite データベースに保存する
+//! 呼び出しは取得と削除を1つのトランザクションで行い、同じカゴを2回取り出せないようにする
+
+use crate::event_context;
+use crate::receipt::ReceiptItem;
+use crate::storage;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 保留取引のデータベースファイル名
+const PARKED_SALES_DB: &str = "parked_sales.sqlite3";
+/// 以前の保存ファイル名（JSON）。見つかった場合はデータベースに移して削除する
+const LEGACY_PARKED_SALES_FILE: &str = "parked_sales.json";
+
+/// 保留中の取引
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ParkedSale {
+    /// 呼び出し番号（P0001形式）
+    pub code: String,
+    pub items: Vec<ReceiptItem>,
+    pub total: u32,
+    pub staff_id: String,
+    /// メモ（客の特徴など）
+    pub memo: Option<String>,
+    /// 保留日時（UNIX秒）
+    pub parked_at: u64,
+}
+
+fn db_error(e: rusqlite::Error) -> String {
+    format!("Parked sales database error: {}", e)
+}
+
+/// 選択中イベントのデータベースを開く
+fn open() -> Result<Connection, String> {
+    let conn = storage::open_sqlite(&event_context::scoped(PARKED_SALES_DB)?)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS parked_sales (
+            code      TEXT PRIMARY KEY,
+            items     TEXT NOT NULL,
+            total     INTEGER NOT NULL,
+            staff_id  TEXT NOT NULL,
+            memo      TEXT,
+            parked_at INTEGER NOT NULL
+        )",
+    )
+    .map_err(db_error)?;
+    migrate_legacy(&conn)?;
+    Ok(conn)
+}
+
+/// JSON で保存していた保留取引をデータベースに移す
+fn migrate_legacy(conn: &Connection) -> Result<(), String> {
+    let legacy_file = event_context::scoped(LEGACY_PARKED_SALES_FILE)?;
+    let Some(sales) = storage::load_json::<Vec<ParkedSale>>(&legacy_file)? else {
+        return Ok(());
+    };
+    for sale in &sales {
+        insert(conn, sale, true)?;
+    }
+    storage::remove_file(&legacy_file)
+}
+
+fn insert(conn: &Connection, sale: &ParkedSale, ignore_existing: bool) -> Result<(), String> {
+    let items = serde_json::to_string(&sale.items).map_err(|e| e.to_string())?;
+    let verb = if ignore_existing {
+        "INSERT OR IGNORE"
+    } else {
+        "INSERT"
+    };
+    conn.execute(
+        &format!(
+            "{} INTO parked_sales (code, items, total, staff_id, memo, parked_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            verb
+        ),
+        params![
+            sale.code,
+            items,
+            sale.total,
+            sale.staff_id,
+            sale.memo,
+            sale.parked_at
+        ],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+fn from_row(row: &Row) -> rusqlite::Result<ParkedSale> {
+    let items: String = row.get("items")?;
+    let items = serde_json::from_str(&items).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    Ok(ParkedSale {
+        code: row.get("code")?,
+        items,
+        total: row.get("total")?,
+        staff_id: row.get("staff_id")?,
+        memo: row.get("memo")?,
+        parked_at: row.get("parked_at")?,
+    })
+}
+
+const SELECT: &str = "SELECT code, items, total, staff_id, memo, parked_at FROM parked_sales";
+
+/// カゴを保留
+pub fn park(
+    items: Vec<ReceiptItem>,
+    total: u32,
+    staff_id: &str,
+    memo: Option<String>,
+) -> Result<ParkedSale, String> {
+    if items.is_empty() {
+        return Err("Basket is empty".to_string());
+    }
+
+    let conn = open()?;
+    let sale = ParkedSale {
+        code: event_context::next_parked_code()?,
+        items,
+        total,
+        staff_id: staff_id.to_string(),
+        memo,
+        parked_at: storage::now_unix(),
+    };
+    insert(&conn, &sale, false)?;
+    Ok(sale)
+}
+
+/// 保留中の取引一覧（保留した順）
+pub fn list() -> Result<Vec<ParkedSale>, String> {
+    let conn = open()?;
+    let mut stmt = conn
+        .prepare(&format!("{} ORDER BY parked_at, code", SELECT))
+        .map_err(db_error)?;
+    let sales = stmt
+        .query_map([], from_row)
+        .map_err(db_error)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(db_error)?;
+    Ok(sales)
+}
+
+fn find_in(conn: &Connection, code: &str) -> Result<Option<ParkedSale>, String> {
+    conn.query_row(&format!("{} WHERE code = ?1", SELECT), [code], from_row)
+        .optional()
+        .map_err(db_error)
+}
+
+/// 保留中の取引を取得
+pub fn find(code: &str) -> Result<Option<ParkedSale>, String> {
+    find_in(&open()?, code)
+}
+
+/// 保留を解除してカゴを取り出す
+pub fn resume(code: &str) -> Result<ParkedSale, String> {
+    let mut conn = open()?;
+    let tx = conn.transaction().map_err(db_error)?;
+    let sale = find_in(&tx, code)?.ok_or_else(|| format!("Parked sale not found: {}", code))?;
+    tx.execute("DELETE FROM parked_sales WHERE code = ?1", [code])
+        .map_err(db_error)?;
+    tx.commit().map_err(db_error)?;
+    Ok(sale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipt::test_support;
+    use crate::storage::test_support::TempDataDir;
+    use serde_json::json;
+
+    fn item(name: &str, price: u32) -> ReceiptItem {
+        test_support::item(json!({ "name": name, "is_book": false, "price": price }))
+    }
+
+    #[test]
+    fn test_park_and_resume_once() {
+        let _dir = TempDataDir::new();
+        let first = park(vec![item("ステッカー", 300)], 300, "S001", None).unwrap();
+        let second = park(
+            vec![item("アクリルスタンド", 1500)],
+            1500,
+            "S001",
+            Some("青い帽子".into()),
+        )
+        .unwrap();
+        assert_eq!(
+            (first.code.as_str(), second.code.as_str()),
+            ("P0001", "P0002")
+        );
+
+        let codes: Vec<String> = list().unwrap().into_iter().map(|s| s.code).collect();
+        assert_eq!(codes, vec!["P0001", "P0002"]);
+
+        let resumed = resume("P0002").unwrap();
+        assert_eq!(resumed.items[0].name, "アクリルスタンド");
+        assert_eq!(resumed.memo.as_deref(), Some("青い帽子"));
+        // 同じカゴは2回取り出せない
+        assert!(resume("P0002").is_err());
+        assert!(find("P0002").unwrap().is_none());
+        assert_eq!(list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_park_rejects_empty_basket() {
+        let _dir = TempDataDir::new();
+        assert!(park(vec![], 0, "S001", None).is_err());
+    }
+
+    #[test]
+    fn test_legacy_json_is_migrated() {
+        let dir = TempDataDir::new();
+        let legacy = ParkedSale {
+            code: "P0007".to_string(),
+            items: vec![item("ステッカー", 300)],
+            total: 300,
+            staff_id: "S001".to_string(),
+            memo: None,
+            parked_at: 1,
+        };
+        let path = event_context::scoped(LEGACY_PARKED_SALES_FILE).unwrap();
+        dir.write(&path, &serde_json::to_string(&vec![legacy]).unwrap());
+
+        assert_eq!(find("P0007").unwrap().unwrap().total, 300);
+        assert_eq!(
+            storage::load_json::<Vec<ParkedSale>>(&path)
+                .unwrap()
+                .map(|s| s.len()),
+            None
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! コマンドの権限モジュール
+//!
+//! ウィンドウごとに呼べるコマンドは capabilities/ の権限セット（permissions/*.toml）で絞り込む
+//! 客面表示のウィンドウ（customer-display）には表示用のコマンドだけを許可し、Keychain やドロワーは操作させない
+//! 責任者向けのコマンドは、メインウィンドウからの呼び出しで責任者セッション中であることをコマンド側でも確認する
+//! 責任者が未登録の端末（初期設定中）はセッションなしで許可する
+
+use crate::staff_auth::{self, SupervisorSession};
+use crate::storage;
+
+/// メインウィンドウのラベル
+pub const MAIN_WINDOW: &str = "main";
+
+fn check(
+    window_label: &str,
+    has_supervisors: bool,
+    session: Option<&SupervisorSession>,
+    now: u64,
+    operation: &str,
+) -> Result<(), String> {
+    if window_label != MAIN_WINDOW {
+        return Err(format!(
+            "{} is only available from the main window",
+            operation
+        ));
+    }
+    if has_supervisors && session.is_none_or(|s| s.expires_at <= now) {
+        return Err(format!("{} requires a supervisor session", operation));
+    }
+    Ok(())
+}
+
+/// 責任者向けの操作の前に呼ぶ（メインウィンドウ以外・責任者セッション外はエラー）
+pub fn ensure_privileged(window_label: &str, operation: &str) -> Result<(), String> {
+    check(
+        window_label,
+        staff_auth::has_supervisors()?,
+        staff_auth::active_session()?.as_ref(),
+        storage::now_unix(),
+        operation,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_privileged_commands_need_main_window_and_session() {
+        let session = SupervisorSession {
+            supervisor_id: "SV01".to_string(),
+            expires_at: 1_000,
+        };
+        assert!(check(MAIN_WINDOW, true, Some(&session), 999, "Drawer").is_ok());
+        // 客面表示のウィンドウからは責任者セッション中でも拒否する
+        assert!(check("customer-display", true, Some(&session), 999, "Drawer").is_err());
+        assert!(check(MAIN_WINDOW, true, None, 999, "Drawer").is_err());
+        assert!(check(MAIN_WINDOW, true, Some(&session), 1_000, "Drawer").is_err());
+        // 責任者が未登録の端末（初期設定中）はセッションなしで許可する
+        assert!(check(MAIN_WINDOW, false, None, 999, "Drawer").is_ok());
+        assert!(check("customer-display", false, None, 999, "Drawer").is_err());
+    }
+
+    /// 型定義にあるコマンドが権限の一覧と権限セットに漏れなく入っているか
+    #[test]
+    fn test_every_command_has_a_permission() {
+        let build = include_str!("../build.rs");
+        let default = include_str!("../permissions/pos-default.toml");
+        let privileged = include_str!("../permissions/pos-privileged.toml");
+        let display = include_str!("../permissions/customer-display.toml");
+
+        let bindings = include_str!("../../src/lib/commands.gen.ts");
+        for command in bindings
+            .split("TAURI_INVOKE(\"")
+            .skip(1)
+            .filter_map(|rest| rest.split('"').next())
+        {
+            let permission = format!("\"allow-{}\"", command.replace('_', "-"));
+            assert!(
+                build.contains(&format!("\"{}\"", command)),
+                "{} is not in APP_COMMANDS",
+                command
+            );
+            assert!(
+                default.contains(&permission) != privileged.contains(&permission),
+                "{} must be in exactly one of pos-default and pos-privileged",
+                command
+            );
+            if privileged.contains(&permission) {
+                assert!(
+                    !display.contains(&permission),
+                    "{} is allowed on the customer display",
+                    command
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! ポイントモジュール
+//!
+//! 会員の売上に設定の還元率でポイントを付け、レシートに今回の獲得ポイントと残高を印字する
+//! 付与したポイントは端末内の台帳に記録し、署名付きでアップロードキューからサーバーに送る
+//! 残高はサーバーから会員と一緒に取り込んだ残高に、その後この端末で付与した分（期限内）を足したもの
+
+use crate::config;
+use crate::members::{self, Member};
+use crate::receipt::{ReceiptData, ReceiptPoints};
+use crate::storage;
+use crate::upload_queue;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// ポイント台帳の保存ファイル名
+const LEDGER_FILE: &str = "points_ledger.jsonl";
+/// 1日の秒数
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// ポイントの設定
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct PointsConfig {
+    pub enabled: bool,
+    /// 1ポイントあたりの金額（円）
+    pub yen_per_point: u32,
+    /// 対象外の商品（JAN）
+    pub excluded_jans: Vec<String>,
+    /// 対象外の商品区分
+    pub excluded_categories: Vec<String>,
+    /// 対象外の支払手段（商品券・ギフト券など。この支払分はポイントの対象から除く）
+    pub excluded_payment_methods: Vec<String>,
+    /// 有効期限（日数、0の場合は無期限）
+    pub expiry_days: u32,
+}
+
+impl Default for PointsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            yen_per_point: 100,
+            excluded_jans: vec![],
+            excluded_categories: vec![],
+            excluded_payment_methods: vec![],
+            expiry_days: 365,
+        }
+    }
+}
+
+/// 付与したポイント（台帳の1件、アップロードの内容）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PointsAccrual {
+    pub member_id: String,
+    pub receipt_number: String,
+    pub points: u64,
+    /// 付与日時（UNIX秒）
+    pub earned_at: u64,
+    /// 有効期限（UNIX秒、無期限の場合はNone）
+    pub expires_at: Option<u64>,
+}
+
+/// 売上の獲得ポイントを計算
+fn compute(config: &PointsConfig, receipt: &ReceiptData) -> u64 {
+    if config.yen_per_point == 0 {
+        return 0;
+    }
+    let eligible_items: u64 = receipt
+        .items
+        .iter()
+        .filter(|i| !config.excluded_jans.contains(&i.jan))
+        .filter(|i| {
+            i.category
+                .as_ref()
+                .is_none_or(|c| !config.excluded_categories.contains(c))
+        })
+        .map(|i| i.price as u64)
+        .sum();
+    let excluded_payments: u64 = receipt
+        .payments
+        .iter()
+        .filter(|p| config.excluded_payment_methods.contains(&p.method))
+        .map(|p| p.amount as u64)
+        .sum();
+    let eligible = eligible_items
+        .min(receipt.total as u64)
+        .saturating_sub(excluded_payments);
+    eligible / config.yen_per_point as u64
+}
+
+/// 会員の残高（サーバーの残高と、取り込み後にこの端末で付与した期限内のポイントの合計）
+fn balance_of(member: &Member, ledger: &[PointsAccrual], now: u64) -> u64 {
+    let local: u64 = ledger
+        .iter()
+        .filter(|a| a.member_id == member.member_id && a.earned_at > member.updated_at)
+        .filter(|a| a.expires_at.is_none_or(|e| e > now))
+        .map(|a| a.points)
+        .sum();
+    member.points_balance + local
+}
+
+fn load_ledger() -> Vec<PointsAccrual> {
+    storage::load_jsonl(LEDGER_FILE)
+}
+
+/// 会員の売上にポイントを付与し、レシートに獲得ポイントと残高を入れる
+///
+/// 同じレシート番号で付与済みの場合は付与し直さない（再印刷・再送に備える）
+pub fn accrue(receipt: &mut ReceiptData) -> Result<(), String> {
+    let config = config::load()?.points;
+    if !config.enabled {
+        return Ok(());
+    }
+    let Some(member_id) = receipt.member_id.as_deref() else {
+        return Ok(());
+    };
+    let Some(member) = members::find(member_id)? else {
+        return Ok(());
+    };
+
+    let now = storage::now_unix();
+    let mut ledger = load_ledger();
+    let earned = match ledger
+        .iter()
+        .find(|a| a.receipt_number == receipt.receipt_number)
+    {
+        Some(existing) => existing.points,
+        None => {
+            let points = compute(&config, receipt);
+            if points > 0 {
+                let accrual = PointsAccrual {
+                    member_id: member.member_id.clone(),
+                    receipt_number: receipt.receipt_number.clone(),
+                    points,
+                    earned_at: now,
+                    expires_at: (config.expiry_days > 0)
+                        .then(|| now + config.expiry_days as u64 * DAY_SECS),
+                };
+                storage::append_jsonl(LEDGER_FILE, &accrual)?;
+                upload_queue::enqueue(
+                    upload_queue::KIND_POINTS_ACCRUAL,
+                    &accrual.receipt_number,
+                    &accrual,
+                )?;
+                ledger.push(accrual);
+            }
+            points
+        }
+    };
+
+    receipt.points = Some(ReceiptPoints {
+        earned,
+        balance: balance_of(&member, &ledger, now),
+    });
+    Ok(())
+}
+
+/// 会員の現在の残高
+pub fn balance(member_id: &str) -> Result<Option<u64>, String> {
+    let Some(member) = members::find(member_id)? else {
+        return Ok(None);
+    };
+    Ok(Some(balance_of(&member, &load_ledger(), storage::now_unix())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipt::test_support;
+    use serde_json::json;
+
+    fn receipt() -> ReceiptData {
+        test_support::receipt("R-1")
+            .item(json!({}))
+            .item(json!({
+                "name": "ステッカー", "jan": "4900000000028", "is_book": false,
+                "quantity": 2, "price": 600, "category": "goods"
+            }))
+            .with(json!({
+                "payments": [
+                    { "method": "現金", "amount": 1100 },
+                    { "method": "百貨店商品券", "amount": 500 }
+                ]
+            }))
+            .build()
+    }
+
+    #[test]
+    fn test_compute_applies_exclusions() {
+        let config = PointsConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        assert_eq!(compute(&config, &receipt()), 16);
+
+        let config = PointsConfig {
+            enabled: true,
+            excluded_categories: vec!["goods".to_string()],
+            excluded_payment_methods: vec!["百貨店商品券".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(compute(&config, &receipt()), 5);
+
+        let config = PointsConfig {
+            yen_per_point: 0,
+            ..Default::default()
+        };
+        assert_eq!(compute(&config, &receipt()), 0);
+    }
+
+    #[test]
+    fn test_balance_counts_unexpired_local_accruals_after_import() {
+        let member = Member {
+            member_id: "M0001".to_string(),
+            name: "みず".to_string(),
+            card_uid: None,
+            points_balance: 100,
+            updated_at: 1000,
+        };
+        let accrual = |earned_at, expires_at, points| PointsAccrual {
+            member_id: "M0001".to_string(),
+            receipt_number: format!("R-{}", earned_at),
+            points,
+            earned_at,
+            expires_at,
+        };
+        let ledger = vec![
+            // 取り込み前（サーバーの残高に含まれている）
+            accrual(900, None, 10),
+            accrual(1100, Some(5000), 20),
+            accrual(1200, Some(1500), 30),
+            accrual(1300, None, 40),
+        ];
+        assert_eq!(balance_of(&member, &ledger, 2000), 160);
+    }
+}
@@ -0,0 +1,264 @@
+//! 価格表モジュール
+//!
+//! 管理サーバーから配信された価格改定を端末の価格表（イベント単位）に反映する
+//! 価格表は1つのファイルに全商品分を書き換えて保存するため、読み出す側は改定前か改定後のどちらかの版だけを見る
+//! 改定ごとに変わった商品の旧価格・新価格と反映日時をジャーナルに残し、
+//! 売上には会計時の価格表の版を記録して、改定の前後どちらの価格で印刷したかを追えるようにする
+//! 改定の直前に始めた会計を確定できるよう1つ前の版の価格も残し、明細の価格はレシートの版の価格表と照合する
+
+use crate::event_context;
+use crate::journal::{self, JournalRecord};
+use crate::receipt::ReceiptData;
+use crate::server_updates::PriceUpdate;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// 価格表の保存ファイル名
+const PRICE_BOOK_FILE: &str = "price_book.json";
+/// 価格表が変わったときに通知するイベント名
+pub const EVENT_PRICE_BOOK_UPDATED: &str = "price-book-updated";
+
+/// 価格表
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct PriceBook {
+    /// 版（反映した価格改定の発行日時、未反映は0）
+    pub version: u64,
+    /// 反映日時（UNIX秒）
+    pub applied_at: Option<u64>,
+    /// 商品IDごとの価格
+    pub prices: BTreeMap<String, u32>,
+    /// 1つ前の版（改定の前に始めた会計の照合用）
+    #[serde(default)]
+    pub previous_version: u64,
+    /// 1つ前の版の商品IDごとの価格
+    #[serde(default)]
+    pub previous_prices: BTreeMap<String, u32>,
+}
+
+/// 価格改定で変わった商品1件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct PriceChange {
+    pub product_id: String,
+    /// 改定前の価格（価格表になかった商品はNone）
+    pub old_price: Option<u32>,
+    pub new_price: u32,
+}
+
+/// 価格改定の反映結果
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PriceBookUpdate {
+    pub book: PriceBook,
+    pub changes: Vec<PriceChange>,
+}
+
+/// 反映を1件ずつ行うためのロック
+fn apply_lock() -> &'static Mutex<()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    &LOCK
+}
+
+/// 現在の価格表（改定の途中の状態は読み出さない）
+pub fn snapshot() -> Result<PriceBook, String> {
+    Ok(storage::load_json(&event_context::scoped(PRICE_BOOK_FILE)?)?.unwrap_or_default())
+}
+
+/// 検証済みの価格改定を価格表に反映し、変わった商品をジャーナルに記録する
+pub fn apply(update: &PriceUpdate, staff_id: Option<&str>) -> Result<PriceBookUpdate, String> {
+    let _guard = apply_lock().lock().map_err(|_| "Price book lock poisoned".to_string())?;
+    let current = snapshot()?;
+    if update.issued_at <= current.version {
+        return Err(format!(
+            "Price update is not newer than the price book ({} <= {})",
+            update.issued_at, current.version
+        ));
+    }
+
+    let path = event_context::scoped(PRICE_BOOK_FILE)?;
+    let effective_at = storage::now_unix();
+    let (book, changes) = revise(current.clone(), update, effective_at);
+    storage::save_json(&path, &book)?;
+    // ジャーナルに残せなかった改定は反映しない（価格表を改定前の版に戻す）
+    if let Err(e) = journal::append(
+        staff_id,
+        JournalRecord::PriceUpdated {
+            issued_at: update.issued_at,
+            effective_at,
+            changes: changes.clone(),
+        },
+    ) {
+        storage::save_json(&path, &current)?;
+        return Err(e);
+    }
+    Ok(PriceBookUpdate { book, changes })
+}
+
+/// 価格表に改定を重ねた新しい版と、変わった商品
+fn revise(mut book: PriceBook, update: &PriceUpdate, effective_at: u64) -> (PriceBook, Vec<PriceChange>) {
+    book.previous_version = book.version;
+    book.previous_prices = book.prices.clone();
+    let mut changes = Vec::new();
+    for entry in &update.prices {
+        let old_price = book.prices.insert(entry.product_id.clone(), entry.price);
+        if old_price != Some(entry.price) {
+            changes.retain(|c: &PriceChange| c.product_id != entry.product_id);
+            changes.push(PriceChange {
+                product_id: entry.product_id.clone(),
+                old_price,
+                new_price: entry.price,
+            });
+        }
+    }
+    book.version = update.issued_at;
+    book.applied_at = Some(effective_at);
+    (book, changes)
+}
+
+/// 売上に会計時の価格表の版を入れ、明細の価格をその版の価格表と照合する（フロントエンドが指定した版は変えない）
+pub fn stamp(receipt: &mut ReceiptData) -> Result<(), String> {
+    check(&snapshot()?, receipt)
+}
+
+/// 版を決めて明細の価格を照合する
+///
+/// 受け付ける版は現在の版と1つ前の版のみ（改定の途中で始めた会計は前の版の価格で確定できる）
+/// 価格変更の承認がある明細と、価格表にない商品は照合しない
+fn check(book: &PriceBook, receipt: &mut ReceiptData) -> Result<(), String> {
+    let version = *receipt.price_version.get_or_insert(book.version);
+    let prices = if version == book.version {
+        &book.prices
+    } else if version == book.previous_version {
+        &book.previous_prices
+    } else {
+        return Err(format!("Unknown price book version: {} (current {})", version, book.version));
+    };
+
+    for item in receipt.items.iter().filter(|i| i.price_override.is_none()) {
+        let Some(product_id) = item.product_id.as_deref() else { continue };
+        let Some(&unit_price) = prices.get(product_id) else { continue };
+        if item.price != unit_price * item.quantity {
+            return Err(format!(
+                "Price of {} does not match price book version {}: {} (expected {})",
+                product_id,
+                version,
+                item.price,
+                unit_price * item.quantity
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server_updates::PriceEntry;
+
+    fn update(issued_at: u64, prices: &[(&str, u32)]) -> PriceUpdate {
+        PriceUpdate {
+            terminal_id: None,
+            issued_at,
+            prices: prices
+                .iter()
+                .map(|&(product_id, price)| PriceEntry {
+                    product_id: product_id.to_string(),
+                    price,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_revise_records_only_changed_prices() {
+        let (book, changes) = revise(PriceBook::default(), &update(100, &[("A", 500), ("B", 1000)]), 150);
+        assert_eq!((book.version, book.applied_at), (100, Some(150)));
+        assert_eq!(changes.len(), 2);
+
+        let (book, changes) = revise(book, &update(200, &[("A", 500), ("B", 800), ("C", 300)]), 250);
+        assert_eq!(book.prices.get("B"), Some(&800));
+        assert_eq!(
+            changes,
+            vec![
+                PriceChange { product_id: "B".to_string(), old_price: Some(1000), new_price: 800 },
+                PriceChange { product_id: "C".to_string(), old_price: None, new_price: 300 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_is_journaled_and_rejects_stale_versions() {
+        let _dir = crate::storage::test_support::TempDataDir::new();
+        let applied = apply(&update(100, &[("A", 500)]), Some("S001")).unwrap();
+        assert_eq!(snapshot().unwrap(), applied.book);
+        assert!(apply(&update(100, &[("A", 400)]), None).is_err());
+        assert_eq!(snapshot().unwrap().prices.get("A"), Some(&500));
+
+        let entries = journal::entries().unwrap();
+        assert!(matches!(
+            &entries[0].record,
+            JournalRecord::PriceUpdated { issued_at: 100, changes, .. } if changes.len() == 1
+        ));
+    }
+
+    fn receipt(price_version: Option<u64>, product_id: &str, quantity: u32, price: u32) -> ReceiptData {
+        crate::receipt::test_support::receipt("R001")
+            .item(serde_json::json!({ "product_id": product_id, "quantity": quantity, "price": price }))
+            .with(serde_json::json!({ "price_version": price_version }))
+            .build()
+    }
+
+    #[test]
+    fn test_receipt_started_before_an_update_is_checked_against_previous_prices() {
+        let (book, _) = revise(PriceBook::default(), &update(100, &[("A", 500)]), 150);
+        let mut started = receipt(None, "A", 2, 1000);
+        check(&book, &mut started).unwrap();
+        assert_eq!(started.price_version, Some(100));
+
+        // 会計中に改定が反映されても、始めたときの版の価格で確定できる
+        let (book, _) = revise(book, &update(200, &[("A", 400)]), 250);
+        check(&book, &mut started).unwrap();
+        assert!(check(&book, &mut receipt(Some(200), "A", 2, 1000)).is_err());
+        check(&book, &mut receipt(None, "A", 2, 800)).unwrap();
+        // 前の版でも価格が合わない明細は受け付けない
+        assert!(check(&book, &mut receipt(Some(100), "A", 1, 400)).is_err());
+
+        // 2つ前の版はもう照合できない
+        let (book, _) = revise(book, &update(300, &[("A", 450)]), 350);
+        assert!(check(&book, &mut started).is_err());
+        // 価格表にない商品は照合しない
+        check(&book, &mut receipt(None, "Z", 1, 1)).unwrap();
+    }
+
+    #[test]
+    fn test_versions_are_tracked_per_event() {
+        let _dir = crate::storage::test_support::TempDataDir::new();
+        event_context::select("EV1", "イベント1").unwrap();
+        apply(&update(200, &[("A", 500)]), None).unwrap();
+
+        // 別のイベントの価格表は、先に反映したイベントより古い改定でも反映できる
+        event_context::select("EV2", "イベント2").unwrap();
+        assert_eq!(snapshot().unwrap().version, 0);
+        apply(&update(100, &[("A", 600)]), None).unwrap();
+
+        event_context::select("EV1", "イベント1").unwrap();
+        assert_eq!(snapshot().unwrap().prices.get("A"), Some(&500));
+        assert!(apply(&update(150, &[("A", 400)]), None).is_err());
+    }
+
+    #[test]
+    fn test_apply_keeps_previous_book_when_journal_fails() {
+        let _dir = crate::storage::test_support::TempDataDir::new();
+        apply(&update(100, &[("A", 500)]), None).unwrap();
+
+        // ジャーナルのパスをディレクトリにして追記を失敗させる
+        let journal = storage::data_dir().unwrap().join(event_context::scoped("journal.jsonl").unwrap());
+        std::fs::remove_file(&journal).unwrap();
+        std::fs::create_dir_all(&journal).unwrap();
+
+        assert!(apply(&update(200, &[("A", 400)]), None).is_err());
+        let book = snapshot().unwrap();
+        assert_eq!((book.version, book.prices.get("A")), (100, Some(&500)));
+    }
+}
@@ -0,0 +1,186 @@
+//! 価格変更モジュール
+//!
+//! 汚損本の値引きなど、明細の価格を変えるには責任者の承認と理由コードを必要とする
+//! 承認した価格変更は売上とは別にジャーナルへ記録し、売上の印刷前にレシートの明細と照合する
+
+use crate::config;
+use crate::journal::{self, JournalEntry, JournalRecord};
+use crate::receipt::{PriceOverride, ReceiptData};
+use crate::staff_auth::{self, SupervisorApproval};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 価格変更の理由
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct PriceOverrideReason {
+    pub code: String,
+    /// レシートに印字する表示名
+    pub label: String,
+}
+
+/// 既定の理由一覧
+pub fn default_reasons() -> Vec<PriceOverrideReason> {
+    [("damaged", "汚損・破損"), ("sample", "見本誌"), ("other", "その他")]
+        .into_iter()
+        .map(|(code, label)| PriceOverrideReason {
+            code: code.to_string(),
+            label: label.to_string(),
+        })
+        .collect()
+}
+
+/// 価格変更の依頼（フロントエンドから渡される）
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct PriceOverrideRequest {
+    pub jan: String,
+    pub quantity: u32,
+    /// 変更前の値段（単価 x 数量）
+    pub original_price: u32,
+    /// 変更後の値段（単価 x 数量）
+    pub price: u32,
+    pub reason_code: String,
+}
+
+/// 責任者の承認を確認して価格変更をジャーナルに記録し、明細に付ける印を返す
+pub fn approve(
+    request: &PriceOverrideRequest,
+    staff_id: &str,
+    approval: &SupervisorApproval,
+) -> Result<PriceOverride, String> {
+    if request.price == request.original_price {
+        return Err("Price is unchanged".to_string());
+    }
+    let reason = config::load()?
+        .price_override_reasons
+        .into_iter()
+        .find(|r| r.code == request.reason_code)
+        .ok_or_else(|| format!("Unknown price override reason: {}", request.reason_code))?;
+    staff_auth::verify_supervisor(approval)?;
+
+    let entry = journal::append(
+        Some(staff_id),
+        JournalRecord::PriceOverride {
+            jan: request.jan.clone(),
+            quantity: request.quantity,
+            original_price: request.original_price,
+            price: request.price,
+            reason_code: reason.code.clone(),
+            supervisor_id: approval.supervisor_id.clone(),
+        },
+    )?;
+    Ok(PriceOverride {
+        override_id: entry.id,
+        original_price: request.original_price,
+        reason_code: reason.code,
+        reason: reason.label,
+        supervisor_id: approval.supervisor_id.clone(),
+    })
+}
+
+/// 価格変更の付いた明細が、承認済みの内容と一致するか確認
+///
+/// 1回の承認は1件の売上にだけ使える（同じレシート番号の再送と、内金の売上に続く残金の売上は除く）
+fn verify_against(entries: &[JournalEntry], receipt: &ReceiptData) -> Result<(), String> {
+    for item in &receipt.items {
+        let Some(ref mark) = item.price_override else {
+            continue;
+        };
+        let approved = entries.iter().any(|e| {
+            e.id == mark.override_id
+                && matches!(&e.record, JournalRecord::PriceOverride { jan, price, .. }
+                    if *jan == item.jan && *price == item.price)
+        });
+        if !approved {
+            return Err(format!("Price override is not approved: {}", item.jan));
+        }
+        // 残金の売上は内金の売上と同じ明細を載せるため、内金の売上での使用は数えない
+        let deposit_receipt_number = receipt
+            .deposit
+            .as_ref()
+            .and_then(|d| d.deposit_receipt_number.as_deref());
+        let used_elsewhere = entries.iter().any(|e| match &e.record {
+            JournalRecord::Sale { receipt: sale } => {
+                sale.receipt_number != receipt.receipt_number
+                    && Some(sale.receipt_number.as_str()) != deposit_receipt_number
+                    && sale.items.iter().any(|i| {
+                        i.price_override.as_ref().is_some_and(|o| o.override_id == mark.override_id)
+                    })
+            }
+            _ => false,
+        });
+        if used_elsewhere {
+            return Err(format!("Price override has already been used: {}", item.jan));
+        }
+    }
+    Ok(())
+}
+
+/// 売上の印刷前に価格変更を照合（価格変更のない売上はジャーナルを読まない）
+pub fn verify(receipt: &ReceiptData) -> Result<(), String> {
+    if receipt.items.iter().all(|i| i.price_override.is_none()) {
+        return Ok(());
+    }
+    verify_against(&journal::entries()?, receipt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::ApiEnvironment;
+    use crate::journal::SaleChannel;
+    use crate::receipt::test_support;
+    use serde_json::json;
+
+    fn entry(id: &str, record: JournalRecord) -> JournalEntry {
+        JournalEntry {
+            id: id.to_string(),
+            recorded_at: 0,
+            staff_id: Some("S001".to_string()),
+            channel: SaleChannel::Counter,
+            duress: false,
+            environment: ApiEnvironment::Production,
+            record,
+        }
+    }
+
+    fn receipt(receipt_number: &str, price: u32, override_id: &str) -> ReceiptData {
+        test_support::receipt(receipt_number)
+            .item(json!({
+                "price": price,
+                "price_override": {
+                    "override_id": override_id, "original_price": 1000, "reason_code": "damaged",
+                    "reason": "汚損・破損", "supervisor_id": "SV01"
+                }
+            }))
+            .build()
+    }
+
+    #[test]
+    fn test_verify_requires_matching_unused_approval() {
+        let approval = entry(
+            "OV-1",
+            JournalRecord::PriceOverride {
+                jan: "4900000000011".to_string(),
+                quantity: 1,
+                original_price: 1000,
+                price: 700,
+                reason_code: "damaged".to_string(),
+                supervisor_id: "SV01".to_string(),
+            },
+        );
+        let mut entries = vec![approval];
+        assert!(verify_against(&entries, &receipt("R-1", 700, "OV-1")).is_ok());
+        assert!(verify_against(&entries, &receipt("R-1", 500, "OV-1")).is_err());
+        assert!(verify_against(&entries, &receipt("R-1", 700, "OV-2")).is_err());
+
+        entries.push(entry(
+            "SALE-1",
+            JournalRecord::Sale {
+                receipt: Box::new(receipt("R-1", 700, "OV-1")),
+            },
+        ));
+        // 同じレシートの再送は通し、別の売上での使い回しは拒否する
+        assert!(verify_against(&entries, &receipt("R-1", 700, "OV-1")).is_ok());
+        assert!(verify_against(&entries, &receipt("R-2", 700, "OV-1")).is_err());
+    }
+}
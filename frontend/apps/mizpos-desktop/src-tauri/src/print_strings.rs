@@ -0,0 +1,419 @@
+//! 印字文言モジュール
+//!
+//! レシート・クレジット売上票・返品伝票・閉局レポートに印字する文言をリソースとして持つ
+//! 起動時とイベント選択時に読み込み、イベントごとの言い回しの調整や将来の翻訳をレイアウトのコードを変えずに行う
+//!
+//! 読み込み順: 既定値 ← 端末共通（print_strings.json） ← イベント別（events/{id}/print_strings.json）
+//! ファイルには変更したい項目だけを書けばよい
+//! 文言中の `{name}` は印字時に値で置き換える
+
+use crate::event_context;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::{Arc, RwLock};
+
+/// 文言ファイル名
+const PRINT_STRINGS_FILE: &str = "print_strings.json";
+
+/// レシートの文言
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct ReceiptStrings {
+    pub title: String,
+    /// 再発行の表示（{copy}: 何回目か）
+    pub reprint: String,
+    /// 再発行時、元の取引が取消済みの場合の表示
+    pub reprint_voided: String,
+    /// 再発行時、元の取引が全明細返品済みの場合の表示
+    pub reprint_refunded: String,
+    /// 再発行時、元の取引が一部返品済みの場合の表示
+    pub reprint_partially_refunded: String,
+    /// 再発行のレシートの末尾の表示（写しであることを明記する）
+    pub reprint_notice: String,
+    /// 宛名の表示（{name}: 宛名）
+    pub customer: String,
+    /// 責任者の表示（{staff}: スタッフ番号）
+    pub staff: String,
+    pub subtotal: String,
+    pub total: String,
+    /// 内税の表示（{rate}: 税率）
+    pub tax: String,
+    pub change: String,
+    pub points_earned: String,
+    pub points_balance: String,
+    /// ポイント数の表示（{points}: ポイント数）
+    pub points: String,
+    /// 価格変更した明細の表示（{reason}: 理由、{price}: 変更前の値段）
+    pub price_override: String,
+    /// 端数を値引した行
+    pub rounding_discount: String,
+    /// 端数を切り上げた行
+    pub rounding_surcharge: String,
+    /// 内金を受け取った売上の表示
+    pub deposit_banner: String,
+    /// 残金を受け取った売上（完了レシート）の表示
+    pub balance_completed: String,
+    pub order_total: String,
+    pub deposit: String,
+    /// 残金の売上に載せる受取済みの内金（{receipt_number}: 内金の売上のレシート番号）
+    pub deposit_paid: String,
+    pub balance: String,
+    pub balance_paid: String,
+    /// 残金のお支払期日（{date}: 期日）
+    pub balance_due: String,
+    /// 免税事業者・返品についての説明文
+    pub disclaimer: String,
+    /// ギフトレシート（金額非表示）の見出し
+    pub gift_title: String,
+    /// ギフトレシートの点数の行（{count}: 点数）
+    pub gift_quantity: String,
+    pub gift_total_quantity: String,
+    /// ギフトレシートの案内
+    pub gift_notice: String,
+    /// レシート・オン・デマンドの控えの案内（1行ずつ）
+    pub stub_notice: Vec<String>,
+    /// レシートの印刷に失敗した売上の控えの見出し
+    pub fallback_title: String,
+    /// レシートの印刷に失敗した売上の控えの案内（1行ずつ）
+    pub fallback_notice: Vec<String>,
+    /// 本番以外の接続先環境で印刷したものの表示（{environment}: 環境名）
+    pub test_environment: String,
+}
+
+impl Default for ReceiptStrings {
+    fn default() -> Self {
+        Self {
+            title: "ご明細書".to_string(),
+            reprint: "再発行(コピー) {copy}回目".to_string(),
+            reprint_voided: "この取引は取消済みです".to_string(),
+            reprint_refunded: "この取引は返品済みです".to_string(),
+            reprint_partially_refunded: "この取引は一部返品済みです".to_string(),
+            reprint_notice: "再発行(コピー) 原本ではありません".to_string(),
+            customer: "{name} 様".to_string(),
+            staff: "責: {staff}".to_string(),
+            subtotal: "小計".to_string(),
+            total: "合計(税込)".to_string(),
+            tax: "(内 {rate}%税)".to_string(),
+            change: "釣り銭".to_string(),
+            points_earned: "今回の獲得ポイント".to_string(),
+            points_balance: "ポイント残高".to_string(),
+            points: "{points}pt".to_string(),
+            price_override: "価格変更（{reason}） 元 {price}".to_string(),
+            rounding_discount: "端数値引".to_string(),
+            rounding_surcharge: "端数調整".to_string(),
+            deposit_banner: "内金お預り".to_string(),
+            balance_completed: "お支払完了".to_string(),
+            order_total: "ご注文合計".to_string(),
+            deposit: "内金(税込)".to_string(),
+            deposit_paid: "内金 #{receipt_number}".to_string(),
+            balance: "残金".to_string(),
+            balance_paid: "残金(税込)".to_string(),
+            balance_due: "残金のお支払期日: {date}".to_string(),
+            disclaimer: "当店は免税事業者であり、適格請求書を発行することはできません。返品・返金は落丁・乱丁の場合のみ受け付けます。返品・返金の場合は本明細書を添付しサポートセンター support-pos@miz.cabにご連絡ください。".to_string(),
+            gift_title: "ギフトレシート".to_string(),
+            gift_quantity: "{count}点".to_string(),
+            gift_total_quantity: "お買上点数".to_string(),
+            gift_notice: "金額は記載しておりません".to_string(),
+            stub_notice: vec![
+                "レシートが必要な方は".to_string(),
+                "この控えを".to_string(),
+                "スタッフにお見せください".to_string(),
+            ],
+            fallback_title: "お買上げ控え".to_string(),
+            fallback_notice: vec![
+                "レシートを印刷できませんでした".to_string(),
+                "この控えをスタッフにお見せいただくと".to_string(),
+                "レシートを再発行します".to_string(),
+            ],
+            test_environment: "*** テスト環境 ({environment}) ***".to_string(),
+        }
+    }
+}
+
+/// クレジット売上票の文言
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct CreditSlipStrings {
+    pub title: String,
+    pub customer_copy: String,
+    pub merchant_copy: String,
+    pub merchant_name: String,
+    pub terminal_number: String,
+    pub transaction_at: String,
+    pub slip_number: String,
+    pub member_number: String,
+    pub transaction_type: String,
+    pub sale: String,
+    pub refund: String,
+    pub payment_type: String,
+    /// 支払区分の既定値
+    pub lump_sum: String,
+    pub card_brand: String,
+    pub amount: String,
+    pub signature_heading: String,
+    pub signature_not_required: String,
+    pub acknowledgement: String,
+}
+
+impl Default for CreditSlipStrings {
+    fn default() -> Self {
+        Self {
+            title: "クレジット売上票".to_string(),
+            customer_copy: "お客様控".to_string(),
+            merchant_copy: "加盟店控".to_string(),
+            merchant_name: "加盟店名:".to_string(),
+            terminal_number: "端末番号:".to_string(),
+            transaction_at: "ご利用日時:".to_string(),
+            slip_number: "伝票番号:".to_string(),
+            member_number: "会員番号:".to_string(),
+            transaction_type: "取引内容:".to_string(),
+            sale: "売上".to_string(),
+            refund: "返品".to_string(),
+            payment_type: "支払区分:".to_string(),
+            lump_sum: "一括".to_string(),
+            card_brand: "カード会社:".to_string(),
+            amount: "ご利用金額:".to_string(),
+            signature_heading: "【お客様サイン】".to_string(),
+            signature_not_required: "＜IC取引につき不要＞".to_string(),
+            acknowledgement: "上記正に受領いたしました".to_string(),
+        }
+    }
+}
+
+/// 返品伝票の文言
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct RefundSlipStrings {
+    pub title: String,
+    /// 返品番号の表示（{id}: 返品記録のIDの先頭8文字）
+    pub refund_number: String,
+    /// 元の売上の表示（{receipt_number}: 元のレシート番号）
+    pub original_receipt: String,
+    pub total: String,
+    /// 内税の表示（{rate}: 税率）
+    pub tax: String,
+    /// 返品理由の表示（{reason}: 理由）
+    pub reason: String,
+    /// 返金の受領の署名欄
+    pub signature_heading: String,
+}
+
+impl Default for RefundSlipStrings {
+    fn default() -> Self {
+        Self {
+            title: "返品伝票".to_string(),
+            refund_number: "返品番号: {id}".to_string(),
+            original_receipt: "元レシート #{receipt_number}".to_string(),
+            total: "返金合計(税込)".to_string(),
+            tax: "(内 {rate}%税)".to_string(),
+            reason: "返品理由: {reason}".to_string(),
+            signature_heading: "【返金受領サイン】".to_string(),
+        }
+    }
+}
+
+/// 閉局レポートの文言
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct ClosingReportStrings {
+    pub title: String,
+    pub report_id: String,
+    pub terminal_id: String,
+    pub staff: String,
+    pub closed_at: String,
+    pub sales_summary: String,
+    pub transaction_count: String,
+    /// 件数の表示（{count}: 件数）
+    pub count: String,
+    pub sales_total_with_tax: String,
+    /// 返品の行（{count}: 件数）
+    pub refunds: String,
+    /// 取消の行（{count}: 件数）
+    pub voids: String,
+    /// 売上の端数調整額の合計の行
+    pub rounding: String,
+    pub payment_methods: String,
+    /// 支払方法の行（{method}: 支払手段名, {count}: 件数）
+    pub payment_method: String,
+    /// 支払方法ごとの返品・取消の行（{count}: 件数）
+    pub payment_method_refunds: String,
+    pub hourly_sales: String,
+    /// 時間帯の表示（{hour}: 時）
+    pub hour: String,
+    /// 時間帯ごとの件数と金額（{count}: 件数, {amount}: 金額）
+    pub hourly_value: String,
+    pub staff_totals: String,
+    /// 担当者ごとの売上の行（{count}: 件数）
+    pub staff_sales: String,
+    /// 担当者ごとの返品の行（{count}: 件数）
+    pub staff_refunds: String,
+    /// 担当者ごとの取消の行（{count}: 件数）
+    pub staff_voids: String,
+    pub staff_drawer: String,
+    /// 回数の表示（{count}: 回数）
+    pub times: String,
+    pub cash_breakdown: String,
+    pub cash_total: String,
+    pub vouchers: String,
+    pub voucher_total: String,
+    /// 売上に使われた商品券の行（{name}: 種別名, {count}: 件数）
+    pub voucher_sales: String,
+    pub settlement: String,
+    pub counted_total: String,
+    pub sales_total: String,
+    pub difference: String,
+    pub footer: String,
+}
+
+impl Default for ClosingReportStrings {
+    fn default() -> Self {
+        Self {
+            title: "閉局レポート".to_string(),
+            report_id: "レポートID:".to_string(),
+            terminal_id: "端末ID:".to_string(),
+            staff: "担当者:".to_string(),
+            closed_at: "閉局日時:".to_string(),
+            sales_summary: "【売上サマリー】".to_string(),
+            transaction_count: "取引件数:".to_string(),
+            count: "{count}件".to_string(),
+            sales_total_with_tax: "売上合計(税込):".to_string(),
+            refunds: "返品 {count}件:".to_string(),
+            voids: "取消 {count}件:".to_string(),
+            rounding: "うち端数調整:".to_string(),
+            payment_methods: "【支払方法別】".to_string(),
+            payment_method: "{method} {count}件".to_string(),
+            payment_method_refunds: "　返品・取消 {count}件".to_string(),
+            hourly_sales: "【時間帯別売上】".to_string(),
+            hour: "{hour}時".to_string(),
+            hourly_value: "{count}件 {amount}".to_string(),
+            staff_totals: "【担当者別】".to_string(),
+            staff_sales: "　売上 {count}件".to_string(),
+            staff_refunds: "　返品 {count}件".to_string(),
+            staff_voids: "　取消 {count}件".to_string(),
+            staff_drawer: "　ドロワー操作".to_string(),
+            times: "{count}回".to_string(),
+            cash_breakdown: "【現金内訳】".to_string(),
+            cash_total: "現金合計:".to_string(),
+            vouchers: "【商品券等】".to_string(),
+            voucher_total: "商品券等合計:".to_string(),
+            voucher_sales: "　売上 {name} {count}件".to_string(),
+            settlement: "【精算】".to_string(),
+            counted_total: "実査合計:".to_string(),
+            sales_total: "売上合計:".to_string(),
+            difference: "差異:".to_string(),
+            footer: "このレポートは閉局処理の記録です".to_string(),
+        }
+    }
+}
+
+/// 印字文言
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct PrintStrings {
+    pub receipt: ReceiptStrings,
+    pub credit_slip: CreditSlipStrings,
+    pub refund_slip: RefundSlipStrings,
+    pub closing_report: ClosingReportStrings,
+}
+
+/// 文言中の `{name}` を値で置き換える
+pub fn fill(template: &str, values: &[(&str, &dyn std::fmt::Display)]) -> String {
+    values.iter().fold(template.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+/// 読み込み済みの文言
+fn cache() -> &'static RwLock<Option<Arc<PrintStrings>>> {
+    static CACHE: RwLock<Option<Arc<PrintStrings>>> = RwLock::new(None);
+    &CACHE
+}
+
+/// 現在の文言（読み込み前は既定値）
+pub fn current() -> Arc<PrintStrings> {
+    cache()
+        .read()
+        .ok()
+        .and_then(|c| c.clone())
+        .unwrap_or_default()
+}
+
+/// 上書き分を重ねる（オブジェクトは項目ごと、それ以外は置き換え）
+fn merge(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                merge(base.entry(key).or_insert(serde_json::Value::Null), value);
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// 既定値に上書き分を順に重ねて文言を作る
+fn resolve(overlays: Vec<serde_json::Value>) -> Result<PrintStrings, String> {
+    let mut value = serde_json::to_value(PrintStrings::default()).map_err(|e| e.to_string())?;
+    for overlay in overlays {
+        merge(&mut value, overlay);
+    }
+    serde_json::from_value(value).map_err(|e| format!("Invalid print strings: {}", e))
+}
+
+/// 文言ファイルを読み込み直す（起動時とイベント選択時に呼ぶ）
+///
+/// ファイルが壊れている場合は既定値を使い、エラーを返す
+pub fn reload() -> Result<Arc<PrintStrings>, String> {
+    let overlays: Result<Vec<_>, String> =
+        [PRINT_STRINGS_FILE.to_string(), event_context::scoped(PRINT_STRINGS_FILE)?]
+            .iter()
+            .filter_map(|file| storage::load_json::<serde_json::Value>(file).transpose())
+            .collect();
+    let (strings, result) = match overlays.and_then(resolve) {
+        Ok(strings) => (Arc::new(strings), Ok(())),
+        Err(e) => (Arc::new(PrintStrings::default()), Err(e)),
+    };
+    if let Ok(mut cache) = cache().write() {
+        *cache = Some(strings.clone());
+    }
+    result.map(|_| strings)
+}
+
+/// 選択中のイベントの文言を保存して読み込み直す
+pub fn save_for_event(strings: &PrintStrings) -> Result<Arc<PrintStrings>, String> {
+    storage::save_json(&event_context::scoped(PRINT_STRINGS_FILE)?, strings)?;
+    reload()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_fill_replaces_named_values() {
+        assert_eq!(fill("再発行 ({copy}回目)", &[("copy", &2)]), "再発行 (2回目)");
+        assert_eq!(fill("(内 {rate}%税)", &[("rate", &10)]), "(内 10%税)");
+        assert_eq!(fill("合計(税込)", &[("rate", &10)]), "合計(税込)");
+    }
+
+    #[test]
+    fn test_resolve_layers_partial_overrides() {
+        let strings = resolve(vec![
+            json!({ "receipt": { "title": "お買上明細", "change": "おつり" } }),
+            json!({ "receipt": { "title": "Receipt" }, "closing_report": { "footer": "" } }),
+        ])
+        .unwrap();
+
+        assert_eq!(strings.receipt.title, "Receipt");
+        assert_eq!(strings.receipt.change, "おつり");
+        assert_eq!(strings.receipt.total, "合計(税込)");
+        assert_eq!(strings.closing_report.footer, "");
+        assert_eq!(strings.credit_slip, CreditSlipStrings::default());
+    }
+
+    #[test]
+    fn test_resolve_rejects_wrong_types() {
+        assert!(resolve(vec![json!({ "receipt": { "title": 1 } })]).is_err());
+    }
+}
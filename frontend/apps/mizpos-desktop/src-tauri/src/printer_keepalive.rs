@@ -0,0 +1,154 @@
+//! プリンターのキープアライブモジュール
+//!
+//! 一定時間使わないとインターフェースが省電力で切れるプリンターでは、しばらくぶりの最初の印刷が失敗する
+//! 開局中は設定した間隔でステータスを問い合わせ、接続を保つ
+//! 問い合わせに失敗し始めたら通知し、レジの前に接続を確認できるようにする
+
+use crate::config;
+use crate::session;
+use crate::spooler::{self, UsbPrinter};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::thread;
+use std::time::Duration;
+use tauri::Emitter;
+
+/// キープアライブに失敗したときに通知するイベント名
+pub const EVENT_PRINTER_KEEPALIVE_FAILED: &str = "printer-keepalive-failed";
+
+/// キープアライブ設定
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct PrinterKeepaliveConfig {
+    /// 問い合わせるプリンター（空の場合は行わない）
+    pub printers: Vec<UsbPrinter>,
+    /// 問い合わせ間隔（秒）
+    pub interval_secs: u64,
+}
+
+impl Default for PrinterKeepaliveConfig {
+    fn default() -> Self {
+        Self {
+            printers: vec![],
+            interval_secs: 240,
+        }
+    }
+}
+
+/// フロントエンドへ通知する内容
+#[derive(Debug, Clone, Serialize)]
+pub struct PrinterKeepaliveFailedEvent {
+    pub printer: UsbPrinter,
+    pub error: String,
+}
+
+/// バックグラウンドでキープアライブを行う
+pub fn start(app: tauri::AppHandle) {
+    thread::spawn(move || {
+        // 失敗を通知済みのプリンター（応答が戻るまで再通知しない）
+        let mut failing: Vec<UsbPrinter> = Vec::new();
+        loop {
+            let config = config::load().unwrap_or_default().printer_keepalive;
+            thread::sleep(Duration::from_secs(config.interval_secs.max(30)));
+            ping_all(&app, &config, &mut failing);
+        }
+    });
+}
+
+fn ping_all(app: &tauri::AppHandle, config: &PrinterKeepaliveConfig, failing: &mut Vec<UsbPrinter>) {
+    if !should_ping(config) {
+        return;
+    }
+
+    for printer in &config.printers {
+        let result = ping(printer);
+        if track_failure(failing, printer, result.is_ok()) {
+            if let Err(error) = result {
+                let _ = app.emit(
+                    EVENT_PRINTER_KEEPALIVE_FAILED,
+                    PrinterKeepaliveFailedEvent {
+                        printer: printer.clone(),
+                        error,
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// 問い合わせるか（開局中で、印刷待ちのジョブがない場合のみ）
+fn should_ping(config: &PrinterKeepaliveConfig) -> bool {
+    if config.printers.is_empty() || !session::current().is_ok_and(|s| s.open) {
+        return false;
+    }
+    // 印刷中はそれ自体が接続を保つため、スプーラーが空のときだけ問い合わせる
+    spooler::pending().is_empty()
+}
+
+/// 問い合わせ結果を記録し、新たに失敗し始めた場合はtrueを返す（応答が戻るまで再通知しない）
+fn track_failure(failing: &mut Vec<UsbPrinter>, printer: &UsbPrinter, ok: bool) -> bool {
+    if ok {
+        failing.retain(|p| p != printer);
+        return false;
+    }
+    if failing.contains(printer) {
+        return false;
+    }
+    failing.push(printer.clone());
+    true
+}
+
+#[cfg(not(target_os = "android"))]
+fn ping(printer: &UsbPrinter) -> Result<(), String> {
+    crate::desktop_printer::read_paper_status(printer).map(|_| ())
+}
+
+#[cfg(target_os = "android")]
+fn ping(_printer: &UsbPrinter) -> Result<(), String> {
+    Err("Bluetooth printing not yet implemented".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_support::TempDataDir;
+
+    fn printer(device_id: u16) -> UsbPrinter {
+        UsbPrinter {
+            vendor_id: 0x0416,
+            device_id,
+            paper_width: Some(58),
+        }
+    }
+
+    #[test]
+    fn test_pings_only_while_session_is_open() {
+        let _dir = TempDataDir::new();
+        let config = PrinterKeepaliveConfig {
+            printers: vec![printer(1)],
+            ..Default::default()
+        };
+        assert!(!should_ping(&config));
+
+        session::open("S001").unwrap();
+        assert!(should_ping(&config));
+        assert!(!should_ping(&PrinterKeepaliveConfig::default()));
+
+        session::close().unwrap();
+        assert!(!should_ping(&config));
+    }
+
+    #[test]
+    fn test_failure_is_notified_once_until_recovery() {
+        let mut failing = Vec::new();
+        assert!(!track_failure(&mut failing, &printer(1), true));
+        assert!(track_failure(&mut failing, &printer(1), false));
+        assert!(!track_failure(&mut failing, &printer(1), false));
+        // 別のプリンターの失敗は別に通知する
+        assert!(track_failure(&mut failing, &printer(2), false));
+
+        assert!(!track_failure(&mut failing, &printer(1), true));
+        assert_eq!(failing, vec![printer(2)]);
+        assert!(track_failure(&mut failing, &printer(1), false));
+    }
+}
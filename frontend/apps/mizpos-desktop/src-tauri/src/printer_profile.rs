@@ -0,0 +1,265 @@
+//! プリンタープロファイルモジュール
+//!
+//! 接続するプリンターごとの印刷方式を保持する
+//! 漢字ROMが想定どおりに動かない機種はラスター（画像）モードに切り替えて印刷する
+
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// プロファイルの保存ファイル名
+const PROFILES_FILE: &str = "printer_profiles.json";
+/// シチズン・システムズのUSBベンダーID
+const CITIZEN_VENDOR_ID: u16 = 0x1d90;
+
+/// 印刷方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderMode {
+    /// 漢字モードでテキストを送信（高速）
+    #[default]
+    Text,
+    /// レシート全体を画像として送信（低速だが機種差がない）
+    Raster,
+}
+
+/// QRコード印刷コマンド（GS ( k）の機能コード体系
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum QrCommandSet {
+    /// EPSON標準（fn = 65〜81 / 0x41〜0x51）
+    #[default]
+    Epson,
+    /// シチズン独自（fn = 0xA5〜0xB5、標準の機能コード + 0x64）
+    Citizen,
+}
+
+impl QrCommandSet {
+    /// EPSON標準の機能コードをこのコマンド体系の機能コードに変換
+    pub fn function_code(self, epson_fn: u8) -> u8 {
+        match self {
+            QrCommandSet::Epson => epson_fn,
+            QrCommandSet::Citizen => epson_fn + 0x64,
+        }
+    }
+
+    /// USBのベンダーIDから自動判別
+    pub fn detect(vendor_id: u16) -> Self {
+        match vendor_id {
+            CITIZEN_VENDOR_ID => QrCommandSet::Citizen,
+            _ => QrCommandSet::Epson,
+        }
+    }
+}
+
+/// 用紙の種類
+///
+/// ラベル紙・ブラックマーク紙はプリンター本体（メモリスイッチ）側でも用紙設定が必要
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaMode {
+    /// 連続紙（通常のレシートロール）
+    #[default]
+    Continuous,
+    /// ブラックマーク付き用紙
+    BlackMark,
+    /// ギャップ（台紙付きのダイカット）ラベル
+    Gap,
+}
+
+/// バーコードの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum BarcodeType {
+    Code128,
+    Ean13,
+    Ean8,
+    Code39,
+    Itf,
+}
+
+/// プリンターの機能
+///
+/// 未指定時は一般的なESC/POSレシートプリンターの機能とする
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct PrinterCapabilities {
+    /// QRコードを印刷できる
+    pub qr_code: bool,
+    /// 印刷できるバーコード
+    pub barcode_types: Vec<BarcodeType>,
+    /// オートカッターがある
+    pub cutter: bool,
+    /// パーシャルカットができる
+    pub partial_cut: bool,
+    /// キャッシュドロワーを接続できる
+    pub cash_drawer: bool,
+    /// 対応する最大の用紙幅（mm）
+    pub max_paper_width: u8,
+    /// 画像（ロゴなど）を印刷できる
+    pub raster: bool,
+}
+
+impl Default for PrinterCapabilities {
+    fn default() -> Self {
+        Self {
+            qr_code: true,
+            barcode_types: vec![BarcodeType::Code128, BarcodeType::Ean13, BarcodeType::Ean8],
+            cutter: true,
+            partial_cut: true,
+            cash_drawer: true,
+            max_paper_width: 80,
+            raster: true,
+        }
+    }
+}
+
+/// プリンタープロファイル
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PrinterProfile {
+    /// プリンターの識別子（USBは `usb:{VID}:{PID}`）
+    pub id: String,
+    /// 表示名
+    pub name: String,
+    #[serde(default)]
+    pub render_mode: RenderMode,
+    /// ラスターモードで使うフォントファイル（未指定時は同梱フォント）
+    #[serde(default)]
+    pub raster_font_path: Option<String>,
+    /// QRコードのコマンド体系（未指定時はベンダーIDから自動判別）
+    #[serde(default)]
+    pub qr_command_set: Option<QrCommandSet>,
+    /// 用紙の種類（ラベル紙と兼用するプリンター向け）
+    #[serde(default)]
+    pub media_mode: MediaMode,
+    /// 機能（未指定時は既定の機能）
+    #[serde(default)]
+    pub capabilities: Option<PrinterCapabilities>,
+}
+
+/// プロファイルと自動判別を反映したプリンターの機能
+///
+/// フロントエンドは対応していない操作（ロゴ登録など）を隠すために使う
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PrinterFeatures {
+    pub printer_id: String,
+    /// プロファイルの表示名（プロファイル未登録時はNone）
+    pub name: Option<String>,
+    pub render_mode: RenderMode,
+    pub qr_command_set: QrCommandSet,
+    pub media_mode: MediaMode,
+    #[serde(flatten)]
+    pub capabilities: PrinterCapabilities,
+}
+
+/// USBプリンターのプロファイルID
+pub fn usb_profile_id(vendor_id: u16, device_id: u16) -> String {
+    format!("usb:{:04x}:{:04x}", vendor_id, device_id)
+}
+
+/// USBプリンターのプロファイルIDからベンダーIDを取り出す
+fn usb_vendor_id(id: &str) -> Option<u16> {
+    let vendor = id.strip_prefix("usb:")?.split(':').next()?;
+    u16::from_str_radix(vendor, 16).ok()
+}
+
+/// プロファイルからプリンターの機能を決める
+fn resolve_features(printer_id: &str, profile: Option<PrinterProfile>) -> PrinterFeatures {
+    let detected_qr = usb_vendor_id(printer_id)
+        .map(QrCommandSet::detect)
+        .unwrap_or_default();
+
+    match profile {
+        Some(profile) => PrinterFeatures {
+            printer_id: printer_id.to_string(),
+            name: Some(profile.name),
+            render_mode: profile.render_mode,
+            qr_command_set: profile.qr_command_set.unwrap_or(detected_qr),
+            media_mode: profile.media_mode,
+            capabilities: profile.capabilities.unwrap_or_default(),
+        },
+        None => PrinterFeatures {
+            printer_id: printer_id.to_string(),
+            name: None,
+            render_mode: RenderMode::default(),
+            qr_command_set: detected_qr,
+            media_mode: MediaMode::default(),
+            capabilities: PrinterCapabilities::default(),
+        },
+    }
+}
+
+/// プリンターの機能を取得
+pub fn features(printer_id: &str) -> Result<PrinterFeatures, String> {
+    Ok(resolve_features(printer_id, find(printer_id)?))
+}
+
+/// プロファイル一覧
+pub fn list() -> Result<Vec<PrinterProfile>, String> {
+    Ok(storage::load_json(PROFILES_FILE)?.unwrap_or_default())
+}
+
+/// プロファイルを取得
+pub fn find(id: &str) -> Result<Option<PrinterProfile>, String> {
+    Ok(list()?.into_iter().find(|p| p.id == id))
+}
+
+/// プロファイルを保存（同じIDがあれば上書き）
+pub fn save(profile: PrinterProfile) -> Result<(), String> {
+    if profile.id.trim().is_empty() {
+        return Err("Printer profile ID is empty".to_string());
+    }
+
+    let mut profiles = list()?;
+    match profiles.iter_mut().find(|p| p.id == profile.id) {
+        Some(existing) => *existing = profile,
+        None => profiles.push(profile),
+    }
+    storage::save_json(PROFILES_FILE, &profiles)
+}
+
+/// プロファイルを削除
+pub fn delete(id: &str) -> Result<(), String> {
+    let mut profiles = list()?;
+    profiles.retain(|p| p.id != id);
+    storage::save_json(PROFILES_FILE, &profiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_features_without_profile_detects_qr_command_set() {
+        let features = resolve_features("usb:1d90:2168", None);
+        assert_eq!(features.qr_command_set, QrCommandSet::Citizen);
+        assert_eq!(features.capabilities, PrinterCapabilities::default());
+
+        let features = resolve_features("usb:04b8:0e15", None);
+        assert_eq!(features.qr_command_set, QrCommandSet::Epson);
+    }
+
+    #[test]
+    fn test_features_use_profile_capabilities() {
+        let profile = PrinterProfile {
+            id: "usb:0416:5011".to_string(),
+            name: "58mm mini".to_string(),
+            render_mode: RenderMode::Raster,
+            raster_font_path: None,
+            qr_command_set: None,
+            media_mode: MediaMode::Continuous,
+            capabilities: Some(PrinterCapabilities {
+                cutter: false,
+                partial_cut: false,
+                cash_drawer: false,
+                max_paper_width: 58,
+                ..PrinterCapabilities::default()
+            }),
+        };
+        let features = resolve_features("usb:0416:5011", Some(profile));
+        assert_eq!(features.name.as_deref(), Some("58mm mini"));
+        assert_eq!(features.render_mode, RenderMode::Raster);
+        assert!(!features.capabilities.cutter);
+        assert_eq!(features.capabilities.max_paper_width, 58);
+    }
+}
@@ -0,0 +1,162 @@
+//! プリンター状態モジュール
+//!
+//! ESC/POS のリアルタイムステータス（DLE EOT）・ステータス送信（GS r）の応答を解釈する
+//! 印刷前に用紙切れ・カバー開・エラーを確認し、レシートを出せない状態でお会計を進めないようにする
+//! モバイルプリンターでは電池残量・電波強度も合わせて返す（取得できる機種のみ）
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 用紙センサーの状態（DLE EOT 4 の応答）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct PaperStatus {
+    /// 用紙残りわずか（ニアエンド）
+    pub near_end: bool,
+    /// 用紙切れ
+    pub out: bool,
+}
+
+impl PaperStatus {
+    /// 応答バイトを解釈（bit2-3: ニアエンド、bit5-6: 用紙切れ）
+    pub fn from_status_byte(status: u8) -> Self {
+        Self {
+            near_end: status & 0x0c != 0,
+            out: status & 0x60 != 0,
+        }
+    }
+
+    /// GS r 1 の応答バイトを解釈（bit0-1: ニアエンド、bit2-3: 用紙切れ）
+    pub fn from_transmitted_byte(status: u8) -> Self {
+        Self {
+            near_end: status & 0x03 != 0,
+            out: status & 0x0c != 0,
+        }
+    }
+}
+
+/// オフラインの状態と原因（DLE EOT 1・DLE EOT 2 の応答）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct OfflineStatus {
+    /// オフライン（印刷を受け付けない）
+    pub offline: bool,
+    /// カバーが開いている
+    pub cover_open: bool,
+    /// 紙送りボタンで紙送り中
+    pub feeding: bool,
+    /// 用紙切れで印刷を止めている
+    pub paper_out_stop: bool,
+    /// エラーが起きている（原因は ErrorStatus）
+    pub error: bool,
+}
+
+impl OfflineStatus {
+    /// 応答バイトを解釈（DLE EOT 1 の bit3: オフライン、DLE EOT 2 の bit2: カバー開、bit3: 紙送り中、bit5: 用紙切れ停止、bit6: エラー）
+    pub fn from_status_bytes(printer: u8, offline: u8) -> Self {
+        Self {
+            offline: printer & 0x08 != 0,
+            cover_open: offline & 0x04 != 0,
+            feeding: offline & 0x08 != 0,
+            paper_out_stop: offline & 0x20 != 0,
+            error: offline & 0x40 != 0,
+        }
+    }
+}
+
+/// エラーの原因（DLE EOT 3 の応答）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ErrorStatus {
+    /// オートカッターのエラー（紙詰まりなど）
+    pub autocutter: bool,
+    /// 復帰不可能なエラー（電源の入れ直し・修理が必要）
+    pub unrecoverable: bool,
+    /// 自動復帰するエラー（ヘッドの温度上昇など）
+    pub auto_recoverable: bool,
+}
+
+impl ErrorStatus {
+    /// 応答バイトを解釈（bit3: オートカッター、bit5: 復帰不可能、bit6: 自動復帰）
+    pub fn from_status_byte(status: u8) -> Self {
+        Self {
+            autocutter: status & 0x08 != 0,
+            unrecoverable: status & 0x20 != 0,
+            auto_recoverable: status & 0x40 != 0,
+        }
+    }
+}
+
+/// プリンターの状態
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct PrinterStatus {
+    pub connected: bool,
+    /// 用紙センサーの状態（応答がない場合はNone）
+    pub paper: Option<PaperStatus>,
+    /// オフラインの状態と原因（応答がない場合はNone）
+    pub offline: Option<OfflineStatus>,
+    /// エラーの原因（応答がない場合はNone）
+    pub error: Option<ErrorStatus>,
+    /// 電池残量（%、モバイルプリンターで取得できる場合のみ）
+    pub battery_level: Option<u8>,
+    /// 電波強度（dBm、Bluetooth接続で測定できた場合のみ）
+    pub rssi: Option<i16>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paper_status_from_status_byte() {
+        // 固定ビット（bit1, bit4）のみ = 用紙あり
+        assert_eq!(PaperStatus::from_status_byte(0x12), PaperStatus::default());
+        assert_eq!(
+            PaperStatus::from_status_byte(0x1e),
+            PaperStatus { near_end: true, out: false }
+        );
+        assert_eq!(
+            PaperStatus::from_status_byte(0x7e),
+            PaperStatus { near_end: true, out: true }
+        );
+    }
+
+    #[test]
+    fn test_offline_and_error_status_from_status_bytes() {
+        // 固定ビット（bit1, bit4）のみ = オンライン・エラーなし
+        assert_eq!(OfflineStatus::from_status_bytes(0x12, 0x12), OfflineStatus::default());
+        assert_eq!(ErrorStatus::from_status_byte(0x12), ErrorStatus::default());
+
+        let cover_open = OfflineStatus::from_status_bytes(0x1a, 0x16);
+        assert!(cover_open.offline && cover_open.cover_open && !cover_open.error);
+        let cutter = ErrorStatus::from_status_byte(0x1a);
+        assert!(cutter.autocutter && !cutter.unrecoverable);
+
+        assert_eq!(
+            PaperStatus::from_transmitted_byte(0x0f),
+            PaperStatus { near_end: true, out: true }
+        );
+    }
+
+    #[test]
+    fn test_printer_status_from_android_bridge() {
+        // MizPosPrinter.getPrinterStatus の応答（取得できない値はnull）
+        let status: PrinterStatus = serde_json::from_str(
+            r#"{"success":true,"connected":true,"paper":{"near_end":true,"out":false},"battery_level":35,"rssi":-67}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            status,
+            PrinterStatus {
+                connected: true,
+                paper: Some(PaperStatus { near_end: true, out: false }),
+                battery_level: Some(35),
+                rssi: Some(-67),
+                ..Default::default()
+            }
+        );
+
+        let status: PrinterStatus = serde_json::from_str(
+            r#"{"success":true,"connected":false,"paper":null,"battery_level":null,"rssi":null}"#,
+        )
+        .unwrap();
+        assert_eq!(status, PrinterStatus::default());
+    }
+}
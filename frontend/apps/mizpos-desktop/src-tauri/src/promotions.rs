@@ -0,0 +1,440 @@
+//! キャンペーンモジュール
+//!
+//! 端末設定のキャンペーン（まとめ買いの無料、セット割引、期間限定の特価）を会計の明細に当てはめて割引額を計算する
+//! 会計時にフロントエンドが明細ごとの割引額と割引後の合計を受け取り、レシートに入れたキャンペーンを印刷時に計算し直して照合する
+//! 適用したキャンペーンはレシートと一緒にジャーナルの売上に記録される
+
+use crate::config;
+use crate::datetime;
+use crate::receipt::{ReceiptData, ReceiptItem};
+use chrono::Utc;
+use chrono_tz::Tz;
+pub use mizpos_core::receipt::{AppliedPromotion, PromotionLineDiscount};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// キャンペーンの割引方法
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PromotionRule {
+    /// 対象商品を buy 点買うと、さらに free 点が無料（組の中で安いものから無料にする）
+    BuyGetFree { jans: Vec<String>, buy: u32, free: u32 },
+    /// 対象商品を1点ずつそろえるとセット価格
+    SetPrice { jans: Vec<String>, price: u32 },
+    /// 対象商品の単価を特価にする
+    PriceOverride { jans: Vec<String>, price: u32 },
+}
+
+/// キャンペーン
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct Promotion {
+    pub id: String,
+    /// レシートに印字する名前
+    pub name: String,
+    #[serde(flatten)]
+    pub rule: PromotionRule,
+    /// 開始日時（ISO8601、未指定の場合は制限なし）
+    #[serde(default)]
+    pub starts_at: Option<String>,
+    /// 終了日時（ISO8601、この日時を含まない）
+    #[serde(default)]
+    pub ends_at: Option<String>,
+}
+
+/// 会計の見積もり（フロントエンドの会計画面用）
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PromotionQuote {
+    pub promotions: Vec<AppliedPromotion>,
+    /// 割引前の小計
+    pub subtotal: u32,
+    /// 割引額の合計
+    pub discount: u32,
+    /// 割引後の合計
+    pub total: u32,
+}
+
+/// 明細のうち同じ単価の商品をまとめたもの
+///
+/// 割り切れない端数を寄せた先頭の1点と残りに分けるため、1明細は最大2つになる
+struct Lot<'a> {
+    /// 明細の番号
+    line: usize,
+    jan: &'a str,
+    /// 1点あたりの価格
+    price: u32,
+    /// セット・まとめ買いの組に使っていない点数
+    count: u32,
+}
+
+impl Promotion {
+    /// 指定日時（UNIX秒）に有効か（読み取れない日時がある場合は無効）
+    fn is_active(&self, now: i64, tz: Tz) -> bool {
+        let within = |value: &Option<String>, check: fn(i64, i64) -> bool| match value {
+            Some(v) => datetime::parse_iso8601(v, tz).is_ok_and(|dt| check(now, dt.timestamp())),
+            None => true,
+        };
+        within(&self.starts_at, |now, start| now >= start) && within(&self.ends_at, |now, end| now < end)
+    }
+}
+
+/// 明細ごとの割引額を集計する
+struct LineDiscounts(Vec<u64>);
+
+impl LineDiscounts {
+    fn add(&mut self, line: usize, amount: u64) -> Result<(), String> {
+        self.0[line] = self.0[line]
+            .checked_add(amount)
+            .ok_or_else(|| "Promotion discount is too large".to_string())?;
+        Ok(())
+    }
+
+    /// セットの割引額を、セットに入れた明細へ先頭から順に各明細の金額まで割り当てる
+    fn add_set(&mut self, parts: &[(usize, u64)], discount: u64, sets: u64) -> Result<(), String> {
+        let mut rest = discount;
+        for &(line, amount) in parts {
+            let share = rest.min(amount);
+            rest -= share;
+            self.add(line, share.checked_mul(sets).ok_or_else(|| "Promotion discount is too large".to_string())?)?;
+        }
+        Ok(())
+    }
+
+    fn into_applied(self, promotion: &Promotion) -> Result<Option<AppliedPromotion>, String> {
+        let mut discount: u32 = 0;
+        let mut lines = Vec::new();
+        for (item_index, amount) in self.0.into_iter().enumerate().filter(|(_, a)| *a > 0) {
+            let amount = u32::try_from(amount).map_err(|_| "Promotion discount is too large".to_string())?;
+            discount = discount
+                .checked_add(amount)
+                .ok_or_else(|| "Promotion discount is too large".to_string())?;
+            lines.push(PromotionLineDiscount { item_index, discount: amount });
+        }
+        Ok((discount > 0).then(|| AppliedPromotion {
+            promotion_id: promotion.id.clone(),
+            name: promotion.name.clone(),
+            discount,
+            lines,
+        }))
+    }
+}
+
+/// 明細にキャンペーンを当てはめる
+///
+/// 特価を先に反映してから、セット価格・まとめ買いの順に当てはめる
+/// セット・まとめ買いの組に使った商品は、他のセット・まとめ買いには使わない
+/// 点数は信頼できない入力のため、1点ずつには分けず、同じ単価の点数から組の数を計算する
+pub fn evaluate(
+    promotions: &[Promotion],
+    items: &[ReceiptItem],
+    now: i64,
+    tz: Tz,
+) -> Result<Vec<AppliedPromotion>, String> {
+    let mut lots: Vec<Lot> = Vec::new();
+    for (line, item) in items.iter().enumerate() {
+        let quantity = item.quantity.max(1);
+        let unit_price = item.price / quantity;
+        // 割り切れない端数は先頭の1点に寄せる
+        let remainder = item.price - unit_price * quantity;
+        lots.push(Lot { line, jan: &item.jan, price: unit_price + remainder, count: 1 });
+        if quantity > 1 {
+            lots.push(Lot { line, jan: &item.jan, price: unit_price, count: quantity - 1 });
+        }
+    }
+
+    let active: Vec<&Promotion> = promotions.iter().filter(|p| p.is_active(now, tz)).collect();
+    let mut applied: Vec<AppliedPromotion> = Vec::new();
+    let new_discounts = || LineDiscounts(vec![0; items.len()]);
+
+    for promotion in &active {
+        if let PromotionRule::PriceOverride { jans, price } = &promotion.rule {
+            let mut discounts = new_discounts();
+            for lot in lots.iter_mut().filter(|l| jans.iter().any(|j| j == l.jan)) {
+                if lot.price > *price {
+                    discounts.add(lot.line, (lot.price - price) as u64 * lot.count as u64)?;
+                    lot.price = *price;
+                }
+            }
+            applied.extend(discounts.into_applied(promotion)?);
+        }
+    }
+
+    for promotion in &active {
+        if let PromotionRule::SetPrice { jans, price } = &promotion.rule {
+            if jans.is_empty() {
+                continue;
+            }
+            let mut discounts = new_discounts();
+            apply_set_price(&mut lots, jans, *price as u64, &mut discounts)?;
+            applied.extend(discounts.into_applied(promotion)?);
+        }
+    }
+
+    for promotion in &active {
+        if let PromotionRule::BuyGetFree { jans, buy, free } = &promotion.rule {
+            let Some(group_size) = buy.checked_add(*free) else {
+                continue;
+            };
+            if *free == 0 || group_size == 0 {
+                continue;
+            }
+            let mut discounts = new_discounts();
+            apply_buy_get_free(&mut lots, jans, *buy as u64, *free as u64, &mut discounts)?;
+            applied.extend(discounts.into_applied(promotion)?);
+        }
+    }
+
+    Ok(applied)
+}
+
+/// セット価格を当てはめる
+///
+/// 各セットには対象商品ごとに、組に使っていない商品を明細の順に入れる
+/// 全対象商品の先頭の明細に点数が残っている間は同じ価格のセットになるため、まとめて計算する
+fn apply_set_price(lots: &mut [Lot], jans: &[String], price: u64, discounts: &mut LineDiscounts) -> Result<(), String> {
+    // 同じ商品が複数指定されている場合は、1セットにその点数を入れる
+    let mut required: Vec<(&str, u32)> = Vec::new();
+    for jan in jans {
+        match required.iter_mut().find(|(j, _)| j == jan) {
+            Some((_, n)) => *n += 1,
+            None => required.push((jan, 1)),
+        }
+    }
+    let available = |lots: &[Lot], jan: &str| -> u64 {
+        lots.iter().filter(|l| l.jan == jan).map(|l| l.count as u64).sum()
+    };
+    let mut sets = required
+        .iter()
+        .map(|(jan, n)| available(lots, jan) / *n as u64)
+        .min()
+        .unwrap_or(0);
+
+    while sets > 0 {
+        let fronts: Vec<usize> = required
+            .iter()
+            .map(|(jan, _)| lots.iter().position(|l| l.jan == *jan && l.count > 0))
+            .collect::<Option<_>>()
+            .ok_or_else(|| "Set price ran out of items".to_string())?;
+        let batch = required
+            .iter()
+            .zip(&fronts)
+            .map(|((_, n), &i)| lots[i].count as u64 / *n as u64)
+            .min()
+            .unwrap_or(0)
+            .min(sets);
+
+        if batch > 0 {
+            let parts: Vec<(usize, u64)> = required
+                .iter()
+                .zip(&fronts)
+                .map(|((_, n), &i)| (lots[i].line, lots[i].price as u64 * *n as u64))
+                .collect();
+            let regular: u64 = parts.iter().map(|(_, amount)| amount).sum();
+            discounts.add_set(&parts, regular.saturating_sub(price), batch)?;
+            for ((_, n), &i) in required.iter().zip(&fronts) {
+                lots[i].count -= (batch * *n as u64) as u32;
+            }
+            sets -= batch;
+        } else {
+            // 先頭の明細の残りが足りない商品は、次の明細にまたがって1セットずつ作る
+            let mut parts: Vec<(usize, u64)> = Vec::new();
+            for (jan, n) in &required {
+                for _ in 0..*n {
+                    let i = lots
+                        .iter()
+                        .position(|l| l.jan == *jan && l.count > 0)
+                        .ok_or_else(|| "Set price ran out of items".to_string())?;
+                    lots[i].count -= 1;
+                    parts.push((lots[i].line, lots[i].price as u64));
+                }
+            }
+            let regular: u64 = parts.iter().map(|(_, amount)| amount).sum();
+            discounts.add_set(&parts, regular.saturating_sub(price), 1)?;
+            sets -= 1;
+        }
+    }
+    Ok(())
+}
+
+/// まとめ買いの無料を当てはめる
+///
+/// 対象商品を高い順に並べて buy + free 点ずつの組にし、各組の末尾（安いもの）free 点を無料にする
+/// 並べた位置から、各明細の商品が組のどこに入るかを計算する
+fn apply_buy_get_free(
+    lots: &mut [Lot],
+    jans: &[String],
+    buy: u64,
+    free: u64,
+    discounts: &mut LineDiscounts,
+) -> Result<(), String> {
+    let group_size = buy + free;
+    let mut eligible: Vec<usize> = (0..lots.len())
+        .filter(|&i| lots[i].count > 0 && jans.iter().any(|j| j == lots[i].jan))
+        .collect();
+    eligible.sort_by(|&a, &b| lots[b].price.cmp(&lots[a].price));
+
+    let total: u64 = eligible.iter().map(|&i| lots[i].count as u64).sum();
+    let grouped = total / group_size * group_size;
+    // 先頭から x 点のうち無料になる点数
+    let free_before = |x: u64| (x / group_size) * free + (x % group_size).saturating_sub(buy);
+
+    let mut start: u64 = 0;
+    for i in eligible {
+        let end = start + lots[i].count as u64;
+        let (from, to) = (start.min(grouped), end.min(grouped));
+        let free_count = free_before(to) - free_before(from);
+        let amount = free_count
+            .checked_mul(lots[i].price as u64)
+            .ok_or_else(|| "Promotion discount is too large".to_string())?;
+        discounts.add(lots[i].line, amount)?;
+        lots[i].count -= (to - from) as u32;
+        start = end;
+    }
+    Ok(())
+}
+
+/// 端末設定のキャンペーンで会計を見積もる
+pub fn quote(items: &[ReceiptItem]) -> Result<PromotionQuote, String> {
+    let config = config::load()?;
+    let tz = datetime::timezone(&config.timezone);
+    let promotions = evaluate(&config.promotions, items, Utc::now().timestamp(), tz)?;
+    let subtotal = items
+        .iter()
+        .try_fold(0u32, |sum, i| sum.checked_add(i.price))
+        .ok_or_else(|| "Subtotal is too large".to_string())?;
+    let discount: u32 = promotions.iter().map(|p| p.discount).sum();
+    Ok(PromotionQuote {
+        promotions,
+        subtotal,
+        discount,
+        total: subtotal.saturating_sub(discount),
+    })
+}
+
+/// 印刷前にレシートのキャンペーンを確認する
+///
+/// フロントエンドは見積もりの結果をそのままレシートに入れる（割引前の金額で会計した場合は入れない）
+/// 入れられたキャンペーンは明細ごとの割引額まで端末設定で計算し直した結果と一致しなければならない
+pub fn apply(receipt: &mut ReceiptData) -> Result<(), String> {
+    if receipt.promotions.is_empty() {
+        return Ok(());
+    }
+    let quote = quote(&receipt.items)?;
+    if quote.promotions != receipt.promotions {
+        return Err("Applied promotions do not match the current promotions for these items".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipt::test_support;
+    use serde_json::json;
+
+    fn item(jan: &str, quantity: u32, price: u32) -> ReceiptItem {
+        test_support::item(json!({ "name": "商品", "jan": jan, "is_book": false, "quantity": quantity, "price": price }))
+    }
+
+    fn promotion(id: &str, rule: serde_json::Value) -> Promotion {
+        let mut value = json!({ "id": id, "name": id });
+        value.as_object_mut().unwrap().extend(rule.as_object().unwrap().clone());
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn discounts(applied: &[AppliedPromotion]) -> Vec<(&str, u32)> {
+        applied.iter().map(|p| (p.promotion_id.as_str(), p.discount)).collect()
+    }
+
+    fn lines(applied: &AppliedPromotion) -> Vec<(usize, u32)> {
+        applied.lines.iter().map(|l| (l.item_index, l.discount)).collect()
+    }
+
+    #[test]
+    fn test_evaluate_applies_overrides_then_sets_then_buy_get_free() {
+        let promotions = vec![
+            promotion("b2g1", json!({ "type": "buy_get_free", "jans": ["A", "B"], "buy": 2, "free": 1 })),
+            promotion("set", json!({ "type": "set_price", "jans": ["C", "D"], "price": 1000 })),
+            promotion("sale", json!({ "type": "price_override", "jans": ["C"], "price": 500 })),
+        ];
+        let items = vec![item("A", 3, 1500), item("B", 1, 300), item("C", 2, 1400), item("D", 1, 800)];
+        let applied = evaluate(&promotions, &items, 0, chrono_tz::Asia::Tokyo).unwrap();
+        // 特価: C 700→500 x2、セット: C+D 1300→1000、まとめ買い: A,A,A,B の最初の組で A が1点無料
+        assert_eq!(discounts(&applied), vec![("sale", 400), ("set", 300), ("b2g1", 500)]);
+        // セットの割引は先頭の明細（C）から割り当てる
+        assert_eq!(lines(&applied[1]), vec![(2, 300)]);
+        assert_eq!(lines(&applied[2]), vec![(0, 500)]);
+    }
+
+    #[test]
+    fn test_evaluate_attributes_discounts_to_lines() {
+        let promotions = vec![
+            promotion("b1g1", json!({ "type": "buy_get_free", "jans": ["A", "B"], "buy": 1, "free": 1 })),
+            promotion("set", json!({ "type": "set_price", "jans": ["C", "C", "D"], "price": 100 })),
+        ];
+        // A は 1001 円を3点（先頭の1点が 335 円）、B は 300 円を2点
+        let items = vec![item("A", 3, 1001), item("B", 2, 600), item("C", 3, 300), item("D", 2, 400)];
+        let applied = evaluate(&promotions, &items, 0, chrono_tz::Asia::Tokyo).unwrap();
+        // セット: C+C+D 400→100 が1組（C の残り1点ではもう1組作れない）
+        assert_eq!(lines(&applied[0]), vec![(2, 200), (3, 100)]);
+        // まとめ買い: 335,333,333,300,300 の組で 333 と 300 が無料、最後の1点は組にならない
+        assert_eq!(lines(&applied[1]), vec![(0, 333), (1, 300)]);
+        assert_eq!(discounts(&applied), vec![("set", 300), ("b1g1", 633)]);
+    }
+
+    #[test]
+    fn test_evaluate_large_quantities_without_expanding_units() {
+        let promotions = vec![
+            promotion("b2g1", json!({ "type": "buy_get_free", "jans": ["A"], "buy": 2, "free": 1 })),
+            promotion("set", json!({ "type": "set_price", "jans": ["B", "C"], "price": 1 })),
+            promotion("overflow", json!({ "type": "buy_get_free", "jans": ["A"], "buy": u32::MAX, "free": 1 })),
+        ];
+        let items = vec![item("A", u32::MAX, u32::MAX), item("B", u32::MAX, u32::MAX), item("C", 1_000_000, 2_000_000)];
+        let applied = evaluate(&promotions, &items, 0, chrono_tz::Asia::Tokyo).unwrap();
+        // A は1点1円で u32::MAX 点（3の倍数）のうち3分の1が無料、buy + free が溢れるキャンペーンは当てはめない
+        assert_eq!(discounts(&applied), vec![("set", 2_000_000), ("b2g1", u32::MAX / 3)]);
+    }
+
+    #[test]
+    fn test_apply_requires_matching_promotions() {
+        let _dir = crate::storage::test_support::TempDataDir::new();
+        let mut config = config::load().unwrap();
+        config.promotions = vec![promotion("sale", json!({ "type": "price_override", "jans": ["A"], "price": 500 }))];
+        config::save(&config).unwrap();
+
+        let mut receipt = test_support::receipt("R1")
+            .item(json!({ "name": "商品", "jan": "A", "is_book": false, "quantity": 2, "price": 2000 }))
+            .with(json!({ "payments": [] }))
+            .build();
+
+        // 割引前の金額で会計した場合はキャンペーンを入れない
+        apply(&mut receipt).unwrap();
+        assert!(receipt.promotions.is_empty());
+
+        let quote = quote(&receipt.items).unwrap();
+        receipt.promotions = quote.promotions.clone();
+        apply(&mut receipt).unwrap();
+
+        receipt.promotions[0].lines[0].discount -= 1;
+        assert!(apply(&mut receipt).is_err());
+        receipt.promotions = quote.promotions;
+        receipt.items[0].quantity = 1;
+        assert!(apply(&mut receipt).is_err());
+    }
+
+    #[test]
+    fn test_evaluate_skips_promotions_outside_their_period() {
+        let mut sale = promotion("sale", json!({ "type": "price_override", "jans": ["A"], "price": 500 }));
+        sale.starts_at = Some("2025-12-30T10:00:00".to_string());
+        sale.ends_at = Some("2025-12-30T12:00:00".to_string());
+        let items = vec![item("A", 1, 1000)];
+        let tz = chrono_tz::Asia::Tokyo;
+        let at = |s: &str| datetime::parse_iso8601(s, tz).unwrap().timestamp();
+
+        assert!(evaluate(&[sale.clone()], &items, at("2025-12-30T09:59:59"), tz).unwrap().is_empty());
+        assert_eq!(
+            discounts(&evaluate(&[sale.clone()], &items, at("2025-12-30T10:00:00"), tz).unwrap()),
+            vec![("sale", 500)]
+        );
+        assert!(evaluate(&[sale], &items, at("2025-12-30T12:00:00"), tz).unwrap().is_empty());
+    }
+}
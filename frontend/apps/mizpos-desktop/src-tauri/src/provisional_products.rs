@@ -0,0 +1,138 @@
+//! 仮登録商品モジュール
+//!
+//! 商品データにないコードをスキャンした場合に、その場で値段を入力して仮の商品として登録する
+//! 仮登録した商品は後で確認するよう印を付けてイベントごとに保存し、アップロードキューで本部へ送る
+//! （本部で正式な商品データに取り込まれるまで、フロントエンドのローカルの商品キャッシュで使う）
+
+use crate::book_code;
+use crate::event_context;
+use crate::storage;
+use crate::upload_queue;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 仮登録商品の保存ファイル名
+const PROVISIONAL_PRODUCTS_FILE: &str = "provisional_products.json";
+
+/// 仮登録した商品
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ProvisionalProduct {
+    /// 商品ID（provisional-{JAN}）
+    pub id: String,
+    /// スキャンしたコード（ISBNは13桁に揃える）
+    pub jan: String,
+    pub is_book: bool,
+    pub name: String,
+    pub circle_name: Option<String>,
+    /// 入力した値段（税込）
+    pub price: u32,
+    /// 登録したスタッフ番号
+    pub created_by: String,
+    /// 登録日時（UNIX秒）
+    pub created_at: u64,
+    /// 確認済みか（未確認のものは要確認として一覧に出す）
+    pub reviewed: bool,
+}
+
+fn load() -> Result<Vec<ProvisionalProduct>, String> {
+    Ok(storage::load_json(&event_context::scoped(PROVISIONAL_PRODUCTS_FILE)?)?.unwrap_or_default())
+}
+
+fn save(products: &[ProvisionalProduct]) -> Result<(), String> {
+    storage::save_json(&event_context::scoped(PROVISIONAL_PRODUCTS_FILE)?, &products)
+}
+
+/// スキャンしたコードと入力内容から仮登録商品を作る
+fn build(
+    code: &str,
+    name: &str,
+    price: u32,
+    circle_name: Option<String>,
+    staff_id: &str,
+) -> Result<ProvisionalProduct, String> {
+    let code = code.trim();
+    let jan = if book_code::is_valid_jan(code) {
+        code.to_string()
+    } else {
+        book_code::normalize_isbn(code).map_err(|_| format!("Invalid JAN: {}", code))?
+    };
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Name is empty".to_string());
+    }
+    if price == 0 {
+        return Err("Price must be greater than zero".to_string());
+    }
+
+    Ok(ProvisionalProduct {
+        id: format!("provisional-{}", jan),
+        is_book: jan.starts_with("978") || jan.starts_with("979"),
+        jan,
+        name: name.to_string(),
+        circle_name: circle_name.filter(|c| !c.trim().is_empty()),
+        price,
+        created_by: staff_id.to_string(),
+        created_at: storage::now_unix(),
+        reviewed: false,
+    })
+}
+
+/// 未登録のコードを仮登録し、本部への送信をアップロードキューに積む
+pub fn create(
+    code: &str,
+    name: &str,
+    price: u32,
+    circle_name: Option<String>,
+    staff_id: &str,
+) -> Result<ProvisionalProduct, String> {
+    let product = build(code, name, price, circle_name, staff_id)?;
+    let mut products = load()?;
+    if products.iter().any(|p| p.jan == product.jan) {
+        return Err(format!("Product is already registered: {}", product.jan));
+    }
+    products.push(product.clone());
+    save(&products)?;
+
+    upload_queue::enqueue(upload_queue::KIND_PROVISIONAL_PRODUCT, &product.jan, &product)?;
+    Ok(product)
+}
+
+/// 仮登録した商品の一覧（登録順）
+pub fn list() -> Result<Vec<ProvisionalProduct>, String> {
+    load()
+}
+
+/// 仮登録した商品を確認済みにする
+pub fn mark_reviewed(jan: &str) -> Result<ProvisionalProduct, String> {
+    let mut products = load()?;
+    let product = products
+        .iter_mut()
+        .find(|p| p.jan == jan)
+        .ok_or_else(|| format!("Provisional product not found: {}", jan))?;
+    product.reviewed = true;
+    let product = product.clone();
+    save(&products)?;
+    Ok(product)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_validates_scanned_code_and_price() {
+        let product = build("4901234567894", " 缶バッジ ", 300, Some(String::new()), "S01").unwrap();
+        assert_eq!(product.id, "provisional-4901234567894");
+        assert_eq!(product.name, "缶バッジ");
+        assert_eq!(product.circle_name, None);
+        assert!(!product.is_book && !product.reviewed);
+
+        let book = build("4-10-109205-2", "既刊", 500, None, "S01").unwrap();
+        assert_eq!(book.jan, "9784101092058");
+        assert!(book.is_book);
+
+        assert!(build("4901234567890", "缶バッジ", 300, None, "S01").is_err());
+        assert!(build("4901234567894", "", 300, None, "S01").is_err());
+        assert!(build("4901234567894", "缶バッジ", 0, None, "S01").is_err());
+    }
+}
@@ -0,0 +1,97 @@
+//! 見積・予約票モジュール
+//!
+//! 売上にならない見積（予約）を保存し、QRコードから後で売上に変換できるようにする
+
+use crate::event_context;
+use crate::receipt::ReceiptItem;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 見積の保存ファイル名
+const QUOTES_FILE: &str = "quotes.json";
+/// QRコードの接頭辞
+const QUOTE_QR_PREFIX: &str = "MZQ:";
+
+/// 見積
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Quote {
+    /// 見積番号（Q0001形式）
+    pub code: String,
+    pub items: Vec<ReceiptItem>,
+    pub total: u32,
+    /// 有効期限（表示用文字列）
+    pub valid_until: String,
+    pub staff_id: String,
+    /// 宛名
+    pub customer_name: Option<String>,
+    /// 作成日時（UNIX秒）
+    pub created_at: u64,
+    /// 売上に変換済みの場合のレシート番号
+    pub converted_receipt_number: Option<String>,
+}
+
+impl Quote {
+    /// QRコードに埋め込む文字列
+    pub fn qr_data(&self) -> String {
+        format!("{}{}", QUOTE_QR_PREFIX, self.code)
+    }
+}
+
+fn load_quotes() -> Result<Vec<Quote>, String> {
+    Ok(storage::load_json(&event_context::scoped(QUOTES_FILE)?)?.unwrap_or_default())
+}
+
+fn save_quotes(quotes: &[Quote]) -> Result<(), String> {
+    storage::save_json(&event_context::scoped(QUOTES_FILE)?, &quotes)
+}
+
+/// 見積を作成
+pub fn create(
+    items: Vec<ReceiptItem>,
+    valid_until: &str,
+    staff_id: &str,
+    customer_name: Option<String>,
+) -> Result<Quote, String> {
+    if items.is_empty() {
+        return Err("Quote has no items".to_string());
+    }
+
+    let quote = Quote {
+        code: event_context::next_quote_code()?,
+        total: items.iter().map(|i| i.price).sum(),
+        items,
+        valid_until: valid_until.to_string(),
+        staff_id: staff_id.to_string(),
+        customer_name,
+        created_at: storage::now_unix(),
+        converted_receipt_number: None,
+    };
+
+    let mut quotes = load_quotes()?;
+    quotes.push(quote.clone());
+    save_quotes(&quotes)?;
+    Ok(quote)
+}
+
+/// 見積番号またはQRコードの文字列から見積を取得
+pub fn find(code_or_qr: &str) -> Result<Option<Quote>, String> {
+    let code = code_or_qr.strip_prefix(QUOTE_QR_PREFIX).unwrap_or(code_or_qr);
+    Ok(load_quotes()?.into_iter().find(|q| q.code == code))
+}
+
+/// 見積を売上に変換済みとして記録
+pub fn mark_converted(code: &str, receipt_number: &str) -> Result<Quote, String> {
+    let mut quotes = load_quotes()?;
+    let quote = quotes
+        .iter_mut()
+        .find(|q| q.code == code)
+        .ok_or_else(|| format!("Quote not found: {}", code))?;
+    if let Some(ref existing) = quote.converted_receipt_number {
+        return Err(format!("Quote already converted: {}", existing));
+    }
+    quote.converted_receipt_number = Some(receipt_number.to_string());
+    let updated = quote.clone();
+    save_quotes(&quotes)?;
+    Ok(updated)
+}
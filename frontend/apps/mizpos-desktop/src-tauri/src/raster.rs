@@ -0,0 +1,483 @@
+//! ラスター描画モジュール
+//!
+//! レシート全体をビットマップとして組み立て、GS v 0 で印刷するための描画処理
+//! 漢字ROMの挙動がプリンターごとに異なっても同じ見た目で印刷できる（速度は遅くなる）
+
+use crate::jp_escpos::{char_width, str_width, Align, TextStyle};
+use fontdue::{Font, FontSettings};
+
+/// 半角1文字の幅（ドット）
+pub const CELL_WIDTH: usize = 12;
+/// 1行の文字高さ（ドット）
+pub const CELL_HEIGHT: usize = 24;
+/// 行間（ドット）
+const LINE_SPACING: usize = 6;
+/// 縦書きの1文字の大きさ（ドット）
+const VERTICAL_CELL: usize = 48;
+/// 縦書きの列間（ドット）
+const VERTICAL_COLUMN_SPACING: usize = 12;
+/// ルビ（ふりがな）の文字高さ（ドット）
+const RUBY_HEIGHT: usize = 12;
+/// ルビと本文の間隔（ドット）
+const RUBY_SPACING: usize = 2;
+/// GS v 0 1回あたりの最大行数
+const MAX_BAND_HEIGHT: usize = 256;
+/// 黒とみなす濃度のしきい値
+const THRESHOLD: u8 = 96;
+
+/// 同梱フォント（BIZ UDゴシック、SIL Open Font License）
+///
+/// 端末ごとにシステムフォントが異なっても同じ字形・字幅で印刷できるよう、バイナリに埋め込む
+/// フォントファイルがない場合は build.rs でビルドを止める
+const EMBEDDED_FONT: &[u8] = include_bytes!("../fonts/BIZUDGothic-Regular.ttf");
+
+/// 日本語フォントを読み込む（指定がなければ同梱フォントを使う）
+pub fn load_font(path: Option<&str>) -> Result<Font, String> {
+    if let Some(path) = path {
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read font {}: {}", path, e))?;
+        return Font::from_bytes(bytes, FontSettings::default())
+            .map_err(|e| format!("Failed to load font {}: {}", path, e));
+    }
+    Font::from_bytes(EMBEDDED_FONT, FontSettings::default())
+        .map_err(|e| format!("Failed to load embedded font: {}", e))
+}
+
+/// 1行分の描画待ちテキスト
+struct Segment {
+    text: String,
+    style: TextStyle,
+}
+
+/// 描画先のビットマップ（1ドット1バイト、1=黒）
+pub struct RasterCanvas {
+    font: Font,
+    width: usize,
+    pixels: Vec<u8>,
+    height: usize,
+    pending: Vec<Segment>,
+}
+
+impl RasterCanvas {
+    pub fn new(font: Font, width_dots: usize) -> Self {
+        Self {
+            font,
+            width: width_dots,
+            pixels: Vec::new(),
+            height: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    /// 描画済み・描画待ちの内容がないか
+    pub fn is_empty(&self) -> bool {
+        self.height == 0 && self.pending.is_empty()
+    }
+
+    /// 行にテキストを追加（改行はしない）
+    pub fn push_text(&mut self, text: &str, style: TextStyle) {
+        self.pending.push(Segment {
+            text: text.to_string(),
+            style,
+        });
+    }
+
+    /// 改行（描画待ちの行があれば描画し、なければ空行を送る）
+    pub fn newline(&mut self) {
+        if self.pending.is_empty() {
+            self.grow(CELL_HEIGHT + LINE_SPACING);
+        } else {
+            self.commit_line();
+        }
+    }
+
+    fn grow(&mut self, rows: usize) {
+        self.height += rows;
+        self.pixels.resize(self.width * self.height, 0);
+    }
+
+    fn set_pixel(&mut self, x: usize, y: usize, on: bool) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = on as u8;
+        }
+    }
+
+    /// 描画待ちのテキストを描画（幅を超える場合は折り返す）
+    fn commit_line(&mut self) {
+        let segments = std::mem::take(&mut self.pending);
+        let align = segments.first().map(|s| s.style.align).unwrap_or(Align::Left);
+        let scale_y = if segments.iter().any(|s| s.style.double_height) { 2 } else { 1 };
+
+        // 文字ごとに幅を計算して物理行に分割
+        let mut lines: Vec<Vec<(char, TextStyle, usize)>> = vec![vec![]];
+        let mut x = 0;
+        for segment in &segments {
+            let scale_x = if segment.style.double_width { 2 } else { 1 };
+            for c in segment.text.chars() {
+                let w = char_width(c) * CELL_WIDTH * scale_x;
+                if x + w > self.width && x > 0 {
+                    lines.push(vec![]);
+                    x = 0;
+                }
+                if let Some(line) = lines.last_mut() {
+                    line.push((c, segment.style, w));
+                }
+                x += w;
+            }
+        }
+
+        let line_height = CELL_HEIGHT * scale_y;
+        for line in lines {
+            let top = self.height;
+            self.grow(line_height + LINE_SPACING);
+
+            let line_width: usize = line.iter().map(|(_, _, w)| w).sum();
+            let mut x = match align {
+                Align::Left => 0,
+                Align::Center => self.width.saturating_sub(line_width) / 2,
+                Align::Right => self.width.saturating_sub(line_width),
+            };
+
+            for (c, style, w) in line {
+                self.draw_char(c, style, x, top, w, line_height);
+                x += w;
+            }
+        }
+    }
+
+    fn draw_char(&mut self, c: char, style: TextStyle, cell_x: usize, top: usize, cell_w: usize, line_height: usize) {
+        let scale_x = if style.double_width { 2 } else { 1 };
+        let scale_y = if style.double_height { 2 } else { 1 };
+
+        // 反転時はセル全体を黒で塗る
+        if style.reverse {
+            for y in top..top + line_height {
+                for x in cell_x..cell_x + cell_w {
+                    self.set_pixel(x, y, true);
+                }
+            }
+        }
+
+        let (metrics, bitmap) = self.font.rasterize(c, CELL_HEIGHT as f32);
+        let ascent = self
+            .font
+            .horizontal_line_metrics(CELL_HEIGHT as f32)
+            .map(|m| m.ascent)
+            .unwrap_or(CELL_HEIGHT as f32 * 0.88);
+
+        let glyph_top = (ascent - metrics.height as f32 - metrics.ymin as f32).max(0.0) as usize;
+        let glyph_left = metrics.xmin.max(0) as usize;
+        let base_y = top + (line_height - CELL_HEIGHT * scale_y);
+
+        for gy in 0..metrics.height {
+            for gx in 0..metrics.width {
+                if bitmap[gy * metrics.width + gx] < THRESHOLD {
+                    continue;
+                }
+                for sy in 0..scale_y {
+                    for sx in 0..scale_x {
+                        let x = cell_x + (glyph_left + gx) * scale_x + sx;
+                        let y = base_y + (glyph_top + gy) * scale_y + sy;
+                        if x >= cell_x + cell_w {
+                            continue;
+                        }
+                        let on = !style.reverse;
+                        self.set_pixel(x, y, on);
+                        // 太字は1ドット右にずらして重ね書き
+                        if style.bold && x + 1 < cell_x + cell_w {
+                            self.set_pixel(x + 1, y, on);
+                        }
+                    }
+                }
+            }
+        }
+
+        if style.underline {
+            let y = top + line_height - 1;
+            for x in cell_x..cell_x + cell_w {
+                self.set_pixel(x, y, !style.reverse);
+            }
+        }
+    }
+
+    /// 次に描画する本文の上にルビを描画
+    ///
+    /// 本文の幅と配置からルビの位置を決め、本文の中央に揃える
+    pub fn push_ruby(&mut self, base: &str, ruby: &str, style: TextStyle) {
+        self.commit_pending();
+
+        let scale_x = if style.double_width { 2 } else { 1 };
+        let base_width = (str_width(base) * CELL_WIDTH * scale_x).min(self.width);
+        let ruby_width = str_width(ruby) * CELL_WIDTH / 2;
+        let base_x = match style.align {
+            Align::Left => 0,
+            Align::Center => (self.width - base_width) / 2,
+            Align::Right => self.width - base_width,
+        };
+        let x = (base_x + base_width / 2)
+            .saturating_sub(ruby_width / 2)
+            .min(self.width.saturating_sub(ruby_width));
+
+        let top = self.height;
+        self.grow(RUBY_HEIGHT + RUBY_SPACING);
+        self.draw_small_text(ruby, x, top);
+    }
+
+    /// 小さい文字で1行描画（明細の補足など）
+    pub fn push_small_line(&mut self, text: &str, indent_cells: usize) {
+        self.commit_pending();
+
+        let top = self.height;
+        self.grow(RUBY_HEIGHT + LINE_SPACING);
+        self.draw_small_text(text, indent_cells * CELL_WIDTH, top);
+    }
+
+    /// ルビと同じ大きさの文字を描画（幅を超えた分は描画しない）
+    fn draw_small_text(&mut self, text: &str, mut x: usize, top: usize) {
+        let ascent = self
+            .font
+            .horizontal_line_metrics(RUBY_HEIGHT as f32)
+            .map(|m| m.ascent)
+            .unwrap_or(RUBY_HEIGHT as f32 * 0.88);
+        for c in text.chars() {
+            let (metrics, bitmap) = self.font.rasterize(c, RUBY_HEIGHT as f32);
+            let glyph_top = (ascent - metrics.height as f32 - metrics.ymin as f32).max(0.0) as usize;
+            let glyph_left = metrics.xmin.max(0) as usize;
+            for gy in 0..metrics.height {
+                for gx in 0..metrics.width {
+                    if bitmap[gy * metrics.width + gx] >= THRESHOLD {
+                        self.set_pixel(x + glyph_left + gx, top + glyph_top + gy, true);
+                    }
+                }
+            }
+            x += char_width(c) * CELL_WIDTH / 2;
+        }
+    }
+
+    /// 縦書きのテキストブロックを描画
+    ///
+    /// 改行で列を分け、右の列から順に上から下へ並べる
+    pub fn push_vertical(&mut self, text: &str, align: Align) {
+        self.commit_pending();
+
+        let columns: Vec<Vec<char>> = text
+            .lines()
+            .map(|l| l.chars().map(vertical_form).collect())
+            .filter(|c: &Vec<char>| !c.is_empty())
+            .collect();
+        if columns.is_empty() {
+            return;
+        }
+
+        let rows = columns.iter().map(|c| c.len()).max().unwrap_or(0);
+        let block_width = (columns.len() * (VERTICAL_CELL + VERTICAL_COLUMN_SPACING) - VERTICAL_COLUMN_SPACING)
+            .min(self.width);
+        let left = match align {
+            Align::Left => 0,
+            Align::Center => (self.width - block_width) / 2,
+            Align::Right => self.width - block_width,
+        };
+
+        let top = self.height;
+        self.grow(rows * VERTICAL_CELL + LINE_SPACING);
+
+        for (i, column) in columns.iter().enumerate() {
+            // 右の列から並べる
+            let Some(offset) = block_width.checked_sub((i + 1) * VERTICAL_CELL + i * VERTICAL_COLUMN_SPACING) else {
+                break;
+            };
+            for (row, &c) in column.iter().enumerate() {
+                self.draw_glyph(c, VERTICAL_CELL, left + offset, top + row * VERTICAL_CELL);
+            }
+        }
+    }
+
+    /// 1文字を正方形のセルの中央に描画
+    fn draw_glyph(&mut self, c: char, size: usize, cell_x: usize, cell_y: usize) {
+        let (metrics, bitmap) = self.font.rasterize(c, size as f32);
+        let ascent = self
+            .font
+            .horizontal_line_metrics(size as f32)
+            .map(|m| m.ascent)
+            .unwrap_or(size as f32 * 0.88);
+
+        let glyph_top = (ascent - metrics.height as f32 - metrics.ymin as f32).max(0.0) as usize;
+        let glyph_left = size.saturating_sub(metrics.width) / 2;
+        for gy in 0..metrics.height {
+            for gx in 0..metrics.width {
+                if bitmap[gy * metrics.width + gx] >= THRESHOLD {
+                    self.set_pixel(cell_x + glyph_left + gx, cell_y + glyph_top + gy, true);
+                }
+            }
+        }
+    }
+
+    fn commit_pending(&mut self) {
+        if !self.pending.is_empty() {
+            self.commit_line();
+        }
+    }
+
+    /// 白黒画像を描画（行の高さは画像の高さ）
+    pub fn push_bitmap(&mut self, bitmap: &Bitmap, align: Align) {
+        self.commit_pending();
+
+        let width = bitmap.width.min(self.width);
+        let left = match align {
+            Align::Left => 0,
+            Align::Center => (self.width - width) / 2,
+            Align::Right => self.width - width,
+        };
+        let top = self.height;
+        self.grow(bitmap.height + LINE_SPACING);
+        for y in 0..bitmap.height {
+            for x in 0..width {
+                if bitmap.pixels[y * bitmap.width + x] != 0 {
+                    self.set_pixel(left + x, top + y, true);
+                }
+            }
+        }
+    }
+
+    /// GS v 0 コマンド列に変換して内容をクリア
+    pub fn take_gs_v0(&mut self) -> Vec<u8> {
+        self.commit_pending();
+        let out = encode_gs_v0(&self.pixels, self.width, self.height);
+        self.pixels.clear();
+        self.height = 0;
+        out
+    }
+}
+
+/// 白黒画像（1ドット1バイト、0以外が黒）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl Bitmap {
+    /// GS v 0 コマンド列に変換
+    pub fn to_gs_v0(&self) -> Vec<u8> {
+        encode_gs_v0(&self.pixels, self.width, self.height)
+    }
+}
+
+/// 白黒のドット列を GS v 0 コマンド列に変換（MAX_BAND_HEIGHT 行ごとに分ける）
+fn encode_gs_v0(pixels: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let bytes_per_row = width.div_ceil(8);
+    let mut out = Vec::new();
+
+    let mut band_top = 0;
+    while band_top < height {
+        let band_height = MAX_BAND_HEIGHT.min(height - band_top);
+
+        // GS v 0 m xL xH yL yH d1...dk
+        out.extend_from_slice(&[
+            0x1d,
+            0x76,
+            0x30,
+            0x00,
+            (bytes_per_row & 0xFF) as u8,
+            ((bytes_per_row >> 8) & 0xFF) as u8,
+            (band_height & 0xFF) as u8,
+            ((band_height >> 8) & 0xFF) as u8,
+        ]);
+
+        for y in band_top..band_top + band_height {
+            let row = &pixels[y * width..(y + 1) * width];
+            for chunk in row.chunks(8) {
+                let mut byte = 0u8;
+                for (i, &p) in chunk.iter().enumerate() {
+                    if p != 0 {
+                        byte |= 0x80 >> i;
+                    }
+                }
+                out.push(byte);
+            }
+        }
+
+        band_top += band_height;
+    }
+    out
+}
+
+/// 縦書き用の字形に置き換え（長音・括弧など）
+fn vertical_form(c: char) -> char {
+    match c {
+        'ー' | '－' | '-' | '—' => '｜',
+        '（' | '(' => '︵',
+        '）' | ')' => '︶',
+        '「' => '﹁',
+        '」' => '﹂',
+        '『' => '﹃',
+        '』' => '﹄',
+        '【' => '︻',
+        '】' => '︼',
+        '、' => '︑',
+        '。' => '︒',
+        '…' => '︙',
+        '〜' | '～' => '≀',
+        _ => c,
+    }
+}
+
+/// 同梱フォントで描画したビットマップのスナップショットテスト
+///
+/// `tests/fixtures/raster/` の期待値と比較する（`#` が黒、行末の白は省略）
+/// フォントや描画処理を変更した場合は `UPDATE_LAYOUT_FIXTURES=1 cargo test` で更新し、差分を確認してコミットする
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// 58mm 用紙の印字幅（ドット）
+    const WIDTH_58MM: usize = 384;
+
+    fn render<F: FnOnce(&mut RasterCanvas)>(draw: F) -> String {
+        let mut canvas = RasterCanvas::new(load_font(None).unwrap(), WIDTH_58MM);
+        draw(&mut canvas);
+        canvas.commit_pending();
+        canvas
+            .pixels
+            .chunks(canvas.width)
+            .map(|row| {
+                let line: String = row.iter().map(|&p| if p != 0 { '#' } else { '.' }).collect();
+                format!("{}\n", line.trim_end_matches('.'))
+            })
+            .collect()
+    }
+
+    fn assert_snapshot(name: &str, actual: &str) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/raster")
+            .join(format!("{}.txt", name));
+
+        if std::env::var("UPDATE_LAYOUT_FIXTURES").is_ok() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("Fixture not found: {} (run with UPDATE_LAYOUT_FIXTURES=1)", path.display()));
+        assert!(expected == actual, "Raster output changed: {} (review and run with UPDATE_LAYOUT_FIXTURES=1 to update)", name);
+    }
+
+    #[test]
+    fn raster_text_line_58mm() {
+        let text = render(|canvas| {
+            canvas.push_text("合計 ￥1,600", TextStyle::default().bold());
+            canvas.newline();
+            canvas.push_text("サークルみず", TextStyle::default().double().center());
+            canvas.newline();
+        });
+        assert_snapshot("text_line_58", &text);
+    }
+
+    #[test]
+    fn raster_vertical_circle_name_58mm() {
+        let text = render(|canvas| canvas.push_vertical("サークル（みず）", Align::Center));
+        assert_snapshot("vertical_circle_name_58", &text);
+    }
+}
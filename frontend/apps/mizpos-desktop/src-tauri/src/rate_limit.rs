@@ -0,0 +1,230 @@
+//! レート制限モジュール
+//!
+//! 署名の生成・PINの照合・Keychainの操作に回数制限をかけ、超えた場合は一定時間受け付けない
+//! ロックアウトが続くたびに待ち時間を倍にし、フロントエンドが乗っ取られても総当たりや署名の乱発ができないようにする
+//! 状態はメモリ上に持つ（アプリの再起動には端末を操作する人が必要なため）
+
+use crate::storage;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use tauri::Emitter;
+
+/// ロックアウトしたときに通知するイベント名
+pub const EVENT_RATE_LIMIT_LOCKOUT: &str = "rate-limit-lockout";
+
+/// 制限の内容
+#[derive(Debug, Clone, Copy)]
+pub struct Policy {
+    /// 期間内に許す回数
+    pub max_attempts: usize,
+    /// 回数を数える期間（秒）
+    pub window_secs: u64,
+    /// 最初のロックアウトの長さ（秒）
+    pub base_lockout_secs: u64,
+    /// ロックアウトの長さの上限（秒）
+    pub max_lockout_secs: u64,
+}
+
+/// PINの照合（失敗だけを数える）
+pub const PIN: Policy = Policy {
+    max_attempts: 5,
+    window_secs: 300,
+    base_lockout_secs: 30,
+    max_lockout_secs: 3600,
+};
+
+/// 署名の生成
+pub const SIGNATURE: Policy = Policy {
+    max_attempts: 120,
+    window_secs: 60,
+    base_lockout_secs: 10,
+    max_lockout_secs: 600,
+};
+
+/// Keychainの操作（鍵の生成・削除）
+pub const KEYCHAIN: Policy = Policy {
+    max_attempts: 5,
+    window_secs: 600,
+    base_lockout_secs: 60,
+    max_lockout_secs: 3600,
+};
+
+/// ロックアウトの通知
+#[derive(Debug, Clone, Serialize)]
+pub struct LockoutEvent {
+    pub scope: String,
+    /// 解除される日時（UNIX秒）
+    pub locked_until: u64,
+    pub retry_after_secs: u64,
+}
+
+/// 対象ごとの状態
+#[derive(Debug, Default)]
+struct Limiter {
+    /// 期間内の試行日時（UNIX秒）
+    attempts: Vec<u64>,
+    /// 続けてロックアウトした回数
+    lockouts: u32,
+    locked_until: Option<u64>,
+}
+
+impl Limiter {
+    /// ロックアウト中なら残り秒数
+    fn retry_after(&mut self, policy: &Policy, now: u64) -> Option<u64> {
+        match self.locked_until {
+            Some(until) if now < until => Some(until - now),
+            Some(until) => {
+                // ロックアウト明けにしばらく何もなければ待ち時間を元に戻す
+                if now >= until + policy.max_lockout_secs {
+                    self.lockouts = 0;
+                }
+                self.locked_until = None;
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// 試行を記録し、回数を超えたらロックアウトする（ロックアウトした場合はその長さ）
+    fn record(&mut self, policy: &Policy, now: u64) -> Option<u64> {
+        self.attempts
+            .retain(|t| now.saturating_sub(*t) < policy.window_secs);
+        self.attempts.push(now);
+        if self.attempts.len() <= policy.max_attempts {
+            return None;
+        }
+
+        self.attempts.clear();
+        self.lockouts += 1;
+        let lockout = policy
+            .base_lockout_secs
+            .saturating_mul(1u64 << (self.lockouts - 1).min(20))
+            .min(policy.max_lockout_secs);
+        self.locked_until = Some(now + lockout);
+        Some(lockout)
+    }
+}
+
+fn limiters() -> &'static Mutex<HashMap<String, Limiter>> {
+    static LIMITERS: OnceLock<Mutex<HashMap<String, Limiter>>> = OnceLock::new();
+    LIMITERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 通知先のアプリ
+fn app_handle() -> &'static OnceLock<tauri::AppHandle> {
+    static APP: OnceLock<tauri::AppHandle> = OnceLock::new();
+    &APP
+}
+
+/// ロックアウトの通知先を登録（起動時に1回呼ぶ）
+pub fn install(app: tauri::AppHandle) {
+    let _ = app_handle().set(app);
+}
+
+fn locked_error(retry_after: u64) -> String {
+    format!("Too many attempts. Try again in {} seconds", retry_after)
+}
+
+fn notify(scope: &str, now: u64, lockout: u64) {
+    if let Some(app) = app_handle().get() {
+        let event = LockoutEvent {
+            scope: scope.to_string(),
+            locked_until: now + lockout,
+            retry_after_secs: lockout,
+        };
+        let _ = app.emit(EVENT_RATE_LIMIT_LOCKOUT, &event);
+    }
+}
+
+/// ロックアウト中でないか確認
+pub fn check(scope: &str, policy: &Policy) -> Result<(), String> {
+    let now = storage::now_unix();
+    let mut limiters = limiters().lock().map_err(|e| e.to_string())?;
+    let limiter = limiters.entry(scope.to_string()).or_default();
+    match limiter.retry_after(policy, now) {
+        Some(retry_after) => Err(locked_error(retry_after)),
+        None => Ok(()),
+    }
+}
+
+/// 試行を記録（回数を超えた場合はロックアウトして通知する）
+pub fn record(scope: &str, policy: &Policy) {
+    let now = storage::now_unix();
+    let lockout = match limiters().lock() {
+        Ok(mut limiters) => limiters.entry(scope.to_string()).or_default().record(policy, now),
+        Err(_) => None,
+    };
+    if let Some(lockout) = lockout {
+        notify(scope, now, lockout);
+    }
+}
+
+/// 確認してから試行を記録（成否に関わらず数える操作に使う）
+pub fn acquire(scope: &str, policy: &Policy) -> Result<(), String> {
+    check(scope, policy)?;
+    record(scope, policy);
+    Ok(())
+}
+
+/// 成功したので記録を消す（失敗だけを数える操作に使う）
+pub fn reset(scope: &str) {
+    if let Ok(mut limiters) = limiters().lock() {
+        limiters.remove(scope);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: Policy = Policy {
+        max_attempts: 3,
+        window_secs: 60,
+        base_lockout_secs: 10,
+        max_lockout_secs: 40,
+    };
+
+    #[test]
+    fn test_locks_out_after_max_attempts_in_window() {
+        let mut limiter = Limiter::default();
+        for now in 0..3 {
+            assert_eq!(limiter.record(&POLICY, now), None);
+        }
+        assert_eq!(limiter.record(&POLICY, 3), Some(10));
+        assert_eq!(limiter.retry_after(&POLICY, 5), Some(8));
+        assert_eq!(limiter.retry_after(&POLICY, 13), None);
+    }
+
+    #[test]
+    fn test_attempts_outside_window_are_forgotten() {
+        let mut limiter = Limiter::default();
+        for now in [0, 1, 2, 61, 62] {
+            assert_eq!(limiter.record(&POLICY, now), None);
+        }
+    }
+
+    #[test]
+    fn test_lockout_doubles_up_to_max() {
+        let mut limiter = Limiter::default();
+        let mut now = 0;
+        let mut lockouts = Vec::new();
+        for _ in 0..4 {
+            let lockout = (0..4).find_map(|_| limiter.record(&POLICY, now)).unwrap();
+            lockouts.push(lockout);
+            now += lockout;
+            assert_eq!(limiter.retry_after(&POLICY, now), None);
+        }
+        assert_eq!(lockouts, vec![10, 20, 40, 40]);
+    }
+
+    #[test]
+    fn test_lockout_length_resets_after_quiet_period() {
+        let mut limiter = Limiter::default();
+        let lockout = (0..4).find_map(|_| limiter.record(&POLICY, 0)).unwrap();
+        assert_eq!(lockout, 10);
+        assert_eq!(limiter.retry_after(&POLICY, 10 + 40), None);
+        let lockout = (0..4).find_map(|_| limiter.record(&POLICY, 50)).unwrap();
+        assert_eq!(lockout, 10);
+    }
+}
@@ -0,0 +1,142 @@
+//! レシートデータモジュール
+//!
+//! 印刷・ジャーナルで共通に使うレシート／閉局レポートのデータモデル
+//! データモデル自体は共通クレート（mizpos-core）で定義し、ここでは印刷用の並べ替えを持つ
+
+pub use mizpos_core::receipt::{
+    AgeVerification, CardDetails, ClosingReportData, DepositTerms, HourlySales, ImageMode, ImageSource,
+    PaymentInfo, PaymentMethodTotal, PriceOverride, ReceiptData, ReceiptItem, ReceiptLogo, ReceiptPoints,
+    StaffTotal, VoucherCount, VoucherSales,
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// レシート明細の並び順
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemOrder {
+    /// スキャンした順（送られてきた順）
+    #[default]
+    ScanOrder,
+    /// サークルごとにまとめる
+    Circle,
+    /// カテゴリーごとにまとめる
+    Category,
+}
+
+/// 見出し付きの明細グループ
+pub struct ItemGroup<'a> {
+    /// グループ見出し（スキャン順の場合はNone）
+    pub label: Option<String>,
+    pub items: Vec<&'a ReceiptItem>,
+}
+
+/// 明細を並び順の設定に従って並べ替え・グループ化する
+///
+/// グループは最初に出てきた順に並べ、グループ内はスキャン順を保つ
+pub fn arrange_items(items: &[ReceiptItem], order: ItemOrder) -> Vec<ItemGroup<'_>> {
+    let key = |item: &ReceiptItem| match order {
+        ItemOrder::ScanOrder => None,
+        ItemOrder::Circle => Some(item.circle_name.clone()),
+        ItemOrder::Category => Some(item.category_label()),
+    };
+
+    let mut groups: Vec<ItemGroup> = Vec::new();
+    for item in items {
+        let label = key(item);
+        match groups.iter_mut().find(|g| g.label == label) {
+            Some(group) => group.items.push(item),
+            None => groups.push(ItemGroup {
+                label,
+                items: vec![item],
+            }),
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+pub mod test_support {
+    use super::{ReceiptData, ReceiptItem};
+    use serde_json::{json, Map, Value};
+
+    /// 明細の既定値（サークルみずの新刊1冊 ¥1,000）に指定した項目を重ねる
+    fn item_value(fields: Value) -> Value {
+        let mut item = json!({
+            "circle_name": "サークルみず", "name": "新刊", "jan": "4900000000011", "isbn": "",
+            "is_book": true, "quantity": 1, "price": 1000
+        });
+        merge(&mut item, fields);
+        item
+    }
+
+    fn merge(base: &mut Value, fields: Value) {
+        if let (Some(base), Value::Object(fields)) = (base.as_object_mut(), fields) {
+            base.extend(fields);
+        }
+    }
+
+    /// テスト用の明細（指定しない項目は既定値）
+    pub fn item(fields: Value) -> ReceiptItem {
+        serde_json::from_value(item_value(fields)).unwrap()
+    }
+
+    /// テスト用のレシート
+    ///
+    /// 明細を追加しない場合は既定の明細1件、合計は明細の合計、支払いは合計を現金、税額は合計の10%（内税）にする
+    pub struct ReceiptBuilder {
+        fields: Map<String, Value>,
+        items: Option<Vec<Value>>,
+    }
+
+    pub fn receipt(receipt_number: &str) -> ReceiptBuilder {
+        let fields = json!({
+            "event_name": "テスト即売会",
+            "staff_id": "S001",
+            "tax_rate": 10,
+            "receipt_number": receipt_number,
+        });
+        ReceiptBuilder {
+            fields: fields.as_object().cloned().unwrap(),
+            items: None,
+        }
+    }
+
+    impl ReceiptBuilder {
+        /// 明細を追加（指定しない項目は既定値）
+        pub fn item(mut self, fields: Value) -> Self {
+            self.items.get_or_insert_with(Vec::new).push(item_value(fields));
+            self
+        }
+
+        /// 明細なし
+        pub fn no_items(mut self) -> Self {
+            self.items = Some(Vec::new());
+            self
+        }
+
+        /// レシートの項目を指定（合計・支払い・税額を指定すると既定値より優先する）
+        pub fn with(mut self, fields: Value) -> Self {
+            if let Value::Object(fields) = fields {
+                self.fields.extend(fields);
+            }
+            self
+        }
+
+        pub fn build(self) -> ReceiptData {
+            let items = self.items.unwrap_or_else(|| vec![item_value(json!({}))]);
+            let mut fields = self.fields;
+            let total = fields
+                .get("total")
+                .and_then(Value::as_u64)
+                .unwrap_or_else(|| items.iter().map(|i| i["price"].as_u64().unwrap()).sum());
+            fields.insert("items".to_string(), Value::Array(items));
+            fields.insert("total".to_string(), json!(total));
+            fields
+                .entry("payments")
+                .or_insert_with(|| json!([{ "method": "現金", "amount": total }]));
+            fields.entry("tax_amount").or_insert_with(|| json!(total / 11));
+            serde_json::from_value(Value::Object(fields)).unwrap()
+        }
+    }
+}
@@ -0,0 +1,504 @@
+//! レシートQRモジュール
+//!
+//! レシートの内容（明細・合計・支払）を端末署名付きで圧縮し、フッターのQRコードに埋め込む
+//! 紙のレシートを読み取るだけで、別の端末やオフラインでも返品処理を始められるようにする
+//! 閉局レポートも同じ形式で末尾のQRコードに集計を埋め込み、端末がオンラインに戻れない場合でも
+//! 本部が紙を読み取って売上を取り込めるようにする
+
+use crate::event_context;
+use crate::journal::JournalTotals;
+use crate::receipt::{ClosingReportData, ReceiptData};
+use crate::storage;
+use crate::terminal_auth::{self, SignatureData};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL, Engine};
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::io::{Read, Write};
+
+/// 署名済みQRの保存ファイル名（イベントごと）
+const SIGNED_QR_FILE: &str = "receipt_qr.jsonl";
+/// QRコードの接頭辞（形式のバージョンを含む）
+const RECEIPT_QR_PREFIX: &str = "MZRC1:";
+/// 閉局レポートのQRコードの接頭辞
+const CLOSING_REPORT_QR_PREFIX: &str = "MZCR1:";
+/// QRコードに埋め込む最大長（超える場合はレシート番号のみのQRにする）
+pub const MAX_QR_PAYLOAD_LEN: usize = 1200;
+/// 展開後の最大サイズ（不正なQRで大量に展開されないように制限）
+const MAX_DECOMPRESSED_LEN: u64 = 64 * 1024;
+
+/// QRコード用の明細
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CompactItem {
+    /// JAN
+    #[serde(rename = "j")]
+    pub jan: String,
+    /// 商品名
+    #[serde(rename = "n")]
+    pub name: String,
+    /// 数量
+    #[serde(rename = "q")]
+    pub quantity: u32,
+    /// 値段（単価 x 数量）
+    #[serde(rename = "p")]
+    pub price: u32,
+}
+
+/// QRコード用の支払情報
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CompactPayment {
+    #[serde(rename = "m")]
+    pub method: String,
+    #[serde(rename = "a")]
+    pub amount: u32,
+}
+
+/// QRコードに埋め込むレシート内容
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CompactReceipt {
+    #[serde(rename = "r")]
+    pub receipt_number: String,
+    #[serde(rename = "i")]
+    pub items: Vec<CompactItem>,
+    #[serde(rename = "t")]
+    pub total: u32,
+    #[serde(rename = "x")]
+    pub tax_amount: u32,
+    #[serde(rename = "y")]
+    pub payments: Vec<CompactPayment>,
+}
+
+impl From<&ReceiptData> for CompactReceipt {
+    fn from(receipt: &ReceiptData) -> Self {
+        Self {
+            receipt_number: receipt.receipt_number.clone(),
+            items: receipt
+                .items
+                .iter()
+                .map(|i| CompactItem {
+                    jan: i.jan.clone(),
+                    name: i.name.clone(),
+                    quantity: i.quantity,
+                    price: i.price,
+                })
+                .collect(),
+            total: receipt.total,
+            tax_amount: receipt.tax_amount,
+            payments: receipt
+                .payments
+                .iter()
+                .map(|p| CompactPayment {
+                    method: p.method.clone(),
+                    amount: p.amount,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// QRコードから読み取ったレシート
+///
+/// 署名はレシート内容のJSON文字列（`body`）に対するもので、発行端末の公開鍵で verify により検証する
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SignedReceipt {
+    pub body: String,
+    pub signature: SignatureData,
+}
+
+impl SignedReceipt {
+    /// レシート内容を取り出す（署名は検証しない）
+    pub fn receipt(&self) -> Result<CompactReceipt, String> {
+        serde_json::from_str(&self.body).map_err(|e| e.to_string())
+    }
+
+    /// 発行端末の公開鍵で署名を検証し、レシート内容を取り出す
+    pub fn verify(&self, public_key: &str) -> Result<CompactReceipt, String> {
+        terminal_auth::verify_signature(public_key, &self.body, &self.signature)
+            .map_err(|e| format!("Receipt QR signature is invalid: {}", e))?;
+        self.receipt()
+    }
+}
+
+/// QRコード用の支払方法別の集計
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CompactPaymentTotal {
+    #[serde(rename = "m")]
+    pub method: String,
+    #[serde(rename = "c")]
+    pub count: u32,
+    #[serde(rename = "a")]
+    pub amount: u64,
+}
+
+/// QRコード用の金種別の枚数
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CompactDenomination {
+    #[serde(rename = "d")]
+    pub denomination: u32,
+    #[serde(rename = "c")]
+    pub count: u32,
+}
+
+/// QRコードに埋め込む閉局レポートの集計
+///
+/// QRコードに収まるよう、時間帯別・担当者別の集計は含めない
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CompactClosingReport {
+    #[serde(rename = "id")]
+    pub report_id: String,
+    #[serde(rename = "e", default, skip_serializing_if = "Option::is_none")]
+    pub event_name: Option<String>,
+    #[serde(rename = "s")]
+    pub staff_id: String,
+    /// 閉局日時
+    #[serde(rename = "at")]
+    pub closed_at: String,
+    /// 取引件数
+    #[serde(rename = "n")]
+    pub transaction_count: u32,
+    /// 売上合計（税込）
+    #[serde(rename = "t")]
+    pub expected_total: u32,
+    /// 実際に数えた合計
+    #[serde(rename = "g")]
+    pub grand_total: u32,
+    #[serde(rename = "cash")]
+    pub cash_total: u32,
+    #[serde(rename = "v")]
+    pub voucher_total: u32,
+    #[serde(rename = "diff")]
+    pub difference: i32,
+    /// 返品の件数と額
+    #[serde(rename = "rc")]
+    pub refund_count: u32,
+    #[serde(rename = "ra")]
+    pub refund_amount: u64,
+    /// 取消の件数と額
+    #[serde(rename = "vc")]
+    pub void_count: u32,
+    #[serde(rename = "va")]
+    pub void_amount: u64,
+    #[serde(rename = "pm")]
+    pub payment_methods: Vec<CompactPaymentTotal>,
+    /// 枚数が0の金種は含めない
+    #[serde(rename = "dn")]
+    pub denominations: Vec<CompactDenomination>,
+}
+
+impl CompactClosingReport {
+    pub fn new(report: &ClosingReportData, totals: &JournalTotals) -> Self {
+        Self {
+            report_id: report.id.clone(),
+            event_name: report.event_name.clone().filter(|n| !n.is_empty()),
+            staff_id: report.staff_id.clone(),
+            closed_at: report.closed_at.clone(),
+            transaction_count: report.transaction_count,
+            expected_total: report.expected_total,
+            grand_total: report.grand_total,
+            cash_total: report.cash_total,
+            voucher_total: report.voucher_total,
+            difference: report.difference,
+            refund_count: totals.refund_count,
+            refund_amount: totals.refund_amount,
+            void_count: totals.void_count,
+            void_amount: totals.void_amount,
+            payment_methods: report
+                .payment_methods
+                .iter()
+                .map(|m| CompactPaymentTotal {
+                    method: m.method.clone(),
+                    count: m.count,
+                    amount: m.amount,
+                })
+                .collect(),
+            denominations: report
+                .denominations
+                .iter()
+                .filter(|d| d.count > 0)
+                .map(|d| CompactDenomination {
+                    denomination: d.denomination,
+                    count: d.count,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// QRコードから読み取った閉局レポート
+///
+/// 署名は集計のJSON文字列（`body`）に対するもので、発行端末の公開鍵で verify により検証する
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SignedClosingReport {
+    pub body: String,
+    pub signature: SignatureData,
+}
+
+impl SignedClosingReport {
+    /// 集計を取り出す（署名は検証しない）
+    pub fn report(&self) -> Result<CompactClosingReport, String> {
+        serde_json::from_str(&self.body).map_err(|e| e.to_string())
+    }
+
+    /// 発行端末の公開鍵で署名を検証し、集計を取り出す
+    pub fn verify(&self, public_key: &str) -> Result<CompactClosingReport, String> {
+        terminal_auth::verify_signature(public_key, &self.body, &self.signature)
+            .map_err(|e| format!("Closing report QR signature is invalid: {}", e))?;
+        self.report()
+    }
+}
+
+/// 署名済みQRの記録（再発行・プレビューのたびに署名し直さないよう、レシートごとに1回だけ署名する）
+///
+/// 閉局レポートはレポートIDを receipt_number に入れて同じファイルに記録する
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SignedQrRecord {
+    receipt_number: String,
+    /// レシート内容のSHA-256（内容が変わった場合は署名し直す）
+    body_digest: String,
+    data: String,
+}
+
+fn body_digest(body: &str) -> String {
+    BASE64_URL.encode(Sha256::digest(body.as_bytes()))
+}
+
+/// レシートを署名・圧縮してQRコード用の文字列にする
+///
+/// 同じ内容のレシートは最初の署名を使い回す（署名の回数制限を再発行で消費しない）
+pub fn encode(receipt: &ReceiptData) -> Result<String, String> {
+    let body = serde_json::to_string(&CompactReceipt::from(receipt)).map_err(|e| e.to_string())?;
+    sign_cached(&receipt.receipt_number, body, |body, signature| {
+        pack(&SignedReceipt { body, signature })
+    })
+}
+
+/// 閉局レポートの集計を署名・圧縮してQRコード用の文字列にする
+///
+/// 同じ内容の閉局レポートは最初の署名を使い回す（スプーラーの再印刷で署名し直さない）
+pub fn encode_closing_report(report: &ClosingReportData, totals: &JournalTotals) -> Result<String, String> {
+    let body = serde_json::to_string(&CompactClosingReport::new(report, totals)).map_err(|e| e.to_string())?;
+    sign_cached(&report.id, body, |body, signature| {
+        compress(CLOSING_REPORT_QR_PREFIX, &SignedClosingReport { body, signature })
+    })
+}
+
+/// 記録済みの署名があれば使い回し、なければ署名して記録する
+fn sign_cached<F>(key: &str, body: String, pack: F) -> Result<String, String>
+where
+    F: FnOnce(String, SignatureData) -> Result<String, String>,
+{
+    let digest = body_digest(&body);
+    let file = event_context::scoped(SIGNED_QR_FILE)?;
+    let cached = storage::load_jsonl::<SignedQrRecord>(&file)
+        .into_iter()
+        .find(|r| r.receipt_number == key && r.body_digest == digest);
+    if let Some(record) = cached {
+        return Ok(record.data);
+    }
+
+    let signature = terminal_auth::sign_message(&body).map_err(|e| e.to_string())?;
+    let data = pack(body, signature)?;
+    storage::append_jsonl(
+        &file,
+        &SignedQrRecord {
+            receipt_number: key.to_string(),
+            body_digest: digest,
+            data: data.clone(),
+        },
+    )?;
+    Ok(data)
+}
+
+/// 署名済みのレシートを圧縮してQRコード用の文字列にする
+fn pack(signed: &SignedReceipt) -> Result<String, String> {
+    compress(RECEIPT_QR_PREFIX, signed)
+}
+
+/// JSONにして圧縮し、接頭辞を付ける
+fn compress<T: Serialize>(prefix: &str, value: &T) -> Result<String, String> {
+    let json = serde_json::to_vec(value).map_err(|e| e.to_string())?;
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(&json).map_err(|e| e.to_string())?;
+    let compressed = encoder.finish().map_err(|e| e.to_string())?;
+
+    let data = format!("{}{}", prefix, BASE64_URL.encode(compressed));
+    if data.len() > MAX_QR_PAYLOAD_LEN {
+        return Err(format!("QR payload too large: {} bytes", data.len()));
+    }
+    Ok(data)
+}
+
+/// レシートのQRコード（レシート番号または埋め込みペイロード）からレシート番号を取り出す
+pub fn receipt_number_from_scan(data: &str) -> Result<String, String> {
+    let data = data.trim();
+    if data.starts_with(RECEIPT_QR_PREFIX) {
+        return Ok(decode(data)?.receipt()?.receipt_number);
+    }
+    if data.is_empty() {
+        return Err("Scanned code is empty".to_string());
+    }
+    Ok(data.to_string())
+}
+
+/// QRコードの文字列からレシートを復元（署名の検証は SignedReceipt::verify で行う）
+pub fn decode(data: &str) -> Result<SignedReceipt, String> {
+    let encoded = data
+        .strip_prefix(RECEIPT_QR_PREFIX)
+        .ok_or_else(|| "Not a receipt QR code".to_string())?;
+    decompress(encoded)
+}
+
+/// QRコードの文字列から閉局レポートを復元（署名の検証は SignedClosingReport::verify で行う）
+pub fn decode_closing_report(data: &str) -> Result<SignedClosingReport, String> {
+    let encoded = data
+        .trim()
+        .strip_prefix(CLOSING_REPORT_QR_PREFIX)
+        .ok_or_else(|| "Not a closing report QR code".to_string())?;
+    decompress(encoded)
+}
+
+fn decompress<T: DeserializeOwned>(encoded: &str) -> Result<T, String> {
+    let compressed = BASE64_URL.decode(encoded).map_err(|e| e.to_string())?;
+
+    let mut json = Vec::new();
+    ZlibDecoder::new(compressed.as_slice())
+        .take(MAX_DECOMPRESSED_LEN)
+        .read_to_end(&mut json)
+        .map_err(|e| e.to_string())?;
+    serde_json::from_slice(&json).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    #[test]
+    fn test_receipt_number_from_plain_stub_qr() {
+        // 控えのQRはレシート番号のみ
+        assert_eq!(receipt_number_from_scan(" C107-00042\n").unwrap(), "C107-00042");
+        assert!(receipt_number_from_scan("  ").is_err());
+        assert!(receipt_number_from_scan("MZRC1:!!!").is_err());
+    }
+
+    fn signed(signing_key: &SigningKey, body: &str) -> SignedReceipt {
+        let timestamp = 1_700_000_000;
+        let message = terminal_auth::signing_message("T1", timestamp, None, None, body);
+        SignedReceipt {
+            body: body.to_string(),
+            signature: SignatureData {
+                terminal_id: "T1".to_string(),
+                timestamp,
+                signature: BASE64.encode(signing_key.sign(message.as_bytes()).to_bytes()),
+                organization_id: None,
+                seat_label: None,
+            },
+        }
+    }
+
+    fn body() -> String {
+        serde_json::to_string(&CompactReceipt {
+            receipt_number: "C107-00042".to_string(),
+            items: vec![CompactItem {
+                jan: "4900000000011".to_string(),
+                name: "アクリルスタンド".to_string(),
+                quantity: 2,
+                price: 3000,
+            }],
+            total: 3000,
+            tax_amount: 272,
+            payments: vec![CompactPayment {
+                method: "現金".to_string(),
+                amount: 3000,
+            }],
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_signed_receipt_roundtrip() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = BASE64.encode(signing_key.verifying_key().to_bytes());
+
+        let data = pack(&signed(&signing_key, &body())).unwrap();
+        assert!(data.starts_with(RECEIPT_QR_PREFIX));
+        let receipt = decode(&data).unwrap().verify(&public_key).unwrap();
+        assert_eq!(receipt.receipt_number, "C107-00042");
+        assert_eq!(receipt.items[0].quantity, 2);
+        assert_eq!(receipt.total, 3000);
+        assert_eq!(receipt_number_from_scan(&data).unwrap(), "C107-00042");
+    }
+
+    #[test]
+    fn test_tampered_receipt_fails_verification() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = BASE64.encode(signing_key.verifying_key().to_bytes());
+
+        // 金額を書き換えたレシート
+        let mut tampered = signed(&signing_key, &body());
+        tampered.body = tampered.body.replace("\"t\":3000", "\"t\":300");
+        assert_ne!(tampered.body, body());
+        let decoded = decode(&pack(&tampered).unwrap()).unwrap();
+        assert!(decoded.verify(&public_key).is_err());
+
+        // 別の端末の鍵では検証できない
+        let other_key = BASE64.encode(SigningKey::from_bytes(&[8u8; 32]).verifying_key().to_bytes());
+        let decoded = decode(&pack(&signed(&signing_key, &body())).unwrap()).unwrap();
+        assert!(decoded.verify(&other_key).is_err());
+    }
+
+    #[test]
+    fn test_closing_report_roundtrip() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = BASE64.encode(signing_key.verifying_key().to_bytes());
+        let report: ClosingReportData = serde_json::from_value(serde_json::json!({
+            "id": "CR-001",
+            "terminal_id": "T1",
+            "staff_id": "S001",
+            "staff_name": "担当",
+            "denominations": [
+                { "denomination": 1000, "count": 12 },
+                { "denomination": 500, "count": 0 }
+            ],
+            "cash_total": 12000,
+            "vouchers": [],
+            "voucher_total": 0,
+            "grand_total": 12000,
+            "expected_total": 12300,
+            "difference": -300,
+            "transaction_count": 8,
+            "closed_at": "2025-12-30T08:00:00Z",
+            "payment_methods": [{ "method": "現金", "count": 8, "amount": 12300, "refund_count": 0, "refund_amount": 0 }]
+        }))
+        .unwrap();
+        let totals = JournalTotals {
+            refund_count: 1,
+            refund_amount: 500,
+            ..JournalTotals::default()
+        };
+        let body = serde_json::to_string(&CompactClosingReport::new(&report, &totals)).unwrap();
+        let signed = signed(&signing_key, &body);
+        let data = compress(
+            CLOSING_REPORT_QR_PREFIX,
+            &SignedClosingReport {
+                body: signed.body,
+                signature: signed.signature,
+            },
+        )
+        .unwrap();
+
+        let decoded = decode_closing_report(&data).unwrap().verify(&public_key).unwrap();
+        assert_eq!(decoded.report_id, "CR-001");
+        assert_eq!(decoded.difference, -300);
+        assert_eq!(decoded.refund_amount, 500);
+        assert_eq!(decoded.denominations.len(), 1);
+        // レシートのQRとは取り違えない
+        assert!(decode(&data).is_err());
+    }
+}
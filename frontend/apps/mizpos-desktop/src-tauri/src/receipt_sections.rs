@@ -0,0 +1,101 @@
+//! レシートの追加セクションモジュール
+//!
+//! アンケートのQRコード・次回イベントの告知・SNSのリンクなど、イベントごとに載せたい内容を端末設定に登録し、
+//! レシートのあらかじめ決めた位置（スロット）に差し込む
+//! レシート本体のレイアウトを変えずに内容を増減できるようにするためのもの
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// セクションを差し込む位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptSlot {
+    /// 店舗情報の下（ご明細書の前）
+    AfterHeader,
+    /// 商品明細の下（合計の前）
+    AfterItems,
+    /// 支払情報の下（免税事業者の説明文の前）
+    AfterTotals,
+    /// レシート番号のQRコードの下（レシートの末尾）
+    Footer,
+}
+
+/// レシートの追加セクション
+///
+/// 本文とQRコードの `{receipt_number}` `{event_name}` はレシートの値に置き換える
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ReceiptSection {
+    pub slot: ReceiptSlot,
+    /// 見出し（太字・中央揃え）
+    #[serde(default)]
+    pub title: Option<String>,
+    /// 本文の各行
+    #[serde(default)]
+    pub lines: Vec<String>,
+    /// QRコードにする内容（アンケートのURLなど）
+    #[serde(default)]
+    pub qr: Option<String>,
+    /// 一時的に載せない場合は false
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ReceiptSection {
+    /// 置き換えた本文の各行
+    pub fn lines(&self, receipt_number: &str, event_name: &str) -> Vec<String> {
+        self.lines
+            .iter()
+            .map(|line| fill(line, receipt_number, event_name))
+            .collect()
+    }
+
+    /// 置き換えたQRコードの内容（空の場合は印刷しない）
+    pub fn qr(&self, receipt_number: &str, event_name: &str) -> Option<String> {
+        self.qr
+            .as_deref()
+            .filter(|qr| !qr.is_empty())
+            .map(|qr| fill(qr, receipt_number, event_name))
+    }
+}
+
+fn fill(template: &str, receipt_number: &str, event_name: &str) -> String {
+    template
+        .replace("{receipt_number}", receipt_number)
+        .replace("{event_name}", event_name)
+}
+
+/// スロットに差し込むセクション（登録順）
+pub fn for_slot(sections: &[ReceiptSection], slot: ReceiptSlot) -> impl Iterator<Item = &ReceiptSection> {
+    sections.iter().filter(move |s| s.enabled && s.slot == slot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_for_slot_keeps_order_and_skips_disabled() {
+        let sections: Vec<ReceiptSection> = serde_json::from_value(json!([
+            { "slot": "footer", "title": "アンケート", "qr": "https://example.com/q?r={receipt_number}" },
+            { "slot": "after_header", "lines": ["次回もよろしくお願いします"] },
+            { "slot": "footer", "lines": ["X: @mizpos"], "enabled": false },
+            { "slot": "footer", "lines": ["{event_name} 次回は夏です"] },
+        ]))
+        .unwrap();
+
+        let footer: Vec<&ReceiptSection> = for_slot(&sections, ReceiptSlot::Footer).collect();
+        assert_eq!(footer.len(), 2);
+        assert_eq!(
+            footer[0].qr("C107-00042", "テスト即売会").as_deref(),
+            Some("https://example.com/q?r=C107-00042")
+        );
+        assert_eq!(footer[1].lines("C107-00042", "テスト即売会"), vec!["テスト即売会 次回は夏です"]);
+        assert_eq!(for_slot(&sections, ReceiptSlot::AfterItems).count(), 0);
+    }
+}
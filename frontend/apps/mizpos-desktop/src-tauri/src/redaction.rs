@@ -0,0 +1,168 @@
+//! ログのマスキングモジュール
+//!
+//! 診断用に記録するコマンドの引数から、カード情報・鍵や署名・スタッフのPIN・お客様の名前などを自動で伏せる
+//! 診断情報（リモートコマンドの診断情報の要求）もここを通してから送るため、サポートにそのまま渡せる
+//! 直近のコマンドの記録はメモリ上にだけ持ち、伏せる前の値はどこにも残さない
+
+use crate::storage;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tauri::ipc::{InvokeBody, InvokeMessage};
+
+/// 伏せた値の代わりに入れる文字列
+const MASK: &str = "[REDACTED]";
+/// 記録しておく直近のコマンドの件数
+const MAX_COMMAND_LOG: usize = 200;
+
+/// 値を伏せる項目名（小文字にして `_` を除いた名前で比べる。引数名はキャメルケース、構造体の項目はスネークケースのため）
+///
+/// PIN（pin・duressPin など `pin` で終わるもの）はこの一覧とは別に伏せる
+const SENSITIVE_KEYS: &[&str] = &[
+    // カード情報
+    "carddetails",
+    "cardnumber",
+    "pan",
+    "last4",
+    "expmonth",
+    "expyear",
+    "cardholdername",
+    "carduid",
+    // 鍵・署名・認証情報
+    "privatekey",
+    "secretkey",
+    "secret",
+    "signature",
+    "token",
+    "password",
+    // お客様の情報
+    "customername",
+    "email",
+    "recipient",
+];
+
+/// 記録したコマンド
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandLogEntry {
+    pub command: String,
+    /// 伏せたあとの引数
+    pub payload: Value,
+    /// 呼び出し日時（UNIX秒）
+    pub invoked_at: u64,
+}
+
+static COMMAND_LOG: Mutex<VecDeque<CommandLogEntry>> = Mutex::new(VecDeque::new());
+
+fn normalize(key: &str) -> String {
+    key.chars()
+        .filter(|c| *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn is_sensitive(key: &str) -> bool {
+    let key = normalize(key);
+    key.ends_with("pin") || SENSITIVE_KEYS.contains(&key.as_str())
+}
+
+/// JSONの値から伏せるべき項目を伏せる
+pub fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            // 会員の名前（宛名）は会員番号と同じオブジェクトに入っている
+            let member = map.keys().any(|k| normalize(k) == "memberid");
+            for (key, value) in map.iter_mut() {
+                if is_sensitive(key) || (member && key == "name") {
+                    if !value.is_null() {
+                        *value = Value::String(MASK.to_string());
+                    }
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// 伏せたJSONに変換
+pub fn redacted<T: Serialize>(value: &T) -> Result<Value, String> {
+    let mut value = serde_json::to_value(value).map_err(|e| e.to_string())?;
+    redact(&mut value);
+    Ok(value)
+}
+
+/// 呼び出されたコマンドを引数を伏せて記録
+pub fn log_invoke<R: tauri::Runtime>(message: &InvokeMessage<R>) {
+    let payload = match message.payload() {
+        InvokeBody::Json(payload) => {
+            let mut payload = payload.clone();
+            redact(&mut payload);
+            payload
+        }
+        // バイナリの引数は中身を残さない
+        InvokeBody::Raw(bytes) => Value::String(format!("<{} bytes>", bytes.len())),
+    };
+    let Ok(mut log) = COMMAND_LOG.lock() else {
+        return;
+    };
+    log.push_back(CommandLogEntry {
+        command: message.command().to_string(),
+        payload,
+        invoked_at: storage::now_unix(),
+    });
+    if log.len() > MAX_COMMAND_LOG {
+        log.pop_front();
+    }
+}
+
+/// 直近に呼び出されたコマンド（古い順）
+pub fn recent_commands() -> Vec<CommandLogEntry> {
+    COMMAND_LOG
+        .lock()
+        .map(|log| log.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_masks_cards_keys_pins_and_customers() {
+        let mut payload = json!({
+            "approval": { "supervisor_id": "SV01", "pin": "1234" },
+            "duressPin": "9999",
+            "receipt": {
+                "receipt_number": "E-00001",
+                "customer_name": "山田 太郎",
+                "card_details": { "brand": "visa", "last4": "4242" },
+                "items": [{ "name": "新刊セット", "price": 1000 }],
+            },
+            "member": { "member_id": "M001", "name": "山田 太郎", "points_balance": 10 },
+            "signature": { "terminal_id": "T1", "signature": "c2ln" },
+            "email": null,
+        });
+        redact(&mut payload);
+
+        assert_eq!(
+            payload,
+            json!({
+                "approval": { "supervisor_id": "SV01", "pin": MASK },
+                "duressPin": MASK,
+                "receipt": {
+                    "receipt_number": "E-00001",
+                    "customer_name": MASK,
+                    "card_details": MASK,
+                    "items": [{ "name": "新刊セット", "price": 1000 }],
+                },
+                "member": { "member_id": "M001", "name": MASK, "points_balance": 10 },
+                "signature": MASK,
+                "email": null,
+            })
+        );
+    }
+}
@@ -0,0 +1,402 @@
+//! リモートコマンドモジュール
+//!
+//! 管理サーバーが発行した署名付きコマンド（閉局レポートの再印刷、設定の配信、端末のロック、診断情報の要求）を
+//! 固定したサーバー公開鍵で検証してから実行する
+//! コマンドの取得（ポーリング・WebSocket）はフロントエンドが行い、受け取った内容をそのまま渡す
+//! 同じコマンドの再実行・期限切れ・他の端末宛てのコマンドは実行しない
+//! 再実行は実行済みの最大の通番（sequence）で判定し、それ以下の通番のコマンドはすべて拒否する
+//! ロック状態はサーバーが署名したロックコマンドごと保存し、読み込むたびに検証する
+
+use crate::config::{self, TerminalConfig};
+use crate::duress;
+use crate::heartbeat::{self, HeartbeatStatus, TerminalStatusReport};
+use crate::journal::{self, JournalTotals};
+use crate::printer_profile::{self, PrinterProfile};
+use crate::receipt::ClosingReportData;
+use crate::redaction::{self, CommandLogEntry};
+use crate::server_key;
+use crate::spooler::{self, SpoolDocument, UsbPrinter};
+use crate::storage;
+use crate::terminal_auth;
+use crate::upload_queue;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 実行履歴の保存ファイル名
+const REMOTE_COMMANDS_FILE: &str = "remote_commands.json";
+/// 実行済みの最大の通番の保存ファイル名
+const SEQUENCE_FILE: &str = "remote_command_sequence.json";
+/// ロック状態の保存ファイル名
+const LOCK_FILE: &str = "terminal_lock.json";
+/// 保持する実行履歴の上限
+const MAX_HISTORY: usize = 500;
+
+/// コマンドを実行したときに通知するイベント名
+pub const EVENT_REMOTE_COMMAND_EXECUTED: &str = "remote-command-executed";
+/// 端末のロック状態が変わったときに通知するイベント名
+pub const EVENT_TERMINAL_LOCK: &str = "terminal-lock";
+
+/// サーバーから届く署名付きコマンド（payload は RemoteCommandEnvelope のJSON文字列）
+pub type SignedRemoteCommand = server_key::SignedPayload;
+
+/// コマンドの宛先と有効期限
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteCommandEnvelope {
+    pub id: String,
+    /// 端末ごとの通番（サーバーが発行順に増やす）
+    pub sequence: u64,
+    /// 宛先の端末ID
+    pub terminal_id: String,
+    /// 発行日時（UNIX秒）
+    pub issued_at: u64,
+    /// 有効期限（UNIX秒）
+    pub expires_at: u64,
+    pub command: RemoteCommand,
+}
+
+/// コマンドの内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RemoteCommand {
+    /// 閉局レポートを再印刷（印刷先を省略した場合はジャーナル用プリンター）
+    ReprintClosingReport {
+        report: Box<ClosingReportData>,
+        printer: Option<UsbPrinter>,
+    },
+    /// 端末設定を配信
+    PushConfig { config: Box<TerminalConfig> },
+    /// 端末をロック（開局・売上を受け付けない）
+    LockTerminal { reason: String },
+    /// ロックを解除
+    UnlockTerminal,
+    /// 診断情報を要求（署名してアップロードキューに積む）
+    RequestDiagnostics,
+    /// 脅迫PINによる記録を解除
+    ClearDuress,
+}
+
+impl RemoteCommand {
+    fn name(&self) -> &'static str {
+        match self {
+            RemoteCommand::ReprintClosingReport { .. } => "reprint_closing_report",
+            RemoteCommand::PushConfig { .. } => "push_config",
+            RemoteCommand::LockTerminal { .. } => "lock_terminal",
+            RemoteCommand::UnlockTerminal => "unlock_terminal",
+            RemoteCommand::RequestDiagnostics => "request_diagnostics",
+            RemoteCommand::ClearDuress => "clear_duress",
+        }
+    }
+}
+
+/// 実行結果
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RemoteCommandResult {
+    pub id: String,
+    pub command: String,
+    pub executed_at: u64,
+    pub success: bool,
+    /// 結果の説明（印刷ジョブID、アップロードIDなど）またはエラー
+    pub detail: Option<String>,
+}
+
+/// 端末のロック状態
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct TerminalLock {
+    pub locked: bool,
+    pub reason: Option<String>,
+    /// ロックしたコマンドのID
+    pub command_id: Option<String>,
+    pub locked_at: Option<u64>,
+}
+
+/// 実行済みの最大の通番
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct SequenceState {
+    last_sequence: u64,
+}
+
+/// ロック状態の保存形式（ロックしたコマンドを署名ごと残す）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredLock {
+    command: SignedRemoteCommand,
+    locked_at: u64,
+}
+
+/// 診断情報
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostics {
+    pub status: TerminalStatusReport,
+    pub heartbeat: HeartbeatStatus,
+    pub config: TerminalConfig,
+    pub printer_profiles: Vec<PrinterProfile>,
+    pub journal_totals: JournalTotals,
+    pub pending_print_jobs: usize,
+    /// 直近に呼び出されたコマンド（引数は伏せてある）
+    pub recent_commands: Vec<CommandLogEntry>,
+}
+
+/// アップロード種別: 診断情報
+pub const KIND_DIAGNOSTICS: &str = "diagnostics";
+
+/// 実行履歴を取得
+pub fn history() -> Result<Vec<RemoteCommandResult>, String> {
+    Ok(storage::load_json(REMOTE_COMMANDS_FILE)?.unwrap_or_default())
+}
+
+fn record(result: &RemoteCommandResult) -> Result<(), String> {
+    let mut history = history()?;
+    history.push(result.clone());
+    if history.len() > MAX_HISTORY {
+        let excess = history.len() - MAX_HISTORY;
+        history.drain(..excess);
+    }
+    storage::save_json(REMOTE_COMMANDS_FILE, &history)
+}
+
+fn last_sequence() -> Result<u64, String> {
+    Ok(storage::load_json::<SequenceState>(SEQUENCE_FILE)?
+        .unwrap_or_default()
+        .last_sequence)
+}
+
+/// ロック状態を取得
+///
+/// 保存したロックコマンドの署名を検証できない場合（書き換え・別の鍵）はロック中として扱う
+pub fn lock_state() -> Result<TerminalLock, String> {
+    let Some(stored) = storage::load_json::<StoredLock>(LOCK_FILE)? else {
+        return Ok(TerminalLock::default());
+    };
+    let envelope = server_key::open::<RemoteCommandEnvelope>(&stored.command);
+    let (reason, command_id) = match envelope {
+        Ok(RemoteCommandEnvelope {
+            id,
+            command: RemoteCommand::LockTerminal { reason },
+            ..
+        }) => (reason, Some(id)),
+        _ => ("Lock state failed verification".to_string(), None),
+    };
+    Ok(TerminalLock {
+        locked: true,
+        reason: Some(reason),
+        command_id,
+        locked_at: Some(stored.locked_at),
+    })
+}
+
+/// ロック中は受け付けない操作の前に呼ぶ（ロック中はエラー）
+pub fn ensure_unlocked(operation: &str) -> Result<(), String> {
+    let lock = lock_state()?;
+    if lock.locked {
+        return Err(format!(
+            "{} is not available while the terminal is locked ({})",
+            operation,
+            lock.reason.unwrap_or_default()
+        ));
+    }
+    Ok(())
+}
+
+/// 署名を検証してコマンドを取り出す
+///
+/// 宛先・有効期限・実行済みかどうかも確認する
+fn verify(signed: &SignedRemoteCommand, terminal_id: &str, now: u64) -> Result<RemoteCommandEnvelope, String> {
+    let envelope: RemoteCommandEnvelope = server_key::open(signed)?;
+    check_envelope(&envelope, terminal_id, now, last_sequence()?)?;
+    Ok(envelope)
+}
+
+/// 宛先・有効期限・再実行を確認
+///
+/// 実行済みの最大の通番以下のコマンドは、履歴から消えた古いコマンドも含めて拒否する
+fn check_envelope(
+    envelope: &RemoteCommandEnvelope,
+    terminal_id: &str,
+    now: u64,
+    last_sequence: u64,
+) -> Result<(), String> {
+    if envelope.terminal_id != terminal_id {
+        return Err(format!("Remote command is for another terminal: {}", envelope.terminal_id));
+    }
+    if now > envelope.expires_at {
+        return Err(format!("Remote command has expired: {}", envelope.id));
+    }
+    if envelope.sequence <= last_sequence {
+        return Err(format!("Remote command was already executed: {}", envelope.id));
+    }
+    Ok(())
+}
+
+/// 署名付きコマンドを検証して実行する
+///
+/// 検証に失敗したコマンドは実行も記録もしない。実行に失敗した場合は失敗として記録する
+pub fn execute(signed: &SignedRemoteCommand) -> Result<RemoteCommandResult, String> {
+    let terminal_id = terminal_auth::get_terminal_status()
+        .map_err(|e| e.to_string())?
+        .terminal_id
+        .ok_or_else(|| "Terminal is not initialized".to_string())?;
+    let envelope = verify(signed, &terminal_id, storage::now_unix())?;
+    // 実行の途中で終了しても同じコマンドを再実行しないよう、先に通番を進める
+    storage::save_json(
+        SEQUENCE_FILE,
+        &SequenceState {
+            last_sequence: envelope.sequence,
+        },
+    )?;
+
+    let outcome = run(&envelope, signed);
+    let result = RemoteCommandResult {
+        id: envelope.id,
+        command: envelope.command.name().to_string(),
+        executed_at: storage::now_unix(),
+        success: outcome.is_ok(),
+        detail: match outcome {
+            Ok(detail) => detail,
+            Err(e) => Some(e),
+        },
+    };
+    record(&result)?;
+    Ok(result)
+}
+
+fn run(envelope: &RemoteCommandEnvelope, signed: &SignedRemoteCommand) -> Result<Option<String>, String> {
+    match &envelope.command {
+        RemoteCommand::ReprintClosingReport { report, printer } => {
+            let printer = printer
+                .clone()
+                .or(config::load()?.journal_printer)
+                .ok_or_else(|| "No printer specified".to_string())?;
+            let job = spooler::submit(
+                SpoolDocument::ClosingReport {
+                    report: report.clone(),
+                    totals: journal::totals()?,
+                },
+                vec![printer],
+            )?;
+            Ok(Some(job.id))
+        }
+        RemoteCommand::PushConfig { config } => {
+            config::save(config)?;
+            Ok(None)
+        }
+        RemoteCommand::LockTerminal { .. } => {
+            let lock = StoredLock {
+                command: signed.clone(),
+                locked_at: storage::now_unix(),
+            };
+            storage::save_json(LOCK_FILE, &lock)?;
+            Ok(None)
+        }
+        RemoteCommand::UnlockTerminal => {
+            storage::remove_file(LOCK_FILE)?;
+            Ok(None)
+        }
+        RemoteCommand::RequestDiagnostics => {
+            let diagnostics = Diagnostics {
+                status: heartbeat::collect()?,
+                heartbeat: heartbeat::status()?,
+                config: config::load()?,
+                printer_profiles: printer_profile::list()?,
+                journal_totals: journal::totals()?,
+                pending_print_jobs: spooler::pending().len(),
+                recent_commands: redaction::recent_commands(),
+            };
+            // サポートに渡せるよう、設定なども含めて伏せてから送る
+            let upload = upload_queue::enqueue(
+                KIND_DIAGNOSTICS,
+                &envelope.id,
+                &redaction::redacted(&diagnostics)?,
+            )?;
+            Ok(Some(upload.id))
+        }
+        RemoteCommand::ClearDuress => {
+            duress::clear()?;
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_support::TempDataDir;
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn envelope(sequence: u64, terminal_id: &str, expires_at: u64) -> RemoteCommandEnvelope {
+        RemoteCommandEnvelope {
+            id: format!("c{}", sequence),
+            sequence,
+            terminal_id: terminal_id.to_string(),
+            issued_at: 0,
+            expires_at,
+            command: RemoteCommand::UnlockTerminal,
+        }
+    }
+
+    /// テスト用のサーバー鍵を固定し、その鍵で署名したコマンドを作る
+    fn signed(server_key: &SigningKey, envelope: &RemoteCommandEnvelope) -> SignedRemoteCommand {
+        let payload = serde_json::to_string(envelope).unwrap();
+        SignedRemoteCommand {
+            signature: BASE64.encode(server_key.sign(payload.as_bytes()).to_bytes()),
+            payload,
+        }
+    }
+
+    fn pin_server_key() -> SigningKey {
+        let server_key = SigningKey::from_bytes(&[5u8; 32]);
+        server_key::pin(&BASE64.encode(server_key.verifying_key().to_bytes()), false).unwrap();
+        server_key
+    }
+
+    #[test]
+    fn test_check_envelope() {
+        assert!(check_envelope(&envelope(2, "T1", 200), "T1", 100, 1).is_ok());
+        assert!(check_envelope(&envelope(2, "T2", 200), "T1", 100, 1).is_err());
+        assert!(check_envelope(&envelope(2, "T1", 99), "T1", 100, 1).is_err());
+    }
+
+    #[test]
+    fn test_check_envelope_rejects_sequence_at_or_below_high_water() {
+        // 履歴の上限を超えて消えた古いコマンドも通番で拒否する
+        assert!(check_envelope(&envelope(1, "T1", 200), "T1", 100, 600).is_err());
+        assert!(check_envelope(&envelope(600, "T1", 200), "T1", 100, 600).is_err());
+        assert!(check_envelope(&envelope(601, "T1", 200), "T1", 100, 600).is_ok());
+    }
+
+    #[test]
+    fn test_command_payload_format() {
+        let payload = r#"{"id":"c1","sequence":1,"terminal_id":"T1","issued_at":1,"expires_at":2,"command":{"type":"lock_terminal","reason":"lost"}}"#;
+        let envelope: RemoteCommandEnvelope = serde_json::from_str(payload).unwrap();
+        assert!(matches!(envelope.command, RemoteCommand::LockTerminal { ref reason } if reason == "lost"));
+    }
+
+    #[test]
+    fn test_lock_survives_only_with_valid_signature() {
+        let _dir = TempDataDir::new();
+        let server_key = pin_server_key();
+        assert!(!lock_state().unwrap().locked);
+
+        let mut lock = envelope(1, "T1", u64::MAX);
+        lock.command = RemoteCommand::LockTerminal { reason: "lost".to_string() };
+        let command = signed(&server_key, &lock);
+        run(&lock, &command).unwrap();
+        let state = lock_state().unwrap();
+        assert!(state.locked);
+        assert_eq!(state.reason.as_deref(), Some("lost"));
+        assert_eq!(state.command_id.as_deref(), Some("c1"));
+
+        // 書き換えたロックは解除されず、検証に失敗したロックとして扱う
+        let mut stored: StoredLock = storage::load_json(LOCK_FILE).unwrap().unwrap();
+        stored.command.payload = stored.command.payload.replace("lock_terminal", "unlock_terminal");
+        storage::save_json(LOCK_FILE, &stored).unwrap();
+        let state = lock_state().unwrap();
+        assert!(state.locked);
+        assert_eq!(state.command_id, None);
+        assert!(ensure_unlocked("Sale").is_err());
+
+        let unlock = envelope(2, "T1", u64::MAX);
+        run(&unlock, &signed(&server_key, &unlock)).unwrap();
+        assert!(!lock_state().unwrap().locked);
+        assert!(ensure_unlocked("Sale").is_ok());
+    }
+}
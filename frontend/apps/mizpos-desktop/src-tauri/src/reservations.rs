@@ -0,0 +1,327 @@
+//! 予約（取り置き）モジュール
+//!
+//! 予約を端末内に登録し、受け取り時にQR・番号で検索して受け渡し済みにする
+//! 同じブースの複数端末で予約一覧を共有できるよう、サーバー経由の同期用に差分の取り出しとマージを行う
+
+use crate::event_context;
+use crate::receipt::ReceiptItem;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+/// 予約の保存ファイル名
+const RESERVATIONS_FILE: &str = "reservations.json";
+/// QRコードの接頭辞
+const RESERVATION_QR_PREFIX: &str = "MZR:";
+
+/// 予約の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ReservationStatus {
+    /// 受け取り待ち
+    Reserved,
+    /// 受け渡し済み
+    Redeemed,
+    /// キャンセル
+    Cancelled,
+}
+
+/// 予約
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Reservation {
+    /// 予約番号（端末間で重複しないようUUIDから生成）
+    pub code: String,
+    pub items: Vec<ReceiptItem>,
+    pub total: u32,
+    /// 予約者名
+    pub customer_name: Option<String>,
+    /// 受け取りに関するメモ（2日目受け取りなど）
+    pub pickup_note: Option<String>,
+    /// 元の見積番号
+    pub quote_code: Option<String>,
+    pub status: ReservationStatus,
+    /// 登録したスタッフ番号
+    pub staff_id: String,
+    /// 受け渡したスタッフ番号
+    pub redeemed_by: Option<String>,
+    /// 受け渡し日時（UNIX秒）
+    pub redeemed_at: Option<u64>,
+    /// 登録日時（UNIX秒）
+    pub created_at: u64,
+    /// 最終更新日時（UNIX秒、同期時のマージに使用）
+    pub updated_at: u64,
+}
+
+impl Reservation {
+    /// QRコードに埋め込む文字列
+    pub fn qr_data(&self) -> String {
+        format!("{}{}", RESERVATION_QR_PREFIX, self.code)
+    }
+}
+
+fn load_reservations() -> Result<Vec<Reservation>, String> {
+    Ok(storage::load_json(&event_context::scoped(RESERVATIONS_FILE)?)?.unwrap_or_default())
+}
+
+fn save_reservations(reservations: &[Reservation]) -> Result<(), String> {
+    storage::save_json(&event_context::scoped(RESERVATIONS_FILE)?, &reservations)
+}
+
+/// 予約を登録
+pub fn register(
+    items: Vec<ReceiptItem>,
+    customer_name: Option<String>,
+    pickup_note: Option<String>,
+    quote_code: Option<String>,
+    staff_id: &str,
+) -> Result<Reservation, String> {
+    if items.is_empty() {
+        return Err("Reservation has no items".to_string());
+    }
+
+    let now = storage::now_unix();
+    let code = Uuid::new_v4().simple().to_string()[..8].to_uppercase();
+    let reservation = Reservation {
+        code: format!("R{}", code),
+        total: items.iter().map(|i| i.price).sum(),
+        items,
+        customer_name,
+        pickup_note,
+        quote_code,
+        status: ReservationStatus::Reserved,
+        staff_id: staff_id.to_string(),
+        redeemed_by: None,
+        redeemed_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let mut reservations = load_reservations()?;
+    reservations.push(reservation.clone());
+    save_reservations(&reservations)?;
+    Ok(reservation)
+}
+
+/// 予約番号またはQRコードの文字列から予約を取得
+pub fn find(code_or_qr: &str) -> Result<Option<Reservation>, String> {
+    let code = code_or_qr
+        .strip_prefix(RESERVATION_QR_PREFIX)
+        .unwrap_or(code_or_qr);
+    Ok(load_reservations()?.into_iter().find(|r| r.code == code))
+}
+
+/// 予約一覧
+pub fn list() -> Result<Vec<Reservation>, String> {
+    load_reservations()
+}
+
+fn update_status(
+    code: &str,
+    status: ReservationStatus,
+    staff_id: &str,
+) -> Result<Reservation, String> {
+    let mut reservations = load_reservations()?;
+    let reservation = reservations
+        .iter_mut()
+        .find(|r| r.code == code)
+        .ok_or_else(|| format!("Reservation not found: {}", code))?;
+
+    if reservation.status != ReservationStatus::Reserved {
+        return Err(format!("Reservation is not pending: {}", code));
+    }
+
+    let now = storage::now_unix();
+    reservation.status = status;
+    reservation.updated_at = now;
+    if status == ReservationStatus::Redeemed {
+        reservation.redeemed_by = Some(staff_id.to_string());
+        reservation.redeemed_at = Some(now);
+    }
+
+    let updated = reservation.clone();
+    save_reservations(&reservations)?;
+    Ok(updated)
+}
+
+/// 受け渡し済みにする
+pub fn redeem(code: &str, staff_id: &str) -> Result<Reservation, String> {
+    update_status(code, ReservationStatus::Redeemed, staff_id)
+}
+
+/// キャンセルする
+pub fn cancel(code: &str, staff_id: &str) -> Result<Reservation, String> {
+    update_status(code, ReservationStatus::Cancelled, staff_id)
+}
+
+/// 指定日時以降に更新された予約を取得（同期の送信用）
+pub fn changed_since(since: u64) -> Result<Vec<Reservation>, String> {
+    Ok(load_reservations()?
+        .into_iter()
+        .filter(|r| r.updated_at >= since)
+        .collect())
+}
+
+/// マージで優先する順（受け渡し済み > キャンセル > 受け取り待ち）
+///
+/// 受け渡し済み・キャンセルは取り消せず、同時に受け渡しとキャンセルがされた場合は
+/// 商品が既に渡っているため受け渡し済みを残す
+fn merge_rank(status: ReservationStatus) -> u8 {
+    match status {
+        ReservationStatus::Reserved => 0,
+        ReservationStatus::Cancelled => 1,
+        ReservationStatus::Redeemed => 2,
+    }
+}
+
+/// 他端末の予約をマージ（同期の受信用）
+///
+/// 状態が進んでいる方を優先し、同じ状態であれば更新日時が新しい方を採用する
+pub fn merge(remote: Vec<Reservation>) -> Result<usize, String> {
+    let mut reservations = load_reservations()?;
+    let mut changed = 0;
+
+    for incoming in remote {
+        match reservations.iter_mut().find(|r| r.code == incoming.code) {
+            Some(local) => {
+                let local_rank = merge_rank(local.status);
+                let incoming_rank = merge_rank(incoming.status);
+                let take = incoming_rank > local_rank
+                    || (incoming_rank == local_rank && incoming.updated_at > local.updated_at);
+                if take {
+                    *local = incoming;
+                    changed += 1;
+                }
+            }
+            None => {
+                reservations.push(incoming);
+                changed += 1;
+            }
+        }
+    }
+
+    if changed > 0 {
+        save_reservations(&reservations)?;
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_support::TempDataDir;
+    use ReservationStatus::{Cancelled, Redeemed, Reserved};
+
+    fn reservation(code: &str, status: ReservationStatus, updated_at: u64) -> Reservation {
+        Reservation {
+            code: code.to_string(),
+            items: vec![],
+            total: 1000,
+            customer_name: None,
+            pickup_note: None,
+            quote_code: None,
+            status,
+            staff_id: "S001".to_string(),
+            redeemed_by: None,
+            redeemed_at: None,
+            created_at: 1,
+            updated_at,
+        }
+    }
+
+    fn with_note(mut r: Reservation, note: &str) -> Reservation {
+        r.pickup_note = Some(note.to_string());
+        r
+    }
+
+    fn stored(code: &str) -> Reservation {
+        find(code).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_merge_adds_remote_reservations() {
+        let _dir = TempDataDir::new();
+        let changed = merge(vec![reservation("R1", Reserved, 10), reservation("R2", Redeemed, 10)]);
+        assert_eq!(changed, Ok(2));
+        assert_eq!(list().unwrap().len(), 2);
+        assert_eq!(stored("MZR:R2").status, Redeemed);
+    }
+
+    #[test]
+    fn test_merge_concurrent_edits_keep_newest() {
+        let _dir = TempDataDir::new();
+        merge(vec![with_note(reservation("R1", Reserved, 10), "local")]).unwrap();
+
+        // 古い編集は無視する
+        assert_eq!(merge(vec![with_note(reservation("R1", Reserved, 5), "old")]), Ok(0));
+        // 同じ更新日時は手元を残す
+        assert_eq!(merge(vec![with_note(reservation("R1", Reserved, 10), "same")]), Ok(0));
+        assert_eq!(stored("R1").pickup_note.as_deref(), Some("local"));
+
+        assert_eq!(merge(vec![with_note(reservation("R1", Reserved, 20), "new")]), Ok(1));
+        assert_eq!(stored("R1").pickup_note.as_deref(), Some("new"));
+    }
+
+    #[test]
+    fn test_merge_cancellation_is_not_undone() {
+        let _dir = TempDataDir::new();
+        merge(vec![reservation("R1", Reserved, 30), reservation("R2", Cancelled, 10)]).unwrap();
+
+        // 他端末でのキャンセルは手元の新しい編集より優先する
+        assert_eq!(merge(vec![reservation("R1", Cancelled, 20)]), Ok(1));
+        assert_eq!(stored("R1").status, Cancelled);
+
+        // キャンセル済みの予約は、新しい受け取り待ちの編集で復活しない
+        assert_eq!(merge(vec![reservation("R2", Reserved, 99)]), Ok(0));
+        assert_eq!(stored("R2").status, Cancelled);
+    }
+
+    #[test]
+    fn test_merge_redeem_wins_over_concurrent_cancel() {
+        let _dir = TempDataDir::new();
+        merge(vec![reservation("R1", Cancelled, 50), reservation("R2", Redeemed, 10)]).unwrap();
+
+        assert_eq!(merge(vec![reservation("R1", Redeemed, 40)]), Ok(1));
+        assert_eq!(stored("R1").status, Redeemed);
+        assert_eq!(merge(vec![reservation("R2", Cancelled, 60)]), Ok(0));
+        assert_eq!(stored("R2").status, Redeemed);
+    }
+
+    #[test]
+    fn test_merge_is_idempotent() {
+        let _dir = TempDataDir::new();
+        let remote = vec![reservation("R1", Reserved, 10), reservation("R2", Cancelled, 20)];
+        assert_eq!(merge(remote.clone()), Ok(2));
+        assert_eq!(merge(remote), Ok(0));
+        assert_eq!(changed_since(15).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_redeem_only_pending_reservation() {
+        let _dir = TempDataDir::new();
+        let registered = register(
+            vec![serde_json::from_value(serde_json::json!({
+                "circle_name": "サークルみず",
+                "name": "新刊",
+                "jan": "",
+                "isbn": "",
+                "isdn": null,
+                "jan2": null,
+                "is_book": true,
+                "quantity": 1,
+                "price": 1000,
+            }))
+            .unwrap()],
+            Some("みず".to_string()),
+            None,
+            None,
+            "S001",
+        )
+        .unwrap();
+
+        let redeemed = redeem(&registered.code, "S002").unwrap();
+        assert_eq!(redeemed.redeemed_by.as_deref(), Some("S002"));
+        assert!(redeem(&registered.code, "S002").is_err());
+        assert!(cancel(&registered.code, "S002").is_err());
+    }
+}
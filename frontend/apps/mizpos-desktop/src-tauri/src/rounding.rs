@@ -0,0 +1,180 @@
+//! 端数調整モジュール
+//!
+//! 会計の合計（キャンペーンの割引後）を端末設定の単位（10円など）に丸め、差額を端数値引（切り上げの場合は端数調整）として
+//! レシートとジャーナルの売上に1行で残す
+//! 会計時にフロントエンドが見積もりの結果をレシートに入れ、印刷前に端末設定で計算し直して照合する
+//! 内金・残金の売上は丸めない
+
+use crate::config;
+use crate::receipt::{PaymentInfo, ReceiptData};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 丸め方
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    /// 切り捨て（端数値引）
+    #[default]
+    Down,
+    /// 四捨五入
+    Nearest,
+    /// 切り上げ
+    Up,
+}
+
+/// 端数調整の設定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct RoundingConfig {
+    pub enabled: bool,
+    /// 丸める単位（円）
+    pub unit: u32,
+    pub mode: RoundingMode,
+    /// 現金だけで支払う会計のみ丸める（カード・電子マネーを含む会計は丸めない）
+    pub cash_only: bool,
+}
+
+impl Default for RoundingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            unit: 10,
+            mode: RoundingMode::Down,
+            cash_only: true,
+        }
+    }
+}
+
+/// 端数調整の見積もり（フロントエンドの会計画面用）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Type)]
+pub struct RoundingQuote {
+    /// 丸める前の合計
+    pub before: u32,
+    /// 端数調整額（値引はマイナス）
+    pub adjustment: i32,
+    /// 丸めた後の合計
+    pub total: u32,
+}
+
+/// 金額を丸めた差額
+pub fn adjustment(config: &RoundingConfig, amount: u32) -> i32 {
+    if !config.enabled || config.unit <= 1 {
+        return 0;
+    }
+    let remainder = amount % config.unit;
+    if remainder == 0 {
+        return 0;
+    }
+    let round_up = match config.mode {
+        RoundingMode::Down => false,
+        RoundingMode::Nearest => remainder * 2 >= config.unit,
+        RoundingMode::Up => true,
+    };
+    if round_up {
+        (config.unit - remainder) as i32
+    } else {
+        -(remainder as i32)
+    }
+}
+
+/// 端末設定の丸め方で会計を見積もる
+///
+/// amount: キャンペーンの割引後の合計、cash_only_payment: 現金だけで支払うか
+pub fn quote(amount: u32, cash_only_payment: bool) -> Result<RoundingQuote, String> {
+    let config = config::load()?.rounding;
+    let adjustment = if config.cash_only && !cash_only_payment {
+        0
+    } else {
+        adjustment(&config, amount)
+    };
+    Ok(RoundingQuote {
+        before: amount,
+        adjustment,
+        total: amount.saturating_add_signed(adjustment),
+    })
+}
+
+/// レシートの丸める前の合計（明細の小計からキャンペーンの割引を引いた額）
+fn amount_before(receipt: &ReceiptData) -> u32 {
+    let subtotal: u32 = receipt.items.iter().map(|i| i.price).sum();
+    let discount: u32 = receipt.promotions.iter().map(|p| p.discount).sum();
+    subtotal.saturating_sub(discount)
+}
+
+/// レシートの内容から求めた端数調整額
+fn expected(config: &RoundingConfig, receipt: &ReceiptData) -> i32 {
+    let cash_only_payment = !receipt.payments.is_empty() && receipt.payments.iter().all(PaymentInfo::is_cash);
+    if receipt.deposit.is_some() || (config.cash_only && !cash_only_payment) {
+        return 0;
+    }
+    adjustment(config, amount_before(receipt))
+}
+
+fn check(config: &RoundingConfig, receipt: &ReceiptData) -> Result<(), String> {
+    let expected = expected(config, receipt);
+    if receipt.rounding_adjustment != expected {
+        return Err(format!(
+            "Rounding adjustment does not match the terminal settings: {} != {}",
+            receipt.rounding_adjustment, expected
+        ));
+    }
+    if expected != 0 && receipt.total != amount_before(receipt).saturating_add_signed(expected) {
+        return Err("Total does not match the rounded amount".to_string());
+    }
+    Ok(())
+}
+
+/// 印刷前にレシートの端数調整を確認する
+pub fn verify(receipt: &ReceiptData) -> Result<(), String> {
+    check(&config::load()?.rounding, receipt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipt::test_support;
+    use serde_json::json;
+
+    fn rounding(mode: RoundingMode) -> RoundingConfig {
+        RoundingConfig {
+            enabled: true,
+            mode,
+            ..Default::default()
+        }
+    }
+
+    fn receipt(total: u32, adjustment: i32, method: &str) -> ReceiptData {
+        test_support::receipt("R-1")
+            .item(json!({ "name": "ステッカー", "jan": "4900000000028", "is_book": false, "quantity": 3, "price": 1234 }))
+            .with(json!({
+                "total": total,
+                "payments": [{ "method": method, "amount": total }],
+                "rounding_adjustment": adjustment,
+            }))
+            .build()
+    }
+
+    #[test]
+    fn test_adjustment_by_mode() {
+        assert_eq!(adjustment(&rounding(RoundingMode::Down), 1234), -4);
+        assert_eq!(adjustment(&rounding(RoundingMode::Nearest), 1234), -4);
+        assert_eq!(adjustment(&rounding(RoundingMode::Nearest), 1235), 5);
+        assert_eq!(adjustment(&rounding(RoundingMode::Up), 1231), 9);
+        assert_eq!(adjustment(&rounding(RoundingMode::Up), 1230), 0);
+        assert_eq!(adjustment(&RoundingConfig::default(), 1234), 0);
+    }
+
+    #[test]
+    fn test_check_requires_matching_adjustment_and_total() {
+        let config = rounding(RoundingMode::Down);
+        assert!(check(&config, &receipt(1230, -4, "現金")).is_ok());
+        // 丸めていない・合計が合わない
+        assert!(check(&config, &receipt(1234, 0, "現金")).is_err());
+        assert!(check(&config, &receipt(1234, -4, "現金")).is_err());
+        // 現金だけの会計のみ丸める
+        assert!(check(&config, &receipt(1234, 0, "クレジットカード")).is_ok());
+        assert!(check(&config, &receipt(1230, -4, "クレジットカード")).is_err());
+        assert!(check(&RoundingConfig::default(), &receipt(1234, 0, "現金")).is_ok());
+    }
+}
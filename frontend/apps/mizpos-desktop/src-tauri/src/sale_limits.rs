@@ -0,0 +1,154 @@
+//! 売上の上限チェックモジュール
+//!
+//! 1明細の数量・1会計の合計・釣り銭に上限を設け、打ち間違い（¥55,000,000 のレシートなど）を印刷・記録前に止める
+//! 上限を超える売上は責任者の承認がある場合だけ受け付け、承認はジャーナルに記録する
+
+use crate::config;
+use crate::journal::{self, JournalRecord};
+use crate::receipt::ReceiptData;
+use crate::staff_auth::{self, SupervisorApproval};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 売上の上限設定
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct SaleLimitsConfig {
+    /// 1明細の数量の上限
+    pub max_line_quantity: u32,
+    /// 1会計の合計金額の上限
+    pub max_total: u32,
+    /// 釣り銭の上限
+    pub max_change: u32,
+}
+
+impl Default for SaleLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_line_quantity: 100,
+            max_total: 500_000,
+            max_change: 50_000,
+        }
+    }
+}
+
+/// 上限の超過
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LimitViolation {
+    LineQuantity { jan: String, quantity: u32, max: u32 },
+    Total { total: u32, max: u32 },
+    Change { change: u32, max: u32 },
+}
+
+impl std::fmt::Display for LimitViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LineQuantity { jan, quantity, max } => {
+                write!(f, "quantity of {} is {} (max {})", jan, quantity, max)
+            }
+            Self::Total { total, max } => write!(f, "total is {} (max {})", total, max),
+            Self::Change { change, max } => write!(f, "change is {} (max {})", change, max),
+        }
+    }
+}
+
+/// 上限を超えている項目を列挙
+fn violations(limits: &SaleLimitsConfig, receipt: &ReceiptData) -> Vec<LimitViolation> {
+    let mut violations: Vec<LimitViolation> = receipt
+        .items
+        .iter()
+        .filter(|i| i.quantity > limits.max_line_quantity)
+        .map(|i| LimitViolation::LineQuantity {
+            jan: i.jan.clone(),
+            quantity: i.quantity,
+            max: limits.max_line_quantity,
+        })
+        .collect();
+    if receipt.total > limits.max_total {
+        violations.push(LimitViolation::Total {
+            total: receipt.total,
+            max: limits.max_total,
+        });
+    }
+    let paid: u64 = receipt.payments.iter().map(|p| p.amount as u64).sum();
+    let change = paid.saturating_sub(receipt.total as u64);
+    if change > limits.max_change as u64 {
+        violations.push(LimitViolation::Change {
+            change: u32::try_from(change).unwrap_or(u32::MAX),
+            max: limits.max_change,
+        });
+    }
+    violations
+}
+
+/// 売上が上限内か確認（超えている場合は責任者の承認を確認してジャーナルに記録する）
+pub fn check(receipt: &ReceiptData, approval: Option<&SupervisorApproval>) -> Result<(), String> {
+    let violations = violations(&config::load()?.sale_limits, receipt);
+    if violations.is_empty() {
+        return Ok(());
+    }
+    let Some(approval) = approval else {
+        let details: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        return Err(format!(
+            "Sale exceeds limits, supervisor approval required: {}",
+            details.join(", ")
+        ));
+    };
+    staff_auth::verify_supervisor(approval)?;
+    journal::append(
+        Some(&receipt.staff_id),
+        JournalRecord::LimitOverride {
+            receipt_number: receipt.receipt_number.clone(),
+            violations,
+            supervisor_id: approval.supervisor_id.clone(),
+        },
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipt::test_support;
+    use serde_json::json;
+
+    fn receipt(quantity: u32, total: u32, paid: u32) -> ReceiptData {
+        test_support::receipt("R-1")
+            .item(json!({ "quantity": quantity, "price": total }))
+            .with(json!({ "payments": [{ "method": "現金", "amount": paid }] }))
+            .build()
+    }
+
+    #[test]
+    fn test_violations_cover_quantity_total_and_change() {
+        let limits = SaleLimitsConfig::default();
+        assert!(violations(&limits, &receipt(2, 2000, 10000)).is_empty());
+
+        // ¥55,000 を ¥55,000,000 と打ち間違え、数量も 1 を 100 にした場合
+        let typo = violations(&limits, &receipt(1000, 55_000_000, 55_000_000));
+        assert_eq!(
+            typo,
+            vec![
+                LimitViolation::LineQuantity {
+                    jan: "4900000000011".to_string(),
+                    quantity: 1000,
+                    max: 100
+                },
+                LimitViolation::Total {
+                    total: 55_000_000,
+                    max: 500_000
+                },
+            ]
+        );
+
+        let change = violations(&limits, &receipt(1, 1000, 100_000));
+        assert_eq!(
+            change,
+            vec![LimitViolation::Change {
+                change: 99_000,
+                max: 50_000
+            }]
+        );
+    }
+}
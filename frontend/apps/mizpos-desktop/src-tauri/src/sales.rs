@@ -0,0 +1,82 @@
+//! 売上確定モジュール
+//!
+//! 印刷・スプーラー・キオスク・Androidの各経路で、売上を記録する前の確認と付与を同じ順序で行う
+//! カード情報の絞り込み → 支払の検証 → 価格変更の照合 → 年齢確認 → 上限チェック → 内金・残金 → 会員 → キャンペーン → 端数調整 → ポイント → ジャーナル
+//! どこかで失敗した場合は売上を記録せず、レシートも印刷しない
+
+use crate::age_check;
+use crate::config;
+use crate::deposits;
+use crate::journal::{self, SaleChannel};
+use crate::members;
+use crate::points;
+use crate::price_book;
+use crate::price_overrides;
+use crate::promotions;
+use crate::receipt::ReceiptData;
+use crate::rounding;
+use crate::sale_limits;
+use crate::staff_auth::SupervisorApproval;
+use crate::tenders;
+
+/// 売上を確認してレシートに会員・キャンペーン・ポイントを入れ、ジャーナルに記録する
+///
+/// 同じレシート番号の再送ではジャーナルとポイントを重ねて記録しない
+pub fn prepare_sale(
+    receipt: &mut ReceiptData,
+    limit_approval: Option<&SupervisorApproval>,
+    channel: SaleChannel,
+) -> Result<(), String> {
+    // カード情報は保存・印刷してよい範囲に絞る
+    receipt.sanitize_card_details();
+    tenders::validate_receipt(&config::load()?.voucher_catalog, receipt)?;
+    price_overrides::verify(receipt)?;
+    price_book::stamp(receipt)?;
+    age_check::check(receipt)?;
+    sale_limits::check(receipt, limit_approval)?;
+    deposits::check(receipt)?;
+    members::personalize(receipt)?;
+    promotions::apply(receipt)?;
+    // セルフレジはカゴの合計で決済するため丸めない
+    if channel == SaleChannel::Counter {
+        rounding::verify(receipt)?;
+    }
+    points::accrue(receipt)?;
+    journal::record_sale_via(receipt, channel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipt::test_support;
+    use crate::storage::test_support::TempDataDir;
+    use serde_json::json;
+
+    fn receipt(receipt_number: &str, age_restricted: bool) -> ReceiptData {
+        test_support::receipt(receipt_number)
+            .item(json!({ "age_restricted": age_restricted }))
+            .build()
+    }
+
+    #[test]
+    fn test_prepare_sale_records_once() {
+        let _dir = TempDataDir::new();
+        let mut sale = receipt("R-1", false);
+        prepare_sale(&mut sale, None, SaleChannel::Counter).unwrap();
+        prepare_sale(&mut sale, None, SaleChannel::Counter).unwrap();
+        let sales = journal::entries()
+            .unwrap()
+            .into_iter()
+            .filter(|e| matches!(e.record, journal::JournalRecord::Sale { .. }))
+            .count();
+        assert_eq!(sales, 1);
+    }
+
+    #[test]
+    fn test_failed_check_records_nothing() {
+        let _dir = TempDataDir::new();
+        let mut sale = receipt("R-1", true);
+        assert!(prepare_sale(&mut sale, None, SaleChannel::Kiosk).is_err());
+        assert!(journal::find_sale("R-1").unwrap().is_none());
+    }
+}
@@ -0,0 +1,142 @@
+//! 売上目標モジュール
+//!
+//! イベントごとに売上目標（と損益分岐点）を設定し、ジャーナルの純売上と比べて進捗を出す
+//! サークルが当日中に頒布の進み具合や赤字を抜けたかを確認できるよう、進捗を短い帳票でも印刷する
+
+use crate::event_context;
+use crate::journal::{self, JournalTotals};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 売上目標の保存ファイル名
+const SALES_TARGET_FILE: &str = "sales_target.json";
+
+/// 売上目標
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct SalesTarget {
+    /// 目標の純売上（税込）
+    pub target_amount: u64,
+    /// 損益分岐点の純売上（頒布物の製作費など）
+    pub break_even_amount: Option<u64>,
+    /// 設定したスタッフ番号
+    pub updated_by: Option<String>,
+    /// 設定日時（UNIX秒）
+    pub updated_at: u64,
+}
+
+/// 目標に対する進捗
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct SalesProgress {
+    pub target: Option<SalesTarget>,
+    /// 純売上（売上 - 返品 - 取消）
+    pub net_amount: i64,
+    pub sale_count: u32,
+    /// 目標に対する達成率（%、目標がない場合は None）
+    pub achieved_percent: Option<u32>,
+    /// 目標までの残り（達成済みの場合は 0）
+    pub remaining_amount: Option<u64>,
+    /// 損益分岐点を超えたか（損益分岐点がない場合は None）
+    pub break_even_reached: Option<bool>,
+}
+
+/// 設定中の売上目標
+pub fn current() -> Result<Option<SalesTarget>, String> {
+    storage::load_json(&event_context::scoped(SALES_TARGET_FILE)?)
+}
+
+/// 売上目標を設定する（目標が 0 の場合は設定を消す）
+pub fn set(
+    target_amount: u64,
+    break_even_amount: Option<u64>,
+    staff_id: Option<&str>,
+) -> Result<Option<SalesTarget>, String> {
+    let file = event_context::scoped(SALES_TARGET_FILE)?;
+    if target_amount == 0 {
+        storage::remove_file(&file)?;
+        return Ok(None);
+    }
+    let target = SalesTarget {
+        target_amount,
+        break_even_amount: break_even_amount.filter(|a| *a > 0),
+        updated_by: staff_id.map(|s| s.to_string()),
+        updated_at: storage::now_unix(),
+    };
+    storage::save_json(&file, &target)?;
+    Ok(Some(target))
+}
+
+/// ジャーナルの集計から進捗を求める
+fn progress_of(target: Option<SalesTarget>, totals: &JournalTotals) -> SalesProgress {
+    let net = totals.net_amount;
+    let reached = |amount: u64| net >= 0 && net as u64 >= amount;
+    SalesProgress {
+        achieved_percent: target
+            .as_ref()
+            .map(|t| (net.max(0) as u64 * 100 / t.target_amount) as u32),
+        remaining_amount: target
+            .as_ref()
+            .map(|t| t.target_amount.saturating_sub(net.max(0) as u64)),
+        break_even_reached: target
+            .as_ref()
+            .and_then(|t| t.break_even_amount)
+            .map(reached),
+        target,
+        net_amount: net,
+        sale_count: totals.sale_count,
+    }
+}
+
+/// 現在の進捗
+pub fn progress() -> Result<SalesProgress, String> {
+    Ok(progress_of(current()?, &journal::totals()?))
+}
+
+/// 達成率の棒グラフ（10マス、100%を超えても10マス）
+pub fn progress_bar(percent: u32) -> String {
+    let filled = (percent / 10).min(10) as usize;
+    format!("{}{}", "■".repeat(filled), "□".repeat(10 - filled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(target_amount: u64, break_even_amount: Option<u64>) -> Option<SalesTarget> {
+        Some(SalesTarget {
+            target_amount,
+            break_even_amount,
+            updated_by: None,
+            updated_at: 0,
+        })
+    }
+
+    fn totals(net_amount: i64) -> JournalTotals {
+        JournalTotals {
+            sale_count: 3,
+            net_amount,
+            ..JournalTotals::default()
+        }
+    }
+
+    #[test]
+    fn test_progress_against_target_and_break_even() {
+        let progress = progress_of(target(50_000, Some(20_000)), &totals(26_000));
+        assert_eq!(progress.achieved_percent, Some(52));
+        assert_eq!(progress.remaining_amount, Some(24_000));
+        assert_eq!(progress.break_even_reached, Some(true));
+        assert_eq!(progress_bar(52), "■■■■■□□□□□");
+
+        let over = progress_of(target(10_000, None), &totals(12_500));
+        assert_eq!((over.achieved_percent, over.remaining_amount), (Some(125), Some(0)));
+        assert_eq!(over.break_even_reached, None);
+        assert_eq!(progress_bar(125), "■■■■■■■■■■");
+
+        // 返品が売上を上回っても0%で止める
+        let refunded = progress_of(target(10_000, Some(1)), &totals(-500));
+        assert_eq!(refunded.achieved_percent, Some(0));
+        assert_eq!(refunded.break_even_reached, Some(false));
+
+        assert_eq!(progress_of(None, &totals(1_000)).achieved_percent, None);
+    }
+}
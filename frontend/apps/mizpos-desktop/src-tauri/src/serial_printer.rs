@@ -0,0 +1,166 @@
+//! シリアルプリンターモジュール
+//!
+//! RS-232 や USB-シリアル変換でしか繋げない古いレシートプリンター（Star・Citizen など）へ、ESC/POS をシリアルポートで送る
+//! ネットワークプリンターと同じく、設定したプリンターを仮想のベンダーIDのデバイスとして一覧に出し、
+//! 既存の印刷コマンド（ベンダーID・デバイスIDの指定）からそのまま使えるようにする
+//! macOS・Windows ではペアリングした Bluetooth（SPP）のプリンターもシリアルポートとして現れるため、
+//! モバイルプリンター（SII MP-B20 など）への印刷も同じ経路で送る
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// シリアルプリンターに割り当てる仮想のベンダーID（USBでは予約済みの値、ネットワークプリンターの次）
+pub const SERIAL_VENDOR_ID: u16 = 0xfffe;
+
+/// フロー制御
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SerialFlowControl {
+    /// なし
+    None,
+    /// RTS/CTS（多くのレシートプリンターの既定）
+    #[default]
+    Hardware,
+    /// XON/XOFF
+    Software,
+}
+
+/// シリアルプリンターの設定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct SerialPrinter {
+    /// 一覧に出す名前
+    pub name: String,
+    /// ポート（COM3、/dev/ttyUSB0 など）
+    pub port: String,
+    /// 通信速度（プリンター本体のディップスイッチの設定に合わせる）
+    #[serde(default = "default_baud_rate")]
+    pub baud_rate: u32,
+    #[serde(default)]
+    pub flow_control: SerialFlowControl,
+}
+
+fn default_baud_rate() -> u32 {
+    9600
+}
+
+/// 端末で見つかったシリアルポート
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SerialPortInfo {
+    /// ポート（設定の port に指定する値）
+    pub port: String,
+    /// USB-シリアル変換の製品名など（分かる場合のみ）
+    pub description: Option<String>,
+}
+
+/// Bluetooth（SPP）プリンターの接続状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum BluetoothLinkStatus {
+    /// ペアリングされていない（OSにポートがない）
+    NotPaired,
+    /// ペアリング済みだが接続できない（電源が切れている・圏外など）
+    Paired,
+    /// 接続できる
+    Connected,
+}
+
+/// Bluetooth（SPP）のシリアルポート
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct BluetoothSerialPort {
+    /// ポート（COM5、/dev/cu.MP-B20-SerialPort など）
+    pub port: String,
+    /// 一覧に出す名前（macOS ではポート名に含まれる機器名）
+    pub name: String,
+}
+
+/// macOS が常に作る、プリンターではない Bluetooth 関連のポート
+const MACOS_NON_PRINTER_PORTS: [&str; 2] = ["Bluetooth-Incoming-Port", "debug-console"];
+
+/// Bluetooth（SPP）のポートなら一覧に出す形にする
+///
+/// - `reported_bluetooth`: OS が Bluetooth のポートと報告しているか（Windows）
+///
+/// macOS はポートの種類を報告しないため、USB 以外の /dev/cu.* をペアリングした機器とみなす
+/// Linux は rfcomm でバインドしたポートを使う
+pub fn bluetooth_port(port: &str, reported_bluetooth: bool) -> Option<BluetoothSerialPort> {
+    if let Some(name) = port.strip_prefix("/dev/cu.") {
+        let is_usb = name.starts_with("usb") || name.contains("SLAB_USB") || name.contains("wchusbserial");
+        if is_usb || MACOS_NON_PRINTER_PORTS.contains(&name) {
+            return None;
+        }
+        return Some(BluetoothSerialPort {
+            port: port.to_string(),
+            name: name.trim_end_matches("-SerialPort").to_string(),
+        });
+    }
+    if reported_bluetooth || port.starts_with("/dev/rfcomm") {
+        return Some(BluetoothSerialPort {
+            port: port.to_string(),
+            name: port.to_string(),
+        });
+    }
+    None
+}
+
+/// Bluetooth（SPP）のポートに送るときの設定
+///
+/// 通信速度とフロー制御は無線側（RFCOMM）で決まるため、ポートに指定する値は使われない
+pub fn bluetooth_printer(port: &BluetoothSerialPort) -> SerialPrinter {
+    SerialPrinter {
+        name: port.name.clone(),
+        port: port.port.clone(),
+        baud_rate: default_baud_rate(),
+        flow_control: SerialFlowControl::None,
+    }
+}
+
+/// 一覧に出すデバイスIDとプリンターの組（デバイスIDは設定の並び順）
+pub fn devices(printers: &[SerialPrinter]) -> impl Iterator<Item = (u16, &SerialPrinter)> {
+    printers
+        .iter()
+        .take(usize::from(u16::MAX))
+        .enumerate()
+        .map(|(i, p)| (i as u16, p))
+}
+
+/// ベンダーID・デバイスIDに対応するシリアルプリンターを探す
+pub fn find(printers: &[SerialPrinter], vendor_id: u16, device_id: u16) -> Option<&SerialPrinter> {
+    if vendor_id != SERIAL_VENDOR_ID {
+        return None;
+    }
+    printers.get(usize::from(device_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_find_maps_virtual_ids_to_configured_printers() {
+        let printers: Vec<SerialPrinter> = serde_json::from_value(json!([
+            { "name": "旧レジ", "port": "COM3" },
+            { "name": "Star", "port": "/dev/ttyUSB0", "baud_rate": 19200, "flow_control": "software" },
+        ]))
+        .unwrap();
+        assert_eq!(printers[0].baud_rate, 9600);
+        assert_eq!(printers[0].flow_control, SerialFlowControl::Hardware);
+
+        assert_eq!(find(&printers, SERIAL_VENDOR_ID, 1).map(|p| p.baud_rate), Some(19200));
+        assert!(find(&printers, SERIAL_VENDOR_ID, 2).is_none());
+        assert!(find(&printers, crate::network_printer::NETWORK_VENDOR_ID, 0).is_none());
+    }
+
+    #[test]
+    fn test_bluetooth_port_detection() {
+        let mp_b20 = bluetooth_port("/dev/cu.MP-B20-SerialPort", false).unwrap();
+        assert_eq!(mp_b20.name, "MP-B20");
+        assert!(bluetooth_port("/dev/cu.Bluetooth-Incoming-Port", false).is_none());
+        assert!(bluetooth_port("/dev/cu.usbserial-1410", false).is_none());
+        assert!(bluetooth_port("/dev/tty.MP-B20-SerialPort", false).is_none());
+
+        assert_eq!(bluetooth_port("COM7", true).map(|p| p.name), Some("COM7".to_string()));
+        assert!(bluetooth_port("COM3", false).is_none());
+        assert!(bluetooth_port("/dev/rfcomm0", false).is_some());
+    }
+}
@@ -0,0 +1,122 @@
+//! サーバー公開鍵モジュール
+//!
+//! 管理サーバーのEd25519公開鍵を端末に固定（ピン留め）し、サーバーから届いた署名付きデータを検証する
+//! 鍵は端末登録時にサーバーから受け取って固定する
+//! 一度固定した鍵は、責任者の承認なしには別の鍵に置き換えられない
+
+use crate::storage;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 固定した鍵の保存ファイル名
+const SERVER_KEY_FILE: &str = "server_key.json";
+
+/// 固定したサーバー公開鍵
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PinnedServerKey {
+    /// Base64エンコードされた公開鍵
+    pub public_key: String,
+    /// 固定した日時（UNIX秒）
+    pub pinned_at: u64,
+}
+
+/// サーバーから届く署名付きデータ
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SignedPayload {
+    /// JSON文字列（署名対象）
+    pub payload: String,
+    /// サーバー鍵による署名（Base64）
+    pub signature: String,
+}
+
+/// 固定した鍵を取得
+pub fn pinned() -> Result<Option<PinnedServerKey>, String> {
+    storage::load_json(SERVER_KEY_FILE)
+}
+
+/// Base64の公開鍵を読み取る
+fn parse_public_key(public_key: &str) -> Result<VerifyingKey, String> {
+    let bytes = BASE64
+        .decode(public_key.trim())
+        .map_err(|e| format!("Invalid server public key: {}", e))?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| "Invalid server public key length".to_string())?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| format!("Invalid server public key: {}", e))
+}
+
+/// 鍵を固定する
+///
+/// 同じ鍵の再固定は何もしない。別の鍵が固定済みの場合は replace が true のときだけ置き換える
+pub fn pin(public_key: &str, replace: bool) -> Result<PinnedServerKey, String> {
+    parse_public_key(public_key)?;
+    let public_key = public_key.trim().to_string();
+
+    if let Some(existing) = pinned()? {
+        if existing.public_key == public_key {
+            return Ok(existing);
+        }
+        if !replace {
+            return Err("A different server key is already pinned".to_string());
+        }
+    }
+
+    let key = PinnedServerKey {
+        public_key,
+        pinned_at: storage::now_unix(),
+    };
+    storage::save_json(SERVER_KEY_FILE, &key)?;
+    Ok(key)
+}
+
+/// 指定した公開鍵で署名を検証
+fn verify_with(public_key: &str, message: &[u8], signature: &str) -> Result<(), String> {
+    let key = parse_public_key(public_key)?;
+    let bytes = BASE64
+        .decode(signature.trim())
+        .map_err(|e| format!("Invalid server signature: {}", e))?;
+    let signature =
+        Signature::from_slice(&bytes).map_err(|e| format!("Invalid server signature: {}", e))?;
+    key.verify(message, &signature)
+        .map_err(|_| "Server signature verification failed".to_string())
+}
+
+/// 固定した鍵でサーバーの署名を検証（鍵が未固定の場合はエラー）
+pub fn verify(message: &[u8], signature: &str) -> Result<(), String> {
+    let key = pinned()?.ok_or_else(|| "Server key is not pinned".to_string())?;
+    verify_with(&key.public_key, message, signature)
+}
+
+/// 固定した鍵で署名を検証して中身を取り出す
+pub fn open<T: DeserializeOwned>(signed: &SignedPayload) -> Result<T, String> {
+    verify(signed.payload.as_bytes(), &signed.signature)?;
+    serde_json::from_str(&signed.payload).map_err(|e| format!("Invalid server payload: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    #[test]
+    fn test_verify_with_pinned_key() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = BASE64.encode(signing_key.verifying_key().to_bytes());
+        let signature = BASE64.encode(signing_key.sign(b"payload").to_bytes());
+
+        assert!(verify_with(&public_key, b"payload", &signature).is_ok());
+        assert!(verify_with(&public_key, b"tampered", &signature).is_err());
+
+        let other = BASE64.encode(SigningKey::from_bytes(&[8u8; 32]).verifying_key().to_bytes());
+        assert!(verify_with(&other, b"payload", &signature).is_err());
+    }
+
+    #[test]
+    fn test_parse_public_key_rejects_wrong_length() {
+        assert!(parse_public_key(&BASE64.encode([1u8; 16])).is_err());
+        assert!(parse_public_key("not base64").is_err());
+    }
+}
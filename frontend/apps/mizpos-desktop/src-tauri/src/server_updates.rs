@@ -0,0 +1,130 @@
+//! サーバー配信データモジュール
+//!
+//! 管理サーバーから配信される端末設定と価格改定を、固定したサーバー公開鍵で検証してから受け入れる
+//! 会場のWi-Fiなど信頼できないネットワーク上で、なりすましや古いデータの再送を受け付けないようにする
+//! 配信データの取得はフロントエンドが行い、受け取った内容をそのまま渡す
+//! 価格改定は端末の価格表（price_book）に反映し、古い改定の再送は価格表の版で受け付けない
+
+use crate::config::{self, TerminalConfig};
+use crate::price_book::{self, PriceBookUpdate};
+use crate::server_key::{self, SignedPayload};
+use crate::storage;
+use crate::terminal_auth;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+
+/// 種別ごとに受け入れた発行日時の保存ファイル名
+const SERVER_UPDATES_FILE: &str = "server_updates.json";
+
+/// 配信データの種別: 端末設定
+const KIND_CONFIG: &str = "config";
+
+/// 端末設定の配信（署名対象）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigUpdate {
+    /// 宛先の端末ID
+    pub terminal_id: String,
+    /// 発行日時（UNIX秒）
+    pub issued_at: u64,
+    pub config: TerminalConfig,
+}
+
+/// 商品ごとの価格
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PriceEntry {
+    pub product_id: String,
+    pub price: u32,
+}
+
+/// 価格改定の配信（署名対象）
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PriceUpdate {
+    /// 宛先の端末ID（全端末宛ての場合はNone）
+    pub terminal_id: Option<String>,
+    /// 発行日時（UNIX秒）
+    pub issued_at: u64,
+    pub prices: Vec<PriceEntry>,
+}
+
+/// 種別ごとに受け入れた発行日時
+fn accepted() -> Result<HashMap<String, u64>, String> {
+    Ok(storage::load_json(SERVER_UPDATES_FILE)?.unwrap_or_default())
+}
+
+/// 前回受け入れたものより新しいか確認
+fn check_newer(accepted: &HashMap<String, u64>, kind: &str, issued_at: u64) -> Result<(), String> {
+    match accepted.get(kind) {
+        Some(&last) if issued_at <= last => Err(format!(
+            "Server {} update is not newer than the applied one ({} <= {})",
+            kind, issued_at, last
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// 発行日時を確認して記録
+fn accept(kind: &str, issued_at: u64) -> Result<(), String> {
+    let mut accepted = accepted()?;
+    check_newer(&accepted, kind, issued_at)?;
+    accepted.insert(kind.to_string(), issued_at);
+    storage::save_json(SERVER_UPDATES_FILE, &accepted)
+}
+
+/// 宛先がこの端末か確認
+fn check_terminal(target: Option<&str>, terminal_id: &str) -> Result<(), String> {
+    match target {
+        Some(target) if target != terminal_id => {
+            Err(format!("Server update is for another terminal: {}", target))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn terminal_id() -> Result<String, String> {
+    terminal_auth::get_terminal_status()
+        .map_err(|e| e.to_string())?
+        .terminal_id
+        .ok_or_else(|| "Terminal is not initialized".to_string())
+}
+
+/// 署名付きの端末設定を検証して保存
+pub fn apply_config(signed: &SignedPayload) -> Result<TerminalConfig, String> {
+    let update: ConfigUpdate = server_key::open(signed)?;
+    check_terminal(Some(&update.terminal_id), &terminal_id()?)?;
+    accept(KIND_CONFIG, update.issued_at)?;
+    config::save(&update.config)?;
+    Ok(update.config)
+}
+
+/// 署名付きの価格改定を検証して端末の価格表に反映する
+///
+/// 価格表はイベントごとにあるため、受け入れ済みの発行日時は端末全体ではなくイベントの価格表の版で確かめる
+/// 反映に失敗した改定は価格表の版が進まないので、同じ配信をもう一度反映できる
+pub fn apply_prices(signed: &SignedPayload, staff_id: Option<&str>) -> Result<PriceBookUpdate, String> {
+    let update: PriceUpdate = server_key::open(signed)?;
+    check_terminal(update.terminal_id.as_deref(), &terminal_id()?)?;
+    price_book::apply(&update, staff_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_newer_rejects_replayed_updates() {
+        let accepted = HashMap::from([(KIND_CONFIG.to_string(), 100)]);
+
+        assert!(check_newer(&accepted, KIND_CONFIG, 101).is_ok());
+        assert!(check_newer(&accepted, KIND_CONFIG, 100).is_err());
+        assert!(check_newer(&accepted, KIND_CONFIG, 99).is_err());
+        assert!(check_newer(&accepted, "prices", 1).is_ok());
+    }
+
+    #[test]
+    fn test_check_terminal() {
+        assert!(check_terminal(Some("T1"), "T1").is_ok());
+        assert!(check_terminal(None, "T1").is_ok());
+        assert!(check_terminal(Some("T2"), "T1").is_err());
+    }
+}
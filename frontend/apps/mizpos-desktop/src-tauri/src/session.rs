@@ -0,0 +1,93 @@
+//! 営業セッションモジュール
+//!
+//! 開局〜閉局までの状態をイベント単位で保持する
+
+use crate::event_context;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// セッションの保存ファイル名
+const SESSION_FILE: &str = "session.json";
+
+/// 営業セッションの状態
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct SessionState {
+    /// 開局中か
+    pub open: bool,
+    /// 開局したスタッフ番号
+    pub staff_id: Option<String>,
+    /// 開局日時（UNIX秒）
+    pub opened_at: Option<u64>,
+    /// 閉局日時（UNIX秒）
+    pub closed_at: Option<u64>,
+    /// 担当者の交代履歴（開局したスタッフから順に）
+    #[serde(default)]
+    pub shifts: Vec<StaffShift>,
+}
+
+/// 担当者の交代
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct StaffShift {
+    pub staff_id: String,
+    /// 表示名（ログイン時に分かる場合のみ）
+    pub staff_name: Option<String>,
+    /// 担当を始めた日時（UNIX秒）
+    pub started_at: u64,
+}
+
+/// 現在のセッション状態を取得
+pub fn current() -> Result<SessionState, String> {
+    Ok(storage::load_json(&event_context::scoped(SESSION_FILE)?)?.unwrap_or_default())
+}
+
+/// 開局
+pub fn open(staff_id: &str) -> Result<SessionState, String> {
+    let now = storage::now_unix();
+    let state = SessionState {
+        open: true,
+        staff_id: Some(staff_id.to_string()),
+        opened_at: Some(now),
+        closed_at: None,
+        shifts: vec![StaffShift {
+            staff_id: staff_id.to_string(),
+            staff_name: None,
+            started_at: now,
+        }],
+    };
+    storage::save_json(&event_context::scoped(SESSION_FILE)?, &state)?;
+    Ok(state)
+}
+
+/// 担当者の交代を記録（スタッフのログイン時に呼ぶ）
+///
+/// 開局していない場合や担当者が変わらない場合は記録しない（表示名だけ補う）
+pub fn switch_staff(staff_id: &str, staff_name: Option<&str>) -> Result<SessionState, String> {
+    let mut state = current()?;
+    if !state.open {
+        return Ok(state);
+    }
+    match state.shifts.last_mut() {
+        Some(shift) if shift.staff_id == staff_id => {
+            if staff_name.is_some() {
+                shift.staff_name = staff_name.map(|n| n.to_string());
+            }
+        }
+        _ => state.shifts.push(StaffShift {
+            staff_id: staff_id.to_string(),
+            staff_name: staff_name.map(|n| n.to_string()),
+            started_at: storage::now_unix(),
+        }),
+    }
+    storage::save_json(&event_context::scoped(SESSION_FILE)?, &state)?;
+    Ok(state)
+}
+
+/// 閉局
+pub fn close() -> Result<SessionState, String> {
+    let mut state = current()?;
+    state.open = false;
+    state.closed_at = Some(storage::now_unix());
+    storage::save_json(&event_context::scoped(SESSION_FILE)?, &state)?;
+    Ok(state)
+}
@@ -0,0 +1,558 @@
+//! 印刷スプーラーモジュール
+//!
+//! 印刷ジョブを受け付けてバックグラウンドで順番に印刷する
+//! 1つのジョブを複数のプリンター（お客様用 + バックオフィスのジャーナル用など）にミラーリングでき、
+//! プリンターごとに独立して再試行したうえで、全体の結果を1つのイベントで通知する
+//!
+//! ジョブには優先度（レシート > 呼び出し票 > レポート）があり、優先度の高いジョブが来た場合は
+//! 印刷中のジョブをプリンター1台分の区切りで中断して先に印刷する
+//! （長い閉局レポートの途中でも、カウンターのお客様のレシートを待たせない）
+//!
+//! 待ち行列と印刷中のジョブはファイルに保存し、アプリを再起動しても印刷待ちのジョブを続きから印刷する
+//! 印刷中に終了したジョブは、そのプリンターから印刷し直す（二重に出る場合があるが、出ないよりよい）
+
+use crate::journal::JournalTotals;
+use crate::metrics;
+use crate::parked_sales::ParkedSale;
+use crate::receipt::{ClosingReportData, ReceiptData};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+use tauri::Emitter;
+use uuid::Uuid;
+
+/// プリンターごとの最大試行回数（USBの抜き差し・再接続を待てるよう、合計で数秒は再試行する）
+const MAX_ATTEMPTS: u32 = 5;
+/// 再試行までの待ち時間（試行ごとに倍にする）
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+/// 待ち行列の保存ファイル名
+const SPOOL_FILE: &str = "print_spool.json";
+
+/// ジョブの処理が終わったときに通知するイベント名
+pub const EVENT_PRINT_JOB_FINISHED: &str = "print-job-finished";
+/// プリンターへの印刷を試みるたびに通知するイベント名
+pub const EVENT_PRINT_JOB_PROGRESS: &str = "print-job-progress";
+
+/// 印刷先のUSBプリンター
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct UsbPrinter {
+    pub vendor_id: u16,
+    pub device_id: u16,
+    pub paper_width: Option<u8>,
+}
+
+/// 印刷の優先度（値が小さいほど先に印刷する）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PrintPriority {
+    /// お客様のレシート
+    Receipt,
+    /// 呼び出し票・お預かり票
+    Ticket,
+    /// 閉局レポートなどの帳票
+    Report,
+}
+
+/// 印刷する内容
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SpoolDocument {
+    /// レシート
+    Receipt { receipt: Box<ReceiptData> },
+    /// 保留取引のお預かり票
+    ParkedSlip { sale: Box<ParkedSale> },
+    /// 閉局レポート（受付時点のジャーナル集計で印刷する）
+    ClosingReport {
+        report: Box<ClosingReportData>,
+        totals: JournalTotals,
+    },
+    /// レシートの印刷に失敗した売上の控え
+    FallbackSlip {
+        receipt_number: String,
+        event_name: String,
+        total: u32,
+    },
+}
+
+impl SpoolDocument {
+    /// 内容ごとの優先度
+    pub fn priority(&self) -> PrintPriority {
+        match self {
+            SpoolDocument::Receipt { .. } | SpoolDocument::FallbackSlip { .. } => PrintPriority::Receipt,
+            SpoolDocument::ParkedSlip { .. } => PrintPriority::Ticket,
+            SpoolDocument::ClosingReport { .. } => PrintPriority::Report,
+        }
+    }
+}
+
+/// 印刷ジョブ
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SpoolJob {
+    pub id: String,
+    pub priority: PrintPriority,
+    pub document: SpoolDocument,
+    /// 印刷先（先頭がメイン、2台目以降はミラー）
+    pub targets: Vec<UsbPrinter>,
+    /// 印刷が終わったプリンターの結果（中断されたジョブは途中から再開する）
+    pub results: Vec<TargetResult>,
+}
+
+impl SpoolJob {
+    /// 次に印刷するプリンター
+    fn next_target(&self) -> Option<&UsbPrinter> {
+        self.targets.get(self.results.len())
+    }
+}
+
+/// プリンターごとの結果
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TargetResult {
+    pub printer: UsbPrinter,
+    pub success: bool,
+    /// 試行回数
+    pub attempts: u32,
+    /// 最後のエラー（成功時はNone）
+    pub error: Option<String>,
+}
+
+/// ジョブの処理結果（全プリンター分をまとめて通知する）
+#[derive(Debug, Clone, Serialize)]
+pub struct PrintJobFinishedEvent {
+    pub job_id: String,
+    /// 全プリンターで成功したか
+    pub success: bool,
+    pub results: Vec<TargetResult>,
+}
+
+/// 印刷の進み具合（プリンターへの印刷を試みるたびに通知する）
+#[derive(Debug, Clone, Serialize)]
+pub struct PrintJobProgressEvent {
+    pub job_id: String,
+    /// 印刷中のプリンター（targets の位置）
+    pub target_index: usize,
+    pub target_count: usize,
+    /// 何回目の試行か
+    pub attempt: u32,
+    /// 直前の試行のエラー（再試行の場合）
+    pub last_error: Option<String>,
+}
+
+/// 印刷中のジョブと待ち行列（保存する内容と同じ）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct PrintQueue {
+    /// 印刷中のジョブ（終わったプリンターの結果を含む）
+    pub printing: Option<SpoolJob>,
+    /// 印刷待ちのジョブ（優先度順）
+    pub pending: Vec<SpoolJob>,
+}
+
+/// 待ち行列
+///
+/// ロックは queue → printing の順に取る
+struct Spooler {
+    queue: Mutex<VecDeque<SpoolJob>>,
+    printing: Mutex<Option<SpoolJob>>,
+    ready: Condvar,
+}
+
+fn spooler() -> &'static Spooler {
+    static SPOOLER: OnceLock<Spooler> = OnceLock::new();
+    SPOOLER.get_or_init(|| Spooler {
+        queue: Mutex::new(VecDeque::new()),
+        printing: Mutex::new(None),
+        ready: Condvar::new(),
+    })
+}
+
+/// 待ち行列と印刷中のジョブを保存する（保存できなくても印刷は続ける）
+fn save(queue: &VecDeque<SpoolJob>, printing: Option<&SpoolJob>) {
+    let saved = PrintQueue {
+        printing: printing.cloned(),
+        pending: queue.iter().cloned().collect(),
+    };
+    let _ = storage::save_json(SPOOL_FILE, &saved);
+}
+
+/// 待ち行列と印刷中のジョブを変更して保存する
+fn update<T>(f: impl FnOnce(&mut VecDeque<SpoolJob>, &mut Option<SpoolJob>) -> T) -> Result<T, String> {
+    let spooler = spooler();
+    let mut queue = spooler
+        .queue
+        .lock()
+        .map_err(|_| "Print spooler is unavailable".to_string())?;
+    let mut printing = spooler
+        .printing
+        .lock()
+        .map_err(|_| "Print spooler is unavailable".to_string())?;
+    let result = f(&mut queue, &mut printing);
+    save(&queue, printing.as_ref());
+    Ok(result)
+}
+
+/// 保存した待ち行列を読み込む（印刷中だったジョブは同じ優先度の先頭に戻す）
+fn load_saved() -> Result<VecDeque<SpoolJob>, String> {
+    let saved: PrintQueue = storage::load_json(SPOOL_FILE)?.unwrap_or_default();
+    let mut queue = VecDeque::new();
+    for job in saved.pending {
+        enqueue(&mut queue, job);
+    }
+    if let Some(job) = saved.printing {
+        requeue_front(&mut queue, job);
+    }
+    Ok(queue)
+}
+
+/// ジョブを受け付ける（印刷はバックグラウンドで行い、結果はイベントで通知する）
+///
+/// 同じプリンターを重複して指定した場合は1回だけ印刷する
+pub fn submit(document: SpoolDocument, targets: Vec<UsbPrinter>) -> Result<SpoolJob, String> {
+    let mut unique: Vec<UsbPrinter> = Vec::with_capacity(targets.len());
+    for target in targets {
+        if !unique.contains(&target) {
+            unique.push(target);
+        }
+    }
+    if unique.is_empty() {
+        return Err("No printer specified".to_string());
+    }
+
+    let job = SpoolJob {
+        id: Uuid::new_v4().to_string(),
+        priority: document.priority(),
+        document,
+        targets: unique,
+        results: Vec::new(),
+    };
+
+    update(|queue, _| enqueue(queue, job.clone()))?;
+    spooler().ready.notify_one();
+    Ok(job)
+}
+
+/// 優先度順に並ぶよう追加する（同じ優先度では受付順）
+fn enqueue(queue: &mut VecDeque<SpoolJob>, job: SpoolJob) {
+    let index = queue
+        .iter()
+        .position(|j| j.priority > job.priority)
+        .unwrap_or(queue.len());
+    queue.insert(index, job);
+}
+
+/// 中断したジョブを同じ優先度の先頭に戻す
+fn requeue_front(queue: &mut VecDeque<SpoolJob>, job: SpoolJob) {
+    let index = queue
+        .iter()
+        .position(|j| j.priority >= job.priority)
+        .unwrap_or(queue.len());
+    queue.insert(index, job);
+}
+
+/// 指定より優先度の高いジョブが待っているか
+fn has_higher_priority(priority: PrintPriority) -> bool {
+    spooler()
+        .queue
+        .lock()
+        .map(|q| q.front().is_some_and(|j| j.priority < priority))
+        .unwrap_or(false)
+}
+
+/// 待ち行列のジョブ一覧
+pub fn pending() -> Vec<SpoolJob> {
+    spooler()
+        .queue
+        .lock()
+        .map(|q| q.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// 印刷中のジョブと待ち行列
+pub fn queue() -> PrintQueue {
+    let printing = spooler().printing.lock().map(|p| p.clone()).unwrap_or_default();
+    PrintQueue {
+        printing,
+        pending: pending(),
+    }
+}
+
+/// 印刷待ちのジョブを取り消す（印刷中のジョブは取り消せない）
+pub fn cancel(job_id: &str) -> Result<SpoolJob, String> {
+    update(|queue, printing| {
+        if printing.as_ref().is_some_and(|j| j.id == job_id) {
+            return Err("Print job is already printing".to_string());
+        }
+        let index = queue
+            .iter()
+            .position(|j| j.id == job_id)
+            .ok_or_else(|| format!("Print job not found: {}", job_id))?;
+        Ok(queue.remove(index).expect("index is in range"))
+    })?
+}
+
+/// 保存した待ち行列を読み込み、バックグラウンドでジョブを処理する
+pub fn start(app: tauri::AppHandle) {
+    // 読み込めない場合も新しいジョブの印刷は続ける
+    if let Ok(saved) = load_saved() {
+        let _ = update(|queue, _| {
+            for job in saved {
+                enqueue(queue, job);
+            }
+        });
+    }
+
+    thread::spawn(move || loop {
+        let Some(mut job) = next_job() else {
+            return;
+        };
+        if run(&app, &mut job) {
+            crate::fallback_slip::on_job_finished(&job);
+            let _ = update(|_, printing| *printing = None);
+            let _ = app.emit(EVENT_PRINT_JOB_FINISHED, finished_event(job));
+        } else {
+            // 優先度の高いジョブに譲る
+            let _ = update(|queue, printing| {
+                *printing = None;
+                requeue_front(queue, job);
+            });
+        }
+    });
+}
+
+/// 次のジョブが来るまで待ち、印刷中のジョブにする
+fn next_job() -> Option<SpoolJob> {
+    let spooler = spooler();
+    let mut queue = spooler.queue.lock().ok()?;
+    loop {
+        if let Some(job) = queue.pop_front() {
+            let mut printing = spooler.printing.lock().ok()?;
+            *printing = Some(job.clone());
+            save(&queue, printing.as_ref());
+            return Some(job);
+        }
+        queue = spooler.ready.wait(queue).ok()?;
+    }
+}
+
+/// ジョブを全プリンターに印刷する
+///
+/// 1台が失敗しても他のプリンターへの印刷は続ける
+/// 優先度の高いジョブが来た場合はプリンター1台分の区切りで中断し、falseを返す
+fn run(app: &tauri::AppHandle, job: &mut SpoolJob) -> bool {
+    while let Some(target) = job.next_target().cloned() {
+        if !job.results.is_empty() && has_higher_priority(job.priority) {
+            return false;
+        }
+        let target_index = job.results.len();
+        let result = print_with_retry(&target, &job.document, |attempt, last_error| {
+            let _ = app.emit(
+                EVENT_PRINT_JOB_PROGRESS,
+                PrintJobProgressEvent {
+                    job_id: job.id.clone(),
+                    target_index,
+                    target_count: job.targets.len(),
+                    attempt,
+                    last_error: last_error.map(str::to_string),
+                },
+            );
+        });
+        job.results.push(result);
+        // 再起動した場合に印刷済みのプリンターを飛ばせるよう、結果を保存する
+        let _ = update(|_, printing| *printing = Some(job.clone()));
+    }
+    true
+}
+
+fn finished_event(job: SpoolJob) -> PrintJobFinishedEvent {
+    PrintJobFinishedEvent {
+        job_id: job.id,
+        success: job.results.iter().all(|r| r.success),
+        results: job.results,
+    }
+}
+
+/// on_attempt: 試行の前に呼ぶ（試行回数と直前のエラー）
+fn print_with_retry(
+    target: &UsbPrinter,
+    document: &SpoolDocument,
+    mut on_attempt: impl FnMut(u32, Option<&str>),
+) -> TargetResult {
+    let mut delay = RETRY_DELAY;
+    let mut last_error: Option<String> = None;
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        on_attempt(attempt, last_error.as_deref());
+        let result = print_document(target, document);
+        metrics::record_print(&result);
+        match result {
+            Ok(()) => {
+                return TargetResult {
+                    printer: target.clone(),
+                    success: true,
+                    attempts: attempt,
+                    error: None,
+                }
+            }
+            Err(e) => last_error = Some(e),
+        }
+        if attempt < MAX_ATTEMPTS {
+            thread::sleep(delay);
+            delay *= 2;
+        }
+    }
+
+    TargetResult {
+        printer: target.clone(),
+        success: false,
+        attempts: MAX_ATTEMPTS,
+        error: last_error,
+    }
+}
+
+#[cfg(not(target_os = "android"))]
+fn print_document(target: &UsbPrinter, document: &SpoolDocument) -> Result<(), String> {
+    crate::desktop_printer::print_spool_document(target, document)
+}
+
+#[cfg(target_os = "android")]
+fn print_document(_target: &UsbPrinter, _document: &SpoolDocument) -> Result<(), String> {
+    Err("Bluetooth printing not yet implemented".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_support::TempDataDir;
+
+    fn job(id: &str, priority: PrintPriority) -> SpoolJob {
+        SpoolJob {
+            id: id.to_string(),
+            priority,
+            document: SpoolDocument::ParkedSlip {
+                sale: Box::new(ParkedSale {
+                    code: "P0001".to_string(),
+                    items: Vec::new(),
+                    total: 0,
+                    staff_id: "S001".to_string(),
+                    memo: None,
+                    parked_at: 0,
+                }),
+            },
+            targets: Vec::new(),
+            results: Vec::new(),
+        }
+    }
+
+    fn printer(device_id: u16) -> UsbPrinter {
+        UsbPrinter {
+            vendor_id: 0x0416,
+            device_id,
+            paper_width: Some(58),
+        }
+    }
+
+    fn result(device_id: u16, success: bool) -> TargetResult {
+        TargetResult {
+            printer: printer(device_id),
+            success,
+            attempts: if success { 1 } else { MAX_ATTEMPTS },
+            error: (!success).then(|| "Printer not found".to_string()),
+        }
+    }
+
+    fn ids(queue: &VecDeque<SpoolJob>) -> Vec<&str> {
+        queue.iter().map(|j| j.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_enqueue_orders_by_priority_then_arrival() {
+        let mut queue = VecDeque::new();
+        enqueue(&mut queue, job("report", PrintPriority::Report));
+        enqueue(&mut queue, job("ticket", PrintPriority::Ticket));
+        enqueue(&mut queue, job("receipt1", PrintPriority::Receipt));
+        enqueue(&mut queue, job("receipt2", PrintPriority::Receipt));
+        assert_eq!(ids(&queue), vec!["receipt1", "receipt2", "ticket", "report"]);
+    }
+
+    #[test]
+    fn test_preempted_job_resumes_before_same_priority() {
+        let mut queue = VecDeque::new();
+        enqueue(&mut queue, job("receipt", PrintPriority::Receipt));
+        enqueue(&mut queue, job("report2", PrintPriority::Report));
+        requeue_front(&mut queue, job("report1", PrintPriority::Report));
+        assert_eq!(ids(&queue), vec!["receipt", "report1", "report2"]);
+    }
+
+    #[test]
+    fn test_submit_deduplicates_mirror_targets() {
+        let _dir = TempDataDir::new();
+        let document = job("slip", PrintPriority::Ticket).document;
+        assert!(submit(document.clone(), Vec::new()).is_err());
+
+        let submitted = submit(document, vec![printer(1), printer(2), printer(1)]).unwrap();
+        assert_eq!(submitted.targets, vec![printer(1), printer(2)]);
+        assert_eq!(submitted.priority, PrintPriority::Ticket);
+        if let Ok(mut queue) = spooler().queue.lock() {
+            queue.retain(|j| j.id != submitted.id);
+        }
+    }
+
+    #[test]
+    fn test_mirrored_job_resumes_at_next_printer() {
+        let mut mirrored = job("receipt", PrintPriority::Receipt);
+        mirrored.targets = vec![printer(1), printer(2), printer(3)];
+        assert_eq!(mirrored.next_target(), Some(&printer(1)));
+
+        // メインで中断されたジョブはミラーの続きから再開する
+        mirrored.results.push(result(1, true));
+        assert_eq!(mirrored.next_target(), Some(&printer(2)));
+        mirrored.results.push(result(2, false));
+        mirrored.results.push(result(3, true));
+        assert_eq!(mirrored.next_target(), None);
+    }
+
+    #[test]
+    fn test_finished_event_fails_if_any_mirror_fails() {
+        let mut mirrored = job("receipt", PrintPriority::Receipt);
+        mirrored.results = vec![result(1, true), result(2, true)];
+        assert!(finished_event(mirrored.clone()).success);
+
+        mirrored.results[1] = result(2, false);
+        let event = finished_event(mirrored);
+        assert!(!event.success);
+        assert_eq!(event.results.len(), 2);
+        assert_eq!(event.results[1].error.as_deref(), Some("Printer not found"));
+    }
+
+    #[test]
+    fn test_saved_queue_resumes_interrupted_job_first() {
+        let _dir = TempDataDir::new();
+        let mut interrupted = job("report1", PrintPriority::Report);
+        interrupted.targets = vec![printer(1), printer(2)];
+        interrupted.results = vec![result(1, true)];
+        let saved = PrintQueue {
+            printing: Some(interrupted),
+            pending: vec![job("report2", PrintPriority::Report), job("receipt", PrintPriority::Receipt)],
+        };
+        storage::save_json(SPOOL_FILE, &saved).unwrap();
+
+        let queue = load_saved().unwrap();
+        assert_eq!(ids(&queue), vec!["receipt", "report1", "report2"]);
+        // 印刷済みのプリンターは飛ばして続きから印刷する
+        assert_eq!(queue[1].next_target(), Some(&printer(2)));
+    }
+
+    #[test]
+    fn test_cancel_removes_pending_job() {
+        let _dir = TempDataDir::new();
+        let document = job("slip", PrintPriority::Ticket).document;
+        let submitted = submit(document, vec![printer(9)]).unwrap();
+        assert!(queue().pending.iter().any(|j| j.id == submitted.id));
+
+        assert_eq!(cancel(&submitted.id).unwrap().id, submitted.id);
+        assert!(!queue().pending.iter().any(|j| j.id == submitted.id));
+        assert!(cancel(&submitted.id).is_err());
+    }
+}
@@ -0,0 +1,328 @@
+//! スタッフ認証モジュール
+//!
+//! 責任者のPINを Argon2id でハッシュ化して保持し、権限が必要な操作の承認に使う
+//! 責任者ごとに脅迫PINを設定でき、脅迫PINでも通常どおり承認する（通報は duress モジュールが行う）
+//! 責任者PINで責任者セッションを開始でき、セッション中は責任者向けのコマンドを受け付ける（permissions モジュール）
+
+use crate::duress;
+use crate::rate_limit;
+use crate::storage;
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::sync::Mutex;
+use subtle::ConstantTimeEq;
+
+/// 責任者一覧の保存ファイル名
+const SUPERVISORS_FILE: &str = "supervisors.json";
+/// PINの最小桁数
+const MIN_PIN_DIGITS: usize = 4;
+/// Argon2id で作ったハッシュの接頭辞（接頭辞のないものは旧形式の SHA-256）
+const ARGON2_PREFIX: &str = "argon2id$";
+/// 責任者セッションの有効期間（秒）
+const SESSION_SECS: u64 = 600;
+
+/// 開始中の責任者セッション（アプリの再起動で切れる）
+static SESSION: Mutex<Option<SupervisorSession>> = Mutex::new(None);
+
+/// 登録済みの責任者
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SupervisorRecord {
+    supervisor_id: String,
+    salt: String,     // Base64
+    pin_hash: String, // "argon2id$" + Base64（旧形式は SHA-256 の Base64）
+    #[serde(default)]
+    duress_pin_hash: Option<String>,
+}
+
+/// 入力されたPINの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PinMatch {
+    Normal,
+    Duress,
+}
+
+/// 責任者承認（フロントエンドから渡される）
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct SupervisorApproval {
+    pub supervisor_id: String,
+    pub pin: String,
+}
+
+/// 責任者セッション
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Type)]
+pub struct SupervisorSession {
+    pub supervisor_id: String,
+    /// 切れる日時（UNIX秒）
+    pub expires_at: u64,
+}
+
+fn load_supervisors() -> Result<Vec<SupervisorRecord>, String> {
+    Ok(storage::load_json(SUPERVISORS_FILE)?.unwrap_or_default())
+}
+
+/// PINが4桁以上の数字か確認
+fn validate_pin(pin: &str) -> Result<(), String> {
+    if pin.len() < MIN_PIN_DIGITS || !pin.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("PIN must be at least {} digits", MIN_PIN_DIGITS));
+    }
+    Ok(())
+}
+
+fn argon2_hash(salt: &[u8], pin: &str) -> Result<[u8; 32], String> {
+    let mut output = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(pin.as_bytes(), salt, &mut output)
+        .map_err(|e| e.to_string())?;
+    Ok(output)
+}
+
+fn hash_pin(salt: &[u8], pin: &str) -> Result<String, String> {
+    Ok(format!("{}{}", ARGON2_PREFIX, BASE64.encode(argon2_hash(salt, pin)?)))
+}
+
+/// 保存済みのハッシュと定数時間で照合
+fn verify_hash(salt: &[u8], pin: &str, stored: &str) -> Result<bool, String> {
+    let (expected, actual) = match stored.strip_prefix(ARGON2_PREFIX) {
+        Some(hash) => (hash, argon2_hash(salt, pin)?.to_vec()),
+        None => {
+            let mut hasher = Sha256::new();
+            hasher.update(salt);
+            hasher.update(pin.as_bytes());
+            (stored, hasher.finalize().to_vec())
+        }
+    };
+    let expected = BASE64.decode(expected).map_err(|e| e.to_string())?;
+    Ok(bool::from(expected.ct_eq(&actual)))
+}
+
+fn is_legacy_hash(stored: &str) -> bool {
+    !stored.starts_with(ARGON2_PREFIX)
+}
+
+fn match_pin(record: &SupervisorRecord, pin: &str) -> Result<PinMatch, String> {
+    let salt = BASE64.decode(&record.salt).map_err(|e| e.to_string())?;
+    // どちらのPINか処理時間から分からないよう、常に両方と照合する
+    let normal = verify_hash(&salt, pin, &record.pin_hash)?;
+    let duress = match &record.duress_pin_hash {
+        Some(hash) => verify_hash(&salt, pin, hash)?,
+        None => false,
+    };
+    if normal {
+        Ok(PinMatch::Normal)
+    } else if duress {
+        Ok(PinMatch::Duress)
+    } else {
+        Err("Supervisor approval failed".to_string())
+    }
+}
+
+/// 旧形式（SHA-256）のハッシュを照合できたPINで Argon2id に置き換える
+fn upgrade_legacy_hash(supervisor_id: &str, matched: PinMatch, pin: &str) -> Result<(), String> {
+    let mut supervisors = load_supervisors()?;
+    let Some(record) = supervisors.iter_mut().find(|r| r.supervisor_id == supervisor_id) else {
+        return Ok(());
+    };
+    let salt = BASE64.decode(&record.salt).map_err(|e| e.to_string())?;
+    let hash = match matched {
+        PinMatch::Normal => &mut record.pin_hash,
+        PinMatch::Duress => match record.duress_pin_hash.as_mut() {
+            Some(hash) => hash,
+            None => return Ok(()),
+        },
+    };
+    if !is_legacy_hash(hash) {
+        return Ok(());
+    }
+    *hash = hash_pin(&salt, pin)?;
+    storage::save_json(SUPERVISORS_FILE, &supervisors)
+}
+
+/// PINを照合する（失敗が続いた責任者IDは一定時間受け付けない）
+fn check_approval(approval: &SupervisorApproval) -> Result<PinMatch, String> {
+    let scope = format!("pin:{}", approval.supervisor_id);
+    rate_limit::check(&scope, &rate_limit::PIN)?;
+    let matched = load_supervisors()?
+        .into_iter()
+        .find(|r| r.supervisor_id == approval.supervisor_id)
+        .ok_or_else(|| "Supervisor approval failed".to_string())
+        .and_then(|record| match_pin(&record, &approval.pin));
+    let matched = match matched {
+        Ok(matched) => {
+            rate_limit::reset(&scope);
+            // 置き換えに失敗しても承認はする（次回の照合で再び置き換える）
+            let _ = upgrade_legacy_hash(&approval.supervisor_id, matched, &approval.pin);
+            matched
+        }
+        Err(e) => {
+            rate_limit::record(&scope, &rate_limit::PIN);
+            return Err(e);
+        }
+    };
+    if matched == PinMatch::Duress {
+        // 通報に失敗しても、画面上は通常どおり承認する
+        let _ = duress::trigger(&approval.supervisor_id);
+    }
+    Ok(matched)
+}
+
+/// 責任者承認を検証
+///
+/// 脅迫PINの場合も承認する
+pub fn verify_supervisor(approval: &SupervisorApproval) -> Result<(), String> {
+    check_approval(approval).map(|_| ())
+}
+
+/// 責任者を登録（既に責任者がいる場合は既存責任者の承認が必要）
+pub fn register_supervisor(
+    supervisor_id: &str,
+    pin: &str,
+    approval: Option<&SupervisorApproval>,
+) -> Result<(), String> {
+    validate_pin(pin)?;
+
+    let mut supervisors = load_supervisors()?;
+    if !supervisors.is_empty() {
+        let approval = approval.ok_or_else(|| "Supervisor approval required".to_string())?;
+        verify_supervisor(approval)?;
+    }
+
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+
+    supervisors.retain(|r| r.supervisor_id != supervisor_id);
+    supervisors.push(SupervisorRecord {
+        supervisor_id: supervisor_id.to_string(),
+        salt: BASE64.encode(salt),
+        pin_hash: hash_pin(&salt, pin)?,
+        duress_pin_hash: None,
+    });
+    storage::save_json(SUPERVISORS_FILE, &supervisors)
+}
+
+/// 責任者が登録されているか
+pub fn has_supervisors() -> Result<bool, String> {
+    Ok(!load_supervisors()?.is_empty())
+}
+
+/// 責任者PINで責任者セッションを開始（開始中のセッションは置き換える）
+pub fn begin_session(approval: &SupervisorApproval) -> Result<SupervisorSession, String> {
+    verify_supervisor(approval)?;
+    let session = SupervisorSession {
+        supervisor_id: approval.supervisor_id.clone(),
+        expires_at: storage::now_unix() + SESSION_SECS,
+    };
+    *SESSION.lock().map_err(|e| e.to_string())? = Some(session.clone());
+    Ok(session)
+}
+
+/// 責任者セッションを終了
+pub fn end_session() -> Result<(), String> {
+    *SESSION.lock().map_err(|e| e.to_string())? = None;
+    Ok(())
+}
+
+/// 開始中の責任者セッション（切れている場合は None）
+pub fn active_session() -> Result<Option<SupervisorSession>, String> {
+    let mut session = SESSION.lock().map_err(|e| e.to_string())?;
+    if session.as_ref().is_some_and(|s| s.expires_at <= storage::now_unix()) {
+        *session = None;
+    }
+    Ok(session.clone())
+}
+
+/// 脅迫PINを設定（本人の通常のPINでの承認が必要）
+pub fn set_duress_pin(approval: &SupervisorApproval, duress_pin: &str) -> Result<(), String> {
+    validate_pin(duress_pin)?;
+    // 脅迫PINでは設定を変更させない
+    if check_approval(approval)? != PinMatch::Normal {
+        return Err("Supervisor approval failed".to_string());
+    }
+    if duress_pin == approval.pin {
+        return Err("Duress PIN must differ from the normal PIN".to_string());
+    }
+
+    let mut supervisors = load_supervisors()?;
+    let record = supervisors
+        .iter_mut()
+        .find(|r| r.supervisor_id == approval.supervisor_id)
+        .ok_or_else(|| "Supervisor approval failed".to_string())?;
+    let salt = BASE64.decode(&record.salt).map_err(|e| e.to_string())?;
+    record.duress_pin_hash = Some(hash_pin(&salt, duress_pin)?);
+    storage::save_json(SUPERVISORS_FILE, &supervisors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_support::TempDataDir;
+
+    fn legacy_hash(salt: &[u8], pin: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(salt);
+        hasher.update(pin.as_bytes());
+        BASE64.encode(hasher.finalize())
+    }
+
+    #[test]
+    fn test_match_pin_distinguishes_duress_pin() {
+        let salt = [3u8; 16];
+        let record = SupervisorRecord {
+            supervisor_id: "S1".to_string(),
+            salt: BASE64.encode(salt),
+            pin_hash: hash_pin(&salt, "1234").unwrap(),
+            duress_pin_hash: Some(hash_pin(&salt, "4321").unwrap()),
+        };
+
+        assert_eq!(match_pin(&record, "1234").unwrap(), PinMatch::Normal);
+        assert_eq!(match_pin(&record, "4321").unwrap(), PinMatch::Duress);
+        assert!(match_pin(&record, "0000").is_err());
+    }
+
+    #[test]
+    fn test_hash_pin_uses_argon2() {
+        let salt = [3u8; 16];
+        let hash = hash_pin(&salt, "1234").unwrap();
+        assert!(hash.starts_with(ARGON2_PREFIX));
+        assert_ne!(hash, format!("{}{}", ARGON2_PREFIX, legacy_hash(&salt, "1234")));
+    }
+
+    #[test]
+    fn test_validate_pin_requires_digits() {
+        assert!(validate_pin("1234").is_ok());
+        assert!(validate_pin("123").is_err());
+        assert!(validate_pin("12a4").is_err());
+        // 全角数字は4文字でも12バイトあるが数字ではない
+        assert!(validate_pin("１２３４").is_err());
+    }
+
+    #[test]
+    fn test_legacy_hash_is_upgraded_on_approval() {
+        let _dir = TempDataDir::new();
+        let salt = [5u8; 16];
+        let record = SupervisorRecord {
+            supervisor_id: "S1".to_string(),
+            salt: BASE64.encode(salt),
+            pin_hash: legacy_hash(&salt, "1234"),
+            duress_pin_hash: Some(legacy_hash(&salt, "4321")),
+        };
+        storage::save_json(SUPERVISORS_FILE, &vec![record]).unwrap();
+
+        let approval = SupervisorApproval {
+            supervisor_id: "S1".to_string(),
+            pin: "1234".to_string(),
+        };
+        assert_eq!(check_approval(&approval).unwrap(), PinMatch::Normal);
+
+        let record = load_supervisors().unwrap().remove(0);
+        assert!(!is_legacy_hash(&record.pin_hash));
+        // 入力されていない脅迫PINは旧形式のまま照合できる
+        assert!(is_legacy_hash(record.duress_pin_hash.as_ref().unwrap()));
+        assert_eq!(match_pin(&record, "1234").unwrap(), PinMatch::Normal);
+        assert_eq!(match_pin(&record, "4321").unwrap(), PinMatch::Duress);
+    }
+}
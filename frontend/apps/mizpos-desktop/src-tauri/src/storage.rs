@@ -0,0 +1,269 @@
+//! ローカル保存モジュール
+//!
+//! 端末のデータディレクトリ配下にJSONファイルとして状態を保存する
+//! 件数が増え、部分的に読み書きするデータは SQLite のデータベースファイルに保存する
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+/// データディレクトリ名
+const DATA_DIR_NAME: &str = "com.miz.mizpos";
+
+/// データディレクトリのパスを取得
+pub fn data_dir() -> Option<PathBuf> {
+    #[cfg(test)]
+    if let Some(dir) = test_support::current() {
+        return Some(dir);
+    }
+    dirs::data_local_dir().map(|p| p.join(DATA_DIR_NAME))
+}
+
+/// JSONファイルを読み込む（存在しない場合はNone）
+///
+/// 読めない・壊れている場合はエラーにする（既定値で上書きしてデータを消さないため）
+pub fn load_json<T: DeserializeOwned>(file_name: &str) -> Result<Option<T>, String> {
+    let path = data_dir()
+        .ok_or_else(|| "Cannot determine data directory".to_string())?
+        .join(file_name);
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", file_name, e)),
+    };
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Broken file {}: {}", file_name, e))
+}
+
+/// JSONファイルに保存
+pub fn save_json<T: Serialize>(file_name: &str, value: &T) -> Result<(), String> {
+    let path = data_dir()
+        .ok_or_else(|| "Cannot determine data directory".to_string())?
+        .join(file_name);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let content =
+        serde_json::to_string(value).map_err(|e| format!("Failed to serialize: {}", e))?;
+
+    // 書き込み途中で落ちても壊れないよう一時ファイル経由で置き換える
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, content).map_err(|e| format!("Failed to write file: {}", e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to write file: {}", e))?;
+
+    Ok(())
+}
+
+/// ファイルを削除（存在しない場合は何もしない）
+pub fn remove_file(file_name: &str) -> Result<(), String> {
+    let path = data_dir()
+        .ok_or_else(|| "Cannot determine data directory".to_string())?
+        .join(file_name);
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove {}: {}", file_name, e)),
+    }
+}
+
+/// ディレクトリを中身ごと削除（存在しない場合は何もしない）
+pub fn remove_dir_all(dir_name: &str) -> Result<(), String> {
+    let path = data_dir()
+        .ok_or_else(|| "Cannot determine data directory".to_string())?
+        .join(dir_name);
+    match fs::remove_dir_all(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove {}: {}", dir_name, e)),
+    }
+}
+
+/// SQLite のデータベースを開く（存在しない場合は作成）
+pub fn open_sqlite(file_name: &str) -> Result<rusqlite::Connection, String> {
+    let path = data_dir()
+        .ok_or_else(|| "Cannot determine data directory".to_string())?
+        .join(file_name);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let conn = rusqlite::Connection::open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", file_name, e))?;
+    // 書き込み途中で落ちてもデータベースが壊れないよう WAL で書き込む
+    conn.pragma_update(None, "journal_mode", "WAL")
+        .map_err(|e| format!("Failed to open {}: {}", file_name, e))?;
+    Ok(conn)
+}
+
+/// 現在時刻（UNIX秒）を取得
+pub fn now_unix() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// JSON Lines ファイルに1行追記
+pub fn append_jsonl<T: Serialize>(file_name: &str, value: &T) -> Result<(), String> {
+    use std::io::Write;
+
+    let path = data_dir()
+        .ok_or_else(|| "Cannot determine data directory".to_string())?
+        .join(file_name);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let mut line =
+        serde_json::to_string(value).map_err(|e| format!("Failed to serialize: {}", e))?;
+    line.push('\n');
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open file: {}", e))?;
+    file.write_all(line.as_bytes())
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+    file.sync_data()
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+
+    Ok(())
+}
+
+/// JSON Lines ファイルを全件書き換える（保存期間経過後の消去など）
+pub fn save_jsonl<T: Serialize>(file_name: &str, values: &[T]) -> Result<(), String> {
+    let path = data_dir()
+        .ok_or_else(|| "Cannot determine data directory".to_string())?
+        .join(file_name);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let mut content = String::new();
+    for value in values {
+        content.push_str(
+            &serde_json::to_string(value).map_err(|e| format!("Failed to serialize: {}", e))?,
+        );
+        content.push('\n');
+    }
+
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, content).map_err(|e| format!("Failed to write file: {}", e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to write file: {}", e))?;
+
+    Ok(())
+}
+
+/// JSON Lines ファイルを読み込む（壊れた行は読み飛ばす）
+pub fn load_jsonl<T: DeserializeOwned>(file_name: &str) -> Vec<T> {
+    let Some(path) = data_dir().map(|p| p.join(file_name)) else {
+        return vec![];
+    };
+    let Ok(content) = fs::read_to_string(path) else {
+        return vec![];
+    };
+    content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+/// JSON Lines ファイルを読み込む（壊れた行があればエラー）
+///
+/// 読み込んだ内容で書き換える場合に、壊れた行を黙って消さないために使う
+pub fn load_jsonl_strict<T: DeserializeOwned>(file_name: &str) -> Result<Vec<T>, String> {
+    let path = data_dir()
+        .ok_or_else(|| "Cannot determine data directory".to_string())?
+        .join(file_name);
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(format!("Failed to read file: {}", e)),
+    };
+    content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| serde_json::from_str(l).map_err(|e| format!("Broken line in {}: {}", file_name, e)))
+        .collect()
+}
+
+/// テスト用の一時データディレクトリ（テストのスレッドごとに切り替える）
+#[cfg(test)]
+pub mod test_support {
+    use std::cell::RefCell;
+    use std::path::PathBuf;
+
+    thread_local! {
+        static DATA_DIR: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+    }
+
+    pub(super) fn current() -> Option<PathBuf> {
+        DATA_DIR.with(|dir| dir.borrow().clone())
+    }
+
+    /// 生きている間、このスレッドのデータディレクトリを一時ディレクトリにする
+    pub struct TempDataDir(PathBuf);
+
+    impl TempDataDir {
+        pub fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("mizpos-test-{}", uuid::Uuid::new_v4()));
+            std::fs::create_dir_all(&dir).unwrap();
+            DATA_DIR.with(|d| *d.borrow_mut() = Some(dir.clone()));
+            TempDataDir(dir)
+        }
+
+        /// データディレクトリ配下のファイルを直接書き込む（壊れたファイルの再現など）
+        pub fn write(&self, file_name: &str, content: &str) {
+            let path = self.0.join(file_name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        pub fn read(&self, file_name: &str) -> String {
+            std::fs::read_to_string(self.0.join(file_name)).unwrap()
+        }
+    }
+
+    impl Drop for TempDataDir {
+        fn drop(&mut self) {
+            DATA_DIR.with(|d| *d.borrow_mut() = None);
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::TempDataDir;
+    use super::*;
+
+    #[test]
+    fn test_load_json_separates_missing_and_broken_files() {
+        let dir = TempDataDir::new();
+        assert_eq!(load_json::<Vec<u32>>("missing.json"), Ok(None));
+
+        dir.write("broken.json", "[1, 2");
+        assert!(load_json::<Vec<u32>>("broken.json").is_err());
+        // 壊れたファイルはそのまま残す
+        assert_eq!(dir.read("broken.json"), "[1, 2");
+
+        save_json("nested/ok.json", &vec![1u32, 2]).unwrap();
+        assert_eq!(load_json::<Vec<u32>>("nested/ok.json"), Ok(Some(vec![1, 2])));
+    }
+
+    #[test]
+    fn test_save_jsonl_creates_parent_directory() {
+        let _dir = TempDataDir::new();
+        save_jsonl("events/e1/log.jsonl", &[1u32, 2]).unwrap();
+        assert_eq!(load_jsonl::<u32>("events/e1/log.jsonl"), vec![1, 2]);
+    }
+}
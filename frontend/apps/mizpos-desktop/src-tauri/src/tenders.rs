@@ -0,0 +1,180 @@
+//! 支払（分割払い）の検証モジュール
+//!
+//! 1会計を複数の支払手段（現金・カード・商品券など）に分けて支払う場合に、支払の合計が売上を満たすか、
+//! おつりをどの支払から出すかを支払手段ごとの規則で決め、合わない支払情報を印刷・記録の前に止める
+//!
+//! - 現金・おつりを出せる商品券: 超過分をおつりとして返す
+//! - おつりを出せない商品券: 超過分はおつりにせず切り捨てる（お客様に確認済みのもの）
+//! - それ以外（カード・電子マネーなど）: 売上を超えて支払えない
+
+use crate::receipt::{PaymentInfo, ReceiptData};
+use crate::vouchers::{self, VoucherKind};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 支払手段ごとのおつりの規則
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeRule {
+    /// 超過分をおつりとして返す
+    Change,
+    /// 超過分は切り捨てる
+    Forfeit,
+    /// 超過できない
+    Exact,
+}
+
+/// 支払1件の充当結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct AppliedTender {
+    pub method: String,
+    /// 受け取った額
+    pub amount: u32,
+    pub rule: ChangeRule,
+    /// 売上に充てた額
+    pub applied: u32,
+    /// この支払から返すおつり
+    pub change: u32,
+    /// この支払の切り捨てた超過分
+    pub forfeited: u32,
+}
+
+/// 支払の検証結果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct TenderBreakdown {
+    /// 受け取った額の合計
+    pub paid: u32,
+    /// おつりの合計
+    pub change: u32,
+    /// 切り捨てた超過分の合計
+    pub forfeited: u32,
+    /// 支払ごとの充当結果（支払情報の順）
+    pub tenders: Vec<AppliedTender>,
+}
+
+/// 支払手段のおつりの規則（商品券は端末設定のカタログで判定）
+pub fn change_rule(catalog: &[VoucherKind], payment: &PaymentInfo) -> ChangeRule {
+    if payment.is_cash() {
+        return ChangeRule::Change;
+    }
+    match vouchers::find(catalog, &payment.method) {
+        Some(kind) if kind.change_allowed => ChangeRule::Change,
+        Some(_) => ChangeRule::Forfeit,
+        None => ChangeRule::Exact,
+    }
+}
+
+/// 支払情報を検証し、支払ごとの充当額・おつりを計算
+///
+/// 超過できない支払、おつりを出せない商品券、おつりを出せる支払の順に売上へ充てる
+pub fn validate(catalog: &[VoucherKind], total: u32, payments: &[PaymentInfo]) -> Result<TenderBreakdown, String> {
+    if payments.is_empty() && total > 0 {
+        return Err("No payments".to_string());
+    }
+    if let Some(p) = payments.iter().find(|p| p.amount == 0 || p.method.is_empty()) {
+        return Err(format!("Invalid payment: {:?} {}", p.method, p.amount));
+    }
+    let paid: u64 = payments.iter().map(|p| p.amount as u64).sum();
+    let paid = u32::try_from(paid).map_err(|_| "Payment total too large".to_string())?;
+    if paid < total {
+        return Err(format!("Payments do not cover the total: {} < {}", paid, total));
+    }
+
+    let mut tenders: Vec<AppliedTender> = payments
+        .iter()
+        .map(|p| AppliedTender {
+            method: p.method.clone(),
+            amount: p.amount,
+            rule: change_rule(catalog, p),
+            applied: 0,
+            change: 0,
+            forfeited: 0,
+        })
+        .collect();
+
+    let mut due = total;
+    for rule in [ChangeRule::Exact, ChangeRule::Forfeit, ChangeRule::Change] {
+        for tender in tenders.iter_mut().filter(|t| t.rule == rule) {
+            tender.applied = tender.amount.min(due);
+            due -= tender.applied;
+            let excess = tender.amount - tender.applied;
+            match rule {
+                ChangeRule::Exact if excess > 0 => {
+                    return Err(format!("{} cannot exceed the amount due", tender.method));
+                }
+                ChangeRule::Exact => {}
+                ChangeRule::Forfeit => tender.forfeited = excess,
+                ChangeRule::Change => tender.change = excess,
+            }
+        }
+    }
+
+    Ok(TenderBreakdown {
+        paid,
+        change: tenders.iter().map(|t| t.change).sum(),
+        forfeited: tenders.iter().map(|t| t.forfeited).sum(),
+        tenders,
+    })
+}
+
+/// レシートの支払情報を検証
+pub fn validate_receipt(catalog: &[VoucherKind], receipt: &ReceiptData) -> Result<TenderBreakdown, String> {
+    validate(catalog, receipt.total, &receipt.payments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payments(list: &[(&str, u32)]) -> Vec<PaymentInfo> {
+        list.iter()
+            .map(|&(method, amount)| PaymentInfo {
+                method: method.to_string(),
+                amount,
+            })
+            .collect()
+    }
+
+    fn applied(breakdown: &TenderBreakdown) -> Vec<(u32, u32, u32)> {
+        breakdown
+            .tenders
+            .iter()
+            .map(|t| (t.applied, t.change, t.forfeited))
+            .collect()
+    }
+
+    #[test]
+    fn test_change_comes_from_cash_after_card_and_vouchers() {
+        let catalog = vouchers::default_catalog();
+        let breakdown = validate(
+            &catalog,
+            4500,
+            &payments(&[("現金", 2000), ("クレジットカード", 2000), ("voucher_event", 1000)]),
+        )
+        .unwrap();
+        // カード → おつりなしの商品券 → 現金の順に充てる
+        assert_eq!(applied(&breakdown), vec![(1500, 500, 0), (2000, 0, 0), (1000, 0, 0)]);
+        assert_eq!((breakdown.paid, breakdown.change, breakdown.forfeited), (5000, 500, 0));
+
+        // おつりを出せない商品券の超過分は切り捨て、おつりを出せる商品券はおつりを出す
+        let breakdown = validate(
+            &catalog,
+            800,
+            &payments(&[("イベント主催者発行商品券", 1000)]),
+        )
+        .unwrap();
+        assert_eq!(applied(&breakdown), vec![(800, 0, 200)]);
+        let breakdown = validate(&catalog, 800, &payments(&[("voucher_department", 1000)])).unwrap();
+        assert_eq!(breakdown.change, 200);
+    }
+
+    #[test]
+    fn test_rejects_inconsistent_payments() {
+        let catalog = vouchers::default_catalog();
+        assert!(validate(&catalog, 3000, &payments(&[("現金", 1000), ("クレジットカード", 1000)])).is_err());
+        assert!(validate(&catalog, 3000, &payments(&[("クレジットカード", 3500)])).is_err());
+        assert!(validate(&catalog, 3000, &payments(&[("クレジットカード", 3000), ("現金", 0)])).is_err());
+        assert!(validate(&catalog, 3000, &[]).is_err());
+        assert_eq!(validate(&catalog, 0, &[]).unwrap(), TenderBreakdown::default());
+    }
+}
@@ -2,15 +2,26 @@
 //!
 //! Ed25519キーペアを生成し、OS Keychainに保存、署名を生成する
 //! Keychainが使えない場合はファイルベースのフォールバックを使用
+//! iOSではKeychain Servicesが常に使えるため、フォールバックファイルは作らない
+//!
+//! 秘密鍵とそのBase64・フォールバックファイルの内容は、使い終わったらメモリから消去する
+//! 鍵素材はログやエラーメッセージに含めない
+//! 署名の生成とKeychainの操作には回数制限をかける（rate_limit モジュール）
 
+use crate::key_usage::{self, KeyUsageSummary};
+use crate::rate_limit;
+use crate::storage;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
-use ed25519_dalek::{Signature, Signer, SigningKey};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
 use std::fs;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 /// Keychainのサービス名
 const KEYCHAIN_SERVICE: &str = "com.miz.mizpos";
@@ -20,14 +31,25 @@ const KEYCHAIN_ACCOUNT_PRIVATE_KEY: &str = "terminal-private-key";
 const KEYCHAIN_ACCOUNT_TERMINAL_ID: &str = "terminal-id";
 /// フォールバック用ファイル名
 const FALLBACK_CREDENTIALS_FILE: &str = "terminal_credentials.json";
+/// 組織・レジ番号の割り当ての保存ファイル名
+const SEAT_BINDING_FILE: &str = "seat_binding.json";
 
-/// フォールバック用の認証情報
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// フォールバック用の認証情報（破棄時に消去）
+#[derive(Clone, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
 struct FallbackCredentials {
     terminal_id: String,
     private_key: String, // Base64
 }
 
+impl std::fmt::Debug for FallbackCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FallbackCredentials")
+            .field("terminal_id", &self.terminal_id)
+            .field("private_key", &"<redacted>")
+            .finish()
+    }
+}
+
 /// フォールバック用のファイルパスを取得
 #[cfg(not(target_os = "android"))]
 fn get_fallback_path() -> Option<PathBuf> {
@@ -38,12 +60,12 @@ fn get_fallback_path() -> Option<PathBuf> {
 #[cfg(not(target_os = "android"))]
 fn load_from_fallback() -> Option<FallbackCredentials> {
     let path = get_fallback_path()?;
-    let content = fs::read_to_string(&path).ok()?;
+    let content = Zeroizing::new(fs::read_to_string(&path).ok()?);
     serde_json::from_str(&content).ok()
 }
 
 /// フォールバックに認証情報を保存
-#[cfg(not(target_os = "android"))]
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
 fn save_to_fallback(creds: &FallbackCredentials) -> Result<(), TerminalAuthError> {
     let path = get_fallback_path()
         .ok_or_else(|| TerminalAuthError::KeychainError("Cannot determine data directory".to_string()))?;
@@ -54,10 +76,12 @@ fn save_to_fallback(creds: &FallbackCredentials) -> Result<(), TerminalAuthError
             .map_err(|e| TerminalAuthError::KeychainError(format!("Failed to create directory: {}", e)))?;
     }
 
-    let content = serde_json::to_string(creds)
-        .map_err(|e| TerminalAuthError::KeychainError(format!("Failed to serialize: {}", e)))?;
+    let content = Zeroizing::new(
+        serde_json::to_string(creds)
+            .map_err(|e| TerminalAuthError::KeychainError(format!("Failed to serialize: {}", e)))?,
+    );
 
-    fs::write(&path, content)
+    fs::write(&path, content.as_bytes())
         .map_err(|e| TerminalAuthError::KeychainError(format!("Failed to write file: {}", e)))?;
 
     Ok(())
@@ -85,7 +109,7 @@ pub enum TerminalStatus {
 }
 
 /// QRコード用のペイロード
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct RegistrationQrPayload {
     /// バージョン
     pub v: u8,
@@ -99,32 +123,81 @@ pub struct RegistrationQrPayload {
     pub os: String,
     /// 生成日時 (ISO8601)
     pub created_at: String,
+    /// 割り当てる組織ID（複数レジで登録する場合）
+    #[serde(default)]
+    pub organization_id: Option<String>,
+    /// レジの表示名（レジ1、レジ2など）
+    #[serde(default)]
+    pub seat_label: Option<String>,
+}
+
+/// 登録時に指定する組織とレジ（フロントエンドから渡される）
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct SeatRequest {
+    pub organization_id: String,
+    pub seat_label: String,
+}
+
+/// 端末に割り当てた組織とレジ
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SeatBinding {
+    pub organization_id: String,
+    pub seat_label: String,
+    /// 割り当てた日時（UNIX秒）
+    pub bound_at: u64,
 }
 
 /// 端末認証の結果
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct TerminalAuthResult {
     pub status: String,
     pub terminal_id: Option<String>,
     pub public_key: Option<String>,
     pub error: Option<String>,
+    /// 署名の回数・日時と異常（初期化済みの場合のみ）
+    #[serde(default)]
+    pub key_usage: Option<KeyUsageSummary>,
 }
 
 /// 署名リクエスト用のデータ
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// 組織・レジを割り当てた端末では、組織とレジも署名対象に含める（signing_message を参照）
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct SignatureData {
     pub terminal_id: String,
     pub timestamp: u64,
     pub signature: String,
+    #[serde(default)]
+    pub organization_id: Option<String>,
+    #[serde(default)]
+    pub seat_label: Option<String>,
+}
+
+/// サポート向けの端末確認の結果
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TerminalVerification {
+    pub terminal_id: String,
+    /// サポートが読み上げた公開鍵（サーバーの記録）とこの端末の鍵が一致したか
+    pub key_matches: bool,
+    pub challenge: String,
+    /// チャレンジへの署名（サポート側で check_verification により検証する）
+    pub signature: SignatureData,
+    /// この端末の公開鍵とチャレンジから作る確認コード（XXXX-XXXX）
+    pub code: String,
 }
 
 /// エラー型
-#[derive(Debug)]
+///
+/// コマンドのエラーとしてフロントエンドに渡す（`{ kind, message }` の形）
+#[derive(Debug, Serialize, Type)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
 pub enum TerminalAuthError {
     KeychainError(String),
     CryptoError(String),
     NotInitialized,
     InvalidKey,
+    /// 回数制限によるロックアウト中
+    RateLimited(String),
 }
 
 impl std::fmt::Display for TerminalAuthError {
@@ -134,6 +207,7 @@ impl std::fmt::Display for TerminalAuthError {
             Self::CryptoError(msg) => write!(f, "Crypto error: {}", msg),
             Self::NotInitialized => write!(f, "Terminal not initialized"),
             Self::InvalidKey => write!(f, "Invalid key"),
+            Self::RateLimited(msg) => write!(f, "Rate limited: {}", msg),
         }
     }
 }
@@ -141,19 +215,22 @@ impl std::fmt::Display for TerminalAuthError {
 impl std::error::Error for TerminalAuthError {}
 
 /// 秘密鍵をBase64から復元
+///
+/// デコードエラーには入力の一部が含まれるため、エラーメッセージには残さない
 #[cfg(not(target_os = "android"))]
 fn decode_private_key(base64_key: &str) -> Result<SigningKey, TerminalAuthError> {
-    let key_bytes = BASE64
-        .decode(base64_key)
-        .map_err(|e| TerminalAuthError::CryptoError(e.to_string()))?;
+    let key_bytes = Zeroizing::new(
+        BASE64
+            .decode(base64_key)
+            .map_err(|_| TerminalAuthError::CryptoError("Invalid private key encoding".to_string()))?,
+    );
 
     if key_bytes.len() != 32 {
         return Err(TerminalAuthError::InvalidKey);
     }
 
-    let key_array: [u8; 32] = key_bytes
-        .try_into()
-        .map_err(|_| TerminalAuthError::InvalidKey)?;
+    let mut key_array = Zeroizing::new([0u8; 32]);
+    key_array.copy_from_slice(&key_bytes);
 
     Ok(SigningKey::from_bytes(&key_array))
 }
@@ -165,6 +242,7 @@ fn load_private_key_from_keychain() -> Result<Option<SigningKey>, TerminalAuthEr
     if let Ok(entry) = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT_PRIVATE_KEY) {
         match entry.get_password() {
             Ok(base64_key) => {
+                let base64_key = Zeroizing::new(base64_key);
                 return Ok(Some(decode_private_key(&base64_key)?));
             }
             Err(keyring::Error::NoEntry) => {
@@ -186,9 +264,10 @@ fn load_private_key_from_keychain() -> Result<Option<SigningKey>, TerminalAuthEr
 
 /// Keychainに秘密鍵を保存（フォールバック付き）
 /// terminal_idも一緒に渡してフォールバック保存に使用
-#[cfg(not(target_os = "android"))]
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
 fn save_private_key_to_keychain(signing_key: &SigningKey, terminal_id: &str) -> Result<(), TerminalAuthError> {
-    let base64_key = BASE64.encode(signing_key.to_bytes());
+    let key_bytes = Zeroizing::new(signing_key.to_bytes());
+    let base64_key = Zeroizing::new(BASE64.encode(key_bytes.as_slice()));
 
     // Keychainへの保存を試みる（失敗しても続行）
     if let Ok(entry) = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT_PRIVATE_KEY) {
@@ -198,7 +277,7 @@ fn save_private_key_to_keychain(signing_key: &SigningKey, terminal_id: &str) ->
     // フォールバックにも保存（Keychainが失敗しても確実に保存）
     let creds = FallbackCredentials {
         terminal_id: terminal_id.to_string(),
-        private_key: base64_key,
+        private_key: base64_key.to_string(),
     };
     save_to_fallback(&creds)?;
 
@@ -225,14 +304,14 @@ fn load_terminal_id_from_keychain() -> Result<Option<String>, TerminalAuthError>
 
     // フォールバックから読み込む
     if let Some(creds) = load_from_fallback() {
-        return Ok(Some(creds.terminal_id));
+        return Ok(Some(creds.terminal_id.clone()));
     }
 
     Ok(None)
 }
 
 /// Keychainに端末IDを保存（フォールバック付き）
-#[cfg(not(target_os = "android"))]
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
 fn save_terminal_id_to_keychain(terminal_id: &str) -> Result<(), TerminalAuthError> {
     // Keychainへの保存を試みる（失敗しても続行）
     if let Ok(entry) = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT_TERMINAL_ID) {
@@ -242,9 +321,72 @@ fn save_terminal_id_to_keychain(terminal_id: &str) -> Result<(), TerminalAuthErr
     Ok(())
 }
 
+/// Keychainに値を保存（iOS、失敗はエラーにする）
+#[cfg(target_os = "ios")]
+fn save_to_ios_keychain(account: &str, value: &str) -> Result<(), TerminalAuthError> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, account)
+        .and_then(|entry| entry.set_password(value))
+        .map_err(|e| TerminalAuthError::KeychainError(format!("Failed to save to Keychain: {}", e)))
+}
+
+/// Keychainに秘密鍵を保存（iOS）
+#[cfg(target_os = "ios")]
+fn save_private_key_to_keychain(signing_key: &SigningKey, _terminal_id: &str) -> Result<(), TerminalAuthError> {
+    let key_bytes = Zeroizing::new(signing_key.to_bytes());
+    let base64_key = Zeroizing::new(BASE64.encode(key_bytes.as_slice()));
+    save_to_ios_keychain(KEYCHAIN_ACCOUNT_PRIVATE_KEY, &base64_key)
+}
+
+/// Keychainに端末IDを保存（iOS）
+#[cfg(target_os = "ios")]
+fn save_terminal_id_to_keychain(terminal_id: &str) -> Result<(), TerminalAuthError> {
+    save_to_ios_keychain(KEYCHAIN_ACCOUNT_TERMINAL_ID, terminal_id)
+}
+
+/// 割り当てた組織とレジを取得
+pub fn seat_binding() -> Result<Option<SeatBinding>, String> {
+    storage::load_json(SEAT_BINDING_FILE)
+}
+
+/// 組織とレジを割り当てる（登録ペイロードの生成時に呼ぶ）
+pub fn bind_seat(seat: &SeatRequest) -> Result<SeatBinding, TerminalAuthError> {
+    let organization_id = seat.organization_id.trim();
+    let seat_label = seat.seat_label.trim();
+    if organization_id.is_empty() || seat_label.is_empty() || seat_label.chars().count() > 32 {
+        return Err(TerminalAuthError::KeychainError(
+            "Organization ID and a seat label of up to 32 characters are required".to_string(),
+        ));
+    }
+    // 署名対象の区切り文字と紛れないようにする
+    if organization_id.contains(':') || seat_label.contains(':') {
+        return Err(TerminalAuthError::KeychainError(
+            "Organization ID and seat label must not contain ':'".to_string(),
+        ));
+    }
+    let binding = SeatBinding {
+        organization_id: organization_id.to_string(),
+        seat_label: seat_label.to_string(),
+        bound_at: storage::now_unix(),
+    };
+    storage::save_json(SEAT_BINDING_FILE, &binding).map_err(TerminalAuthError::KeychainError)?;
+    Ok(binding)
+}
+
+/// 割り当てを登録ペイロードに反映
+pub fn apply_seat_binding(payload: &mut RegistrationQrPayload) -> Result<(), TerminalAuthError> {
+    let binding = seat_binding().map_err(TerminalAuthError::KeychainError)?;
+    payload.organization_id = binding.as_ref().map(|b| b.organization_id.clone());
+    payload.seat_label = binding.map(|b| b.seat_label);
+    Ok(())
+}
+
 /// Keychainから認証情報を削除（フォールバック含む）
+///
+/// 組織・レジの割り当ても新しい端末IDで登録し直すため削除する
 #[cfg(not(target_os = "android"))]
 pub fn clear_keychain() -> Result<(), TerminalAuthError> {
+    rate_limit::acquire("keychain", &rate_limit::KEYCHAIN).map_err(TerminalAuthError::RateLimited)?;
+
     // Keychainから秘密鍵を削除
     if let Ok(entry) = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT_PRIVATE_KEY) {
         let _ = entry.delete_credential();
@@ -257,6 +399,9 @@ pub fn clear_keychain() -> Result<(), TerminalAuthError> {
 
     // フォールバックファイルも削除
     clear_fallback();
+    if let Some(path) = storage::data_dir().map(|p| p.join(SEAT_BINDING_FILE)) {
+        let _ = fs::remove_file(path);
+    }
 
     Ok(())
 }
@@ -277,6 +422,7 @@ pub fn get_terminal_status() -> Result<TerminalAuthResult, TerminalAuthError> {
                 terminal_id: Some(id),
                 public_key: Some(public_key_base64),
                 error: None,
+                key_usage: key_usage::summary().ok(),
             })
         }
         (None, None) => Ok(TerminalAuthResult {
@@ -284,6 +430,7 @@ pub fn get_terminal_status() -> Result<TerminalAuthResult, TerminalAuthError> {
             terminal_id: None,
             public_key: None,
             error: None,
+            key_usage: None,
         }),
         _ => {
             // 不整合な状態（片方だけある）- クリアして再初期化
@@ -293,14 +440,22 @@ pub fn get_terminal_status() -> Result<TerminalAuthResult, TerminalAuthError> {
                 terminal_id: None,
                 public_key: None,
                 error: Some("Inconsistent state, cleared".to_string()),
+                key_usage: None,
             })
         }
     }
 }
 
 /// 新しいキーペアを生成して保存
+///
+/// 組織とレジを指定した場合は割り当ててペイロードに含める
 #[cfg(not(target_os = "android"))]
-pub fn initialize_terminal(device_name: &str) -> Result<RegistrationQrPayload, TerminalAuthError> {
+pub fn initialize_terminal(
+    device_name: &str,
+    seat: Option<&SeatRequest>,
+) -> Result<RegistrationQrPayload, TerminalAuthError> {
+    rate_limit::acquire("keychain", &rate_limit::KEYCHAIN).map_err(TerminalAuthError::RateLimited)?;
+
     // 既存のキーがあればエラー
     if load_private_key_from_keychain()?.is_some() {
         return Err(TerminalAuthError::KeychainError(
@@ -327,21 +482,52 @@ pub fn initialize_terminal(device_name: &str) -> Result<RegistrationQrPayload, T
     let os = get_os_type();
 
     // QRコード用ペイロードを作成
-    let payload = RegistrationQrPayload {
+    let mut payload = RegistrationQrPayload {
         v: 1,
         terminal_id,
         public_key: BASE64.encode(verifying_key.to_bytes()),
         device_name: device_name.to_string(),
         os,
         created_at: now,
+        organization_id: None,
+        seat_label: None,
     };
+    if let Some(seat) = seat {
+        bind_seat(seat)?;
+    }
+    apply_seat_binding(&mut payload)?;
 
     Ok(payload)
 }
 
+/// 署名対象の文字列を作る
+///
+/// 署名対象は `{terminal_id}:{timestamp}`、組織・レジを割り当てた端末では
+/// `{terminal_id}:{timestamp}:{organization_id}:{seat_label}` となり、メッセージがある場合は末尾に `:{message}` を付ける
+pub fn signing_message(
+    terminal_id: &str,
+    timestamp: u64,
+    organization_id: Option<&str>,
+    seat_label: Option<&str>,
+    message: &str,
+) -> String {
+    let mut signed = format!("{}:{}", terminal_id, timestamp);
+    if let (Some(organization_id), Some(seat_label)) = (organization_id, seat_label) {
+        signed.push_str(&format!(":{}:{}", organization_id, seat_label));
+    }
+    if !message.is_empty() {
+        signed.push(':');
+        signed.push_str(message);
+    }
+    signed
+}
+
 /// 署名を生成
+///
+/// 署名対象は signing_message を参照
 #[cfg(not(target_os = "android"))]
-pub fn sign_message(_message: &str) -> Result<SignatureData, TerminalAuthError> {
+pub fn sign_message(message: &str) -> Result<SignatureData, TerminalAuthError> {
+    rate_limit::acquire("signature", &rate_limit::SIGNATURE).map_err(TerminalAuthError::RateLimited)?;
     let signing_key = load_private_key_from_keychain()?.ok_or(TerminalAuthError::NotInitialized)?;
     let terminal_id = load_terminal_id_from_keychain()?.ok_or(TerminalAuthError::NotInitialized)?;
 
@@ -351,13 +537,122 @@ pub fn sign_message(_message: &str) -> Result<SignatureData, TerminalAuthError>
         .as_secs();
 
     // 署名対象のメッセージを構築
-    let sign_message = format!("{}:{}", terminal_id, timestamp);
+    let binding = seat_binding().map_err(TerminalAuthError::KeychainError)?;
+    let sign_message = signing_message(
+        &terminal_id,
+        timestamp,
+        binding.as_ref().map(|b| b.organization_id.as_str()),
+        binding.as_ref().map(|b| b.seat_label.as_str()),
+        message,
+    );
     let signature: Signature = signing_key.sign(sign_message.as_bytes());
+    // 記録に失敗しても署名は返す
+    let _ = key_usage::record();
 
     Ok(SignatureData {
         terminal_id,
         timestamp,
         signature: BASE64.encode(signature.to_bytes()),
+        organization_id: binding.as_ref().map(|b| b.organization_id.clone()),
+        seat_label: binding.map(|b| b.seat_label),
+    })
+}
+
+/// 確認コードに使う文字（Crockford's Base32）
+const CODE_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// 公開鍵とチャレンジから確認コードを作る
+///
+/// 端末とサポートがそれぞれ手元の公開鍵から計算し、印字したコードを読み合わせて鍵の一致を確かめる
+pub fn verification_code(public_key: &str, challenge: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(b"mizpos-terminal-verification\0");
+    hasher.update(public_key.trim().as_bytes());
+    hasher.update(b"\0");
+    hasher.update(challenge.trim().as_bytes());
+    let digest = hasher.finalize();
+
+    // 先頭40ビットを5ビットずつ8文字にする
+    let bits = digest[..5]
+        .iter()
+        .fold(0u64, |acc, b| (acc << 8) | *b as u64);
+    let code: String = (0..8)
+        .rev()
+        .map(|i| CODE_ALPHABET[((bits >> (i * 5)) & 0x1f) as usize] as char)
+        .collect();
+    format!("{}-{}", &code[..4], &code[4..])
+}
+
+/// チャレンジを検証して署名対象のメッセージにする
+fn challenge_message(challenge: &str) -> Result<String, TerminalAuthError> {
+    let challenge = challenge.trim();
+    if challenge.is_empty() || challenge.len() > 64 {
+        return Err(TerminalAuthError::CryptoError(
+            "Challenge must be 1 to 64 characters".to_string(),
+        ));
+    }
+    Ok(format!("verify:{}", challenge))
+}
+
+/// 端末の署名を公開鍵で検証する
+///
+/// メッセージは sign_message に渡したもの。組織・レジは署名データに含まれる値で検証する
+pub fn verify_signature(
+    public_key: &str,
+    message: &str,
+    signature: &SignatureData,
+) -> Result<(), TerminalAuthError> {
+    let key_bytes: [u8; 32] = BASE64
+        .decode(public_key.trim())
+        .map_err(|_| TerminalAuthError::InvalidKey)?
+        .try_into()
+        .map_err(|_| TerminalAuthError::InvalidKey)?;
+    let key = VerifyingKey::from_bytes(&key_bytes).map_err(|_| TerminalAuthError::InvalidKey)?;
+    let signature_bytes = BASE64
+        .decode(&signature.signature)
+        .map_err(|e| TerminalAuthError::CryptoError(e.to_string()))?;
+    let sig = Signature::from_slice(&signature_bytes)
+        .map_err(|e| TerminalAuthError::CryptoError(e.to_string()))?;
+
+    let message = signing_message(
+        &signature.terminal_id,
+        signature.timestamp,
+        signature.organization_id.as_deref(),
+        signature.seat_label.as_deref(),
+        message,
+    );
+    key.verify(message.as_bytes(), &sig)
+        .map_err(|_| TerminalAuthError::CryptoError("Verification failed".to_string()))
+}
+
+/// 端末の応答をサポート側で検証し、確認コードを返す
+///
+/// 署名がサーバーに記録された公開鍵で検証できれば、その端末が秘密鍵を持っていることが分かる
+pub fn check_verification(
+    public_key: &str,
+    challenge: &str,
+    signature: &SignatureData,
+) -> Result<String, TerminalAuthError> {
+    verify_signature(public_key, &challenge_message(challenge)?, signature)?;
+    Ok(verification_code(public_key, challenge))
+}
+
+/// サポートから伝えられた公開鍵とチャレンジで端末を確認する
+///
+/// 鍵が一致しない場合もエラーにせず、この端末の鍵で計算したコードを返す（サポート側のコードと食い違う）
+#[cfg(not(target_os = "android"))]
+pub fn verify_terminal(public_key: &str, challenge: &str) -> Result<TerminalVerification, TerminalAuthError> {
+    let message = challenge_message(challenge)?;
+    let status = get_terminal_status()?;
+    let own_key = status.public_key.ok_or(TerminalAuthError::NotInitialized)?;
+    let signature = sign_message(&message)?;
+
+    Ok(TerminalVerification {
+        terminal_id: signature.terminal_id.clone(),
+        key_matches: own_key == public_key.trim(),
+        challenge: challenge.trim().to_string(),
+        code: verification_code(&own_key, challenge),
+        signature,
     })
 }
 
@@ -392,11 +687,15 @@ fn get_os_type() -> String {
     #[cfg(target_os = "android")]
     return "android".to_string();
 
+    #[cfg(target_os = "ios")]
+    return "ios".to_string();
+
     #[cfg(not(any(
         target_os = "macos",
         target_os = "windows",
         target_os = "linux",
-        target_os = "android"
+        target_os = "android",
+        target_os = "ios"
     )))]
     return "unknown".to_string();
 }
@@ -446,7 +745,10 @@ pub fn get_terminal_status() -> Result<TerminalAuthResult, TerminalAuthError> {
 }
 
 #[cfg(target_os = "android")]
-pub fn initialize_terminal(_device_name: &str) -> Result<RegistrationQrPayload, TerminalAuthError> {
+pub fn initialize_terminal(
+    _device_name: &str,
+    _seat: Option<&SeatRequest>,
+) -> Result<RegistrationQrPayload, TerminalAuthError> {
     Err(TerminalAuthError::KeychainError(
         "Android Keystore not implemented yet".to_string(),
     ))
@@ -466,6 +768,13 @@ pub fn create_auth_signature() -> Result<SignatureData, TerminalAuthError> {
     ))
 }
 
+#[cfg(target_os = "android")]
+pub fn verify_terminal(_public_key: &str, _challenge: &str) -> Result<TerminalVerification, TerminalAuthError> {
+    Err(TerminalAuthError::KeychainError(
+        "Android Keystore not implemented yet".to_string(),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -475,4 +784,84 @@ mod tests {
         let os = get_os_type();
         assert!(!os.is_empty());
     }
+
+    #[test]
+    fn test_fallback_credentials_debug_redacts_private_key() {
+        let creds = FallbackCredentials {
+            terminal_id: "T1".to_string(),
+            private_key: "c2VjcmV0".to_string(),
+        };
+        let debug = format!("{:?}", creds);
+        assert!(debug.contains("T1"));
+        assert!(!debug.contains("c2VjcmV0"));
+    }
+
+    #[test]
+    fn test_verification_code_format() {
+        let code = verification_code("cHVibGlj", "1234");
+        assert_eq!(code.len(), 9);
+        assert_eq!(code.as_bytes()[4], b'-');
+        assert_eq!(code, verification_code(" cHVibGlj ", "1234"));
+        assert_ne!(code, verification_code("cHVibGlj", "1235"));
+        assert_ne!(code, verification_code("b3RoZXI=", "1234"));
+    }
+
+    #[test]
+    fn test_check_verification() {
+        let signing_key = SigningKey::from_bytes(&[9u8; 32]);
+        let public_key = BASE64.encode(signing_key.verifying_key().to_bytes());
+        let message = signing_message("T1", 100, None, None, &challenge_message("1234").unwrap());
+        let signature = SignatureData {
+            terminal_id: "T1".to_string(),
+            timestamp: 100,
+            signature: BASE64.encode(signing_key.sign(message.as_bytes()).to_bytes()),
+            organization_id: None,
+            seat_label: None,
+        };
+
+        assert_eq!(
+            check_verification(&public_key, "1234", &signature).unwrap(),
+            verification_code(&public_key, "1234")
+        );
+        assert!(check_verification(&public_key, "9999", &signature).is_err());
+        let other = BASE64.encode(SigningKey::from_bytes(&[1u8; 32]).verifying_key().to_bytes());
+        assert!(check_verification(&other, "1234", &signature).is_err());
+    }
+
+    #[test]
+    fn test_seat_binding_is_signed() {
+        assert_eq!(signing_message("T1", 100, None, None, ""), "T1:100");
+        assert_eq!(signing_message("T1", 100, None, None, "m"), "T1:100:m");
+        assert_eq!(
+            signing_message("T1", 100, Some("ORG1"), Some("R1"), "m"),
+            "T1:100:ORG1:R1:m"
+        );
+
+        let signing_key = SigningKey::from_bytes(&[9u8; 32]);
+        let public_key = BASE64.encode(signing_key.verifying_key().to_bytes());
+        let message = signing_message("T1", 100, Some("ORG1"), Some("R1"), "m");
+        let mut signature = SignatureData {
+            terminal_id: "T1".to_string(),
+            timestamp: 100,
+            signature: BASE64.encode(signing_key.sign(message.as_bytes()).to_bytes()),
+            organization_id: Some("ORG1".to_string()),
+            seat_label: Some("R1".to_string()),
+        };
+        assert!(verify_signature(&public_key, "m", &signature).is_ok());
+
+        // 署名後にレジや組織を書き換えると検証できない
+        signature.seat_label = Some("R2".to_string());
+        assert!(verify_signature(&public_key, "m", &signature).is_err());
+        signature.seat_label = None;
+        signature.organization_id = None;
+        assert!(verify_signature(&public_key, "m", &signature).is_err());
+    }
+
+    #[cfg(not(target_os = "android"))]
+    #[test]
+    fn test_decode_private_key_error_does_not_echo_input() {
+        let err = decode_private_key("c2Vj*mV0").unwrap_err();
+        assert!(!err.to_string().contains("c2Vj"));
+        assert!(!err.to_string().contains('*'));
+    }
 }
@@ -0,0 +1,135 @@
+//! 文字の正規化モジュール
+//!
+//! 商品マスタ・手入力の商品名やサークル名には全角英数（ＡＢＣ・１２３）と半角英数、半角カナが混在し、
+//! そのまま印刷すると同じ名前でも字幅が変わって桁がそろわない
+//! 印刷前に NFKC で互換文字をそろえ、端末設定に応じて英字・数字を全角または半角に統一する
+
+use crate::receipt::{ReceiptData, ReceiptItem};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use unicode_normalization::UnicodeNormalization;
+
+/// 英字・数字の字幅の統一方法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum WidthForm {
+    /// 変換しない（NFKC の結果のまま。全角英数は半角になる）
+    #[default]
+    Keep,
+    /// 半角にする
+    Halfwidth,
+    /// 全角にする
+    Fullwidth,
+}
+
+/// 商品名・サークル名の正規化の設定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct TextNormalization {
+    /// NFKC で正規化する（全角英数・半角カナ・丸数字などの互換文字をそろえる）
+    pub nfkc: bool,
+    /// 数字の字幅
+    pub digits: WidthForm,
+    /// 数字以外の英字・記号の字幅
+    pub ascii: WidthForm,
+}
+
+impl Default for TextNormalization {
+    fn default() -> Self {
+        Self {
+            nfkc: true,
+            digits: WidthForm::Keep,
+            ascii: WidthForm::Keep,
+        }
+    }
+}
+
+impl TextNormalization {
+    /// 文字列を正規化
+    pub fn apply(&self, text: &str) -> String {
+        let text: String = if self.nfkc { text.nfkc().collect() } else { text.to_string() };
+        text.chars()
+            .map(|c| {
+                let form = if to_halfwidth(c).is_ascii_digit() { self.digits } else { self.ascii };
+                match form {
+                    WidthForm::Keep => c,
+                    WidthForm::Halfwidth => to_halfwidth(c),
+                    WidthForm::Fullwidth => to_fullwidth(c),
+                }
+            })
+            .collect()
+    }
+
+    /// 明細の商品名・サークル名を正規化
+    pub fn item(&self, item: &ReceiptItem) -> ReceiptItem {
+        ReceiptItem {
+            circle_name: self.apply(&item.circle_name),
+            name: self.apply(&item.name),
+            ..item.clone()
+        }
+    }
+
+    /// レシートの明細とサークル名を正規化
+    pub fn receipt(&self, receipt: &ReceiptData) -> ReceiptData {
+        ReceiptData {
+            circle_name: receipt.circle_name.as_deref().map(|n| self.apply(n)),
+            items: receipt.items.iter().map(|i| self.item(i)).collect(),
+            ..receipt.clone()
+        }
+    }
+}
+
+/// 全角英数・記号・空白を半角にする（それ以外はそのまま）
+pub fn to_halfwidth(c: char) -> char {
+    match c {
+        '\u{3000}' => ' ',
+        '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// 半角英数・記号・空白を全角にする（それ以外はそのまま）
+pub fn to_fullwidth(c: char) -> char {
+    match c {
+        ' ' => '\u{3000}',
+        '!'..='~' => char::from_u32(c as u32 + 0xfee0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// 半角数字を全角数字に変換（数字以外はそのまま）
+pub fn to_fullwidth_digits(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_ascii_digit() { to_fullwidth(c) } else { c })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_unifies_compatibility_characters() {
+        let norm = TextNormalization::default();
+        assert_eq!(norm.apply("ＡＢＣ本 ｶﾀｶﾅ 第１巻"), "ABC本 カタカナ 第1巻");
+        assert_eq!(norm.apply("ABC本 第1巻"), "ABC本 第1巻");
+    }
+
+    #[test]
+    fn test_width_forms() {
+        let norm = TextNormalization {
+            digits: WidthForm::Fullwidth,
+            ascii: WidthForm::Halfwidth,
+            ..Default::default()
+        };
+        assert_eq!(norm.apply("ＶＯＬ.3 ｾｯﾄ"), "VOL.３ セット");
+
+        let norm = TextNormalization {
+            nfkc: false,
+            digits: WidthForm::Halfwidth,
+            ascii: WidthForm::Fullwidth,
+        };
+        assert_eq!(norm.apply("ab 12 ｱ"), "ａｂ\u{3000}12\u{3000}ｱ");
+        assert_eq!(to_fullwidth_digits("1,600円"), "１,６００円");
+    }
+}
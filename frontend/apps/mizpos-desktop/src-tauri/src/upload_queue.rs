@@ -0,0 +1,140 @@
+//! アップロードキューモジュール
+//!
+//! 端末鍵で署名したペイロードをローカルに保持し、フロントエンド経由でサーバーへ送信する
+//! 送信に失敗した場合も再送できるよう永続化しておく
+
+use crate::metrics;
+use crate::storage;
+use crate::terminal_auth;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+/// キューの保存ファイル名
+const UPLOAD_QUEUE_FILE: &str = "upload_queue.json";
+
+/// アップロード種別: 閉局レポート
+pub const KIND_CLOSING_REPORT: &str = "closing_report";
+/// アップロード種別: メールレシートの送信依頼
+pub const KIND_EMAIL_RECEIPT: &str = "email_receipt";
+/// アップロード種別: 脅迫PINの通報
+pub const KIND_DURESS_ALERT: &str = "duress_alert";
+/// アップロード種別: 会員へのポイント付与
+pub const KIND_POINTS_ACCRUAL: &str = "points_accrual";
+/// アップロード種別: 稼働状況の計測値（オプトイン）
+pub const KIND_METRICS: &str = "metrics";
+/// アップロード種別: 未登録のコードから仮登録した商品
+pub const KIND_PROVISIONAL_PRODUCT: &str = "provisional_product";
+/// アップロード種別: イベント終了時のアーカイブの目録
+pub const KIND_EVENT_ARCHIVE: &str = "event_archive";
+
+/// 送信待ちのアップロード
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PendingUpload {
+    /// キュー内ID (UUID)
+    pub id: String,
+    /// 種別（closing_report など）
+    pub kind: String,
+    /// 元データのID（閉局レポートIDなど）
+    pub reference_id: String,
+    /// 送信するJSON文字列（署名対象）
+    pub payload: String,
+    /// 署名データ
+    pub signature: terminal_auth::SignatureData,
+    /// 送信試行回数
+    pub attempts: u32,
+    /// 最後の送信エラー
+    pub last_error: Option<String>,
+    /// キュー投入日時（UNIX秒）
+    pub created_at: u64,
+}
+
+fn load_queue() -> Result<Vec<PendingUpload>, String> {
+    Ok(storage::load_json(UPLOAD_QUEUE_FILE)?.unwrap_or_default())
+}
+
+fn save_queue(queue: &[PendingUpload]) -> Result<(), String> {
+    storage::save_json(UPLOAD_QUEUE_FILE, &queue)
+}
+
+/// ペイロードに署名してキューに追加
+pub fn enqueue<T: Serialize>(
+    kind: &str,
+    reference_id: &str,
+    payload: &T,
+) -> Result<PendingUpload, String> {
+    let payload = serde_json::to_string(payload).map_err(|e| e.to_string())?;
+    let signature = terminal_auth::sign_message(&payload).map_err(|e| e.to_string())?;
+
+    let upload = PendingUpload {
+        id: Uuid::new_v4().to_string(),
+        kind: kind.to_string(),
+        reference_id: reference_id.to_string(),
+        payload,
+        signature,
+        attempts: 0,
+        last_error: None,
+        created_at: storage::now_unix(),
+    };
+
+    let mut queue = load_queue()?;
+    // 同じデータの再送要求は古いものを置き換える
+    queue.retain(|u| !(u.kind == upload.kind && u.reference_id == upload.reference_id));
+    queue.push(upload.clone());
+    save_queue(&queue)?;
+    metrics::record_queue_depth(queue.len());
+
+    Ok(upload)
+}
+
+/// 送信待ちのアップロード一覧を取得
+pub fn list_pending() -> Result<Vec<PendingUpload>, String> {
+    load_queue()
+}
+
+/// 送信完了としてキューから削除
+pub fn mark_uploaded(id: &str) -> Result<(), String> {
+    let mut queue = load_queue()?;
+    let index = queue
+        .iter()
+        .position(|u| u.id == id)
+        .ok_or_else(|| format!("Upload not found: {}", id))?;
+    let upload = queue.remove(index);
+    save_queue(&queue)?;
+    metrics::record_sync(Some(storage::now_unix().saturating_sub(upload.created_at)));
+    metrics::record_queue_depth(queue.len());
+    Ok(())
+}
+
+/// 送信失敗を記録
+pub fn mark_failed(id: &str, error: &str) -> Result<PendingUpload, String> {
+    let mut queue = load_queue()?;
+    let upload = queue
+        .iter_mut()
+        .find(|u| u.id == id)
+        .ok_or_else(|| format!("Upload not found: {}", id))?;
+    upload.attempts += 1;
+    upload.last_error = Some(error.to_string());
+    let updated = upload.clone();
+    save_queue(&queue)?;
+    metrics::record_sync(None);
+    Ok(updated)
+}
+
+/// アップロード失敗時にフロントエンドへ通知するイベント
+///
+/// 受け取った画面で再印刷・再送信を促す
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadFailedEvent {
+    /// キュー内ID（キュー投入自体に失敗した場合はNone）
+    pub upload_id: Option<String>,
+    pub kind: String,
+    pub reference_id: String,
+    pub error: String,
+    pub attempts: u32,
+}
+
+/// キュー投入イベント名
+pub const EVENT_UPLOAD_QUEUED: &str = "upload-queued";
+/// アップロード失敗イベント名
+pub const EVENT_UPLOAD_FAILED: &str = "upload-failed";
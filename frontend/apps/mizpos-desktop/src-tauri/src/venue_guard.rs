@@ -0,0 +1,214 @@
+//! 会場ネットワーク確認モジュール
+//!
+//! 開局時に、端末が会場のWi-Fi（SSID）またはIPアドレス範囲に接続しているかを確認する
+//! 自宅などで本番設定のまま誤ってテスト販売するのを防ぐ
+//! SSIDはOSごとに取得方法が異なるため、フロントエンドから報告してもらう
+//! IPアドレスは端末から外部へ向かう経路のローカルアドレスを使う（実際の通信は行わない）
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::net::{IpAddr, UdpSocket};
+use std::sync::Mutex;
+
+/// 開局時の確認に一致しなかったときに通知するイベント名
+pub const EVENT_VENUE_GUARD_WARNING: &str = "venue-guard-warning";
+
+/// 一致しなかったときの動作
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum VenueGuardMode {
+    /// 確認しない
+    #[default]
+    Off,
+    /// 開局はするが警告を通知する
+    Warn,
+    /// 責任者の承認がない限り開局しない
+    Block,
+}
+
+/// 会場ネットワーク確認の設定
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct VenueGuardConfig {
+    pub mode: VenueGuardMode,
+    /// 会場のSSID
+    pub allowed_ssids: Vec<String>,
+    /// 会場のIPアドレス範囲（CIDR表記。例: 192.168.10.0/24）
+    pub allowed_ip_ranges: Vec<String>,
+}
+
+/// 現在のネットワーク
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct NetworkInfo {
+    pub ssid: Option<String>,
+    pub ip: Option<IpAddr>,
+}
+
+/// 確認結果
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VenueCheck {
+    pub mode: VenueGuardMode,
+    /// 会場のネットワークに一致したか（確認しない場合はtrue）
+    pub matched: bool,
+    pub network: NetworkInfo,
+    /// 一致しなかった理由
+    pub reason: Option<String>,
+}
+
+/// フロントエンドから最後に報告されたSSID
+static SSID: Mutex<Option<String>> = Mutex::new(None);
+
+/// 接続中のSSIDを記録（未接続・取得できない場合はNone）
+pub fn report_ssid(ssid: Option<String>) {
+    if let Ok(mut current) = SSID.lock() {
+        *current = ssid.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    }
+}
+
+/// 外部へ向かう経路のローカルアドレス
+///
+/// UDPソケットの接続先を決めるだけでパケットは送らない
+fn local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:80").ok()?;
+    socket.local_addr().ok().map(|a| a.ip())
+}
+
+/// 現在のネットワークを取得
+pub fn current_network() -> NetworkInfo {
+    NetworkInfo {
+        ssid: SSID.lock().ok().and_then(|s| s.clone()),
+        ip: local_ip(),
+    }
+}
+
+/// CIDR表記の範囲にアドレスが含まれるか
+fn in_range(ip: IpAddr, range: &str) -> Result<bool, String> {
+    let (network, prefix) = match range.trim().split_once('/') {
+        Some((network, prefix)) => (network, Some(prefix)),
+        None => (range.trim(), None),
+    };
+    let network: IpAddr = network
+        .parse()
+        .map_err(|_| format!("Invalid IP range: {}", range))?;
+    let (ip, network, bits) = match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => {
+            (u32::from(ip) as u128, u32::from(network) as u128, 32)
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) => (u128::from(ip), u128::from(network), 128),
+        _ => return Ok(false),
+    };
+    let prefix: u32 = match prefix {
+        Some(prefix) => prefix
+            .parse()
+            .ok()
+            .filter(|p| *p <= bits)
+            .ok_or_else(|| format!("Invalid IP range: {}", range))?,
+        None => bits,
+    };
+    if prefix == 0 {
+        return Ok(true);
+    }
+    let shift = bits - prefix;
+    Ok(ip >> shift == network >> shift)
+}
+
+/// 設定とネットワークを照合
+///
+/// SSIDとIPアドレス範囲のどちらかに一致すれば会場とみなす
+pub fn check(config: &VenueGuardConfig, network: NetworkInfo) -> Result<VenueCheck, String> {
+    let unconfigured = config.allowed_ssids.is_empty() && config.allowed_ip_ranges.is_empty();
+    if config.mode == VenueGuardMode::Off || unconfigured {
+        return Ok(VenueCheck {
+            mode: config.mode,
+            matched: true,
+            network,
+            reason: None,
+        });
+    }
+
+    let ssid_matched = network
+        .ssid
+        .as_ref()
+        .is_some_and(|ssid| config.allowed_ssids.iter().any(|s| s == ssid));
+    let mut ip_matched = false;
+    if let Some(ip) = network.ip {
+        for range in &config.allowed_ip_ranges {
+            if in_range(ip, range)? {
+                ip_matched = true;
+                break;
+            }
+        }
+    }
+
+    let matched = ssid_matched || ip_matched;
+    let reason = (!matched).then(|| {
+        format!(
+            "Not on the venue network (SSID: {}, IP: {})",
+            network.ssid.as_deref().unwrap_or("unknown"),
+            network.ip.map(|ip| ip.to_string()).unwrap_or_else(|| "unknown".to_string())
+        )
+    });
+    Ok(VenueCheck {
+        mode: config.mode,
+        matched,
+        network,
+        reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(mode: VenueGuardMode) -> VenueGuardConfig {
+        VenueGuardConfig {
+            mode,
+            allowed_ssids: vec!["venue-staff".to_string()],
+            allowed_ip_ranges: vec!["10.20.0.0/16".to_string()],
+        }
+    }
+
+    fn network(ssid: Option<&str>, ip: &str) -> NetworkInfo {
+        NetworkInfo {
+            ssid: ssid.map(|s| s.to_string()),
+            ip: ip.parse().ok(),
+        }
+    }
+
+    #[test]
+    fn test_in_range() {
+        let ip: IpAddr = "192.168.10.25".parse().unwrap();
+        assert!(in_range(ip, "192.168.10.0/24").unwrap());
+        assert!(!in_range(ip, "192.168.11.0/24").unwrap());
+        assert!(in_range(ip, "192.168.10.25").unwrap());
+        assert!(in_range(ip, "0.0.0.0/0").unwrap());
+        assert!(!in_range(ip, "fd00::/8").unwrap());
+        assert!(in_range("fd00::1".parse().unwrap(), "fd00::/8").unwrap());
+        assert!(in_range(ip, "192.168.10.0/33").is_err());
+        assert!(in_range(ip, "venue").is_err());
+    }
+
+    #[test]
+    fn test_check_matches_ssid_or_ip_range() {
+        let config = config(VenueGuardMode::Block);
+        assert!(check(&config, network(Some("venue-staff"), "192.168.1.2")).unwrap().matched);
+        assert!(check(&config, network(None, "10.20.3.4")).unwrap().matched);
+
+        let result = check(&config, network(Some("home"), "192.168.1.2")).unwrap();
+        assert!(!result.matched);
+        assert!(result.reason.unwrap().contains("home"));
+    }
+
+    #[test]
+    fn test_check_off_or_unconfigured_always_matches() {
+        let home = network(Some("home"), "192.168.1.2");
+        assert!(check(&config(VenueGuardMode::Off), home.clone()).unwrap().matched);
+
+        let unconfigured = VenueGuardConfig {
+            mode: VenueGuardMode::Block,
+            ..Default::default()
+        };
+        assert!(check(&unconfigured, home).unwrap().matched);
+    }
+}
@@ -0,0 +1,180 @@
+//! 商品券モジュール
+//!
+//! 端末設定の商品券カタログ（種別コード・額面・おつりの可否）で、支払時の商品券と閉局時の実査を検証する
+//! 売上に使われた商品券はジャーナルから種別ごとに集計し、閉局レポートに載せる
+
+use crate::receipt::VoucherCount;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 商品券の種別
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct VoucherKind {
+    /// 種別コード（支払情報の支払手段と同じ。例: voucher_department）
+    pub code: String,
+    /// 表示名
+    pub name: String,
+    /// 額面（空の場合は任意の金額）
+    #[serde(default)]
+    pub denominations: Vec<u32>,
+    /// 額面が支払額を超えた場合におつりを出すか
+    #[serde(default)]
+    pub change_allowed: bool,
+}
+
+/// 既定のカタログ（フロントエンドの既定の商品券設定と同じ）
+pub fn default_catalog() -> Vec<VoucherKind> {
+    vec![
+        VoucherKind {
+            code: "voucher_department".to_string(),
+            name: "百貨店商品券".to_string(),
+            denominations: vec![1000, 500],
+            change_allowed: true,
+        },
+        VoucherKind {
+            code: "voucher_event".to_string(),
+            name: "イベント主催者発行商品券".to_string(),
+            denominations: vec![],
+            change_allowed: false,
+        },
+    ]
+}
+
+/// 支払に使う商品券（種別・額面ごと）
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct VoucherTender {
+    pub code: String,
+    /// 額面（任意金額の種別では合計額）
+    pub denomination: u32,
+    pub count: u32,
+}
+
+/// 商品券での支払の検証結果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct VoucherTenderResult {
+    /// 額面の合計
+    pub face_total: u32,
+    /// 支払に充てる額
+    pub applied: u32,
+    /// おつり（おつりを出せる商品券の超過分）
+    pub change: u32,
+    /// おつりを出せない商品券の超過分（お客様に確認が必要）
+    pub forfeited: u32,
+    /// 商品券で足りない残額（現金などで支払う）
+    pub remaining: u32,
+}
+
+/// 種別コードまたは表示名から種別を探す（ジャーナルには表示名で記録される場合がある）
+pub fn find<'a>(catalog: &'a [VoucherKind], code_or_name: &str) -> Option<&'a VoucherKind> {
+    catalog
+        .iter()
+        .find(|k| k.code == code_or_name || k.name == code_or_name)
+}
+
+/// 支払に使う商品券を検証し、充当額・おつりを計算
+///
+/// おつりを出せない商品券から先に充てるため、併用時もおつりはおつりを出せる商品券の分だけになる
+pub fn validate_tender(
+    catalog: &[VoucherKind],
+    tenders: &[VoucherTender],
+    amount_due: u32,
+) -> Result<VoucherTenderResult, String> {
+    if tenders.is_empty() {
+        return Err("No vouchers tendered".to_string());
+    }
+    let mut with_change: u64 = 0;
+    let mut without_change: u64 = 0;
+    for tender in tenders {
+        let kind = catalog
+            .iter()
+            .find(|k| k.code == tender.code)
+            .ok_or_else(|| format!("Unknown voucher type: {}", tender.code))?;
+        if tender.count == 0 || tender.denomination == 0 {
+            return Err(format!("Invalid voucher count: {}", tender.code));
+        }
+        if !kind.denominations.is_empty() && !kind.denominations.contains(&tender.denomination) {
+            return Err(format!(
+                "Invalid denomination for {}: {}",
+                kind.name, tender.denomination
+            ));
+        }
+        let face = tender.denomination as u64 * tender.count as u64;
+        if kind.change_allowed {
+            with_change += face;
+        } else {
+            without_change += face;
+        }
+    }
+    let face_total = u32::try_from(with_change + without_change)
+        .map_err(|_| "Voucher total too large".to_string())?;
+
+    let due = amount_due as u64;
+    let applied_without = without_change.min(due);
+    let applied_with = with_change.min(due - applied_without);
+    let applied = (applied_without + applied_with) as u32;
+    Ok(VoucherTenderResult {
+        face_total,
+        applied,
+        change: (with_change - applied_with) as u32,
+        forfeited: (without_change - applied_without) as u32,
+        remaining: amount_due - applied,
+    })
+}
+
+/// 閉局時の実査を検証し、種別コードのある行の表示名をカタログの名前にそろえる
+pub fn resolve_counts(catalog: &[VoucherKind], counts: &mut [VoucherCount]) -> Result<(), String> {
+    for count in counts {
+        if let Some(ref code) = count.code {
+            let kind = catalog
+                .iter()
+                .find(|k| &k.code == code)
+                .ok_or_else(|| format!("Unknown voucher type: {}", code))?;
+            count.voucher_type = kind.name.clone();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tender(code: &str, denomination: u32, count: u32) -> VoucherTender {
+        VoucherTender {
+            code: code.to_string(),
+            denomination,
+            count,
+        }
+    }
+
+    #[test]
+    fn test_validate_tender_gives_change_only_for_change_allowed_vouchers() {
+        let catalog = default_catalog();
+        let result = validate_tender(&catalog, &[tender("voucher_department", 1000, 2)], 1500).unwrap();
+        assert_eq!((result.applied, result.change, result.forfeited), (1500, 500, 0));
+
+        let result = validate_tender(&catalog, &[tender("voucher_event", 2000, 1)], 1500).unwrap();
+        assert_eq!((result.applied, result.change, result.forfeited), (1500, 0, 500));
+
+        // おつりを出せない商品券から先に充てる
+        let result = validate_tender(
+            &catalog,
+            &[tender("voucher_department", 1000, 1), tender("voucher_event", 1000, 1)],
+            1500,
+        )
+        .unwrap();
+        assert_eq!((result.applied, result.change, result.forfeited), (1500, 500, 0));
+
+        let result = validate_tender(&catalog, &[tender("voucher_department", 500, 1)], 1500).unwrap();
+        assert_eq!((result.applied, result.remaining), (500, 1000));
+    }
+
+    #[test]
+    fn test_validate_tender_rejects_unknown_type_and_denomination() {
+        let catalog = default_catalog();
+        assert!(validate_tender(&catalog, &[tender("gift", 1000, 1)], 1000).is_err());
+        assert!(validate_tender(&catalog, &[tender("voucher_department", 300, 1)], 1000).is_err());
+        assert!(validate_tender(&catalog, &[tender("voucher_department", 1000, 0)], 1000).is_err());
+        assert!(validate_tender(&catalog, &[], 1000).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 import { css } from "styled-system/css";
 import { saveTransaction, updateSalesSummary } from "../lib/db";
+import { apiBaseUrl } from "../lib/environment";
 import { useAuthStore } from "../stores/auth";
 import { type AppliedCoupon, useCartStore } from "../stores/cart";
 import { usePairingStore } from "../stores/pairing";
@@ -15,8 +16,6 @@ import { PairingModal } from "./PairingModal";
 import { TerminalPaymentModal } from "./TerminalPaymentModal";
 import { Button, Modal } from "./ui";
 
-const API_BASE_URL = import.meta.env.VITE_API_BASE_URL;
-
 // クーポン入力スタイル
 const couponStyles = {
   container: css({
@@ -609,7 +608,7 @@ export function CheckoutModal({
     setCouponError(null);
 
     try {
-      const response = await fetch(`${API_BASE_URL}/pos/coupons/apply`, {
+      const response = await fetch(`${apiBaseUrl()}/pos/coupons/apply`, {
         method: "POST",
         headers: {
           "Content-Type": "application/json",
@@ -745,7 +744,7 @@ export function CheckoutModal({
             saleBody.subtotal = subtotal;
           }
 
-          const response = await fetch(`${API_BASE_URL}/pos/sales`, {
+          const response = await fetch(`${apiBaseUrl()}/pos/sales`, {
             method: "POST",
             headers: {
               "Content-Type": "application/json",
@@ -893,7 +892,7 @@ export function CheckoutModal({
               saleBody.subtotal = subtotal;
             }
 
-            const response = await fetch(`${API_BASE_URL}/pos/sales`, {
+            const response = await fetch(`${apiBaseUrl()}/pos/sales`, {
               method: "POST",
               headers: {
                 "Content-Type": "application/json",
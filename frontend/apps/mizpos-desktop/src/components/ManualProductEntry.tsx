@@ -1,11 +1,16 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 import { css } from "styled-system/css";
+import { commands } from "../lib/commands.gen";
+import { createProductFromScan } from "../lib/db";
+import { useAuthStore } from "../stores/auth";
 import { useCartStore } from "../stores/cart";
 import type { Product } from "../types";
 import { Button, Input, Modal } from "./ui";
 
 interface ManualProductEntryProps {
   onClose: () => void;
+  /** 未登録のコードをスキャンした場合のコード（指定すると商品を仮登録する） */
+  scannedCode?: string;
 }
 
 // スタイル定義
@@ -76,16 +81,43 @@ const fieldStyles = {
   }),
 };
 
-export function ManualProductEntry({ onClose }: ManualProductEntryProps) {
+export function ManualProductEntry({
+  onClose,
+  scannedCode,
+}: ManualProductEntryProps) {
   const [name, setName] = useState("");
   const [price, setPrice] = useState("");
+  const [circleName, setCircleName] = useState<string | undefined>();
+  const [error, setError] = useState<string | null>(null);
   const nameRef = useRef<HTMLInputElement>(null);
   const { addItem } = useCartStore();
+  const { session } = useAuthStore();
 
   useEffect(() => {
     nameRef.current?.focus();
   }, []);
 
+  // 同人誌のISDNは登録情報から書名・サークル名・値段を補う（照会できない場合は手入力）
+  useEffect(() => {
+    if (!scannedCode || !/^27[89]/.test(scannedCode)) return;
+    let cancelled = false;
+    commands
+      .lookupIsdn(scannedCode)
+      .then(({ record }) => {
+        if (cancelled || !record) return;
+        setName((current) => current || record.title);
+        setCircleName(record.circle_name ?? undefined);
+        if (record.price) {
+          const price = String(record.price);
+          setPrice((current) => current || price);
+        }
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+    };
+  }, [scannedCode]);
+
   const handlePriceChange = useCallback(
     (e: React.ChangeEvent<HTMLInputElement>) => {
       const value = e.target.value.replace(/\D/g, "");
@@ -97,13 +129,30 @@ export function ManualProductEntry({ onClose }: ManualProductEntryProps) {
   );
 
   const handleSubmit = useCallback(
-    (e: React.FormEvent) => {
+    async (e: React.FormEvent) => {
       e.preventDefault();
       if (!name.trim() || !price) return;
 
+      // 未登録のコードは仮登録して、次回からスキャンで追加できるようにする
+      if (scannedCode) {
+        try {
+          const product = await createProductFromScan(
+            scannedCode,
+            name.trim(),
+            Number.parseInt(price, 10),
+            session?.staffId ?? "",
+            circleName,
+          );
+          addItem(product);
+          onClose();
+        } catch (err) {
+          setError(err instanceof Error ? err.message : String(err));
+        }
+        return;
+      }
+
       const product: Product = {
         id: `manual-${Date.now()}`,
-        jan: "",
         name: name.trim(),
         price: Number.parseInt(price, 10),
         isBook: false, // 手動入力は非書籍扱い
@@ -112,7 +161,7 @@ export function ManualProductEntry({ onClose }: ManualProductEntryProps) {
       addItem(product);
       onClose();
     },
-    [name, price, addItem, onClose],
+    [name, price, scannedCode, circleName, session, addItem, onClose],
   );
 
   const priceValue = price ? Number.parseInt(price, 10) : 0;
@@ -121,7 +170,12 @@ export function ManualProductEntry({ onClose }: ManualProductEntryProps) {
   const quickPrices = [100, 300, 500, 1000];
 
   return (
-    <Modal open onClose={onClose} title="手動登録" maxWidth="460px">
+    <Modal
+      open
+      onClose={onClose}
+      title={scannedCode ? `未登録商品の仮登録（${scannedCode}）` : "手動登録"}
+      maxWidth="460px"
+    >
       <form onSubmit={handleSubmit}>
         {/* 商品名 */}
         <div className={fieldStyles.container}>
@@ -176,6 +230,18 @@ export function ManualProductEntry({ onClose }: ManualProductEntryProps) {
           ))}
         </div>
 
+        {error && (
+          <div
+            className={css({
+              marginBottom: "12px",
+              fontSize: "14px",
+              color: "#fca5a5",
+            })}
+          >
+            {error}
+          </div>
+        )}
+
         {/* 登録ボタン */}
         <Button
           type="submit"
@@ -184,7 +250,7 @@ export function ManualProductEntry({ onClose }: ManualProductEntryProps) {
           fullWidth
           disabled={!isValid}
         >
-          カートに追加
+          {scannedCode ? "仮登録してカートに追加" : "カートに追加"}
         </Button>
       </form>
     </Modal>
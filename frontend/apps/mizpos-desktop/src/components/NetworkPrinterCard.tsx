@@ -0,0 +1,152 @@
+import { useCallback, useState } from "react";
+import { css } from "styled-system/css";
+import { commands, type NetworkPrinter } from "../lib/commands.gen";
+import { useAuthStore } from "../stores/auth";
+import { useSettingsStore } from "../stores/settings";
+import { Button, Card } from "./ui";
+
+const styles = {
+  title: css({
+    margin: "0 0 8px",
+    fontSize: "16px",
+    fontWeight: 600,
+    color: "#f8fafc",
+  }),
+  description: css({
+    fontSize: "14px",
+    color: "#94a3b8",
+    marginBottom: "16px",
+  }),
+  row: css({
+    display: "flex",
+    alignItems: "center",
+    gap: "8px",
+    marginTop: "12px",
+    fontSize: "14px",
+    color: "#f8fafc",
+  }),
+  host: css({
+    flex: 1,
+    fontFamily: "monospace",
+  }),
+  message: css({
+    marginTop: "12px",
+    fontSize: "14px",
+  }),
+};
+
+/**
+ * LAN接続のプリンター（RAW 9100番）の探索
+ *
+ * 見つかった機器に接続確認を印刷し、職長は端末設定のネットワークプリンターに追加できる
+ */
+export function NetworkPrinterCard() {
+  const { settings } = useSettingsStore();
+  const { session } = useAuthStore();
+  const [isSearching, setIsSearching] = useState(false);
+  const [found, setFound] = useState<NetworkPrinter[] | null>(null);
+  const [message, setMessage] = useState<{
+    success: boolean;
+    text: string;
+  } | null>(null);
+
+  const handleDiscover = useCallback(async () => {
+    setIsSearching(true);
+    setMessage(null);
+    try {
+      setFound(await commands.discoverNetworkPrinters(null));
+    } catch (error) {
+      setFound(null);
+      setMessage({ success: false, text: String(error) });
+    } finally {
+      setIsSearching(false);
+    }
+  }, []);
+
+  const handleTestPrint = useCallback(
+    async (printer: NetworkPrinter) => {
+      try {
+        await commands.networkPrintTestPage(
+          printer.host,
+          printer.port,
+          settings.printer?.paperWidth ?? null,
+        );
+        setMessage({ success: true, text: `${printer.host} に印刷しました` });
+      } catch (error) {
+        setMessage({ success: false, text: String(error) });
+      }
+    },
+    [settings.printer],
+  );
+
+  const handleAdd = useCallback(async (printer: NetworkPrinter) => {
+    try {
+      const config = await commands.getTerminalConfig();
+      if (config.network_printers.some((p) => p.host === printer.host)) {
+        setMessage({ success: true, text: "追加済みのプリンターです" });
+        return;
+      }
+      await commands.updateTerminalConfig({
+        ...config,
+        network_printers: [...config.network_printers, printer],
+      });
+      setMessage({
+        success: true,
+        text: `${printer.host} を追加しました（プリンター選択に表示されます）`,
+      });
+    } catch (error) {
+      setMessage({ success: false, text: String(error) });
+    }
+  }, []);
+
+  return (
+    <Card padding="lg">
+      <h2 className={styles.title}>LANプリンター</h2>
+      <div className={styles.description}>
+        端末と同じネットワークにあるレシートプリンター（9100番）を探します。
+      </div>
+      <Button
+        variant="outline"
+        fullWidth
+        onClick={handleDiscover}
+        disabled={isSearching}
+      >
+        {isSearching ? "探索中..." : "LANのプリンターを探す"}
+      </Button>
+      {found?.length === 0 && (
+        <div className={styles.message}>見つかりませんでした</div>
+      )}
+      {found?.map((printer) => (
+        <div key={printer.host} className={styles.row}>
+          <span className={styles.host}>
+            {printer.host}:{printer.port}
+          </span>
+          <Button
+            variant="outline"
+            size="sm"
+            onClick={() => handleTestPrint(printer)}
+          >
+            接続確認
+          </Button>
+          {session?.role === "manager" && (
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={() => handleAdd(printer)}
+            >
+              追加
+            </Button>
+          )}
+        </div>
+      ))}
+      {message && (
+        <div
+          className={styles.message}
+          style={{ color: message.success ? "#86efac" : "#fca5a5" }}
+        >
+          {message.text}
+        </div>
+      )}
+    </Card>
+  );
+}
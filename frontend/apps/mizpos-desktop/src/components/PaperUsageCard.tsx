@@ -0,0 +1,85 @@
+import { useEffect, useState } from "react";
+import { css } from "styled-system/css";
+import { commands, type DailyPaperUsage } from "../lib/commands.gen";
+import { Card, Input } from "./ui";
+
+const styles = {
+  title: css({
+    margin: "0 0 8px",
+    fontSize: "16px",
+    fontWeight: 600,
+    color: "#f8fafc",
+  }),
+  description: css({
+    fontSize: "14px",
+    color: "#94a3b8",
+    marginBottom: "16px",
+  }),
+  row: css({
+    display: "flex",
+    justifyContent: "space-between",
+    padding: "6px 0",
+    fontSize: "14px",
+    color: "#f8fafc",
+    borderBottom: "1px solid #334155",
+  }),
+  total: css({
+    marginTop: "12px",
+    fontSize: "14px",
+    fontWeight: 600,
+    color: "#f8fafc",
+  }),
+};
+
+/**
+ * 日別の用紙使用量
+ *
+ * ロール1本の長さを入れると、今回のイベントで使ったロールの本数を見積もる
+ */
+export function PaperUsageCard() {
+  const [usage, setUsage] = useState<DailyPaperUsage[]>([]);
+  const [rollLength, setRollLength] = useState("40");
+
+  useEffect(() => {
+    commands
+      .getPaperUsage()
+      .then(setUsage)
+      .catch(() => setUsage([]));
+  }, []);
+
+  const totalMm = usage.reduce((sum, day) => sum + day.length_mm, 0);
+  const rollMm = Number.parseFloat(rollLength) * 1000;
+  const rolls = rollMm > 0 ? totalMm / rollMm : null;
+
+  return (
+    <Card padding="lg">
+      <h2 className={styles.title}>用紙使用量</h2>
+      <div className={styles.description}>
+        このイベントで印刷した用紙の長さ（見積もり）です。
+      </div>
+      {usage.length === 0 && (
+        <div className={styles.description}>まだ記録がありません</div>
+      )}
+      {usage.map((day) => (
+        <div key={`${day.date}-${day.paper_width}`} className={styles.row}>
+          <span>
+            {day.date}（{day.paper_width}mm・{day.jobs}回）
+          </span>
+          <span>{(day.length_mm / 1000).toFixed(1)} m</span>
+        </div>
+      ))}
+      <div className={styles.total}>
+        合計 {(totalMm / 1000).toFixed(1)} m
+        {rolls !== null && `（ロール約${rolls.toFixed(1)}本）`}
+      </div>
+      <div className={css({ marginTop: "12px" })}>
+        <Input
+          label="ロール1本の長さ（m）"
+          inputMode="decimal"
+          value={rollLength}
+          onChange={(e) => setRollLength(e.target.value)}
+        />
+      </div>
+    </Card>
+  );
+}
@@ -10,6 +10,9 @@ import { useSettingsStore } from "../stores/settings";
 import type { PaymentMethod, Transaction } from "../types";
 import { Button } from "./ui";
 
+/** 印刷前に確認するレシートの長さ（mm） */
+const LONG_RECEIPT_MM = 1000;
+
 /**
  * 支払い方法を日本語表示名に変換
  */
@@ -224,14 +227,17 @@ export function ReceiptModal({ transaction, onClose }: ReceiptModalProps) {
         throw new Error(connectResult.error || "プリンター接続に失敗しました");
       }
 
-      // 取引日時をフォーマット（例: 2025年12月31日 10:30）
-      const now = new Date();
-      const saleDateTime = `${now.getFullYear()}/${
-        now.getMonth() + 1
-      }/${now.getDate()} ${now.getHours().toString().padStart(2, "0")}:${now
-        .getMinutes()
-        .toString()
-        .padStart(2, "0")}`;
+      // 取引日時（ISO8601、端末のタイムゾーンへの変換は印刷側で行う）
+      const saleDateTime = new Date().toISOString();
+      // カゴの商品が同じ版の価格表で値付けされていれば、その版の価格で照合する
+      // （会計中に価格改定が反映されても、追加したときの価格で確定できる）
+      const priceVersions = new Set(
+        transaction.items.flatMap((item) =>
+          item.product.priceVersion === undefined
+            ? []
+            : [item.product.priceVersion],
+        ),
+      );
 
       const receiptData: FullReceiptData = {
         event_name: isTraining
@@ -242,6 +248,7 @@ export function ReceiptModal({ transaction, onClose }: ReceiptModalProps) {
         sale_start_date_time: saleDateTime,
         staff_id: transaction.staffId,
         items: transaction.items.map((item) => ({
+          product_id: item.product.id,
           circle_name: item.product.circleName || "",
           name: item.product.name,
           jan: item.product.jan,
@@ -249,6 +256,7 @@ export function ReceiptModal({ transaction, onClose }: ReceiptModalProps) {
           isdn: item.product.isdn,
           jan2: item.product.jan2,
           is_book: item.product.isBook,
+          age_restricted: item.product.ageRestricted ?? false,
           quantity: item.quantity,
           price: item.product.price * item.quantity,
         })),
@@ -262,6 +270,7 @@ export function ReceiptModal({ transaction, onClose }: ReceiptModalProps) {
         })),
         tax_rate: transaction.taxRate,
         tax_amount: transaction.taxAmount,
+        price_version: priceVersions.size === 1 ? [...priceVersions][0] : null,
         receipt_number: isTraining
           ? `TRAINING-${transaction.id}`
           : transaction.id,
@@ -284,6 +293,20 @@ export function ReceiptModal({ transaction, onClose }: ReceiptModalProps) {
         payment_intent_id: transaction.paymentIntentId,
       };
 
+      // 明細が多いレシートは長くなるため、印刷前に確認する
+      const length = await printer
+        .estimateReceiptLength(receiptData)
+        .catch(() => null);
+      if (
+        length &&
+        length.length_mm >= LONG_RECEIPT_MM &&
+        !confirm(
+          `このレシートは約${(length.length_mm / 1000).toFixed(1)}mになります。印刷しますか？`,
+        )
+      ) {
+        return;
+      }
+
       const printResult = await printer.printFullReceipt(receiptData);
       if (!printResult.success) {
         throw new Error(printResult.error || "印刷に失敗しました");
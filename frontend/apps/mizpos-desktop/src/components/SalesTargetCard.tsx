@@ -0,0 +1,153 @@
+import { useCallback, useEffect, useState } from "react";
+import { css } from "styled-system/css";
+import { commands, type SalesProgress } from "../lib/commands.gen";
+import { useAuthStore } from "../stores/auth";
+import { useSettingsStore } from "../stores/settings";
+import { Button, Card, Input } from "./ui";
+
+const styles = {
+  title: css({
+    margin: "0 0 20px",
+    fontSize: "16px",
+    fontWeight: 600,
+    color: "#f8fafc",
+  }),
+  fields: css({
+    display: "grid",
+    gridTemplateColumns: "1fr 1fr",
+    gap: "12px",
+    marginBottom: "12px",
+  }),
+  progress: css({
+    marginBottom: "12px",
+    fontSize: "14px",
+    color: "#94a3b8",
+  }),
+  actions: css({
+    display: "flex",
+    gap: "8px",
+  }),
+  message: css({
+    marginTop: "12px",
+    fontSize: "14px",
+  }),
+};
+
+function parseAmount(value: string): number | null {
+  const amount = Number.parseInt(value.replace(/\D/g, ""), 10);
+  return Number.isNaN(amount) ? null : amount;
+}
+
+/**
+ * イベントの売上目標の設定と進捗
+ *
+ * 損益分岐点を入れておくと、頒布の途中で赤字を抜けたかを確認できる
+ */
+export function SalesTargetCard() {
+  const { settings } = useSettingsStore();
+  const { session } = useAuthStore();
+  const [target, setTarget] = useState("");
+  const [breakEven, setBreakEven] = useState("");
+  const [progress, setProgress] = useState<SalesProgress | null>(null);
+  const [message, setMessage] = useState<{
+    success: boolean;
+    text: string;
+  } | null>(null);
+
+  const refresh = useCallback(async () => {
+    const current = await commands.getSalesProgress();
+    setProgress(current);
+    setTarget(current.target ? String(current.target.target_amount) : "");
+    setBreakEven(
+      current.target?.break_even_amount
+        ? String(current.target.break_even_amount)
+        : "",
+    );
+  }, []);
+
+  useEffect(() => {
+    refresh().catch(() => setProgress(null));
+  }, [refresh]);
+
+  const handleSave = useCallback(async () => {
+    try {
+      await commands.setSalesTarget(
+        parseAmount(target) ?? 0,
+        parseAmount(breakEven),
+        session?.staffId ?? null,
+      );
+      await refresh();
+      setMessage({ success: true, text: "売上目標を保存しました" });
+    } catch (error) {
+      setMessage({ success: false, text: String(error) });
+    }
+  }, [target, breakEven, session, refresh]);
+
+  const handlePrint = useCallback(async () => {
+    const printer = settings.printer;
+    if (printer?.type !== "usb" || !printer.vendorId || !printer.deviceId) {
+      setMessage({ success: false, text: "USBプリンターが選択されていません" });
+      return;
+    }
+    try {
+      setProgress(
+        await commands.printSalesProgress(
+          {
+            transport: "device",
+            vendor_id: printer.vendorId,
+            device_id: printer.deviceId,
+          },
+          printer.paperWidth,
+        ),
+      );
+      setMessage(null);
+    } catch (error) {
+      setMessage({ success: false, text: String(error) });
+    }
+  }, [settings.printer]);
+
+  return (
+    <Card padding="lg">
+      <h2 className={styles.title}>売上目標</h2>
+      <div className={styles.fields}>
+        <Input
+          label="目標（税込）"
+          inputMode="numeric"
+          value={target}
+          onChange={(e) => setTarget(e.target.value)}
+          placeholder="50000"
+        />
+        <Input
+          label="損益分岐点"
+          inputMode="numeric"
+          value={breakEven}
+          onChange={(e) => setBreakEven(e.target.value)}
+          placeholder="任意"
+        />
+      </div>
+      {progress?.achieved_percent != null && (
+        <div className={styles.progress}>
+          純売上 ¥{progress.net_amount.toLocaleString()}（
+          {progress.achieved_percent}%）
+          {progress.break_even_reached === true && " ・損益分岐点を超えました"}
+        </div>
+      )}
+      <div className={styles.actions}>
+        <Button variant="outline" onClick={handleSave} fullWidth>
+          保存
+        </Button>
+        <Button variant="outline" onClick={handlePrint} fullWidth>
+          進捗を印刷
+        </Button>
+      </div>
+      {message && (
+        <div
+          className={styles.message}
+          style={{ color: message.success ? "#86efac" : "#fca5a5" }}
+        >
+          {message.text}
+        </div>
+      )}
+    </Card>
+  );
+}
@@ -0,0 +1,143 @@
+import { useCallback, useState } from "react";
+import { css } from "styled-system/css";
+import { commands, type SerialPortInfo } from "../lib/commands.gen";
+import { useAuthStore } from "../stores/auth";
+import { Button, Card, Input } from "./ui";
+
+const styles = {
+  title: css({
+    margin: "0 0 8px",
+    fontSize: "16px",
+    fontWeight: 600,
+    color: "#f8fafc",
+  }),
+  description: css({
+    fontSize: "14px",
+    color: "#94a3b8",
+    marginBottom: "16px",
+  }),
+  row: css({
+    display: "flex",
+    alignItems: "center",
+    gap: "8px",
+    marginTop: "12px",
+    fontSize: "14px",
+    color: "#f8fafc",
+  }),
+  port: css({
+    flex: 1,
+    fontFamily: "monospace",
+  }),
+  message: css({
+    marginTop: "12px",
+    fontSize: "14px",
+  }),
+};
+
+/**
+ * シリアル接続（RS-232・USB-シリアル変換）のプリンター
+ *
+ * 端末のシリアルポートを一覧し、職長は通信速度を指定して端末設定のシリアルプリンターに追加できる
+ * フロー制御は多くのレシートプリンターの既定（RTS/CTS）で追加する
+ */
+export function SerialPrinterCard() {
+  const { session } = useAuthStore();
+  const [ports, setPorts] = useState<SerialPortInfo[] | null>(null);
+  const [baudRate, setBaudRate] = useState("9600");
+  const [message, setMessage] = useState<{
+    success: boolean;
+    text: string;
+  } | null>(null);
+
+  const handleList = useCallback(async () => {
+    setMessage(null);
+    try {
+      setPorts(await commands.listSerialPorts());
+    } catch (error) {
+      setPorts(null);
+      setMessage({ success: false, text: String(error) });
+    }
+  }, []);
+
+  const handleAdd = useCallback(
+    async (port: SerialPortInfo) => {
+      const baud = Number.parseInt(baudRate, 10);
+      if (!(baud > 0)) {
+        setMessage({ success: false, text: "通信速度を入力してください" });
+        return;
+      }
+      try {
+        const config = await commands.getTerminalConfig();
+        if (config.serial_printers.some((p) => p.port === port.port)) {
+          setMessage({ success: true, text: "追加済みのポートです" });
+          return;
+        }
+        await commands.updateTerminalConfig({
+          ...config,
+          serial_printers: [
+            ...config.serial_printers,
+            {
+              name: port.description ?? port.port,
+              port: port.port,
+              baud_rate: baud,
+              flow_control: "hardware",
+            },
+          ],
+        });
+        setMessage({
+          success: true,
+          text: `${port.port} を追加しました（プリンター選択に表示されます）`,
+        });
+      } catch (error) {
+        setMessage({ success: false, text: String(error) });
+      }
+    },
+    [baudRate],
+  );
+
+  return (
+    <Card padding="lg">
+      <h2 className={styles.title}>シリアルプリンター</h2>
+      <div className={styles.description}>
+        RS-232・USB-シリアル変換で接続したレシートプリンターを使います。
+      </div>
+      <Button variant="outline" fullWidth onClick={handleList}>
+        シリアルポートを探す
+      </Button>
+      {session?.role === "manager" && ports && ports.length > 0 && (
+        <div className={css({ marginTop: "12px" })}>
+          <Input
+            label="通信速度（bps）"
+            inputMode="numeric"
+            value={baudRate}
+            onChange={(e) => setBaudRate(e.target.value)}
+          />
+        </div>
+      )}
+      {ports?.length === 0 && (
+        <div className={styles.message}>見つかりませんでした</div>
+      )}
+      {ports?.map((port) => (
+        <div key={port.port} className={styles.row}>
+          <span className={styles.port}>
+            {port.port}
+            {port.description && `（${port.description}）`}
+          </span>
+          {session?.role === "manager" && (
+            <Button variant="outline" size="sm" onClick={() => handleAdd(port)}>
+              追加
+            </Button>
+          )}
+        </div>
+      ))}
+      {message && (
+        <div
+          className={styles.message}
+          style={{ color: message.success ? "#86efac" : "#fca5a5" }}
+        >
+          {message.text}
+        </div>
+      )}
+    </Card>
+  );
+}
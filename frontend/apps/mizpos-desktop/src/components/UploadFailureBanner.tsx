@@ -0,0 +1,124 @@
+import { useEffect, useState } from "react";
+import { css } from "styled-system/css";
+import { commands } from "../lib/commands.gen";
+import {
+  drainUploads,
+  onUploadFailed,
+  type UploadFailedEvent,
+} from "../lib/uploads";
+import { Button } from "./ui";
+
+const KIND_LABELS: Record<string, string> = {
+  closing_report: "閉局レポート",
+  email_receipt: "メールレシート",
+  duress_alert: "緊急通報",
+  points_accrual: "ポイント付与",
+  diagnostics: "診断情報",
+};
+
+function kindLabel(kind: string): string {
+  return KIND_LABELS[kind] ?? kind;
+}
+
+function failureKey(failure: UploadFailedEvent): string {
+  return `${failure.kind}:${failure.reference_id}`;
+}
+
+/**
+ * 送信に失敗したアップロードの通知
+ *
+ * キューに残っている送信は再送でき、キューへの投入自体に失敗したもの
+ * （閉局レポートの署名失敗など）は元の画面からの再印刷を促す
+ */
+export function UploadFailureBanner() {
+  const [failures, setFailures] = useState<UploadFailedEvent[]>([]);
+  const [isRetrying, setIsRetrying] = useState(false);
+
+  useEffect(() => {
+    const unlisten = onUploadFailed((failure) => {
+      setFailures((current) => [
+        ...current.filter((f) => failureKey(f) !== failureKey(failure)),
+        failure,
+      ]);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  if (failures.length === 0) return null;
+
+  const handleRetry = async () => {
+    setIsRetrying(true);
+    // 再送で再び失敗したものはイベントで通知し直される
+    setFailures((current) => current.filter((f) => !f.upload_id));
+    try {
+      await drainUploads();
+    } catch (error) {
+      console.error("Failed to retry uploads:", error);
+    } finally {
+      setIsRetrying(false);
+    }
+    // キューに残っていない送信の通知は消す
+    const pending = new Set(
+      (await commands.getPendingUploads()).map((u) => u.id),
+    );
+    setFailures((current) =>
+      current.filter((f) => !f.upload_id || pending.has(f.upload_id)),
+    );
+  };
+
+  const hasQueued = failures.some((f) => f.upload_id);
+
+  return (
+    <div
+      role="alert"
+      className={css({
+        position: "fixed",
+        left: "16px",
+        right: "16px",
+        bottom: "16px",
+        zIndex: 1000,
+        padding: "16px",
+        borderRadius: "10px",
+        background: "#7f1d1d",
+        color: "#fecaca",
+        boxShadow: "0 8px 24px rgba(0, 0, 0, 0.4)",
+        display: "flex",
+        alignItems: "center",
+        gap: "16px",
+      })}
+    >
+      <ul
+        className={css({
+          flex: 1,
+          margin: 0,
+          padding: 0,
+          listStyle: "none",
+          fontSize: "14px",
+        })}
+      >
+        {failures.map((failure) => (
+          <li key={failureKey(failure)}>
+            {failure.upload_id
+              ? `${kindLabel(failure.kind)}の送信に失敗しました（${failure.attempts}回目）: ${failure.error}`
+              : `${kindLabel(failure.kind)}の送信準備に失敗しました。もう一度印刷してください: ${failure.error}`}
+          </li>
+        ))}
+      </ul>
+      {hasQueued && (
+        <Button
+          variant="danger"
+          size="sm"
+          onClick={handleRetry}
+          disabled={isRetrying}
+        >
+          {isRetrying ? "再送中..." : "再送"}
+        </Button>
+      )}
+      <Button variant="ghost" size="sm" onClick={() => setFailures([])}>
+        閉じる
+      </Button>
+    </div>
+  );
+}
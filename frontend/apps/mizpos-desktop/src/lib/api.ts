@@ -1,4 +1,4 @@
-const API_BASE_URL = import.meta.env.VITE_API_BASE_URL;
+import { apiBaseUrl } from "./environment";
 
 export interface ApiProduct {
   product_id: string;
@@ -27,7 +27,7 @@ interface ProductsResponse {
  * 商品一覧を取得
  */
 export async function fetchProducts(category?: string): Promise<ApiProduct[]> {
-  const url = new URL(`${API_BASE_URL}/stock/products`);
+  const url = new URL(`${apiBaseUrl()}/stock/products`);
   if (category) {
     url.searchParams.set("category", category);
   }
@@ -0,0 +1,37 @@
+/**
+ * アプリが背面に回っても続けたい処理（印刷・同期）の保持
+ *
+ * Android では決済アプリへ切り替えた間もプロセスが止められないよう、
+ * 処理の間だけフォアグラウンドサービスを動かす（window.MizPosBackground）
+ * それ以外のプラットフォームでは何もしない
+ */
+
+export type BackgroundWork = "print" | "sync";
+
+interface MizPosBackgroundBridge {
+  begin(work: string): string;
+  end(work: string): string;
+}
+
+declare global {
+  interface Window {
+    MizPosBackground?: MizPosBackgroundBridge;
+  }
+}
+
+/**
+ * 処理の間だけフォアグラウンドサービスを保持して実行
+ */
+export async function withBackgroundWork<T>(
+  work: BackgroundWork,
+  task: () => Promise<T>,
+): Promise<T> {
+  const bridge =
+    typeof window !== "undefined" ? window.MizPosBackground : undefined;
+  bridge?.begin(work);
+  try {
+    return await task();
+  } finally {
+    bridge?.end(work);
+  }
+}
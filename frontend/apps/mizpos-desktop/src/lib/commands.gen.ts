@@ -0,0 +1,4007 @@
+// @ts-nocheck
+// This file was generated by [tauri-specta](https://github.com/oscartbeaumont/tauri-specta). Do not edit this file manually.
+
+/** user-defined commands **/
+
+
+export const commands = {
+async getPlatform() : Promise<string> {
+    return await TAURI_INVOKE("get_platform");
+},
+/**
+ * 端末の状態を取得
+ */
+async getTerminalStatus() : Promise<TerminalAuthResult> {
+    return await TAURI_INVOKE("get_terminal_status");
+},
+/**
+ * 端末を初期化（キーペア生成）
+ * 
+ * 組織とレジを指定した場合は割り当てて登録ペイロードに含める
+ */
+async initializeTerminal(deviceName: string, seat: SeatRequest | null) : Promise<RegistrationQrPayload> {
+    return await TAURI_INVOKE("initialize_terminal", { deviceName, seat });
+},
+/**
+ * QRコード用のJSONデータを生成
+ * 
+ * 初期化済みの端末でも組織とレジを指定すると割り当て直す（サーバーへの再登録が必要）
+ */
+async generateRegistrationQr(deviceName: string, seat: SeatRequest | null) : Promise<string> {
+    return await TAURI_INVOKE("generate_registration_qr", { deviceName, seat });
+},
+/**
+ * 認証用の署名データを生成
+ */
+async createAuthSignature() : Promise<SignatureData> {
+    return await TAURI_INVOKE("create_auth_signature");
+},
+/**
+ * 割り当てた組織とレジを取得
+ */
+async getSeatBinding() : Promise<SeatBinding | null> {
+    return await TAURI_INVOKE("get_seat_binding");
+},
+/**
+ * サポートから伝えられた公開鍵とチャレンジで端末を確認
+ */
+async verifyTerminal(publicKey: string, challenge: string) : Promise<TerminalVerification> {
+    return await TAURI_INVOKE("verify_terminal", { publicKey, challenge });
+},
+/**
+ * 端末の応答を検証して確認コードを返す（サポート側で使う）
+ */
+async checkTerminalVerification(publicKey: string, challenge: string, signature: SignatureData) : Promise<string> {
+    return await TAURI_INVOKE("check_terminal_verification", { publicKey, challenge, signature });
+},
+/**
+ * Keychainをクリア（デバッグ用、メインウィンドウから責任者セッション中にだけ呼べる）
+ */
+async clearTerminalKeychain() : Promise<null> {
+    return await TAURI_INVOKE("clear_terminal_keychain");
+},
+/**
+ * 送信待ちのアップロード一覧を取得
+ */
+async getPendingUploads() : Promise<PendingUpload[]> {
+    return await TAURI_INVOKE("get_pending_uploads");
+},
+/**
+ * 送信完了を通知
+ */
+async completeUpload(id: string) : Promise<null> {
+    return await TAURI_INVOKE("complete_upload", { id });
+},
+/**
+ * 送信失敗を通知（再送を促すイベントを発行）
+ */
+async failUpload(id: string, error: string) : Promise<null> {
+    return await TAURI_INVOKE("fail_upload", { id, error });
+},
+/**
+ * レシートをメールで送信（署名した送信依頼をキューに積む）
+ */
+async emailReceipt(receiptNumber: string, address: string, staffId: string) : Promise<PendingUpload> {
+    return await TAURI_INVOKE("email_receipt", { receiptNumber, address, staffId });
+},
+/**
+ * サーバーから受け取った署名付きコマンドを検証して実行
+ */
+async executeRemoteCommand(command: SignedPayload) : Promise<RemoteCommandResult> {
+    return await TAURI_INVOKE("execute_remote_command", { command });
+},
+/**
+ * リモートコマンドの実行履歴を取得
+ */
+async getRemoteCommandHistory() : Promise<RemoteCommandResult[]> {
+    return await TAURI_INVOKE("get_remote_command_history");
+},
+/**
+ * 端末のロック状態を取得
+ */
+async getTerminalLock() : Promise<TerminalLock> {
+    return await TAURI_INVOKE("get_terminal_lock");
+},
+/**
+ * 固定したサーバー公開鍵を取得
+ */
+async getServerKey() : Promise<PinnedServerKey | null> {
+    return await TAURI_INVOKE("get_server_key");
+},
+/**
+ * サーバー公開鍵を固定（別の鍵への置き換えは責任者承認が必要）
+ */
+async pinServerKey(publicKey: string, approval: SupervisorApproval | null) : Promise<PinnedServerKey> {
+    return await TAURI_INVOKE("pin_server_key", { publicKey, approval });
+},
+/**
+ * 送信状況（最後にサーバーが受信を確認した日時など）を取得
+ */
+async getHeartbeatStatus() : Promise<HeartbeatStatus> {
+    return await TAURI_INVOKE("get_heartbeat_status");
+},
+/**
+ * すぐにハートビートを作成（定期送信を待たずに送る場合）
+ */
+async createHeartbeat() : Promise<SignedHeartbeat> {
+    return await TAURI_INVOKE("create_heartbeat");
+},
+/**
+ * サーバーの受信確認を通知
+ */
+async ackHeartbeat(acknowledgedAt: number | null) : Promise<HeartbeatStatus> {
+    return await TAURI_INVOKE("ack_heartbeat", { acknowledgedAt });
+},
+/**
+ * 送信失敗を通知
+ */
+async failHeartbeat(error: string) : Promise<HeartbeatStatus> {
+    return await TAURI_INVOKE("fail_heartbeat", { error });
+},
+/**
+ * バッテリーの状態を報告
+ */
+async reportBattery(level: number, charging: boolean) : Promise<null> {
+    return await TAURI_INVOKE("report_battery", { level, charging });
+},
+/**
+ * 選択中のイベントを取得
+ */
+async getActiveEvent() : Promise<ActiveEvent | null> {
+    return await TAURI_INVOKE("get_active_event");
+},
+/**
+ * イベントを選択（開局中は切り替え不可）
+ */
+async selectEvent(eventId: string, eventName: string) : Promise<ActiveEvent> {
+    return await TAURI_INVOKE("select_event", { eventId, eventName });
+},
+/**
+ * 端末内にデータが残っているイベント一覧
+ */
+async listLocalEvents() : Promise<string[]> {
+    return await TAURI_INVOKE("list_local_events");
+},
+/**
+ * 選択中イベントのレシート番号を採番
+ */
+async issueReceiptNumber() : Promise<string> {
+    return await TAURI_INVOKE("issue_receipt_number");
+},
+/**
+ * 選択中イベントのデータをアーカイブに書き出し、必要なら端末から消去する（責任者のみ）
+ */
+async archiveEvent(destination: string, upload: boolean, reset: boolean) : Promise<EventArchive> {
+    return await TAURI_INVOKE("archive_event", { destination, upload, reset });
+},
+/**
+ * 端末設定を取得
+ */
+async getTerminalConfig() : Promise<TerminalConfig> {
+    return await TAURI_INVOKE("get_terminal_config");
+},
+/**
+ * 端末設定を保存（メインウィンドウから責任者セッション中にだけ呼べる）
+ */
+async updateTerminalConfig(config: TerminalConfig) : Promise<null> {
+    return await TAURI_INVOKE("update_terminal_config", { config });
+},
+/**
+ * サーバーから配信された署名付きの端末設定を検証して保存
+ */
+async applySignedConfig(update: SignedPayload) : Promise<TerminalConfig> {
+    return await TAURI_INVOKE("apply_signed_config", { update });
+},
+/**
+ * サーバーから配信された署名付きの価格改定を検証して価格表に反映（変わった商品はジャーナルに記録）
+ */
+async applyPriceUpdate(update: SignedPayload, staffId: string | null) : Promise<PriceBookUpdate> {
+    return await TAURI_INVOKE("apply_price_update", { update, staffId });
+},
+/**
+ * 価格表を取得（会計中のカゴはこの版の価格で計算し、レシートに版を入れる）
+ */
+async getPriceBook() : Promise<PriceBook> {
+    return await TAURI_INVOKE("get_price_book");
+},
+/**
+ * 印字文言を取得
+ */
+async getPrintStrings() : Promise<PrintStrings> {
+    return await TAURI_INVOKE("get_print_strings");
+},
+/**
+ * 選択中のイベントの印字文言を保存
+ */
+async updatePrintStrings(strings: PrintStrings) : Promise<null> {
+    return await TAURI_INVOKE("update_print_strings", { strings });
+},
+/**
+ * レシートQRを復元し、発行端末の公開鍵で署名を検証する（返品処理用）
+ * 
+ * 別の端末が発行したレシートは、その端末の公開鍵（サーバーの登録情報）を指定する
+ * 省略した場合はこの端末が発行したレシートとして検証する
+ */
+async decodeReceiptQr(data: string, publicKey: string | null) : Promise<DecodedReceiptQr> {
+    return await TAURI_INVOKE("decode_receipt_qr", { data, publicKey });
+},
+/**
+ * 閉局レポートのQRを復元し、発行端末の公開鍵で署名を検証する（本部での取り込み用）
+ */
+async decodeClosingReportQr(data: string, publicKey: string) : Promise<DecodedClosingReportQr> {
+    return await TAURI_INVOKE("decode_closing_report_qr", { data, publicKey });
+},
+/**
+ * 閉局レポートのQRを復元し、発行端末の公開鍵で署名を検証する（本部での取り込み用）
+ */
+async decodeClosingReportQr(data: string, publicKey: string) : Promise<DecodedClosingReportQr> {
+    return await TAURI_INVOKE("decode_closing_report_qr", { data, publicKey });
+},
+/**
+ * プリンタープロファイル一覧を取得
+ */
+async getPrinterProfiles() : Promise<PrinterProfile[]> {
+    return await TAURI_INVOKE("get_printer_profiles");
+},
+/**
+ * プリンタープロファイルを保存
+ */
+async savePrinterProfile(profile: PrinterProfile) : Promise<null> {
+    return await TAURI_INVOKE("save_printer_profile", { profile });
+},
+/**
+ * プリンタープロファイルを削除
+ */
+async deletePrinterProfile(id: string) : Promise<null> {
+    return await TAURI_INVOKE("delete_printer_profile", { id });
+},
+/**
+ * プリンターの機能を取得（QR・バーコード・カッター・ドロワー・用紙幅・画像印刷）
+ */
+async getPrinterFeatures(printerId: string) : Promise<PrinterFeatures> {
+    return await TAURI_INVOKE("get_printer_features", { printerId });
+},
+/**
+ * 選択中イベントの日別の用紙使用量
+ */
+async getPaperUsage() : Promise<DailyPaperUsage[]> {
+    return await TAURI_INVOKE("get_paper_usage");
+},
+/**
+ * 責任者を登録（2人目以降は既存責任者の承認が必要）
+ */
+async registerSupervisor(supervisorId: string, pin: string, approval: SupervisorApproval | null) : Promise<null> {
+    return await TAURI_INVOKE("register_supervisor", { supervisorId, pin, approval });
+},
+/**
+ * 責任者承認を検証
+ */
+async verifySupervisor(approval: SupervisorApproval) : Promise<null> {
+    return await TAURI_INVOKE("verify_supervisor", { approval });
+},
+/**
+ * 脅迫PINを設定（本人の通常のPINでの承認が必要）
+ */
+async setDuressPin(approval: SupervisorApproval, duressPin: string) : Promise<null> {
+    return await TAURI_INVOKE("set_duress_pin", { approval, duressPin });
+},
+/**
+ * 責任者セッションを開始（責任者向けのコマンドを一定時間受け付ける）
+ */
+async beginSupervisorSession(approval: SupervisorApproval) : Promise<SupervisorSession> {
+    return await TAURI_INVOKE("begin_supervisor_session", { approval });
+},
+/**
+ * 責任者セッションを終了
+ */
+async endSupervisorSession() : Promise<null> {
+    return await TAURI_INVOKE("end_supervisor_session");
+},
+/**
+ * 開始中の責任者セッションを取得
+ */
+async getSupervisorSession() : Promise<SupervisorSession | null> {
+    return await TAURI_INVOKE("get_supervisor_session");
+},
+/**
+ * 選択中イベントのジャーナルを取得
+ */
+async getJournal() : Promise<JournalEntry[]> {
+    return await TAURI_INVOKE("get_journal");
+},
+/**
+ * レシートの再発行回数を取得
+ */
+async getReprintCount(receiptNumber: string) : Promise<number> {
+    return await TAURI_INVOKE("get_reprint_count", { receiptNumber });
+},
+/**
+ * 返品を記録（linesを省略した場合は全返品）
+ */
+async refundSale(originalReceiptNumber: string, lines: RefundLine[] | null, reason: string, staffId: string) : Promise<JournalEntry> {
+    return await TAURI_INVOKE("refund_sale", { originalReceiptNumber, lines, reason, staffId });
+},
+/**
+ * 取引取消を記録
+ */
+async voidSale(receiptNumber: string, reason: string, staffId: string) : Promise<JournalEntry> {
+    return await TAURI_INVOKE("void_sale", { receiptNumber, reason, staffId });
+},
+/**
+ * 売上・返品・取消の集計を取得
+ */
+async getJournalTotals() : Promise<JournalTotals> {
+    return await TAURI_INVOKE("get_journal_totals");
+},
+/**
+ * キオスクの状態を取得
+ */
+async getKioskState() : Promise<KioskState> {
+    return await TAURI_INVOKE("get_kiosk_state");
+},
+/**
+ * キオスクモードを開始
+ */
+async startKiosk() : Promise<KioskState> {
+    return await TAURI_INVOKE("start_kiosk");
+},
+/**
+ * キオスクモードを終了（無人運用のため責任者承認が必要）
+ */
+async stopKiosk(approval: SupervisorApproval) : Promise<KioskState> {
+    return await TAURI_INVOKE("stop_kiosk", { approval });
+},
+/**
+ * スキャンした商品をカゴに追加
+ */
+async kioskScan(item: ReceiptItem) : Promise<KioskState> {
+    return await TAURI_INVOKE("kiosk_scan", { item });
+},
+/**
+ * カゴから明細を削除
+ */
+async kioskRemoveItem(lineIndex: number) : Promise<KioskState> {
+    return await TAURI_INVOKE("kiosk_remove_item", { lineIndex });
+},
+/**
+ * カゴを空にする
+ */
+async kioskClearBasket() : Promise<KioskState> {
+    return await TAURI_INVOKE("kiosk_clear_basket");
+},
+/**
+ * 会計に進む（QR決済の照合用IDを発行）
+ */
+async kioskCheckout() : Promise<KioskState> {
+    return await TAURI_INVOKE("kiosk_checkout");
+},
+/**
+ * 決済をやめてスキャンに戻る
+ */
+async kioskCancelPayment() : Promise<KioskState> {
+    return await TAURI_INVOKE("kiosk_cancel_payment");
+},
+/**
+ * QR決済の完了を受けて売上を確定し、レシートを自動印刷する
+ * 
+ * 売上は確定済みのため、印刷の失敗は通知のみ（スタッフが再発行で対応する）
+ */
+async kioskCompletePayment(paymentRef: string, paymentId: string | null) : Promise<ReceiptData> {
+    return await TAURI_INVOKE("kiosk_complete_payment", { paymentRef, paymentId });
+},
+/**
+ * レシートをスプーラー経由で印刷（結果は print-job-finished イベントで通知）
+ * 
+ * mirror を省略した場合は端末設定のジャーナル用プリンターにもミラー印刷する
+ */
+async spoolReceipt(receipt: ReceiptData, printer: UsbPrinter, mirror: UsbPrinter | null, idempotencyKey: string | null, limitApproval: SupervisorApproval | null) : Promise<SpoolJob> {
+    return await TAURI_INVOKE("spool_receipt", { receipt, printer, mirror, idempotencyKey, limitApproval });
+},
+/**
+ * 保留取引のお預かり票をスプーラー経由で印刷（レシートより後、レポートより先に印刷）
+ */
+async spoolParkedSlip(code: string, printer: UsbPrinter, idempotencyKey: string | null) : Promise<SpoolJob> {
+    return await TAURI_INVOKE("spool_parked_slip", { code, printer, idempotencyKey });
+},
+/**
+ * 閉局レポートをスプーラー経由で印刷（最も低い優先度で印刷）
+ * 
+ * 集計は受付時点のジャーナルで固定し、本部へのアップロードも受付時にキューに追加する
+ */
+async spoolClosingReport(report: ClosingReportData, printer: UsbPrinter, idempotencyKey: string | null) : Promise<SpoolJob> {
+    return await TAURI_INVOKE("spool_closing_report", { report, printer, idempotencyKey });
+},
+/**
+ * 印刷中のジョブと印刷待ちのジョブ一覧（優先度順、進み具合は print-job-progress イベントで通知）
+ */
+async getPrintQueue() : Promise<PrintQueue> {
+    return await TAURI_INVOKE("get_print_queue");
+},
+/**
+ * 印刷待ちのジョブを取り消す（印刷中のジョブは取り消せない）
+ */
+async cancelPrintJob(jobId: string) : Promise<SpoolJob> {
+    return await TAURI_INVOKE("cancel_print_job", { jobId });
+},
+/**
+ * カゴを保留
+ */
+async parkSale(items: ReceiptItem[], total: number, staffId: string, memo: string | null) : Promise<ParkedSale> {
+    return await TAURI_INVOKE("park_sale", { items, total, staffId, memo });
+},
+/**
+ * 保留中の取引一覧
+ */
+async listParkedSales() : Promise<ParkedSale[]> {
+    return await TAURI_INVOKE("list_parked_sales");
+},
+/**
+ * 保留を解除してカゴを取り出す
+ */
+async resumeParkedSale(code: string) : Promise<ParkedSale> {
+    return await TAURI_INVOKE("resume_parked_sale", { code });
+},
+/**
+ * 呼び出し表示の状態を取得
+ */
+async getNowServing() : Promise<NowServing> {
+    return await TAURI_INVOKE("get_now_serving");
+},
+/**
+ * 番号を呼び出して客面表示に出す（呼び出し票は print_recall_slip で印刷）
+ */
+async callNowServing(code: string, staffId: string) : Promise<NowServing> {
+    return await TAURI_INVOKE("call_now_serving", { code, staffId });
+},
+/**
+ * 呼び出し表示を消す
+ */
+async clearNowServing() : Promise<NowServing> {
+    return await TAURI_INVOKE("clear_now_serving");
+},
+/**
+ * 見積番号またはQRコードの文字列から見積を取得
+ */
+async getQuote(code: string) : Promise<Quote> {
+    return await TAURI_INVOKE("get_quote", { code });
+},
+/**
+ * 見積を売上に変換済みとして記録
+ */
+async convertQuote(code: string, receiptNumber: string) : Promise<Quote> {
+    return await TAURI_INVOKE("convert_quote", { code, receiptNumber });
+},
+/**
+ * 予約を登録
+ */
+async registerReservation(items: ReceiptItem[], customerName: string | null, pickupNote: string | null, quoteCode: string | null, staffId: string) : Promise<Reservation> {
+    return await TAURI_INVOKE("register_reservation", { items, customerName, pickupNote, quoteCode, staffId });
+},
+/**
+ * 予約番号またはQRコードの文字列から予約を取得
+ */
+async findReservation(code: string) : Promise<Reservation> {
+    return await TAURI_INVOKE("find_reservation", { code });
+},
+/**
+ * 予約一覧
+ */
+async listReservations() : Promise<Reservation[]> {
+    return await TAURI_INVOKE("list_reservations");
+},
+/**
+ * 受け渡し済みにする
+ */
+async redeemReservation(code: string, staffId: string) : Promise<Reservation> {
+    return await TAURI_INVOKE("redeem_reservation", { code, staffId });
+},
+/**
+ * 予約をキャンセル
+ */
+async cancelReservation(code: string, staffId: string) : Promise<Reservation> {
+    return await TAURI_INVOKE("cancel_reservation", { code, staffId });
+},
+/**
+ * 同期用: 指定日時以降に更新された予約を取得
+ */
+async exportReservations(since: number) : Promise<Reservation[]> {
+    return await TAURI_INVOKE("export_reservations", { since });
+},
+/**
+ * 同期用: 他端末の予約をマージ
+ */
+async mergeReservations(reservations: Reservation[]) : Promise<number> {
+    return await TAURI_INVOKE("merge_reservations", { reservations });
+},
+/**
+ * 残金を受け取っていない内金の売上一覧
+ */
+async listOpenDeposits() : Promise<ReceiptData[]> {
+    return await TAURI_INVOKE("list_open_deposits");
+},
+/**
+ * 内金の売上から残金の売上を作る（支払情報を入れて print_receipt で印刷すると記録される）
+ */
+async prepareDepositBalance(depositReceiptNumber: string, staffId: string) : Promise<ReceiptData> {
+    return await TAURI_INVOKE("prepare_deposit_balance", { depositReceiptNumber, staffId });
+},
+/**
+ * 内金の売上に対する残金の売上（残金を受け取っていない場合は null）
+ */
+async getDepositBalanceSale(depositReceiptNumber: string) : Promise<ReceiptData | null> {
+    return await TAURI_INVOKE("get_deposit_balance_sale", { depositReceiptNumber });
+},
+/**
+ * 商品券カタログを取得
+ */
+async getVoucherCatalog() : Promise<VoucherKind[]> {
+    return await TAURI_INVOKE("get_voucher_catalog");
+},
+/**
+ * 支払に使う商品券を検証し、充当額・おつりを計算
+ */
+async validateVoucherTender(tenders: VoucherTender[], amountDue: number) : Promise<VoucherTenderResult> {
+    return await TAURI_INVOKE("validate_voucher_tender", { tenders, amountDue });
+},
+/**
+ * 分割払いの支払情報を検証し、支払ごとの充当額・おつりを計算（印刷時にも同じ検証を行う）
+ */
+async validatePayments(total: number, payments: PaymentInfo[]) : Promise<TenderBreakdown> {
+    return await TAURI_INVOKE("validate_payments", { total, payments });
+},
+/**
+ * 券番号またはQRコードの文字列から、支払に使えるか確認
+ */
+async validateGiftVoucher(code: string) : Promise<GiftVoucher> {
+    return await TAURI_INVOKE("validate_gift_voucher", { code });
+},
+/**
+ * 使用済みにする
+ */
+async redeemGiftVoucher(code: string, staffId: string, receiptNumber: string | null) : Promise<GiftVoucher> {
+    return await TAURI_INVOKE("redeem_gift_voucher", { code, staffId, receiptNumber });
+},
+/**
+ * ギフト券一覧
+ */
+async listGiftVouchers() : Promise<GiftVoucher[]> {
+    return await TAURI_INVOKE("list_gift_vouchers");
+},
+/**
+ * 同期用: 指定日時以降に更新されたギフト券を取得
+ */
+async exportGiftVouchers(since: number) : Promise<GiftVoucher[]> {
+    return await TAURI_INVOKE("export_gift_vouchers", { since });
+},
+/**
+ * 同期用: 他端末のギフト券をマージ
+ */
+async mergeGiftVouchers(vouchers: GiftVoucher[]) : Promise<number> {
+    return await TAURI_INVOKE("merge_gift_vouchers", { vouchers });
+},
+/**
+ * 会員証のQRコード・NFCのUID・会員番号から会員を検索
+ */
+async findMember(scanned: string) : Promise<Member> {
+    return await TAURI_INVOKE("find_member", { scanned });
+},
+/**
+ * 会員一覧
+ */
+async listMembers() : Promise<Member[]> {
+    return await TAURI_INVOKE("list_members");
+},
+/**
+ * サーバーから受け取った会員を取り込む
+ */
+async importMembers(members: Member[]) : Promise<number> {
+    return await TAURI_INVOKE("import_members", { members });
+},
+/**
+ * 購入履歴を取得（書き出し用。member_idを省略した場合は全会員）
+ */
+async getMemberPurchases(memberId: string | null, since: number) : Promise<MemberPurchase[]> {
+    return await TAURI_INVOKE("get_member_purchases", { memberId, since });
+},
+/**
+ * 会員のポイント残高
+ */
+async getPointsBalance(memberId: string) : Promise<number> {
+    return await TAURI_INVOKE("get_points_balance", { memberId });
+},
+/**
+ * 価格変更の理由一覧
+ */
+async getPriceOverrideReasons() : Promise<PriceOverrideReason[]> {
+    return await TAURI_INVOKE("get_price_override_reasons");
+},
+/**
+ * 責任者の承認で明細の価格を変更（返した印を明細の price_override に入れて印刷する）
+ */
+async approvePriceOverride(request: PriceOverrideRequest, staffId: string, approval: SupervisorApproval) : Promise<PriceOverride> {
+    return await TAURI_INVOKE("approve_price_override", { request, staffId, approval });
+},
+/**
+ * スタッフが年齢を確認したことを記録（返した記録をレシートの age_verification に入れて印刷する。1件の売上にだけ使える）
+ */
+async confirmAgeVerification(staffId: string) : Promise<AgeVerification> {
+    return await TAURI_INVOKE("confirm_age_verification", { staffId });
+},
+/**
+ * 設定されたキャンペーン一覧
+ */
+async getPromotions() : Promise<Promotion[]> {
+    return await TAURI_INVOKE("get_promotions");
+},
+/**
+ * 会計の明細にキャンペーンを当てはめ、割引後の合計を計算
+ */
+async quotePromotions(items: ReceiptItem[]) : Promise<PromotionQuote> {
+    return await TAURI_INVOKE("quote_promotions", { items });
+},
+/**
+ * 会計の合計（キャンペーンの割引後）を端末設定で丸める
+ */
+async quoteRounding(amount: number, cashOnlyPayment: boolean) : Promise<RoundingQuote> {
+    return await TAURI_INVOKE("quote_rounding", { amount, cashOnlyPayment });
+},
+/**
+ * 売上を確認してジャーナルに記録し、印刷するレシートを返す
+ * 
+ * Rustで印刷しない経路（AndroidのBluetoothプリンター）は、印刷の前にこのコマンドを通す
+ */
+async prepareSale(receipt: ReceiptData, idempotencyKey: string | null, limitApproval: SupervisorApproval | null) : Promise<ReceiptData> {
+    return await TAURI_INVOKE("prepare_sale", { receipt, idempotencyKey, limitApproval });
+},
+/**
+ * 現在のセッション状態を取得
+ */
+async getSession() : Promise<SessionState> {
+    return await TAURI_INVOKE("get_session");
+},
+/**
+ * 開局
+ * 
+ * 会場ネットワーク確認に一致しない場合、警告モードでは警告を通知して開局し、
+ * ブロックモードでは責任者の承認がない限り開局しない
+ */
+async openSession(staffId: string, approval: SupervisorApproval | null) : Promise<SessionState> {
+    return await TAURI_INVOKE("open_session", { staffId, approval });
+},
+/**
+ * 会場ネットワークに接続しているか確認
+ */
+async checkVenueNetwork() : Promise<VenueCheck> {
+    return await TAURI_INVOKE("check_venue_network");
+},
+/**
+ * 接続中のSSIDを報告（取得できない場合はnull）
+ */
+async reportNetworkSsid(ssid: string | null) : Promise<void> {
+    await TAURI_INVOKE("report_network_ssid", { ssid });
+},
+/**
+ * 担当者の交代を記録（スタッフのログイン時に呼ぶ）
+ */
+async switchStaff(staffId: string, staffName: string | null) : Promise<SessionState> {
+    return await TAURI_INVOKE("switch_staff", { staffId, staffName });
+},
+/**
+ * 閉局
+ * 
+ * 計測値の送信が有効な場合は、あわせてアップロードキューに追加する（失敗しても閉局は続ける）
+ */
+async closeSession() : Promise<SessionState> {
+    return await TAURI_INVOKE("close_session");
+},
+/**
+ * 閉局リマインダーを設定
+ */
+async setClosingReminder(eventEndAt: number, printer: ReminderPrinter | null) : Promise<ReminderConfig> {
+    return await TAURI_INVOKE("set_closing_reminder", { eventEndAt, printer });
+},
+/**
+ * 閉局リマインダー設定を取得
+ */
+async getClosingReminder() : Promise<ReminderConfig | null> {
+    return await TAURI_INVOKE("get_closing_reminder");
+},
+/**
+ * 閉局リマインダーを解除
+ */
+async cancelClosingReminder() : Promise<null> {
+    return await TAURI_INVOKE("cancel_closing_reminder");
+},
+/**
+ * ドロワー在高を取得
+ */
+async getDrawerContents() : Promise<DenominationCount[]> {
+    return await TAURI_INVOKE("get_drawer_contents");
+},
+/**
+ * ドロワー在高（釣銭準備金）を設定
+ * 
+ * 担当者別の集計のため、設定したことをジャーナルに記録する
+ * メインウィンドウから責任者セッション中にだけ呼べる
+ */
+async setDrawerContents(denominations: DenominationCount[], staffId: string | null) : Promise<null> {
+    return await TAURI_INVOKE("set_drawer_contents", { denominations, staffId });
+},
+/**
+ * 釣り銭の金種内訳を提案（availableを省略した場合はドロワー在高を使用）
+ */
+async suggestChange(amount: number, available: DenominationCount[] | null) : Promise<DenominationCount[]> {
+    return await TAURI_INVOKE("suggest_change", { amount, available });
+},
+/**
+ * 記録した稼働状況の計測値を取得（計測が無効な場合は空の値）
+ */
+async getMetrics() : Promise<Metrics> {
+    return await TAURI_INVOKE("get_metrics");
+},
+/**
+ * 選択中の接続先環境を取得
+ */
+async getEnvironment() : Promise<ApiEnvironment> {
+    return await TAURI_INVOKE("get_environment");
+},
+/**
+ * 接続先環境を切り替え
+ * 
+ * メインウィンドウから責任者セッション中に、閉局して送信待ちのデータがない状態でだけ切り替えられる
+ */
+async switchEnvironment(environment: ApiEnvironment) : Promise<EnvironmentState> {
+    return await TAURI_INVOKE("switch_environment", { environment });
+},
+/**
+ * CSV・Excelの商品一覧を読み込んで検証する（ローカルの商品キャッシュへの追加はフロントエンドで行う）
+ */
+async importCatalog(path: string, mapping: CatalogMapping) : Promise<CatalogImport> {
+    return await TAURI_INVOKE("import_catalog", { path, mapping });
+},
+/**
+ * 商品データにないコードをスキャンした場合に、入力した値段で商品を仮登録する
+ * 
+ * 要確認の印を付けて保存し、本部への送信をアップロードキューに積む
+ */
+async createProductFromScan(code: string, name: string, price: number, circleName: string | null, staffId: string) : Promise<ProvisionalProduct> {
+    return await TAURI_INVOKE("create_product_from_scan", { code, name, price, circleName, staffId });
+},
+/**
+ * ISDNの登録情報を照会する（商品の追加時の入力補助）
+ * 
+ * 照会に時間がかかってもメインスレッドを止めないよう、非同期のコマンドとして実行する
+ */
+async lookupIsdn(isdn: string) : Promise<IsdnLookup> {
+    return await TAURI_INVOKE("lookup_isdn", { isdn });
+},
+/**
+ * 仮登録した商品の一覧
+ */
+async listProvisionalProducts() : Promise<ProvisionalProduct[]> {
+    return await TAURI_INVOKE("list_provisional_products");
+},
+/**
+ * 仮登録した商品を確認済みにする（メインウィンドウから責任者セッション中にだけ呼べる）
+ */
+async reviewProvisionalProduct(jan: string) : Promise<ProvisionalProduct> {
+    return await TAURI_INVOKE("review_provisional_product", { jan });
+},
+/**
+ * 設定中の売上目標を取得
+ */
+async getSalesTarget() : Promise<SalesTarget | null> {
+    return await TAURI_INVOKE("get_sales_target");
+},
+/**
+ * 売上目標を設定（目標を 0 にすると設定を消す）
+ */
+async setSalesTarget(targetAmount: number, breakEvenAmount: number | null, staffId: string | null) : Promise<SalesTarget | null> {
+    return await TAURI_INVOKE("set_sales_target", { targetAmount, breakEvenAmount, staffId });
+},
+/**
+ * 売上目標に対する進捗を取得
+ */
+async getSalesProgress() : Promise<SalesProgress> {
+    return await TAURI_INVOKE("get_sales_progress");
+},
+/**
+ * 接続されているプリンターの一覧（設定したシリアルプリンター・ネットワークプリンターを含む）
+ */
+async getUsbDevices() : Promise<DeviceInfo[]> {
+    return await TAURI_INVOKE("get_usb_devices");
+},
+async textPrint(vendorId: number, deviceId: number, text: string, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("text_print", { vendorId, deviceId, text, paperWidth });
+},
+/**
+ * 印刷先を指定してテキストを印刷
+ */
+async textPrintTo(target: PrinterTarget, text: string, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("text_print_to", { target, text, paperWidth });
+},
+/**
+ * バーコードを1枚印刷（棚札・取り置き札の貼り替え用）
+ * 
+ * EAN-13・EAN-8 はチェックデジットを含めて指定し、合わない場合は印刷しない
+ */
+async printBarcode(target: PrinterTarget, data: string, symbology: BarcodeSymbology, options: BarcodeOptions | null, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("print_barcode", { target, data, symbology, options, paperWidth });
+},
+/**
+ * プリンターの動作確認ページ印刷
+ * 
+ * レシートで使う機能（文字装飾・漢字・QR・バーコード・画像・カット）を見出し付きで一通り印刷する
+ */
+async printTestPage(vendorId: number, deviceId: number, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("print_test_page", { vendorId, deviceId, paperWidth });
+},
+/**
+ * 印刷先を指定して動作確認ページ印刷
+ */
+async printTestPageTo(target: PrinterTarget, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("print_test_page_to", { target, paperWidth });
+},
+/**
+ * 同じサブネットでRAW印刷のポート（既定は9100番）に接続できる機器を探す
+ */
+async discoverNetworkPrinters(port: number | null) : Promise<NetworkPrinter[]> {
+    return await TAURI_INVOKE("discover_network_printers", { port });
+},
+/**
+ * アドレスを指定してネットワークプリンターに接続確認を印刷（設定に追加する前の確認用）
+ */
+async networkPrintTestPage(host: string, port: number, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("network_print_test_page", { host, port, paperWidth });
+},
+/**
+ * アドレスを指定してネットワークプリンターにテキストを印刷
+ */
+async networkPrintText(host: string, port: number, text: string, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("network_print_text", { host, port, text, paperWidth });
+},
+/**
+ * 端末のシリアルポートの一覧（シリアルプリンターの設定用）
+ */
+async listSerialPorts() : Promise<SerialPortInfo[]> {
+    return await TAURI_INVOKE("list_serial_ports");
+},
+/**
+ * ペアリング済みの Bluetooth（SPP）プリンターの一覧
+ * 
+ * ペアリングは OS の Bluetooth 設定で行う（ペアリングするとシリアルポートが作られる）
+ */
+async listBluetoothPrinters() : Promise<BluetoothSerialPort[]> {
+    return await TAURI_INVOKE("list_bluetooth_printers");
+},
+/**
+ * Bluetooth（SPP）プリンターのペアリング・接続状態（接続できるかは実際に開いて確かめる）
+ */
+async bluetoothPrinterStatus(port: string) : Promise<BluetoothLinkStatus> {
+    return await TAURI_INVOKE("bluetooth_printer_status", { port });
+},
+/**
+ * 用紙センサーの状態を取得
+ */
+async getPaperStatus(vendorId: number, deviceId: number) : Promise<PaperStatus> {
+    return await TAURI_INVOKE("get_paper_status", { vendorId, deviceId });
+},
+/**
+ * プリンターの状態を取得（用紙ニアエンド・用紙切れ・カバー開・エラー。USB接続のため電池残量・電波強度はない）
+ * 
+ * 開けない・状態の問い合わせに応答しない場合は未接続として返す
+ */
+async getPrinterStatus(vendorId: number, deviceId: number) : Promise<PrinterStatus> {
+    return await TAURI_INVOKE("get_printer_status", { vendorId, deviceId });
+},
+/**
+ * 商品ラベル印刷（ラベル紙1枚に1商品）
+ * 
+ * copies 枚を連続して印刷する（ラベル紙以外でも1枚ずつカットして印刷する）
+ */
+async printProductLabel(target: PrinterTarget, item: ReceiptItem, copies: number | null, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("print_product_label", { target, item, copies, paperWidth });
+},
+async welcomePrint(target: PrinterTarget, id: string, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("welcome_print", { target, id, paperWidth });
+},
+/**
+ * レシート印刷
+ * 
+ * reprint: 記録済みの売上の写しとして印刷する（再発行(コピー)と明記し、回数をジャーナルに残す）
+ * 再発行では receipt のレシート番号だけを使い、売上の記録・確認（prepare_sale）と limit_approval は使わない
+ */
+async printReceipt(vendorId: number, deviceId: number, receipt: ReceiptData, paperWidth: number | null, idempotencyKey: string | null, limitApproval: SupervisorApproval | null, reprint: ReprintRequest | null) : Promise<null> {
+    return await TAURI_INVOKE("print_receipt", { vendorId, deviceId, receipt, paperWidth, idempotencyKey, limitApproval, reprint });
+},
+/**
+ * Bluetooth（SPP）プリンターでレシート印刷（持ち歩くモバイルプリンター用）
+ * 
+ * 売上の記録とレシートの内容は print_receipt と同じ
+ */
+async bluetoothPrintReceipt(port: string, receipt: ReceiptData, paperWidth: number | null, idempotencyKey: string | null, limitApproval: SupervisorApproval | null) : Promise<null> {
+    return await TAURI_INVOKE("bluetooth_print_receipt", { port, receipt, paperWidth, idempotencyKey, limitApproval });
+},
+/**
+ * 印刷先を指定してレシート印刷
+ */
+async printReceiptTo(target: PrinterTarget, receipt: ReceiptData, paperWidth: number | null, idempotencyKey: string | null, limitApproval: SupervisorApproval | null, reprint: ReprintRequest | null) : Promise<null> {
+    return await TAURI_INVOKE("print_receipt_to", { target, receipt, paperWidth, idempotencyKey, limitApproval, reprint });
+},
+/**
+ * レシートの長さ（mm）と行数を見積もる（印刷はしない）
+ * 
+ * 大量の明細で長くなるレシートを、印刷前に分割するか確認するために使う
+ */
+async estimateReceiptLength(receipt: ReceiptData, paperWidth: number | null) : Promise<PaperLength> {
+    return await TAURI_INVOKE("estimate_receipt_length", { receipt, paperWidth });
+},
+/**
+ * レシート再発行
+ * 
+ * ジャーナルの売上記録から再描画する
+ * 再発行回数が設定の上限を超える場合は責任者承認が必要
+ */
+async reprintReceipt(vendorId: number, deviceId: number, receiptNumber: string, staffId: string, paperWidth: number | null, approval: SupervisorApproval | null, idempotencyKey: string | null) : Promise<number> {
+    return await TAURI_INVOKE("reprint_receipt", { vendorId, deviceId, receiptNumber, staffId, paperWidth, approval, idempotencyKey });
+},
+/**
+ * 印刷先を指定してレシート再発行
+ * 
+ * 元の売上と異なるプリンター・用紙幅でも、印刷先の用紙幅でレイアウトし直して印刷する
+ * （例: 58mm のモバイルプリンターで渡したレシートを、事務所の 80mm のプリンターで再発行する）
+ */
+async reprintReceiptTo(target: PrinterTarget, receiptNumber: string, staffId: string, paperWidth: number | null, approval: SupervisorApproval | null, idempotencyKey: string | null) : Promise<number> {
+    return await TAURI_INVOKE("reprint_receipt_to", { target, receiptNumber, staffId, paperWidth, approval, idempotencyKey });
+},
+/**
+ * 返品伝票印刷
+ * 
+ * ジャーナルの返品記録（refund_sale の戻り値のID）と元の売上から描画する
+ */
+async printRefundReceipt(target: PrinterTarget, refundId: string, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("print_refund_receipt", { target, refundId, paperWidth });
+},
+/**
+ * レシート・オン・デマンドの控えのQRから本レシートを印刷
+ * 
+ * 1回目は再発行扱いにしない（2回目以降は reprint_receipt を使う）
+ */
+async printReceiptFromStub(target: PrinterTarget, scanned: string, staffId: string, paperWidth: number | null, idempotencyKey: string | null) : Promise<string> {
+    return await TAURI_INVOKE("print_receipt_from_stub", { target, scanned, staffId, paperWidth, idempotencyKey });
+},
+/**
+ * ギフトレシート（金額非表示）を印刷
+ * 
+ * ジャーナルの売上記録から、商品名と点数だけを載せたレシートを描画する（取消済みの売上は印刷しない）
+ */
+async printGiftReceipt(target: PrinterTarget, receiptNumber: string, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("print_gift_receipt", { target, receiptNumber, paperWidth });
+},
+/**
+ * 閉局レポート印刷
+ * 
+ * 印刷に成功したらレポートを署名付きでアップロードキューに追加する
+ */
+async printClosingReport(target: PrinterTarget, report: ClosingReportData, paperWidth: number | null, idempotencyKey: string | null) : Promise<null> {
+    return await TAURI_INVOKE("print_closing_report", { target, report, paperWidth, idempotencyKey });
+},
+/**
+ * ギフト券を発行して印刷
+ * 
+ * 発行した時点でジャーナルに記録するため、印刷に失敗した場合は券番号を指定して再印刷する
+ */
+async issueGiftVoucher(target: PrinterTarget, amount: number, staffId: string, paperWidth: number | null) : Promise<GiftVoucher> {
+    return await TAURI_INVOKE("issue_gift_voucher", { target, amount, staffId, paperWidth });
+},
+/**
+ * 発行済みのギフト券を印刷（未使用のもののみ）
+ */
+async printGiftVoucher(target: PrinterTarget, serial: string, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("print_gift_voucher", { target, serial, paperWidth });
+},
+/**
+ * 保留取引の呼び出し伝票印刷
+ */
+async printParkedSlip(target: PrinterTarget, code: string, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("print_parked_slip", { target, code, paperWidth });
+},
+/**
+ * 呼び出し中の番号の呼び出し票を印刷（呼び直しの場合は回数を添える）
+ */
+async printRecallSlip(target: PrinterTarget, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("print_recall_slip", { target, paperWidth });
+},
+/**
+ * サポート向けの端末確認票を印刷
+ * 
+ * 確認コードを大きく印字し、サポート側で計算したコードと読み合わせる
+ */
+async printTerminalVerification(target: PrinterTarget, publicKey: string, challenge: string, paperWidth: number | null) : Promise<TerminalVerification> {
+    return await TAURI_INVOKE("print_terminal_verification", { target, publicKey, challenge, paperWidth });
+},
+/**
+ * 見積・予約票印刷
+ * 
+ * 見積を保存して印刷する（売上にはならない）
+ */
+async printQuote(target: PrinterTarget, items: ReceiptItem[], validUntil: string, staffId: string, customerName: string | null, paperWidth: number | null) : Promise<Quote> {
+    return await TAURI_INVOKE("print_quote", { target, items, validUntil, staffId, customerName, paperWidth });
+},
+/**
+ * 予約票印刷（受け取り時に提示するQRコード付き）
+ */
+async printReservationSlip(target: PrinterTarget, code: string, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("print_reservation_slip", { target, code, paperWidth });
+},
+/**
+ * 予約の受け渡し確認票印刷
+ */
+async printPickupConfirmation(target: PrinterTarget, code: string, paperWidth: number | null) : Promise<null> {
+    return await TAURI_INVOKE("print_pickup_confirmation", { target, code, paperWidth });
+},
+/**
+ * 売上目標の進捗を印刷（目標と実績を並べた短い帳票）
+ */
+async printSalesProgress(target: PrinterTarget, paperWidth: number | null) : Promise<SalesProgress> {
+    return await TAURI_INVOKE("print_sales_progress", { target, paperWidth });
+}
+}
+
+/** user-defined events **/
+
+
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+/**
+ * 選択中のイベント
+ */
+export type ActiveEvent = { event_id: string; event_name: string; 
+/**
+ * 選択日時（UNIX秒）
+ */
+selected_at: number }
+/**
+ * 年齢確認の記録
+ */
+export type AgeVerification = { 
+/**
+ * 端末が発行した確認の番号（1件の売上にだけ使える）
+ */
+token?: string; 
+/**
+ * 確認したスタッフ番号
+ */
+staff_id: string; 
+/**
+ * 確認日時（UNIX秒）
+ */
+verified_at: number }
+/**
+ * 接続先の環境
+ */
+export type ApiEnvironment = "production" | "staging" | "local"
+/**
+ * 適用したキャンペーン
+ */
+export type AppliedPromotion = { promotion_id: string; name: string; 
+/**
+ * 割引額
+ */
+discount: number; 
+/**
+ * 明細ごとの割引額（合計は discount と一致する）
+ */
+lines?: PromotionLineDiscount[] }
+/**
+ * 支払1件の充当結果
+ */
+export type AppliedTender = { method: string; 
+/**
+ * 受け取った額
+ */
+amount: number; rule: ChangeRule; 
+/**
+ * 売上に充てた額
+ */
+applied: number; 
+/**
+ * この支払から返すおつり
+ */
+change: number; 
+/**
+ * この支払の切り捨てた超過分
+ */
+forfeited: number }
+/**
+ * アーカイブの目録（アーカイブの先頭に manifest.json として入れる）
+ */
+export type ArchiveManifest = { event_id: string; event_name: string | null; 
+/**
+ * 接続先環境名
+ */
+environment: string; 
+/**
+ * 作成日時（UNIX秒）
+ */
+created_at: number; files: ArchivedFile[] }
+/**
+ * アーカイブに入れたファイル
+ */
+export type ArchivedFile = { 
+/**
+ * イベントのデータディレクトリからの相対パス
+ */
+path: string; size: number; 
+/**
+ * SHA-256（16進）
+ */
+sha256: string }
+/**
+ * バーコードの高さ・幅・HRI の位置（省略した項目は既定値）
+ */
+export type BarcodeOptions = { 
+/**
+ * 高さ（ドット、既定: 80）
+ */
+height?: number | null; 
+/**
+ * モジュール幅 2〜6（既定: 2）
+ */
+width?: number | null; hri?: HriPosition }
+/**
+ * 印刷するバーコードの種類
+ */
+export type BarcodeSymbology = 
+/**
+ * EAN-13（JAN-13）
+ */
+"ean_13" | 
+/**
+ * EAN-8（JAN-8）
+ */
+"ean_8" | 
+/**
+ * CODE128（コードセットB）
+ */
+"code_128"
+/**
+ * バーコードの種類
+ */
+export type BarcodeType = "code_128" | "ean_13" | "ean_8" | "code_39" | "itf"
+/**
+ * Bluetooth（SPP）プリンターの接続状態
+ */
+export type BluetoothLinkStatus = 
+/**
+ * ペアリングされていない（OSにポートがない）
+ */
+"not_paired" | 
+/**
+ * ペアリング済みだが接続できない（電源が切れている・圏外など）
+ */
+"paired" | 
+/**
+ * 接続できる
+ */
+"connected"
+/**
+ * Bluetooth（SPP）のシリアルポート
+ */
+export type BluetoothSerialPort = { 
+/**
+ * ポート（COM5、/dev/cu.MP-B20-SerialPort など）
+ */
+port: string; 
+/**
+ * 一覧に出す名前（macOS ではポート名に含まれる機器名）
+ */
+name: string }
+/**
+ * カード詳細情報（クレジット売上票用）
+ */
+export type CardDetails = { 
+/**
+ * カードブランド（visa, mastercard等）
+ */
+brand?: string | null; 
+/**
+ * カード番号下4桁
+ */
+last4?: string | null; 
+/**
+ * 有効期限（月）
+ */
+exp_month?: number | null; 
+/**
+ * 有効期限（年）
+ */
+exp_year?: number | null; 
+/**
+ * カード名義人
+ */
+cardholder_name?: string | null; 
+/**
+ * カード種別（credit, debit等）
+ */
+funding?: string | null; 
+/**
+ * 端末シリアル番号
+ */
+terminal_serial_number?: string | null; 
+/**
+ * 加盟店名（Stripeアカウント名）
+ */
+merchant_name?: string | null; 
+/**
+ * 取引種別（sale/refund）
+ */
+transaction_type?: string | null; 
+/**
+ * 支払区分
+ */
+payment_type?: string | null; 
+/**
+ * 取引日時（ISO8601形式）
+ */
+transaction_at?: string | null }
+/**
+ * 取り込みの結果
+ */
+export type CatalogImport = { products: ImportedProduct[]; errors: CatalogImportError[] }
+/**
+ * 取り込めなかった行
+ */
+export type CatalogImportError = { 
+/**
+ * ファイル上の行番号（見出しが1行目）
+ */
+row: number; message: string }
+/**
+ * 列の割り当て（見出しの名前）
+ */
+export type CatalogMapping = { 
+/**
+ * 商品名の列
+ */
+name: string; 
+/**
+ * 値段（税込）の列
+ */
+price: string; 
+/**
+ * JAN（1段目）の列
+ */
+jan: string | null; 
+/**
+ * 書籍JANコード2段目の列
+ */
+jan2: string | null; 
+/**
+ * ISBNの列
+ */
+isbn: string | null; 
+/**
+ * サークル名（版元）の列
+ */
+circle_name: string | null; 
+/**
+ * 成人向けの列（「1」「true」「○」「R18」などを成人向けとみなす）
+ */
+age_restricted: string | null }
+/**
+ * 支払手段ごとのおつりの規則
+ */
+export type ChangeRule = 
+/**
+ * 超過分をおつりとして返す
+ */
+"change" | 
+/**
+ * 超過分は切り捨てる
+ */
+"forfeit" | 
+/**
+ * 超過できない
+ */
+"exact"
+/**
+ * 閉局レポートデータ
+ */
+export type ClosingReportData = { id: string; terminal_id: string; staff_id: string; staff_name: string; event_name?: string | null; denominations: DenominationCount[]; cash_total: number; vouchers: VoucherCount[]; voucher_total: number; grand_total: number; expected_total: number; difference: number; transaction_count: number; closed_at: string; 
+/**
+ * 支払方法別の集計（空の場合は印刷時にジャーナルから集計する）
+ */
+payment_methods?: PaymentMethodTotal[]; 
+/**
+ * 時間帯別の売上（空の場合は印刷時にジャーナルから集計する）
+ */
+hourly_sales?: HourlySales[]; 
+/**
+ * 担当者別の集計（空の場合は印刷時にジャーナルから集計する）
+ */
+staff_totals?: StaffTotal[]; 
+/**
+ * 売上に使われた商品券の種別ごとの集計（空の場合は印刷時にジャーナルから集計する）
+ */
+voucher_sales?: VoucherSales[] }
+/**
+ * 閉局レポートの文言
+ */
+export type ClosingReportStrings = { title: string; report_id: string; terminal_id: string; staff: string; closed_at: string; sales_summary: string; transaction_count: string; 
+/**
+ * 件数の表示（{count}: 件数）
+ */
+count: string; sales_total_with_tax: string; 
+/**
+ * 返品の行（{count}: 件数）
+ */
+refunds: string; 
+/**
+ * 取消の行（{count}: 件数）
+ */
+voids: string; 
+/**
+ * 売上の端数調整額の合計の行
+ */
+rounding: string; payment_methods: string; 
+/**
+ * 支払方法の行（{method}: 支払手段名, {count}: 件数）
+ */
+payment_method: string; 
+/**
+ * 支払方法ごとの返品・取消の行（{count}: 件数）
+ */
+payment_method_refunds: string; hourly_sales: string; 
+/**
+ * 時間帯の表示（{hour}: 時）
+ */
+hour: string; 
+/**
+ * 時間帯ごとの件数と金額（{count}: 件数, {amount}: 金額）
+ */
+hourly_value: string; staff_totals: string; 
+/**
+ * 担当者ごとの売上の行（{count}: 件数）
+ */
+staff_sales: string; 
+/**
+ * 担当者ごとの返品の行（{count}: 件数）
+ */
+staff_refunds: string; 
+/**
+ * 担当者ごとの取消の行（{count}: 件数）
+ */
+staff_voids: string; staff_drawer: string; 
+/**
+ * 回数の表示（{count}: 回数）
+ */
+times: string; cash_breakdown: string; cash_total: string; vouchers: string; voucher_total: string; 
+/**
+ * 売上に使われた商品券の行（{name}: 種別名, {count}: 件数）
+ */
+voucher_sales: string; settlement: string; counted_total: string; sales_total: string; difference: string; footer: string }
+/**
+ * QRコードに埋め込む閉局レポートの集計
+ * 
+ * QRコードに収まるよう、時間帯別・担当者別の集計は含めない
+ */
+export type CompactClosingReport = { id: string; e?: string | null; s: string; 
+/**
+ * 閉局日時
+ */
+at: string; 
+/**
+ * 取引件数
+ */
+n: number; 
+/**
+ * 売上合計（税込）
+ */
+t: number; 
+/**
+ * 実際に数えた合計
+ */
+g: number; cash: number; v: number; diff: number; 
+/**
+ * 返品の件数と額
+ */
+rc: number; ra: number; 
+/**
+ * 取消の件数と額
+ */
+vc: number; va: number; pm: CompactPaymentTotal[]; 
+/**
+ * 枚数が0の金種は含めない
+ */
+dn: CompactDenomination[] }
+/**
+ * QRコード用の金種別の枚数
+ */
+export type CompactDenomination = { d: number; c: number }
+/**
+ * QRコード用の明細
+ */
+export type CompactItem = { 
+/**
+ * JAN
+ */
+j: string; 
+/**
+ * 商品名
+ */
+n: string; 
+/**
+ * 数量
+ */
+q: number; 
+/**
+ * 値段（単価 x 数量）
+ */
+p: number }
+/**
+ * QRコード用の支払情報
+ */
+export type CompactPayment = { m: string; a: number }
+/**
+ * QRコード用の支払方法別の集計
+ */
+export type CompactPaymentTotal = { m: string; c: number; a: number }
+/**
+ * QRコードに埋め込むレシート内容
+ */
+export type CompactReceipt = { r: string; i: CompactItem[]; t: number; x: number; y: CompactPayment[] }
+/**
+ * クレジット売上票の文言
+ */
+export type CreditSlipStrings = { title: string; customer_copy: string; merchant_copy: string; merchant_name: string; terminal_number: string; transaction_at: string; slip_number: string; member_number: string; transaction_type: string; sale: string; refund: string; payment_type: string; 
+/**
+ * 支払区分の既定値
+ */
+lump_sum: string; card_brand: string; amount: string; signature_heading: string; signature_not_required: string; acknowledgement: string }
+/**
+ * 日別・用紙幅別の使用量
+ */
+export type DailyPaperUsage = { 
+/**
+ * 日付（設定のタイムゾーン、YYYY-MM-DD）
+ */
+date: string; 
+/**
+ * 用紙幅（mm）
+ */
+paper_width: number; 
+/**
+ * 印刷回数
+ */
+jobs: number; 
+/**
+ * 用紙を送った長さ（mm）
+ */
+length_mm: number }
+/**
+ * 1日の使用状況
+ */
+export type DailyUsage = { 
+/**
+ * 日付（YYYY-MM-DD）
+ */
+date: string; count: number; 
+/**
+ * 深夜の署名回数
+ */
+night_count: number; 
+/**
+ * 最初・最後に署名した日時（UNIX秒）
+ */
+first_at: number; last_at: number }
+/**
+ * 読み取った閉局レポートQRの内容
+ */
+export type DecodedClosingReportQr = { report: CompactClosingReport; 
+/**
+ * サーバーでの検証用
+ */
+signed: SignedClosingReport }
+/**
+ * 読み取ったレシートQRの内容
+ */
+export type DecodedReceiptQr = { receipt: CompactReceipt; 
+/**
+ * サーバーでの検証用
+ */
+signed: SignedReceipt }
+/**
+ * 金種カウント
+ */
+export type DenominationCount = { denomination: number; count: number }
+/**
+ * 内金・残金の条件
+ * 
+ * 内金を受け取る売上と残金を受け取る売上の2件に分けて記録し、残金の売上は内金の売上のレシート番号を持つ
+ */
+export type DepositTerms = { 
+/**
+ * ご注文の合計金額（内金と残金の合計）
+ */
+order_total: number; 
+/**
+ * 内金
+ */
+deposit: number; 
+/**
+ * 残金のお支払期日（YYYY-MM-DD）
+ */
+due_date: string; 
+/**
+ * 内金を受け取った売上のレシート番号（残金の売上のみ）
+ */
+deposit_receipt_number?: string | null }
+export type DeviceInfo = { vendor_id: number; device_id: number; name: string }
+/**
+ * 選択中の環境と切り替えた記録
+ */
+export type EnvironmentState = { environment: ApiEnvironment; 
+/**
+ * 切り替えた日時（UNIX秒）
+ */
+switched_at: number; 
+/**
+ * 切り替えた責任者のスタッフ番号
+ */
+switched_by: string | null }
+/**
+ * エラーの原因（DLE EOT 3 の応答）
+ */
+export type ErrorStatus = { 
+/**
+ * オートカッターのエラー（紙詰まりなど）
+ */
+autocutter: boolean; 
+/**
+ * 復帰不可能なエラー（電源の入れ直し・修理が必要）
+ */
+unrecoverable: boolean; 
+/**
+ * 自動復帰するエラー（ヘッドの温度上昇など）
+ */
+auto_recoverable: boolean }
+/**
+ * 作成したアーカイブ
+ */
+export type EventArchive = { 
+/**
+ * 書き出したファイルのパス
+ */
+path: string; manifest: ArchiveManifest; 
+/**
+ * アーカイブファイル全体の SHA-256（16進、受け渡し後の照合用）
+ */
+archive_sha256: string; archive_size: number; 
+/**
+ * 目録をアップロードキューに積んだか
+ */
+uploaded: boolean; 
+/**
+ * イベントのデータを端末から消去したか
+ */
+reset: boolean }
+/**
+ * ギフト券
+ */
+export type GiftVoucher = { 
+/**
+ * 券番号（端末間で重複しないようUUIDから生成）
+ */
+serial: string; amount: number; status: GiftVoucherStatus; 
+/**
+ * 発行したスタッフ番号
+ */
+issued_by: string; 
+/**
+ * 発行日時（UNIX秒）
+ */
+issued_at: number; 
+/**
+ * 券面の署名（署名対象は `signed_message` の文字列）
+ */
+signature: SignatureData; 
+/**
+ * 使用したスタッフ番号
+ */
+redeemed_by: string | null; 
+/**
+ * 使用日時（UNIX秒）
+ */
+redeemed_at: number | null; 
+/**
+ * 使用した売上のレシート番号
+ */
+redeemed_receipt: string | null; 
+/**
+ * 最終更新日時（UNIX秒、同期時のマージに使用）
+ */
+updated_at: number }
+/**
+ * ギフト券の状態
+ */
+export type GiftVoucherStatus = 
+/**
+ * 未使用
+ */
+"issued" | 
+/**
+ * 使用済み
+ */
+"redeemed"
+/**
+ * ハートビート設定
+ */
+export type HeartbeatConfig = { enabled: boolean; 
+/**
+ * 送信間隔（秒）
+ */
+interval_secs: number }
+/**
+ * 送信状況
+ */
+export type HeartbeatStatus = { 
+/**
+ * 最後に作成したハートビートのID
+ */
+last_heartbeat_id: string | null; 
+/**
+ * 最後に作成した日時（UNIX秒）
+ */
+last_sent_at: number | null; 
+/**
+ * サーバーが最後に受信を確認した日時（UNIX秒）
+ */
+last_acknowledged_at: number | null; 
+/**
+ * 最後の送信エラー
+ */
+last_error: string | null }
+/**
+ * 時間帯別の売上
+ */
+export type HourlySales = { 
+/**
+ * 日付（YYYY-MM-DD、端末のタイムゾーン）
+ */
+date: string; 
+/**
+ * 時（0〜23）
+ */
+hour: number; count: number; amount: number }
+/**
+ * バーコードの下・上に印刷する読み取り用の数字（HRI）の位置
+ */
+export type HriPosition = "none" | "above" | "below" | "both"
+/**
+ * 画像を白黒に変換する方法
+ */
+export type ImageMode = 
+/**
+ * 誤差拡散（写真・グラデーション向け）
+ */
+"dither" | 
+/**
+ * しきい値で2値化（線画・文字のロゴ向け）
+ */
+"threshold"
+/**
+ * 画像の読み込み元
+ */
+export type ImageSource = 
+/**
+ * Base64 でエンコードした PNG（`data:image/png;base64,` から始まるものも可）
+ */
+{ type: "base_64"; data: string } | 
+/**
+ * 端末の PNG ファイル
+ */
+{ type: "file"; path: string }
+/**
+ * 取り込んだ商品
+ */
+export type ImportedProduct = { 
+/**
+ * 商品ID（取り込んだ商品はコードから作る）
+ */
+id: string; 
+/**
+ * JAN（書籍でJANの列がない場合はISBNの13桁）
+ */
+jan: string; jan2: string | null; 
+/**
+ * ISBN（13桁）
+ */
+isbn: string | null; is_book: boolean; name: string; circle_name: string | null; price: number; age_restricted: boolean }
+/**
+ * 照会結果
+ */
+export type IsdnLookup = { record: IsdnRecord | null; source: IsdnLookupSource; 
+/**
+ * 照会に失敗した理由（保存済みの結果を返した場合も入る）
+ */
+error: string | null }
+/**
+ * 照会結果の出どころ
+ */
+export type IsdnLookupSource = 
+/**
+ * 登録機関に照会した
+ */
+"registry" | 
+/**
+ * 保存済みの結果
+ */
+"cache" | 
+/**
+ * 照会できず、保存済みの結果もない（オフラインなど）
+ */
+"unavailable" | 
+/**
+ * 照会が無効
+ */
+"disabled"
+/**
+ * ISDNの登録情報（商品の追加に使う項目のみ）
+ */
+export type IsdnRecord = { 
+/**
+ * ハイフンなしのISDN
+ */
+isdn: string; 
+/**
+ * 書名
+ */
+title: string; 
+/**
+ * 発行者（サークル）名
+ */
+circle_name: string | null; 
+/**
+ * 値段
+ */
+price: number | null; 
+/**
+ * 2段目バーコード
+ */
+jan2: string | null; 
+/**
+ * 成人向け（登録の対象年齢が18歳以上）
+ */
+age_restricted: boolean }
+/**
+ * 照会の設定（既定は無効）
+ */
+export type IsdnRegistryConfig = { 
+/**
+ * 登録機関に照会する
+ */
+enabled: boolean; 
+/**
+ * 登録情報（XML）のURL（{isdn}: ハイフンなしのISDN）
+ */
+url: string }
+/**
+ * レシート明細の並び順
+ */
+export type ItemOrder = 
+/**
+ * スキャンした順（送られてきた順）
+ */
+"scan_order" | 
+/**
+ * サークルごとにまとめる
+ */
+"circle" | 
+/**
+ * カテゴリーごとにまとめる
+ */
+"category"
+/**
+ * ジャーナルの1件
+ */
+export type JournalEntry = (
+/**
+ * 売上
+ */
+{ type: "sale"; receipt: ReceiptData } | 
+/**
+ * レシート再発行
+ */
+{ type: "reprint"; receipt_number: string; copy_number: number; supervisor_id: string | null } | 
+/**
+ * レシート控えのQRから本レシートを発行（レシート・オン・デマンド）
+ */
+{ type: "receipt_issued"; receipt_number: string } | 
+/**
+ * 決済後のレシート印刷の失敗（代替の控えを印刷する）
+ */
+{ type: "receipt_print_failed"; receipt_number: string; total: number; error: string; fallback_job_id: string | null } | 
+/**
+ * レシートをメールで送信（宛先はマスクして記録）
+ */
+{ type: "receipt_emailed"; receipt_number: string; address: string; upload_id: string } | 
+/**
+ * 返品（全部・一部）
+ */
+{ type: "refund"; original_receipt_number: string; items: RefundItem[]; amount: number; reason: string } | 
+/**
+ * 取引取消（売上全体の取り消し）
+ */
+{ type: "void"; receipt_number: string; amount: number; reason: string } | 
+/**
+ * ドロワー在高の設定（釣銭の補充・回収）
+ */
+{ type: "drawer_updated"; denominations: DenominationCount[] } | 
+/**
+ * 明細の価格変更（責任者の承認済み。エントリーのIDを明細の印に使う）
+ */
+{ type: "price_override"; jan: string; quantity: number; original_price: number; price: number; reason_code: string; supervisor_id: string } | 
+/**
+ * 上限を超える売上の責任者承認
+ */
+{ type: "limit_override"; receipt_number: string; violations: LimitViolation[]; supervisor_id: string } | 
+/**
+ * ギフト券の発行
+ */
+{ type: "gift_voucher_issued"; serial: string; amount: number } | 
+/**
+ * ギフト券の使用
+ */
+{ type: "gift_voucher_redeemed"; serial: string; amount: number; receipt_number: string | null } | 
+/**
+ * 価格改定の反映（変わった商品のみ）
+ */
+{ type: "price_updated"; issued_at: number; effective_at: number; changes: PriceChange[] }) & { id: string; 
+/**
+ * 記録日時（UNIX秒）
+ */
+recorded_at: number; 
+/**
+ * 操作したスタッフ番号
+ */
+staff_id: string | null; 
+/**
+ * 記録した経路
+ */
+channel?: SaleChannel; 
+/**
+ * 脅迫PINの入力後、解除されるまでに記録したか
+ */
+duress?: boolean; 
+/**
+ * 記録したときの接続先環境
+ */
+environment?: ApiEnvironment }
+/**
+ * 閉局レポート用の集計
+ */
+export type JournalTotals = { sale_count: number; sale_amount: number; refund_count: number; refund_amount: number; void_count: number; void_amount: number; 
+/**
+ * 売上の端数調整額の合計（値引はマイナス、売上の金額には調整後の金額で含まれる）
+ */
+rounding_adjustment?: number; 
+/**
+ * 売上 - 返品 - 取消
+ */
+net_amount: number }
+/**
+ * 端末の状態と一緒に返す使用状況
+ */
+export type KeyUsageSummary = { 
+/**
+ * 記録を始めてからの署名回数
+ */
+total: number; last_used_at: number | null; 
+/**
+ * 日ごとの使用状況（古い順）
+ */
+days: DailyUsage[]; anomalies: UsageAnomaly[] }
+/**
+ * セルフレジ（キオスク）の設定
+ */
+export type KioskConfig = { 
+/**
+ * 自動印刷するプリンターのUSBベンダーID
+ */
+printer_vendor_id: number | null; 
+/**
+ * 自動印刷するプリンターのUSBデバイスID
+ */
+printer_device_id: number | null; 
+/**
+ * 用紙幅（mm）
+ */
+paper_width: number | null; 
+/**
+ * 消費税率（%、内税）
+ */
+tax_rate: number; 
+/**
+ * レシートに印字する支払手段名
+ */
+payment_method_label: string; 
+/**
+ * 1会計の上限金額（無人のため）
+ */
+max_total: number; 
+/**
+ * 1会計の上限明細数
+ */
+max_lines: number }
+/**
+ * キオスクの状態
+ */
+export type KioskState = (
+/**
+ * 商品をスキャン中
+ */
+{ step: "scanning" } | 
+/**
+ * QR決済の完了待ち（カゴは変更できない）
+ */
+{ step: "awaiting_payment"; payment_ref: string; amount: number; requested_at: number }) & { 
+/**
+ * キオスクモード中か
+ */
+active: boolean; 
+/**
+ * カゴの明細
+ */
+basket: ReceiptItem[]; 
+/**
+ * カゴの合計
+ */
+total: number; 
+/**
+ * キオスクモードの開始日時（UNIX秒）
+ */
+started_at: number | null }
+/**
+ * 上限の超過
+ */
+export type LimitViolation = { type: "line_quantity"; jan: string; quantity: number; max: number } | { type: "total"; total: number; max: number } | { type: "change"; change: number; max: number }
+/**
+ * 用紙の種類
+ * 
+ * ラベル紙・ブラックマーク紙はプリンター本体（メモリスイッチ）側でも用紙設定が必要
+ */
+export type MediaMode = 
+/**
+ * 連続紙（通常のレシートロール）
+ */
+"continuous" | 
+/**
+ * ブラックマーク付き用紙
+ */
+"black_mark" | 
+/**
+ * ギャップ（台紙付きのダイカット）ラベル
+ */
+"gap"
+/**
+ * 会員
+ */
+export type Member = { member_id: string; 
+/**
+ * 宛名に印字する名前
+ */
+name: string; 
+/**
+ * 会員証のNFCタグのUID（16進数）
+ */
+card_uid?: string | null; 
+/**
+ * サーバーでのポイント残高（更新日時の時点）
+ */
+points_balance?: number; 
+/**
+ * 最終更新日時（UNIX秒、取り込み時のマージに使用）
+ */
+updated_at: number }
+/**
+ * 会員の購入履歴
+ */
+export type MemberPurchase = { member_id: string; receipt_number: string; event_id: string; total: number; 
+/**
+ * 点数
+ */
+quantity: number; 
+/**
+ * 記録日時（UNIX秒）
+ */
+purchased_at: number }
+/**
+ * 記録した計測値
+ */
+export type Metrics = { 
+/**
+ * 集計を始めた日時（UNIX秒、送信するたびに区切る）
+ */
+since: number; 
+/**
+ * 印刷に成功した回数
+ */
+prints: number; 
+/**
+ * 印刷に失敗した回数（エラーの種類別）
+ */
+print_failures: Partial<{ [key in string]: number }>; 
+/**
+ * 送信に成功した件数
+ */
+syncs: number; 
+/**
+ * 送信に失敗した回数
+ */
+sync_failures: number; 
+/**
+ * キュー投入から送信完了までの秒数の合計・最大
+ */
+sync_latency_total_secs: number; sync_latency_max_secs: number; 
+/**
+ * 最後に確認した送信待ちの件数・その最大
+ */
+queue_depth: number; queue_depth_max: number }
+/**
+ * 計測の設定（既定はすべて無効）
+ */
+export type MetricsConfig = { 
+/**
+ * 端末に記録する
+ */
+enabled: boolean; 
+/**
+ * 閉局時に本部へ送信する
+ */
+upload: boolean }
+/**
+ * 現在のネットワーク
+ */
+export type NetworkInfo = { ssid: string | null; ip: string | null }
+/**
+ * ネットワークプリンターの設定
+ */
+export type NetworkPrinter = { 
+/**
+ * 一覧に出す名前
+ */
+name: string; 
+/**
+ * IPアドレスまたはホスト名
+ */
+host: string; 
+/**
+ * ポート番号
+ */
+port?: number }
+/**
+ * 呼び出し表示の状態
+ */
+export type NowServing = { 
+/**
+ * 呼び出し中の番号
+ */
+current: string | null; 
+/**
+ * 呼び出し中の番号を呼んだ回数（呼び直しで増える）
+ */
+call_count: number; 
+/**
+ * 呼び出した日時（UNIX秒）
+ */
+called_at: number | null; 
+/**
+ * 呼び出した番号を操作したスタッフ番号
+ */
+staff_id: string | null; 
+/**
+ * 以前に呼び出した番号（新しい順）
+ */
+recent: string[] }
+/**
+ * 数値・金額の書式
+ */
+export type NumberFormat = { 
+/**
+ * 通貨記号
+ * 
+ * 既定は全角￥（U+FFE5）。Shift_JISでは半角¥と\が同じコードになるため
+ */
+currency_symbol: string; 
+/**
+ * 通貨記号の位置
+ */
+symbol_position: SymbolPosition; 
+/**
+ * 桁区切り文字（空文字の場合は区切らない）
+ */
+grouping_separator: string; 
+/**
+ * 桁区切りの間隔（0の場合は区切らない）
+ */
+grouping_size: number; 
+/**
+ * 数字を全角で印字する
+ */
+fullwidth_digits: boolean }
+/**
+ * オフラインの状態と原因（DLE EOT 1・DLE EOT 2 の応答）
+ */
+export type OfflineStatus = { 
+/**
+ * オフライン（印刷を受け付けない）
+ */
+offline: boolean; 
+/**
+ * カバーが開いている
+ */
+cover_open: boolean; 
+/**
+ * 紙送りボタンで紙送り中
+ */
+feeding: boolean; 
+/**
+ * 用紙切れで印刷を止めている
+ */
+paper_out_stop: boolean; 
+/**
+ * エラーが起きている（原因は ErrorStatus）
+ */
+error: boolean }
+/**
+ * 用紙の長さ
+ */
+export type PaperLength = { 
+/**
+ * 用紙を送った長さ（mm）
+ */
+length_mm: number; 
+/**
+ * 改行の数
+ */
+lines: number }
+/**
+ * 監視設定
+ */
+export type PaperMonitorConfig = { 
+/**
+ * 監視するプリンター（未設定なら監視しない）
+ */
+printer: UsbPrinter | null; 
+/**
+ * 確認間隔（秒）
+ */
+interval_secs: number; 
+/**
+ * ニアエンドを検知したときに警告伝票を印刷する
+ */
+print_warning_strip: boolean }
+/**
+ * 用紙センサーの状態（DLE EOT 4 の応答）
+ */
+export type PaperStatus = { 
+/**
+ * 用紙残りわずか（ニアエンド）
+ */
+near_end: boolean; 
+/**
+ * 用紙切れ
+ */
+out: boolean }
+/**
+ * 保留中の取引
+ */
+export type ParkedSale = { 
+/**
+ * 呼び出し番号（P0001形式）
+ */
+code: string; items: ReceiptItem[]; total: number; staff_id: string; 
+/**
+ * メモ（客の特徴など）
+ */
+memo: string | null; 
+/**
+ * 保留日時（UNIX秒）
+ */
+parked_at: number }
+/**
+ * 支払情報
+ */
+export type PaymentInfo = { 
+/**
+ * 支払手段名（現金、クレジットカードなど）
+ */
+method: string; 
+/**
+ * 支払金額
+ */
+amount: number }
+/**
+ * 支払方法別の集計
+ */
+export type PaymentMethodTotal = { 
+/**
+ * 支払手段名
+ */
+method: string; 
+/**
+ * 売上件数（併用された売上はそれぞれの支払方法で数える）
+ */
+count: number; 
+/**
+ * 受取額（現金は釣り銭を除く）
+ */
+amount: number; 
+/**
+ * 返品・取消の件数と額（元の売上で最も多く支払われた方法に計上する）
+ */
+refund_count: number; refund_amount: number }
+/**
+ * 送信待ちのアップロード
+ */
+export type PendingUpload = { 
+/**
+ * キュー内ID (UUID)
+ */
+id: string; 
+/**
+ * 種別（closing_report など）
+ */
+kind: string; 
+/**
+ * 元データのID（閉局レポートIDなど）
+ */
+reference_id: string; 
+/**
+ * 送信するJSON文字列（署名対象）
+ */
+payload: string; 
+/**
+ * 署名データ
+ */
+signature: SignatureData; 
+/**
+ * 送信試行回数
+ */
+attempts: number; 
+/**
+ * 最後の送信エラー
+ */
+last_error: string | null; 
+/**
+ * キュー投入日時（UNIX秒）
+ */
+created_at: number }
+/**
+ * 固定したサーバー公開鍵
+ */
+export type PinnedServerKey = { 
+/**
+ * Base64エンコードされた公開鍵
+ */
+public_key: string; 
+/**
+ * 固定した日時（UNIX秒）
+ */
+pinned_at: number }
+/**
+ * ポイントの設定
+ */
+export type PointsConfig = { enabled: boolean; 
+/**
+ * 1ポイントあたりの金額（円）
+ */
+yen_per_point: number; 
+/**
+ * 対象外の商品（JAN）
+ */
+excluded_jans: string[]; 
+/**
+ * 対象外の商品区分
+ */
+excluded_categories: string[]; 
+/**
+ * 対象外の支払手段（商品券・ギフト券など。この支払分はポイントの対象から除く）
+ */
+excluded_payment_methods: string[]; 
+/**
+ * 有効期限（日数、0の場合は無期限）
+ */
+expiry_days: number }
+/**
+ * 価格表
+ */
+export type PriceBook = { 
+/**
+ * 版（反映した価格改定の発行日時、未反映は0）
+ */
+version: number; 
+/**
+ * 反映日時（UNIX秒）
+ */
+applied_at: number | null; 
+/**
+ * 商品IDごとの価格
+ */
+prices: Partial<{ [key in string]: number }>; 
+/**
+ * 1つ前の版（改定の前に始めた会計の照合用）
+ */
+previous_version?: number; 
+/**
+ * 1つ前の版の商品IDごとの価格
+ */
+previous_prices?: Partial<{ [key in string]: number }> }
+/**
+ * 価格改定の反映結果
+ */
+export type PriceBookUpdate = { book: PriceBook; changes: PriceChange[] }
+/**
+ * 価格改定で変わった商品1件
+ */
+export type PriceChange = { product_id: string; 
+/**
+ * 改定前の価格（価格表になかった商品はNone）
+ */
+old_price: number | null; new_price: number }
+/**
+ * 明細の価格変更の印（承認時のジャーナルの記録と対応する）
+ */
+export type PriceOverride = { 
+/**
+ * 承認を記録したジャーナルのID
+ */
+override_id: string; 
+/**
+ * 変更前の値段（単価 x 数量）
+ */
+original_price: number; reason_code: string; 
+/**
+ * 理由の表示名
+ */
+reason: string; 
+/**
+ * 承認した責任者
+ */
+supervisor_id: string }
+/**
+ * 価格変更の理由
+ */
+export type PriceOverrideReason = { code: string; 
+/**
+ * レシートに印字する表示名
+ */
+label: string }
+/**
+ * 価格変更の依頼（フロントエンドから渡される）
+ */
+export type PriceOverrideRequest = { jan: string; quantity: number; 
+/**
+ * 変更前の値段（単価 x 数量）
+ */
+original_price: number; 
+/**
+ * 変更後の値段（単価 x 数量）
+ */
+price: number; reason_code: string }
+/**
+ * 印刷の優先度（値が小さいほど先に印刷する）
+ */
+export type PrintPriority = 
+/**
+ * お客様のレシート
+ */
+"receipt" | 
+/**
+ * 呼び出し票・お預かり票
+ */
+"ticket" | 
+/**
+ * 閉局レポートなどの帳票
+ */
+"report"
+/**
+ * 印刷中のジョブと待ち行列（保存する内容と同じ）
+ */
+export type PrintQueue = { 
+/**
+ * 印刷中のジョブ（終わったプリンターの結果を含む）
+ */
+printing: SpoolJob | null; 
+/**
+ * 印刷待ちのジョブ（優先度順）
+ */
+pending: SpoolJob[] }
+/**
+ * 印字文言
+ */
+export type PrintStrings = { receipt: ReceiptStrings; credit_slip: CreditSlipStrings; refund_slip: RefundSlipStrings; closing_report: ClosingReportStrings }
+/**
+ * プリンターの機能
+ * 
+ * 未指定時は一般的なESC/POSレシートプリンターの機能とする
+ */
+export type PrinterCapabilities = { 
+/**
+ * QRコードを印刷できる
+ */
+qr_code: boolean; 
+/**
+ * 印刷できるバーコード
+ */
+barcode_types: BarcodeType[]; 
+/**
+ * オートカッターがある
+ */
+cutter: boolean; 
+/**
+ * パーシャルカットができる
+ */
+partial_cut: boolean; 
+/**
+ * キャッシュドロワーを接続できる
+ */
+cash_drawer: boolean; 
+/**
+ * 対応する最大の用紙幅（mm）
+ */
+max_paper_width: number; 
+/**
+ * 画像（ロゴなど）を印刷できる
+ */
+raster: boolean }
+/**
+ * プロファイルと自動判別を反映したプリンターの機能
+ * 
+ * フロントエンドは対応していない操作（ロゴ登録など）を隠すために使う
+ */
+export type PrinterFeatures = ({ 
+/**
+ * QRコードを印刷できる
+ */
+qr_code: boolean; 
+/**
+ * 印刷できるバーコード
+ */
+barcode_types: BarcodeType[]; 
+/**
+ * オートカッターがある
+ */
+cutter: boolean; 
+/**
+ * パーシャルカットができる
+ */
+partial_cut: boolean; 
+/**
+ * キャッシュドロワーを接続できる
+ */
+cash_drawer: boolean; 
+/**
+ * 対応する最大の用紙幅（mm）
+ */
+max_paper_width: number; 
+/**
+ * 画像（ロゴなど）を印刷できる
+ */
+raster: boolean }) & { printer_id: string; 
+/**
+ * プロファイルの表示名（プロファイル未登録時はNone）
+ */
+name: string | null; render_mode: RenderMode; qr_command_set: QrCommandSet; media_mode: MediaMode }
+/**
+ * キープアライブ設定
+ */
+export type PrinterKeepaliveConfig = { 
+/**
+ * 問い合わせるプリンター（空の場合は行わない）
+ */
+printers: UsbPrinter[]; 
+/**
+ * 問い合わせ間隔（秒）
+ */
+interval_secs: number }
+/**
+ * プリンタープロファイル
+ */
+export type PrinterProfile = { 
+/**
+ * プリンターの識別子（USBは `usb:{VID}:{PID}`）
+ */
+id: string; 
+/**
+ * 表示名
+ */
+name: string; render_mode?: RenderMode; 
+/**
+ * ラスターモードで使うフォントファイル（未指定時は同梱フォント）
+ */
+raster_font_path?: string | null; 
+/**
+ * QRコードのコマンド体系（未指定時はベンダーIDから自動判別）
+ */
+qr_command_set?: QrCommandSet | null; 
+/**
+ * 用紙の種類（ラベル紙と兼用するプリンター向け）
+ */
+media_mode?: MediaMode; 
+/**
+ * 機能（未指定時は既定の機能）
+ */
+capabilities?: PrinterCapabilities | null }
+/**
+ * プリンターの状態
+ */
+export type PrinterStatus = { connected: boolean; 
+/**
+ * 用紙センサーの状態（応答がない場合はNone）
+ */
+paper: PaperStatus | null; 
+/**
+ * オフラインの状態と原因（応答がない場合はNone）
+ */
+offline: OfflineStatus | null; 
+/**
+ * エラーの原因（応答がない場合はNone）
+ */
+error: ErrorStatus | null; 
+/**
+ * 電池残量（%、モバイルプリンターで取得できる場合のみ）
+ */
+battery_level: number | null; 
+/**
+ * 電波強度（dBm、Bluetooth接続で測定できた場合のみ）
+ */
+rssi: number | null }
+/**
+ * 印刷先
+ */
+export type PrinterTarget = 
+/**
+ * ベンダーID・デバイスIDで指定するプリンター（USB、設定済みのネットワーク・シリアルプリンターは仮想のベンダーID）
+ */
+{ transport: "device"; vendor_id: number; device_id: number } | 
+/**
+ * 設定していないネットワークプリンター（RAW）
+ */
+{ transport: "network"; host: string; port: number } | 
+/**
+ * 設定していないシリアルプリンター
+ */
+{ transport: "serial"; port: string; baud_rate: number; flow_control: SerialFlowControl } | 
+/**
+ * ペアリング済みの Bluetooth（SPP）プリンター（list_bluetooth_printers のポート）
+ */
+{ transport: "bluetooth"; port: string } | 
+/**
+ * ファイル（Linux のプリンターのデバイスファイル、またはファイル名だけを指定して印刷データをスプールディレクトリに保存）
+ * 
+ * 画面から任意のファイルに書き込めないよう、これ以外のパスは受け付けない
+ */
+{ transport: "file"; path: string }
+/**
+ * キャンペーン
+ */
+export type Promotion = (
+/**
+ * 対象商品を buy 点買うと、さらに free 点が無料（組の中で安いものから無料にする）
+ */
+{ type: "buy_get_free"; jans: string[]; buy: number; free: number } | 
+/**
+ * 対象商品を1点ずつそろえるとセット価格
+ */
+{ type: "set_price"; jans: string[]; price: number } | 
+/**
+ * 対象商品の単価を特価にする
+ */
+{ type: "price_override"; jans: string[]; price: number }) & { id: string; 
+/**
+ * レシートに印字する名前
+ */
+name: string; 
+/**
+ * 開始日時（ISO8601、未指定の場合は制限なし）
+ */
+starts_at?: string | null; 
+/**
+ * 終了日時（ISO8601、この日時を含まない）
+ */
+ends_at?: string | null }
+/**
+ * キャンペーンの割引額のうち、1つの明細に割り当てた分
+ */
+export type PromotionLineDiscount = { 
+/**
+ * 明細の番号（items の並び順、0始まり）
+ */
+item_index: number; discount: number }
+/**
+ * 会計の見積もり（フロントエンドの会計画面用）
+ */
+export type PromotionQuote = { promotions: AppliedPromotion[]; 
+/**
+ * 割引前の小計
+ */
+subtotal: number; 
+/**
+ * 割引額の合計
+ */
+discount: number; 
+/**
+ * 割引後の合計
+ */
+total: number }
+/**
+ * 仮登録した商品
+ */
+export type ProvisionalProduct = { 
+/**
+ * 商品ID（provisional-{JAN}）
+ */
+id: string; 
+/**
+ * スキャンしたコード（ISBNは13桁に揃える）
+ */
+jan: string; is_book: boolean; name: string; circle_name: string | null; 
+/**
+ * 入力した値段（税込）
+ */
+price: number; 
+/**
+ * 登録したスタッフ番号
+ */
+created_by: string; 
+/**
+ * 登録日時（UNIX秒）
+ */
+created_at: number; 
+/**
+ * 確認済みか（未確認のものは要確認として一覧に出す）
+ */
+reviewed: boolean }
+/**
+ * QRコード印刷コマンド（GS ( k）の機能コード体系
+ */
+export type QrCommandSet = 
+/**
+ * EPSON標準（fn = 65〜81 / 0x41〜0x51）
+ */
+"epson" | 
+/**
+ * シチズン独自（fn = 0xA5〜0xB5、標準の機能コード + 0x64）
+ */
+"citizen"
+/**
+ * 見積
+ */
+export type Quote = { 
+/**
+ * 見積番号（Q0001形式）
+ */
+code: string; items: ReceiptItem[]; total: number; 
+/**
+ * 有効期限（表示用文字列）
+ */
+valid_until: string; staff_id: string; 
+/**
+ * 宛名
+ */
+customer_name: string | null; 
+/**
+ * 作成日時（UNIX秒）
+ */
+created_at: number; 
+/**
+ * 売上に変換済みの場合のレシート番号
+ */
+converted_receipt_number: string | null }
+/**
+ * レシートデータ
+ */
+export type ReceiptData = { 
+/**
+ * イベント名称
+ */
+event_name: string; 
+/**
+ * サークル名（トップに大きく表示）
+ */
+circle_name?: string | null; 
+/**
+ * サークル名を縦書きで表示する（領収書風の装飾）
+ */
+circle_name_vertical?: boolean; 
+/**
+ * ロゴ画像（レシートの先頭に印刷）
+ */
+logo?: ReceiptLogo | null; 
+/**
+ * 会場住所
+ */
+venue_address?: string | null; 
+/**
+ * 発売日時
+ */
+sale_start_date_time?: string | null; 
+/**
+ * スタッフ番号
+ */
+staff_id: string; 
+/**
+ * 宛名（様の前に表示。会員の売上では会員名）
+ */
+customer_name?: string | null; 
+/**
+ * 会員番号
+ */
+member_id?: string | null; 
+/**
+ * 会員の獲得ポイントと残高（印刷時に付与して入れる）
+ */
+points?: ReceiptPoints | null; 
+/**
+ * 商品明細リスト
+ */
+items: ReceiptItem[]; 
+/**
+ * 適用したキャンペーン（合計は割引後の金額）
+ */
+promotions?: AppliedPromotion[]; 
+/**
+ * 合計金額
+ */
+total: number; 
+/**
+ * 支払情報リスト
+ */
+payments: PaymentInfo[]; 
+/**
+ * 消費税率（%）
+ */
+tax_rate: number; 
+/**
+ * 消費税金額
+ */
+tax_amount: number; 
+/**
+ * レシート番号
+ */
+receipt_number: string; 
+/**
+ * カード詳細情報（クレジット決済時、読み込み時に保存してよい範囲に絞る）
+ */
+card_details?: CardDetails | null; 
+/**
+ * Stripe PaymentIntent ID（クレジット決済時）
+ */
+payment_intent_id?: string | null; 
+/**
+ * 釣り銭の金種内訳（高額の釣り銭時に印字）
+ */
+change_breakdown?: DenominationCount[] | null; 
+/**
+ * 年齢確認の記録（成人向けの商品を含む場合に必須）
+ */
+age_verification?: AgeVerification | null; 
+/**
+ * 内金・残金（予約販売の売上のみ。total はこの取引で受け取る内金または残金）
+ */
+deposit?: DepositTerms | null; 
+/**
+ * 端数調整額（キャンペーンの割引後の金額を丸めた差額、値引はマイナス。total は調整後の金額）
+ */
+rounding_adjustment?: number; 
+/**
+ * 会計時の価格表の版（価格改定の発行日時。未指定の場合は売上の確定時の版を入れる）
+ */
+price_version?: number | null }
+/**
+ * レシート上部に印刷する店舗情報
+ * 
+ * 未設定の項目はレシートデータの値（サークル名・会場住所）を使う
+ */
+export type ReceiptHeader = { 
+/**
+ * 店舗名（サークル名）
+ */
+shop_name: string | null; 
+/**
+ * 住所
+ */
+address: string | null; 
+/**
+ * 電話番号
+ */
+phone: string | null; 
+/**
+ * 適格請求書発行事業者の登録番号
+ */
+registration_number: string | null; 
+/**
+ * スペース番号（ブース番号）
+ */
+booth_number: string | null }
+/**
+ * 商品明細
+ */
+export type ReceiptItem = { 
+/**
+ * 商品ID（端末の価格表の価格と照合する）
+ */
+product_id?: string | null; 
+/**
+ * 出版サークル名
+ */
+circle_name: string; 
+/**
+ * サークル名の読み（受け渡し時の呼び出し用）
+ */
+circle_name_ruby?: string | null; 
+/**
+ * 商品名
+ */
+name: string; 
+/**
+ * JAN
+ */
+jan: string; 
+/**
+ * ISBN
+ */
+isbn: string; 
+/**
+ * ISDN（書籍の場合）
+ */
+isdn?: string | null; 
+/**
+ * 2段目バーコード（Cコード＋値段、書籍の場合）
+ */
+jan2?: string | null; 
+/**
+ * 書籍フラグ
+ */
+is_book: boolean; 
+/**
+ * 商品数
+ */
+quantity: number; 
+/**
+ * 値段（単価 x 数量）
+ */
+price: number; 
+/**
+ * 明細のメモ（特典付き、サイン本など）
+ */
+note?: string | null; 
+/**
+ * 商品カテゴリー（未指定時は書籍フラグから判定）
+ */
+category?: string | null; 
+/**
+ * 責任者の承認による価格変更
+ */
+price_override?: PriceOverride | null; 
+/**
+ * 成人向け（R18）の商品
+ */
+age_restricted?: boolean; 
+/**
+ * 明細の下に JAN のバーコードを印刷する（受け渡し・返品時の読み取り用）
+ */
+print_barcode?: boolean }
+/**
+ * レシートに印刷するロゴ画像（PNG）
+ */
+export type ReceiptLogo = { source: ImageSource; 
+/**
+ * 印刷する幅の上限（ドット、未指定の場合は用紙幅。これより大きい画像は縮小する）
+ */
+max_width?: number | null; 
+/**
+ * 白黒への変換方法
+ */
+mode?: ImageMode }
+/**
+ * レシートに印字するポイント
+ */
+export type ReceiptPoints = { 
+/**
+ * 今回の獲得ポイント
+ */
+earned: number; 
+/**
+ * 今回の分を含む残高
+ */
+balance: number }
+/**
+ * レシートの追加セクション
+ * 
+ * 本文とQRコードの `{receipt_number}` `{event_name}` はレシートの値に置き換える
+ */
+export type ReceiptSection = { slot: ReceiptSlot; 
+/**
+ * 見出し（太字・中央揃え）
+ */
+title?: string | null; 
+/**
+ * 本文の各行
+ */
+lines?: string[]; 
+/**
+ * QRコードにする内容（アンケートのURLなど）
+ */
+qr?: string | null; 
+/**
+ * 一時的に載せない場合は false
+ */
+enabled?: boolean }
+/**
+ * セクションを差し込む位置
+ */
+export type ReceiptSlot = 
+/**
+ * 店舗情報の下（ご明細書の前）
+ */
+"after_header" | 
+/**
+ * 商品明細の下（合計の前）
+ */
+"after_items" | 
+/**
+ * 支払情報の下（免税事業者の説明文の前）
+ */
+"after_totals" | 
+/**
+ * レシート番号のQRコードの下（レシートの末尾）
+ */
+"footer"
+/**
+ * レシートの文言
+ */
+export type ReceiptStrings = { title: string; 
+/**
+ * 再発行の表示（{copy}: 何回目か）
+ */
+reprint: string; 
+/**
+ * 再発行時、元の取引が取消済みの場合の表示
+ */
+reprint_voided: string; 
+/**
+ * 再発行時、元の取引が全明細返品済みの場合の表示
+ */
+reprint_refunded: string; 
+/**
+ * 再発行時、元の取引が一部返品済みの場合の表示
+ */
+reprint_partially_refunded: string; 
+/**
+ * 再発行のレシートの末尾の表示（写しであることを明記する）
+ */
+reprint_notice: string; 
+/**
+ * 宛名の表示（{name}: 宛名）
+ */
+customer: string; 
+/**
+ * 責任者の表示（{staff}: スタッフ番号）
+ */
+staff: string; subtotal: string; total: string; 
+/**
+ * 内税の表示（{rate}: 税率）
+ */
+tax: string; change: string; points_earned: string; points_balance: string; 
+/**
+ * ポイント数の表示（{points}: ポイント数）
+ */
+points: string; 
+/**
+ * 価格変更した明細の表示（{reason}: 理由、{price}: 変更前の値段）
+ */
+price_override: string; 
+/**
+ * 端数を値引した行
+ */
+rounding_discount: string; 
+/**
+ * 端数を切り上げた行
+ */
+rounding_surcharge: string; 
+/**
+ * 内金を受け取った売上の表示
+ */
+deposit_banner: string; 
+/**
+ * 残金を受け取った売上（完了レシート）の表示
+ */
+balance_completed: string; order_total: string; deposit: string; 
+/**
+ * 残金の売上に載せる受取済みの内金（{receipt_number}: 内金の売上のレシート番号）
+ */
+deposit_paid: string; balance: string; balance_paid: string; 
+/**
+ * 残金のお支払期日（{date}: 期日）
+ */
+balance_due: string; 
+/**
+ * 免税事業者・返品についての説明文
+ */
+disclaimer: string; 
+/**
+ * ギフトレシート（金額非表示）の見出し
+ */
+gift_title: string; 
+/**
+ * ギフトレシートの点数の行（{count}: 点数）
+ */
+gift_quantity: string; gift_total_quantity: string; 
+/**
+ * ギフトレシートの案内
+ */
+gift_notice: string; 
+/**
+ * レシート・オン・デマンドの控えの案内（1行ずつ）
+ */
+stub_notice: string[]; 
+/**
+ * レシートの印刷に失敗した売上の控えの見出し
+ */
+fallback_title: string; 
+/**
+ * レシートの印刷に失敗した売上の控えの案内（1行ずつ）
+ */
+fallback_notice: string[]; 
+/**
+ * 本番以外の接続先環境で印刷したものの表示（{environment}: 環境名）
+ */
+test_environment: string }
+/**
+ * 返品明細
+ */
+export type RefundItem = { 
+/**
+ * 元のレシート内の明細位置
+ */
+line_index: number; jan: string; name: string; quantity: number; amount: number }
+/**
+ * 返品対象の指定（フロントエンドから渡される）
+ */
+export type RefundLine = { line_index: number; quantity: number }
+/**
+ * 返品伝票の文言
+ */
+export type RefundSlipStrings = { title: string; 
+/**
+ * 返品番号の表示（{id}: 返品記録のIDの先頭8文字）
+ */
+refund_number: string; 
+/**
+ * 元の売上の表示（{receipt_number}: 元のレシート番号）
+ */
+original_receipt: string; total: string; 
+/**
+ * 内税の表示（{rate}: 税率）
+ */
+tax: string; 
+/**
+ * 返品理由の表示（{reason}: 理由）
+ */
+reason: string; 
+/**
+ * 返金の受領の署名欄
+ */
+signature_heading: string }
+/**
+ * QRコード用のペイロード
+ */
+export type RegistrationQrPayload = { 
+/**
+ * バージョン
+ */
+v: number; 
+/**
+ * 端末ID (UUID)
+ */
+terminal_id: string; 
+/**
+ * Base64エンコードされた公開鍵
+ */
+public_key: string; 
+/**
+ * 端末名
+ */
+device_name: string; 
+/**
+ * OS種別
+ */
+os: string; 
+/**
+ * 生成日時 (ISO8601)
+ */
+created_at: string; 
+/**
+ * 割り当てる組織ID（複数レジで登録する場合）
+ */
+organization_id?: string | null; 
+/**
+ * レジの表示名（レジ1、レジ2など）
+ */
+seat_label?: string | null }
+/**
+ * リマインダー設定
+ */
+export type ReminderConfig = { 
+/**
+ * イベント終了時刻（UNIX秒）
+ */
+event_end_at: number; 
+/**
+ * 伝票を印刷する場合の印刷先
+ */
+printer: ReminderPrinter | null; 
+/**
+ * 通知済みか（1回のみ通知する）
+ */
+fired?: boolean }
+/**
+ * リマインダー伝票の印刷先
+ */
+export type ReminderPrinter = { vendor_id: number; device_id: number; paper_width: number | null }
+/**
+ * 実行結果
+ */
+export type RemoteCommandResult = { id: string; command: string; executed_at: number; success: boolean; 
+/**
+ * 結果の説明（印刷ジョブID、アップロードIDなど）またはエラー
+ */
+detail: string | null }
+/**
+ * 印刷方式
+ */
+export type RenderMode = 
+/**
+ * 漢字モードでテキストを送信（高速）
+ */
+"text" | 
+/**
+ * レシート全体を画像として送信（低速だが機種差がない）
+ */
+"raster"
+/**
+ * レシートを再発行として印刷する指定
+ */
+export type ReprintRequest = { 
+/**
+ * 再発行を操作するスタッフ番号（ジャーナルの再発行記録に残す）
+ */
+staff_id: string; 
+/**
+ * 再発行の上限を超えるときの責任者承認
+ */
+approval: SupervisorApproval | null }
+/**
+ * 予約
+ */
+export type Reservation = { 
+/**
+ * 予約番号（端末間で重複しないようUUIDから生成）
+ */
+code: string; items: ReceiptItem[]; total: number; 
+/**
+ * 予約者名
+ */
+customer_name: string | null; 
+/**
+ * 受け取りに関するメモ（2日目受け取りなど）
+ */
+pickup_note: string | null; 
+/**
+ * 元の見積番号
+ */
+quote_code: string | null; status: ReservationStatus; 
+/**
+ * 登録したスタッフ番号
+ */
+staff_id: string; 
+/**
+ * 受け渡したスタッフ番号
+ */
+redeemed_by: string | null; 
+/**
+ * 受け渡し日時（UNIX秒）
+ */
+redeemed_at: number | null; 
+/**
+ * 登録日時（UNIX秒）
+ */
+created_at: number; 
+/**
+ * 最終更新日時（UNIX秒、同期時のマージに使用）
+ */
+updated_at: number }
+/**
+ * 予約の状態
+ */
+export type ReservationStatus = 
+/**
+ * 受け取り待ち
+ */
+"reserved" | 
+/**
+ * 受け渡し済み
+ */
+"redeemed" | 
+/**
+ * キャンセル
+ */
+"cancelled"
+/**
+ * 端数調整の設定
+ */
+export type RoundingConfig = { enabled: boolean; 
+/**
+ * 丸める単位（円）
+ */
+unit: number; mode: RoundingMode; 
+/**
+ * 現金だけで支払う会計のみ丸める（カード・電子マネーを含む会計は丸めない）
+ */
+cash_only: boolean }
+/**
+ * 丸め方
+ */
+export type RoundingMode = 
+/**
+ * 切り捨て（端数値引）
+ */
+"down" | 
+/**
+ * 四捨五入
+ */
+"nearest" | 
+/**
+ * 切り上げ
+ */
+"up"
+/**
+ * 端数調整の見積もり（フロントエンドの会計画面用）
+ */
+export type RoundingQuote = { 
+/**
+ * 丸める前の合計
+ */
+before: number; 
+/**
+ * 端数調整額（値引はマイナス）
+ */
+adjustment: number; 
+/**
+ * 丸めた後の合計
+ */
+total: number }
+/**
+ * 売上の経路
+ */
+export type SaleChannel = 
+/**
+ * 有人レジ
+ */
+"counter" | 
+/**
+ * セルフレジ（キオスク）
+ */
+"kiosk"
+/**
+ * 売上の上限設定
+ */
+export type SaleLimitsConfig = { 
+/**
+ * 1明細の数量の上限
+ */
+max_line_quantity: number; 
+/**
+ * 1会計の合計金額の上限
+ */
+max_total: number; 
+/**
+ * 釣り銭の上限
+ */
+max_change: number }
+/**
+ * 目標に対する進捗
+ */
+export type SalesProgress = { target: SalesTarget | null; 
+/**
+ * 純売上（売上 - 返品 - 取消）
+ */
+net_amount: number; sale_count: number; 
+/**
+ * 目標に対する達成率（%、目標がない場合は None）
+ */
+achieved_percent: number | null; 
+/**
+ * 目標までの残り（達成済みの場合は 0）
+ */
+remaining_amount: number | null; 
+/**
+ * 損益分岐点を超えたか（損益分岐点がない場合は None）
+ */
+break_even_reached: boolean | null }
+/**
+ * 売上目標
+ */
+export type SalesTarget = { 
+/**
+ * 目標の純売上（税込）
+ */
+target_amount: number; 
+/**
+ * 損益分岐点の純売上（頒布物の製作費など）
+ */
+break_even_amount: number | null; 
+/**
+ * 設定したスタッフ番号
+ */
+updated_by: string | null; 
+/**
+ * 設定日時（UNIX秒）
+ */
+updated_at: number }
+/**
+ * 端末に割り当てた組織とレジ
+ */
+export type SeatBinding = { organization_id: string; seat_label: string; 
+/**
+ * 割り当てた日時（UNIX秒）
+ */
+bound_at: number }
+/**
+ * 登録時に指定する組織とレジ（フロントエンドから渡される）
+ */
+export type SeatRequest = { organization_id: string; seat_label: string }
+/**
+ * フロー制御
+ */
+export type SerialFlowControl = 
+/**
+ * なし
+ */
+"none" | 
+/**
+ * RTS/CTS（多くのレシートプリンターの既定）
+ */
+"hardware" | 
+/**
+ * XON/XOFF
+ */
+"software"
+/**
+ * 端末で見つかったシリアルポート
+ */
+export type SerialPortInfo = { 
+/**
+ * ポート（設定の port に指定する値）
+ */
+port: string; 
+/**
+ * USB-シリアル変換の製品名など（分かる場合のみ）
+ */
+description: string | null }
+/**
+ * シリアルプリンターの設定
+ */
+export type SerialPrinter = { 
+/**
+ * 一覧に出す名前
+ */
+name: string; 
+/**
+ * ポート（COM3、/dev/ttyUSB0 など）
+ */
+port: string; 
+/**
+ * 通信速度（プリンター本体のディップスイッチの設定に合わせる）
+ */
+baud_rate?: number; flow_control?: SerialFlowControl }
+/**
+ * 営業セッションの状態
+ */
+export type SessionState = { 
+/**
+ * 開局中か
+ */
+open: boolean; 
+/**
+ * 開局したスタッフ番号
+ */
+staff_id: string | null; 
+/**
+ * 開局日時（UNIX秒）
+ */
+opened_at: number | null; 
+/**
+ * 閉局日時（UNIX秒）
+ */
+closed_at: number | null; 
+/**
+ * 担当者の交代履歴（開局したスタッフから順に）
+ */
+shifts?: StaffShift[] }
+/**
+ * 署名リクエスト用のデータ
+ * 
+ * 組織・レジを割り当てた端末では、組織とレジも署名対象に含める（signing_message を参照）
+ */
+export type SignatureData = { terminal_id: string; timestamp: number; signature: string; organization_id?: string | null; seat_label?: string | null }
+/**
+ * QRコードから読み取った閉局レポート
+ * 
+ * 署名は集計のJSON文字列（`body`）に対するもので、発行端末の公開鍵で verify により検証する
+ */
+export type SignedClosingReport = { body: string; signature: SignatureData }
+/**
+ * 署名済みのハートビート
+ */
+export type SignedHeartbeat = { id: string; 
+/**
+ * 送信するJSON文字列（署名対象）
+ */
+payload: string; signature: SignatureData }
+/**
+ * サーバーから届く署名付きデータ
+ */
+export type SignedPayload = { 
+/**
+ * JSON文字列（署名対象）
+ */
+payload: string; 
+/**
+ * サーバー鍵による署名（Base64）
+ */
+signature: string }
+/**
+ * QRコードから読み取ったレシート
+ * 
+ * 署名はレシート内容のJSON文字列（`body`）に対するもので、発行端末の公開鍵で verify により検証する
+ */
+export type SignedReceipt = { body: string; signature: SignatureData }
+/**
+ * 印刷する内容
+ */
+export type SpoolDocument = 
+/**
+ * レシート
+ */
+{ kind: "receipt"; receipt: ReceiptData } | 
+/**
+ * 保留取引のお預かり票
+ */
+{ kind: "parked_slip"; sale: ParkedSale } | 
+/**
+ * 閉局レポート（受付時点のジャーナル集計で印刷する）
+ */
+{ kind: "closing_report"; report: ClosingReportData; totals: JournalTotals } | 
+/**
+ * レシートの印刷に失敗した売上の控え
+ */
+{ kind: "fallback_slip"; receipt_number: string; event_name: string; total: number }
+/**
+ * 印刷ジョブ
+ */
+export type SpoolJob = { id: string; priority: PrintPriority; document: SpoolDocument; 
+/**
+ * 印刷先（先頭がメイン、2台目以降はミラー）
+ */
+targets: UsbPrinter[]; 
+/**
+ * 印刷が終わったプリンターの結果（中断されたジョブは途中から再開する）
+ */
+results: TargetResult[] }
+/**
+ * 担当者の交代
+ */
+export type StaffShift = { staff_id: string; 
+/**
+ * 表示名（ログイン時に分かる場合のみ）
+ */
+staff_name: string | null; 
+/**
+ * 担当を始めた日時（UNIX秒）
+ */
+started_at: number }
+/**
+ * 担当者別の集計
+ */
+export type StaffTotal = { staff_id: string; staff_name?: string | null; sale_count: number; sale_amount: number; 
+/**
+ * 返品の件数と額
+ */
+refund_count: number; refund_amount: number; 
+/**
+ * 取消の件数と額
+ */
+void_count?: number; void_amount?: number; 
+/**
+ * ドロワー在高の設定（釣銭の補充・回収）の回数
+ */
+drawer_count: number }
+/**
+ * 責任者承認（フロントエンドから渡される）
+ */
+export type SupervisorApproval = { supervisor_id: string; pin: string }
+/**
+ * 責任者セッション
+ */
+export type SupervisorSession = { supervisor_id: string; 
+/**
+ * 切れる日時（UNIX秒）
+ */
+expires_at: number }
+/**
+ * 通貨記号の位置
+ */
+export type SymbolPosition = 
+/**
+ * 金額の前（例: ￥1,000）
+ */
+"prefix" | 
+/**
+ * 金額の後（例: 1,000円）
+ */
+"suffix"
+/**
+ * プリンターごとの結果
+ */
+export type TargetResult = { printer: UsbPrinter; success: boolean; 
+/**
+ * 試行回数
+ */
+attempts: number; 
+/**
+ * 最後のエラー（成功時はNone）
+ */
+error: string | null }
+/**
+ * 支払の検証結果
+ */
+export type TenderBreakdown = { 
+/**
+ * 受け取った額の合計
+ */
+paid: number; 
+/**
+ * おつりの合計
+ */
+change: number; 
+/**
+ * 切り捨てた超過分の合計
+ */
+forfeited: number; 
+/**
+ * 支払ごとの充当結果（支払情報の順）
+ */
+tenders: AppliedTender[] }
+/**
+ * エラー型
+ * 
+ * コマンドのエラーとしてフロントエンドに渡す（`{ kind, message }` の形）
+ */
+export type TerminalAuthError = { kind: "keychain_error"; message: string } | { kind: "crypto_error"; message: string } | { kind: "not_initialized" } | { kind: "invalid_key" } | 
+/**
+ * 回数制限によるロックアウト中
+ */
+{ kind: "rate_limited"; message: string }
+/**
+ * 端末認証の結果
+ */
+export type TerminalAuthResult = { status: string; terminal_id: string | null; public_key: string | null; error: string | null; 
+/**
+ * 署名の回数・日時と異常（初期化済みの場合のみ）
+ */
+key_usage?: KeyUsageSummary | null }
+/**
+ * 端末設定
+ */
+export type TerminalConfig = { 
+/**
+ * 責任者承認なしで再発行できる回数
+ */
+reprint_limit: number; 
+/**
+ * レシート明細の並び順
+ */
+item_order: ItemOrder; 
+/**
+ * フッターのQRコードにレシート内容（署名付き・圧縮）を埋め込む
+ */
+receipt_qr_payload: boolean; 
+/**
+ * クレジット売上票をお客様控・加盟店控の2枚に分けて印刷する
+ */
+credit_slip_copies: boolean; 
+/**
+ * カード情報（下4桁・有効期限）をジャーナルに残す日数
+ */
+card_retention_days: number; 
+/**
+ * レシート上部の店舗情報
+ */
+receipt_header: ReceiptHeader; 
+/**
+ * レシートに差し込む追加セクション（アンケートQR・次回イベントの告知・SNSなど）
+ */
+receipt_sections: ReceiptSection[]; 
+/**
+ * 接続テストページ
+ */
+welcome_page: WelcomePage; 
+/**
+ * 印字する日時のタイムゾーン（IANA名）
+ */
+timezone: string; 
+/**
+ * 金額・数値の書式
+ */
+number_format: NumberFormat; 
+/**
+ * 印刷する商品名・サークル名の全角・半角の正規化
+ */
+text_normalization: TextNormalization; 
+/**
+ * セルフレジ（キオスク）
+ */
+kiosk: KioskConfig; 
+/**
+ * バックオフィスのジャーナル用プリンター（設定時はスプーラーのレシートをミラー印刷する）
+ */
+journal_printer: UsbPrinter | null; 
+/**
+ * 用紙ニアエンド監視
+ */
+paper_monitor: PaperMonitorConfig; 
+/**
+ * プリンターのキープアライブ
+ */
+printer_keepalive: PrinterKeepaliveConfig; 
+/**
+ * レシート・オン・デマンド（QRだけの控えを印刷し、必要な方にだけ本レシートを発行する）
+ */
+receipt_on_demand: boolean; 
+/**
+ * メールレシートの送信先リレー（未設定の場合はメール送信できない）
+ */
+email_relay_url: string | null; 
+/**
+ * シミュレーションモード（開発用。USBプリンターの代わりに印刷プレビューをイベントで通知する）
+ */
+simulation_mode: boolean; 
+/**
+ * ハートビート（端末状態の定期送信）
+ */
+heartbeat: HeartbeatConfig; 
+/**
+ * 開局時の会場ネットワーク確認
+ */
+venue_guard: VenueGuardConfig; 
+/**
+ * 受け付ける商品券の種別
+ */
+voucher_catalog: VoucherKind[]; 
+/**
+ * 会員のポイント
+ */
+points: PointsConfig; 
+/**
+ * キャンペーン
+ */
+promotions: Promotion[]; 
+/**
+ * 端数調整（合計の丸め）
+ */
+rounding: RoundingConfig; 
+/**
+ * 価格変更の理由コード
+ */
+price_override_reasons: PriceOverrideReason[]; 
+/**
+ * 成人向け（R18）として扱う商品のJAN（商品マスタの指定に加えて判定する）
+ */
+age_restricted_jans: string[]; 
+/**
+ * 売上の上限（超える場合は責任者の承認が必要）
+ */
+sale_limits: SaleLimitsConfig; 
+/**
+ * LAN接続のレシートプリンター
+ */
+network_printers: NetworkPrinter[]; 
+/**
+ * シリアル接続のレシートプリンター
+ */
+serial_printers: SerialPrinter[]; 
+/**
+ * 稼働状況の計測（オプトイン）
+ */
+metrics: MetricsConfig; 
+/**
+ * ISDNの登録情報の照会（オプトイン）
+ */
+isdn_registry: IsdnRegistryConfig }
+/**
+ * 端末のロック状態
+ */
+export type TerminalLock = { locked: boolean; reason: string | null; 
+/**
+ * ロックしたコマンドのID
+ */
+command_id: string | null; locked_at: number | null }
+/**
+ * サポート向けの端末確認の結果
+ */
+export type TerminalVerification = { terminal_id: string; 
+/**
+ * サポートが読み上げた公開鍵（サーバーの記録）とこの端末の鍵が一致したか
+ */
+key_matches: boolean; challenge: string; 
+/**
+ * チャレンジへの署名（サポート側で check_verification により検証する）
+ */
+signature: SignatureData; 
+/**
+ * この端末の公開鍵とチャレンジから作る確認コード（XXXX-XXXX）
+ */
+code: string }
+/**
+ * 商品名・サークル名の正規化の設定
+ */
+export type TextNormalization = { 
+/**
+ * NFKC で正規化する（全角英数・半角カナ・丸数字などの互換文字をそろえる）
+ */
+nfkc: boolean; 
+/**
+ * 数字の字幅
+ */
+digits: WidthForm; 
+/**
+ * 数字以外の英字・記号の字幅
+ */
+ascii: WidthForm }
+/**
+ * 使用状況の異常
+ */
+export type UsageAnomaly = 
+/**
+ * 1日の署名回数が多すぎる
+ */
+{ type: "high_daily_volume"; date: string; count: number } | 
+/**
+ * 深夜の署名回数が多すぎる
+ */
+{ type: "night_activity"; date: string; count: number }
+/**
+ * 印刷先のUSBプリンター
+ */
+export type UsbPrinter = { vendor_id: number; device_id: number; paper_width: number | null }
+/**
+ * 確認結果
+ */
+export type VenueCheck = { mode: VenueGuardMode; 
+/**
+ * 会場のネットワークに一致したか（確認しない場合はtrue）
+ */
+matched: boolean; network: NetworkInfo; 
+/**
+ * 一致しなかった理由
+ */
+reason: string | null }
+/**
+ * 会場ネットワーク確認の設定
+ */
+export type VenueGuardConfig = { mode: VenueGuardMode; 
+/**
+ * 会場のSSID
+ */
+allowed_ssids: string[]; 
+/**
+ * 会場のIPアドレス範囲（CIDR表記。例: 192.168.10.0/24）
+ */
+allowed_ip_ranges: string[] }
+/**
+ * 一致しなかったときの動作
+ */
+export type VenueGuardMode = 
+/**
+ * 確認しない
+ */
+"off" | 
+/**
+ * 開局はするが警告を通知する
+ */
+"warn" | 
+/**
+ * 責任者の承認がない限り開局しない
+ */
+"block"
+/**
+ * 商品券カウント
+ */
+export type VoucherCount = { 
+/**
+ * 表示名（種別コードがある場合はカタログの名前で置き換える）
+ */
+type: string; 
+/**
+ * 商品券カタログの種別コード
+ */
+code?: string | null; amount: number; memo?: string | null }
+/**
+ * 商品券の種別
+ */
+export type VoucherKind = { 
+/**
+ * 種別コード（支払情報の支払手段と同じ。例: voucher_department）
+ */
+code: string; 
+/**
+ * 表示名
+ */
+name: string; 
+/**
+ * 額面（空の場合は任意の金額）
+ */
+denominations?: number[]; 
+/**
+ * 額面が支払額を超えた場合におつりを出すか
+ */
+change_allowed?: boolean }
+/**
+ * 売上に使われた商品券の集計
+ */
+export type VoucherSales = { code: string; name: string; 
+/**
+ * 使われた売上の件数
+ */
+count: number; 
+/**
+ * 売上に充てた額
+ */
+amount: number }
+/**
+ * 支払に使う商品券（種別・額面ごと）
+ */
+export type VoucherTender = { code: string; 
+/**
+ * 額面（任意金額の種別では合計額）
+ */
+denomination: number; count: number }
+/**
+ * 商品券での支払の検証結果
+ */
+export type VoucherTenderResult = { 
+/**
+ * 額面の合計
+ */
+face_total: number; 
+/**
+ * 支払に充てる額
+ */
+applied: number; 
+/**
+ * おつり（おつりを出せる商品券の超過分）
+ */
+change: number; 
+/**
+ * おつりを出せない商品券の超過分（お客様に確認が必要）
+ */
+forfeited: number; 
+/**
+ * 商品券で足りない残額（現金などで支払う）
+ */
+remaining: number }
+/**
+ * 接続テスト（ウェルカム）ページの内容
+ */
+export type WelcomePage = { 
+/**
+ * ロゴ（白黒反転・2倍角で印刷する文字列）
+ */
+logo_text: string | null; 
+/**
+ * タイトル
+ */
+title: string; 
+/**
+ * 本文の各行
+ */
+lines: string[]; 
+/**
+ * ターミナルIDのQRコードを印刷する
+ */
+terminal_id_qr: boolean }
+/**
+ * 英字・数字の字幅の統一方法
+ */
+export type WidthForm = 
+/**
+ * 変換しない（NFKC の結果のまま。全角英数は半角になる）
+ */
+"keep" | 
+/**
+ * 半角にする
+ */
+"halfwidth" | 
+/**
+ * 全角にする
+ */
+"fullwidth"
+
+/** tauri-specta globals **/
+
+import {
+	invoke as TAURI_INVOKE,
+	Channel as TAURI_CHANNEL,
+} from "@tauri-apps/api/core";
+import * as TAURI_API_EVENT from "@tauri-apps/api/event";
+import { type WebviewWindow as __WebviewWindow__ } from "@tauri-apps/api/webviewWindow";
+
+type __EventObj__<T> = {
+	listen: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.listen<T>>;
+	once: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.once<T>>;
+	emit: null extends T
+		? (payload?: T) => ReturnType<typeof TAURI_API_EVENT.emit>
+		: (payload: T) => ReturnType<typeof TAURI_API_EVENT.emit>;
+};
+
+export type Result<T, E> =
+	| { status: "ok"; data: T }
+	| { status: "error"; error: E };
+
+function __makeEvents__<T extends Record<string, any>>(
+	mappings: Record<keyof T, string>,
+) {
+	return new Proxy(
+		{} as unknown as {
+			[K in keyof T]: __EventObj__<T[K]> & {
+				(handle: __WebviewWindow__): __EventObj__<T[K]>;
+			};
+		},
+		{
+			get: (_, event) => {
+				const name = mappings[event as keyof T];
+
+				return new Proxy((() => {}) as any, {
+					apply: (_, __, [window]: [__WebviewWindow__]) => ({
+						listen: (arg: any) => window.listen(name, arg),
+						once: (arg: any) => window.once(name, arg),
+						emit: (arg: any) => window.emit(name, arg),
+					}),
+					get: (_, command: keyof __EventObj__<any>) => {
+						switch (command) {
+							case "listen":
+								return (arg: any) => TAURI_API_EVENT.listen(name, arg);
+							case "once":
+								return (arg: any) => TAURI_API_EVENT.once(name, arg);
+							case "emit":
+								return (arg: any) => TAURI_API_EVENT.emit(name, arg);
+						}
+					},
+				});
+			},
+		},
+	);
+}
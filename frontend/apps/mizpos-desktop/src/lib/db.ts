@@ -7,6 +7,13 @@ import type {
   Transaction,
 } from "../types";
 import { type ApiProduct, fetchProducts } from "./api";
+import {
+  type CatalogImportError,
+  type CatalogMapping,
+  commands,
+  type PriceBook,
+} from "./commands.gen";
+import { withBackgroundWork } from "./background";
 
 /**
  * 販売サマリー（レポート用）
@@ -85,7 +92,7 @@ export const db = new MizPOSDatabase();
  * DynamoDBに保存されたバーコードをそのまま使用する
  */
 export async function syncProducts(): Promise<number> {
-  const apiProducts = await fetchProducts();
+  const apiProducts = await withBackgroundWork("sync", () => fetchProducts());
 
   const products: Product[] = apiProducts.map((p: ApiProduct) => ({
     id: p.product_id,
@@ -104,10 +111,104 @@ export async function syncProducts(): Promise<number> {
   // 全商品をクリアして再挿入
   await db.products.clear();
   await db.products.bulkPut(products);
+  // APIの価格より端末に反映済みの価格改定を優先する
+  await applyPriceBook(await commands.getPriceBook());
 
   return products.length;
 }
 
+/**
+ * 端末の価格表（署名付きの価格改定を反映したもの）の価格を商品キャッシュに反映
+ * 会計中のカゴの商品は追加したときの価格と版のまま変えない
+ */
+export async function applyPriceBook(book: PriceBook): Promise<number> {
+  return db.transaction("rw", db.products, async () => {
+    let updated = 0;
+    for (const [productId, price] of Object.entries(book.prices)) {
+      if (price === undefined) continue;
+      updated += await db.products.update(productId, {
+        price,
+        priceVersion: book.version,
+      });
+    }
+    return updated;
+  });
+}
+
+/**
+ * 商品一覧ファイルの既定の列の割り当て（サークルから受け取る表の見出し）
+ */
+export const DEFAULT_CATALOG_MAPPING: CatalogMapping = {
+  name: "商品名",
+  price: "価格",
+  jan: "JAN",
+  jan2: "JAN2",
+  isbn: "ISBN",
+  circle_name: "サークル名",
+  age_restricted: "成人向け",
+};
+
+/**
+ * CSV・Excelの商品一覧を取り込んでローカルの商品キャッシュに追加
+ * 同じJANの商品は上書きする（サーバーから同期した商品は残る）
+ */
+export async function importCatalog(
+  path: string,
+  mapping: CatalogMapping = DEFAULT_CATALOG_MAPPING,
+): Promise<{ count: number; errors: CatalogImportError[] }> {
+  const result = await commands.importCatalog(path, mapping);
+
+  const products: Product[] = [];
+  for (const p of result.products) {
+    // サーバーの商品と同じコードの場合は、そのIDを使って置き換える
+    const existing = await db.products.where("jan").equals(p.jan).first();
+    products.push({
+      id: existing?.id ?? p.id,
+      jan: p.jan,
+      jan2: p.jan2 ?? undefined,
+      isbn: p.isbn ?? undefined,
+      isBook: p.is_book,
+      ageRestricted: p.age_restricted,
+      name: p.name,
+      circleName: p.circle_name ?? undefined,
+      price: p.price,
+    });
+  }
+  await db.products.bulkPut(products);
+
+  return { count: products.length, errors: result.errors };
+}
+
+/**
+ * 未登録のコードを値段を入力して仮登録し、ローカルの商品キャッシュに追加
+ * 仮登録した商品は要確認として本部へ送られる
+ */
+export async function createProductFromScan(
+  code: string,
+  name: string,
+  price: number,
+  staffId: string,
+  circleName?: string,
+): Promise<Product> {
+  const p = await commands.createProductFromScan(
+    code,
+    name,
+    price,
+    circleName ?? null,
+    staffId,
+  );
+  const product: Product = {
+    id: p.id,
+    jan: p.jan,
+    isBook: p.is_book,
+    name: p.name,
+    circleName: p.circle_name ?? undefined,
+    price: p.price,
+  };
+  await db.products.put(product);
+  return product;
+}
+
 /**
  * JANコードで商品を検索（1段目または2段目バーコードで検索）
  * 論理削除された商品は除外
@@ -0,0 +1,61 @@
+/**
+ * 接続先環境（本番・ステージング・ローカル）
+ *
+ * 選択中の環境は Rust 側（environment.rs）が保存し、接続先のURLはビルド設定から選ぶ
+ * 起動時に loadEnvironment() で読み込み、以降は apiBaseUrl() で接続先を取得する
+ */
+
+import { type ApiEnvironment, commands } from "./commands.gen";
+
+const API_BASE_URLS: Record<ApiEnvironment, string | undefined> = {
+  production: import.meta.env.VITE_API_BASE_URL,
+  staging: import.meta.env.VITE_STAGING_API_BASE_URL,
+  local: import.meta.env.VITE_LOCAL_API_BASE_URL,
+};
+
+/** 読み込んだ環境（読み込めていない間は送信しない） */
+let current: ApiEnvironment | null = null;
+
+/**
+ * 選択中の環境を読み込む
+ */
+export async function loadEnvironment(): Promise<ApiEnvironment> {
+  current = await commands.getEnvironment();
+  return current;
+}
+
+/**
+ * 選択中の環境（読み込み前は null）
+ */
+export function currentEnvironment(): ApiEnvironment | null {
+  return current;
+}
+
+/**
+ * 選択中の環境のAPIのURL
+ */
+export function apiBaseUrl(): string {
+  if (!current) {
+    throw new Error("API environment is not loaded");
+  }
+  const url = API_BASE_URLS[current];
+  if (!url) {
+    throw new Error(`API base URL is not configured for ${current}`);
+  }
+  return url;
+}
+
+/**
+ * 環境を切り替えて画面を読み込み直す（接続中のクライアント・状態を作り直すため）
+ *
+ * 責任者セッション中に、閉局して送信待ちのデータがない状態でだけ切り替えられる
+ */
+export async function switchEnvironment(
+  environment: ApiEnvironment,
+): Promise<void> {
+  if (!API_BASE_URLS[environment]) {
+    throw new Error(`API base URL is not configured for ${environment}`);
+  }
+  await commands.switchEnvironment(environment);
+  window.location.reload();
+}
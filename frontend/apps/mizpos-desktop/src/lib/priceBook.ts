@@ -0,0 +1,36 @@
+/**
+ * 端末の価格表と商品キャッシュの同期
+ *
+ * Rust 側が署名付きの価格改定を価格表（price_book.rs）に反映したら、
+ * 会計で使う商品キャッシュ（db.products）の価格も同じ版にそろえる
+ */
+
+import { listen } from "@tauri-apps/api/event";
+import { commands, type PriceBook } from "./commands.gen";
+import { applyPriceBook } from "./db";
+
+/** Rust 側が発行するイベント名（price_book.rs） */
+const EVENT_PRICE_BOOK_UPDATED = "price-book-updated";
+
+/**
+ * 価格表の同期を開始
+ *
+ * 起動時と価格改定の反映のたびに商品キャッシュへ反映する
+ * 戻り値の関数で停止する
+ */
+export async function startPriceBookSync(): Promise<() => void> {
+  const apply = (book: PriceBook) => {
+    applyPriceBook(book).catch((error) => {
+      console.error("Failed to apply price book:", error);
+    });
+  };
+
+  const unlisten = await listen<PriceBook>(EVENT_PRICE_BOOK_UPDATED, (event) =>
+    apply(event.payload),
+  );
+  commands.getPriceBook().then(apply, (error) => {
+    console.error("Failed to load price book:", error);
+  });
+
+  return unlisten;
+}
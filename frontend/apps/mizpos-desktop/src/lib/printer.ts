@@ -5,7 +5,25 @@
  * - Android: Uses JavaScript Interface (window.MizPosPrinter)
  */
 
-import { invoke } from "@tauri-apps/api/core";
+import type {
+  CardDetails,
+  ClosingReportData,
+  ReceiptData as GeneratedReceiptData,
+} from "../types/receipt.gen";
+import {
+  commands,
+  type PaperLength,
+  type PrinterStatus,
+  type ReprintRequest,
+} from "./commands.gen";
+
+export type { PrinterStatus } from "./commands.gen";
+export type {
+  AppliedPromotion,
+  PaymentInfo,
+  PriceOverride,
+  ReceiptItem,
+} from "../types/receipt.gen";
 
 // Types
 export type Platform = "android" | "desktop";
@@ -43,6 +61,8 @@ interface MizPosPrinterBridge {
   welcomePrintWithWidth(terminalId: string, paperWidth: number): string;
   printReceipt(jsonData: string): string;
   printClosingReport(jsonData: string): string;
+  getPrinterStatus(): string;
+  refreshSignal(): string;
 }
 
 declare global {
@@ -56,7 +76,8 @@ declare global {
  */
 export async function getPlatform(): Promise<Platform> {
   try {
-    const platform = await invoke<string>("get_platform");
+    const platform = await commands.getPlatform();
+    // iOS uses the desktop commands (network printers)
     return platform === "android" ? "android" : "desktop";
   } catch {
     // Fallback: check if Android bridge exists
@@ -79,7 +100,7 @@ export function isAndroid(): boolean {
 // ===================
 
 export async function getUsbDevices(): Promise<UsbDevice[]> {
-  return invoke<UsbDevice[]>("get_usb_devices");
+  return commands.getUsbDevices();
 }
 
 export async function usbWelcomePrint(
@@ -88,12 +109,11 @@ export async function usbWelcomePrint(
   terminalId: string,
   paperWidth?: number,
 ): Promise<void> {
-  return invoke("welcome_print", {
-    vendorId,
-    deviceId,
-    id: terminalId,
-    paperWidth,
-  });
+  await commands.welcomePrint(
+    { transport: "device", vendor_id: vendorId, device_id: deviceId },
+    terminalId,
+    paperWidth ?? null,
+  );
 }
 
 export async function usbTextPrint(
@@ -102,29 +122,99 @@ export async function usbTextPrint(
   text: string,
   paperWidth?: number,
 ): Promise<void> {
-  return invoke("text_print", {
-    vendorId,
-    deviceId,
-    text,
-    paperWidth,
-  });
+  await commands.textPrint(vendorId, deviceId, text, paperWidth ?? null);
+}
+
+/**
+ * USB プリンターの状態を取得
+ */
+export async function usbGetPrinterStatus(
+  vendorId: number,
+  deviceId: number,
+): Promise<PrinterStatus> {
+  return commands.getPrinterStatus(vendorId, deviceId);
+}
+
+/**
+ * 印刷前にお知らせするプリンターの状態（問題がなければ null）
+ *
+ * 用紙切れ・カバー開・エラーは印刷できないため、会計を進める前に表示する
+ */
+export function printerStatusWarning(status: PrinterStatus): string | null {
+  if (!status.connected) {
+    return "プリンターに接続できません";
+  }
+  if (status.offline?.cover_open) {
+    return "プリンターのカバーが開いています";
+  }
+  if (status.paper?.out || status.offline?.paper_out_stop) {
+    return "用紙がありません。ロール紙を交換してください";
+  }
+  if (status.error?.autocutter) {
+    return "カッターのエラーです。紙詰まりを確認してください";
+  }
+  if (status.error?.unrecoverable) {
+    return "プリンターのエラーです。電源を入れ直してください";
+  }
+  if (status.error?.auto_recoverable || status.offline?.error) {
+    return "プリンターのエラーです。しばらく待ってから印刷してください";
+  }
+  if (status.offline?.offline) {
+    return "プリンターがオフラインです";
+  }
+  if (status.paper?.near_end) {
+    return "用紙が残りわずかです";
+  }
+  return null;
 }
 
 /**
  * USB プリンターで領収書形式のレシートを印刷
+ *
+ * idempotencyKey: 再送時に同じキーを渡すと二重印刷しない
+ * limitApproval: 数量・合計・釣り銭の上限を超える売上を印刷する場合の責任者承認
+ * reprint: 記録済みの売上を再発行(コピー)として印刷する（操作したスタッフと、
+ *   再発行の上限を超えるときの責任者承認を渡す）
  */
 export async function usbPrintFullReceipt(
   vendorId: number,
   deviceId: number,
   receipt: FullReceiptData,
   paperWidth?: number,
+  idempotencyKey?: string,
+  limitApproval?: { supervisor_id: string; pin: string },
+  reprint?: ReprintRequest,
 ): Promise<void> {
-  return invoke("print_receipt", {
+  await commands.printReceipt(
     vendorId,
     deviceId,
     receipt,
-    paperWidth,
-  });
+    paperWidth ?? null,
+    idempotencyKey ?? null,
+    limitApproval ?? null,
+    reprint ?? null,
+  );
+}
+
+/**
+ * デスクトップで Bluetooth（SPP）プリンターに領収書形式のレシートを印刷
+ *
+ * port: listBluetoothPrinters で得たポート（ペアリングは OS の設定で行う）
+ */
+export async function desktopBluetoothPrintFullReceipt(
+  port: string,
+  receipt: FullReceiptData,
+  paperWidth?: number,
+  idempotencyKey?: string,
+  limitApproval?: { supervisor_id: string; pin: string },
+): Promise<void> {
+  await commands.bluetoothPrintReceipt(
+    port,
+    receipt,
+    paperWidth ?? null,
+    idempotencyKey ?? null,
+    limitApproval ?? null,
+  );
 }
 
 // ===================
@@ -170,6 +260,33 @@ export function isBluetoothConnected(): boolean {
   return parsed.connected;
 }
 
+/**
+ * Bluetooth プリンターの状態（電池残量・電波強度を含む）
+ */
+export function getBluetoothPrinterStatus(): PrinterStatus | null {
+  if (!window.MizPosPrinter) {
+    return null;
+  }
+  const result = parseAndroidResult<PrinterResult & PrinterStatus>(
+    window.MizPosPrinter.getPrinterStatus(),
+  );
+  return result.success ? result : null;
+}
+
+/**
+ * Bluetooth プリンターの電波強度を測り直す
+ *
+ * 接続中は接続が乱れるため測らず、最後に接続したプリンターを未接続の間に測る
+ */
+export function refreshBluetoothSignal(): PrinterResult {
+  if (!window.MizPosPrinter) {
+    return { success: false, error: "Bluetooth not available" };
+  }
+  return parseAndroidResult<PrinterResult>(
+    window.MizPosPrinter.refreshSignal(),
+  );
+}
+
 export function bluetoothWelcomePrint(
   terminalId: string,
   paperWidth?: number,
@@ -205,98 +322,12 @@ export interface ReceiptData {
 }
 
 /**
- * レシート印刷用の商品明細
- */
-export interface ReceiptItem {
-  /** 出版サークル名 */
-  circle_name: string;
-  /** 商品名 */
-  name: string;
-  /** JAN */
-  jan: string;
-  /** ISBN */
-  isbn: string;
-  /** ISDN（書籍の場合） */
-  isdn?: string;
-  /** 2段目バーコード（Cコード＋値段、書籍の場合） */
-  jan2?: string;
-  /** 書籍フラグ */
-  is_book: boolean;
-  /** 商品数 */
-  quantity: number;
-  /** 値段（単価 x 数量） */
-  price: number;
-}
-
-/**
- * レシート印刷用の支払情報
- */
-export interface PaymentInfo {
-  /** 支払手段名（現金、クレジットカードなど） */
-  method: string;
-  /** 支払金額 */
-  amount: number;
-}
-
-/**
- * カード詳細情報（クレジット売上票用）
- */
-export interface CardDetailsForReceipt {
-  /** カードブランド（visa, mastercard等） */
-  brand?: string;
-  /** カード番号下4桁 */
-  last4?: string;
-  /** 有効期限（月） */
-  exp_month?: number;
-  /** 有効期限（年） */
-  exp_year?: number;
-  /** カード名義人 */
-  cardholder_name?: string;
-  /** カード種別（credit, debit等） */
-  funding?: string;
-  /** 端末シリアル番号 */
-  terminal_serial_number?: string;
-  /** 取引種別（sale/refund） */
-  transaction_type?: string;
-  /** 支払区分 */
-  payment_type?: string;
-  /** 取引日時（ISO8601形式） */
-  transaction_at?: string;
-}
-
-/**
- * 新レシート印刷データ（領収書形式）
+ * レシート印刷データ（領収書形式）
+ *
+ * 型は mizpos-core クレートの JSON Schema から生成している（receipt.gen.ts）
  */
-export interface FullReceiptData {
-  /** イベント名称 */
-  event_name: string;
-  /** サークル名（トップに大きく表示） */
-  circle_name?: string;
-  /** 会場住所 */
-  venue_address?: string;
-  /** 発売日時 */
-  sale_start_date_time?: string;
-  /** スタッフ番号 */
-  staff_id: string;
-  /** 宛名（様の前に表示） */
-  customer_name?: string;
-  /** 商品明細リスト */
-  items: ReceiptItem[];
-  /** 合計金額 */
-  total: number;
-  /** 支払情報リスト */
-  payments: PaymentInfo[];
-  /** 消費税率（%） */
-  tax_rate: number;
-  /** 消費税金額 */
-  tax_amount: number;
-  /** レシート番号 */
-  receipt_number: string;
-  /** カード詳細情報（クレジット決済時） */
-  card_details?: CardDetailsForReceipt;
-  /** Stripe PaymentIntent ID（クレジット決済時） */
-  payment_intent_id?: string;
-}
+export type FullReceiptData = GeneratedReceiptData;
+export type CardDetailsForReceipt = CardDetails;
 
 export function bluetoothPrintReceipt(data: ReceiptData): PrinterResult {
   if (!window.MizPosPrinter) {
@@ -307,24 +338,28 @@ export function bluetoothPrintReceipt(data: ReceiptData): PrinterResult {
 }
 
 /**
- * 閉局レポートデータ
+ * 閉局レポートデータ（paper_width は Android の印刷でだけ使う）
  */
-export interface ClosingReportPrintData {
-  id: string;
-  terminal_id: string;
-  staff_id: string;
-  staff_name: string;
-  event_name?: string;
-  denominations: Array<{ denomination: number; count: number }>;
-  cash_total: number;
-  vouchers: Array<{ type: string; amount: number; memo?: string }>;
-  voucher_total: number;
-  grand_total: number;
-  expected_total: number;
-  difference: number;
-  transaction_count: number;
-  closed_at: string;
+export type ClosingReportPrintData = ClosingReportData & {
   paper_width?: number;
+};
+
+/**
+ * ISO8601の日時を端末のローカル時刻で整形（例: 2025/12/31 10:30）
+ * Android（Bluetooth）はネイティブ側で日時を変換しないため、送信前に整形する
+ */
+function formatLocalDateTime(iso: string): string {
+  const date = new Date(iso);
+  if (Number.isNaN(date.getTime())) {
+    return iso;
+  }
+  return date.toLocaleString("ja-JP", {
+    year: "numeric",
+    month: "2-digit",
+    day: "2-digit",
+    hour: "2-digit",
+    minute: "2-digit",
+  });
 }
 
 export function bluetoothPrintClosingReport(
@@ -345,13 +380,14 @@ export async function usbPrintClosingReport(
   deviceId: number,
   report: ClosingReportPrintData,
   paperWidth?: number,
+  idempotencyKey?: string,
 ): Promise<void> {
-  return invoke("print_closing_report", {
-    vendorId,
-    deviceId,
+  await commands.printClosingReport(
+    { transport: "device", vendor_id: vendorId, device_id: deviceId },
     report,
-    paperWidth,
-  });
+    paperWidth ?? null,
+    idempotencyKey ?? null,
+  );
 }
 
 // ===================
@@ -496,17 +532,39 @@ export class UnifiedPrinter {
    * - クーポン処理
    * - 支払い方法・釣り銭
    */
+  /**
+   * レシートの長さを見積もる（印刷はしない、Androidでは見積もれないため null）
+   */
+  async estimateReceiptLength(
+    data: FullReceiptData,
+  ): Promise<PaperLength | null> {
+    if (this.config.platform === "android") {
+      return null;
+    }
+    return commands.estimateReceiptLength(data, this.config.paperWidth ?? null);
+  }
+
   async printFullReceipt(data: FullReceiptData): Promise<PrinterResult> {
     if (this.config.platform === "android") {
-      // Android: 新フォーマットでJavaScript Interfaceを使用
+      // Android: 印刷はネイティブ側で行うため、先にRust側で売上を確認・記録する
+      let sale: FullReceiptData;
+      try {
+        sale = await commands.prepareSale(data, null, null);
+      } catch (e) {
+        return { success: false, error: String(e) };
+      }
+
+      // 新フォーマットでJavaScript Interfaceを使用
       // 単価を計算（price / quantity）
       const androidData = {
-        event_name: data.event_name,
-        circle_name: data.circle_name || "",
-        venue_address: data.venue_address || "",
-        sale_start_date_time: data.sale_start_date_time || "",
-        staff_id: data.staff_id,
-        items: data.items.map((item) => ({
+        event_name: sale.event_name,
+        circle_name: sale.circle_name || "",
+        venue_address: sale.venue_address || "",
+        sale_start_date_time: sale.sale_start_date_time
+          ? formatLocalDateTime(sale.sale_start_date_time)
+          : "",
+        staff_id: sale.staff_id,
+        items: sale.items.map((item) => ({
           shop_name: item.circle_name,
           product_name: item.name,
           product_number: item.jan,
@@ -516,15 +574,15 @@ export class UnifiedPrinter {
           unit_price: Math.floor(item.price / item.quantity),
           quantity: item.quantity,
         })),
-        subtotal: data.total,
-        tax_rate: data.tax_rate,
-        tax_amount: data.tax_amount,
+        subtotal: sale.total,
+        tax_rate: sale.tax_rate,
+        tax_amount: sale.tax_amount,
         coupon_discount: 0, // TODO: クーポン割引がある場合は追加
-        payment_method: data.payments[0]?.method || "",
-        payment_amount: data.payments[0]?.amount || 0,
+        payment_method: sale.payments[0]?.method || "",
+        payment_amount: sale.payments[0]?.amount || 0,
         change:
-          data.payments.length > 0 ? data.payments[0].amount - data.total : 0,
-        receipt_number: data.receipt_number,
+          sale.payments.length > 0 ? sale.payments[0].amount - sale.total : 0,
+        receipt_number: sale.receipt_number,
         paperWidth: this.config.paperWidth,
       };
       return bluetoothPrintReceipt(androidData as unknown as ReceiptData);
@@ -557,6 +615,7 @@ export class UnifiedPrinter {
     if (this.config.platform === "android") {
       return bluetoothPrintClosingReport({
         ...data,
+        closed_at: formatLocalDateTime(data.closed_at),
         paper_width: this.config.paperWidth,
       });
     }
@@ -0,0 +1,165 @@
+/**
+ * 署名付き送信（アップロードキュー・ハートビート）のサーバーへの送信
+ *
+ * Rust 側が端末鍵で署名してキューに積んだペイロード（閉局レポート・脅迫PIN通報・
+ * ポイント付与など）を POST /pos/terminals/uploads へ送り、結果をキューに戻す
+ * 失敗した送信はキューに残り、次のキュー投入・ハートビート・オンライン復帰時に再送する
+ */
+
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { withBackgroundWork } from "./background";
+import {
+  commands,
+  type SignatureData,
+  type SignedHeartbeat,
+} from "./commands.gen";
+import { apiBaseUrl } from "./environment";
+
+/** Rust 側が発行するイベント名（upload_queue.rs / heartbeat.rs） */
+const EVENT_UPLOAD_QUEUED = "upload-queued";
+const EVENT_UPLOAD_FAILED = "upload-failed";
+const EVENT_HEARTBEAT = "terminal-heartbeat";
+
+/** ハートビートを送るときの種別 */
+const KIND_HEARTBEAT = "heartbeat";
+
+/** 送信失敗の通知（upload_queue::UploadFailedEvent） */
+export interface UploadFailedEvent {
+  /** キュー内ID（キューへの投入自体に失敗した場合は null） */
+  upload_id: string | null;
+  kind: string;
+  reference_id: string;
+  error: string;
+  attempts: number;
+}
+
+interface SignedUpload {
+  id: string;
+  kind: string;
+  reference_id: string;
+  payload: string;
+  signature: SignatureData;
+}
+
+interface UploadResponse {
+  accepted: boolean;
+  duplicate: boolean;
+  upload_id: string;
+  received_at: number;
+}
+
+/**
+ * 署名付きのペイロードを送信
+ */
+async function postUpload(upload: SignedUpload): Promise<UploadResponse> {
+  const response = await fetch(`${apiBaseUrl()}/pos/terminals/uploads`, {
+    method: "POST",
+    headers: {
+      "Content-Type": "application/json",
+    },
+    body: JSON.stringify({
+      terminal_id: upload.signature.terminal_id,
+      timestamp: upload.signature.timestamp,
+      signature: upload.signature.signature,
+      upload_id: upload.id,
+      kind: upload.kind,
+      reference_id: upload.reference_id,
+      payload: upload.payload,
+    }),
+  });
+
+  if (!response.ok) {
+    throw new Error(`Failed to send upload: ${response.status}`);
+  }
+  return response.json();
+}
+
+function errorMessage(error: unknown): string {
+  return error instanceof Error ? error.message : String(error);
+}
+
+let draining: Promise<void> | null = null;
+
+/**
+ * 送信待ちのアップロードをすべて送信
+ *
+ * 実行中に呼ばれた場合は実行中の送信の完了を待つ
+ */
+export function drainUploads(): Promise<void> {
+  if (!draining) {
+    draining = withBackgroundWork("sync", async () => {
+      const pending = await commands.getPendingUploads();
+      for (const upload of pending) {
+        try {
+          await postUpload(upload);
+          await commands.completeUpload(upload.id);
+        } catch (error) {
+          await commands.failUpload(upload.id, errorMessage(error));
+        }
+      }
+    }).finally(() => {
+      draining = null;
+    });
+  }
+  return draining;
+}
+
+/**
+ * ハートビートを送信し、結果を Rust 側に記録
+ */
+async function sendHeartbeat(heartbeat: SignedHeartbeat): Promise<void> {
+  try {
+    const response = await postUpload({
+      id: heartbeat.id,
+      kind: KIND_HEARTBEAT,
+      reference_id: "",
+      payload: heartbeat.payload,
+      signature: heartbeat.signature,
+    });
+    await commands.ackHeartbeat(response.received_at);
+  } catch (error) {
+    await commands.failHeartbeat(errorMessage(error));
+  }
+}
+
+/**
+ * 送信処理を開始
+ *
+ * キュー投入・ハートビート・オンライン復帰のたびに送信待ちを送る
+ * 戻り値の関数で停止する
+ */
+export async function startUploadSync(): Promise<() => void> {
+  const drain = () => {
+    drainUploads().catch((error) => {
+      console.error("Failed to drain upload queue:", error);
+    });
+  };
+
+  const unlisteners: UnlistenFn[] = await Promise.all([
+    listen(EVENT_UPLOAD_QUEUED, drain),
+    listen<SignedHeartbeat>(EVENT_HEARTBEAT, async (event) => {
+      await sendHeartbeat(event.payload);
+      // 定期的なハートビートを失敗した送信の再送の機会にもする
+      drain();
+    }),
+  ]);
+  window.addEventListener("online", drain);
+
+  drain();
+
+  return () => {
+    for (const unlisten of unlisteners) unlisten();
+    window.removeEventListener("online", drain);
+  };
+}
+
+/**
+ * 送信失敗の通知を購読
+ */
+export function onUploadFailed(
+  handler: (event: UploadFailedEvent) => void,
+): Promise<UnlistenFn> {
+  return listen<UploadFailedEvent>(EVENT_UPLOAD_FAILED, (event) =>
+    handler(event.payload),
+  );
+}
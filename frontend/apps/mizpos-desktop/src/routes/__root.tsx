@@ -1,6 +1,9 @@
 import { createRootRoute, Outlet } from "@tanstack/react-router";
 import { useEffect, useState } from "react";
 import { css } from "styled-system/css";
+import { UploadFailureBanner } from "../components/UploadFailureBanner";
+import { startPriceBookSync } from "../lib/priceBook";
+import { startUploadSync } from "../lib/uploads";
 import { useAuthStore } from "../stores/auth";
 import { useSettingsStore } from "../stores/settings";
 import { useTerminalStore } from "../stores/terminal";
@@ -28,6 +31,24 @@ function RootLayout() {
     init();
   }, [initAuth, initSettings, initTerminal]);
 
+  // 署名付き送信（閉局レポート・ハートビートなど）のサーバーへの送信
+  useEffect(() => {
+    if (!isInitialized) return;
+    const stop = startUploadSync();
+    return () => {
+      stop.then((fn) => fn());
+    };
+  }, [isInitialized]);
+
+  // 価格改定を反映した価格表を商品キャッシュへ反映
+  useEffect(() => {
+    if (!isInitialized) return;
+    const stop = startPriceBookSync();
+    return () => {
+      stop.then((fn) => fn());
+    };
+  }, [isInitialized]);
+
   if (!isInitialized) {
     return (
       <div
@@ -57,7 +78,12 @@ function RootLayout() {
     );
   }
 
-  return <Outlet />;
+  return (
+    <>
+      <Outlet />
+      <UploadFailureBanner />
+    </>
+  );
 }
 
 export const Route = createRootRoute({
@@ -495,13 +495,6 @@ function ClosingPage() {
             paperWidth: settings.printer.paperWidth,
           });
 
-          const closedAtStr = report.closedAt.toLocaleString("ja-JP", {
-            year: "numeric",
-            month: "2-digit",
-            day: "2-digit",
-            hour: "2-digit",
-            minute: "2-digit",
-          });
 
           const printData: ClosingReportPrintData = {
             id: report.id,
@@ -521,7 +514,7 @@ function ClosingPage() {
             expected_total: report.expectedTotal,
             difference: report.difference,
             transaction_count: salesTotal.transactionCount,
-            closed_at: closedAtStr,
+            closed_at: report.closedAt.toISOString(),
           };
 
           const result = await printer.printClosingReport(printData);
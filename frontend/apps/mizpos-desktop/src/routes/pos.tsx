@@ -331,6 +331,8 @@ function POSPage() {
   const [barcodeInput, setBarcodeInput] = useState("");
   const [isProcessing, setIsProcessing] = useState(false);
   const [showManualEntry, setShowManualEntry] = useState(false);
+  // 未登録のコード（仮登録の入力画面を開く）
+  const [unknownCode, setUnknownCode] = useState<string | null>(null);
   const [showProductSelect, setShowProductSelect] = useState(false);
   const [showCheckout, setShowCheckout] = useState(false);
   const [completedTransaction, setCompletedTransaction] =
@@ -494,8 +496,10 @@ function POSPage() {
         } else {
           setNotification({
             type: "warning",
-            message: `未登録のコード: ${cleaned}`,
+            message: `未登録のコード: ${cleaned}（値段を入力して仮登録できます）`,
           });
+          setUnknownCode(cleaned);
+          setShowManualEntry(true);
         }
       } catch {
         setNotification({
@@ -810,7 +814,13 @@ function POSPage() {
 
       {/* モーダル */}
       {showManualEntry && (
-        <ManualProductEntry onClose={() => setShowManualEntry(false)} />
+        <ManualProductEntry
+          scannedCode={unknownCode ?? undefined}
+          onClose={() => {
+            setShowManualEntry(false);
+            setUnknownCode(null);
+          }}
+        />
       )}
       <ProductSelectModal
         isOpen={showProductSelect}
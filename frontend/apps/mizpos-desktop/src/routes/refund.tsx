@@ -3,10 +3,9 @@ import { confirm } from "@tauri-apps/plugin-dialog";
 import { useCallback, useEffect, useState } from "react";
 import { css } from "styled-system/css";
 import { Button, Card, Input } from "../components/ui";
+import { apiBaseUrl } from "../lib/environment";
 import { useAuthStore } from "../stores/auth";
 
-const API_BASE_URL = import.meta.env.VITE_API_BASE_URL;
-
 // 販売データの型
 interface SaleItem {
   product_id: string;
@@ -208,7 +207,7 @@ function RefundPage() {
     setSale(null);
 
     try {
-      const response = await fetch(`${API_BASE_URL}/pos/sales/${saleId}`, {
+      const response = await fetch(`${apiBaseUrl()}/pos/sales/${saleId}`, {
         method: "GET",
         headers: {
           "Content-Type": "application/json",
@@ -279,7 +278,7 @@ function RefundPage() {
       // クレジット決済の場合は先にStripe返金を実行
       if (isStripePayment && hasPaymentIntentId) {
         const stripeRefundResponse = await fetch(
-          `${API_BASE_URL}/terminal/refunds`,
+          `${apiBaseUrl()}/terminal/refunds`,
           {
             method: "POST",
             headers: {
@@ -310,7 +309,7 @@ function RefundPage() {
         product_name: item.product_name,
       }));
 
-      const response = await fetch(`${API_BASE_URL}/pos/refunds`, {
+      const response = await fetch(`${apiBaseUrl()}/pos/refunds`, {
         method: "POST",
         headers: {
           "Content-Type": "application/json",
@@ -2,11 +2,10 @@ import { createFileRoute, useNavigate } from "@tanstack/react-router";
 import { useEffect, useState } from "react";
 import { css } from "styled-system/css";
 import { Button, Card } from "../components/ui";
+import { apiBaseUrl } from "../lib/environment";
 import { useAuthStore } from "../stores/auth";
 import { useSettingsStore } from "../stores/settings";
 
-const API_BASE_URL = import.meta.env.VITE_API_BASE_URL;
-
 interface Event {
   event_id: string;
   name: string;
@@ -154,7 +153,7 @@ function SelectEventPage() {
     const fetchEvents = async () => {
       try {
         setIsLoading(true);
-        const response = await fetch(`${API_BASE_URL}/pos/events`, {
+        const response = await fetch(`${apiBaseUrl()}/pos/events`, {
           headers: {
             "Content-Type": "application/json",
             "X-POS-Session": session?.sessionId || "",
@@ -194,7 +193,7 @@ function SelectEventPage() {
 
     try {
       // セッションにイベントIDを紐づけるAPI呼び出し
-      const response = await fetch(`${API_BASE_URL}/pos/auth/set-event`, {
+      const response = await fetch(`${apiBaseUrl()}/pos/auth/set-event`, {
         method: "POST",
         headers: {
           "Content-Type": "application/json",
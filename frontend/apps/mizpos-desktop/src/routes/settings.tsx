@@ -1,9 +1,19 @@
 import { createFileRoute, useNavigate } from "@tanstack/react-router";
+import { open, save } from "@tauri-apps/plugin-dialog";
 import { useCallback, useEffect, useState } from "react";
 import { css } from "styled-system/css";
+import { NetworkPrinterCard } from "../components/NetworkPrinterCard";
 import { PairingModal } from "../components/PairingModal";
+import { PaperUsageCard } from "../components/PaperUsageCard";
+import { SalesTargetCard } from "../components/SalesTargetCard";
+import { SerialPrinterCard } from "../components/SerialPrinterCard";
 import { Badge, Button, Card, Input } from "../components/ui";
-import { getTodayOpeningReport, syncProducts } from "../lib/db";
+import { commands } from "../lib/commands.gen";
+import {
+  getTodayOpeningReport,
+  importCatalog,
+  syncProducts,
+} from "../lib/db";
 import {
   type BluetoothDevice,
   getBluetoothDevices,
@@ -187,6 +197,13 @@ function SettingsPage() {
     count?: number;
     error?: string;
   } | null>(null);
+  const [isImporting, setIsImporting] = useState(false);
+  const [importResult, setImportResult] = useState<{
+    success: boolean;
+    count?: number;
+    errors?: string[];
+    error?: string;
+  } | null>(null);
   const [isTestPrinting, setIsTestPrinting] = useState(false);
   const [testPrintResult, setTestPrintResult] = useState<{
     success: boolean;
@@ -271,6 +288,35 @@ function SettingsPage() {
     navigate({ to: "/select-event" });
   }, [clearEventId, updateSettings, navigate]);
 
+  const handleArchiveEvent = useCallback(async () => {
+    if (
+      !confirm(
+        "イベントのデータをアーカイブに書き出し、端末から消去しますか？\n消去後はイベント選択画面に戻ります。",
+      )
+    ) {
+      return;
+    }
+    const path = await save({
+      defaultPath: `mizpos-${settings.eventName || "event"}.tar.gz`,
+      filters: [{ name: "アーカイブ", extensions: ["tar.gz"] }],
+    });
+    if (!path) return;
+
+    try {
+      const archive = await commands.archiveEvent(path, true, true);
+      alert(
+        `アーカイブを保存しました（${archive.manifest.files.length}ファイル）\n` +
+          `SHA-256: ${archive.archive_sha256}`,
+      );
+    } catch (error) {
+      alert(`アーカイブに失敗しました: ${error}`);
+      return;
+    }
+    await clearEventId();
+    await updateSettings({ eventName: "" });
+    navigate({ to: "/select-event" });
+  }, [settings.eventName, clearEventId, updateSettings, navigate]);
+
   const handleSyncProducts = useCallback(async () => {
     setIsSyncing(true);
     setSyncResult(null);
@@ -287,6 +333,34 @@ function SettingsPage() {
     }
   }, []);
 
+  const handleImportCatalog = useCallback(async () => {
+    const path = await open({
+      multiple: false,
+      filters: [
+        { name: "商品一覧", extensions: ["csv", "xlsx", "xls", "ods"] },
+      ],
+    });
+    if (typeof path !== "string") return;
+
+    setIsImporting(true);
+    setImportResult(null);
+    try {
+      const { count, errors } = await importCatalog(path);
+      setImportResult({
+        success: true,
+        count,
+        errors: errors.map((e) => `${e.row}行目: ${e.message}`),
+      });
+    } catch (error) {
+      setImportResult({
+        success: false,
+        error: error instanceof Error ? error.message : String(error),
+      });
+    } finally {
+      setIsImporting(false);
+    }
+  }, []);
+
   const handleTestPrint = useCallback(async () => {
     if (!selectedPrinter) {
       setTestPrintResult({
@@ -707,8 +781,49 @@ function SettingsPage() {
                   : `エラー: ${syncResult.error}`}
               </div>
             )}
+            <div
+              className={css({
+                fontSize: "14px",
+                color: "#94a3b8",
+                margin: "16px 0",
+              })}
+            >
+              サークルから受け取った商品一覧（CSV・Excel）を取り込みます。見出しは「商品名」「価格」「JAN」「ISBN」「サークル名」などにしてください。
+            </div>
+            <Button
+              variant="outline"
+              onClick={handleImportCatalog}
+              disabled={isImporting}
+              fullWidth
+            >
+              {isImporting ? "取り込み中..." : "ファイルから取り込む"}
+            </Button>
+            {importResult && (
+              <div
+                className={css({
+                  marginTop: "12px",
+                  padding: "12px",
+                  borderRadius: "8px",
+                  fontSize: "14px",
+                  background: importResult.success ? "#14532d" : "#7f1d1d",
+                  color: importResult.success ? "#86efac" : "#fca5a5",
+                })}
+              >
+                {importResult.success
+                  ? `${importResult.count}件の商品を取り込みました`
+                  : `エラー: ${importResult.error}`}
+                {importResult.errors?.map((e) => (
+                  <div key={e} className={css({ color: "#fca5a5" })}>
+                    {e}
+                  </div>
+                ))}
+              </div>
+            )}
           </Card>
 
+          {/* 売上目標 */}
+          <SalesTargetCard />
+
           {/* プリンター設定 */}
           <Card padding="lg">
             <div className={sectionStyles.header}>
@@ -881,6 +996,15 @@ function SettingsPage() {
             )}
           </Card>
 
+          {/* LANプリンターの探索（Androidは Bluetooth のみ） */}
+          {!isBluetoothMode && <NetworkPrinterCard />}
+
+          {/* シリアルプリンター */}
+          {!isBluetoothMode && <SerialPrinterCard />}
+
+          {/* 用紙使用量 */}
+          {!isBluetoothMode && <PaperUsageCard />}
+
           {/* 保存ボタン */}
           <Button variant="primary" size="xl" fullWidth onClick={handleSave}>
             設定を保存
@@ -961,6 +1085,33 @@ function SettingsPage() {
             </Card>
           )}
 
+          {/* イベント終了（職長のみ） */}
+          {session?.role === "manager" && (
+            <Card padding="lg">
+              <h2 className={sectionStyles.title}>イベント終了</h2>
+              <div
+                className={css({
+                  fontSize: "14px",
+                  color: "#94a3b8",
+                  marginBottom: "16px",
+                })}
+              >
+                ジャーナル・レポートなどイベントのデータをアーカイブに書き出し、検証してから端末から消去します。
+                <br />
+                <span className={css({ color: "#f87171" })}>
+                  ※閉局とアップロードの完了後に行ってください
+                </span>
+              </div>
+              <Button
+                variant="outlineDanger"
+                fullWidth
+                onClick={handleArchiveEvent}
+              >
+                アーカイブして端末をリセット
+              </Button>
+            </Card>
+          )}
+
           {/* バージョン情報 */}
           <button
             type="button"
@@ -1,11 +1,11 @@
 import { Store } from "@tauri-apps/plugin-store";
 import { create } from "zustand";
+import { commands } from "../lib/commands.gen";
 import { getTodayOpeningReport, syncProducts } from "../lib/db";
+import { apiBaseUrl } from "../lib/environment";
 import type { Session } from "../types";
 import { useSettingsStore } from "./settings";
 
-const API_BASE_URL = import.meta.env.VITE_API_BASE_URL;
-
 interface CircleInfo {
   publisher_id: string;
   name: string;
@@ -117,7 +117,7 @@ export const useAuthStore = create<AuthState>((set) => ({
       const terminalId = useSettingsStore.getState().settings.terminalId;
 
       // APIで認証
-      const response = await fetch(`${API_BASE_URL}/pos/auth/login`, {
+      const response = await fetch(`${apiBaseUrl()}/pos/auth/login`, {
         method: "POST",
         headers: {
           "Content-Type": "application/json",
@@ -181,10 +181,15 @@ export const useAuthStore = create<AuthState>((set) => ({
 
       set({ session, isLoading: false });
 
+      // 閉局レポートの担当者別集計のため、担当者の交代を端末に記録
+      commands
+        .switchStaff(session.staffId, session.staffName)
+        .catch((e) => console.warn("[Auth] Failed to record staff shift:", e));
+
       // 従業員にevent_idが紐づいている場合、イベント情報を取得してsettingsを更新
       if (data.event_id) {
         try {
-          const eventsResponse = await fetch(`${API_BASE_URL}/pos/events`, {
+          const eventsResponse = await fetch(`${apiBaseUrl()}/pos/events`, {
             headers: {
               "Content-Type": "application/json",
               "X-POS-Session": data.session_id,
@@ -231,7 +236,7 @@ export const useAuthStore = create<AuthState>((set) => ({
       // サーバー側のセッションも無効化（ベストエフォート）
       if (session?.sessionId) {
         try {
-          await fetch(`${API_BASE_URL}/pos/auth/logout`, {
+          await fetch(`${apiBaseUrl()}/pos/auth/logout`, {
             method: "POST",
             headers: {
               "Content-Type": "application/json",
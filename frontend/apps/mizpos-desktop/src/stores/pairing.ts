@@ -7,16 +7,17 @@
 
 import { createSalesClient } from "@mizpos/api";
 import { create } from "zustand";
-
-const API_BASE_URL = import.meta.env.VITE_API_BASE_URL;
+import { apiBaseUrl } from "../lib/environment";
 
 // Sales APIクライアント（API Gatewayの/salesプレフィックスを追加）
-const salesClient = createSalesClient({
-  baseUrl: `${API_BASE_URL}/sales`,
-  headers: {
-    "Content-Type": "application/json",
-  },
-});
+// 接続先環境は起動後に読み込むため、使うときに作る
+const salesClient = () =>
+  createSalesClient({
+    baseUrl: `${apiBaseUrl()}/sales`,
+    headers: {
+      "Content-Type": "application/json",
+    },
+  });
 
 /** ペアリング状態 */
 export type PairingStatus =
@@ -151,15 +152,18 @@ export const usePairingStore = create<PairingState>((set, get) => {
       const pinCode = generatePinCode();
 
       try {
-        const { error } = await salesClient.POST("/terminal/pairing/register", {
-          body: {
-            pin_code: pinCode,
-            pos_id: posId,
-            pos_name: posName,
-            event_id: eventId,
-            event_name: eventName,
+        const { error } = await salesClient().POST(
+          "/terminal/pairing/register",
+          {
+            body: {
+              pin_code: pinCode,
+              pos_id: posId,
+              pos_name: posName,
+              event_id: eventId,
+              event_name: eventName,
+            },
           },
-        });
+        );
 
         if (error) {
           throw new Error("Failed to register pairing");
@@ -205,7 +209,7 @@ export const usePairingStore = create<PairingState>((set, get) => {
 
       if (pairingInfo?.pinCode) {
         try {
-          await salesClient.DELETE("/terminal/pairing/{pin_code}", {
+          await salesClient().DELETE("/terminal/pairing/{pin_code}", {
             params: { path: { pin_code: pairingInfo.pinCode } },
           });
         } catch (err) {
@@ -240,7 +244,7 @@ export const usePairingStore = create<PairingState>((set, get) => {
       });
 
       try {
-        const { data, error, response } = await salesClient.POST(
+        const { data, error, response } = await salesClient().POST(
           "/terminal/payment-requests",
           {
             body: {
@@ -355,7 +359,7 @@ export const usePairingStore = create<PairingState>((set, get) => {
       }
 
       try {
-        await salesClient.DELETE("/terminal/payment-requests/{request_id}", {
+        await salesClient().DELETE("/terminal/payment-requests/{request_id}", {
           params: { path: { request_id: currentPaymentRequest.requestId } },
         });
 
@@ -381,7 +385,7 @@ export const usePairingStore = create<PairingState>((set, get) => {
           data,
           error,
           response: httpResponse,
-        } = await salesClient.GET("/terminal/payment-requests/{request_id}", {
+        } = await salesClient().GET("/terminal/payment-requests/{request_id}", {
           params: { path: { request_id: currentPaymentRequest.requestId } },
         });
 
@@ -537,7 +541,7 @@ export const usePairingStore = create<PairingState>((set, get) => {
       }
 
       try {
-        const { data, error, response } = await salesClient.GET(
+        const { data, error, response } = await salesClient().GET(
           "/terminal/pairing/{pin_code}",
           {
             params: { path: { pin_code: pairingInfo.pinCode } },
@@ -1,9 +1,18 @@
-import { invoke } from "@tauri-apps/api/core";
 import nacl from "tweetnacl";
 import { decodeBase64, encodeBase64 } from "tweetnacl-util";
 import { create } from "zustand";
+import {
+  commands,
+  type RegistrationQrPayload,
+  type SignatureData,
+  type TerminalAuthError,
+  type TerminalAuthResult,
+} from "../lib/commands.gen";
+import { apiBaseUrl } from "../lib/environment";
 import { useSettingsStore } from "./settings";
 
+export type { RegistrationQrPayload, SignatureData } from "../lib/commands.gen";
+
 /** 端末の状態 */
 export type TerminalStatus =
   | "uninitialized" // キーペア未生成
@@ -11,35 +20,30 @@ export type TerminalStatus =
   | "registered" // 登録済み
   | "revoked"; // 無効化済み
 
-/** 端末認証の結果 */
-interface TerminalAuthResult {
-  status: string;
-  terminal_id: string | null;
-  public_key: string | null;
-  error: string | null;
-}
-
-/** QRコード用のペイロード */
-export interface RegistrationQrPayload {
-  v: number;
-  terminal_id: string;
-  public_key: string;
-  device_name: string;
-  os: string;
-  created_at: string;
-}
-
-/** 署名データ */
-export interface SignatureData {
-  terminal_id: string;
-  timestamp: number;
-  signature: string;
+/**
+ * 端末認証のエラーを表示用の文字列にする
+ *
+ * Rust 側のコマンドは TerminalAuthError（{ kind, message }）で reject する
+ */
+function terminalErrorMessage(error: unknown): string {
+  if (error instanceof Error) {
+    return error.message;
+  }
+  if (typeof error === "object" && error !== null && "kind" in error) {
+    const authError = error as TerminalAuthError;
+    return "message" in authError
+      ? `${authError.kind}: ${authError.message}`
+      : authError.kind;
+  }
+  return "Unknown error";
 }
 
 /** サーバーからの端末登録確認レスポンス */
 interface CheckTerminalResponse {
   registered: boolean;
   status: string | null;
+  /** 管理サーバーの公開鍵（Base64、配信データの署名検証に使う） */
+  server_public_key?: string | null;
 }
 
 /** Android JavaScript Interface の型定義 */
@@ -153,7 +157,7 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
         };
       } else {
         // Desktop: Tauriコマンドを使用
-        result = await invoke<TerminalAuthResult>("get_terminal_status");
+        result = await commands.getTerminalStatus();
       }
 
       if (result.status === "initialized") {
@@ -184,7 +188,7 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
       set({
         status: "uninitialized",
         isInitialized: true,
-        error: error instanceof Error ? error.message : "Unknown error",
+        error: terminalErrorMessage(error),
       });
     }
   },
@@ -199,6 +203,8 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
         const terminalId = generateUUID();
         const privateKeyBase64 = encodeBase64(keyPair.secretKey.slice(0, 32)); // Ed25519の秘密鍵は先頭32バイト
         const publicKeyBase64 = encodeBase64(keyPair.publicKey);
+        // 保存用にエンコードしたら秘密鍵のバイト列は消去する
+        keyPair.secretKey.fill(0);
 
         const saveResultJson = window.MizPosTerminalAuth?.saveKeyPair(
           terminalId,
@@ -226,9 +232,7 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
         };
       } else {
         // Desktop: Tauriコマンドを使用
-        payload = await invoke<RegistrationQrPayload>("initialize_terminal", {
-          deviceName,
-        });
+        payload = await commands.initializeTerminal(deviceName, null);
       }
 
       set({
@@ -247,8 +251,7 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
       return payload;
     } catch (error) {
       console.error("Failed to initialize terminal:", error);
-      const errorMessage =
-        error instanceof Error ? error.message : "Unknown error";
+      const errorMessage = terminalErrorMessage(error);
       set({ error: errorMessage });
       throw new Error(errorMessage);
     }
@@ -268,9 +271,10 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
         }
       } else {
         // Desktop: Tauriコマンドを使用
-        const jsonData = await invoke<string>("generate_registration_qr", {
+        const jsonData = await commands.generateRegistrationQr(
           deviceName,
-        });
+          null,
+        );
         payload = JSON.parse(jsonData) as RegistrationQrPayload;
       }
 
@@ -317,8 +321,10 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
         const seedBytes = decodeBase64(response.private_key);
         const keyPair = nacl.sign.keyPair.fromSeed(seedBytes);
 
-        // 署名を生成
+        // 署名を生成し、秘密鍵のバイト列は消去する
         const signature = nacl.sign.detached(messageBytes, keyPair.secretKey);
+        keyPair.secretKey.fill(0);
+        seedBytes.fill(0);
 
         return {
           terminal_id: terminalId,
@@ -327,9 +333,7 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
         };
       } else {
         // Desktop: Tauriコマンドを使用
-        const signatureData = await invoke<SignatureData>(
-          "create_auth_signature",
-        );
+        const signatureData = await commands.createAuthSignature();
         return signatureData;
       }
     } catch (error) {
@@ -345,9 +349,8 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
     }
 
     try {
-      const apiBaseUrl = import.meta.env.VITE_API_BASE_URL;
       const response = await fetch(
-        `${apiBaseUrl}/pos/terminals/check/${terminalId}`,
+        `${apiBaseUrl()}/pos/terminals/check/${terminalId}`,
       );
 
       if (!response.ok) {
@@ -358,6 +361,14 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
 
       if (data.registered) {
         if (data.status === "active") {
+          if (data.server_public_key) {
+            // 初回は固定し、固定済みの鍵と異なる場合は置き換えずにエラーとする
+            await commands
+              .pinServerKey(data.server_public_key, null)
+              .catch((error) => {
+                console.error("Failed to pin server key:", error);
+              });
+          }
           set({ isRegisteredOnServer: true, status: "registered" });
           return true;
         } else if (data.status === "revoked") {
@@ -392,7 +403,7 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
         }
       } else {
         // Desktop: Tauriコマンドを使用
-        await invoke("clear_terminal_keychain");
+        await commands.clearTerminalKeychain();
       }
 
       set({
@@ -423,10 +434,9 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
 
     try {
       // サーバーに無効化リクエストを送信
-      const apiBaseUrl = import.meta.env.VITE_API_BASE_URL;
       try {
         const signatureData = await get().createAuthSignature();
-        const response = await fetch(`${apiBaseUrl}/pos/terminals/revoke`, {
+        const response = await fetch(`${apiBaseUrl()}/pos/terminals/revoke`, {
           method: "POST",
           headers: {
             "Content-Type": "application/json",
@@ -37,9 +37,11 @@ export interface Product {
   isbn?: string;
   isdn?: string; // ISDN（ハイフン区切り）
   isBook: boolean; // 書籍フラグ
+  ageRestricted?: boolean; // 成人向け（R18、販売時に年齢確認が必要）
   name: string;
   circleName?: string;
   price: number;
+  priceVersion?: number; // 価格を反映した価格表の版（価格表にない商品は未設定）
   imageUrl?: string;
   deletedAt?: Date; // 論理削除日時（nullの場合は有効）
 }
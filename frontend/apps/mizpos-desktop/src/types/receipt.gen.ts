@@ -0,0 +1,309 @@
+// This file is generated by scripts/generate-receipt-types.mjs
+// from src-tauri/crates/mizpos-core/schema/receipt.schema.json.
+// Do not edit by hand.
+
+/** 年齢確認の記録 */
+export interface AgeVerification {
+  /** 確認したスタッフ番号 */
+  staff_id: string;
+  /** 端末が発行した確認の番号（1件の売上にだけ使える） */
+  token?: string;
+  /** 確認日時（UNIX秒） */
+  verified_at: number;
+}
+
+/** 適用したキャンペーン */
+export interface AppliedPromotion {
+  /** 割引額 */
+  discount: number;
+  /** 明細ごとの割引額（合計は discount と一致する） */
+  lines?: PromotionLineDiscount[];
+  name: string;
+  promotion_id: string;
+}
+
+/** カード詳細情報（クレジット売上票用） */
+export interface CardDetails {
+  /** カードブランド（visa, mastercard等） */
+  brand?: string | null;
+  /** カード名義人 */
+  cardholder_name?: string | null;
+  /** 有効期限（月） */
+  exp_month?: number | null;
+  /** 有効期限（年） */
+  exp_year?: number | null;
+  /** カード種別（credit, debit等） */
+  funding?: string | null;
+  /** カード番号下4桁 */
+  last4?: string | null;
+  /** 加盟店名（Stripeアカウント名） */
+  merchant_name?: string | null;
+  /** 支払区分 */
+  payment_type?: string | null;
+  /** 端末シリアル番号 */
+  terminal_serial_number?: string | null;
+  /** 取引日時（ISO8601形式） */
+  transaction_at?: string | null;
+  /** 取引種別（sale/refund） */
+  transaction_type?: string | null;
+}
+
+/** 閉局レポートデータ */
+export interface ClosingReportData {
+  cash_total: number;
+  closed_at: string;
+  denominations: DenominationCount[];
+  difference: number;
+  event_name?: string | null;
+  expected_total: number;
+  grand_total: number;
+  /** 時間帯別の売上（空の場合は印刷時にジャーナルから集計する） */
+  hourly_sales?: HourlySales[];
+  id: string;
+  /** 支払方法別の集計（空の場合は印刷時にジャーナルから集計する） */
+  payment_methods?: PaymentMethodTotal[];
+  staff_id: string;
+  staff_name: string;
+  /** 担当者別の集計（空の場合は印刷時にジャーナルから集計する） */
+  staff_totals?: StaffTotal[];
+  terminal_id: string;
+  transaction_count: number;
+  /** 売上に使われた商品券の種別ごとの集計（空の場合は印刷時にジャーナルから集計する） */
+  voucher_sales?: VoucherSales[];
+  voucher_total: number;
+  vouchers: VoucherCount[];
+}
+
+/** 金種カウント */
+export interface DenominationCount {
+  count: number;
+  denomination: number;
+}
+
+/**
+ * 内金・残金の条件
+ *
+ * 内金を受け取る売上と残金を受け取る売上の2件に分けて記録し、残金の売上は内金の売上のレシート番号を持つ
+ */
+export interface DepositTerms {
+  /** 内金 */
+  deposit: number;
+  /** 内金を受け取った売上のレシート番号（残金の売上のみ） */
+  deposit_receipt_number?: string | null;
+  /** 残金のお支払期日（YYYY-MM-DD） */
+  due_date: string;
+  /** ご注文の合計金額（内金と残金の合計） */
+  order_total: number;
+}
+
+/** 時間帯別の売上 */
+export interface HourlySales {
+  amount: number;
+  count: number;
+  /** 日付（YYYY-MM-DD、端末のタイムゾーン） */
+  date: string;
+  /** 時（0〜23） */
+  hour: number;
+}
+
+/** 画像を白黒に変換する方法 */
+export type ImageMode =
+  /** 誤差拡散（写真・グラデーション向け） */
+  | "dither"
+  /** しきい値で2値化（線画・文字のロゴ向け） */
+  | "threshold";
+
+/** 画像の読み込み元 */
+export type ImageSource =
+  /** Base64 でエンコードした PNG（`data:image/png;base64,` から始まるものも可） */
+  | {
+    data: string;
+    type: "base64";
+  }
+  /** 端末の PNG ファイル */
+  | {
+    path: string;
+    type: "file";
+  };
+
+/** 支払情報 */
+export interface PaymentInfo {
+  /** 支払金額 */
+  amount: number;
+  /** 支払手段名（現金、クレジットカードなど） */
+  method: string;
+}
+
+/** 支払方法別の集計 */
+export interface PaymentMethodTotal {
+  /** 受取額（現金は釣り銭を除く） */
+  amount: number;
+  /** 売上件数（併用された売上はそれぞれの支払方法で数える） */
+  count: number;
+  /** 支払手段名 */
+  method: string;
+  refund_amount: number;
+  /** 返品・取消の件数と額（元の売上で最も多く支払われた方法に計上する） */
+  refund_count: number;
+}
+
+/** 明細の価格変更の印（承認時のジャーナルの記録と対応する） */
+export interface PriceOverride {
+  /** 変更前の値段（単価 x 数量） */
+  original_price: number;
+  /** 承認を記録したジャーナルのID */
+  override_id: string;
+  /** 理由の表示名 */
+  reason: string;
+  reason_code: string;
+  /** 承認した責任者 */
+  supervisor_id: string;
+}
+
+/** キャンペーンの割引額のうち、1つの明細に割り当てた分 */
+export interface PromotionLineDiscount {
+  discount: number;
+  /** 明細の番号（items の並び順、0始まり） */
+  item_index: number;
+}
+
+/** レシートデータ */
+export interface ReceiptData {
+  /** 年齢確認の記録（成人向けの商品を含む場合に必須） */
+  age_verification?: AgeVerification | null;
+  /** カード詳細情報（クレジット決済時、読み込み時に保存してよい範囲に絞る） */
+  card_details?: CardDetails | null;
+  /** 釣り銭の金種内訳（高額の釣り銭時に印字） */
+  change_breakdown?: DenominationCount[] | null;
+  /** サークル名（トップに大きく表示） */
+  circle_name?: string | null;
+  /** サークル名を縦書きで表示する（領収書風の装飾） */
+  circle_name_vertical?: boolean;
+  /** 宛名（様の前に表示。会員の売上では会員名） */
+  customer_name?: string | null;
+  /** 内金・残金（予約販売の売上のみ。total はこの取引で受け取る内金または残金） */
+  deposit?: DepositTerms | null;
+  /** イベント名称 */
+  event_name: string;
+  /** 商品明細リスト */
+  items: ReceiptItem[];
+  /** ロゴ画像（レシートの先頭に印刷） */
+  logo?: ReceiptLogo | null;
+  /** 会員番号 */
+  member_id?: string | null;
+  /** Stripe PaymentIntent ID（クレジット決済時） */
+  payment_intent_id?: string | null;
+  /** 支払情報リスト */
+  payments: PaymentInfo[];
+  /** 会員の獲得ポイントと残高（印刷時に付与して入れる） */
+  points?: ReceiptPoints | null;
+  /** 会計時の価格表の版（価格改定の発行日時。未指定の場合は売上の確定時の版を入れる） */
+  price_version?: number | null;
+  /** 適用したキャンペーン（合計は割引後の金額） */
+  promotions?: AppliedPromotion[];
+  /** レシート番号 */
+  receipt_number: string;
+  /** 端数調整額（キャンペーンの割引後の金額を丸めた差額、値引はマイナス。total は調整後の金額） */
+  rounding_adjustment?: number;
+  /** 発売日時 */
+  sale_start_date_time?: string | null;
+  /** スタッフ番号 */
+  staff_id: string;
+  /** 消費税金額 */
+  tax_amount: number;
+  /** 消費税率（%） */
+  tax_rate: number;
+  /** 合計金額 */
+  total: number;
+  /** 会場住所 */
+  venue_address?: string | null;
+}
+
+/** 商品明細 */
+export interface ReceiptItem {
+  /** 成人向け（R18）の商品 */
+  age_restricted?: boolean;
+  /** 商品カテゴリー（未指定時は書籍フラグから判定） */
+  category?: string | null;
+  /** 出版サークル名 */
+  circle_name: string;
+  /** サークル名の読み（受け渡し時の呼び出し用） */
+  circle_name_ruby?: string | null;
+  /** 書籍フラグ */
+  is_book: boolean;
+  /** ISBN */
+  isbn: string;
+  /** ISDN（書籍の場合） */
+  isdn?: string | null;
+  /** JAN */
+  jan: string;
+  /** 2段目バーコード（Cコード＋値段、書籍の場合） */
+  jan2?: string | null;
+  /** 商品名 */
+  name: string;
+  /** 明細のメモ（特典付き、サイン本など） */
+  note?: string | null;
+  /** 値段（単価 x 数量） */
+  price: number;
+  /** 責任者の承認による価格変更 */
+  price_override?: PriceOverride | null;
+  /** 明細の下に JAN のバーコードを印刷する（受け渡し・返品時の読み取り用） */
+  print_barcode?: boolean;
+  /** 商品ID（端末の価格表の価格と照合する） */
+  product_id?: string | null;
+  /** 商品数 */
+  quantity: number;
+}
+
+/** レシートに印刷するロゴ画像（PNG） */
+export interface ReceiptLogo {
+  /** 印刷する幅の上限（ドット、未指定の場合は用紙幅。これより大きい画像は縮小する） */
+  max_width?: number | null;
+  /** 白黒への変換方法 */
+  mode?: ImageMode;
+  source: ImageSource;
+}
+
+/** レシートに印字するポイント */
+export interface ReceiptPoints {
+  /** 今回の分を含む残高 */
+  balance: number;
+  /** 今回の獲得ポイント */
+  earned: number;
+}
+
+/** 担当者別の集計 */
+export interface StaffTotal {
+  /** ドロワー在高の設定（釣銭の補充・回収）の回数 */
+  drawer_count: number;
+  refund_amount: number;
+  /** 返品の件数と額 */
+  refund_count: number;
+  sale_amount: number;
+  sale_count: number;
+  staff_id: string;
+  staff_name?: string | null;
+  void_amount?: number;
+  /** 取消の件数と額 */
+  void_count?: number;
+}
+
+/** 商品券カウント */
+export interface VoucherCount {
+  amount: number;
+  /** 商品券カタログの種別コード */
+  code?: string | null;
+  memo?: string | null;
+  /** 表示名（種別コードがある場合はカタログの名前で置き換える） */
+  type: string;
+}
+
+/** 売上に使われた商品券の集計 */
+export interface VoucherSales {
+  /** 売上に充てた額 */
+  amount: number;
+  code: string;
+  /** 使われた売上の件数 */
+  count: number;
+  name: string;
+}
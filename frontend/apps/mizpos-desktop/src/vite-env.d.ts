@@ -3,3 +3,12 @@
 declare const __APP_VERSION__: string;
 declare const __COMMIT_HASH__: string;
 declare const __BUILD_TIMESTAMP__: string;
+
+interface ImportMetaEnv {
+  /** 本番環境のAPI */
+  readonly VITE_API_BASE_URL: string;
+  /** ステージング環境のAPI */
+  readonly VITE_STAGING_API_BASE_URL?: string;
+  /** ローカル環境のAPI */
+  readonly VITE_LOCAL_API_BASE_URL?: string;
+}
@@ -1537,6 +1537,8 @@ async def register_terminal_endpoint(
             device_name=request.device_name,
             os_type=request.os,
             registered_by=current_user_id,
+            organization_id=request.organization_id,
+            seat_label=request.seat_label,
         )
         return {"terminal": terminal}
     except ValueError as e:
@@ -477,9 +477,21 @@ class RegisterTerminalRequest(BaseModel):
     )
     os: str = Field(
         ...,
-        pattern="^(macos|windows|android|linux)$",
+        pattern="^(macos|windows|android|ios|linux)$",
         description="OS種別",
     )
+    organization_id: str | None = Field(
+        None,
+        max_length=100,
+        pattern="^[^:]+$",
+        description="割り当てた組織ID（端末の署名対象に含まれる）",
+    )
+    seat_label: str | None = Field(
+        None,
+        max_length=32,
+        pattern="^[^:]+$",
+        description="割り当てたレジ（端末の署名対象に含まれる）",
+    )
 
 
 class TerminalResponse(BaseModel):
@@ -491,6 +503,8 @@ class TerminalResponse(BaseModel):
     status: str  # active | revoked
     registered_by: str
     registered_at: str
+    organization_id: str | None = None
+    seat_label: str | None = None
     revoked_at: str | None = None
     last_seen_at: str | None = None
 
@@ -51,6 +51,8 @@ def register_terminal(
     device_name: str,
     os_type: str,
     registered_by: str,
+    organization_id: Optional[str] = None,
+    seat_label: Optional[str] = None,
 ) -> dict:
     """端末を登録
 
@@ -58,8 +60,10 @@ def register_terminal(
         terminal_id: 端末ID (UUID)
         public_key: Base64エンコードされたEd25519公開鍵
         device_name: 端末名
-        os_type: OS種別 (macos, windows, android)
+        os_type: OS種別 (macos, windows, linux, android, ios)
         registered_by: 登録したユーザーのuser_id
+        organization_id: 割り当てた組織ID（端末の署名対象に含まれる）
+        seat_label: 割り当てたレジ（端末の署名対象に含まれる）
 
     Returns:
         登録された端末情報
@@ -93,6 +97,9 @@ def register_terminal(
         "registered_by": registered_by,
         "registered_at": now,
     }
+    if organization_id and seat_label:
+        item["organization_id"] = organization_id
+        item["seat_label"] = seat_label
 
     try:
         terminals_table.put_item(
@@ -219,6 +226,24 @@ def update_terminal_last_seen(terminal_id: str) -> None:
         pass  # 更新失敗は無視
 
 
+def build_signed_message(
+    terminal: dict, terminal_id: str, timestamp: int, message: Optional[str] = None
+) -> bytes:
+    """端末が署名した文字列を組み立てる
+
+    組織・レジを割り当てて登録した端末は、登録時の組織・レジも署名対象に含める
+    形式: {terminal_id}:{timestamp}[:{organization_id}:{seat_label}][:{message}]
+    """
+    signed = f"{terminal_id}:{timestamp}"
+    organization_id = terminal.get("organization_id")
+    seat_label = terminal.get("seat_label")
+    if organization_id and seat_label:
+        signed += f":{organization_id}:{seat_label}"
+    if message:
+        signed += f":{message}"
+    return signed.encode("utf-8")
+
+
 def verify_terminal_signature(
     terminal_id: str,
     timestamp: int,
@@ -262,7 +287,7 @@ def verify_terminal_signature(
         return False, None, f"Invalid public key: {e}"
 
     # 署名対象のメッセージを構築
-    message = f"{terminal_id}:{timestamp}".encode("utf-8")
+    message = build_signed_message(terminal, terminal_id, timestamp)
 
     # 署名を検証
     try:
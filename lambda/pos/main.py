@@ -6,6 +6,7 @@ POS端末専用のAPI（端末認証・従業員認証・販売処理）
 import json
 import logging
 import os
+import time
 import traceback
 
 from fastapi import APIRouter, FastAPI, HTTPException, Request, status
@@ -24,6 +25,7 @@ from models import (
     PosSetEventRequest,
     TerminalAuthRequest,
     TerminalRevokeRequest,
+    TerminalUploadRequest,
 )
 from services.coupon import apply_coupon, get_coupon_by_code
 from services.employee import (
@@ -45,6 +47,7 @@ from services.terminal import (
     authenticate_terminal,
     check_terminal_registered,
     revoke_terminal,
+    store_terminal_upload,
     verify_terminal_signature,
 )
 
@@ -173,6 +176,50 @@ async def revoke_terminal_endpoint(request: TerminalRevokeRequest):
         raise HTTPException(status_code=500, detail=str(e)) from e
 
 
+@router.post("/terminals/uploads", response_model=dict)
+async def terminal_upload_endpoint(request: TerminalUploadRequest):
+    """端末からの署名付き送信を受信
+
+    認証不要（端末からの呼び出し）
+    オフライン中にキューへ積まれた送信も受け付けるため、過去のタイムスタンプを許容する
+    同じ送信の再送は保存済みとして成功を返す
+    """
+    try:
+        success, _, error = verify_terminal_signature(
+            terminal_id=request.terminal_id,
+            timestamp=request.timestamp,
+            signature=request.signature,
+            message=request.payload,
+            allow_past=True,
+        )
+        if not success:
+            raise HTTPException(
+                status_code=status.HTTP_401_UNAUTHORIZED,
+                detail=error or "Invalid signature",
+            )
+
+        created = store_terminal_upload(
+            terminal_id=request.terminal_id,
+            timestamp=request.timestamp,
+            signature=request.signature,
+            upload_id=request.upload_id,
+            kind=request.kind,
+            reference_id=request.reference_id,
+            payload=request.payload,
+        )
+        return {
+            "accepted": True,
+            "duplicate": not created,
+            "upload_id": request.upload_id,
+            "received_at": int(time.time()),
+        }
+    except HTTPException:
+        raise
+    except Exception as e:
+        logger.error(f"Error storing terminal upload: {e}")
+        raise HTTPException(status_code=500, detail=str(e)) from e
+
+
 # ==========================================
 # 従業員認証エンドポイント（認証不要）
 # ==========================================
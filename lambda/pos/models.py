@@ -29,6 +29,20 @@ class TerminalRevokeRequest(BaseModel):
     signature: str = Field(..., description="Base64エンコードされたEd25519署名")
 
 
+class TerminalUploadRequest(BaseModel):
+    """端末からの署名付き送信（閉局レポート・ハートビートなど）"""
+
+    terminal_id: str = Field(..., description="端末ID (UUID)")
+    timestamp: int = Field(..., description="署名時のUnix タイムスタンプ")
+    signature: str = Field(
+        ..., description="payloadを含むメッセージのBase64エンコードされたEd25519署名"
+    )
+    upload_id: str = Field(..., description="端末側のキューID")
+    kind: str = Field(..., description="種別（closing_report, heartbeat など）")
+    reference_id: str = Field("", description="元データのID")
+    payload: str = Field(..., min_length=1, description="署名対象のJSON文字列")
+
+
 # ==========================================
 # 従業員認証
 # ==========================================
@@ -25,6 +25,9 @@ except ImportError:
 # 環境変数
 ENVIRONMENT = os.environ.get("ENVIRONMENT", "dev")
 TERMINALS_TABLE = os.environ.get("TERMINALS_TABLE", f"{ENVIRONMENT}-mizpos-terminals")
+TERMINAL_UPLOADS_TABLE = os.environ.get(
+    "TERMINAL_UPLOADS_TABLE", f"{ENVIRONMENT}-mizpos-terminal-uploads"
+)
 
 # リプレイ攻撃防止のための許容時間差（秒）
 TIMESTAMP_TOLERANCE = 300  # 5分
@@ -32,6 +35,7 @@ TIMESTAMP_TOLERANCE = 300  # 5分
 # AWS クライアント
 dynamodb = boto3.resource("dynamodb")
 terminals_table = dynamodb.Table(TERMINALS_TABLE)
+terminal_uploads_table = dynamodb.Table(TERMINAL_UPLOADS_TABLE)
 
 
 def dynamo_to_dict(item: dict) -> dict:
@@ -82,10 +86,30 @@ def update_terminal_last_seen(terminal_id: str) -> None:
         pass  # 更新失敗は無視
 
 
+def build_signed_message(
+    terminal: dict, terminal_id: str, timestamp: int, message: Optional[str] = None
+) -> bytes:
+    """端末が署名した文字列を組み立てる
+
+    組織・レジを割り当てて登録した端末は、登録時の組織・レジも署名対象に含める
+    形式: {terminal_id}:{timestamp}[:{organization_id}:{seat_label}][:{message}]
+    """
+    signed = f"{terminal_id}:{timestamp}"
+    organization_id = terminal.get("organization_id")
+    seat_label = terminal.get("seat_label")
+    if organization_id and seat_label:
+        signed += f":{organization_id}:{seat_label}"
+    if message:
+        signed += f":{message}"
+    return signed.encode("utf-8")
+
+
 def verify_terminal_signature(
     terminal_id: str,
     timestamp: int,
     signature: str,
+    message: Optional[str] = None,
+    allow_past: bool = False,
 ) -> tuple[bool, Optional[dict], Optional[str]]:
     """端末の署名を検証
 
@@ -93,6 +117,8 @@ def verify_terminal_signature(
         terminal_id: 端末ID
         timestamp: Unix タイムスタンプ
         signature: Base64エンコードされたEd25519署名
+        message: 署名対象に含まれるメッセージ（認証のみの場合はNone）
+        allow_past: 過去のタイムスタンプを許容するか（オフライン中にキューへ積まれた送信用）
 
     Returns:
         (検証成功, 端末情報, エラーメッセージ) のタプル
@@ -106,7 +132,9 @@ def verify_terminal_signature(
 
     # タイムスタンプの検証（リプレイ攻撃対策）
     current_time = int(time.time())
-    if abs(current_time - timestamp) > TIMESTAMP_TOLERANCE:
+    if timestamp - current_time > TIMESTAMP_TOLERANCE:
+        return False, None, "Timestamp out of range"
+    if not allow_past and current_time - timestamp > TIMESTAMP_TOLERANCE:
         return False, None, "Timestamp out of range"
 
     # 端末情報を取得
@@ -125,12 +153,12 @@ def verify_terminal_signature(
         return False, None, f"Invalid public key: {e}"
 
     # 署名対象のメッセージを構築
-    message = f"{terminal_id}:{timestamp}".encode("utf-8")
+    signed = build_signed_message(terminal, terminal_id, timestamp, message)
 
     # 署名を検証
     try:
         signature_bytes = base64.b64decode(signature)
-        verify_key.verify(message, signature_bytes)
+        verify_key.verify(signed, signature_bytes)
     except BadSignatureError:
         return False, None, "Invalid signature"
     except Exception as e:
@@ -210,3 +238,51 @@ def revoke_terminal(terminal_id: str) -> bool:
         if e.response["Error"]["Code"] == "ConditionalCheckFailedException":
             return False
         raise
+
+
+def store_terminal_upload(
+    terminal_id: str,
+    timestamp: int,
+    signature: str,
+    upload_id: str,
+    kind: str,
+    reference_id: str,
+    payload: str,
+) -> bool:
+    """端末からの署名付き送信を保存
+
+    署名は送信ごとに一意なため、端末IDと署名をキーにして同じ送信の再送を一度だけ保存する
+
+    Args:
+        terminal_id: 端末ID
+        timestamp: 署名時のUnix タイムスタンプ
+        signature: Base64エンコードされたEd25519署名
+        upload_id: 端末側のキューID
+        kind: 種別（closing_report など）
+        reference_id: 元データのID
+        payload: 署名対象のJSON文字列
+
+    Returns:
+        新規に保存した場合True、保存済みの再送だった場合False
+    """
+    now = datetime.now(timezone.utc).isoformat()
+
+    try:
+        terminal_uploads_table.put_item(
+            Item={
+                "terminal_id": terminal_id,
+                "signature": signature,
+                "upload_id": upload_id,
+                "kind": kind,
+                "reference_id": reference_id,
+                "payload": payload,
+                "signed_at": timestamp,
+                "received_at": now,
+            },
+            ConditionExpression="attribute_not_exists(signature)",
+        )
+        return True
+    except ClientError as e:
+        if e.response["Error"]["Code"] == "ConditionalCheckFailedException":
+            return False
+        raise
@@ -463,6 +463,33 @@ resource "aws_dynamodb_table" "terminals" {
   }
 }
 
+# Terminal uploads table - 端末からの署名付き送信（閉局レポート・ハートビートなど）
+# 端末の署名は送信ごとに一意なため、同じ送信の再送は一度だけ保存される
+resource "aws_dynamodb_table" "terminal_uploads" {
+  name         = "${var.environment}-mizpos-terminal-uploads"
+  billing_mode = "PAY_PER_REQUEST"
+  hash_key     = "terminal_id"
+  range_key    = "signature"
+
+  attribute {
+    name = "terminal_id"
+    type = "S"
+  }
+
+  attribute {
+    name = "signature"
+    type = "S"
+  }
+
+  point_in_time_recovery {
+    enabled = true
+  }
+
+  tags = {
+    Name = "${var.environment}-mizpos-terminal-uploads"
+  }
+}
+
 # Coupons table - クーポン管理
 # クーポンタイプ:
 #   - fixed: 固定金額割引（例: ¥500引き）- 売上計算上マイナス円の商品として扱う
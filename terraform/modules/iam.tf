@@ -359,6 +359,7 @@ resource "aws_iam_role_policy" "lambda_pos" {
         Resource = [
           aws_dynamodb_table.terminals.arn,
           "${aws_dynamodb_table.terminals.arn}/index/*",
+          aws_dynamodb_table.terminal_uploads.arn,
           aws_dynamodb_table.pos_employees.arn,
           "${aws_dynamodb_table.pos_employees.arn}/index/*",
           aws_dynamodb_table.pos_sessions.arn,
@@ -173,6 +173,7 @@ resource "aws_lambda_function" "pos" {
     variables = {
       ENVIRONMENT               = var.environment
       TERMINALS_TABLE           = aws_dynamodb_table.terminals.name
+      TERMINAL_UPLOADS_TABLE    = aws_dynamodb_table.terminal_uploads.name
       POS_EMPLOYEES_TABLE       = aws_dynamodb_table.pos_employees.name
       POS_SESSIONS_TABLE        = aws_dynamodb_table.pos_sessions.name
       OFFLINE_SALES_QUEUE_TABLE = aws_dynamodb_table.offline_sales_queue.name